@@ -0,0 +1,35 @@
+//! Simulação de um circuito GHZ com 1 e 4 threads
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use orchestrator_core::graph::QuantumCircuit;
+use orchestrator_core::layers::NoiseModel;
+use orchestrator_core::quantum;
+
+const QUBITS: usize = 20;
+const SHOTS: u32 = 100_000;
+
+fn ghz(qubits: usize, shots: u32) -> QuantumCircuit {
+    let circuit = (1..qubits).fold(QuantumCircuit::new(qubits).with_gate("h 0"), |circuit, qubit| {
+        circuit.with_gate(format!("cx {} {}", qubit - 1, qubit))
+    });
+    QuantumCircuit { shots, ..circuit }
+}
+
+fn bench_ghz(c: &mut Criterion) {
+    let circuit = ghz(QUBITS, SHOTS);
+    let noise = NoiseModel { gate_error_rate: 0.0, measurement_error_rate: 0.001, decoherence_time_ns: 0.0 };
+
+    let mut group = c.benchmark_group("ghz_20_qubits");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(SHOTS as u64));
+    for threads in [1, 4] {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, _| {
+            b.iter(|| pool.install(|| quantum::simulate(&circuit, &noise, 7).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ghz);
+criterion_main!(benches);
@@ -0,0 +1,47 @@
+//! Tamanho e tempo de codificação/decodificação de um snapshot com 10k
+//! tarefas, para cada combinação de codec e compressão
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use orchestrator_core::backup::{SnapshotEncoding, TaskGraphSnapshot};
+use orchestrator_core::graph::{TaskMesh, TaskNode};
+
+const TASKS: usize = 10_000;
+
+fn snapshot() -> TaskGraphSnapshot {
+    let fixture = include_str!("../fixtures/compat/task_graph_snapshot.json");
+    let metrics = TaskGraphSnapshot::from_json(fixture.as_bytes()).unwrap().system_metrics;
+    let mut mesh = TaskMesh::new();
+    for i in 0..TASKS {
+        mesh.add_task(TaskNode::new(format!("task-{}", i), None)).unwrap();
+    }
+    TaskGraphSnapshot::capture(&mesh, &metrics, chrono::Utc::now())
+}
+
+fn bench_snapshot_codec(c: &mut Criterion) {
+    let snapshot = snapshot();
+    let label = |encoding: &SnapshotEncoding| encoding.extension().trim_start_matches('.').to_string();
+
+    // O criterion não mede tamanho: os bytes de cada combinação vão para a saída
+    for encoding in SnapshotEncoding::all() {
+        let size = snapshot.encode(encoding).unwrap().len();
+        println!("snapshot_10k/{:<10} {:>10} bytes", label(&encoding), size);
+    }
+
+    let mut group = c.benchmark_group("snapshot_10k");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(TASKS as u64));
+    for encoding in SnapshotEncoding::all() {
+        group.bench_with_input(BenchmarkId::new("encode", label(&encoding)), &encoding, |b, encoding| {
+            b.iter(|| snapshot.encode(*encoding).unwrap())
+        });
+
+        let blob = snapshot.encode(encoding).unwrap();
+        group.bench_with_input(BenchmarkId::new("decode", label(&encoding)), &blob, |b, blob| {
+            b.iter(|| TaskGraphSnapshot::decode(blob, false).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_snapshot_codec);
+criterion_main!(benches);
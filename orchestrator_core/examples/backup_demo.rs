@@ -9,7 +9,7 @@
 use orchestrator_core::{
     backup::{
         BackupSystem, BackupConfig, MinioConfig, SqliteConfig, 
-        SnapshotConfig, CheckpointConfig, SystemState
+        SnapshotConfig, SnapshotCodec, Compression, CheckpointConfig, SystemState
     },
     graph::{TaskMesh, TaskNode, TaskId, TaskStatus, TaskPriority},
     metrics::SystemMetrics,
@@ -150,6 +150,8 @@ fn create_backup_config() -> BackupConfig {
             interval_seconds: 300, // 5 minutos
             max_snapshots: 10,
             compression_enabled: true,
+            codec: SnapshotCodec::Json,
+            compression: Compression::Zstd { level: 3 },
             snapshot_prefix: "taskgraph".to_string(),
         },
         checkpoint_config: CheckpointConfig {
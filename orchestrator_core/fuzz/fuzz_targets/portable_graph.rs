@@ -0,0 +1,21 @@
+//! Documento de grafo portável arbitrário: nenhuma entrada pode causar pânico
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use orchestrator_core::graph::TaskMesh;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else { return };
+    let Ok(mesh) = TaskMesh::from_json(json) else { return };
+
+    // Grafo aceito precisa sobreviver às operações usuais
+    let json = mesh.to_json().expect("grafo aceito serializa");
+    TaskMesh::from_json(&json).expect("exportação é relida");
+    let _ = mesh.topological_sort();
+    let _ = mesh.next_release_time(chrono::Utc::now());
+    for task in mesh.get_all_tasks() {
+        let _ = mesh.release_time(&task.id);
+        let _ = task.validate_configuration();
+    }
+});
@@ -0,0 +1,16 @@
+//! Circuito quântico arbitrário: erros são `Err`, nunca pânico
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use orchestrator_core::graph::{CircuitGate, QuantumCircuit};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    if let Ok(circuit) = QuantumCircuit::from_json(text) {
+        circuit.parse_gates().expect("circuito aceito tem portas válidas");
+    }
+    for line in text.lines() {
+        let _ = CircuitGate::parse(line);
+    }
+});
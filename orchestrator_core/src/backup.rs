@@ -11,15 +11,19 @@ use rusoto_core::Region;
 use rusoto_s3::{S3Client, S3, PutObjectRequest, GetObjectRequest};
 use serde::{Deserialize, Serialize};
 use sqlx::{SqlitePool, Row};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::fs;
 use tracing::{info, warn, error, debug};
 use uuid::Uuid;
 
+use crate::background::BackgroundTasks;
+use crate::clock::{Clock, SystemClock};
+use crate::cost::CostEntry;
 use crate::errors::{OrchestratorError, Result};
-use crate::graph::{TaskMesh, TaskId, TaskStatus};
+use crate::graph::{PortableGraph, TaskMesh, TaskId, TaskStatus};
 use crate::metrics::SystemMetrics;
 
 /// Configuração do sistema de backup
@@ -60,12 +64,168 @@ pub struct SnapshotConfig {
     pub interval_seconds: u64,
     /// Número máximo de snapshots a manter
     pub max_snapshots: u32,
-    /// Compressão dos snapshots
+    /// Compressão dos snapshots (`false` grava sem compressão, qualquer que
+    /// seja `compression`)
     pub compression_enabled: bool,
+    /// Formato de serialização dos snapshots
+    #[serde(default)]
+    pub codec: SnapshotCodec,
+    /// Compressão aplicada com `compression_enabled` (padrão: zstd)
+    #[serde(default)]
+    pub compression: Compression,
     /// Prefixo dos snapshots no MinIO
     pub snapshot_prefix: String,
 }
 
+impl SnapshotConfig {
+    /// Codec e compressão efetivos na gravação
+    pub fn encoding(&self) -> SnapshotEncoding {
+        SnapshotEncoding {
+            codec: self.codec,
+            compression: if self.compression_enabled { self.compression } else { Compression::None },
+        }
+    }
+}
+
+/// Assinatura do cabeçalho de codec, a mesma dos blobs do `task_mesh_core`
+///
+/// O cabeçalho tem 6 bytes: a assinatura, o codec e a compressão. Snapshots
+/// sem ele são JSON, comprimidos com gzip quando a chave termina em `.gz`.
+pub const CODEC_MAGIC: [u8; 4] = *b"TMCD";
+
+/// Nível padrão do zstd (o mesmo da CLI `zstd`)
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Formato de serialização dos snapshots
+///
+/// O grafo portável é lido por um formato autodescritivo (ver
+/// [`PortableGraph::deserialize_compat`]), por isso não há bincode aqui.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotCodec {
+    /// Legível por humanos e por qualquer linguagem
+    #[default]
+    Json,
+    /// Binário autodescritivo (RFC 8949), menor e mais rápido que JSON
+    Cbor,
+}
+
+/// Compressão aplicada sobre o snapshot serializado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    None,
+    Gzip,
+    /// Nível de 1 (mais rápido) a 22 (menor)
+    Zstd { level: i32 },
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::Zstd { level: DEFAULT_ZSTD_LEVEL }
+    }
+}
+
+/// Codec e compressão de um snapshot gravado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEncoding {
+    pub codec: SnapshotCodec,
+    pub compression: Compression,
+}
+
+impl SnapshotEncoding {
+    /// Todas as combinações de codec e compressão (zstd no nível padrão)
+    pub fn all() -> Vec<Self> {
+        let compressions = [Compression::None, Compression::Gzip, Compression::default()];
+        [SnapshotCodec::Json, SnapshotCodec::Cbor]
+            .into_iter()
+            .flat_map(|codec| compressions.into_iter().map(move |compression| Self { codec, compression }))
+            .collect()
+    }
+
+    /// Extensão da chave no MinIO (ex.: `.cbor.zst`)
+    pub fn extension(&self) -> String {
+        let codec = match self.codec {
+            SnapshotCodec::Json => "json",
+            SnapshotCodec::Cbor => "cbor",
+        };
+        let compression = match self.compression {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd { .. } => ".zst",
+        };
+        format!(".{}{}", codec, compression)
+    }
+
+    fn content_type(&self) -> &'static str {
+        match (self.codec, self.compression) {
+            (SnapshotCodec::Json, Compression::None) => "application/json",
+            (SnapshotCodec::Cbor, Compression::None) => "application/cbor",
+            _ => "application/octet-stream",
+        }
+    }
+
+    /// Codec do tag gravado no cabeçalho (0 é o bincode, não usado em snapshots)
+    fn from_header(codec: u8, compression: u8) -> Result<Self> {
+        let codec = match codec {
+            1 => SnapshotCodec::Json,
+            2 => SnapshotCodec::Cbor,
+            other => return Err(OrchestratorError::BackupError(format!("Codec de snapshot não suportado: {}", other))),
+        };
+        let compression = match compression {
+            0 => Compression::None,
+            1 => Compression::Gzip,
+            2 => Compression::default(),
+            other => return Err(OrchestratorError::BackupError(format!("Compressão desconhecida: {}", other))),
+        };
+        Ok(Self { codec, compression })
+    }
+
+    fn header(&self) -> [u8; 2] {
+        let codec = match self.codec {
+            SnapshotCodec::Json => 1,
+            SnapshotCodec::Cbor => 2,
+        };
+        let compression = match self.compression {
+            Compression::None => 0,
+            Compression::Gzip => 1,
+            Compression::Zstd { .. } => 2,
+        };
+        [codec, compression]
+    }
+}
+
+impl Compression {
+    fn compress(&self, payload: &[u8], out: &mut Vec<u8>) -> std::io::Result<()> {
+        use std::io::Write;
+        
+        match self {
+            Compression::None => out.extend_from_slice(payload),
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(out, flate2::Compression::default());
+                encoder.write_all(payload)?;
+                encoder.finish()?;
+            }
+            Compression::Zstd { level } => zstd::stream::copy_encode(payload, out, *level)?,
+        }
+        Ok(())
+    }
+
+    fn decompress(&self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        use std::io::Read;
+        
+        let mut payload = Vec::new();
+        match self {
+            Compression::None => payload.extend_from_slice(body),
+            Compression::Gzip => {
+                flate2::read::GzDecoder::new(body).read_to_end(&mut payload)?;
+            }
+            Compression::Zstd { .. } => zstd::stream::copy_decode(body, &mut payload)?,
+        }
+        Ok(payload)
+    }
+}
+
 /// Configuração de checkpoints
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointConfig {
@@ -77,15 +237,109 @@ pub struct CheckpointConfig {
     pub auto_cleanup: bool,
 }
 
+/// Versão do formato dos snapshots; incrementada a cada mudança incompatível
+///
+/// Campos novos entram com `#[serde(default)]` e renomeações mantêm o nome
+/// antigo via `alias`, de modo que snapshots antigos seguem legíveis; a
+/// fixture em `fixtures/compat/` quebra o build se um campo sumir.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
 /// Dados de um snapshot do TaskGraph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskGraphSnapshot {
     pub id: Uuid,
     pub timestamp: DateTime<Utc>,
     pub version: String,
-    pub task_graph: TaskMesh,
+    /// Grafo no formato portável (snapshots antigos são convertidos na leitura)
+    #[serde(deserialize_with = "PortableGraph::deserialize_compat")]
+    pub task_graph: PortableGraph,
     pub system_metrics: SystemMetrics,
     pub metadata: SnapshotMetadata,
+    /// Versão do formato em que o snapshot foi gravado (0: sem versão)
+    #[serde(default)]
+    pub format_version: u32,
+}
+
+impl TaskGraphSnapshot {
+    /// Desserializa um snapshot, recusando versões de formato mais novas que
+    /// [`SNAPSHOT_FORMAT_VERSION`]
+    pub fn from_json(data: &[u8]) -> Result<Self> {
+        let snapshot: Self = serde_json::from_slice(data)
+            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao deserializar snapshot: {}", e)))?;
+        snapshot.check_format_version()
+    }
+
+    /// Serializa e comprime o snapshot, com o cabeçalho de codec
+    pub fn encode(&self, encoding: SnapshotEncoding) -> Result<Vec<u8>> {
+        let payload = match encoding.codec {
+            SnapshotCodec::Json => serde_json::to_vec(self).map_err(|e| e.to_string()),
+            SnapshotCodec::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(self, &mut buffer).map(|()| buffer).map_err(|e| e.to_string())
+            }
+        }
+        .map_err(|e| OrchestratorError::BackupError(format!("Erro ao serializar snapshot: {}", e)))?;
+        
+        let mut data = Vec::with_capacity(CODEC_MAGIC.len() + 2 + payload.len());
+        data.extend_from_slice(&CODEC_MAGIC);
+        data.extend_from_slice(&encoding.header());
+        encoding.compression.compress(&payload, &mut data)
+            .map_err(|e| OrchestratorError::BackupError(format!("Erro na compressão: {}", e)))?;
+        Ok(data)
+    }
+
+    /// Lê um snapshot gravado em qualquer codec e compressão
+    ///
+    /// O formato vem do cabeçalho; sem ele, o blob é JSON, com gzip se
+    /// `legacy_gzip`. Retorna também o tamanho do conteúdo descomprimido.
+    pub fn decode(data: &[u8], legacy_gzip: bool) -> Result<(Self, u64)> {
+        let decompress_error = |e: std::io::Error| OrchestratorError::BackupError(format!("Erro na descompressão: {}", e));
+        let Some(header) = data.strip_prefix(&CODEC_MAGIC) else {
+            let legacy = if legacy_gzip { Compression::Gzip } else { Compression::None };
+            let payload = legacy.decompress(data).map_err(decompress_error)?;
+            return Ok((Self::from_json(&payload)?, payload.len() as u64));
+        };
+        let (&[codec, compression], body) = header.split_at(2.min(header.len())) else {
+            return Err(OrchestratorError::BackupError("Cabeçalho de codec truncado".to_string()));
+        };
+        let encoding = SnapshotEncoding::from_header(codec, compression)?;
+        let payload = encoding.compression.decompress(body).map_err(decompress_error)?;
+        let snapshot = match encoding.codec {
+            SnapshotCodec::Json => Self::from_json(&payload)?,
+            SnapshotCodec::Cbor => ciborium::from_reader::<Self, _>(payload.as_slice())
+                .map_err(|e| OrchestratorError::BackupError(format!("Erro ao deserializar snapshot: {}", e)))?
+                .check_format_version()?,
+        };
+        Ok((snapshot, payload.len() as u64))
+    }
+
+    fn check_format_version(self) -> Result<Self> {
+        if self.format_version > SNAPSHOT_FORMAT_VERSION {
+            return Err(OrchestratorError::UnsupportedFormatVersion {
+                found: self.format_version,
+                supported: SNAPSHOT_FORMAT_VERSION,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Reconstrói o TaskMesh contido no snapshot
+    pub fn restore_graph(&self) -> Result<TaskMesh> {
+        TaskMesh::from_portable(self.task_graph.clone())
+    }
+
+    /// Captura o estado atual do grafo num snapshot ainda não persistido
+    pub fn capture(task_graph: &TaskMesh, system_metrics: &SystemMetrics, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            timestamp,
+            version: crate::VERSION.to_string(),
+            task_graph: task_graph.to_portable(),
+            system_metrics: system_metrics.clone(),
+            metadata: snapshot_metadata(task_graph),
+            format_version: SNAPSHOT_FORMAT_VERSION,
+        }
+    }
 }
 
 /// Metadados do snapshot
@@ -95,6 +349,9 @@ pub struct SnapshotMetadata {
     pub completed_tasks: u32,
     pub failed_tasks: u32,
     pub running_tasks: u32,
+    /// Tarefas puladas pela condição
+    #[serde(default)]
+    pub skipped_tasks: u32,
     pub compression_ratio: Option<f64>,
     pub size_bytes: u64,
 }
@@ -139,6 +396,37 @@ pub enum BackupOperationType {
     Cleanup,
 }
 
+/// Calcula metadados do snapshot
+fn snapshot_metadata(task_graph: &TaskMesh) -> SnapshotMetadata {
+    let tasks = task_graph.get_all_tasks();
+    let total_tasks = tasks.len() as u32;
+    let mut completed_tasks = 0;
+    let mut failed_tasks = 0;
+    let mut running_tasks = 0;
+    let mut skipped_tasks = 0;
+    
+    // Contar tarefas por status
+    for task in tasks {
+        match task.status {
+            TaskStatus::Completed => completed_tasks += 1,
+            TaskStatus::Failed => failed_tasks += 1,
+            TaskStatus::Running => running_tasks += 1,
+            TaskStatus::Skipped { .. } => skipped_tasks += 1,
+            _ => {}
+        }
+    }
+    
+    SnapshotMetadata {
+        total_tasks,
+        completed_tasks,
+        failed_tasks,
+        running_tasks,
+        skipped_tasks,
+        compression_ratio: None, // Será calculado após compressão
+        size_bytes: 0, // Será atualizado após serialização
+    }
+}
+
 /// Sistema principal de backup e checkpoint
 pub struct BackupSystem {
     config: BackupConfig,
@@ -147,11 +435,29 @@ pub struct BackupSystem {
     completed_tasks_count: Arc<std::sync::atomic::AtomicU32>,
     last_snapshot: Arc<tokio::sync::RwLock<Option<DateTime<Utc>>>>,
     last_checkpoint: Arc<tokio::sync::RwLock<Option<DateTime<Utc>>>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for BackupSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Sem a configuração: ela traz as credenciais do MinIO
+        f.debug_struct("BackupSystem")
+            .field("completed_tasks_count", &self.completed_tasks_count)
+            .finish_non_exhaustive()
+    }
 }
 
 impl BackupSystem {
     /// Cria uma nova instância do sistema de backup
     pub async fn new(config: BackupConfig) -> Result<Self> {
+        Self::with_clock(config, Arc::new(SystemClock)).await
+    }
+    
+    /// Cria o sistema de backup com uma fonte de tempo específica
+    ///
+    /// Os timestamps gravados vêm de `clock.wall()`; a ordem entre snapshots e
+    /// checkpoints é dada por uma sequência crescente, imune a ajustes do relógio.
+    pub async fn with_clock(config: BackupConfig, clock: Arc<dyn Clock>) -> Result<Self> {
         info!("Inicializando sistema de backup e checkpoint");
         
         // Configurar cliente MinIO
@@ -170,6 +476,7 @@ impl BackupSystem {
             completed_tasks_count: Arc::new(std::sync::atomic::AtomicU32::new(0)),
             last_snapshot: Arc::new(tokio::sync::RwLock::new(None)),
             last_checkpoint: Arc::new(tokio::sync::RwLock::new(None)),
+            clock,
         })
     }
     
@@ -222,6 +529,7 @@ impl BackupSystem {
                 last_completed_task TEXT,
                 system_state TEXT NOT NULL,
                 recovery_data TEXT NOT NULL,
+                seq INTEGER NOT NULL DEFAULT 0,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP
             )
             "#
@@ -243,6 +551,7 @@ impl BackupSystem {
                 failed_tasks INTEGER NOT NULL,
                 size_bytes INTEGER NOT NULL,
                 compression_ratio REAL,
+                seq INTEGER NOT NULL DEFAULT 0,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP
             )
             "#
@@ -269,10 +578,101 @@ impl BackupSystem {
         .await
         .map_err(|e| OrchestratorError::BackupError(format!("Erro ao criar tabela backup_operations: {}", e)))?;
         
+        // Custos agregados (ver `crate::cost`)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS cost_ledger (
+                day TEXT NOT NULL,
+                namespace TEXT NOT NULL,
+                layer TEXT NOT NULL,
+                tags TEXT NOT NULL,
+                tasks INTEGER NOT NULL,
+                cost REAL NOT NULL,
+                PRIMARY KEY (day, namespace, layer, tags)
+            )
+            "#
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| OrchestratorError::BackupError(format!("Erro ao criar tabela cost_ledger: {}", e)))?;
+        
+        // Bancos criados antes da coluna de sequência
+        for table in ["checkpoints", "snapshot_metadata"] {
+            Self::ensure_seq_column(pool, table).await?;
+        }
+        
         info!("Tabelas do banco de dados inicializadas com sucesso");
         Ok(())
     }
     
+    /// Adiciona a coluna `seq` a uma tabela existente, se ausente
+    async fn ensure_seq_column(pool: &SqlitePool, table: &str) -> Result<()> {
+        let has_seq = sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao inspecionar {}: {}", table, e)))?
+            .iter()
+            .any(|row| row.get::<String, _>("name") == "seq");
+        
+        if !has_seq {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN seq INTEGER NOT NULL DEFAULT 0", table))
+                .execute(pool)
+                .await
+                .map_err(|e| OrchestratorError::BackupError(format!("Erro ao migrar {}: {}", table, e)))?;
+        }
+        Ok(())
+    }
+    
+    /// Soma uma linha de custo à tabela `cost_ledger`
+    pub async fn record_cost(&self, entry: &CostEntry) -> Result<()> {
+        let layer = serde_json::to_string(&entry.layer)
+            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao serializar camada: {}", e)))?;
+        let tags = serde_json::to_string(&entry.tags)
+            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao serializar tags: {}", e)))?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO cost_ledger (day, namespace, layer, tags, tasks, cost)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (day, namespace, layer, tags)
+            DO UPDATE SET tasks = tasks + excluded.tasks, cost = cost + excluded.cost
+            "#
+        )
+        .bind(entry.day.to_string())
+        .bind(&entry.namespace)
+        .bind(layer)
+        .bind(tags)
+        .bind(entry.tasks as i64)
+        .bind(entry.cost)
+        .execute(&self.sqlite_pool)
+        .await
+        .map_err(|e| OrchestratorError::BackupError(format!("Erro ao salvar custo: {}", e)))?;
+        
+        Ok(())
+    }
+    
+    /// Linhas da tabela `cost_ledger`
+    pub async fn load_costs(&self) -> Result<Vec<CostEntry>> {
+        let rows = sqlx::query("SELECT day, namespace, layer, tags, tasks, cost FROM cost_ledger")
+            .fetch_all(&self.sqlite_pool)
+            .await
+            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao carregar custos: {}", e)))?;
+        
+        rows.iter()
+            .map(|row| {
+                let invalid = |e: String| OrchestratorError::BackupError(format!("Linha de custo inválida: {}", e));
+                Ok(CostEntry {
+                    day: row.get::<String, _>("day").parse().map_err(|e: chrono::ParseError| invalid(e.to_string()))?,
+                    namespace: row.get("namespace"),
+                    layer: serde_json::from_str(&row.get::<String, _>("layer")).map_err(|e| invalid(e.to_string()))?,
+                    tags: serde_json::from_str(&row.get::<String, _>("tags")).map_err(|e| invalid(e.to_string()))?,
+                    tasks: row.get::<i64, _>("tasks") as u64,
+                    cost: row.get("cost"),
+                })
+            })
+            .collect()
+    }
+    
     /// Cria um snapshot do TaskGraph e envia para MinIO
     pub async fn create_snapshot(
         &self,
@@ -282,43 +682,24 @@ impl BackupSystem {
         let start_time = std::time::Instant::now();
         info!("Iniciando criação de snapshot do TaskGraph");
         
-        let snapshot_id = Uuid::new_v4();
-        let timestamp = Utc::now();
+        let timestamp = self.clock.wall();
+        let snapshot = TaskGraphSnapshot::capture(task_graph, system_metrics, timestamp);
+        let snapshot_id = snapshot.id;
         
-        // Calcular metadados
-        let metadata = self.calculate_snapshot_metadata(task_graph);
-        
-        // Criar snapshot
-        let snapshot = TaskGraphSnapshot {
-            id: snapshot_id,
-            timestamp,
-            version: crate::VERSION.to_string(),
-            task_graph: task_graph.clone(),
-            system_metrics: system_metrics.clone(),
-            metadata,
-        };
-        
-        // Serializar snapshot
-        let snapshot_data = serde_json::to_vec(&snapshot)
-            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao serializar snapshot: {}", e)))?;
-        
-        // Comprimir se habilitado
-        let final_data = if self.config.snapshot_config.compression_enabled {
-            self.compress_data(&snapshot_data)?
-        } else {
-            snapshot_data
-        };
+        // Serializar e comprimir no formato configurado
+        let encoding = self.config.snapshot_config.encoding();
+        let final_data = snapshot.encode(encoding)?;
         
         // Enviar para MinIO
         let minio_key = format!(
-            "{}/snapshot_{}_{}.json{}",
+            "{}/snapshot_{}_{}{}",
             self.config.snapshot_config.snapshot_prefix,
             timestamp.format("%Y%m%d_%H%M%S"),
             snapshot_id,
-            if self.config.snapshot_config.compression_enabled { ".gz" } else { "" }
+            encoding.extension()
         );
         
-        self.upload_to_minio(&minio_key, final_data.clone()).await?;
+        self.upload_to_minio(&minio_key, final_data.clone(), encoding.content_type()).await?;
         
         // Salvar metadados no SQLite
         self.save_snapshot_metadata(&snapshot, &minio_key, final_data.len() as u64).await?;
@@ -348,68 +729,13 @@ impl BackupSystem {
         
         Ok(snapshot)
     }
-    /// Calcula metadados do snapshot
-    fn calculate_snapshot_metadata(&self, task_graph: &TaskMesh) -> SnapshotMetadata {
-        let total_tasks = task_graph.node_count() as u32;
-        let mut completed_tasks = 0;
-        let mut failed_tasks = 0;
-        let mut running_tasks = 0;
-        
-        // Contar tarefas por status
-        for node_index in task_graph.node_indices() {
-            if let Some(task) = task_graph.node_weight(node_index) {
-                match task.status {
-                    TaskStatus::Completed => completed_tasks += 1,
-                    TaskStatus::Failed => failed_tasks += 1,
-                    TaskStatus::Running => running_tasks += 1,
-                    _ => {}
-                }
-            }
-        }
-        
-        SnapshotMetadata {
-            total_tasks,
-            completed_tasks,
-            failed_tasks,
-            running_tasks,
-            compression_ratio: None, // Será calculado após compressão
-            size_bytes: 0, // Será atualizado após serialização
-        }
-    }
-    
-    /// Comprime dados usando gzip
-    fn compress_data(&self, data: &[u8]) -> Result<Vec<u8>> {
-        use std::io::Write;
-        use flate2::{Compression, write::GzEncoder};
-        
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(data)
-            .map_err(|e| OrchestratorError::BackupError(format!("Erro na compressão: {}", e)))?;
-        
-        encoder.finish()
-            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao finalizar compressão: {}", e)))
-    }
-    
-    /// Descomprime dados gzip
-    fn decompress_data(&self, data: &[u8]) -> Result<Vec<u8>> {
-        use std::io::Read;
-        use flate2::read::GzDecoder;
-        
-        let mut decoder = GzDecoder::new(data);
-        let mut decompressed = Vec::new();
-        decoder.read_to_end(&mut decompressed)
-            .map_err(|e| OrchestratorError::BackupError(format!("Erro na descompressão: {}", e)))?;
-        
-        Ok(decompressed)
-    }
-    
     /// Faz upload de dados para MinIO
-    async fn upload_to_minio(&self, key: &str, data: Vec<u8>) -> Result<()> {
+    async fn upload_to_minio(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<()> {
         let request = PutObjectRequest {
             bucket: self.config.minio_config.bucket_name.clone(),
             key: key.to_string(),
             body: Some(data.into()),
-            content_type: Some("application/json".to_string()),
+            content_type: Some(content_type.to_string()),
             ..Default::default()
         };
         
@@ -454,8 +780,9 @@ impl BackupSystem {
             r#"
             INSERT INTO snapshot_metadata (
                 id, timestamp, version, minio_key, total_tasks, 
-                completed_tasks, failed_tasks, size_bytes, compression_ratio
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                completed_tasks, failed_tasks, size_bytes, compression_ratio, seq
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?,
+                (SELECT COALESCE(MAX(seq), 0) + 1 FROM snapshot_metadata))
             "#
         )
         .bind(snapshot.id.to_string())
@@ -486,7 +813,7 @@ impl BackupSystem {
         info!("Iniciando criação de checkpoint local");
         
         let checkpoint_id = Uuid::new_v4();
-        let timestamp = Utc::now();
+        let timestamp = self.clock.wall();
         
         let checkpoint = LocalCheckpoint {
             id: checkpoint_id,
@@ -509,8 +836,9 @@ impl BackupSystem {
             r#"
             INSERT INTO checkpoints (
                 id, timestamp, task_count, last_completed_task, 
-                system_state, recovery_data
-            ) VALUES (?, ?, ?, ?, ?, ?)
+                system_state, recovery_data, seq
+            ) VALUES (?, ?, ?, ?, ?, ?,
+                (SELECT COALESCE(MAX(seq), 0) + 1 FROM checkpoints))
             "#
         )
         .bind(checkpoint_id.to_string())
@@ -617,7 +945,7 @@ impl BackupSystem {
         
         // Buscar snapshots ordenados por timestamp
         let rows = sqlx::query(
-            "SELECT id, minio_key FROM snapshot_metadata ORDER BY timestamp DESC LIMIT -1 OFFSET ?"
+            "SELECT id, minio_key FROM snapshot_metadata ORDER BY seq DESC, timestamp DESC LIMIT -1 OFFSET ?"
         )
         .bind(retention_count as i64)
         .fetch_all(&self.sqlite_pool)
@@ -649,7 +977,7 @@ impl BackupSystem {
     /// Limpa checkpoints antigos
     async fn cleanup_old_checkpoints(&self) -> Result<()> {
         let retention_days = self.config.checkpoint_config.retention_days;
-        let cutoff_date = Utc::now() - chrono::Duration::days(retention_days as i64);
+        let cutoff_date = self.clock.wall() - chrono::Duration::days(retention_days as i64);
         
         sqlx::query("DELETE FROM checkpoints WHERE timestamp < ?")
             .bind(cutoff_date.to_rfc3339())
@@ -684,7 +1012,7 @@ impl BackupSystem {
         
         // Buscar snapshot mais recente
         let row = sqlx::query(
-            "SELECT id, minio_key, timestamp FROM snapshot_metadata ORDER BY timestamp DESC LIMIT 1"
+            "SELECT id, minio_key, timestamp FROM snapshot_metadata ORDER BY seq DESC, timestamp DESC LIMIT 1"
         )
         .fetch_optional(&self.sqlite_pool)
         .await
@@ -701,19 +1029,7 @@ impl BackupSystem {
         
         info!("Restaurando snapshot: ID={}, timestamp={}", snapshot_id, timestamp);
         
-        // Baixar dados do MinIO
-        let compressed_data = self.download_from_minio(&minio_key).await?;
-        
-        // Descomprimir se necessário
-        let snapshot_data = if minio_key.ends_with(".gz") {
-            self.decompress_data(&compressed_data)?
-        } else {
-            compressed_data
-        };
-        
-        // Deserializar snapshot
-        let snapshot: TaskGraphSnapshot = serde_json::from_slice(&snapshot_data)
-            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao deserializar snapshot: {}", e)))?;
+        let (snapshot, size_bytes) = self.fetch_snapshot(&minio_key).await?;
         
         // Registrar operação de restauração
         let duration_ms = start_time.elapsed().as_millis() as u64;
@@ -721,7 +1037,7 @@ impl BackupSystem {
             operation_type: BackupOperationType::Restore,
             success: true,
             duration_ms,
-            size_bytes: Some(snapshot_data.len() as u64),
+            size_bytes: Some(size_bytes),
             error_message: None,
         }).await?;
         
@@ -734,13 +1050,48 @@ impl BackupSystem {
         Ok(Some(snapshot))
     }
     
+    /// Baixa, descomprime e desserializa o snapshot gravado em `minio_key`
+    ///
+    /// O formato vem do cabeçalho do blob, não da configuração atual.
+    /// Retorna também o tamanho do conteúdo descomprimido.
+    async fn fetch_snapshot(&self, minio_key: &str) -> Result<(TaskGraphSnapshot, u64)> {
+        let data = self.download_from_minio(minio_key).await?;
+        TaskGraphSnapshot::decode(&data, minio_key.ends_with(".gz"))
+    }
+    
+    /// Carrega um snapshot específico pelo ID
+    pub async fn load_snapshot(&self, snapshot_id: Uuid) -> Result<TaskGraphSnapshot> {
+        let minio_key: Option<String> = sqlx::query_scalar("SELECT minio_key FROM snapshot_metadata WHERE id = ?")
+            .bind(snapshot_id.to_string())
+            .fetch_optional(&self.sqlite_pool)
+            .await
+            .map_err(|e| OrchestratorError::BackupError(format!("Erro ao buscar snapshot: {}", e)))?;
+        
+        let minio_key = minio_key
+            .ok_or_else(|| OrchestratorError::BackupError(format!("Snapshot não encontrado: {}", snapshot_id)))?;
+        Ok(self.fetch_snapshot(&minio_key).await?.0)
+    }
+    
+    /// Compara dois snapshots persistidos
+    ///
+    /// Os snapshots são carregados um de cada vez e reduzidos a um índice
+    /// ordenado dos nós do formato portável antes de carregar o próximo, de
+    /// modo que os dois grafos completos nunca ficam em memória ao mesmo
+    /// tempo. Snapshots de versões de formato diferentes são comparáveis
+    /// porque a leitura já os converte para o formato portável atual.
+    pub async fn diff_snapshots(&self, id_a: Uuid, id_b: Uuid) -> Result<SnapshotDiff> {
+        let from = SnapshotIndex::from(self.load_snapshot(id_a).await?);
+        let to = SnapshotIndex::from(self.load_snapshot(id_b).await?);
+        Ok(SnapshotDiff::between(&from, &to))
+    }
+    
     /// Restaura checkpoint mais recente
     pub async fn restore_latest_checkpoint(&self) -> Result<Option<LocalCheckpoint>> {
         let start_time = std::time::Instant::now();
         info!("Iniciando restauração do checkpoint mais recente");
         
         let row = sqlx::query(
-            "SELECT * FROM checkpoints ORDER BY timestamp DESC LIMIT 1"
+            "SELECT * FROM checkpoints ORDER BY seq DESC, timestamp DESC LIMIT 1"
         )
         .fetch_optional(&self.sqlite_pool)
         .await
@@ -831,19 +1182,26 @@ impl BackupSystem {
     }
     
     /// Inicia task periódica de snapshots
+    ///
+    /// O loop é registrado em `background` e termina no shutdown dele.
     pub fn start_periodic_snapshots(
-        &self,
+        self: &Arc<Self>,
         task_graph: Arc<tokio::sync::RwLock<TaskMesh>>,
         system_metrics: Arc<tokio::sync::RwLock<SystemMetrics>>,
+        background: &BackgroundTasks,
     ) {
-        let backup_system = Arc::new(self);
+        let backup_system = Arc::clone(self);
         let interval = self.config.snapshot_config.interval_seconds;
+        let token = background.token();
         
-        tokio::spawn(async move {
+        background.spawn("periodic_snapshots", async move {
             let mut interval_timer = tokio::time::interval(tokio::time::Duration::from_secs(interval));
             
             loop {
-                interval_timer.tick().await;
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = interval_timer.tick() => {}
+                }
                 
                 let graph = task_graph.read().await.clone();
                 let metrics = system_metrics.read().await.clone();
@@ -901,3 +1259,400 @@ pub struct BackupStats {
     pub completed_tasks_count: u32,
 }
 
+/// Tarefa como aparece num snapshot, reduzida ao necessário para comparação
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotTaskEntry {
+    pub id: TaskId,
+    pub name: String,
+    pub status: TaskStatus,
+}
+
+/// Índice compacto de um snapshot: nós ordenados por ID e métricas achatadas
+#[derive(Debug, Clone)]
+pub struct SnapshotIndex {
+    pub snapshot_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    tasks: Vec<SnapshotTaskEntry>,
+    metrics: BTreeMap<String, f64>,
+}
+
+impl From<TaskGraphSnapshot> for SnapshotIndex {
+    fn from(snapshot: TaskGraphSnapshot) -> Self {
+        let mut metrics = BTreeMap::new();
+        if let Ok(value) = serde_json::to_value(&snapshot.metadata) {
+            flatten_metrics("metadata", &value, &mut metrics);
+        }
+        if let Ok(value) = serde_json::to_value(&snapshot.system_metrics) {
+            flatten_metrics("system_metrics", &value, &mut metrics);
+        }
+        // Tamanho e compressão dependem da serialização, não do estado
+        metrics.retain(|key, _| key != "metadata.size_bytes" && key != "metadata.compression_ratio");
+
+        let mut tasks: Vec<SnapshotTaskEntry> = snapshot
+            .task_graph
+            .nodes
+            .into_iter()
+            .map(|node| SnapshotTaskEntry { id: node.id, name: node.name, status: node.status })
+            .collect();
+        tasks.sort_by_key(|task| task.id);
+
+        Self { snapshot_id: snapshot.id, timestamp: snapshot.timestamp, tasks, metrics }
+    }
+}
+
+/// Achata os campos numéricos de um documento JSON em chaves `a.b.c`
+fn flatten_metrics(prefix: &str, value: &serde_json::Value, out: &mut BTreeMap<String, f64>) {
+    match value {
+        serde_json::Value::Number(number) => {
+            if let Some(number) = number.as_f64() {
+                out.insert(prefix.to_string(), number);
+            }
+        }
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields {
+                flatten_metrics(&format!("{}.{}", prefix, key), field, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Diferença entre dois snapshots do TaskGraph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub from_snapshot: Uuid,
+    pub to_snapshot: Uuid,
+    /// Tarefas presentes apenas no segundo snapshot
+    pub added_tasks: Vec<SnapshotTaskEntry>,
+    /// Tarefas presentes apenas no primeiro snapshot
+    pub removed_tasks: Vec<SnapshotTaskEntry>,
+    /// Tarefas cujo status mudou: `(id, de, para)`
+    pub status_changes: Vec<(TaskId, TaskStatus, TaskStatus)>,
+    /// Variação (`para - de`) das métricas numéricas que mudaram
+    pub metric_deltas: BTreeMap<String, f64>,
+}
+
+impl SnapshotDiff {
+    /// Compara dois snapshots já carregados
+    pub fn compute(from: &TaskGraphSnapshot, to: &TaskGraphSnapshot) -> Self {
+        Self::between(&SnapshotIndex::from(from.clone()), &SnapshotIndex::from(to.clone()))
+    }
+
+    /// Compara dois índices percorrendo as listas ordenadas em paralelo
+    pub fn between(from: &SnapshotIndex, to: &SnapshotIndex) -> Self {
+        let mut added_tasks = Vec::new();
+        let mut removed_tasks = Vec::new();
+        let mut status_changes = Vec::new();
+
+        let mut old = from.tasks.iter().peekable();
+        let mut new = to.tasks.iter().peekable();
+        loop {
+            match (old.peek(), new.peek()) {
+                (Some(a), Some(b)) if a.id == b.id => {
+                    if a.status != b.status {
+                        status_changes.push((a.id, a.status.clone(), b.status.clone()));
+                    }
+                    old.next();
+                    new.next();
+                }
+                (Some(a), Some(b)) if a.id < b.id => {
+                    removed_tasks.push((*a).clone());
+                    old.next();
+                }
+                (Some(a), None) => {
+                    removed_tasks.push((*a).clone());
+                    old.next();
+                }
+                (_, Some(b)) => {
+                    added_tasks.push((*b).clone());
+                    new.next();
+                }
+                (None, None) => break,
+            }
+        }
+
+        let mut metric_deltas = BTreeMap::new();
+        for key in from.metrics.keys().chain(to.metrics.keys()) {
+            let before = from.metrics.get(key).copied().unwrap_or(0.0);
+            let after = to.metrics.get(key).copied().unwrap_or(0.0);
+            if after != before {
+                metric_deltas.insert(key.clone(), after - before);
+            }
+        }
+
+        Self {
+            from_snapshot: from.snapshot_id,
+            to_snapshot: to.snapshot_id,
+            added_tasks,
+            removed_tasks,
+            status_changes,
+            metric_deltas,
+        }
+    }
+
+    /// Indica se os snapshots são equivalentes
+    pub fn is_empty(&self) -> bool {
+        self.added_tasks.is_empty()
+            && self.removed_tasks.is_empty()
+            && self.status_changes.is_empty()
+            && self.metric_deltas.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "snapshot {} -> {}", self.from_snapshot, self.to_snapshot)?;
+        if self.is_empty() {
+            return writeln!(f, "  sem diferenças");
+        }
+        if !self.added_tasks.is_empty() {
+            writeln!(f, "tarefas adicionadas ({}):", self.added_tasks.len())?;
+            for task in &self.added_tasks {
+                writeln!(f, "  + {} {} [{:?}]", task.id, task.name, task.status)?;
+            }
+        }
+        if !self.removed_tasks.is_empty() {
+            writeln!(f, "tarefas removidas ({}):", self.removed_tasks.len())?;
+            for task in &self.removed_tasks {
+                writeln!(f, "  - {} {} [{:?}]", task.id, task.name, task.status)?;
+            }
+        }
+        if !self.status_changes.is_empty() {
+            writeln!(f, "mudanças de status ({}):", self.status_changes.len())?;
+            for (id, from, to) in &self.status_changes {
+                writeln!(f, "  ~ {} {:?} -> {:?}", id, from, to)?;
+            }
+        }
+        if !self.metric_deltas.is_empty() {
+            writeln!(f, "métricas:")?;
+            for (key, delta) in &self.metric_deltas {
+                writeln!(f, "  {} {:+}", key, delta)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    pub(crate) fn test_config(database_path: PathBuf) -> BackupConfig {
+        BackupConfig {
+            minio_config: MinioConfig {
+                endpoint: "http://localhost:9000".to_string(),
+                bucket_name: "test".to_string(),
+                access_key: "test".to_string(),
+                secret_key: "test".to_string(),
+                region: "local".to_string(),
+            },
+            sqlite_config: SqliteConfig {
+                database_path,
+                max_connections: 1,
+                connection_timeout_seconds: 5,
+            },
+            snapshot_config: SnapshotConfig {
+                interval_seconds: 60,
+                max_snapshots: 10,
+                compression_enabled: false,
+                codec: SnapshotCodec::default(),
+                compression: Compression::default(),
+                snapshot_prefix: "snapshots".to_string(),
+            },
+            checkpoint_config: CheckpointConfig {
+                tasks_per_checkpoint: 1,
+                retention_days: 7,
+                auto_cleanup: false,
+            },
+        }
+    }
+
+    fn empty_state() -> SystemState {
+        SystemState {
+            active_tasks: Vec::new(),
+            pending_tasks: Vec::new(),
+            failed_tasks: Vec::new(),
+            resource_usage: HashMap::new(),
+            configuration_hash: "test".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_latest_checkpoint_survives_wall_clock_jumps() {
+        let path = std::env::temp_dir().join(format!("backup-{}.db", Uuid::new_v4()));
+        std::fs::File::create(&path).unwrap();
+        let clock = Arc::new(MockClock::new());
+        let backup = BackupSystem::with_clock(test_config(path.clone()), clock.clone()).await.unwrap();
+
+        backup.create_checkpoint(1, None, empty_state(), HashMap::new()).await.unwrap();
+
+        // Relógio volta 1h: o checkpoint seguinte continua sendo o mais recente
+        clock.jump_wall(chrono::Duration::hours(-1));
+        let second = backup.create_checkpoint(2, None, empty_state(), HashMap::new()).await.unwrap();
+        let latest = backup.restore_latest_checkpoint().await.unwrap().unwrap();
+        assert_eq!(latest.id, second.id);
+
+        // Relógio avança 2h e depois volta: a ordem segue a criação
+        clock.jump_wall(chrono::Duration::hours(2));
+        backup.create_checkpoint(3, None, empty_state(), HashMap::new()).await.unwrap();
+        clock.jump_wall(chrono::Duration::hours(-2));
+        let fourth = backup.create_checkpoint(4, None, empty_state(), HashMap::new()).await.unwrap();
+        let latest = backup.restore_latest_checkpoint().await.unwrap().unwrap();
+        assert_eq!(latest.id, fourth.id);
+        assert_eq!(latest.task_count, 4);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_cost_ledger_rows_accumulate_across_instances() {
+        use crate::layers::ExecutionLayer;
+        
+        let path = std::env::temp_dir().join(format!("backup-{}.db", Uuid::new_v4()));
+        std::fs::File::create(&path).unwrap();
+        let entry = |layer: ExecutionLayer, cost: f64| CostEntry {
+            day: chrono::NaiveDate::from_ymd_opt(2026, 10, 17).unwrap(),
+            namespace: "research".to_string(),
+            layer,
+            tags: vec!["etl".to_string(), "ml".to_string()],
+            tasks: 1,
+            cost,
+        };
+        
+        let backup = BackupSystem::new(test_config(path.clone())).await.unwrap();
+        backup.record_cost(&entry(ExecutionLayer::Local, 2.0)).await.unwrap();
+        backup.record_cost(&entry(ExecutionLayer::Local, 3.0)).await.unwrap();
+        backup.record_cost(&entry(ExecutionLayer::Cluster, 10.0)).await.unwrap();
+        drop(backup);
+        
+        let reopened = BackupSystem::new(test_config(path.clone())).await.unwrap();
+        let mut costs = reopened.load_costs().await.unwrap();
+        costs.sort_by(|a, b| a.cost.total_cmp(&b.cost));
+        assert_eq!(costs, vec![
+            CostEntry { tasks: 2, ..entry(ExecutionLayer::Local, 5.0) },
+            entry(ExecutionLayer::Cluster, 10.0),
+        ]);
+        
+        let _ = std::fs::remove_file(path);
+    }
+
+    const SNAPSHOT_FIXTURE: &str = include_str!("../fixtures/compat/task_graph_snapshot.json");
+
+    #[test]
+    fn test_snapshot_fixture_round_trip() {
+        let snapshot = TaskGraphSnapshot::from_json(SNAPSHOT_FIXTURE.as_bytes()).unwrap();
+        assert_eq!(snapshot.format_version, SNAPSHOT_FORMAT_VERSION);
+        assert_eq!(snapshot.metadata.total_tasks, 4);
+        assert!(snapshot.restore_graph().is_ok());
+
+        let expected: serde_json::Value = serde_json::from_str(SNAPSHOT_FIXTURE).unwrap();
+        assert_eq!(serde_json::to_value(&snapshot).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_snapshot_newer_format_version_is_rejected() {
+        let newer = SNAPSHOT_FIXTURE.replace("\"format_version\": 1", "\"format_version\": 2");
+        assert!(matches!(
+            TaskGraphSnapshot::from_json(newer.as_bytes()),
+            Err(OrchestratorError::UnsupportedFormatVersion { found: 2, supported: SNAPSHOT_FORMAT_VERSION })
+        ));
+
+        let unversioned = SNAPSHOT_FIXTURE.replace(",\n  \"format_version\": 1", "");
+        assert_eq!(TaskGraphSnapshot::from_json(unversioned.as_bytes()).unwrap().format_version, 0);
+    }
+
+    #[test]
+    fn test_snapshot_restores_from_every_encoding() {
+        let snapshot = TaskGraphSnapshot::from_json(SNAPSHOT_FIXTURE.as_bytes()).unwrap();
+        let expected = serde_json::to_value(&snapshot).unwrap();
+
+        for encoding in SnapshotEncoding::all() {
+            let blob = snapshot.encode(encoding).unwrap();
+            // O cabeçalho prevalece sobre a extensão da chave
+            for legacy_gzip in [false, true] {
+                let (restored, _) = TaskGraphSnapshot::decode(&blob, legacy_gzip).unwrap();
+                assert_eq!(serde_json::to_value(&restored).unwrap(), expected, "{:?}", encoding);
+            }
+        }
+
+        // Snapshots anteriores ao cabeçalho: JSON puro ou `.json.gz`
+        let (plain, size) = TaskGraphSnapshot::decode(SNAPSHOT_FIXTURE.as_bytes(), false).unwrap();
+        assert_eq!((plain.id, size), (snapshot.id, SNAPSHOT_FIXTURE.len() as u64));
+        let mut gzipped = Vec::new();
+        Compression::Gzip.compress(SNAPSHOT_FIXTURE.as_bytes(), &mut gzipped).unwrap();
+        assert_eq!(TaskGraphSnapshot::decode(&gzipped, true).unwrap().0.id, snapshot.id);
+
+        // Bincode (tag 0) é um codec do task_mesh_core, não de snapshots
+        assert!(TaskGraphSnapshot::decode(b"TMCD\x00\x00", false).is_err());
+    }
+
+    fn entry(id: TaskId, name: &str, status: TaskStatus) -> SnapshotTaskEntry {
+        SnapshotTaskEntry { id, name: name.to_string(), status }
+    }
+
+    /// Dois snapshots em torno de um lote de mutações: uma tarefa removida,
+    /// uma adicionada, uma concluída e o contador de métricas incrementado
+    fn snapshots_around_mutations() -> (TaskGraphSnapshot, TaskGraphSnapshot, [TaskId; 3]) {
+        use crate::graph::TaskNode;
+
+        let mut metrics = TaskGraphSnapshot::from_json(SNAPSHOT_FIXTURE.as_bytes()).unwrap().system_metrics;
+        let mut mesh = TaskMesh::new();
+        mesh.add_task(TaskNode::new("kept".to_string(), None)).unwrap();
+        let finished = mesh.add_task(TaskNode::new("finished".to_string(), None)).unwrap();
+        let dropped = mesh.add_task(TaskNode::new("dropped".to_string(), None)).unwrap();
+        let before = TaskGraphSnapshot::capture(&mesh, &metrics, Utc::now());
+
+        let mut portable = mesh.to_portable();
+        portable.nodes.retain(|node| node.id != dropped);
+        let mut mesh = TaskMesh::from_portable(portable).unwrap();
+        let added = mesh.add_task(TaskNode::new("added".to_string(), None)).unwrap();
+        mesh.get_task_mut(&finished).unwrap().update_status(TaskStatus::Completed);
+        metrics.tasks.completed_tasks += 1;
+        let after = TaskGraphSnapshot::capture(&mesh, &metrics, Utc::now());
+
+        (before, after, [finished, dropped, added])
+    }
+
+    #[test]
+    fn test_snapshot_diff_after_mutations() {
+        let (before, after, [finished, dropped, added]) = snapshots_around_mutations();
+
+        let diff = SnapshotDiff::compute(&before, &after);
+        assert_eq!(
+            diff,
+            SnapshotDiff {
+                from_snapshot: before.id,
+                to_snapshot: after.id,
+                added_tasks: vec![entry(added, "added", TaskStatus::Pending)],
+                removed_tasks: vec![entry(dropped, "dropped", TaskStatus::Pending)],
+                status_changes: vec![(finished, TaskStatus::Pending, TaskStatus::Completed)],
+                metric_deltas: BTreeMap::from([
+                    ("metadata.completed_tasks".to_string(), 1.0),
+                    ("system_metrics.tasks.completed_tasks".to_string(), 1.0),
+                ]),
+            }
+        );
+
+        let rendered = diff.to_string();
+        assert!(rendered.contains(&format!("  + {} added [Pending]", added)));
+        assert!(rendered.contains(&format!("  - {} dropped [Pending]", dropped)));
+        assert!(rendered.contains(&format!("  ~ {} Pending -> Completed", finished)));
+        assert!(rendered.contains("  system_metrics.tasks.completed_tasks +1"));
+
+        assert!(SnapshotDiff::compute(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_diff_across_format_versions() {
+        let (before, after, _) = snapshots_around_mutations();
+
+        // O snapshot antigo foi gravado antes do campo de versão existir
+        let mut legacy = serde_json::to_value(&before).unwrap();
+        legacy.as_object_mut().unwrap().remove("format_version");
+        let legacy = TaskGraphSnapshot::from_json(&serde_json::to_vec(&legacy).unwrap()).unwrap();
+        assert_eq!(legacy.format_version, 0);
+
+        assert_eq!(SnapshotDiff::compute(&legacy, &after), SnapshotDiff::compute(&before, &after));
+    }
+}
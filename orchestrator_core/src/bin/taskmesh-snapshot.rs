@@ -0,0 +1,83 @@
+//! Ferramentas de snapshot do orchestrator
+//!
+//! Invocado diretamente ou via `taskmesh snapshot ...`.
+//!
+//! Uso:
+//!   taskmesh-snapshot diff [--config ARQUIVO] [--json] <snapshot_a> <snapshot_b>
+
+use std::process::ExitCode;
+
+use orchestrator_core::backup::{BackupConfig, BackupSystem};
+use orchestrator_core::{OrchestratorError, Result};
+use uuid::Uuid;
+
+const USAGE: &str = "uso:
+  taskmesh snapshot diff [--config ARQUIVO] [--json] <snapshot_a> <snapshot_b>";
+
+/// Arquivo de configuração do backup quando `--config` é omitido
+const DEFAULT_CONFIG_PATH: &str = "config/backup.toml";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("diff") => run_diff(&args[1..]).await,
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("erro: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Subcomando `diff`: o que mudou entre dois snapshots
+async fn run_diff(args: &[String]) -> Result<()> {
+    let mut config_path = DEFAULT_CONFIG_PATH.to_string();
+    let mut json = false;
+    let mut ids = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => {
+                config_path = iter
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| OrchestratorError::ConfigurationError("valor ausente para --config".to_string()))?
+            }
+            "--json" => json = true,
+            value => ids.push(Uuid::parse_str(value).map_err(|e| {
+                OrchestratorError::ConfigurationError(format!("ID de snapshot inválido '{}': {}", value, e))
+            })?),
+        }
+    }
+    let [id_a, id_b] = ids[..] else {
+        return Err(OrchestratorError::ConfigurationError(USAGE.to_string()));
+    };
+
+    let backup = BackupSystem::new(load_config(&config_path)?).await?;
+    let diff = backup.diff_snapshots(id_a, id_b).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
+
+fn load_config(path: &str) -> Result<BackupConfig> {
+    config::Config::builder()
+        .add_source(config::File::with_name(path))
+        .add_source(config::Environment::with_prefix("ORCHESTRATOR_BACKUP").separator("__"))
+        .build()
+        .and_then(|settings| settings.try_deserialize())
+        .map_err(|e| OrchestratorError::ConfigurationError(format!("{}: {}", path, e)))
+}
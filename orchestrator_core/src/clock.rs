@@ -0,0 +1,94 @@
+//! # Relógios
+//!
+//! Abstração de tempo injetável. Decisões de agendamento (backoff de retry,
+//! timeout do circuit breaker) usam o relógio monotônico; o relógio de parede
+//! pode saltar em ajustes de NTP e serve apenas para exibição e persistência.
+
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Fonte de tempo
+pub trait Clock: Debug + Send + Sync {
+    /// Instante monotônico (nunca retrocede)
+    fn now(&self) -> Instant;
+
+    /// Horário de parede, apenas para exibição
+    fn wall(&self) -> DateTime<Utc>;
+}
+
+/// Relógio do sistema
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wall(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Relógio controlado manualmente, para testes determinísticos
+#[derive(Debug)]
+pub struct MockClock {
+    state: Mutex<(Instant, DateTime<Utc>)>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new((Instant::now(), Utc::now())),
+        }
+    }
+
+    /// Avança os dois relógios
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.0 += duration;
+        state.1 += chrono::Duration::from_std(duration).unwrap();
+    }
+
+    /// Salta apenas o relógio de parede (para frente ou para trás)
+    pub fn jump_wall(&self, delta: chrono::Duration) {
+        self.state.lock().unwrap().1 += delta;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.state.lock().unwrap().0
+    }
+
+    fn wall(&self) -> DateTime<Utc> {
+        self.state.lock().unwrap().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_wall_jump_keeps_monotonic() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let wall = clock.wall();
+
+        clock.jump_wall(chrono::Duration::hours(-1));
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.wall(), wall - chrono::Duration::hours(1));
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(clock.now() - start, Duration::from_secs(5));
+    }
+}
@@ -6,7 +6,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use crate::layers::{ExecutionConfig, ClusterConfig, QuantumSimConfig};
+use crate::cost::CostConfig;
+use crate::errors::OrchestratorError;
+use crate::layers::{ExecutionConfig, ClusterConfig, QuantumBackend, QuantumSimConfig};
 use crate::learning::LearningConfig;
 
 /// Configuração principal do orchestrator
@@ -16,9 +18,14 @@ pub struct OrchestratorConfig {
     pub general: GeneralConfig,
     /// Configuração das camadas de execução
     pub execution: ExecutionConfig,
-    /// Configuração do cluster
+    /// Camadas de execução habilitadas
+    #[serde(default)]
+    pub layers: LayersConfig,
+    /// Obsoleto: use `layers.cluster` (migrado por [`Self::migrate_legacy_layers`])
+    #[serde(default, skip_serializing)]
     pub cluster: Option<ClusterConfig>,
-    /// Configuração de simulação quântica
+    /// Obsoleto: use `layers.quantum` (migrado por [`Self::migrate_legacy_layers`])
+    #[serde(default, skip_serializing)]
     pub quantum: Option<QuantumSimConfig>,
     /// Configuração de aprendizado
     pub learning: LearningConfig,
@@ -30,6 +37,104 @@ pub struct OrchestratorConfig {
     pub security: SecurityConfig,
     /// Configuração de observabilidade
     pub observability: ObservabilityConfig,
+    /// Orçamentos de custo por namespace
+    #[serde(default)]
+    pub cost: CostConfig,
+}
+
+/// Qubits máximos do simulador local (vetor de estado em memória)
+pub const MAX_SIMULATOR_QUBITS: usize = 30;
+
+/// Camadas de execução registradas pelo orchestrator
+///
+/// Apenas as seções presentes viram camadas; sem `local`, tarefas precisam
+/// ser roteadas para outra camada. A seção `local` apenas habilita a camada:
+/// seus parâmetros são os de [`OrchestratorConfig::execution`], os mesmos
+/// recebidos a cada execução.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayersConfig {
+    pub local: Option<ExecutionConfig>,
+    pub cluster: Option<ClusterConfig>,
+    pub quantum: Option<QuantumSimConfig>,
+}
+
+impl Default for LayersConfig {
+    fn default() -> Self {
+        Self {
+            local: Some(ExecutionConfig::default()),
+            cluster: None,
+            quantum: None,
+        }
+    }
+}
+
+impl LayersConfig {
+    /// Valida as seções configuradas
+    ///
+    /// Todos os problemas encontrados são reunidos em um único `ValidationError`.
+    pub fn validate(&self) -> crate::errors::Result<()> {
+        let mut problems = Vec::new();
+        
+        if let Some(cluster) = &self.cluster {
+            if cluster.nodes.is_empty() {
+                problems.push("layers.cluster.nodes must contain at least one node".to_string());
+            }
+            for node in &cluster.nodes {
+                match reqwest::Url::parse(&node.endpoint) {
+                    Ok(url) if url.has_host() => {}
+                    Ok(_) => problems.push(format!(
+                        "layers.cluster node '{}' endpoint '{}' has no host", node.id, node.endpoint
+                    )),
+                    Err(e) => problems.push(format!(
+                        "layers.cluster node '{}' endpoint '{}' is not a valid URL: {}", node.id, node.endpoint, e
+                    )),
+                }
+                if let Some(tls) = &node.tls {
+                    if tls.client_cert.is_some() != tls.client_key.is_some() {
+                        problems.push(format!(
+                            "layers.cluster node '{}' tls needs both client_cert and client_key", node.id
+                        ));
+                    }
+                }
+            }
+        }
+        
+        if let Some(quantum) = &self.quantum {
+            if quantum.qubits == 0 {
+                problems.push("layers.quantum.qubits must be at least 1".to_string());
+            } else if matches!(quantum.backend, QuantumBackend::Simulator) && quantum.qubits > MAX_SIMULATOR_QUBITS {
+                problems.push(format!(
+                    "layers.quantum.qubits {} exceeds the local simulator limit of {}", quantum.qubits, MAX_SIMULATOR_QUBITS
+                ));
+            }
+            if quantum.max_threads == Some(0) {
+                problems.push("layers.quantum.max_threads must be at least 1".to_string());
+            }
+            let noise = &quantum.noise_model;
+            for (field, rate) in [
+                ("gate_error_rate", noise.gate_error_rate),
+                ("measurement_error_rate", noise.measurement_error_rate),
+            ] {
+                if !(0.0..=1.0).contains(&rate) {
+                    problems.push(format!("layers.quantum.noise_model.{} must be within [0, 1], got {}", field, rate));
+                }
+            }
+            if noise.decoherence_time_ns.is_nan() || noise.decoherence_time_ns < 0.0 {
+                problems.push("layers.quantum.noise_model.decoherence_time_ns must be non-negative".to_string());
+            }
+        }
+        
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(OrchestratorError::validation(
+                "layers",
+                "layer_config",
+                &problems.len().to_string(),
+                problems.join("; "),
+            ))
+        }
+    }
 }
 
 /// Configuração geral
@@ -219,6 +324,7 @@ impl Default for OrchestratorConfig {
                 debug_mode: false,
             },
             execution: ExecutionConfig::default(),
+            layers: LayersConfig::default(),
             cluster: None,
             quantum: None,
             learning: LearningConfig::default(),
@@ -271,6 +377,7 @@ impl Default for OrchestratorConfig {
                     check_timeout: 5,
                 },
             },
+            cost: CostConfig::default(),
         }
     }
 }
@@ -283,7 +390,23 @@ impl OrchestratorConfig {
             .add_source(config::Environment::with_prefix("ORCHESTRATOR"))
             .build()?;
             
-        settings.try_deserialize()
+        let mut config: Self = settings.try_deserialize()?;
+        config.migrate_legacy_layers();
+        Ok(config)
+    }
+    
+    /// Move as seções obsoletas `cluster` e `quantum` de nível superior para `layers`
+    ///
+    /// Seções já presentes em `layers` prevalecem; cada seção obsoleta gera um aviso.
+    pub fn migrate_legacy_layers(&mut self) {
+        if let Some(cluster) = self.cluster.take() {
+            tracing::warn!("Top-level `cluster` section is deprecated; use `layers.cluster`");
+            self.layers.cluster.get_or_insert(cluster);
+        }
+        if let Some(quantum) = self.quantum.take() {
+            tracing::warn!("Top-level `quantum` section is deprecated; use `layers.quantum`");
+            self.layers.quantum.get_or_insert(quantum);
+        }
     }
     
     /// Salva configuração em arquivo
@@ -312,6 +435,10 @@ impl OrchestratorConfig {
             return Err("Evolution rate must be between 0 and 1".to_string());
         }
         
+        if let Some((namespace, _)) = self.cost.budgets.iter().find(|(_, budget)| budget.is_nan() || **budget < 0.0) {
+            return Err(format!("Budget for namespace '{}' must be a non-negative number", namespace));
+        }
+        
         Ok(())
     }
     
@@ -391,6 +518,88 @@ mod tests {
         assert!(prod_config.security.authentication_enabled);
     }
     
+    fn cluster_with_endpoints(endpoints: &[&str]) -> ClusterConfig {
+        use crate::layers::*;
+        
+        ClusterConfig {
+            nodes: endpoints
+                .iter()
+                .enumerate()
+                .map(|(i, endpoint)| ClusterNode {
+                    id: format!("node-{}", i),
+                    endpoint: endpoint.to_string(),
+                    capacity: ExecutionConfig::default().resource_limits,
+                    status: NodeStatus::Active,
+                    tls: None,
+                    auth: None,
+                })
+                .collect(),
+            load_balancer: LoadBalancerConfig { strategy: LoadBalancingStrategy::RoundRobin, health_check_interval: 30 },
+            fault_tolerance: FaultToleranceConfig { max_retries: 3, retry_delay_ms: 100, failover_enabled: true },
+            cost: Default::default(),
+        }
+    }
+    
+    fn quantum_with_qubits(qubits: usize) -> QuantumSimConfig {
+        use crate::layers::NoiseModel;
+        
+        QuantumSimConfig {
+            qubits,
+            gates: vec![],
+            noise_model: NoiseModel { gate_error_rate: 0.01, measurement_error_rate: 0.02, decoherence_time_ns: 5e4 },
+            backend: QuantumBackend::Simulator,
+            cost: Default::default(),
+            seed: None,
+            cache_ttl_seconds: 0,
+            max_threads: None,
+        }
+    }
+    
+    #[test]
+    fn test_full_layers_config_is_valid() {
+        let layers = LayersConfig {
+            local: Some(ExecutionConfig::default()),
+            cluster: Some(cluster_with_endpoints(&["http://10.0.0.1:8080", "https://worker.example.com"])),
+            quantum: Some(quantum_with_qubits(MAX_SIMULATOR_QUBITS)),
+        };
+        assert!(layers.validate().is_ok());
+    }
+    
+    #[test]
+    fn test_layers_validation_reports_every_problem() {
+        let mut quantum = quantum_with_qubits(500);
+        quantum.noise_model.gate_error_rate = 1.5;
+        let layers = LayersConfig {
+            local: None,
+            cluster: Some(cluster_with_endpoints(&["http://ok:1", "not a url"])),
+            quantum: Some(quantum),
+        };
+        
+        match layers.validate() {
+            Err(OrchestratorError::ValidationError { field, message, .. }) => {
+                assert_eq!(field, "layers");
+                assert!(message.contains("node 'node-1' endpoint 'not a url'"), "{}", message);
+                assert!(message.contains("qubits 500 exceeds"), "{}", message);
+                assert!(message.contains("gate_error_rate"), "{}", message);
+                assert!(!message.contains("node-0"), "{}", message);
+            }
+            other => panic!("expected aggregated validation error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_legacy_top_level_layers_are_migrated() {
+        let mut value = serde_json::to_value(OrchestratorConfig::default()).unwrap();
+        value["quantum"] = serde_json::to_value(quantum_with_qubits(4)).unwrap();
+        let mut config: OrchestratorConfig = serde_json::from_value(value).unwrap();
+        assert!(config.layers.quantum.is_none());
+        
+        config.migrate_legacy_layers();
+        assert_eq!(config.layers.quantum.as_ref().map(|quantum| quantum.qubits), Some(4));
+        assert!(config.quantum.is_none());
+        assert!(serde_json::to_value(&config).unwrap().get("quantum").is_none());
+    }
+    
     #[test]
     fn test_file_serialization() {
         let config = OrchestratorConfig::default();
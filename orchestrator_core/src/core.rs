@@ -3,22 +3,34 @@
 //! Módulo principal do Task Mesh IA Orchestrator Core.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use futures::StreamExt;
 use tokio::sync::{RwLock, Mutex};
 use chrono::{DateTime, Utc};
 use tracing::{info, warn, error, debug};
 
+use crate::background::BackgroundTasks;
+use crate::backup::BackupSystem;
 use crate::config::OrchestratorConfig;
+use crate::cost::{BudgetAction, CostGroupBy, CostLedger, CostReport, COST_CONTEXT_KEY};
 use crate::errors::{OrchestratorError, Result};
-use crate::graph::{TaskMesh, TaskNode, TaskId, TaskStatus};
-use crate::layers::{LayerManager, ExecutionLayer, TaskExecutionResult, ExecutionLayerTrait};
-use crate::symbiotic::{SymbioticConsciousness, SystemEvent, EventSeverity};
+use crate::graph::{DependencyEdge, EdgeId, LayerPolicy, TaskMesh, TaskNode, TaskId, TaskStatus};
+use crate::layers::{
+    ClusterLayer, ExecutionLayer, ExecutionLayerTrait, LayerManager, LocalLayer,
+    QuantumSimLayer, TaskExecutionResult, TaskExecutionStatus,
+};
+use crate::symbiotic::{ActionSpec, SymbioticConsciousness, SystemEvent, EventSeverity, EventType, TaskFilter, EVENT_SOURCE};
 use crate::learning::ContinuousLearning;
 use crate::metrics::MetricsCollector;
 
 /// Resultado de execução de tarefa (re-export)
 pub use crate::layers::TaskExecutionResult;
 
+/// Tempo máximo de espera pelos loops de background no `stop`
+const BACKGROUND_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Estado do orchestrator
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OrchestratorStatus {
@@ -36,6 +48,32 @@ pub enum OrchestratorStatus {
     Error,
 }
 
+/// Resultado de uma ação de recomendação
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionOutcome {
+    /// Ação executada
+    Applied,
+    /// Ação seria executada (dry-run)
+    WouldApply,
+    /// Ação sem efeito neste orchestrator
+    Skipped(String),
+}
+
+/// Ação de uma recomendação e seu resultado
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedAction {
+    pub spec: ActionSpec,
+    pub outcome: ActionOutcome,
+}
+
+/// Resultado de `OrchestratorCore::apply_recommendation`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedActions {
+    pub recommendation_id: String,
+    pub dry_run: bool,
+    pub actions: Vec<AppliedAction>,
+}
+
 /// Core principal do orchestrator
 #[derive(Debug)]
 pub struct OrchestratorCore {
@@ -59,22 +97,56 @@ pub struct OrchestratorCore {
     running_tasks: Arc<RwLock<HashMap<TaskId, tokio::task::JoinHandle<()>>>>,
     /// Timestamp de inicialização
     started_at: DateTime<Utc>,
+    /// Loops de background (execução, métricas, consciência)
+    background: BackgroundTasks,
+    /// Roteamentos aplicados a partir de recomendações (o mais recente vence)
+    layer_overrides: Arc<RwLock<Vec<(TaskFilter, ExecutionLayer)>>>,
+    /// Limite atual de tarefas em paralelo
+    max_parallel_tasks: Arc<AtomicUsize>,
+    /// Sistema de backup para checkpoints sob demanda
+    backup: Option<Arc<BackupSystem>>,
+    /// Custos acumulados das execuções
+    costs: Arc<CostLedger>,
 }
 
 impl OrchestratorCore {
-    /// Cria nova instância do orchestrator
+    /// Cria nova instância do orchestrator (ver [`OrchestratorCore::from_config`])
     pub async fn new(config: OrchestratorConfig) -> Result<Self> {
+        Self::from_config(config).await
+    }
+    
+    /// Cria o orchestrator registrando exatamente as camadas de `config.layers`
+    ///
+    /// Seções de camada inválidas geram um único `ValidationError` com todos
+    /// os problemas.
+    pub async fn from_config(mut config: OrchestratorConfig) -> Result<Self> {
         info!("Initializing Orchestrator Core with config: {:?}", config.general.instance_name);
         
         // Valida configuração
+        config.migrate_legacy_layers();
         config.validate().map_err(|e| OrchestratorError::ConfigurationError(e))?;
+        config.layers.validate()?;
         
         // Inicializa componentes
         let task_mesh = Arc::new(RwLock::new(TaskMesh::new()));
-        let layer_manager = Arc::new(LayerManager::new());
+        let mut layer_manager = LayerManager::new();
+        if let Some(local) = &config.layers.local {
+            if serde_json::to_value(local).ok() != serde_json::to_value(&config.execution).ok() {
+                warn!("layers.local differs from execution; the local layer uses execution");
+            }
+            layer_manager.add_layer(Box::new(LocalLayer::new(config.execution.clone())));
+        }
+        if let Some(cluster) = &config.layers.cluster {
+            layer_manager.add_layer(Box::new(ClusterLayer::new(cluster.clone())));
+        }
+        if let Some(quantum) = &config.layers.quantum {
+            layer_manager.add_layer(Box::new(QuantumSimLayer::new(quantum.clone())));
+        }
+        let layer_manager = Arc::new(layer_manager);
         let consciousness = Arc::new(SymbioticConsciousness::new());
         let learning = Arc::new(ContinuousLearning::new(config.learning.clone()));
         let metrics = Arc::new(MetricsCollector::new()?);
+        let max_parallel_tasks = Arc::new(AtomicUsize::new(config.execution.max_parallel_tasks.max(1)));
         
         let orchestrator = Self {
             config,
@@ -87,34 +159,62 @@ impl OrchestratorCore {
             execution_queue: Arc::new(Mutex::new(Vec::new())),
             running_tasks: Arc::new(RwLock::new(HashMap::new())),
             started_at: Utc::now(),
+            background: BackgroundTasks::new(),
+            layer_overrides: Arc::new(RwLock::new(Vec::new())),
+            max_parallel_tasks,
+            backup: None,
+            costs: Arc::new(CostLedger::new()),
         };
         
         info!("Orchestrator Core initialized successfully");
         Ok(orchestrator)
     }
     
+    /// Usa o sistema de backup para as ações `CreateCheckpoint` e para
+    /// persistir o ledger de custos (recarregado no [`Self::start`])
+    pub fn with_backup(mut self, backup: Arc<BackupSystem>) -> Self {
+        self.backup = Some(backup);
+        self
+    }
+    
+    /// Registra uma camada adicional (substitui a camada do mesmo tipo)
+    ///
+    /// Deve ser chamado antes de o orchestrator ser compartilhado.
+    pub fn with_layer(mut self, layer: Box<dyn ExecutionLayerTrait>) -> Self {
+        Arc::get_mut(&mut self.layer_manager)
+            .expect("layers are registered before the orchestrator is shared")
+            .add_layer(layer);
+        self
+    }
+    
     /// Inicia o orchestrator
     pub async fn start(&self) -> Result<()> {
         info!("Starting Orchestrator Core");
+        self.background.reset();
+        
+        if let Some(backup) = &self.backup {
+            let costs = backup.load_costs().await?;
+            info!("Restored {} cost ledger rows", costs.len());
+            self.costs.restore(costs).await;
+        }
         
         {
             let mut status = self.status.write().await;
             *status = OrchestratorStatus::Running;
         }
         
+        // Verificação inicial das camadas (handshake e matriz de compatibilidade do cluster)
+        self.layer_manager.health_check_all().await;
+        
         // Inicializa loops de execução
         self.start_execution_loop().await;
         self.start_metrics_collection_loop().await;
         self.start_consciousness_loop().await;
         
         // Emite evento de inicialização
-        let start_event = SystemEvent {
-            event_type: "orchestrator_started".to_string(),
-            data: HashMap::new(),
-            timestamp: Utc::now(),
-            source: "orchestrator_core".to_string(),
-            severity: EventSeverity::Medium,
-        };
+        let start_event = SystemEvent::new(EventType::Custom("orchestrator_started".to_string()), None, serde_json::json!({}))
+            .with_severity(EventSeverity::Medium)
+            .with_source(EVENT_SOURCE);
         
         let _ = self.consciousness.process_event(start_event).await;
         
@@ -136,6 +236,13 @@ impl OrchestratorCore {
         for handle in running_tasks.values() {
             handle.abort();
         }
+        drop(running_tasks);
+        
+        // Aguarda o término dos loops de background
+        let lingering = self.background.shutdown(BACKGROUND_SHUTDOWN_TIMEOUT).await;
+        if !lingering.is_empty() {
+            warn!("{} background loops did not stop: {:?}", lingering.len(), lingering);
+        }
         
         {
             let mut status = self.status.write().await;
@@ -146,16 +253,35 @@ impl OrchestratorCore {
         Ok(())
     }
     
+    /// Número de loops de background em execução
+    pub fn background_task_count(&self) -> usize {
+        self.background.count()
+    }
+    
     /// Adiciona tarefa ao grafo
+    ///
+    /// Com o orçamento mensal do namespace esgotado, a tarefa é recusada ou
+    /// fixada na camada local, conforme `cost.on_exhausted`.
     pub async fn add_task(&self, mut task: TaskNode) -> Result<TaskId> {
         let task_id = task.id;
         
         debug!("Adding task: {} ({})", task.name, task_id);
         
+        let downgrade = self.check_budget(&task).await?;
+        
         // Adiciona ao grafo
         {
             let mut mesh = self.task_mesh.write().await;
             mesh.add_task(task.clone())?;
+            // Fixada após a validação: a configuração pode ser de outra camada
+            if downgrade {
+                if let Some(task_mut) = mesh.get_task_mut(&task_id) {
+                    task_mut.configuration.insert(
+                        crate::graph::config_keys::LAYER_POLICY.to_string(),
+                        serde_json::json!(ExecutionLayer::Local.as_str()),
+                    );
+                }
+            }
         }
         
         // Enfileira para execução se não tiver dependências
@@ -169,16 +295,12 @@ impl OrchestratorCore {
         self.metrics.increment_task_counter().await;
         
         // Emite evento para consciência
-        let task_event = SystemEvent {
-            event_type: "task_added".to_string(),
-            data: HashMap::from([
-                ("task_id".to_string(), serde_json::Value::String(task_id.to_string())),
-                ("task_name".to_string(), serde_json::Value::String(task.name.clone())),
-            ]),
-            timestamp: Utc::now(),
-            source: "orchestrator_core".to_string(),
-            severity: EventSeverity::Low,
-        };
+        let task_event = SystemEvent::new(
+            EventType::Custom("task_added".to_string()),
+            Some(task_id),
+            serde_json::json!({ "task_id": task_id.to_string(), "task_name": task.name }),
+        )
+        .with_source(EVENT_SOURCE);
         
         let _ = self.consciousness.process_event(task_event).await;
         
@@ -186,6 +308,129 @@ impl OrchestratorCore {
         Ok(task_id)
     }
     
+    /// Verifica o orçamento do namespace da tarefa
+    ///
+    /// Retorna `true` se a tarefa deve ser rebaixada para a camada local.
+    async fn check_budget(&self, task: &TaskNode) -> Result<bool> {
+        let namespace = task.namespace();
+        let Some(budget) = self.config.cost.budgets.get(namespace).copied() else {
+            return Ok(false);
+        };
+        let spent = self.costs.month_to_date(namespace, Utc::now()).await;
+        if spent < budget {
+            return Ok(false);
+        }
+        
+        let action = self.config.cost.on_exhausted;
+        warn!("Budget of namespace '{}' exhausted ({:.2} of {:.2}), {:?} task {}", namespace, spent, budget, action, task.id);
+        let budget_event = SystemEvent::new(
+            EventType::Custom("budget_exceeded".to_string()),
+            Some(task.id),
+            serde_json::json!({
+                "namespace": namespace,
+                "task_id": task.id.to_string(),
+                "spent": spent,
+                "budget": budget,
+                "action": format!("{:?}", action),
+            }),
+        )
+        .with_severity(EventSeverity::High)
+        .with_source(EVENT_SOURCE);
+        let _ = self.consciousness.process_event(budget_event).await;
+        
+        match action {
+            BudgetAction::Reject => Err(OrchestratorError::ResourceLimitExceeded(format!(
+                "Monthly budget of namespace '{}' exhausted ({:.2} of {:.2})", namespace, spent, budget
+            ))),
+            BudgetAction::DowngradeToLocal => Ok(true),
+        }
+    }
+    
+    /// Relatório de custo dos dias em `range`, agrupado por `group_by`
+    pub async fn cost_report(
+        &self,
+        range: std::ops::RangeInclusive<chrono::NaiveDate>,
+        group_by: CostGroupBy,
+    ) -> CostReport {
+        self.costs.report(range, group_by).await
+    }
+    
+    /// Submete uma tarefa para execução
+    ///
+    /// A tarefa é executada por `execute_ready_tasks`/`run_until_idle` (ou pelo
+    /// loop de execução, se iniciado) assim que suas dependências concluírem.
+    pub async fn submit(&self, task: TaskNode) -> Result<TaskId> {
+        self.add_task(task).await
+    }
+    
+    /// Adiciona uma dependência entre tarefas já submetidas
+    pub async fn add_dependency(&self, edge: DependencyEdge) -> Result<EdgeId> {
+        let target = edge.target;
+        let edge_id = self.task_mesh.write().await.add_dependency(edge)?;
+        
+        // O alvo pode ter sido enfileirado antes de ganhar a dependência
+        if !self.is_task_ready(&target).await? {
+            self.execution_queue.lock().await.retain(|&id| id != target);
+        }
+        
+        Ok(edge_id)
+    }
+    
+    /// Executa todas as tarefas cujas dependências já foram concluídas
+    ///
+    /// Falhas ficam registradas nos nós e não interrompem as demais tarefas.
+    pub async fn execute_ready_tasks(&self) -> Result<Vec<TaskExecutionResult>> {
+        let ready = self.ready_task_ids().await?;
+        let limit = self.max_parallel_tasks.load(Ordering::Relaxed);
+        let outcomes: Vec<_> = futures::stream::iter(ready.iter().map(|task_id| self.execute_task(*task_id)))
+            .buffered(limit)
+            .collect()
+            .await;
+        
+        let mut results = Vec::with_capacity(outcomes.len());
+        for (task_id, outcome) in ready.iter().zip(outcomes) {
+            match outcome {
+                Ok(result) => results.push(result),
+                Err(e) => warn!("Task {} not executed: {}", task_id, e),
+            }
+        }
+        Ok(results)
+    }
+    
+    /// Executa rodadas de tarefas prontas até que nenhuma reste
+    ///
+    /// Tarefas retidas pelo `lag` de uma dependência são aguardadas.
+    pub async fn run_until_idle(&self) -> Result<Vec<TaskExecutionResult>> {
+        let mut results = Vec::new();
+        let mut previous_round = Vec::new();
+        
+        loop {
+            let mut ready = self.ready_task_ids().await?;
+            if ready.is_empty() {
+                let next_release = self.task_mesh.read().await.next_release_time(Utc::now());
+                let Some(release) = next_release else { break };
+                let wait = (release - Utc::now()).to_std().unwrap_or_default();
+                debug!("Waiting {:?} for lagged dependencies", wait);
+                tokio::time::sleep(wait).await;
+                ready = self.ready_task_ids().await?;
+            }
+            // Mesmo conjunto pronto duas vezes seguidas: nada progrediu
+            if ready.is_empty() || ready == previous_round {
+                break;
+            }
+            results.extend(self.execute_ready_tasks().await?);
+            previous_round = ready;
+        }
+        
+        Ok(results)
+    }
+    
+    /// IDs das tarefas prontas, em ordem de prioridade
+    async fn ready_task_ids(&self) -> Result<Vec<TaskId>> {
+        let mesh = self.task_mesh.read().await;
+        Ok(mesh.get_ready_tasks()?.iter().map(|task| task.id).collect())
+    }
+    
     /// Remove tarefa do grafo
     pub async fn remove_task(&self, task_id: TaskId) -> Result<()> {
         debug!("Removing task: {}", task_id);
@@ -226,54 +471,94 @@ impl OrchestratorCore {
             ));
         }
         
-        // Atualiza status da tarefa
-        {
-            let mut mesh = self.task_mesh.write().await;
-            if let Some(task_mut) = mesh.get_task_mut(&task_id) {
-                task_mut.update_status(TaskStatus::Running);
+        // Condição sobre os resultados das dependências
+        let should_run = self.task_mesh.read().await.evaluate_condition(&task_id);
+        match should_run {
+            Ok(true) => {},
+            Ok(false) => return Ok(self.skip_task(&task).await),
+            Err(e) => {
+                self.mark_failed(&task_id, format!("Condition evaluation failed: {}", e)).await;
+                self.metrics.record_task_failure().await;
+                return Err(e);
             }
         }
         
+        // Entrega as saídas das dependências de dados
+        let mut task = task;
+        let inputs = self.task_mesh.read().await.data_inputs(&task_id)?;
+        if !inputs.is_empty() {
+            task.execution_context.insert("inputs".to_string(), serde_json::json!(inputs));
+        }
+        
         // Seleciona camada de execução
-        let layer = self.select_execution_layer(&task).await?;
+        let layer = match self.select_execution_layer(&task).await {
+            Ok(layer) => layer,
+            Err(e) => {
+                self.mark_failed(&task_id, e.to_string()).await;
+                return Err(e);
+            }
+        };
         
         // Obtém executor da camada
         let executor = self.layer_manager.get_layer(&layer)
             .ok_or_else(|| OrchestratorError::LayerNotAvailable(layer.clone()))?;
         
+        // Atualiza status da tarefa
+        {
+            let mut mesh = self.task_mesh.write().await;
+            if let Some(task_mut) = mesh.get_task_mut(&task_id) {
+                task_mut.update_status(TaskStatus::Running);
+                task_mut.metrics.execution_layer = layer.clone();
+            }
+        }
+        
         // Executa tarefa
-        let start_time = Utc::now();
         let result = executor.execute_task(&task, &self.config.execution).await;
+        Self::sync_layer_metrics(&self.metrics, &layer, executor).await;
         
         let execution_result = match result {
-            Ok(mut exec_result) => {
-                // Atualiza status da tarefa
+            Ok(exec_result) => {
+                let succeeded = exec_result.status == TaskExecutionStatus::Success;
+                
+                // Execuções com falha também são cobradas
+                let cost = executor.cost_model().cost_of(&task, &exec_result);
+                let entry = self.costs.record(&task, &exec_result, cost).await;
+                if let Some(backup) = &self.backup {
+                    if let Err(e) = backup.record_cost(&entry).await {
+                        warn!("Failed to persist cost of task {}: {}", task_id, e);
+                    }
+                }
+                
+                // Grava o resultado no nó
                 {
                     let mut mesh = self.task_mesh.write().await;
                     if let Some(task_mut) = mesh.get_task_mut(&task_id) {
-                        task_mut.update_status(TaskStatus::Completed);
-                        task_mut.metrics.start_time = Some(start_time);
-                        task_mut.metrics.end_time = exec_result.end_time;
+                        Self::apply_result(task_mut, &exec_result);
+                        task_mut.execution_context.insert(COST_CONTEXT_KEY.to_string(), serde_json::json!(cost));
+                        task_mut.update_status(if succeeded { TaskStatus::Completed } else { TaskStatus::Failed });
                     }
                 }
                 
-                // Registra sucesso nas métricas
-                let duration = (Utc::now() - start_time).num_milliseconds() as f64;
-                self.metrics.record_task_success(duration).await;
+                // Registra nas métricas
+                if succeeded {
+                    self.metrics.record_task_success(exec_result.resource_usage.execution_time_ms as f64).await;
+                } else {
+                    self.metrics.record_task_failure().await;
+                }
                 
                 // Adiciona dados ao aprendizado
                 let _ = self.learning.add_execution_data(&task, &exec_result).await;
                 
+                if !succeeded {
+                    warn!("Task finished with status {:?}: {}", exec_result.status, task_id);
+                    self.propagate_failure(&task_id).await;
+                    return Ok(exec_result);
+                }
+                
                 exec_result
             },
             Err(e) => {
-                // Atualiza status da tarefa como falha
-                {
-                    let mut mesh = self.task_mesh.write().await;
-                    if let Some(task_mut) = mesh.get_task_mut(&task_id) {
-                        task_mut.update_status(TaskStatus::Failed);
-                    }
-                }
+                self.mark_failed(&task_id, e.to_string()).await;
                 
                 // Registra falha nas métricas
                 self.metrics.record_task_failure().await;
@@ -287,18 +572,15 @@ impl OrchestratorCore {
         self.enqueue_dependent_tasks(&task_id).await?;
         
         // Emite evento de conclusão
-        let completion_event = SystemEvent {
-            event_type: "task_completed".to_string(),
-            data: HashMap::from([
-                ("task_id".to_string(), serde_json::Value::String(task_id.to_string())),
-                ("execution_time_ms".to_string(), serde_json::Value::Number(
-                    serde_json::Number::from(execution_result.resource_usage.execution_time_ms)
-                )),
-            ]),
-            timestamp: Utc::now(),
-            source: "orchestrator_core".to_string(),
-            severity: EventSeverity::Low,
-        };
+        let completion_event = SystemEvent::new(
+            EventType::TaskCompleted,
+            Some(task_id),
+            serde_json::json!({
+                "task_id": task_id.to_string(),
+                "execution_time_ms": execution_result.resource_usage.execution_time_ms,
+            }),
+        )
+        .with_source(EVENT_SOURCE);
         
         let _ = self.consciousness.process_event(completion_event).await;
         
@@ -306,6 +588,89 @@ impl OrchestratorCore {
         Ok(execution_result)
     }
     
+    /// Grava o resultado de uma execução no nó do grafo
+    fn apply_result(task: &mut TaskNode, result: &TaskExecutionResult) {
+        task.metrics.start_time = Some(result.start_time);
+        task.metrics.end_time = result.end_time;
+        task.metrics.cpu_usage = result.resource_usage.cpu_percent;
+        task.metrics.memory_usage = result.resource_usage.memory_mb;
+        task.metrics.network_usage = result.resource_usage.network_io_mb;
+        task.metrics.execution_layer = result.layer.clone();
+        if let Some(error) = &result.error_message {
+            task.metrics.error_messages.push(error.clone());
+        }
+        if let Ok(value) = serde_json::to_value(result) {
+            task.execution_context.insert("last_result".to_string(), value);
+        }
+    }
+    
+    /// Marca uma tarefa cuja condição é falsa como pulada, sem executá-la
+    ///
+    /// Dependentes hard/data são cancelados; soft e de recurso seguem.
+    async fn skip_task(&self, task: &TaskNode) -> TaskExecutionResult {
+        let reason = format!("condition '{}' evaluated to false", task.condition.as_deref().unwrap_or_default());
+        let now = Utc::now();
+        {
+            let mut mesh = self.task_mesh.write().await;
+            if let Some(task_mut) = mesh.get_task_mut(&task.id) {
+                task_mut.metrics.end_time = Some(now);
+                task_mut.update_status(TaskStatus::Skipped { reason: reason.clone() });
+            }
+        }
+        info!("Task skipped: {} ({})", task.id, reason);
+        self.propagate_failure(&task.id).await;
+        
+        TaskExecutionResult {
+            task_id: task.id,
+            status: TaskExecutionStatus::Skipped,
+            start_time: now,
+            end_time: Some(now),
+            output: None,
+            error_message: Some(reason),
+            resource_usage: crate::layers::ResourceUsage::default(),
+            layer: task.metrics.execution_layer.clone(),
+        }
+    }
+    
+    /// Marca uma tarefa como falha, registrando a mensagem de erro
+    async fn mark_failed(&self, task_id: &TaskId, error: String) {
+        {
+            let mut mesh = self.task_mesh.write().await;
+            if let Some(task_mut) = mesh.get_task_mut(task_id) {
+                task_mut.metrics.error_messages.push(error);
+                task_mut.update_status(TaskStatus::Failed);
+            }
+        }
+        self.propagate_failure(task_id).await;
+    }
+    
+    /// Cancela dependentes hard/data de uma tarefa falha (ou pulada) e libera os soft
+    async fn propagate_failure(&self, task_id: &TaskId) {
+        let cancelled = self.task_mesh.write().await.propagate_failure(task_id);
+        if !cancelled.is_empty() {
+            warn!("Cancelled {} dependent tasks of unsuccessful task {}", cancelled.len(), task_id);
+            self.execution_queue.lock().await.retain(|id| !cancelled.contains(id));
+        }
+        if let Err(e) = self.enqueue_dependent_tasks(task_id).await {
+            warn!("Failed to enqueue dependents of {}: {}", task_id, e);
+        }
+    }
+    
+    /// Reflete as estatísticas de uma camada no coletor de métricas
+    async fn sync_layer_metrics(metrics: &MetricsCollector, layer: &ExecutionLayer, executor: &dyn ExecutionLayerTrait) {
+        let Ok(stats) = executor.get_statistics().await else { return };
+        let executed = stats.total_tasks_executed.max(1) as f64;
+        
+        metrics.update_layer_metrics(layer.clone(), crate::metrics::LayerStatistics {
+            tasks_executed: stats.total_tasks_executed,
+            success_rate: stats.successful_tasks as f64 / executed,
+            average_execution_time_ms: stats.average_execution_time_ms,
+            resource_utilization: stats.resource_utilization(),
+            availability: 1.0,
+            error_count: stats.failed_tasks,
+        }).await;
+    }
+    
     /// Verifica se uma tarefa está pronta para execução
     async fn is_task_ready(&self, task_id: &TaskId) -> Result<bool> {
         let mesh = self.task_mesh.read().await;
@@ -313,24 +678,70 @@ impl OrchestratorCore {
     }
     
     /// Seleciona camada de execução para uma tarefa
+    ///
+    /// Aprendizado e heurística definem a ordem de preferência entre as camadas
+    /// registradas; a consciência simbiótica toma a decisão final.
     async fn select_execution_layer(&self, task: &TaskNode) -> Result<ExecutionLayer> {
+        // Política fixa na configuração dispensa o roteamento
+        if let Some(LayerPolicy::Pinned(layer)) = task.layer_policy() {
+            if self.layer_manager.get_layer(&layer).is_none() {
+                return Err(OrchestratorError::LayerNotAvailable(layer));
+            }
+            return Ok(layer);
+        }
+        
+        // Roteamento aplicado por recomendação
+        let overridden = self.layer_overrides.read().await
+            .iter()
+            .rev()
+            .find(|(filter, _)| filter.matches(task))
+            .map(|(_, layer)| layer.clone());
+        if let Some(layer) = overridden {
+            if self.layer_manager.get_layer(&layer).is_some() {
+                debug!("Recommendation routes task {} to {:?}", task.id, layer);
+                return Ok(layer);
+            }
+        }
+        
+        let mut preferences = Vec::new();
+        
         // Tenta usar aprendizado para recomendar camada
         if let Ok(recommended_layer) = self.learning.recommend_execution_layer(task).await {
             debug!("Learning recommended layer: {:?} for task: {}", recommended_layer, task.id);
-            return Ok(recommended_layer);
+            preferences.push(recommended_layer);
         }
+        preferences.push(Self::heuristic_layer(task));
+        preferences.push(ExecutionLayer::Local);
         
-        // Fallback para seleção baseada em heurísticas
+        let available = self.layer_manager.available_layers();
+        let mut candidates: Vec<ExecutionLayer> = Vec::new();
+        for layer in preferences {
+            if available.contains(&layer) && !candidates.contains(&layer) {
+                candidates.push(layer);
+            }
+        }
+        
+        let decision = self.consciousness.route_task(task, &candidates).await?;
+        decision.parameters.get("layer")
+            .and_then(|value| value.as_str())
+            .and_then(ExecutionLayer::from_name)
+            .ok_or_else(|| OrchestratorError::InvalidState(
+                format!("Routing decision without layer for task {}", task.id)
+            ))
+    }
+    
+    /// Camada sugerida por heurística de prioridade e tamanho
+    fn heuristic_layer(task: &TaskNode) -> ExecutionLayer {
         match task.priority {
-            crate::graph::TaskPriority::Critical => Ok(ExecutionLayer::Local),
+            crate::graph::TaskPriority::Critical => ExecutionLayer::Local,
             crate::graph::TaskPriority::High => {
                 if task.task_type == crate::graph::TaskType::ExtraLarge {
-                    Ok(ExecutionLayer::QuantumSim)
+                    ExecutionLayer::QuantumSim
                 } else {
-                    Ok(ExecutionLayer::Cluster)
+                    ExecutionLayer::Cluster
                 }
             },
-            _ => Ok(ExecutionLayer::Local),
+            _ => ExecutionLayer::Local,
         }
     }
     
@@ -355,12 +766,23 @@ impl OrchestratorCore {
     async fn start_execution_loop(&self) {
         let queue = Arc::clone(&self.execution_queue);
         let running_tasks = Arc::clone(&self.running_tasks);
+        let max_parallel_tasks = Arc::clone(&self.max_parallel_tasks);
         let orchestrator = self.clone_for_tasks();
+        let token = self.background.token();
         
-        tokio::spawn(async move {
+        self.background.spawn("execution", async move {
             loop {
+                // Respeita o limite de paralelismo
+                let at_capacity = {
+                    let mut running = running_tasks.write().await;
+                    running.retain(|_, handle| !handle.is_finished());
+                    running.len() >= max_parallel_tasks.load(Ordering::Relaxed)
+                };
+                
                 // Processa fila de execução
-                let task_id = {
+                let task_id = if at_capacity {
+                    None
+                } else {
                     let mut q = queue.lock().await;
                     q.pop()
                 };
@@ -376,7 +798,10 @@ impl OrchestratorCore {
                     running_tasks.write().await.insert(task_id, handle);
                 }
                 
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {}
+                }
             }
         });
     }
@@ -384,19 +809,35 @@ impl OrchestratorCore {
     /// Inicia loop de coleta de métricas
     async fn start_metrics_collection_loop(&self) {
         let metrics = Arc::clone(&self.metrics);
+        let consciousness = Arc::clone(&self.consciousness);
+        let layer_manager = Arc::clone(&self.layer_manager);
         let config = self.config.clone();
+        let token = self.background.token();
         
-        tokio::spawn(async move {
+        self.background.spawn("metrics_collection", async move {
             let mut interval = tokio::time::interval(
                 tokio::time::Duration::from_secs(config.observability.metrics.collection_interval)
             );
             
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
                 
                 // Coleta métricas do sistema
                 let system_metrics = metrics.collect_system_metrics().await;
                 metrics.update_system_resources(system_metrics).await;
+                
+                // Estatísticas das camadas (inclui o tempo de atividade)
+                for layer in layer_manager.available_layers() {
+                    if let Some(executor) = layer_manager.get_layer(&layer) {
+                        Self::sync_layer_metrics(&metrics, &layer, executor).await;
+                    }
+                }
+                
+                // Expõe as métricas às condições das regras
+                consciousness.observe_metrics(&metrics.get_metrics().await).await;
             }
         });
     }
@@ -405,14 +846,18 @@ impl OrchestratorCore {
     async fn start_consciousness_loop(&self) {
         let consciousness = Arc::clone(&self.consciousness);
         let metrics = Arc::clone(&self.metrics);
+        let token = self.background.token();
         
-        tokio::spawn(async move {
+        self.background.spawn("consciousness", async move {
             let mut interval = tokio::time::interval(
                 tokio::time::Duration::from_secs(60) // Processa consciência a cada minuto
             );
             
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
                 
                 // Força evolução periódica da consciência
                 if let Err(e) = consciousness.evolve().await {
@@ -450,6 +895,113 @@ impl OrchestratorCore {
         }
     }
     
+    /// Aplica as ações de uma recomendação da consciência
+    ///
+    /// Todas as ações são validadas antes que qualquer uma seja executada.
+    /// Com `dry_run`, nada é alterado e nenhum feedback é registrado. Caso
+    /// contrário, a aceitação ou rejeição volta para a consciência.
+    pub async fn apply_recommendation(&self, id: &str, dry_run: bool) -> Result<AppliedActions> {
+        let recommendation = self.consciousness.recommendation(id).await
+            .ok_or_else(|| OrchestratorError::InvalidState(format!("Unknown or already applied recommendation {}", id)))?;
+        
+        let planned = if recommendation.is_expired(Utc::now()) {
+            Err(OrchestratorError::InvalidState(
+                format!("Recommendation {} expired at {}", id, recommendation.expires_at)
+            ))
+        } else {
+            recommendation.actions.iter().map(|spec| self.check_action(spec)).collect::<Result<Vec<_>>>()
+        };
+        let planned = match planned {
+            Ok(planned) => planned,
+            Err(e) => {
+                if !dry_run {
+                    self.record_feedback(id, false, &e.to_string()).await;
+                }
+                return Err(e);
+            }
+        };
+        
+        let mut actions = Vec::with_capacity(planned.len());
+        for (spec, skip_reason) in recommendation.actions.into_iter().zip(planned) {
+            let outcome = match skip_reason {
+                Some(reason) => ActionOutcome::Skipped(reason),
+                None if dry_run => ActionOutcome::WouldApply,
+                None => {
+                    if let Err(e) = self.execute_action(&spec).await {
+                        self.record_feedback(id, false, &e.to_string()).await;
+                        return Err(e);
+                    }
+                    ActionOutcome::Applied
+                }
+            };
+            actions.push(AppliedAction { spec, outcome });
+        }
+        
+        if !dry_run {
+            info!("Applied recommendation {} ({} actions)", id, actions.len());
+            self.record_feedback(id, true, "applied").await;
+        }
+        Ok(AppliedActions { recommendation_id: id.to_string(), dry_run, actions })
+    }
+    
+    /// Verifica se uma ação pode ser executada; `Some` indica que será ignorada
+    fn check_action(&self, spec: &ActionSpec) -> Result<Option<String>> {
+        match spec {
+            ActionSpec::SwitchLayer { layer, .. } => {
+                if self.layer_manager.get_layer(layer).is_none() {
+                    return Err(OrchestratorError::LayerNotAvailable(layer.clone()));
+                }
+                Ok(None)
+            }
+            ActionSpec::AdjustConcurrency { .. } => Ok(None),
+            ActionSpec::CreateCheckpoint => match self.backup {
+                Some(_) => Ok(None),
+                None => Err(OrchestratorError::UnsupportedOperation("No backup system configured".to_string())),
+            },
+            ActionSpec::EnableHedging { .. } => Ok(Some("Hedging is not supported by the orchestrator core".to_string())),
+            ActionSpec::Custom(_) => Ok(Some("Custom actions are left to external integrations".to_string())),
+        }
+    }
+    
+    /// Executa uma ação já validada
+    async fn execute_action(&self, spec: &ActionSpec) -> Result<()> {
+        match spec {
+            ActionSpec::SwitchLayer { task_filter, layer } => {
+                info!("Routing tasks matching {:?} to {:?}", task_filter, layer);
+                self.layer_overrides.write().await.push((task_filter.clone(), layer.clone()));
+            }
+            ActionSpec::AdjustConcurrency { delta } => {
+                let delta = *delta as i64;
+                let previous = self.max_parallel_tasks
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                        Some((current as i64 + delta).max(1) as usize)
+                    })
+                    .unwrap_or_default();
+                info!("Max parallel tasks adjusted from {} by {}", previous, delta);
+            }
+            ActionSpec::CreateCheckpoint => {
+                if let Some(backup) = &self.backup {
+                    let graph = self.task_mesh.read().await.clone();
+                    let metrics = self.metrics.get_metrics().await;
+                    backup.create_snapshot(&graph, &metrics).await?;
+                }
+            }
+            ActionSpec::EnableHedging { .. } | ActionSpec::Custom(_) => {}
+        }
+        Ok(())
+    }
+    
+    async fn record_feedback(&self, id: &str, accepted: bool, detail: &str) {
+        if let Err(e) = self.consciousness.record_recommendation_feedback(id, accepted, detail).await {
+            warn!("Failed to record feedback for recommendation {}: {}", id, e);
+        }
+    }
+    
+    /// Limite atual de tarefas em paralelo
+    pub fn max_parallel_tasks(&self) -> usize {
+        self.max_parallel_tasks.load(Ordering::Relaxed)
+    }
+    
     /// Obtém status atual
     pub async fn get_status(&self) -> OrchestratorStatus {
         self.status.read().await.clone()
@@ -552,5 +1104,374 @@ mod tests {
         orchestrator.stop().await.unwrap();
         assert_eq!(orchestrator.get_status().await, OrchestratorStatus::Stopped);
     }
-}
+    
+    #[tokio::test]
+    async fn test_repeated_start_stop_leaves_no_background_tasks() {
+        let orchestrator = OrchestratorCore::new(OrchestratorConfig::default()).await.unwrap();
+        
+        for _ in 0..50 {
+            orchestrator.start().await.unwrap();
+            assert_eq!(orchestrator.background_task_count(), 3);
+            orchestrator.stop().await.unwrap();
+            assert_eq!(orchestrator.background_task_count(), 0);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_run_until_idle_executes_dag_on_local_layer() {
+        use crate::graph::{DependencyEdge, DependencyType};
+        
+        let config = OrchestratorConfig::default();
+        let orchestrator = OrchestratorCore::new(config).await.unwrap();
+        
+        // a -> b -> c, a -> c
+        let a = orchestrator.submit(TaskNode::new("a".to_string(), None)).await.unwrap();
+        let b = orchestrator.submit(TaskNode::new("b".to_string(), None)).await.unwrap();
+        let c = orchestrator.submit(TaskNode::new("c".to_string(), None)).await.unwrap();
+        orchestrator.add_dependency(DependencyEdge::new(a, b, DependencyType::Hard)).await.unwrap();
+        orchestrator.add_dependency(DependencyEdge::new(b, c, DependencyType::Hard)).await.unwrap();
+        orchestrator.add_dependency(DependencyEdge::new(a, c, DependencyType::Hard)).await.unwrap();
+        
+        let results = orchestrator.run_until_idle().await.unwrap();
+        assert_eq!(results.len(), 3);
+        
+        {
+            let mesh = orchestrator.task_mesh.read().await;
+            for task_id in [a, b, c] {
+                let node = mesh.get_task(&task_id).unwrap();
+                assert_eq!(node.status, TaskStatus::Completed);
+                assert_eq!(node.metrics.execution_layer, ExecutionLayer::Local);
+                assert!(node.execution_context.contains_key("last_result"));
+            }
+            // Dependências respeitadas: c terminou depois de b
+            let b_end = mesh.get_task(&b).unwrap().metrics.end_time.unwrap();
+            let c_start = mesh.get_task(&c).unwrap().metrics.start_time.unwrap();
+            assert!(c_start >= b_end);
+        }
+        
+        let local = orchestrator.layer_manager.get_layer(&ExecutionLayer::Local).unwrap();
+        let stats = local.get_statistics().await.unwrap();
+        assert_eq!(stats.total_tasks_executed, 3);
+        assert_eq!(stats.successful_tasks, 3);
+        
+        let metrics = orchestrator.get_metrics().await;
+        assert_eq!(metrics.layers.local.tasks_executed, 3);
+        assert_eq!(metrics.tasks.completed_tasks, 3);
+    }
+    
+    #[tokio::test]
+    async fn test_lag_edge_delays_dispatch() {
+        use crate::graph::{DependencyEdge, DependencyType};
+        
+        let orchestrator = OrchestratorCore::new(OrchestratorConfig::default()).await.unwrap();
+        let parent = orchestrator.submit(TaskNode::new("parent".to_string(), None)).await.unwrap();
+        let child = orchestrator.submit(TaskNode::new("child".to_string(), None)).await.unwrap();
+        let lag = std::time::Duration::from_millis(300);
+        orchestrator.add_dependency(
+            DependencyEdge::new(parent, child, DependencyType::Data).with_lag(lag)
+        ).await.unwrap();
+        
+        let results = orchestrator.run_until_idle().await.unwrap();
+        assert_eq!(results.len(), 2);
+        
+        let mesh = orchestrator.task_mesh.read().await;
+        let parent_end = mesh.get_task(&parent).unwrap().metrics.end_time.unwrap();
+        let child_node = mesh.get_task(&child).unwrap();
+        assert_eq!(child_node.status, TaskStatus::Completed);
+        assert!(child_node.metrics.start_time.unwrap() - parent_end >= chrono::Duration::milliseconds(300));
+    }
+    
+    #[tokio::test]
+    async fn test_false_condition_skips_task_and_releases_soft_dependents() {
+        use crate::graph::{DependencyEdge, DependencyType};
+        
+        let orchestrator = OrchestratorCore::new(OrchestratorConfig::default()).await.unwrap();
+        let check = orchestrator.submit(TaskNode::new("check".to_string(), None)).await.unwrap();
+        let deploy = TaskNode::builder("deploy").condition(r#"parents.check.status == "Failed""#).build().unwrap();
+        let deploy = orchestrator.submit(deploy).await.unwrap();
+        let notify = orchestrator.submit(TaskNode::new("notify".to_string(), None)).await.unwrap();
+        let publish = orchestrator.submit(TaskNode::new("publish".to_string(), None)).await.unwrap();
+        orchestrator.add_dependency(DependencyEdge::new(check, deploy, DependencyType::Hard)).await.unwrap();
+        orchestrator.add_dependency(DependencyEdge::new(deploy, notify, DependencyType::Soft)).await.unwrap();
+        orchestrator.add_dependency(DependencyEdge::new(deploy, publish, DependencyType::Data)).await.unwrap();
+        
+        let results = orchestrator.run_until_idle().await.unwrap();
+        let skipped = results.iter().find(|result| result.task_id == deploy).unwrap();
+        assert_eq!(skipped.status, TaskExecutionStatus::Skipped);
+        
+        let mesh = orchestrator.task_mesh.read().await;
+        match &mesh.get_task(&deploy).unwrap().status {
+            TaskStatus::Skipped { reason } => assert!(reason.contains("evaluated to false"), "{}", reason),
+            other => panic!("expected skipped, got {:?}", other),
+        }
+        assert_eq!(mesh.get_task(&notify).unwrap().status, TaskStatus::Completed);
+        assert_eq!(mesh.get_task(&publish).unwrap().status, TaskStatus::Cancelled);
+    }
+    
+    #[tokio::test]
+    async fn test_condition_evaluation_error_fails_task() {
+        use crate::graph::{DependencyEdge, DependencyType};
+        
+        let orchestrator = OrchestratorCore::new(OrchestratorConfig::default()).await.unwrap();
+        let parent = orchestrator.submit(TaskNode::new("parent".to_string(), None)).await.unwrap();
+        // Erro de digitação no nome da dependência
+        let child = TaskNode::builder("child").condition("parents.prent.output.ok == true").build().unwrap();
+        let child = orchestrator.submit(child).await.unwrap();
+        orchestrator.add_dependency(DependencyEdge::new(parent, child, DependencyType::Hard)).await.unwrap();
+        
+        orchestrator.execute_task(parent).await.unwrap();
+        assert!(orchestrator.execute_task(child).await.is_err());
+        
+        let mesh = orchestrator.task_mesh.read().await;
+        let node = mesh.get_task(&child).unwrap();
+        assert_eq!(node.status, TaskStatus::Failed);
+        let message = node.metrics.error_messages.last().unwrap();
+        assert!(message.contains("Condition evaluation failed") && message.contains("'prent'"), "{}", message);
+    }
+    
+    fn event(event_type: &str) -> SystemEvent {
+        SystemEvent::new(EventType::from_name(event_type), None, serde_json::json!({}))
+            .with_severity(EventSeverity::Low)
+            .with_source("test")
+    }
+    
+    #[tokio::test]
+    async fn test_switch_layer_recommendation_routes_matching_tasks() {
+        use crate::graph::TaskType;
+        use crate::layers::{NoiseModel, QuantumBackend, QuantumSimConfig};
+        
+        let mut config = OrchestratorConfig::default();
+        config.layers.quantum = Some(QuantumSimConfig {
+            qubits: 2,
+            gates: vec![],
+            noise_model: NoiseModel { gate_error_rate: 0.0, measurement_error_rate: 0.0, decoherence_time_ns: 0.0 },
+            backend: QuantumBackend::Simulator,
+            cost: Default::default(),
+            seed: None,
+            cache_ttl_seconds: 0,
+            max_threads: None,
+        });
+        let orchestrator = OrchestratorCore::new(config).await.unwrap();
+        
+        let response = orchestrator.consciousness.process_event(event("load_spike")).await.unwrap();
+        let recommendation = response.recommendations[0].clone();
+        assert!(matches!(&recommendation.actions[0],
+            ActionSpec::SwitchLayer { layer: ExecutionLayer::QuantumSim, task_filter } if task_filter.task_type == Some(TaskType::ExtraLarge)));
+        
+        let preview = orchestrator.apply_recommendation(&recommendation.id, true).await.unwrap();
+        assert_eq!(preview.actions[0].outcome, ActionOutcome::WouldApply);
+        assert!(orchestrator.layer_overrides.read().await.is_empty());
+        
+        let applied = orchestrator.apply_recommendation(&recommendation.id, false).await.unwrap();
+        assert_eq!(applied.actions[0].outcome, ActionOutcome::Applied);
+        assert!(orchestrator.apply_recommendation(&recommendation.id, false).await.is_err());
+        
+        let mut large = TaskNode::new("large".to_string(), None);
+        large.task_type = TaskType::ExtraLarge;
+        let large = orchestrator.submit(large).await.unwrap();
+        let small = orchestrator.submit(TaskNode::new("small".to_string(), None)).await.unwrap();
+        orchestrator.run_until_idle().await.unwrap();
+        
+        let mesh = orchestrator.task_mesh.read().await;
+        assert_eq!(mesh.get_task(&large).unwrap().metrics.execution_layer, ExecutionLayer::QuantumSim);
+        assert_eq!(mesh.get_task(&small).unwrap().metrics.execution_layer, ExecutionLayer::Local);
+    }
+    
+    #[tokio::test]
+    async fn test_expired_or_unsupported_recommendations_are_rejected() {
+        use crate::symbiotic::Recommendation;
+        
+        let orchestrator = OrchestratorCore::new(OrchestratorConfig::default()).await.unwrap();
+        let episodes = || async { orchestrator.consciousness.get_state().await.episodic_memory.episodes.len() };
+        
+        let expired = orchestrator.consciousness.recommend(
+            Recommendation::new("stale", "", vec![ActionSpec::AdjustConcurrency { delta: 2 }])
+                .expires_at(Utc::now() - chrono::Duration::seconds(1))
+        ).await;
+        let before = episodes().await;
+        assert!(matches!(orchestrator.apply_recommendation(&expired.id, false).await, Err(OrchestratorError::InvalidState(_))));
+        assert_eq!(episodes().await, before + 1, "rejection is fed back to the consciousness");
+        assert_eq!(orchestrator.max_parallel_tasks(), 4);
+        
+        // Checkpoint sem backup invalida a recomendação inteira
+        let checkpoint = orchestrator.consciousness.recommend(Recommendation::new("checkpoint", "", vec![
+            ActionSpec::AdjustConcurrency { delta: 2 },
+            ActionSpec::CreateCheckpoint,
+        ])).await;
+        assert!(orchestrator.apply_recommendation(&checkpoint.id, false).await.is_err());
+        assert_eq!(orchestrator.max_parallel_tasks(), 4);
+        
+        let tuning = orchestrator.consciousness.recommend(Recommendation::new("tuning", "", vec![
+            ActionSpec::AdjustConcurrency { delta: -10 },
+            ActionSpec::Custom(serde_json::json!({ "notify": "ops" })),
+        ])).await;
+        let applied = orchestrator.apply_recommendation(&tuning.id, false).await.unwrap();
+        assert!(matches!(applied.actions[1].outcome, ActionOutcome::Skipped(_)));
+        assert_eq!(orchestrator.max_parallel_tasks(), 1);
+    }
+    
+    /// Camada com duração e taxas fixas
+    #[derive(Debug)]
+    struct PricedLayer {
+        layer: ExecutionLayer,
+        cost: crate::cost::CostModel,
+    }
+    
+    #[async_trait::async_trait]
+    impl ExecutionLayerTrait for PricedLayer {
+        async fn execute_task(&self, task: &TaskNode, _config: &crate::layers::ExecutionConfig) -> Result<TaskExecutionResult> {
+            let now = Utc::now();
+            Ok(TaskExecutionResult {
+                task_id: task.id,
+                status: TaskExecutionStatus::Success,
+                start_time: now,
+                end_time: Some(now),
+                output: None,
+                error_message: None,
+                resource_usage: crate::layers::ResourceUsage { execution_time_ms: 2000, ..Default::default() },
+                layer: self.layer.clone(),
+            })
+        }
+        async fn health_check(&self) -> Result<crate::layers::LayerHealth> {
+            Err(OrchestratorError::UnsupportedOperation("mock".to_string()))
+        }
+        async fn get_statistics(&self) -> Result<crate::layers::LayerStatistics> {
+            Err(OrchestratorError::UnsupportedOperation("mock".to_string()))
+        }
+        async fn cancel_task(&self, _task_id: TaskId) -> Result<()> { Ok(()) }
+        async fn list_running_tasks(&self) -> Result<Vec<TaskId>> { Ok(Vec::new()) }
+        fn layer_type(&self) -> ExecutionLayer { self.layer.clone() }
+        fn cost_model(&self) -> crate::cost::CostModel { self.cost }
+    }
+    
+    async fn priced_orchestrator(config: OrchestratorConfig) -> OrchestratorCore {
+        use crate::cost::CostModel;
+        
+        OrchestratorCore::new(config).await.unwrap()
+            .with_layer(Box::new(PricedLayer {
+                layer: ExecutionLayer::Local,
+                cost: CostModel { per_second: 0.5, per_task: 1.0, per_qubit_shot: 0.0 },
+            }))
+            .with_layer(Box::new(PricedLayer {
+                layer: ExecutionLayer::Cluster,
+                cost: CostModel { per_task: 10.0, ..CostModel::default() },
+            }))
+    }
+    
+    fn priced_task(namespace: &str, tag: &str, layer: ExecutionLayer) -> TaskNode {
+        use crate::graph::LayerPolicy;
+        
+        TaskNode::builder(tag).namespace(namespace).tag(tag).layer_policy(LayerPolicy::Pinned(layer)).build().unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_cost_report_aggregates_layer_rates() {
+        let orchestrator = priced_orchestrator(OrchestratorConfig::default()).await;
+        orchestrator.submit(priced_task("research", "etl", ExecutionLayer::Local)).await.unwrap();
+        orchestrator.submit(priced_task("research", "etl", ExecutionLayer::Local)).await.unwrap();
+        orchestrator.submit(priced_task("research", "ml", ExecutionLayer::Cluster)).await.unwrap();
+        orchestrator.submit(priced_task("ops", "etl", ExecutionLayer::Local)).await.unwrap();
+        orchestrator.run_until_idle().await.unwrap();
+        
+        let today = Utc::now().date_naive();
+        let totals = |report: CostReport| -> Vec<(String, u64, f64)> {
+            report.rows.into_iter().map(|row| (row.key, row.tasks, row.cost)).collect()
+        };
+        
+        let by_namespace = orchestrator.cost_report(today..=today, CostGroupBy::Namespace).await;
+        assert_eq!(by_namespace.total, 16.0);
+        assert_eq!(totals(by_namespace), vec![("ops".to_string(), 1, 2.0), ("research".to_string(), 3, 14.0)]);
+        
+        let by_layer = orchestrator.cost_report(today..=today, CostGroupBy::Layer).await;
+        assert_eq!(totals(by_layer), vec![("cluster".to_string(), 1, 10.0), ("local".to_string(), 3, 6.0)]);
+        
+        let by_tag = orchestrator.cost_report(today..=today, CostGroupBy::Tag).await;
+        assert_eq!(totals(by_tag), vec![("etl".to_string(), 3, 6.0), ("ml".to_string(), 1, 10.0)]);
+        
+        let yesterday = today - chrono::Duration::days(1);
+        assert_eq!(orchestrator.cost_report(yesterday..=yesterday, CostGroupBy::Day).await.total, 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_cost_ledger_survives_restart_with_backup() {
+        let path = std::env::temp_dir().join(format!("costs-{}.db", uuid::Uuid::new_v4()));
+        std::fs::File::create(&path).unwrap();
+        let backup = Arc::new(BackupSystem::new(crate::backup::tests::test_config(path.clone())).await.unwrap());
+        
+        let orchestrator = priced_orchestrator(OrchestratorConfig::default()).await.with_backup(backup.clone());
+        orchestrator.submit(priced_task("research", "ml", ExecutionLayer::Cluster)).await.unwrap();
+        orchestrator.submit(priced_task("research", "etl", ExecutionLayer::Local)).await.unwrap();
+        orchestrator.run_until_idle().await.unwrap();
+        
+        let restarted = priced_orchestrator(OrchestratorConfig::default()).await.with_backup(backup);
+        restarted.start().await.unwrap();
+        let today = Utc::now().date_naive();
+        let report = restarted.cost_report(today..=today, CostGroupBy::Namespace).await;
+        assert_eq!((report.total, report.rows[0].tasks), (12.0, 2));
+        assert_eq!(restarted.costs.month_to_date("research", Utc::now()).await, 12.0);
+        restarted.stop().await.unwrap();
+        
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_exhausted_budget_rejects_or_downgrades_submissions() {
+        for action in [BudgetAction::Reject, BudgetAction::DowngradeToLocal] {
+            let mut config = OrchestratorConfig::default();
+            config.cost.budgets.insert("research".to_string(), 10.0);
+            config.cost.on_exhausted = action;
+            let orchestrator = priced_orchestrator(config).await;
+            
+            orchestrator.submit(priced_task("research", "ml", ExecutionLayer::Cluster)).await.unwrap();
+            orchestrator.run_until_idle().await.unwrap();
+            
+            // Outros namespaces não são afetados
+            orchestrator.submit(priced_task("ops", "ml", ExecutionLayer::Cluster)).await.unwrap();
+            
+            let over = orchestrator.submit(priced_task("research", "ml", ExecutionLayer::Cluster)).await;
+            match action {
+                BudgetAction::Reject => {
+                    assert!(matches!(over, Err(OrchestratorError::ResourceLimitExceeded(_))));
+                }
+                BudgetAction::DowngradeToLocal => {
+                    let task_id = over.unwrap();
+                    orchestrator.run_until_idle().await.unwrap();
+                    let mesh = orchestrator.task_mesh.read().await;
+                    assert_eq!(mesh.get_task(&task_id).unwrap().metrics.execution_layer, ExecutionLayer::Local);
+                }
+            }
+            
+            let state = orchestrator.get_consciousness_state().await;
+            assert!(state.episodic_memory.episodes.iter().any(|episode| {
+                episode.context.external_factors.get("event_type") == Some(&serde_json::json!("budget_exceeded"))
+            }));
+        }
+    }
 
+    
+    #[tokio::test]
+    async fn test_from_config_registers_only_configured_layers() {
+        use crate::layers::{NoiseModel, QuantumBackend, QuantumSimConfig};
+        
+        let mut config = OrchestratorConfig::default();
+        config.layers.local = None;
+        config.layers.quantum = Some(QuantumSimConfig {
+            qubits: 4,
+            gates: vec![],
+            noise_model: NoiseModel { gate_error_rate: 0.0, measurement_error_rate: 0.0, decoherence_time_ns: 0.0 },
+            backend: QuantumBackend::Simulator,
+            cost: Default::default(),
+            seed: None,
+            cache_ttl_seconds: 0,
+            max_threads: None,
+        });
+        let orchestrator = OrchestratorCore::from_config(config.clone()).await.unwrap();
+        assert_eq!(orchestrator.layer_manager.available_layers(), vec![ExecutionLayer::QuantumSim]);
+        
+        config.layers.quantum.as_mut().unwrap().qubits = 500;
+        assert!(matches!(
+            OrchestratorCore::from_config(config).await,
+            Err(OrchestratorError::ValidationError { field, .. }) if field == "layers"
+        ));
+    }
+}
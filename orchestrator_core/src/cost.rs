@@ -0,0 +1,230 @@
+//! # Cost Accounting
+//!
+//! Custo das execuções por camada. Cada camada informa seu [`CostModel`]
+//! (taxas por segundo, por tarefa e por qubit-shot, vindas da configuração
+//! da camada); o orchestrator calcula o custo de cada `TaskExecutionResult`
+//! e o acumula no [`CostLedger`], agregado por dia, namespace, camada e
+//! tags. Orçamentos mensais por namespace ([`CostConfig::budgets`]) são
+//! verificados na submissão.
+//!
+//! Com um [`BackupSystem`](crate::backup::BackupSystem) configurado, cada
+//! custo também vai para a tabela `cost_ledger` e o ledger é recarregado
+//! dela no `start`, de modo que totais e orçamentos sobrevivem a reinícios.
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeInclusive;
+use tokio::sync::RwLock;
+
+use crate::graph::{config_keys, QuantumCircuit, TaskNode};
+use crate::layers::{ExecutionLayer, TaskExecutionResult};
+
+/// Chave de contexto onde o custo da última execução é gravado
+pub const COST_CONTEXT_KEY: &str = "cost";
+
+/// Taxas de uma camada de execução
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostModel {
+    /// Custo por segundo de execução
+    #[serde(default)]
+    pub per_second: f64,
+    /// Custo fixo por tarefa executada
+    #[serde(default)]
+    pub per_task: f64,
+    /// Custo por qubit × shot do circuito
+    #[serde(default)]
+    pub per_qubit_shot: f64,
+}
+
+impl CostModel {
+    /// Custo de uma execução
+    ///
+    /// Qubit-shots vêm do circuito da tarefa; sem circuito, do número de
+    /// qubits informado na saída (um shot).
+    pub fn cost_of(&self, task: &TaskNode, result: &TaskExecutionResult) -> f64 {
+        let seconds = result.resource_usage.execution_time_ms as f64 / 1000.0;
+        let qubit_shots = task
+            .configuration
+            .get(config_keys::QUANTUM_CIRCUIT)
+            .and_then(|value| serde_json::from_value::<QuantumCircuit>(value.clone()).ok())
+            .map(|circuit| circuit.qubits as f64 * circuit.shots as f64)
+            .or_else(|| {
+                result.output.as_ref()
+                    .and_then(|output| output.get("qubits_used"))
+                    .and_then(|qubits| qubits.as_f64())
+            })
+            .unwrap_or(0.0);
+        self.per_task + self.per_second * seconds + self.per_qubit_shot * qubit_shots
+    }
+}
+
+/// O que fazer quando o orçamento de um namespace se esgota
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BudgetAction {
+    /// Recusa novas submissões do namespace
+    #[default]
+    Reject,
+    /// Aceita, fixando a tarefa na camada local
+    DowngradeToLocal,
+}
+
+/// Configuração de orçamentos
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostConfig {
+    /// Orçamento mensal por namespace
+    #[serde(default)]
+    pub budgets: HashMap<String, f64>,
+    #[serde(default)]
+    pub on_exhausted: BudgetAction,
+}
+
+/// Agrupamento de um relatório de custo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CostGroupBy {
+    Namespace,
+    /// Tarefas com várias tags contam em cada uma delas
+    Tag,
+    Day,
+    Layer,
+}
+
+/// Linha de um relatório de custo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostReportRow {
+    pub key: String,
+    pub tasks: u64,
+    pub cost: f64,
+}
+
+/// Relatório de custo de um intervalo de dias
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: CostGroupBy,
+    /// Linhas ordenadas pela chave
+    pub rows: Vec<CostReportRow>,
+    /// Total do intervalo (cada execução contada uma vez)
+    pub total: f64,
+}
+
+/// Linha do ledger: execuções e custo de um dia, namespace, camada e tags
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostEntry {
+    pub day: NaiveDate,
+    pub namespace: String,
+    pub layer: ExecutionLayer,
+    /// Tags ordenadas
+    pub tags: Vec<String>,
+    pub tasks: u64,
+    pub cost: f64,
+}
+
+/// Chave de agregação do ledger
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CostKey {
+    day: NaiveDate,
+    namespace: String,
+    layer: ExecutionLayer,
+    /// Tags ordenadas
+    tags: Vec<String>,
+}
+
+/// Tabela de custos agregados
+#[derive(Debug, Default)]
+pub struct CostLedger {
+    rows: RwLock<HashMap<CostKey, (u64, f64)>>,
+}
+
+impl CostLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acumula o custo de uma execução no dia em que ela terminou
+    ///
+    /// Retorna o acréscimo (uma execução), para persistência.
+    pub async fn record(&self, task: &TaskNode, result: &TaskExecutionResult, cost: f64) -> CostEntry {
+        let mut tags: Vec<String> = task.tags.iter().cloned().collect();
+        tags.sort();
+        let key = CostKey {
+            day: result.end_time.unwrap_or(result.start_time).date_naive(),
+            namespace: task.namespace().to_string(),
+            layer: result.layer.clone(),
+            tags,
+        };
+        let entry = CostEntry {
+            day: key.day,
+            namespace: key.namespace.clone(),
+            layer: key.layer.clone(),
+            tags: key.tags.clone(),
+            tasks: 1,
+            cost,
+        };
+        let mut rows = self.rows.write().await;
+        let row = rows.entry(key).or_default();
+        row.0 += 1;
+        row.1 += cost;
+        entry
+    }
+
+    /// Substitui o conteúdo do ledger por linhas persistidas
+    pub async fn restore(&self, entries: Vec<CostEntry>) {
+        let mut rows = self.rows.write().await;
+        rows.clear();
+        for entry in entries {
+            let key = CostKey { day: entry.day, namespace: entry.namespace, layer: entry.layer, tags: entry.tags };
+            let row = rows.entry(key).or_default();
+            row.0 += entry.tasks;
+            row.1 += entry.cost;
+        }
+    }
+
+    /// Gasto de um namespace no mês de `now`
+    pub async fn month_to_date(&self, namespace: &str, now: DateTime<Utc>) -> f64 {
+        let today = now.date_naive();
+        self.rows
+            .read()
+            .await
+            .iter()
+            .filter(|(key, _)| {
+                key.namespace == namespace && key.day.year() == today.year() && key.day.month() == today.month()
+            })
+            .map(|(_, (_, cost))| cost)
+            .sum()
+    }
+
+    /// Relatório dos dias em `range`
+    pub async fn report(&self, range: RangeInclusive<NaiveDate>, group_by: CostGroupBy) -> CostReport {
+        let mut grouped: BTreeMap<String, (u64, f64)> = BTreeMap::new();
+        let mut total = 0.0;
+        for (key, (tasks, cost)) in self.rows.read().await.iter() {
+            if !range.contains(&key.day) {
+                continue;
+            }
+            total += cost;
+            let keys = match group_by {
+                CostGroupBy::Namespace => vec![key.namespace.clone()],
+                CostGroupBy::Tag => key.tags.clone(),
+                CostGroupBy::Day => vec![key.day.to_string()],
+                CostGroupBy::Layer => vec![key.layer.as_str().to_string()],
+            };
+            for group in keys {
+                let row = grouped.entry(group).or_default();
+                row.0 += tasks;
+                row.1 += cost;
+            }
+        }
+        CostReport {
+            from: *range.start(),
+            to: *range.end(),
+            group_by,
+            rows: grouped
+                .into_iter()
+                .map(|(key, (tasks, cost))| CostReportRow { key, tasks, cost })
+                .collect(),
+            total,
+        }
+    }
+}
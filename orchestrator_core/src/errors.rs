@@ -5,7 +5,7 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,8 @@ use thiserror::Error;
 use tracing::{error, warn, info, debug, instrument};
 use uuid::Uuid;
 
+use crate::clock::{Clock, SystemClock};
+
 /// Resultado padrão para operações do orchestrator
 pub type Result<T> = std::result::Result<T, OrchestratorError>;
 
@@ -117,6 +119,10 @@ pub enum OrchestratorError {
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
     
+    /// Dado persistido em versão de formato mais nova que a suportada
+    #[error("Unsupported format version {found} (supported up to {supported})")]
+    UnsupportedFormatVersion { found: u32, supported: u32 },
+    
     /// Erro de consciência simbiótica
     #[error("Symbiotic consciousness error: {0}")]
     ConsciousnessError(String),
@@ -156,6 +162,11 @@ pub enum ErrorKind {
         stack_trace: String,
         recovery_suggestion: String,
     },
+    /// Certificado rejeitado no handshake TLS (não adianta repetir)
+    Certificate {
+        service: String,
+        endpoint: String,
+    },
 }
 
 impl ErrorKind {
@@ -165,6 +176,7 @@ impl ErrorKind {
             ErrorKind::Runtime { .. } => true,
             ErrorKind::External { .. } => true,
             ErrorKind::Panic { .. } => false,
+            ErrorKind::Certificate { .. } => false,
         }
     }
 
@@ -174,6 +186,7 @@ impl ErrorKind {
             ErrorKind::Runtime { .. } => ErrorSeverity::Error,
             ErrorKind::External { .. } => ErrorSeverity::Warning,
             ErrorKind::Panic { .. } => ErrorSeverity::Critical,
+            ErrorKind::Certificate { .. } => ErrorSeverity::Error,
         }
     }
 
@@ -183,6 +196,7 @@ impl ErrorKind {
             ErrorKind::Runtime { .. } => "symbiotic.runtime",
             ErrorKind::External { .. } => "symbiotic.external",
             ErrorKind::Panic { .. } => "symbiotic.panic",
+            ErrorKind::Certificate { .. } => "symbiotic.certificate",
         }
     }
 }
@@ -205,6 +219,9 @@ pub struct RetryInfo {
     pub total_duration: Duration,
     pub exponential_base: f64,
     pub jitter_factor: f64,
+    /// Instante monotônico do próximo retry; `next_retry_at` é só para exibição
+    #[serde(skip)]
+    retry_deadline: Option<Instant>,
 }
 
 impl RetryInfo {
@@ -219,21 +236,43 @@ impl RetryInfo {
             total_duration: Duration::ZERO,
             exponential_base: 2.0,
             jitter_factor: 0.1,
+            retry_deadline: None,
         }
     }
 
     pub fn should_retry(&self) -> bool {
-        self.attempt < self.max_attempts && Utc::now() >= self.next_retry_at
+        self.should_retry_with(&SystemClock)
+    }
+
+    /// Verifica se o retry é permitido segundo o relógio monotônico de `clock`
+    pub fn should_retry_with(&self, clock: &dyn Clock) -> bool {
+        self.has_attempts_left()
+            && self.retry_deadline.map_or(true, |deadline| clock.now() >= deadline)
+    }
+
+    pub fn has_attempts_left(&self) -> bool {
+        self.attempt < self.max_attempts
+    }
+
+    /// Tempo restante até o próximo retry
+    pub fn remaining_backoff(&self, clock: &dyn Clock) -> Duration {
+        self.retry_deadline
+            .map_or(Duration::ZERO, |deadline| deadline.saturating_duration_since(clock.now()))
     }
 
     pub fn record_attempt(&mut self) {
+        self.record_attempt_with(&SystemClock)
+    }
+
+    pub fn record_attempt_with(&mut self, clock: &dyn Clock) {
         self.attempt += 1;
-        self.last_attempt_at = Utc::now();
+        self.last_attempt_at = clock.wall();
         let base_delay = Duration::from_millis((100.0 * self.exponential_base.powi(self.attempt as i32 - 1)) as u64);
         let jitter = fastrand::f64() * self.jitter_factor;
         let jitter_factor = 1.0 + (jitter - self.jitter_factor / 2.0);
         self.backoff_duration = Duration::from_millis((base_delay.as_millis() as f64 * jitter_factor) as u64);
         self.next_retry_at = self.last_attempt_at + chrono::Duration::from_std(self.backoff_duration).unwrap();
+        self.retry_deadline = Some(clock.now() + self.backoff_duration);
         self.total_duration += base_delay;
     }
 }
@@ -326,6 +365,39 @@ impl ErrorContext {
 pub type SymbioticResult<T> = std::result::Result<T, OrchestratorError>;
 
 impl OrchestratorError {
+    /// Cria um erro de validação para o campo indicado
+    pub fn validation(field: &str, rule: &str, value: &str, message: impl Into<String>) -> Self {
+        OrchestratorError::ValidationError {
+            field: field.to_string(),
+            message: message.into(),
+            kind: ErrorKind::Validation {
+                field: field.to_string(),
+                rule: rule.to_string(),
+                value: value.to_string(),
+            },
+            context: ErrorContext::new("validate", "orchestrator_core"),
+        }
+    }
+
+    /// Cria um erro de certificado TLS de um serviço externo
+    pub fn certificate(service: &str, endpoint: &str, message: impl Into<String>) -> Self {
+        OrchestratorError::ExternalServiceError {
+            service: service.to_string(),
+            message: message.into(),
+            kind: ErrorKind::Certificate {
+                service: service.to_string(),
+                endpoint: endpoint.to_string(),
+            },
+            context: ErrorContext::new("tls_handshake", service),
+            circuit_breaker_state: CircuitBreakerState::Open { opened_at: Utc::now(), failure_count: 1 },
+        }
+    }
+
+    /// Verifica se o erro é uma rejeição de certificado TLS
+    pub fn is_certificate_error(&self) -> bool {
+        matches!(self, OrchestratorError::ExternalServiceError { kind: ErrorKind::Certificate { .. }, .. })
+    }
+
     /// Verifica se o erro é recuperável
     pub fn is_recoverable(&self) -> bool {
         match self {
@@ -346,6 +418,7 @@ impl OrchestratorError {
             OrchestratorError::Timeout(_) => true,
             OrchestratorError::InvalidState(_) => false,
             OrchestratorError::UnsupportedOperation(_) => false,
+            OrchestratorError::UnsupportedFormatVersion { .. } => false,
             OrchestratorError::ConsciousnessError(_) => true,
             OrchestratorError::QuantumError(_) => true,
             OrchestratorError::InternalError(_) => false,
@@ -377,6 +450,7 @@ impl OrchestratorError {
             OrchestratorError::Timeout(_) => "TIMEOUT",
             OrchestratorError::InvalidState(_) => "INVALID_STATE",
             OrchestratorError::UnsupportedOperation(_) => "UNSUPPORTED_OPERATION",
+            OrchestratorError::UnsupportedFormatVersion { .. } => "UNSUPPORTED_FORMAT_VERSION",
             OrchestratorError::ConsciousnessError(_) => "CONSCIOUSNESS_ERROR",
             OrchestratorError::QuantumError(_) => "QUANTUM_ERROR",
             OrchestratorError::InternalError(_) => "INTERNAL_ERROR",
@@ -400,6 +474,7 @@ impl OrchestratorError {
             OrchestratorError::InsufficientData => ErrorCategory::Data,
             OrchestratorError::ConfigurationError(_) => ErrorCategory::Configuration,
             OrchestratorError::SerializationError(_) => ErrorCategory::Data,
+            OrchestratorError::UnsupportedFormatVersion { .. } => ErrorCategory::Data,
             OrchestratorError::IoError(_) => ErrorCategory::System,
             OrchestratorError::NetworkError(_) => ErrorCategory::Network,
             OrchestratorError::DatabaseError(_) => ErrorCategory::Database,
@@ -482,6 +557,7 @@ pub struct RetryManager {
     default_exponential_base: f64,
     default_jitter_factor: f64,
     metrics: Arc<RwLock<RetryMetrics>>,
+    clock: Arc<dyn Clock>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -499,9 +575,16 @@ impl RetryManager {
             default_exponential_base: 2.0,
             default_jitter_factor: 0.1,
             metrics: Arc::new(RwLock::new(RetryMetrics::default())),
+            clock: Arc::new(SystemClock),
         }
     }
     
+    /// Substitui a fonte de tempo (ex.: `MockClock` em testes)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
     #[instrument(skip(self, operation))]
     pub async fn retry_with_backoff<T, F, Fut>(
         &self,
@@ -515,7 +598,7 @@ impl RetryManager {
         let mut retry_info = RetryInfo::new(self.default_max_attempts);
         
         loop {
-            retry_info.record_attempt();
+            retry_info.record_attempt_with(self.clock.as_ref());
             
             // Update metrics
             {
@@ -546,7 +629,7 @@ impl RetryManager {
                     return Ok(result);
                 }
                 Err(err) => {
-                    if !err.is_recoverable() || !retry_info.should_retry() {
+                    if !err.is_recoverable() || !retry_info.has_attempts_left() {
                         error!(
                             attempt = retry_info.attempt,
                             max_attempts = retry_info.max_attempts,
@@ -578,8 +661,8 @@ impl RetryManager {
                         "Operation failed, will retry"
                     );
                     
-                    // Wait for backoff period
-                    tokio::time::sleep(retry_info.backoff_duration).await;
+                    // Wait for backoff period (monotonic, immune to wall-clock steps)
+                    tokio::time::sleep(retry_info.remaining_backoff(self.clock.as_ref())).await;
                 }
             }
         }
@@ -599,6 +682,9 @@ pub struct CircuitBreaker {
     timeout_duration: Duration,
     half_open_timeout: Duration,
     metrics: Arc<RwLock<CircuitBreakerMetrics>>,
+    /// Instante monotônico da abertura; `opened_at` no estado é só para exibição
+    opened_instant: Arc<std::sync::Mutex<Option<Instant>>>,
+    clock: Arc<dyn Clock>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -619,6 +705,23 @@ impl CircuitBreaker {
             timeout_duration,
             half_open_timeout: Duration::from_secs(30),
             metrics: Arc::new(RwLock::new(CircuitBreakerMetrics::default())),
+            opened_instant: Arc::new(std::sync::Mutex::new(None)),
+            clock: Arc::new(SystemClock),
+        }
+    }
+    
+    /// Substitui a fonte de tempo (ex.: `MockClock` em testes)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Tempo desde a abertura do circuito, pelo relógio monotônico
+    fn open_elapsed(&self) -> Duration {
+        match *self.opened_instant.lock().unwrap() {
+            Some(opened) => self.clock.now().saturating_duration_since(opened),
+            // Estado sem instante conhecido (ex.: restaurado): considerar expirado
+            None => Duration::MAX,
         }
     }
     
@@ -636,8 +739,8 @@ impl CircuitBreaker {
         let current_state = {
             let mut state = self.state.write().await;
             match *state {
-                CircuitBreakerState::Open { opened_at, failure_count } => {
-                    if Utc::now().signed_duration_since(opened_at).to_std().unwrap() > self.timeout_duration {
+                CircuitBreakerState::Open { opened_at, .. } => {
+                    if self.open_elapsed() > self.timeout_duration {
                         *state = CircuitBreakerState::HalfOpen {
                             opened_at,
                             test_request_sent: false,
@@ -730,9 +833,10 @@ impl CircuitBreaker {
             CircuitBreakerState::Closed => {
                 if metrics.failed_calls >= self.failure_threshold as u64 {
                     *state = CircuitBreakerState::Open {
-                        opened_at: Utc::now(),
+                        opened_at: self.clock.wall(),
                         failure_count: metrics.failed_calls as u32,
                     };
+                    *self.opened_instant.lock().unwrap() = Some(self.clock.now());
                     metrics.circuit_opens += 1;
                     warn!(
                         name = self.name,
@@ -796,7 +900,6 @@ mod tests {
         let context_result = result.with_context(context);
         assert!(context_result.is_err());
     }
-}
 
     #[test]
     fn test_retry_info() {
@@ -807,11 +910,60 @@ mod tests {
         
         retry_info.record_attempt();
         assert_eq!(retry_info.attempt, 1);
-        assert!(retry_info.should_retry());
+        assert!(retry_info.has_attempts_left());
         
         retry_info.record_attempt();
         retry_info.record_attempt();
         assert_eq!(retry_info.attempt, 3);
         assert!(!retry_info.should_retry());
     }
-
+    
+    #[test]
+    fn test_retry_schedule_ignores_wall_clock_jumps() {
+        use crate::clock::MockClock;
+        
+        let clock = MockClock::new();
+        let mut retry_info = RetryInfo::new(3);
+        retry_info.record_attempt_with(&clock);
+        assert!(!retry_info.should_retry_with(&clock));
+        
+        // Relógio de parede volta 1h: retry não fica bloqueado para sempre
+        clock.jump_wall(chrono::Duration::hours(-1));
+        clock.advance(retry_info.backoff_duration);
+        assert!(retry_info.should_retry_with(&clock));
+        
+        // Relógio de parede avança 1h: retry não é antecipado
+        retry_info.record_attempt_with(&clock);
+        clock.jump_wall(chrono::Duration::hours(1));
+        assert!(!retry_info.should_retry_with(&clock));
+        assert_eq!(retry_info.remaining_backoff(&clock), retry_info.backoff_duration);
+    }
+    
+    #[tokio::test]
+    async fn test_circuit_breaker_timeout_ignores_wall_clock_jumps() {
+        use crate::clock::MockClock;
+        
+        let clock = Arc::new(MockClock::new());
+        let breaker = CircuitBreaker::new("svc".to_string(), 1, Duration::from_secs(10))
+            .with_clock(clock.clone());
+        let context = ErrorContext::new("call", "test");
+        
+        let failing = || async { Err::<(), _>(OrchestratorError::InternalError("falha".to_string())) };
+        let ok = || async { Ok::<(), OrchestratorError>(()) };
+        
+        assert!(breaker.call(failing, context.clone()).await.is_err());
+        assert!(matches!(breaker.get_state().await, CircuitBreakerState::Open { .. }));
+        
+        // Salto para trás não causa pânico nem fecha o circuito
+        clock.jump_wall(chrono::Duration::hours(-1));
+        assert!(breaker.call(ok, context.clone()).await.is_err());
+        
+        // Salto para frente sozinho não expira o timeout
+        clock.jump_wall(chrono::Duration::hours(2));
+        assert!(breaker.call(ok, context.clone()).await.is_err());
+        
+        clock.advance(Duration::from_secs(11));
+        assert!(breaker.call(ok, context).await.is_ok());
+        assert_eq!(breaker.get_state().await, CircuitBreakerState::Closed);
+    }
+}
@@ -3,14 +3,17 @@
 //! Implementação do grafo de tarefas (DAG) com nós e arestas de dependência.
 
 use chrono::{DateTime, Utc};
+use petgraph::visit::EdgeRef;
 use petgraph::{Graph, Directed, Direction};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::time::Duration;
 use uuid::Uuid;
 
 use crate::errors::{OrchestratorError, Result};
 use crate::layers::ExecutionLayer;
+use crate::rules::Condition;
 
 /// Identificador único para tarefas
 pub type TaskId = Uuid;
@@ -35,6 +38,10 @@ pub enum TaskStatus {
     Cancelled,
     /// Tarefa pausada temporariamente
     Paused,
+    /// Condição da tarefa avaliada como falsa; não executou
+    ///
+    /// Satisfaz dependências soft e de recurso, mas não hard/data.
+    Skipped { reason: String },
 }
 
 /// Prioridade de execução da tarefa
@@ -90,6 +97,9 @@ pub struct TaskNode {
     pub metrics: TaskMetrics,
     pub configuration: HashMap<String, serde_json::Value>,
     pub execution_context: HashMap<String, serde_json::Value>,
+    /// Condição avaliada quando as dependências terminam (ver [`TaskMesh::evaluate_condition`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
 }
 
 impl TaskNode {
@@ -121,6 +131,7 @@ impl TaskNode {
             },
             configuration: HashMap::new(),
             execution_context: HashMap::new(),
+            condition: None,
         }
     }
 
@@ -136,6 +147,82 @@ impl TaskNode {
         self.updated_at = Utc::now();
     }
 
+    /// Inicia a construção de uma tarefa com configuração tipada
+    pub fn builder(name: impl Into<String>) -> TaskNodeBuilder {
+        TaskNodeBuilder::new(name)
+    }
+
+    /// Política de camada declarada na configuração, se houver
+    pub fn layer_policy(&self) -> Option<LayerPolicy> {
+        self.configuration
+            .get(config_keys::LAYER_POLICY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Namespace da tarefa (`default` quando não configurado)
+    pub fn namespace(&self) -> &str {
+        self.configuration
+            .get(config_keys::NAMESPACE)
+            .and_then(|value| value.as_str())
+            .unwrap_or(DEFAULT_NAMESPACE)
+    }
+
+    /// Valida a configuração contra o esquema das camadas
+    ///
+    /// Chaves desconhecidas são rejeitadas, exceto com `allow_extra: true`.
+    /// Com uma política de camada fixa, apenas as chaves daquela camada valem.
+    pub fn validate_configuration(&self) -> Result<()> {
+        let allow_extra = match self.configuration.get(config_keys::ALLOW_EXTRA) {
+            None => false,
+            Some(value) => value.as_bool().ok_or_else(|| OrchestratorError::validation(
+                &field_path(config_keys::ALLOW_EXTRA), "type", &value.to_string(), "expected a boolean",
+            ))?,
+        };
+
+        let schemas: Vec<&ConfigSchema> = match self.configuration.get(config_keys::LAYER_POLICY) {
+            None => ConfigSchema::ALL.iter().collect(),
+            Some(value) => match check_field::<LayerPolicy>(config_keys::LAYER_POLICY, value)? {
+                LayerPolicy::Auto => ConfigSchema::ALL.iter().collect(),
+                LayerPolicy::Pinned(layer) => vec![ConfigSchema::for_layer(&layer)],
+            },
+        };
+
+        for (key, value) in &self.configuration {
+            if !schemas.iter().any(|schema| schema.accepts(key)) {
+                if allow_extra {
+                    continue;
+                }
+                return Err(OrchestratorError::validation(
+                    &field_path(key), "unknown_field", key, format!("unknown configuration key '{}'", key),
+                ));
+            }
+
+            match key.as_str() {
+                config_keys::COMMAND => { check_field::<String>(key, value)?; },
+                config_keys::ARGS => { check_field::<Vec<String>>(key, value)?; },
+                config_keys::ENV => { check_field::<HashMap<String, String>>(key, value)?; },
+                config_keys::TIMEOUT_SECS => { check_field::<u64>(key, value)?; },
+                config_keys::RESOURCES => { check_field::<ResourceHints>(key, value)?; },
+                config_keys::QUANTUM_CIRCUIT => { check_field::<QuantumCircuit>(key, value)?.validate()?; },
+                config_keys::QUANTUM_SEED => { check_field::<u64>(key, value)?; },
+                config_keys::NODE_SELECTOR => { check_field::<HashMap<String, String>>(key, value)?; },
+                config_keys::REQUIRED_CAPABILITIES => { check_field::<Vec<String>>(key, value)?; },
+                config_keys::NAMESPACE => { check_field::<String>(key, value)?; },
+                _ => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifica a sintaxe da condição da tarefa, se houver
+    pub fn validate_condition(&self) -> Result<()> {
+        match &self.condition {
+            Some(source) => Condition::parse_task_condition(source).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
     /// Verifica se a tarefa pode ser executada
     pub fn can_execute(&self) -> bool {
         matches!(self.status, TaskStatus::Pending | TaskStatus::Waiting)
@@ -143,7 +230,10 @@ impl TaskNode {
 
     /// Verifica se a tarefa está completa
     pub fn is_complete(&self) -> bool {
-        matches!(self.status, TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled)
+        matches!(
+            self.status,
+            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::Skipped { .. }
+        )
     }
 
     /// Calcula a duração da execução
@@ -155,19 +245,408 @@ impl TaskNode {
     }
 }
 
+/// Namespace de tarefas sem `namespace` configurado
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Chaves de configuração conhecidas
+pub mod config_keys {
+    /// Comando executado pelas camadas local e cluster
+    pub const COMMAND: &str = "command";
+    /// Argumentos do comando
+    pub const ARGS: &str = "args";
+    /// Variáveis de ambiente do comando local
+    pub const ENV: &str = "env";
+    /// Tempo limite do comando local, em segundos
+    pub const TIMEOUT_SECS: &str = "timeout_secs";
+    /// Dicas de recursos (`ResourceHints`)
+    pub const RESOURCES: &str = "resources";
+    /// Circuito para a camada quântica (`QuantumCircuit`)
+    pub const QUANTUM_CIRCUIT: &str = "quantum_circuit";
+    /// Semente da amostragem quântica, sobrepõe a da camada
+    pub const QUANTUM_SEED: &str = "quantum_seed";
+    /// Seletor de nós do cluster
+    pub const NODE_SELECTOR: &str = "node_selector";
+    /// Capacidades que o nó do cluster precisa anunciar (ex.: `docker`, `gpu`)
+    pub const REQUIRED_CAPABILITIES: &str = "required_capabilities";
+    /// Política de escolha de camada (`LayerPolicy`)
+    pub const LAYER_POLICY: &str = "layer_policy";
+    /// Aceita chaves fora do esquema
+    pub const ALLOW_EXTRA: &str = "allow_extra";
+    /// Namespace para contabilidade de custo e orçamentos
+    pub const NAMESPACE: &str = "namespace";
+}
+
+/// Chaves aceitas na configuração de tarefas de uma camada
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSchema {
+    pub layer: ExecutionLayer,
+    pub keys: &'static [&'static str],
+}
+
+impl ConfigSchema {
+    /// Esquema da camada local
+    pub const LOCAL: ConfigSchema = ConfigSchema {
+        layer: ExecutionLayer::Local,
+        keys: &[config_keys::COMMAND, config_keys::ARGS, config_keys::ENV, config_keys::TIMEOUT_SECS, config_keys::RESOURCES],
+    };
+
+    /// Esquema da camada de cluster
+    pub const CLUSTER: ConfigSchema = ConfigSchema {
+        layer: ExecutionLayer::Cluster,
+        keys: &[config_keys::COMMAND, config_keys::ARGS, config_keys::RESOURCES, config_keys::NODE_SELECTOR, config_keys::REQUIRED_CAPABILITIES],
+    };
+
+    /// Esquema da camada de simulação quântica
+    pub const QUANTUM_SIM: ConfigSchema = ConfigSchema {
+        layer: ExecutionLayer::QuantumSim,
+        keys: &[config_keys::QUANTUM_CIRCUIT, config_keys::QUANTUM_SEED, config_keys::RESOURCES],
+    };
+
+    /// Esquemas de todas as camadas
+    pub const ALL: &'static [ConfigSchema] = &[Self::LOCAL, Self::CLUSTER, Self::QUANTUM_SIM];
+
+    /// Chaves válidas para qualquer camada
+    pub const COMMON_KEYS: &'static [&'static str] =
+        &[config_keys::LAYER_POLICY, config_keys::ALLOW_EXTRA, config_keys::NAMESPACE];
+
+    /// Esquema de uma camada
+    pub fn for_layer(layer: &ExecutionLayer) -> &'static ConfigSchema {
+        match layer {
+            ExecutionLayer::Local => &Self::LOCAL,
+            ExecutionLayer::Cluster => &Self::CLUSTER,
+            ExecutionLayer::QuantumSim => &Self::QUANTUM_SIM,
+        }
+    }
+
+    /// Verifica se a chave pertence ao esquema
+    pub fn accepts(&self, key: &str) -> bool {
+        self.keys.contains(&key) || Self::COMMON_KEYS.contains(&key)
+    }
+}
+
+/// Dicas de recursos para escalonamento
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceHints {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_cores: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+}
+
+/// Circuito executado pela camada quântica
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuantumCircuit {
+    pub qubits: usize,
+    pub gates: Vec<String>,
+    #[serde(default = "default_shots")]
+    pub shots: u32,
+}
+
+fn default_shots() -> u32 {
+    1024
+}
+
+impl QuantumCircuit {
+    /// Cria um circuito sem portas
+    pub fn new(qubits: usize) -> Self {
+        Self { qubits, gates: Vec::new(), shots: default_shots() }
+    }
+
+    /// Adiciona uma porta ao circuito
+    pub fn with_gate(mut self, gate: impl Into<String>) -> Self {
+        self.gates.push(gate.into());
+        self
+    }
+
+    /// Lê e valida um circuito em JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        let circuit: Self = serde_json::from_str(json)?;
+        circuit.validate()?;
+        Ok(circuit)
+    }
+
+    /// Interpreta as portas do circuito, validando os qubits usados
+    pub fn parse_gates(&self) -> Result<Vec<CircuitGate>> {
+        self.gates
+            .iter()
+            .enumerate()
+            .map(|(position, gate)| {
+                let field = format!("{}.gates[{}]", field_path(config_keys::QUANTUM_CIRCUIT), position);
+                let parsed = CircuitGate::parse(gate)
+                    .map_err(|message| OrchestratorError::validation(&field, "syntax", gate, message))?;
+                if let Some(qubit) = parsed.qubits.iter().find(|&&qubit| qubit >= self.qubits) {
+                    return Err(OrchestratorError::validation(
+                        &field, "range", gate, format!("qubit {} outside circuit of {} qubits", qubit, self.qubits),
+                    ));
+                }
+                Ok(parsed)
+            })
+            .collect()
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.qubits == 0 {
+            return Err(OrchestratorError::validation(
+                &format!("{}.qubits", field_path(config_keys::QUANTUM_CIRCUIT)), "range", "0", "circuit needs at least one qubit",
+            ));
+        }
+        self.parse_gates()?;
+        Ok(())
+    }
+}
+
+/// Porta de um circuito: `nome[(parâmetros)] qubits...`, ex. `rz(0.5) 0` ou `cx 0 1`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitGate {
+    pub name: String,
+    pub params: Vec<f64>,
+    pub qubits: Vec<usize>,
+}
+
+impl CircuitGate {
+    /// Interpreta uma porta; erros descrevem o problema sem entrar em pânico
+    pub fn parse(gate: &str) -> std::result::Result<Self, String> {
+        let gate = gate.trim();
+        let (name, params, rest) = match gate.find('(') {
+            Some(open) => {
+                let close = gate[open..].find(')').map(|offset| open + offset).ok_or("unclosed parameter list")?;
+                (&gate[..open], Self::parse_params(&gate[open + 1..close])?, &gate[close + 1..])
+            }
+            None => {
+                let end = gate.find(char::is_whitespace).unwrap_or(gate.len());
+                (&gate[..end], Vec::new(), &gate[end..])
+            }
+        };
+
+        let name = name.trim();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("invalid gate name '{}'", name));
+        }
+
+        let mut qubits = Vec::new();
+        for token in rest.split_whitespace() {
+            let qubit: usize = token.parse().map_err(|_| format!("invalid qubit index '{}'", token))?;
+            if qubits.contains(&qubit) {
+                return Err(format!("qubit {} used twice", qubit));
+            }
+            qubits.push(qubit);
+        }
+        if qubits.is_empty() {
+            return Err("gate acts on no qubits".to_string());
+        }
+
+        Ok(Self { name: name.to_lowercase(), params, qubits })
+    }
+
+    fn parse_params(params: &str) -> std::result::Result<Vec<f64>, String> {
+        if params.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        params
+            .split(',')
+            .map(|param| match param.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(value),
+                Ok(_) => Err(format!("non-finite parameter '{}'", param.trim())),
+                Err(_) => Err(format!("invalid parameter '{}'", param.trim())),
+            })
+            .collect()
+    }
+}
+
+/// Política de escolha da camada de execução
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerPolicy {
+    /// Orquestrador decide
+    Auto,
+    /// Sempre a camada indicada
+    Pinned(ExecutionLayer),
+}
+
+impl Serialize for LayerPolicy {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            LayerPolicy::Auto => serializer.serialize_str("auto"),
+            LayerPolicy::Pinned(layer) => serializer.serialize_str(layer.as_str()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LayerPolicy {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        if name == "auto" {
+            return Ok(LayerPolicy::Auto);
+        }
+        ExecutionLayer::from_name(&name)
+            .map(LayerPolicy::Pinned)
+            .ok_or_else(|| serde::de::Error::unknown_variant(&name, &["auto", "local", "cluster", "quantum_sim"]))
+    }
+}
+
+/// Caminho de um campo de configuração, usado em erros de validação
+fn field_path(key: &str) -> String {
+    format!("configuration.{}", key)
+}
+
+/// Desserializa um campo de configuração no tipo esperado
+fn check_field<T: serde::de::DeserializeOwned>(key: &str, value: &serde_json::Value) -> Result<T> {
+    serde_json::from_value(value.clone()).map_err(|e| {
+        OrchestratorError::validation(&field_path(key), "schema", &value.to_string(), e.to_string())
+    })
+}
+
+/// Construtor de tarefas com configuração tipada
+///
+/// `build` valida a configuração, reportando o mesmo erro que a inserção
+/// no grafo reportaria.
+#[derive(Debug, Clone)]
+pub struct TaskNodeBuilder {
+    node: TaskNode,
+}
+
+impl TaskNodeBuilder {
+    /// Inicia uma tarefa com o nome indicado
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { node: TaskNode::new(name.into(), None) }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.node.description = Some(description.into());
+        self
+    }
+
+    pub fn priority(mut self, priority: TaskPriority) -> Self {
+        self.node.priority = priority;
+        self
+    }
+
+    pub fn task_type(mut self, task_type: TaskType) -> Self {
+        self.node.task_type = task_type;
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.node.tags.insert(tag.into());
+        self
+    }
+
+    pub fn deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.node.deadline = Some(deadline);
+        self
+    }
+
+    /// Condição sobre as dependências (ex.: `parents.fetch.output.rows > 0`)
+    pub fn condition(mut self, condition: impl Into<String>) -> Self {
+        self.node.condition = Some(condition.into());
+        self
+    }
+
+    /// Comando e argumentos (camadas local e cluster)
+    pub fn command(mut self, command: impl Into<String>, args: Vec<String>) -> Self {
+        self.set(config_keys::COMMAND, command.into());
+        if !args.is_empty() {
+            self.set(config_keys::ARGS, args);
+        }
+        self
+    }
+
+    pub fn resources(mut self, resources: ResourceHints) -> Self {
+        self.set(config_keys::RESOURCES, resources);
+        self
+    }
+
+    pub fn quantum_circuit(mut self, circuit: QuantumCircuit) -> Self {
+        self.set(config_keys::QUANTUM_CIRCUIT, circuit);
+        self
+    }
+
+    pub fn node_selector(mut self, selector: HashMap<String, String>) -> Self {
+        self.set(config_keys::NODE_SELECTOR, selector);
+        self
+    }
+
+    pub fn required_capabilities<I, S>(mut self, capabilities: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let capabilities: Vec<String> = capabilities.into_iter().map(Into::into).collect();
+        self.set(config_keys::REQUIRED_CAPABILITIES, capabilities);
+        self
+    }
+
+    pub fn layer_policy(mut self, policy: LayerPolicy) -> Self {
+        self.set(config_keys::LAYER_POLICY, policy);
+        self
+    }
+
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.set(config_keys::NAMESPACE, namespace.into());
+        self
+    }
+
+    /// Aceita chaves fora do esquema
+    pub fn allow_extra(mut self, allow: bool) -> Self {
+        self.set(config_keys::ALLOW_EXTRA, allow);
+        self
+    }
+
+    /// Chave livre de configuração (rejeitada sem `allow_extra` se desconhecida)
+    pub fn extra(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.node.configuration.insert(key.into(), value);
+        self
+    }
+
+    /// Valida e retorna a tarefa
+    pub fn build(self) -> Result<TaskNode> {
+        self.node.validate_configuration()?;
+        self.node.validate_condition()?;
+        Ok(self.node)
+    }
+
+    fn set(&mut self, key: &str, value: impl Serialize) {
+        // Tipos acima sempre serializam para JSON
+        let value = serde_json::to_value(value).expect("configuration value is serializable");
+        self.node.configuration.insert(key.to_string(), value);
+    }
+}
+
 /// Tipo de dependência entre tarefas
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DependencyType {
-    /// Dependência hard - deve ser concluída antes
+    /// Dependência hard - deve ser concluída com sucesso antes
     Hard,
-    /// Dependência soft - preferência de ordem
+    /// Dependência soft - basta ter terminado, em qualquer estado final
     Soft,
-    /// Dependência de recurso - compartilha recurso
+    /// Dependência de recurso - compartilha recurso (ordena como soft)
     Resource,
-    /// Dependência de dados - necessita de saída
+    /// Dependência de dados - hard, e a saída é entregue à dependente
     Data,
 }
 
+impl DependencyType {
+    /// A origem precisa ter concluído com sucesso
+    pub fn requires_success(&self) -> bool {
+        matches!(self, DependencyType::Hard | DependencyType::Data)
+    }
+
+    /// Estilo da aresta na exportação DOT
+    fn dot_style(&self) -> &'static str {
+        match self {
+            DependencyType::Hard => "solid",
+            DependencyType::Soft => "dashed",
+            DependencyType::Resource => "dotted",
+            DependencyType::Data => "bold",
+        }
+    }
+}
+
 /// Aresta do grafo representando dependência entre tarefas
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyEdge {
@@ -176,6 +655,9 @@ pub struct DependencyEdge {
     pub target: TaskId,
     pub dependency_type: DependencyType,
     pub weight: f64,
+    /// Intervalo mínimo entre o fim da origem e o início do alvo
+    #[serde(default)]
+    pub lag: Option<Duration>,
     pub metadata: HashMap<String, serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
@@ -189,11 +671,18 @@ impl DependencyEdge {
             target,
             dependency_type,
             weight: 1.0,
+            lag: None,
             metadata: HashMap::new(),
             created_at: Utc::now(),
         }
     }
 
+    /// Define o intervalo mínimo após o fim da origem
+    pub fn with_lag(mut self, lag: Duration) -> Self {
+        self.lag = Some(lag);
+        self
+    }
+
     /// Define o peso da dependência
     pub fn with_weight(mut self, weight: f64) -> Self {
         self.weight = weight;
@@ -208,7 +697,7 @@ impl DependencyEdge {
 }
 
 /// Grafo de tarefas (DAG) principal
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TaskMesh {
     graph: Graph<TaskNode, DependencyEdge, Directed>,
     task_index: HashMap<TaskId, petgraph::graph::NodeIndex>,
@@ -227,10 +716,17 @@ impl TaskMesh {
 
     /// Adiciona uma tarefa ao grafo
     pub fn add_task(&mut self, task: TaskNode) -> Result<TaskId> {
+        task.validate_configuration()?;
+        task.validate_condition()?;
+        Ok(self.insert_node(task))
+    }
+
+    /// Insere o nó sem validar a configuração (dados já persistidos)
+    fn insert_node(&mut self, task: TaskNode) -> TaskId {
         let task_id = task.id;
         let node_index = self.graph.add_node(task);
         self.task_index.insert(task_id, node_index);
-        Ok(task_id)
+        task_id
     }
 
     /// Adiciona uma dependência entre tarefas
@@ -245,7 +741,7 @@ impl TaskMesh {
         self.edge_index.insert(edge_id, edge_index);
         
         // Verifica se o grafo continua sendo acíclico
-        if !petgraph::algo::is_cyclic_directed(&self.graph) {
+        if !self.has_cycle() {
             Ok(edge_id)
         } else {
             // Remove a aresta que criou o ciclo
@@ -255,6 +751,12 @@ impl TaskMesh {
         }
     }
 
+    /// `toposort` é iterativo; `is_cyclic_directed` recursa e estoura a
+    /// pilha em cadeias longas
+    fn has_cycle(&self) -> bool {
+        petgraph::algo::toposort(&self.graph, None).is_err()
+    }
+
     /// Obtém uma tarefa pelo ID
     pub fn get_task(&self, task_id: &TaskId) -> Option<&TaskNode> {
         let node_idx = self.task_index.get(task_id)?;
@@ -300,6 +802,11 @@ impl TaskMesh {
 
     /// Verifica se uma tarefa pode ser executada (todas dependências satisfeitas)
     pub fn can_execute_task(&self, task_id: &TaskId) -> Result<bool> {
+        self.can_execute_task_at(task_id, Utc::now())
+    }
+
+    /// Verifica se uma tarefa pode ser executada no instante indicado
+    pub fn can_execute_task_at(&self, task_id: &TaskId, now: DateTime<Utc>) -> Result<bool> {
         let task = self.get_task(task_id)
             .ok_or_else(|| OrchestratorError::TaskNotFound(*task_id))?;
         
@@ -307,8 +814,165 @@ impl TaskMesh {
             return Ok(false);
         }
 
-        let dependencies = self.get_dependencies(task_id)?;
-        Ok(dependencies.iter().all(|dep| dep.is_complete()))
+        Ok(self.release_time(task_id)?.is_some_and(|release| release <= now))
+    }
+
+    /// Instante a partir do qual as dependências da tarefa estão satisfeitas
+    ///
+    /// `None` se alguma dependência ainda não terminou (ou, em arestas
+    /// hard/data, terminou sem sucesso). O instante considera o `lag` das arestas.
+    pub fn release_time(&self, task_id: &TaskId) -> Result<Option<DateTime<Utc>>> {
+        let node_idx = self.task_index.get(task_id)
+            .ok_or_else(|| OrchestratorError::TaskNotFound(*task_id))?;
+
+        let mut release = DateTime::<Utc>::MIN_UTC;
+        for edge in self.graph.edges_directed(*node_idx, Direction::Incoming) {
+            let Some(source) = self.graph.node_weight(edge.source()) else { continue };
+            let dependency = edge.weight();
+
+            let satisfied = if dependency.dependency_type.requires_success() {
+                source.status == TaskStatus::Completed
+            } else {
+                source.is_complete()
+            };
+            if !satisfied {
+                return Ok(None);
+            }
+
+            if let Some(lag) = dependency.lag {
+                let finished = source.metrics.end_time.unwrap_or(source.updated_at);
+                let lag = chrono::Duration::from_std(lag).unwrap_or(chrono::Duration::MAX);
+                release = release.max(finished.checked_add_signed(lag).unwrap_or(DateTime::<Utc>::MAX_UTC));
+            }
+        }
+
+        Ok(Some(release))
+    }
+
+    /// Próximo instante futuro em que uma tarefa retida por `lag` fica pronta
+    pub fn next_release_time(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.get_all_tasks()
+            .into_iter()
+            .filter(|task| task.can_execute())
+            .filter_map(|task| self.release_time(&task.id).ok().flatten())
+            .filter(|release| *release > now)
+            .min()
+    }
+
+    /// Cancela as tarefas pendentes que dependem (via hard/data) de uma tarefa
+    /// que falhou, foi cancelada ou pulada
+    ///
+    /// Arestas soft e de recurso não propagam: seus alvos seguem executáveis.
+    pub fn propagate_failure(&mut self, task_id: &TaskId) -> Vec<TaskId> {
+        let mut cancelled = Vec::new();
+        let mut pending = VecDeque::from([*task_id]);
+
+        while let Some(current) = pending.pop_front() {
+            let Some(&node_idx) = self.task_index.get(&current) else { continue };
+            let targets: Vec<_> = self.graph
+                .edges_directed(node_idx, Direction::Outgoing)
+                .filter(|edge| edge.weight().dependency_type.requires_success())
+                .map(|edge| edge.target())
+                .collect();
+
+            for target_idx in targets {
+                let Some(target) = self.graph.node_weight_mut(target_idx) else { continue };
+                if !target.can_execute() {
+                    continue;
+                }
+                target.metrics.error_messages.push(format!("Dependency {} did not complete", current));
+                target.update_status(TaskStatus::Cancelled);
+                cancelled.push(target.id);
+                pending.push_back(target.id);
+            }
+        }
+
+        cancelled
+    }
+
+    /// Saídas das dependências de dados de uma tarefa, por ID da origem
+    pub fn data_inputs(&self, task_id: &TaskId) -> Result<HashMap<String, serde_json::Value>> {
+        let node_idx = self.task_index.get(task_id)
+            .ok_or_else(|| OrchestratorError::TaskNotFound(*task_id))?;
+
+        Ok(self.graph
+            .edges_directed(*node_idx, Direction::Incoming)
+            .filter(|edge| edge.weight().dependency_type == DependencyType::Data)
+            .filter_map(|edge| self.graph.node_weight(edge.source()))
+            .map(|source| (source.id.to_string(), last_output(source)))
+            .collect())
+    }
+
+    /// Avalia a condição da tarefa sobre as suas dependências
+    ///
+    /// Tarefas sem condição sempre executam. O contexto é
+    /// `{"parents": {<nome>: {"status": ..., "output": ...}}}`, com o status
+    /// textual e a saída da última execução de cada dependência. Referenciar
+    /// uma tarefa que não é dependência (ou um nome repetido entre elas) é
+    /// erro, e não `null`, para que um erro de digitação não pule a tarefa.
+    pub fn evaluate_condition(&self, task_id: &TaskId) -> Result<bool> {
+        let node_idx = self.task_index.get(task_id)
+            .ok_or_else(|| OrchestratorError::TaskNotFound(*task_id))?;
+        let Some(source) = self.graph.node_weight(*node_idx).and_then(|task| task.condition.as_ref()) else {
+            return Ok(true);
+        };
+        let condition = Condition::parse_task_condition(source)?;
+
+        let mut parents = serde_json::Map::new();
+        let mut ids: HashMap<&str, TaskId> = HashMap::new();
+        let mut ambiguous = HashSet::new();
+        for parent in self.graph
+            .neighbors_directed(*node_idx, Direction::Incoming)
+            .filter_map(|idx| self.graph.node_weight(idx))
+        {
+            if *ids.entry(parent.name.as_str()).or_insert(parent.id) != parent.id {
+                ambiguous.insert(parent.name.as_str());
+            }
+            parents.insert(parent.name.clone(), serde_json::json!({
+                "status": parent.status.to_string(),
+                "output": last_output(parent),
+            }));
+        }
+
+        for path in condition.paths() {
+            let Some(name) = path.get(1) else { continue };
+            let problem = if ambiguous.contains(name.as_str()) {
+                "matches more than one dependency"
+            } else if !parents.contains_key(name) {
+                "is not a dependency of this task"
+            } else {
+                continue;
+            };
+            return Err(OrchestratorError::validation(
+                "task.condition", "parent", source, format!("parent '{}' {}", name, problem),
+            ));
+        }
+
+        Ok(condition.evaluate(&serde_json::json!({ "parents": parents })))
+    }
+
+    /// Exporta o grafo no formato DOT (Graphviz)
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph task_mesh {\n");
+        for task in self.graph.node_weights() {
+            let style = if matches!(task.status, TaskStatus::Skipped { .. }) { ", style=dashed" } else { "" };
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n{}\"{}];\n",
+                task.id, task.name.replace('"', "\\\""), task.status, style
+            ));
+        }
+        for edge in self.graph.edge_weights() {
+            let mut label = format!("{:?}", edge.dependency_type).to_lowercase();
+            if let Some(lag) = edge.lag {
+                label.push_str(&format!(" +{}ms", lag.as_millis()));
+            }
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\", style={}];\n",
+                edge.source, edge.target, label, edge.dependency_type.dot_style()
+            ));
+        }
+        dot.push_str("}\n");
+        dot
     }
 
     /// Obtém tarefas prontas para execução
@@ -372,12 +1036,288 @@ impl TaskMesh {
     }
 }
 
+/// Exportação e importação no formato portável
+impl TaskMesh {
+    /// Converte o grafo para o formato de troca versionado
+    pub fn to_portable(&self) -> PortableGraph {
+        let nodes = self.graph.node_weights().map(PortableNode::from).collect();
+        let edges = self.graph.edge_weights().map(PortableEdge::from).collect();
+        PortableGraph { version: PORTABLE_FORMAT_VERSION, nodes, edges }
+    }
+
+    /// Reconstrói o grafo a partir do formato de troca
+    ///
+    /// A configuração dos nós não é revalidada: documentos exportados por
+    /// versões anteriores continuam legíveis mesmo que o esquema mude.
+    pub fn from_portable(portable: PortableGraph) -> Result<Self> {
+        if portable.version == 0 || portable.version > PORTABLE_FORMAT_VERSION {
+            return Err(OrchestratorError::validation(
+                "version", "supported", &portable.version.to_string(),
+                format!("unsupported graph format version (max {})", PORTABLE_FORMAT_VERSION),
+            ));
+        }
+
+        let mut mesh = TaskMesh::new();
+        for node in portable.nodes {
+            if mesh.task_index.contains_key(&node.id) {
+                return Err(OrchestratorError::validation(
+                    "nodes.id", "unique", &node.id.to_string(), "duplicate node id",
+                ));
+            }
+            mesh.insert_node(node.into());
+        }
+
+        // Ciclos são verificados uma vez ao final: por aresta seria quadrático
+        for edge in portable.edges {
+            let edge: DependencyEdge = edge.into();
+            if mesh.edge_index.contains_key(&edge.id) {
+                return Err(OrchestratorError::validation(
+                    "edges.id", "unique", &edge.id.to_string(), "duplicate edge id",
+                ));
+            }
+            if !edge.weight.is_finite() {
+                return Err(OrchestratorError::validation(
+                    "edges.weight", "finite", &edge.weight.to_string(), "edge weight must be finite",
+                ));
+            }
+            let source = *mesh.task_index.get(&edge.source).ok_or(OrchestratorError::TaskNotFound(edge.source))?;
+            let target = *mesh.task_index.get(&edge.target).ok_or(OrchestratorError::TaskNotFound(edge.target))?;
+            let edge_id = edge.id;
+            let edge_index = mesh.graph.add_edge(source, target, edge);
+            mesh.edge_index.insert(edge_id, edge_index);
+        }
+        if mesh.has_cycle() {
+            return Err(OrchestratorError::CyclicDependency);
+        }
+        Ok(mesh)
+    }
+
+    /// Serializa o grafo como documento JSON portável
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.to_portable())?)
+    }
+
+    /// Lê um documento JSON portável (ou no formato legado)
+    pub fn from_json(json: &str) -> Result<Self> {
+        let portable = PortableGraph::from_value(serde_json::from_str(json)?)?;
+        Self::from_portable(portable)
+    }
+}
+
 impl Default for TaskMesh {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Versão atual do formato portável do grafo
+pub const PORTABLE_FORMAT_VERSION: u32 = 1;
+
+/// Formato de troca estável do grafo
+///
+/// Independe da representação interna do petgraph. Campos desconhecidos são
+/// ignorados na leitura, para que documentos de versões futuras possam ser lidos.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortableGraph {
+    pub version: u32,
+    #[serde(default)]
+    pub nodes: Vec<PortableNode>,
+    #[serde(default)]
+    pub edges: Vec<PortableEdge>,
+}
+
+/// Nó no formato portável
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortableNode {
+    pub id: TaskId,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_portable_status")]
+    pub status: TaskStatus,
+    #[serde(default = "default_portable_priority")]
+    pub priority: TaskPriority,
+    #[serde(default = "default_portable_task_type")]
+    pub task_type: TaskType,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub configuration: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub execution_context: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub retry_count: u32,
+    #[serde(default)]
+    pub error_messages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+fn default_portable_status() -> TaskStatus {
+    TaskStatus::Pending
+}
+
+fn default_portable_priority() -> TaskPriority {
+    TaskPriority::Medium
+}
+
+fn default_portable_task_type() -> TaskType {
+    TaskType::Medium
+}
+
+impl From<&TaskNode> for PortableNode {
+    fn from(task: &TaskNode) -> Self {
+        let mut tags: Vec<String> = task.tags.iter().cloned().collect();
+        tags.sort();
+        Self {
+            id: task.id,
+            name: task.name.clone(),
+            description: task.description.clone(),
+            status: task.status.clone(),
+            priority: task.priority.clone(),
+            task_type: task.task_type.clone(),
+            tags,
+            components: task.components.clone(),
+            created_at: Some(task.created_at),
+            updated_at: Some(task.updated_at),
+            scheduled_at: task.scheduled_at,
+            deadline: task.deadline,
+            configuration: task.configuration.clone(),
+            execution_context: task.execution_context.clone(),
+            retry_count: task.metrics.retry_count,
+            error_messages: task.metrics.error_messages.clone(),
+            condition: task.condition.clone(),
+        }
+    }
+}
+
+impl From<PortableNode> for TaskNode {
+    fn from(node: PortableNode) -> Self {
+        let mut task = TaskNode::new(node.name, node.description);
+        task.id = node.id;
+        task.status = node.status;
+        task.priority = node.priority;
+        task.task_type = node.task_type;
+        task.tags = node.tags.into_iter().collect();
+        task.components = node.components;
+        task.created_at = node.created_at.unwrap_or(task.created_at);
+        task.updated_at = node.updated_at.unwrap_or(task.created_at);
+        task.scheduled_at = node.scheduled_at;
+        task.deadline = node.deadline;
+        task.configuration = node.configuration;
+        task.execution_context = node.execution_context;
+        task.metrics.retry_count = node.retry_count;
+        task.metrics.error_messages = node.error_messages;
+        task.condition = node.condition;
+        task
+    }
+}
+
+/// Aresta no formato portável
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortableEdge {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<EdgeId>,
+    pub from: TaskId,
+    pub to: TaskId,
+    #[serde(default = "default_portable_kind")]
+    pub kind: DependencyType,
+    #[serde(default = "default_portable_weight")]
+    pub weight: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lag_ms: Option<u64>,
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+fn default_portable_kind() -> DependencyType {
+    DependencyType::Hard
+}
+
+fn default_portable_weight() -> f64 {
+    1.0
+}
+
+impl From<&DependencyEdge> for PortableEdge {
+    fn from(edge: &DependencyEdge) -> Self {
+        Self {
+            id: Some(edge.id),
+            from: edge.source,
+            to: edge.target,
+            kind: edge.dependency_type.clone(),
+            weight: edge.weight,
+            lag_ms: edge.lag.map(|lag| lag.as_millis() as u64),
+            metadata: edge.metadata.clone(),
+        }
+    }
+}
+
+impl From<PortableEdge> for DependencyEdge {
+    fn from(edge: PortableEdge) -> Self {
+        let mut dependency = DependencyEdge::new(edge.from, edge.to, edge.kind).with_weight(edge.weight);
+        if let Some(id) = edge.id {
+            dependency.id = id;
+        }
+        dependency.lag = edge.lag_ms.map(Duration::from_millis);
+        dependency.metadata = edge.metadata;
+        dependency
+    }
+}
+
+/// Grafo serializado diretamente pelo serde do petgraph (snapshots antigos)
+#[derive(Deserialize)]
+struct LegacyTaskMesh {
+    graph: LegacyGraph,
+}
+
+#[derive(Deserialize)]
+struct LegacyGraph {
+    nodes: Vec<TaskNode>,
+    #[serde(default)]
+    edges: Vec<Option<(usize, usize, DependencyEdge)>>,
+}
+
+impl PortableGraph {
+    /// Interpreta um documento portável ou um grafo no formato legado
+    pub fn from_value(value: serde_json::Value) -> Result<Self> {
+        if value.get("version").is_some() {
+            return Ok(serde_json::from_value(value)?);
+        }
+
+        let legacy: LegacyTaskMesh = serde_json::from_value(value)?;
+        Ok(PortableGraph {
+            version: PORTABLE_FORMAT_VERSION,
+            nodes: legacy.graph.nodes.iter().map(PortableNode::from).collect(),
+            edges: legacy.graph.edges.iter().flatten().map(|(_, _, edge)| PortableEdge::from(edge)).collect(),
+        })
+    }
+
+    /// Desserializador para campos que podem conter o formato legado
+    pub fn deserialize_compat<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Self::from_value(value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Saída da última execução de uma tarefa (`null` se não houver)
+fn last_output(task: &TaskNode) -> serde_json::Value {
+    task.execution_context
+        .get("last_result")
+        .and_then(|result| result.get("output"))
+        .cloned()
+        .unwrap_or(serde_json::Value::Null)
+}
+
 /// Estatísticas do Task Mesh
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskMeshStatistics {
@@ -398,6 +1338,7 @@ impl fmt::Display for TaskStatus {
             TaskStatus::Failed => write!(f, "Failed"),
             TaskStatus::Cancelled => write!(f, "Cancelled"),
             TaskStatus::Paused => write!(f, "Paused"),
+            TaskStatus::Skipped { .. } => write!(f, "Skipped"),
         }
     }
 }
@@ -467,5 +1408,327 @@ mod tests {
         
         assert!(matches!(result, Err(OrchestratorError::CyclicDependency)));
     }
-}
 
+    #[test]
+    fn test_valid_quantum_node() {
+        let task = TaskNode::builder("bell")
+            .quantum_circuit(QuantumCircuit::new(2).with_gate("h 0").with_gate("cx 0 1"))
+            .resources(ResourceHints { timeout_seconds: Some(30), ..Default::default() })
+            .layer_policy(LayerPolicy::Pinned(ExecutionLayer::QuantumSim))
+            .build()
+            .unwrap();
+        assert_eq!(task.layer_policy(), Some(LayerPolicy::Pinned(ExecutionLayer::QuantumSim)));
+        assert_eq!(task.configuration["layer_policy"], "quantum_sim");
+
+        let mut mesh = TaskMesh::new();
+        assert!(mesh.add_task(task).is_ok());
+
+        // Comando não pertence ao esquema quântico
+        let pinned = TaskNode::builder("bad")
+            .command("echo", vec![])
+            .layer_policy(LayerPolicy::Pinned(ExecutionLayer::QuantumSim))
+            .build();
+        assert!(matches!(pinned, Err(OrchestratorError::ValidationError { field, .. }) if field == "configuration.command"));
+    }
+
+    #[test]
+    fn test_misspelled_key_rejected() {
+        let mut task = TaskNode::new("typo".to_string(), None);
+        task.configuration.insert("comand".to_string(), serde_json::json!("echo hi"));
+
+        let mut mesh = TaskMesh::new();
+        match mesh.add_task(task) {
+            Err(OrchestratorError::ValidationError { field, .. }) => assert_eq!(field, "configuration.comand"),
+            other => panic!("expected validation error, got {:?}", other),
+        }
+        assert_eq!(mesh.get_all_tasks().len(), 0);
+
+        // Campo aninhado desconhecido e tipo errado
+        let nested = TaskNode::builder("nested")
+            .extra("resources", serde_json::json!({ "cpu_core": 2 }))
+            .build();
+        assert!(matches!(nested, Err(OrchestratorError::ValidationError { field, message, .. })
+            if field == "configuration.resources" && message.contains("cpu_core")));
+        let wrong_type = TaskNode::builder("wrong").extra("command", serde_json::json!(42)).build();
+        assert!(wrong_type.is_err());
+    }
+
+    #[test]
+    fn test_allow_extra_escape_hatch() {
+        let task = TaskNode::builder("extra")
+            .command("echo", vec!["hi".to_string()])
+            .extra("team", serde_json::json!("infra"))
+            .allow_extra(true)
+            .build()
+            .unwrap();
+
+        let mut mesh = TaskMesh::new();
+        assert!(mesh.add_task(task).is_ok());
+
+        // Chaves conhecidas continuam tipadas
+        let invalid = TaskNode::builder("extra")
+            .extra("command", serde_json::json!(["echo"]))
+            .allow_extra(true)
+            .build();
+        assert!(invalid.is_err());
+    }
+
+    fn sample_mesh() -> (TaskMesh, TaskId, TaskId) {
+        let mut mesh = TaskMesh::new();
+        let build = TaskNode::builder("build").command("cargo", vec!["build".to_string()]).tag("ci").build().unwrap();
+        let mut test = TaskNode::new("test".to_string(), Some("run tests".to_string()));
+        test.update_status(TaskStatus::Failed);
+        test.metrics.error_messages.push("exit 101".to_string());
+        let (build_id, test_id) = (build.id, test.id);
+        mesh.add_task(build).unwrap();
+        mesh.add_task(test).unwrap();
+        mesh.add_dependency(DependencyEdge::new(build_id, test_id, DependencyType::Data).with_weight(2.5)).unwrap();
+        (mesh, build_id, test_id)
+    }
+
+    #[test]
+    fn test_portable_round_trip() {
+        let (mesh, build_id, test_id) = sample_mesh();
+
+        let json = mesh.to_json().unwrap();
+        let restored = TaskMesh::from_json(&json).unwrap();
+        assert_eq!(restored.to_portable(), mesh.to_portable());
+
+        let test = restored.get_task(&test_id).unwrap();
+        assert_eq!(test.status, TaskStatus::Failed);
+        assert_eq!(test.metrics.error_messages, vec!["exit 101".to_string()]);
+        assert_eq!(restored.get_dependencies(&test_id).unwrap()[0].id, build_id);
+        assert!(restored.get_task(&build_id).unwrap().tags.contains("ci"));
+    }
+
+    #[test]
+    fn test_portable_v1_fixture() {
+        // Documento escrito à mão, com campos de versões futuras
+        let json = r#"{
+            "version": 1,
+            "generator": "future-release",
+            "nodes": [
+                { "id": "6f1c1a3e-0000-4000-8000-000000000001", "name": "fetch", "status": "Completed",
+                  "configuration": { "command": "curl" }, "estimated_cost": 3 },
+                { "id": "6f1c1a3e-0000-4000-8000-000000000002", "name": "parse", "priority": "High" }
+            ],
+            "edges": [
+                { "from": "6f1c1a3e-0000-4000-8000-000000000001", "to": "6f1c1a3e-0000-4000-8000-000000000002",
+                  "kind": "Hard", "label": "ignored" }
+            ]
+        }"#;
+
+        let mesh = TaskMesh::from_json(json).unwrap();
+        let fetch: TaskId = "6f1c1a3e-0000-4000-8000-000000000001".parse().unwrap();
+        let parse: TaskId = "6f1c1a3e-0000-4000-8000-000000000002".parse().unwrap();
+
+        assert_eq!(mesh.get_task(&fetch).unwrap().status, TaskStatus::Completed);
+        assert_eq!(mesh.get_task(&parse).unwrap().priority, TaskPriority::High);
+        assert_eq!(mesh.get_task(&parse).unwrap().status, TaskStatus::Pending);
+        assert!(mesh.can_execute_task(&parse).unwrap());
+        assert_eq!(mesh.statistics().total_dependencies, 1);
+
+        let future = json.replace("\"version\": 1", "\"version\": 2");
+        assert!(TaskMesh::from_json(&future).is_err());
+    }
+
+    #[test]
+    fn test_legacy_graph_format() {
+        let (mesh, _, test_id) = sample_mesh();
+        let nodes: Vec<&TaskNode> = mesh.graph.node_weights().collect();
+        let edge = mesh.graph.edge_weights().next().unwrap();
+        let legacy = serde_json::json!({
+            "graph": {
+                "nodes": nodes,
+                "node_holes": [],
+                "edge_property": "directed",
+                "edges": [[0, 1, edge]]
+            },
+            "task_index": {},
+            "edge_index": {}
+        });
+
+        let restored = TaskMesh::from_portable(PortableGraph::from_value(legacy).unwrap()).unwrap();
+        assert_eq!(restored.to_portable(), mesh.to_portable());
+        assert_eq!(restored.get_dependencies(&test_id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_soft_edge_runs_after_failed_parent() {
+        let mut mesh = TaskMesh::new();
+        let parent = TaskNode::new("parent".to_string(), None);
+        let soft_child = TaskNode::new("soft".to_string(), None);
+        let hard_child = TaskNode::new("hard".to_string(), None);
+        let grandchild = TaskNode::new("grandchild".to_string(), None);
+        let ids = [parent.id, soft_child.id, hard_child.id, grandchild.id];
+        for task in [parent, soft_child, hard_child, grandchild] {
+            mesh.add_task(task).unwrap();
+        }
+        mesh.add_dependency(DependencyEdge::new(ids[0], ids[1], DependencyType::Soft)).unwrap();
+        mesh.add_dependency(DependencyEdge::new(ids[0], ids[2], DependencyType::Hard)).unwrap();
+        mesh.add_dependency(DependencyEdge::new(ids[2], ids[3], DependencyType::Data)).unwrap();
+
+        mesh.get_task_mut(&ids[0]).unwrap().update_status(TaskStatus::Failed);
+        assert!(mesh.can_execute_task(&ids[1]).unwrap());
+        assert!(!mesh.can_execute_task(&ids[2]).unwrap());
+
+        // Falha se propaga apenas por arestas hard/data
+        let cancelled = mesh.propagate_failure(&ids[0]);
+        assert_eq!(cancelled, vec![ids[2], ids[3]]);
+        assert_eq!(mesh.get_task(&ids[1]).unwrap().status, TaskStatus::Pending);
+        assert_eq!(mesh.get_task(&ids[3]).unwrap().status, TaskStatus::Cancelled);
+
+        let dot = mesh.to_dot();
+        assert!(dot.contains("[label=\"soft\", style=dashed]"));
+        assert!(dot.contains("[label=\"data\", style=bold]"));
+    }
+
+    #[test]
+    fn test_condition_reads_parent_output_and_skip_releases_soft_edges() {
+        let mut mesh = TaskMesh::new();
+        let fetch = TaskNode::new("fetch".to_string(), None);
+        let load = TaskNode::builder("load").condition("parents.fetch.output.rows > 0").build().unwrap();
+        let soft_child = TaskNode::new("report".to_string(), None);
+        let data_child = TaskNode::new("index".to_string(), None);
+        let ids = [fetch.id, load.id, soft_child.id, data_child.id];
+        for task in [fetch, load, soft_child, data_child] {
+            mesh.add_task(task).unwrap();
+        }
+        mesh.add_dependency(DependencyEdge::new(ids[0], ids[1], DependencyType::Data)).unwrap();
+        mesh.add_dependency(DependencyEdge::new(ids[1], ids[2], DependencyType::Soft)).unwrap();
+        mesh.add_dependency(DependencyEdge::new(ids[1], ids[3], DependencyType::Data)).unwrap();
+
+        let finish = |mesh: &mut TaskMesh, rows: u64| {
+            let fetch = mesh.get_task_mut(&ids[0]).unwrap();
+            fetch.execution_context.insert("last_result".to_string(), serde_json::json!({ "output": { "rows": rows } }));
+            fetch.update_status(TaskStatus::Completed);
+        };
+        finish(&mut mesh, 3);
+        assert!(mesh.evaluate_condition(&ids[1]).unwrap());
+        finish(&mut mesh, 0);
+        assert!(!mesh.evaluate_condition(&ids[1]).unwrap());
+
+        let reason = "condition evaluated to false".to_string();
+        mesh.get_task_mut(&ids[1]).unwrap().update_status(TaskStatus::Skipped { reason });
+        assert!(mesh.can_execute_task(&ids[2]).unwrap());
+        assert!(!mesh.can_execute_task(&ids[3]).unwrap());
+        assert_eq!(mesh.propagate_failure(&ids[1]), vec![ids[3]]);
+
+        let dot = mesh.to_dot();
+        assert!(dot.contains(&format!("\"{}\" [label=\"load\\nSkipped\", style=dashed];", ids[1])), "{}", dot);
+        assert!(dot.contains(&format!("\"{}\" [label=\"fetch\\nCompleted\"];", ids[0])), "{}", dot);
+
+        let restored = TaskMesh::from_json(&mesh.to_json().unwrap()).unwrap();
+        let load = restored.get_task(&ids[1]).unwrap();
+        assert_eq!(load.condition.as_deref(), Some("parents.fetch.output.rows > 0"));
+        assert!(matches!(load.status, TaskStatus::Skipped { .. }));
+    }
+
+    #[test]
+    fn test_condition_errors_are_reported() {
+        assert!(TaskNode::builder("bad").condition("parents.x >").build().is_err());
+
+        let mut mesh = TaskMesh::new();
+        let parent = TaskNode::new("parent".to_string(), None);
+        let twin = TaskNode::new("parent".to_string(), None);
+        let child = TaskNode::builder("child").condition("parents.parent.status == \"Completed\"").build().unwrap();
+        let orphan = TaskNode::builder("orphan").condition("parents.missing.output.ok").build().unwrap();
+        let ids = [parent.id, twin.id, child.id, orphan.id];
+        for task in [parent, twin, child, orphan] {
+            mesh.add_task(task).unwrap();
+        }
+        mesh.add_dependency(DependencyEdge::new(ids[0], ids[2], DependencyType::Hard)).unwrap();
+        mesh.add_dependency(DependencyEdge::new(ids[1], ids[2], DependencyType::Hard)).unwrap();
+
+        for (task_id, expected) in [(ids[2], "matches more than one dependency"), (ids[3], "is not a dependency")] {
+            match mesh.evaluate_condition(&task_id) {
+                Err(OrchestratorError::ValidationError { field, message, .. }) => {
+                    assert_eq!(field, "task.condition");
+                    assert!(message.contains(expected), "{}", message);
+                }
+                other => panic!("expected validation error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_lag_edge_delays_release() {
+        let mut mesh = TaskMesh::new();
+        let parent = TaskNode::new("parent".to_string(), None);
+        let child = TaskNode::new("child".to_string(), None);
+        let (parent_id, child_id) = (parent.id, child.id);
+        mesh.add_task(parent).unwrap();
+        mesh.add_task(child).unwrap();
+        mesh.add_dependency(
+            DependencyEdge::new(parent_id, child_id, DependencyType::Hard).with_lag(Duration::from_secs(30))
+        ).unwrap();
+
+        let finished = Utc::now();
+        let parent = mesh.get_task_mut(&parent_id).unwrap();
+        parent.update_status(TaskStatus::Completed);
+        parent.metrics.end_time = Some(finished);
+
+        let release = finished + chrono::Duration::seconds(30);
+        assert_eq!(mesh.release_time(&child_id).unwrap(), Some(release));
+        assert!(!mesh.can_execute_task_at(&child_id, finished + chrono::Duration::seconds(29)).unwrap());
+        assert!(mesh.can_execute_task_at(&child_id, release).unwrap());
+        assert_eq!(mesh.next_release_time(finished), Some(release));
+
+        let restored = TaskMesh::from_json(&mesh.to_json().unwrap()).unwrap();
+        assert_eq!(restored.release_time(&child_id).unwrap(), Some(release));
+    }
+
+    #[test]
+    fn test_nasty_portable_documents_are_errors() {
+        let deep = format!(r#"{{"version":1,"nodes":[{{"id":"{}","name":"x","configuration":{{"k":{}{}}}}}]}}"#,
+            Uuid::new_v4(), "[".repeat(10_000), "]".repeat(10_000));
+        assert!(matches!(TaskMesh::from_json(&deep), Err(OrchestratorError::SerializationError(_))));
+
+        for json in ["", "null", "[]", r#"{"version":0}"#, r#"{"version":1,"edges":[{"from":"x","to":"y"}]}"#] {
+            assert!(TaskMesh::from_json(json).is_err(), "{}", json);
+        }
+
+        let dangling = PortableEdge {
+            id: None, from: Uuid::new_v4(), to: Uuid::new_v4(), kind: DependencyType::Hard,
+            weight: f64::NAN, lag_ms: Some(u64::MAX), metadata: HashMap::new(),
+        };
+        let graph = PortableGraph { version: 1, nodes: vec![], edges: vec![dangling] };
+        assert!(TaskMesh::from_portable(graph).is_err());
+    }
+
+    #[test]
+    fn test_long_chain_round_trips_and_cycle_is_rejected() {
+        let mut portable = PortableGraph { version: PORTABLE_FORMAT_VERSION, nodes: vec![], edges: vec![] };
+        for i in 0..10_000 {
+            let node = PortableNode::from(&TaskNode::new(format!("n{}", i), None));
+            if let Some(previous) = portable.nodes.last() {
+                portable.edges.push(PortableEdge::from(&DependencyEdge::new(previous.id, node.id, DependencyType::Hard)));
+            }
+            portable.nodes.push(node);
+        }
+
+        let mesh = TaskMesh::from_portable(portable.clone()).unwrap();
+        assert_eq!(mesh.get_all_tasks().len(), 10_000);
+        assert!(TaskMesh::from_json(&mesh.to_json().unwrap()).is_ok());
+
+        let (first, last) = (portable.nodes[0].id, portable.nodes[9_999].id);
+        portable.edges.push(PortableEdge::from(&DependencyEdge::new(last, first, DependencyType::Soft)));
+        assert!(matches!(TaskMesh::from_portable(portable), Err(OrchestratorError::CyclicDependency)));
+    }
+
+    #[test]
+    fn test_malformed_gates_are_validation_errors() {
+        let ok = QuantumCircuit::from_json(r#"{"qubits":2,"gates":["h 0","rz(0.5) 1","u3(0.1, -2, 3e-1) 0","cx 0 1"]}"#).unwrap();
+        let gates = ok.parse_gates().unwrap();
+        assert_eq!(gates[2], CircuitGate { name: "u3".to_string(), params: vec![0.1, -2.0, 0.3], qubits: vec![0] });
+
+        for gate in ["rz(NaN) 0", "rz(inf) 0", "rz(0.5 0", "rz() ", "h", "h 2", "cx 0 0", "h -1", "é 0", "(1) 0", "h 99999999999999999999999"] {
+            let json = serde_json::json!({ "qubits": 2, "gates": [gate] }).to_string();
+            match QuantumCircuit::from_json(&json) {
+                Err(OrchestratorError::ValidationError { field, .. }) => assert_eq!(field, "configuration.quantum_circuit.gates[0]"),
+                other => panic!("{:?}: expected validation error, got {:?}", gate, other),
+            }
+        }
+        assert!(QuantumCircuit::from_json(r#"{"qubits":-1,"gates":[]}"#).is_err());
+    }
+}
@@ -8,12 +8,19 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::process::Stdio;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{oneshot, RwLock};
+use tracing::{info, warn};
 
+use crate::clock::{Clock, SystemClock};
+use crate::cost::CostModel;
 use crate::errors::{OrchestratorError, Result};
-use crate::graph::{TaskId, TaskNode};
+use crate::graph::{config_keys, QuantumCircuit, ResourceHints, TaskId, TaskNode};
+use crate::quantum::{self, SimulationCache, SimulationKey};
 
 /// Resultado da execução de uma tarefa
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +50,8 @@ pub enum TaskExecutionStatus {
     Cancelled,
     /// Timeout na execução
     Timeout,
+    /// Não executada: condição da tarefa falsa
+    Skipped,
 }
 
 /// Métricas de uso de recursos
@@ -78,6 +87,27 @@ pub enum ExecutionLayer {
     QuantumSim,
 }
 
+impl ExecutionLayer {
+    /// Nome usado em configurações e decisões de roteamento
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionLayer::Local => "local",
+            ExecutionLayer::Cluster => "cluster",
+            ExecutionLayer::QuantumSim => "quantum_sim",
+        }
+    }
+
+    /// Converte um nome de camada (inverso de `as_str`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "local" => Some(ExecutionLayer::Local),
+            "cluster" => Some(ExecutionLayer::Cluster),
+            "quantum_sim" => Some(ExecutionLayer::QuantumSim),
+            _ => None,
+        }
+    }
+}
+
 /// Configuração de execução
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionConfig {
@@ -86,6 +116,9 @@ pub struct ExecutionConfig {
     pub retry_attempts: u32,
     pub resource_limits: ResourceLimits,
     pub layer_specific: HashMap<String, serde_json::Value>,
+    /// Taxas da camada local
+    #[serde(default)]
+    pub cost: CostModel,
 }
 
 /// Limites de recursos
@@ -110,6 +143,7 @@ impl Default for ExecutionConfig {
                 max_network_io_mb: 50.0,
             },
             layer_specific: HashMap::new(),
+            cost: CostModel::default(),
         }
     }
 }
@@ -134,6 +168,11 @@ pub trait ExecutionLayerTrait: Send + Sync {
     
     /// Tipo da camada
     fn layer_type(&self) -> ExecutionLayer;
+    
+    /// Taxas cobradas pela camada (gratuita por padrão)
+    fn cost_model(&self) -> CostModel {
+        CostModel::default()
+    }
 }
 
 /// Saúde de uma camada de execução
@@ -166,6 +205,110 @@ pub struct LayerStatistics {
     pub average_execution_time_ms: f64,
     pub total_resource_usage: ResourceUsage,
     pub uptime_seconds: u64,
+    /// Execuções atendidas por cache (também contadas em `total_tasks_executed`)
+    #[serde(default)]
+    pub cache_hits: u64,
+    /// Chamadas que terminaram em erro, sem resultado (também contadas em
+    /// `failed_tasks`)
+    #[serde(default)]
+    pub error_count: u64,
+}
+
+impl LayerStatistics {
+    /// Estatísticas zeradas de uma camada
+    pub fn new(layer: ExecutionLayer) -> Self {
+        Self {
+            layer,
+            total_tasks_executed: 0,
+            successful_tasks: 0,
+            failed_tasks: 0,
+            average_execution_time_ms: 0.0,
+            total_resource_usage: ResourceUsage::default(),
+            uptime_seconds: 0,
+            cache_hits: 0,
+            error_count: 0,
+        }
+    }
+    
+    /// Registra o resultado de uma execução
+    ///
+    /// A média de tempo cobre apenas execuções com resultado e é atualizada
+    /// incrementalmente (`média += (x - média) / n`), sem acumular somas.
+    pub fn record(&mut self, result: &Result<TaskExecutionResult>) {
+        self.total_tasks_executed += 1;
+        
+        let execution = match result {
+            Ok(execution) => execution,
+            Err(_) => {
+                self.failed_tasks += 1;
+                self.error_count += 1;
+                return;
+            }
+        };
+        if execution.status == TaskExecutionStatus::Success {
+            self.successful_tasks += 1;
+        } else {
+            self.failed_tasks += 1;
+        }
+        let usage = &execution.resource_usage;
+        self.total_resource_usage.cpu_percent += usage.cpu_percent;
+        self.total_resource_usage.memory_mb += usage.memory_mb;
+        self.total_resource_usage.disk_io_mb += usage.disk_io_mb;
+        self.total_resource_usage.network_io_mb += usage.network_io_mb;
+        self.total_resource_usage.execution_time_ms += usage.execution_time_ms;
+        
+        let completed = self.completed_tasks() as f64;
+        self.average_execution_time_ms +=
+            (usage.execution_time_ms as f64 - self.average_execution_time_ms) / completed;
+    }
+    
+    /// Execuções que produziram resultado (com sucesso ou não)
+    pub fn completed_tasks(&self) -> u64 {
+        self.total_tasks_executed - self.error_count
+    }
+    
+    /// Uso médio de CPU por execução com resultado, em fração (0..1)
+    pub fn resource_utilization(&self) -> f64 {
+        match self.completed_tasks() {
+            0 => 0.0,
+            completed => self.total_resource_usage.cpu_percent / completed as f64 / 100.0,
+        }
+    }
+}
+
+/// Estatísticas de uma camada, atualizadas a cada `execute_task`
+///
+/// Usado pelas três camadas; o tempo de atividade vem do instante de criação.
+#[derive(Debug)]
+pub struct StatsRecorder {
+    statistics: RwLock<LayerStatistics>,
+    started: std::time::Instant,
+}
+
+impl StatsRecorder {
+    pub fn new(layer: ExecutionLayer) -> Self {
+        Self {
+            statistics: RwLock::new(LayerStatistics::new(layer)),
+            started: std::time::Instant::now(),
+        }
+    }
+    
+    /// Registra a conclusão (ou falha) de uma execução
+    pub async fn record(&self, result: &Result<TaskExecutionResult>) {
+        self.statistics.write().await.record(result);
+    }
+    
+    /// Conta uma execução atendida pelo cache da camada
+    pub async fn record_cache_hit(&self) {
+        self.statistics.write().await.cache_hits += 1;
+    }
+    
+    /// Cópia das estatísticas atuais
+    pub async fn snapshot(&self) -> LayerStatistics {
+        let mut statistics = self.statistics.read().await.clone();
+        statistics.uptime_seconds = self.started.elapsed().as_secs();
+        statistics
+    }
 }
 
 // ============================================================================
@@ -177,7 +320,7 @@ pub struct LayerStatistics {
 pub struct LocalLayer {
     config: ExecutionConfig,
     running_tasks: Arc<RwLock<HashMap<TaskId, tokio::task::JoinHandle<()>>>>,
-    statistics: Arc<RwLock<LayerStatistics>>,
+    statistics: StatsRecorder,
 }
 
 impl LocalLayer {
@@ -186,65 +329,174 @@ impl LocalLayer {
         Self {
             config,
             running_tasks: Arc::new(RwLock::new(HashMap::new())),
-            statistics: Arc::new(RwLock::new(LayerStatistics {
-                layer: ExecutionLayer::Local,
-                total_tasks_executed: 0,
-                successful_tasks: 0,
-                failed_tasks: 0,
-                average_execution_time_ms: 0.0,
-                total_resource_usage: ResourceUsage::default(),
-                uptime_seconds: 0,
-            })),
+            statistics: StatsRecorder::new(ExecutionLayer::Local),
         }
     }
     
     /// Executa uma tarefa localmente
-    async fn execute_local_task(&self, task: &TaskNode) -> Result<TaskExecutionResult> {
+    ///
+    /// O processo roda em uma tarefa registrada em `running_tasks` até
+    /// terminar, de modo que `cancel_task` o interrompe (o processo filho
+    /// morre junto) e a vaga é liberada ao final. Tarefas sem `command`
+    /// concluem imediatamente. Timeout e memória seguem `config`; o número de
+    /// tarefas concorrentes, a configuração da camada.
+    async fn execute_local_task(&self, task: &TaskNode, config: &ExecutionConfig) -> Result<TaskExecutionResult> {
         let start_time = Utc::now();
+        let Some(command) = LocalCommand::from_task(task, config)? else {
+            self.ensure_capacity(&*self.running_tasks.read().await)?;
+            let output = serde_json::json!({ "message": "No command configured", "layer": "local" });
+            return Ok(local_result(task, start_time, TaskExecutionStatus::Success, Some(output), None));
+        };
+        if let Some(memory_mb) = command.memory_mb.filter(|&memory_mb| memory_mb > config.resource_limits.max_memory_mb) {
+            return Err(OrchestratorError::ResourceLimitExceeded(format!(
+                "task requests {} MB of memory, local limit is {} MB", memory_mb, config.resource_limits.max_memory_mb
+            )));
+        }
         
-        // Simula execução de tarefa (implementação simplificada)
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        
-        let end_time = Utc::now();
-        let execution_time = (end_time - start_time).num_milliseconds() as u64;
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut running = self.running_tasks.write().await;
+            self.ensure_capacity(&running)?;
+            let mut process = command.process();
+            let timeout = command.timeout;
+            running.insert(task.id, tokio::spawn(async move {
+                let run = async move { process.spawn()?.wait_with_output().await };
+                let _ = sender.send(tokio::time::timeout(timeout, run).await);
+            }));
+        }
+        let outcome = receiver.await;
+        self.running_tasks.write().await.remove(&task.id);
         
-        // Simula uso de recursos
-        let resource_usage = ResourceUsage {
-            cpu_percent: 25.0,
-            memory_mb: 128.0,
-            disk_io_mb: 10.0,
-            network_io_mb: 5.0,
-            execution_time_ms: execution_time,
+        let result = match outcome {
+            Err(_) => local_result(task, start_time, TaskExecutionStatus::Cancelled, None, Some("Task cancelled".to_string())),
+            Ok(Err(_)) => {
+                let message = format!("Command timed out after {}s", command.timeout.as_secs());
+                local_result(task, start_time, TaskExecutionStatus::Timeout, None, Some(message))
+            }
+            Ok(Ok(Err(e))) => {
+                let message = format!("Failed to run command '{}': {}", command.command, e);
+                local_result(task, start_time, TaskExecutionStatus::Failed, None, Some(message))
+            }
+            Ok(Ok(Ok(output))) => {
+                let captured = serde_json::json!({
+                    "stdout": String::from_utf8_lossy(&output.stdout),
+                    "stderr": String::from_utf8_lossy(&output.stderr),
+                    "exit_code": output.status.code(),
+                });
+                if output.status.success() {
+                    local_result(task, start_time, TaskExecutionStatus::Success, Some(captured), None)
+                } else {
+                    let message = match output.status.code() {
+                        Some(code) => format!("Command exited with code {}", code),
+                        None => "Command terminated by signal".to_string(),
+                    };
+                    local_result(task, start_time, TaskExecutionStatus::Failed, Some(captured), Some(message))
+                }
+            }
         };
-        
-        Ok(TaskExecutionResult {
-            task_id: task.id,
-            status: TaskExecutionStatus::Success,
-            start_time,
-            end_time: Some(end_time),
-            output: Some(serde_json::json!({
-                "message": "Task executed successfully",
-                "layer": "local"
-            })),
-            error_message: None,
-            resource_usage,
-            layer: ExecutionLayer::Local,
-        })
+        Ok(result)
     }
-}
-
-#[async_trait]
-impl ExecutionLayerTrait for LocalLayer {
-    async fn execute_task(&self, task: &TaskNode, _config: &ExecutionConfig) -> Result<TaskExecutionResult> {
-        // Verifica limites de tarefas concorrentes
-        let running_count = self.running_tasks.read().await.len();
-        if running_count >= self.config.max_parallel_tasks {
+    
+    /// Verifica limites de tarefas concorrentes
+    fn ensure_capacity(&self, running: &HashMap<TaskId, tokio::task::JoinHandle<()>>) -> Result<()> {
+        if running.len() >= self.config.max_parallel_tasks {
             return Err(OrchestratorError::ResourceLimitExceeded(
                 "Max parallel tasks reached".to_string()
             ));
         }
+        Ok(())
+    }
+}
+
+/// Comando de uma tarefa local, lido da configuração do nó
+///
+/// Com `args`, `command` é o programa; sem, é uma linha de shell.
+#[derive(Debug, Clone)]
+struct LocalCommand {
+    command: String,
+    args: Option<Vec<String>>,
+    env: HashMap<String, String>,
+    /// `timeout_secs` (ou `resources.timeout_seconds`), limitado pelo da camada
+    timeout: Duration,
+    memory_mb: Option<f64>,
+}
+
+impl LocalCommand {
+    /// `None` para tarefas sem `command`
+    fn from_task(task: &TaskNode, config: &ExecutionConfig) -> Result<Option<Self>> {
+        fn field<T: serde::de::DeserializeOwned>(task: &TaskNode, key: &str) -> Result<Option<T>> {
+            Ok(task.configuration.get(key).map(|value| serde_json::from_value(value.clone())).transpose()?)
+        }
         
-        self.execute_local_task(task).await
+        let Some(command) = field::<String>(task, config_keys::COMMAND)? else { return Ok(None) };
+        let resources = field::<ResourceHints>(task, config_keys::RESOURCES)?.unwrap_or_default();
+        let timeout_secs = field::<u64>(task, config_keys::TIMEOUT_SECS)?
+            .or(resources.timeout_seconds)
+            .map_or(config.timeout_seconds, |seconds| seconds.min(config.timeout_seconds));
+        Ok(Some(Self {
+            command,
+            args: field(task, config_keys::ARGS)?,
+            env: field(task, config_keys::ENV)?.unwrap_or_default(),
+            timeout: Duration::from_secs(timeout_secs),
+            memory_mb: resources.memory_mb,
+        }))
+    }
+    
+    /// Processo com saída capturada; morre se a tarefa for abortada
+    fn process(&self) -> tokio::process::Command {
+        let mut process = match &self.args {
+            Some(args) => {
+                let mut process = tokio::process::Command::new(&self.command);
+                process.args(args);
+                process
+            }
+            None => {
+                let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+                let mut process = tokio::process::Command::new(shell);
+                process.args([flag, self.command.as_str()]);
+                process
+            }
+        };
+        process
+            .envs(&self.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        process
+    }
+}
+
+/// Resultado de uma execução local (CPU e memória não são medidos)
+fn local_result(
+    task: &TaskNode,
+    start_time: DateTime<Utc>,
+    status: TaskExecutionStatus,
+    output: Option<serde_json::Value>,
+    error_message: Option<String>,
+) -> TaskExecutionResult {
+    let end_time = Utc::now();
+    TaskExecutionResult {
+        task_id: task.id,
+        status,
+        start_time,
+        end_time: Some(end_time),
+        output,
+        error_message,
+        resource_usage: ResourceUsage {
+            execution_time_ms: (end_time - start_time).num_milliseconds().max(0) as u64,
+            ..ResourceUsage::default()
+        },
+        layer: ExecutionLayer::Local,
+    }
+}
+
+#[async_trait]
+impl ExecutionLayerTrait for LocalLayer {
+    async fn execute_task(&self, task: &TaskNode, config: &ExecutionConfig) -> Result<TaskExecutionResult> {
+        let result = self.execute_local_task(task, config).await;
+        self.statistics.record(&result).await;
+        result
     }
     
     async fn health_check(&self) -> Result<LayerHealth> {
@@ -265,7 +517,7 @@ impl ExecutionLayerTrait for LocalLayer {
     }
     
     async fn get_statistics(&self) -> Result<LayerStatistics> {
-        Ok(self.statistics.read().await.clone())
+        Ok(self.statistics.snapshot().await)
     }
     
     async fn cancel_task(&self, task_id: TaskId) -> Result<()> {
@@ -283,6 +535,10 @@ impl ExecutionLayerTrait for LocalLayer {
     fn layer_type(&self) -> ExecutionLayer {
         ExecutionLayer::Local
     }
+    
+    fn cost_model(&self) -> CostModel {
+        self.config.cost
+    }
 }
 
 // ============================================================================
@@ -295,6 +551,86 @@ pub struct ClusterConfig {
     pub nodes: Vec<ClusterNode>,
     pub load_balancer: LoadBalancerConfig,
     pub fault_tolerance: FaultToleranceConfig,
+    /// Taxas do cluster
+    #[serde(default)]
+    pub cost: CostModel,
+}
+
+/// Caminho, relativo ao endpoint do nó, que recebe as tarefas
+pub const CLUSTER_TASKS_PATH: &str = "/tasks";
+
+/// Caminho com a versão, os protocolos e as capacidades do nó ([`NodeInfo`])
+pub const CLUSTER_INFO_PATH: &str = "/v1/info";
+
+/// Cabeçalho com a versão de protocolo negociada, enviado com cada tarefa
+pub const PROTOCOL_VERSION_HEADER: &str = "x-arkitect-protocol-version";
+
+/// Versões do protocolo de cluster que o orchestrator fala
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[u32] = &[1, 2];
+
+/// Capacidades anunciadas pelos nós
+pub mod capabilities {
+    pub const DOCKER: &str = "docker";
+    pub const WASM: &str = "wasm";
+    pub const GPU: &str = "gpu";
+}
+
+/// Resposta de `GET /v1/info`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeInfo {
+    /// Versão do crate em execução no nó
+    pub version: String,
+    /// Versões de protocolo que o nó aceita
+    pub protocol_versions: Vec<u32>,
+    /// Capacidades de execução (ver [`capabilities`]); nomes desconhecidos são mantidos
+    #[serde(default)]
+    pub capabilities: BTreeSet<String>,
+}
+
+/// Compatibilidade de um nó segundo o último handshake
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeCompatibility {
+    /// Ausente se o nó não respondeu
+    pub info: Option<NodeInfo>,
+    /// Maior versão de protocolo em comum
+    pub protocol_version: Option<u32>,
+    /// Motivo de o nó estar fora da seleção
+    pub reason: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl NodeCompatibility {
+    /// O nó aceita tarefas que exigem `required`
+    fn supports(&self, required: &BTreeSet<String>) -> bool {
+        match (&self.info, self.protocol_version) {
+            (Some(info), Some(_)) => required.is_subset(&info.capabilities),
+            _ => false,
+        }
+    }
+}
+
+/// Maior versão de protocolo falada pelos dois lados
+fn negotiate_protocol(offered: &[u32]) -> Option<u32> {
+    offered.iter().copied().filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version)).max()
+}
+
+/// Capacidades que a tarefa exige do nó (`gpu` também vem das dicas de recursos)
+fn required_capabilities(task: &TaskNode) -> BTreeSet<String> {
+    let mut required: BTreeSet<String> = task.configuration
+        .get(config_keys::REQUIRED_CAPABILITIES)
+        .and_then(|value| serde_json::from_value::<Vec<String>>(value.clone()).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let wants_gpu = task.configuration
+        .get(config_keys::RESOURCES)
+        .and_then(|value| serde_json::from_value::<ResourceHints>(value.clone()).ok())
+        .and_then(|hints| hints.gpu)
+        .unwrap_or(false);
+    if wants_gpu {
+        required.insert(capabilities::GPU.to_string());
+    }
+    required
 }
 
 /// Nó do cluster
@@ -304,6 +640,37 @@ pub struct ClusterNode {
     pub endpoint: String,
     pub capacity: ResourceLimits,
     pub status: NodeStatus,
+    /// TLS/mTLS da conexão com o nó
+    #[serde(default)]
+    pub tls: Option<NodeTlsConfig>,
+    /// Credenciais enviadas em cada requisição
+    #[serde(default)]
+    pub auth: Option<NodeAuth>,
+}
+
+/// TLS da conexão com um nó (arquivos PEM)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeTlsConfig {
+    /// CA que assina o certificado do nó (além das raízes do sistema)
+    pub ca_cert: Option<PathBuf>,
+    /// Certificado do cliente para mTLS
+    pub client_cert: Option<PathBuf>,
+    /// Chave do cliente em PKCS#8
+    pub client_key: Option<PathBuf>,
+    #[serde(default = "default_verify_hostname")]
+    pub verify_hostname: bool,
+}
+
+fn default_verify_hostname() -> bool {
+    true
+}
+
+/// Autenticação de requisições a um nó
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeAuth {
+    Bearer { token: String },
+    Basic { username: String, password: Option<String> },
 }
 
 /// Status de um nó do cluster
@@ -313,6 +680,8 @@ pub enum NodeStatus {
     Inactive,
     Maintenance,
     Failed,
+    /// Fora da seleção por erro que repetir não resolve (ex.: certificado)
+    Degraded,
 }
 
 /// Configuração do load balancer
@@ -343,50 +712,240 @@ pub struct FaultToleranceConfig {
 #[derive(Debug)]
 pub struct ClusterLayer {
     config: ClusterConfig,
-    client: reqwest::Client,
-    statistics: Arc<RwLock<LayerStatistics>>,
+    /// Nós atuais (configurados e adicionados em execução)
+    nodes: RwLock<Vec<ClusterNode>>,
+    /// Cliente HTTP de cada nó, com sua identidade TLS
+    clients: RwLock<HashMap<String, reqwest::Client>>,
+    /// Resultado do handshake de cada nó
+    compatibility: RwLock<HashMap<String, NodeCompatibility>>,
+    statistics: StatsRecorder,
 }
 
 impl ClusterLayer {
     /// Cria nova instância da camada cluster
     pub fn new(config: ClusterConfig) -> Self {
         Self {
+            nodes: RwLock::new(config.nodes.clone()),
+            clients: RwLock::new(HashMap::new()),
+            compatibility: RwLock::new(HashMap::new()),
             config,
-            client: reqwest::Client::new(),
-            statistics: Arc::new(RwLock::new(LayerStatistics {
-                layer: ExecutionLayer::Cluster,
-                total_tasks_executed: 0,
-                successful_tasks: 0,
-                failed_tasks: 0,
-                average_execution_time_ms: 0.0,
-                total_resource_usage: ResourceUsage::default(),
-                uptime_seconds: 0,
-            })),
+            statistics: StatsRecorder::new(ExecutionLayer::Cluster),
         }
     }
     
-    /// Seleciona o melhor nó para execução
-    async fn select_node(&self) -> Result<&ClusterNode> {
-        // Implementação simplificada - seleciona primeiro nó ativo
-        self.config.nodes
-            .iter()
-            .find(|node| node.status == NodeStatus::Active)
-            .ok_or_else(|| OrchestratorError::NoActiveNodes)
+    /// Adiciona (ou substitui, pelo ID) um nó em execução
+    ///
+    /// O cliente do nó é montado antes da inclusão: arquivos TLS inválidos
+    /// recusam o nó em vez de falhar na primeira tarefa.
+    pub async fn add_node(&self, node: ClusterNode) -> Result<()> {
+        let client = build_node_client(&node)?;
+        self.clients.write().await.insert(node.id.clone(), client);
+        self.compatibility.write().await.remove(&node.id);
+        let mut nodes = self.nodes.write().await;
+        nodes.retain(|existing| existing.id != node.id);
+        nodes.push(node);
+        Ok(())
+    }
+    
+    /// Status atual de um nó
+    pub async fn node_status(&self, node_id: &str) -> Option<NodeStatus> {
+        self.nodes.read().await.iter().find(|node| node.id == node_id).map(|node| node.status.clone())
+    }
+    
+    async fn set_node_status(&self, node_id: &str, status: NodeStatus) {
+        if let Some(node) = self.nodes.write().await.iter_mut().find(|node| node.id == node_id) {
+            node.status = status;
+        }
+    }
+    
+    /// Devolve à seleção um nó em `Failed` que voltou a negociar protocolo
+    async fn recover_failed_node(&self, node_id: &str) {
+        let mut nodes = self.nodes.write().await;
+        if let Some(node) = nodes.iter_mut().find(|node| node.id == node_id && node.status == NodeStatus::Failed) {
+            info!("Cluster node {} renegotiated its protocol, marked as active", node_id);
+            node.status = NodeStatus::Active;
+        }
+    }
+    
+    /// Versão, protocolo negociado e capacidades de cada nó já contatado
+    pub async fn node_capabilities(&self) -> HashMap<String, NodeCompatibility> {
+        self.compatibility.read().await.clone()
+    }
+    
+    /// Refaz o handshake dos nós ativos ou falhos cujo resultado expirou
+    ///
+    /// O resultado vale por `health_check_interval` segundos.
+    pub async fn refresh_node_info(&self) -> HashMap<String, NodeCompatibility> {
+        let nodes: Vec<ClusterNode> = self.nodes.read().await.iter()
+            .filter(|node| matches!(node.status, NodeStatus::Active | NodeStatus::Failed))
+            .cloned()
+            .collect();
+        for node in &nodes {
+            // Certificado rejeitado já degradou o nó
+            let _ = self.ensure_handshake(node).await;
+        }
+        self.node_capabilities().await
+    }
+    
+    /// Último handshake do nó, refeito se expirou
+    async fn ensure_handshake(&self, node: &ClusterNode) -> Result<NodeCompatibility> {
+        let max_age = chrono::Duration::seconds(self.config.load_balancer.health_check_interval as i64);
+        if let Some(known) = self.compatibility.read().await.get(&node.id) {
+            if Utc::now() - known.checked_at < max_age {
+                return Ok(known.clone());
+            }
+        }
+        self.handshake(node).await
+    }
+    
+    /// Consulta `GET {endpoint}/v1/info` e negocia a versão de protocolo
+    ///
+    /// Sem versão em comum o nó vai para `Failed`, e volta a `Active` quando
+    /// um handshake posterior negocia uma versão; nó inacessível só fica
+    /// fora da seleção até o próximo handshake. Mudanças de compatibilidade
+    /// são registradas no log, o que forma a matriz na inicialização. Só
+    /// certificados rejeitados retornam erro.
+    async fn handshake(&self, node: &ClusterNode) -> Result<NodeCompatibility> {
+        let info = match self.fetch_info(node).await {
+            Ok(info) => info,
+            Err(e) if e.is_certificate_error() => return Err(e),
+            Err(e) => {
+                return Ok(self.record_compatibility(node, NodeCompatibility {
+                    info: None,
+                    protocol_version: None,
+                    reason: Some(format!("unreachable: {}", e)),
+                    checked_at: Utc::now(),
+                }).await);
+            }
+        };
+        let protocol_version = negotiate_protocol(&info.protocol_versions);
+        let reason = protocol_version.is_none().then(|| format!(
+            "incompatible protocol: node v{} speaks {:?}, orchestrator v{} speaks {:?}",
+            info.version, info.protocol_versions, env!("CARGO_PKG_VERSION"), SUPPORTED_PROTOCOL_VERSIONS,
+        ));
+        match &reason {
+            Some(reason) => {
+                warn!("Cluster node {} marked as failed: {}", node.id, reason);
+                self.set_node_status(&node.id, NodeStatus::Failed).await;
+            }
+            None => self.recover_failed_node(&node.id).await,
+        }
+        Ok(self.record_compatibility(node, NodeCompatibility {
+            info: Some(info),
+            protocol_version,
+            reason,
+            checked_at: Utc::now(),
+        }).await)
+    }
+    
+    async fn fetch_info(&self, node: &ClusterNode) -> Result<NodeInfo> {
+        let client = self.client_for(node).await?;
+        let url = format!("{}{}", node.endpoint.trim_end_matches('/'), CLUSTER_INFO_PATH);
+        Ok(self.send(node, &url, client.get(&url)).await?.json().await?)
+    }
+    
+    async fn record_compatibility(&self, node: &ClusterNode, compatibility: NodeCompatibility) -> NodeCompatibility {
+        let previous = self.compatibility.write().await.insert(node.id.clone(), compatibility.clone());
+        let changed = previous.map_or(true, |previous| {
+            (previous.info, previous.protocol_version, previous.reason)
+                != (compatibility.info.clone(), compatibility.protocol_version, compatibility.reason.clone())
+        });
+        if changed {
+            match (&compatibility.info, compatibility.protocol_version) {
+                (Some(info), Some(protocol)) => info!(
+                    "Cluster node {}: v{}, protocol {}, capabilities {:?}",
+                    node.id, info.version, protocol, info.capabilities
+                ),
+                _ => warn!(
+                    "Cluster node {}: {}",
+                    node.id, compatibility.reason.as_deref().unwrap_or("incompatible")
+                ),
+            }
+        }
+        compatibility
+    }
+    
+    /// Seleciona o primeiro nó ativo compatível com a tarefa
+    ///
+    /// Retorna o nó e a versão de protocolo negociada. Nós ativos que não
+    /// têm as capacidades exigidas geram `UnsupportedOperation`.
+    async fn select_node(&self, task: &TaskNode) -> Result<(ClusterNode, u32)> {
+        let required = required_capabilities(task);
+        let active: Vec<ClusterNode> = self.nodes.read().await.iter()
+            .filter(|node| node.status == NodeStatus::Active)
+            .cloned()
+            .collect();
+        let mut compatible = 0;
+        for node in active {
+            let compatibility = self.ensure_handshake(&node).await?;
+            let Some(protocol_version) = compatibility.protocol_version else { continue };
+            compatible += 1;
+            if compatibility.supports(&required) {
+                return Ok((node, protocol_version));
+            }
+        }
+        if compatible == 0 {
+            return Err(OrchestratorError::NoActiveNodes);
+        }
+        Err(OrchestratorError::UnsupportedOperation(format!(
+            "no active cluster node offers capabilities {:?}", required
+        )))
+    }
+    
+    /// Cliente do nó, montado na primeira requisição e reaproveitado
+    async fn client_for(&self, node: &ClusterNode) -> Result<reqwest::Client> {
+        if let Some(client) = self.clients.read().await.get(&node.id) {
+            return Ok(client.clone());
+        }
+        let client = build_node_client(node)?;
+        self.clients.write().await.insert(node.id.clone(), client.clone());
+        Ok(client)
+    }
+    
+    /// Envia uma requisição autenticada ao nó
+    ///
+    /// Certificado rejeitado marca o nó como `Degraded`: ele sai da seleção
+    /// até ser substituído via `add_node`.
+    async fn send(&self, node: &ClusterNode, url: &str, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        match authorize(request, node).send().await.and_then(|response| response.error_for_status()) {
+            Ok(response) => Ok(response),
+            Err(e) if is_certificate_error(&e) => {
+                warn!("Cluster node {} rejected by TLS: {}", node.id, e);
+                self.set_node_status(&node.id, NodeStatus::Degraded).await;
+                Err(OrchestratorError::certificate(&format!("cluster node {}", node.id), url, error_chain(&e)))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    /// Envia a tarefa ao nó (`POST {endpoint}/tasks`) na versão negociada
+    async fn send_to_node(
+        &self,
+        node: &ClusterNode,
+        protocol_version: u32,
+        payload: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let client = self.client_for(node).await?;
+        let url = format!("{}{}", node.endpoint.trim_end_matches('/'), CLUSTER_TASKS_PATH);
+        let request = client.post(&url).json(payload).header(PROTOCOL_VERSION_HEADER, protocol_version.to_string());
+        Ok(self.send(node, &url, request).await?.json().await?)
     }
     
     /// Executa tarefa em nó do cluster
-    async fn execute_cluster_task(&self, task: &TaskNode, node: &ClusterNode) -> Result<TaskExecutionResult> {
+    async fn execute_cluster_task(
+        &self,
+        task: &TaskNode,
+        node: &ClusterNode,
+        protocol_version: u32,
+    ) -> Result<TaskExecutionResult> {
         let start_time = Utc::now();
         
-        // Simula envio da tarefa para o nó
         let payload = serde_json::json!({
             "task_id": task.id,
             "name": task.name,
             "configuration": task.configuration
         });
-        
-        // TODO: Implementar comunicação real com o cluster
-        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+        let response = self.send_to_node(node, protocol_version, &payload).await?;
         
         let end_time = Utc::now();
         let execution_time = (end_time - start_time).num_milliseconds() as u64;
@@ -399,7 +958,8 @@ impl ClusterLayer {
             output: Some(serde_json::json!({
                 "message": "Task executed on cluster",
                 "node_id": node.id,
-                "layer": "cluster"
+                "layer": "cluster",
+                "response": response
             })),
             error_message: None,
             resource_usage: ResourceUsage {
@@ -417,26 +977,32 @@ impl ClusterLayer {
 #[async_trait]
 impl ExecutionLayerTrait for ClusterLayer {
     async fn execute_task(&self, task: &TaskNode, _config: &ExecutionConfig) -> Result<TaskExecutionResult> {
-        let node = self.select_node().await?;
-        self.execute_cluster_task(task, node).await
+        let result = match self.select_node(task).await {
+            Ok((node, protocol_version)) => self.execute_cluster_task(task, &node, protocol_version).await,
+            Err(e) => Err(e),
+        };
+        self.statistics.record(&result).await;
+        result
     }
     
     async fn health_check(&self) -> Result<LayerHealth> {
-        let active_nodes = self.config.nodes
-            .iter()
-            .filter(|node| node.status == NodeStatus::Active)
-            .count();
+        self.refresh_node_info().await;
+        let nodes = self.nodes.read().await;
+        let active_nodes = nodes.iter().filter(|node| node.status == NodeStatus::Active).count();
+        let degraded_nodes = nodes.iter().filter(|node| node.status == NodeStatus::Degraded).count();
             
-        let status = if active_nodes > 0 {
-            HealthStatus::Healthy
-        } else {
+        let status = if active_nodes == 0 {
             HealthStatus::Unhealthy
+        } else if degraded_nodes > 0 {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
         };
         
         Ok(LayerHealth {
             layer: ExecutionLayer::Cluster,
             status,
-            message: format!("Cluster has {} active nodes", active_nodes),
+            message: format!("Cluster has {} active nodes ({} degraded)", active_nodes, degraded_nodes),
             available_resources: ResourceUsage {
                 cpu_percent: 10.0,
                 memory_mb: 4096.0,
@@ -450,7 +1016,7 @@ impl ExecutionLayerTrait for ClusterLayer {
     }
     
     async fn get_statistics(&self) -> Result<LayerStatistics> {
-        Ok(self.statistics.read().await.clone())
+        Ok(self.statistics.snapshot().await)
     }
     
     async fn cancel_task(&self, _task_id: TaskId) -> Result<()> {
@@ -466,6 +1032,73 @@ impl ExecutionLayerTrait for ClusterLayer {
     fn layer_type(&self) -> ExecutionLayer {
         ExecutionLayer::Cluster
     }
+    
+    fn cost_model(&self) -> CostModel {
+        self.config.cost
+    }
+}
+
+/// Monta o cliente HTTP de um nó com sua CA e identidade de cliente
+fn build_node_client(node: &ClusterNode) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(tls) = &node.tls {
+        let read = |path: &PathBuf| {
+            std::fs::read(path).map_err(|e| {
+                OrchestratorError::ConfigurationError(format!(
+                    "Cluster node {}: cannot read {}: {}", node.id, path.display(), e
+                ))
+            })
+        };
+        let invalid = |what: &str, e: reqwest::Error| {
+            OrchestratorError::ConfigurationError(format!("Cluster node {}: invalid {}: {}", node.id, what, e))
+        };
+        if let Some(ca) = &tls.ca_cert {
+            let certificate = reqwest::Certificate::from_pem(&read(ca)?).map_err(|e| invalid("CA certificate", e))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        match (&tls.client_cert, &tls.client_key) {
+            (Some(cert), Some(key)) => {
+                let identity = reqwest::Identity::from_pkcs8_pem(&read(cert)?, &read(key)?)
+                    .map_err(|e| invalid("client identity", e))?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => {
+                return Err(OrchestratorError::ConfigurationError(format!(
+                    "Cluster node {}: client_cert and client_key must be set together", node.id
+                )));
+            }
+        }
+        builder = builder.danger_accept_invalid_hostnames(!tls.verify_hostname);
+    }
+    builder.build().map_err(|e| {
+        OrchestratorError::ConfigurationError(format!("Cluster node {}: cannot build client: {}", node.id, e))
+    })
+}
+
+/// Acrescenta as credenciais do nó à requisição
+fn authorize(request: reqwest::RequestBuilder, node: &ClusterNode) -> reqwest::RequestBuilder {
+    match &node.auth {
+        Some(NodeAuth::Bearer { token }) => request.bearer_auth(token),
+        Some(NodeAuth::Basic { username, password }) => request.basic_auth(username, password.as_ref()),
+        None => request,
+    }
+}
+
+/// Mensagens do erro e de todas as suas causas
+fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut messages = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        messages.push(cause.to_string());
+        source = cause.source();
+    }
+    messages.join(": ")
+}
+
+/// O handshake falhou por causa de um certificado
+fn is_certificate_error(error: &reqwest::Error) -> bool {
+    error.is_connect() && error_chain(error).to_lowercase().contains("certificate")
 }
 
 // ============================================================================
@@ -479,6 +1112,22 @@ pub struct QuantumSimConfig {
     pub gates: Vec<QuantumGate>,
     pub noise_model: NoiseModel,
     pub backend: QuantumBackend,
+    /// Taxas do simulador/backend quântico
+    #[serde(default)]
+    pub cost: CostModel,
+    /// Semente da amostragem; tarefas podem sobrescrever com `quantum_seed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Validade dos resultados em cache, em segundos (0 desativa o cache)
+    #[serde(default = "default_cache_ttl_seconds")]
+    pub cache_ttl_seconds: u64,
+    /// Threads da simulação (padrão: pool global do rayon)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_threads: Option<usize>,
+}
+
+fn default_cache_ttl_seconds() -> u64 {
+    3600
 }
 
 /// Porta quântica
@@ -516,57 +1165,140 @@ pub struct QuantumSimulationResult {
     pub qubits_used: usize,
     pub gate_count: usize,
     pub circuit_depth: usize,
+    /// Bits do resultado mais frequente (um por qubit)
     pub measurement_results: Vec<u8>,
     pub fidelity: f64,
+    /// Tempo de parede da simulação original, mesmo quando vinda do cache
     pub execution_time_ns: u64,
+    /// Threads usadas pela simulação original
+    #[serde(default)]
+    pub threads: usize,
+    /// Contagem por string de bits (o caractere `q` é o qubit `q`)
+    #[serde(default)]
+    pub counts: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub shots: u32,
+    /// Semente usada na amostragem (sorteada quando não configurada)
+    #[serde(default)]
+    pub seed: u64,
+    /// Resultado reaproveitado do cache
+    #[serde(default)]
+    pub cached: bool,
 }
 
 /// Executor de simulação quântica
 #[derive(Debug)]
 pub struct QuantumSimLayer {
     config: QuantumSimConfig,
-    statistics: Arc<RwLock<LayerStatistics>>,
+    statistics: StatsRecorder,
+    cache: SimulationCache,
+    /// Pool próprio quando `max_threads` está configurado
+    pool: Option<Arc<rayon::ThreadPool>>,
 }
 
 impl QuantumSimLayer {
     /// Cria nova instância da camada quantum
     pub fn new(config: QuantumSimConfig) -> Self {
+        Self::with_clock(config, Arc::new(SystemClock))
+    }
+    
+    /// Camada cujo cache expira pelo relógio informado
+    pub fn with_clock(config: QuantumSimConfig, clock: Arc<dyn Clock>) -> Self {
+        let pool = config.max_threads.and_then(|threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|index| format!("quantum-sim-{}", index))
+                .build()
+                .map_err(|e| warn!("Quantum simulator falling back to the global thread pool: {}", e))
+                .ok()
+                .map(Arc::new)
+        });
         Self {
+            cache: SimulationCache::new(std::time::Duration::from_secs(config.cache_ttl_seconds), clock),
+            pool,
             config,
-            statistics: Arc::new(RwLock::new(LayerStatistics {
-                layer: ExecutionLayer::QuantumSim,
-                total_tasks_executed: 0,
-                successful_tasks: 0,
-                failed_tasks: 0,
-                average_execution_time_ms: 0.0,
-                total_resource_usage: ResourceUsage::default(),
-                uptime_seconds: 0,
-            })),
+            statistics: StatsRecorder::new(ExecutionLayer::QuantumSim),
         }
     }
     
     /// Executa simulação quântica
+    ///
+    /// Execuções com semente (da tarefa ou da camada) consultam o cache antes
+    /// de simular; sem semente, uma é sorteada e o resultado não é guardado.
     async fn execute_quantum_simulation(&self, task: &TaskNode) -> Result<QuantumSimulationResult> {
-        // Implementação simplificada de simulação quântica
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        
-        Ok(QuantumSimulationResult {
-            qubits_used: self.config.qubits,
-            gate_count: 100,
-            circuit_depth: 10,
-            measurement_results: vec![0, 1, 0, 1],
-            fidelity: 0.95,
-            execution_time_ns: 1_000_000, // 1ms em nanosegundos
+        let circuit: QuantumCircuit = task.configuration
+            .get(config_keys::QUANTUM_CIRCUIT)
+            .map(|value| serde_json::from_value(value.clone()))
+            .transpose()?
+            .ok_or_else(|| OrchestratorError::validation(
+                "configuration.quantum_circuit", "required", "", "quantum task has no circuit",
+            ))?;
+        if circuit.qubits > self.config.qubits {
+            return Err(OrchestratorError::ResourceLimitExceeded(format!(
+                "circuit needs {} qubits, simulator has {}", circuit.qubits, self.config.qubits
+            )));
+        }
+        let task_seed = task.configuration
+            .get(config_keys::QUANTUM_SEED)
+            .map(|value| serde_json::from_value::<u64>(value.clone()))
+            .transpose()?;
+        
+        let key = match task_seed.or(self.config.seed) {
+            Some(seed) => Some(SimulationKey::new(&circuit, &self.config.noise_model, seed)?),
+            None => None,
+        };
+        if let Some(key) = &key {
+            if let Some(hit) = self.cache.get(key).await {
+                return Ok(hit);
+            }
+        }
+        
+        let seed = key.map(|key| key.seed).unwrap_or_else(|| fastrand::u64(..));
+        let noise = self.config.noise_model.clone();
+        let shots = circuit.shots;
+        let qubits = circuit.qubits;
+        let pool = self.pool.clone();
+        let started = std::time::Instant::now();
+        let simulate = move || quantum::simulate(&circuit, &noise, seed);
+        let simulation = tokio::task::spawn_blocking(move || match pool {
+            Some(pool) => pool.install(simulate),
+            None => simulate(),
         })
+        .await
+        .map_err(|e| OrchestratorError::InternalError(format!("Quantum simulation aborted: {}", e)))??;
+        
+        let most_frequent = simulation.counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(bits, _)| bits.bytes().map(|bit| bit - b'0').collect())
+            .unwrap_or_default();
+        let result = QuantumSimulationResult {
+            qubits_used: qubits,
+            gate_count: simulation.gate_count,
+            circuit_depth: simulation.circuit_depth,
+            measurement_results: most_frequent,
+            fidelity: simulation.fidelity,
+            execution_time_ns: started.elapsed().as_nanos() as u64,
+            threads: simulation.threads,
+            counts: simulation.counts,
+            shots,
+            seed,
+            cached: false,
+        };
+        if let Some(key) = key {
+            self.cache.insert(key, result.clone()).await;
+        }
+        Ok(result)
     }
-}
-
-#[async_trait]
-impl ExecutionLayerTrait for QuantumSimLayer {
-    async fn execute_task(&self, task: &TaskNode, _config: &ExecutionConfig) -> Result<TaskExecutionResult> {
+    
+    /// Executa uma tarefa como simulação quântica
+    async fn execute_quantum_task(&self, task: &TaskNode) -> Result<TaskExecutionResult> {
         let start_time = Utc::now();
         
         let sim_result = self.execute_quantum_simulation(task).await?;
+        if sim_result.cached {
+            self.statistics.record_cache_hit().await;
+        }
         
         let end_time = Utc::now();
         let execution_time = (end_time - start_time).num_milliseconds() as u64;
@@ -588,6 +1320,15 @@ impl ExecutionLayerTrait for QuantumSimLayer {
             layer: ExecutionLayer::QuantumSim,
         })
     }
+}
+
+#[async_trait]
+impl ExecutionLayerTrait for QuantumSimLayer {
+    async fn execute_task(&self, task: &TaskNode, _config: &ExecutionConfig) -> Result<TaskExecutionResult> {
+        let result = self.execute_quantum_task(task).await;
+        self.statistics.record(&result).await;
+        result
+    }
     
     async fn health_check(&self) -> Result<LayerHealth> {
         Ok(LayerHealth {
@@ -607,7 +1348,7 @@ impl ExecutionLayerTrait for QuantumSimLayer {
     }
     
     async fn get_statistics(&self) -> Result<LayerStatistics> {
-        Ok(self.statistics.read().await.clone())
+        Ok(self.statistics.snapshot().await)
     }
     
     async fn cancel_task(&self, _task_id: TaskId) -> Result<()> {
@@ -623,6 +1364,10 @@ impl ExecutionLayerTrait for QuantumSimLayer {
     fn layer_type(&self) -> ExecutionLayer {
         ExecutionLayer::QuantumSim
     }
+    
+    fn cost_model(&self) -> CostModel {
+        self.config.cost
+    }
 }
 
 /// Gerenciador de camadas de execução
@@ -708,6 +1453,101 @@ mod tests {
         assert_eq!(health_result.status, HealthStatus::Healthy);
     }
     
+    #[tokio::test]
+    async fn test_local_statistics_match_executions() {
+        let config = ExecutionConfig { max_parallel_tasks: 1, ..ExecutionConfig::default() };
+        let local_layer = LocalLayer::new(config.clone());
+        
+        let mut results = Vec::new();
+        for index in 0..10 {
+            let task = TaskNode::new(format!("task-{}", index), None);
+            if index % 3 == 2 {
+                // Execução simulada ocupa a única vaga: a tarefa é recusada
+                let occupant = tokio::spawn(std::future::pending::<()>());
+                local_layer.running_tasks.write().await.insert(uuid::Uuid::new_v4(), occupant);
+                assert!(local_layer.execute_task(&task, &config).await.is_err());
+                for (_, handle) in local_layer.running_tasks.write().await.drain() {
+                    handle.abort();
+                }
+            } else {
+                results.push(local_layer.execute_task(&task, &config).await.unwrap());
+            }
+        }
+        let times: Vec<u64> = results.iter().map(|result| result.resource_usage.execution_time_ms).collect();
+        
+        let statistics = local_layer.get_statistics().await.unwrap();
+        assert_eq!(statistics.total_tasks_executed, 10);
+        assert_eq!((statistics.successful_tasks, statistics.failed_tasks, statistics.error_count), (7, 3, 3));
+        assert_eq!(statistics.total_resource_usage.execution_time_ms, times.iter().sum::<u64>());
+        let mean = times.iter().sum::<u64>() as f64 / times.len() as f64;
+        assert!((statistics.average_execution_time_ms - mean).abs() < 1e-9);
+        let cpu: f64 = results.iter().map(|result| result.resource_usage.cpu_percent).sum();
+        assert!((statistics.resource_utilization() - cpu / 7.0 / 100.0).abs() < 1e-9);
+    }
+    
+    fn command_task(command: &str, extra: &[(&str, serde_json::Value)]) -> TaskNode {
+        let mut task = TaskNode::new(command.to_string(), None);
+        task.configuration.insert(config_keys::COMMAND.to_string(), serde_json::json!(command));
+        for (key, value) in extra {
+            task.configuration.insert(key.to_string(), value.clone());
+        }
+        task
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_local_commands_report_output_and_exit_status() {
+        let config = ExecutionConfig::default();
+        let local_layer = LocalLayer::new(config.clone());
+        
+        let echo = command_task("echo \"$GREETING\"", &[(config_keys::ENV, serde_json::json!({ "GREETING": "hello" }))]);
+        let result = local_layer.execute_task(&echo, &config).await.unwrap();
+        assert_eq!(result.status, TaskExecutionStatus::Success);
+        assert_eq!(result.output.unwrap()["stdout"], "hello\n");
+        
+        let failing = command_task("echo oops >&2; exit 3", &[]);
+        let result = local_layer.execute_task(&failing, &config).await.unwrap();
+        assert_eq!(result.status, TaskExecutionStatus::Failed);
+        assert_eq!(result.error_message.as_deref(), Some("Command exited with code 3"));
+        let output = result.output.unwrap();
+        assert_eq!((output["exit_code"].clone(), output["stderr"].clone()), (serde_json::json!(3), serde_json::json!("oops\n")));
+        
+        let greedy = command_task("true", &[(config_keys::RESOURCES, serde_json::json!({ "memory_mb": 4096.0 }))]);
+        assert!(matches!(
+            local_layer.execute_task(&greedy, &config).await,
+            Err(OrchestratorError::ResourceLimitExceeded(_))
+        ));
+        assert!(local_layer.list_running_tasks().await.unwrap().is_empty());
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_local_timeout_and_cancellation_stop_the_command() {
+        let config = ExecutionConfig { max_parallel_tasks: 1, ..ExecutionConfig::default() };
+        let local_layer = Arc::new(LocalLayer::new(config.clone()));
+        
+        let slow = command_task("sleep 30", &[(config_keys::TIMEOUT_SECS, serde_json::json!(1))]);
+        let result = local_layer.execute_task(&slow, &config).await.unwrap();
+        assert_eq!(result.status, TaskExecutionStatus::Timeout);
+        assert!(result.resource_usage.execution_time_ms < 5_000);
+        
+        let task = command_task("sleep 30", &[]);
+        let running = tokio::spawn({
+            let (local_layer, task, config) = (local_layer.clone(), task.clone(), config.clone());
+            async move { local_layer.execute_task(&task, &config).await }
+        });
+        while local_layer.list_running_tasks().await.unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        local_layer.cancel_task(task.id).await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), running).await.unwrap().unwrap().unwrap();
+        assert_eq!(result.status, TaskExecutionStatus::Cancelled);
+        
+        // A vaga foi liberada: a próxima tarefa não é recusada
+        let result = local_layer.execute_task(&command_task("true", &[]), &config).await.unwrap();
+        assert_eq!(result.status, TaskExecutionStatus::Success);
+    }
+    
     #[test]
     fn test_layer_manager() {
         let mut manager = LayerManager::new();
@@ -722,5 +1562,279 @@ mod tests {
         let layer = manager.get_layer(&ExecutionLayer::Local);
         assert!(layer.is_some());
     }
-}
 
+    
+    /// CA de teste com certificado de servidor (localhost) e de cliente
+    struct TestPki {
+        ca_der: Vec<u8>,
+        ca_pem: String,
+        server_cert_der: Vec<u8>,
+        server_key_der: Vec<u8>,
+        client_cert_pem: String,
+        client_key_pem: String,
+    }
+    
+    fn test_pki() -> TestPki {
+        use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa};
+        
+        let mut ca_params = CertificateParams::new(vec![]);
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = Certificate::from_params(ca_params).unwrap();
+        let server = Certificate::from_params(CertificateParams::new(vec!["localhost".to_string()])).unwrap();
+        let client = Certificate::from_params(CertificateParams::new(vec!["cluster-client".to_string()])).unwrap();
+        TestPki {
+            ca_der: ca.serialize_der().unwrap(),
+            ca_pem: ca.serialize_pem().unwrap(),
+            server_cert_der: server.serialize_der_with_signer(&ca).unwrap(),
+            server_key_der: server.serialize_private_key_der(),
+            client_cert_pem: client.serialize_pem_with_signer(&ca).unwrap(),
+            client_key_pem: client.serialize_private_key_pem(),
+        }
+    }
+    
+    /// Nó HTTPS que exige certificado de cliente assinado pela CA de `client_ca`
+    ///
+    /// Responde com o `task_id` recebido e o cabeçalho `Authorization`.
+    async fn spawn_node_server(server: &TestPki, client_ca: &TestPki) -> u16 {
+        use axum::{http::HeaderMap, routing::{get, post}, Json, Router};
+        
+        let mut client_roots = rustls::RootCertStore::empty();
+        client_roots.add(&rustls::Certificate(client_ca.ca_der.clone())).unwrap();
+        let tls = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(client_roots).boxed())
+            .with_single_cert(
+                vec![rustls::Certificate(server.server_cert_der.clone())],
+                rustls::PrivateKey(server.server_key_der.clone()),
+            )
+            .unwrap();
+        let app = Router::new()
+            .route(CLUSTER_INFO_PATH, get(|| async { Json(node_info(&[1], &[])) }))
+            .route(
+                CLUSTER_TASKS_PATH,
+                post(|headers: HeaderMap, Json(body): Json<serde_json::Value>| async move {
+                    Json(serde_json::json!({
+                        "task_id": body["task_id"],
+                        "authorization": headers.get("authorization").and_then(|value| value.to_str().ok()),
+                    }))
+                }),
+            );
+        
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let config = axum_server::tls_rustls::RustlsConfig::from_config(Arc::new(tls));
+        tokio::spawn(axum_server::from_tcp_rustls(listener, config).serve(app.into_make_service()));
+        port
+    }
+    
+    /// Nó com mTLS e bearer token, confiando na CA de `trusted`
+    fn tls_node(dir: &std::path::Path, port: u16, trusted: &TestPki) -> ClusterNode {
+        let write = |name: &str, content: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        ClusterNode {
+            id: "node-a".to_string(),
+            endpoint: format!("https://localhost:{}", port),
+            capacity: ExecutionConfig::default().resource_limits,
+            status: NodeStatus::Active,
+            tls: Some(NodeTlsConfig {
+                ca_cert: Some(write("ca.pem", &trusted.ca_pem)),
+                client_cert: Some(write("client.pem", &trusted.client_cert_pem)),
+                client_key: Some(write("client.key", &trusted.client_key_pem)),
+                verify_hostname: true,
+            }),
+            auth: Some(NodeAuth::Bearer { token: "s3cret".to_string() }),
+        }
+    }
+    
+    fn node_info(protocol_versions: &[u32], capabilities: &[&str]) -> NodeInfo {
+        NodeInfo {
+            version: "0.1.0".to_string(),
+            protocol_versions: protocol_versions.to_vec(),
+            capabilities: capabilities.iter().map(|capability| capability.to_string()).collect(),
+        }
+    }
+    
+    /// Nó HTTP que anuncia `info` e ecoa a versão de protocolo recebida
+    async fn spawn_info_node(id: &str, info: NodeInfo) -> ClusterNode {
+        use axum::{http::HeaderMap, routing::{get, post}, Json, Router};
+        
+        let app = Router::new()
+            .route(CLUSTER_INFO_PATH, get(move || async move { Json(info) }))
+            .route(
+                CLUSTER_TASKS_PATH,
+                post(|headers: HeaderMap| async move {
+                    Json(serde_json::json!({
+                        "protocol": headers.get(PROTOCOL_VERSION_HEADER).and_then(|value| value.to_str().ok()),
+                    }))
+                }),
+            );
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+        ClusterNode {
+            id: id.to_string(),
+            endpoint,
+            capacity: ExecutionConfig::default().resource_limits,
+            status: NodeStatus::Active,
+            tls: None,
+            auth: None,
+        }
+    }
+    
+    fn empty_cluster() -> ClusterLayer {
+        ClusterLayer::new(ClusterConfig {
+            nodes: vec![],
+            load_balancer: LoadBalancerConfig { strategy: LoadBalancingStrategy::RoundRobin, health_check_interval: 30 },
+            fault_tolerance: FaultToleranceConfig { max_retries: 3, retry_delay_ms: 100, failover_enabled: true },
+            cost: CostModel::default(),
+        })
+    }
+    
+    #[tokio::test]
+    async fn test_cluster_mtls_round_trip_with_hot_added_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let pki = test_pki();
+        let port = spawn_node_server(&pki, &pki).await;
+        
+        let cluster = empty_cluster();
+        cluster.add_node(tls_node(dir.path(), port, &pki)).await.unwrap();
+        
+        let task = TaskNode::new("remote".to_string(), None);
+        let result = cluster.execute_task(&task, &ExecutionConfig::default()).await.unwrap();
+        let response = &result.output.unwrap()["response"];
+        assert_eq!(response["task_id"], serde_json::json!(task.id));
+        assert_eq!(response["authorization"], "Bearer s3cret");
+        assert_eq!(cluster.node_status("node-a").await, Some(NodeStatus::Active));
+    }
+    
+    #[tokio::test]
+    async fn test_handshake_routes_by_capability_and_fails_incompatible_protocol() {
+        let cluster = empty_cluster();
+        cluster.add_node(spawn_info_node("legacy", node_info(&[0], &["docker", "gpu"])).await).await.unwrap();
+        cluster.add_node(spawn_info_node("docker", node_info(&[1], &["docker"])).await).await.unwrap();
+        cluster.add_node(spawn_info_node("gpu", node_info(&[1, 2, 3], &["gpu", "wasm"])).await).await.unwrap();
+        
+        let run = |capabilities: &[&str]| {
+            let task = TaskNode::builder("remote").required_capabilities(capabilities.to_vec()).build().unwrap();
+            let cluster = &cluster;
+            async move { cluster.execute_task(&task, &ExecutionConfig::default()).await }
+        };
+        let output = run(&["gpu"]).await.unwrap().output.unwrap();
+        assert_eq!((output["node_id"].clone(), output["response"]["protocol"].clone()), (serde_json::json!("gpu"), serde_json::json!("2")));
+        let output = run(&["docker"]).await.unwrap().output.unwrap();
+        assert_eq!((output["node_id"].clone(), output["response"]["protocol"].clone()), (serde_json::json!("docker"), serde_json::json!("1")));
+        assert!(matches!(run(&["fpga"]).await, Err(OrchestratorError::UnsupportedOperation(_))));
+        
+        // Sem protocolo em comum: o nó sai da seleção com o motivo registrado
+        assert_eq!(cluster.node_status("legacy").await, Some(NodeStatus::Failed));
+        let matrix = cluster.node_capabilities().await;
+        let legacy = &matrix["legacy"];
+        assert_eq!(legacy.protocol_version, None);
+        assert!(legacy.reason.as_deref().unwrap().contains("incompatible protocol"), "{:?}", legacy.reason);
+        assert_eq!(matrix["gpu"].protocol_version, Some(2));
+        assert_eq!(matrix["docker"].info.as_ref().unwrap().capabilities, BTreeSet::from(["docker".to_string()]));
+    }
+    
+    #[tokio::test]
+    async fn test_successful_handshake_recovers_failed_node() {
+        let cluster = empty_cluster();
+        let mut node = spawn_info_node("upgraded", node_info(&[1], &["docker"])).await;
+        node.status = NodeStatus::Failed;
+        cluster.add_node(node).await.unwrap();
+        
+        let matrix = cluster.refresh_node_info().await;
+        assert_eq!(matrix["upgraded"].protocol_version, Some(1));
+        assert_eq!(cluster.node_status("upgraded").await, Some(NodeStatus::Active));
+    }
+    
+    #[tokio::test]
+    async fn test_untrusted_server_cert_degrades_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server_pki, client_pki) = (test_pki(), test_pki());
+        let port = spawn_node_server(&server_pki, &client_pki).await;
+        
+        let cluster = empty_cluster();
+        cluster.add_node(tls_node(dir.path(), port, &client_pki)).await.unwrap();
+        
+        let task = TaskNode::new("remote".to_string(), None);
+        let error = cluster.execute_task(&task, &ExecutionConfig::default()).await.unwrap_err();
+        assert!(error.is_certificate_error(), "{}", error);
+        assert!(!error.is_recoverable());
+        assert_eq!(cluster.node_status("node-a").await, Some(NodeStatus::Degraded));
+        assert!(matches!(
+            cluster.execute_task(&task, &ExecutionConfig::default()).await,
+            Err(OrchestratorError::NoActiveNodes)
+        ));
+    }
+    
+    fn quantum_layer(seed: Option<u64>) -> QuantumSimLayer {
+        QuantumSimLayer::new(QuantumSimConfig {
+            qubits: 4,
+            gates: vec![],
+            noise_model: NoiseModel { gate_error_rate: 0.0, measurement_error_rate: 0.0, decoherence_time_ns: 0.0 },
+            backend: QuantumBackend::Simulator,
+            cost: CostModel::default(),
+            seed,
+            cache_ttl_seconds: 60,
+            max_threads: None,
+        })
+    }
+    
+    fn bell_task(shots: u32) -> TaskNode {
+        let circuit = QuantumCircuit { shots, ..QuantumCircuit::new(2).with_gate("h 0").with_gate("cx 0 1") };
+        let mut task = TaskNode::new("bell".to_string(), None);
+        task.configuration.insert(config_keys::QUANTUM_CIRCUIT.to_string(), serde_json::to_value(circuit).unwrap());
+        task
+    }
+    
+    async fn simulate(layer: &QuantumSimLayer, task: &TaskNode) -> (QuantumSimulationResult, std::time::Duration) {
+        let started = std::time::Instant::now();
+        let result = layer.execute_task(task, &ExecutionConfig::default()).await.unwrap();
+        let elapsed = started.elapsed();
+        (serde_json::from_value(result.output.unwrap()).unwrap(), elapsed)
+    }
+    
+    #[tokio::test]
+    async fn test_seeded_bell_circuit_is_served_from_cache() {
+        let layer = quantum_layer(Some(42));
+        let task = bell_task(500_000);
+        
+        let (first, first_time) = simulate(&layer, &task).await;
+        let (second, second_time) = simulate(&layer, &task).await;
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(first.counts, second.counts);
+        assert_eq!(first.counts.keys().collect::<Vec<_>>(), vec!["00", "11"]);
+        assert!(second_time * 10 < first_time, "{:?} vs {:?}", second_time, first_time);
+        
+        let statistics = layer.get_statistics().await.unwrap();
+        assert_eq!((statistics.total_tasks_executed, statistics.successful_tasks, statistics.cache_hits), (2, 2, 1));
+    }
+    
+    #[tokio::test]
+    async fn test_task_seed_overrides_layer_and_unseeded_runs_skip_cache() {
+        let unseeded = quantum_layer(None);
+        let task = bell_task(100);
+        assert!(!simulate(&unseeded, &task).await.0.cached);
+        assert!(!simulate(&unseeded, &task).await.0.cached);
+        
+        let layer = quantum_layer(Some(1));
+        let mut seeded = task.clone();
+        seeded.configuration.insert(config_keys::QUANTUM_SEED.to_string(), serde_json::json!(7));
+        let (result, _) = simulate(&layer, &seeded).await;
+        assert_eq!((result.seed, result.cached), (7, false));
+        assert!(!simulate(&layer, &task).await.0.cached);
+        assert!(simulate(&layer, &seeded).await.0.cached);
+        
+        let mut wide = task.clone();
+        wide.configuration.insert(config_keys::QUANTUM_CIRCUIT.to_string(), serde_json::to_value(QuantumCircuit::new(5)).unwrap());
+        assert!(matches!(
+            layer.execute_task(&wide, &ExecutionConfig::default()).await,
+            Err(OrchestratorError::ResourceLimitExceeded(_))
+        ));
+    }
+}
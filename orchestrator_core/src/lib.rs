@@ -16,10 +16,16 @@ pub mod errors;
 pub mod config;
 pub mod metrics;
 pub mod backup;
+pub mod clock;
+pub mod rules;
+pub use system_events::background;
+pub mod cost;
+pub mod quantum;
+pub mod problem;
 
 // Re-exports principais
 pub use crate::core::{OrchestratorCore, TaskExecutionResult};
-pub use crate::graph::{TaskMesh, TaskNode, DependencyEdge};
+pub use crate::graph::{TaskMesh, TaskNode, TaskNodeBuilder, DependencyEdge, PortableGraph};
 pub use crate::layers::{ExecutionLayer, LocalLayer, ClusterLayer, QuantumSimLayer};
 pub use crate::symbiotic::{SymbioticConsciousness, ConsciousnessState};
 pub use crate::learning::{ContinuousLearning, LearningMetrics};
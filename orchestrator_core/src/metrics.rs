@@ -5,8 +5,8 @@
 use chrono::{DateTime, Utc};
 use prometheus::{
     Counter, Gauge, Histogram, IntCounter, IntGauge, Registry,
-    opts, register_counter, register_gauge, register_histogram,
-    register_int_counter, register_int_gauge
+    opts, register_gauge_with_registry, register_histogram_with_registry,
+    register_int_counter_with_registry, register_int_gauge_with_registry
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -133,40 +133,49 @@ pub struct MetricsCollector {
 impl MetricsCollector {
     /// Cria novo coletor de métricas
     pub fn new() -> Result<Self> {
+        // Registry próprio: várias instâncias podem coexistir no mesmo processo
         let registry = Registry::new();
         let start_time = Utc::now();
         
         // Inicializa métricas Prometheus
-        let task_counter = register_int_counter!(
-            opts!("orchestrator_tasks_total", "Total number of tasks processed")
+        let task_counter = register_int_counter_with_registry!(
+            opts!("orchestrator_tasks_total", "Total number of tasks processed"),
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
-        let task_success_counter = register_int_counter!(
-            opts!("orchestrator_tasks_success_total", "Total number of successful tasks")
+        let task_success_counter = register_int_counter_with_registry!(
+            opts!("orchestrator_tasks_success_total", "Total number of successful tasks"),
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
-        let task_failure_counter = register_int_counter!(
-            opts!("orchestrator_tasks_failure_total", "Total number of failed tasks")
+        let task_failure_counter = register_int_counter_with_registry!(
+            opts!("orchestrator_tasks_failure_total", "Total number of failed tasks"),
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
-        let active_tasks_gauge = register_int_gauge!(
-            opts!("orchestrator_active_tasks", "Number of currently active tasks")
+        let active_tasks_gauge = register_int_gauge_with_registry!(
+            opts!("orchestrator_active_tasks", "Number of currently active tasks"),
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
-        let consciousness_level_gauge = register_gauge!(
-            opts!("orchestrator_consciousness_level", "Current consciousness level")
+        let consciousness_level_gauge = register_gauge_with_registry!(
+            opts!("orchestrator_consciousness_level", "Current consciousness level"),
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
-        let resource_usage_gauge = register_gauge!(
-            opts!("orchestrator_resource_usage", "Resource usage percentage")
+        let resource_usage_gauge = register_gauge_with_registry!(
+            opts!("orchestrator_resource_usage", "Resource usage percentage"),
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
-        let task_execution_histogram = register_histogram!(
-            opts!("orchestrator_task_execution_duration_seconds", "Task execution duration")
+        let task_execution_histogram = register_histogram_with_registry!(
+            "orchestrator_task_execution_duration_seconds", "Task execution duration",
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
-        let response_time_histogram = register_histogram!(
-            opts!("orchestrator_response_time_seconds", "API response time")
+        let response_time_histogram = register_histogram_with_registry!(
+            "orchestrator_response_time_seconds", "API response time",
+            registry
         ).map_err(|e| OrchestratorError::InternalError(e.to_string()))?;
         
         let initial_metrics = SystemMetrics {
@@ -407,7 +416,7 @@ impl MetricsCollector {
     
     /// Exporta métricas no formato Prometheus
     pub fn export_prometheus_metrics(&self) -> String {
-        prometheus::gather().into_iter()
+        self.registry.gather().into_iter()
             .map(|mf| prometheus::TextEncoder::new().encode_to_string(&[mf]).unwrap_or_default())
             .collect::<Vec<_>>()
             .join("")
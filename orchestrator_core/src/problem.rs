@@ -0,0 +1,290 @@
+//! # Problem Details
+//!
+//! Respostas de erro estruturadas (RFC 7807, `application/problem+json`)
+//! para superfícies HTTP do orchestrator. Cada [`OrchestratorError`] tem um
+//! `type` estável derivado do seu [`OrchestratorError::error_code`], um
+//! título fixo e um status HTTP; o mapeamento é um `match` exaustivo, então
+//! uma variante nova não compila sem a sua entrada. O corpo é o de
+//! [`system_events::problem`], comum ao `task_mesh_core`.
+//!
+//! Mensagens de erros 5xx podem conter detalhes internos (caminhos, SQL,
+//! endereços) e só vão para o corpo com [`ApiError::with_debug`]; o cliente
+//! recebe o `trace_id` para correlacionar com os logs.
+
+use hyper::{header, Body, Response, StatusCode};
+pub use system_events::problem::{FieldError, ProblemDetails, PROBLEM_JSON};
+use system_events::problem::problem_type;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::errors::OrchestratorError;
+
+/// Prefixo dos URIs de `type`
+pub const PROBLEM_TYPE_PREFIX: &str = "urn:arkitect:problem:";
+
+/// Status e título de cada variante
+fn classify(error: &OrchestratorError) -> (StatusCode, &'static str) {
+    match error {
+        OrchestratorError::TaskNotFound(_) => (StatusCode::NOT_FOUND, "Task not found"),
+        OrchestratorError::ModelNotFound(_) => (StatusCode::NOT_FOUND, "Learning model not found"),
+        OrchestratorError::ValidationError { .. } => (StatusCode::UNPROCESSABLE_ENTITY, "Validation failed"),
+        OrchestratorError::CyclicDependency => (StatusCode::UNPROCESSABLE_ENTITY, "Cyclic dependency"),
+        OrchestratorError::InsufficientData => (StatusCode::UNPROCESSABLE_ENTITY, "Insufficient training data"),
+        OrchestratorError::AuthenticationError(_) => (StatusCode::UNAUTHORIZED, "Authentication required"),
+        OrchestratorError::AuthorizationError(_) => (StatusCode::FORBIDDEN, "Forbidden"),
+        OrchestratorError::InvalidState(_) => (StatusCode::CONFLICT, "Invalid state"),
+        OrchestratorError::UnsupportedOperation(_) => (StatusCode::METHOD_NOT_ALLOWED, "Unsupported operation"),
+        OrchestratorError::ResourceLimitExceeded(_) => (StatusCode::TOO_MANY_REQUESTS, "Resource limit exceeded"),
+        OrchestratorError::NoActiveNodes => (StatusCode::SERVICE_UNAVAILABLE, "No active nodes"),
+        OrchestratorError::LayerNotAvailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "Execution layer unavailable"),
+        OrchestratorError::Timeout(_) => (StatusCode::GATEWAY_TIMEOUT, "Operation timed out"),
+        OrchestratorError::NetworkError(_) => (StatusCode::BAD_GATEWAY, "Upstream network error"),
+        OrchestratorError::ExternalServiceError { .. } => (StatusCode::BAD_GATEWAY, "External service error"),
+        OrchestratorError::ConfigurationError(_)
+        | OrchestratorError::SerializationError(_)
+        | OrchestratorError::IoError(_)
+        | OrchestratorError::DatabaseError(_)
+        | OrchestratorError::UnsupportedFormatVersion { .. }
+        | OrchestratorError::ConsciousnessError(_)
+        | OrchestratorError::QuantumError(_)
+        | OrchestratorError::InternalError(_)
+        | OrchestratorError::ExternalError(_)
+        | OrchestratorError::RuntimeError { .. }
+        | OrchestratorError::PanicError { .. } => (StatusCode::INTERNAL_SERVER_ERROR, "Internal error"),
+    }
+}
+
+/// `trace_id` do contexto do erro, quando a variante tem um
+fn context_trace_id(error: &OrchestratorError) -> Option<&str> {
+    match error {
+        OrchestratorError::ValidationError { context, .. }
+        | OrchestratorError::RuntimeError { context, .. }
+        | OrchestratorError::ExternalServiceError { context, .. }
+        | OrchestratorError::PanicError { context, .. } => Some(&context.trace_id),
+        _ => None,
+    }
+}
+
+/// Erro pronto para virar uma resposta HTTP
+#[derive(Debug)]
+pub struct ApiError {
+    pub error: OrchestratorError,
+    trace_id: String,
+    debug: bool,
+}
+
+impl From<OrchestratorError> for ApiError {
+    fn from(error: OrchestratorError) -> Self {
+        Self::new(error)
+    }
+}
+
+impl ApiError {
+    /// Usa o `trace_id` do contexto do erro ou gera um novo
+    pub fn new(error: OrchestratorError) -> Self {
+        let trace_id = context_trace_id(&error).map(str::to_string).unwrap_or_else(|| Uuid::new_v4().to_string());
+        Self { error, trace_id, debug: false }
+    }
+
+    /// `trace_id` da requisição (ex.: do cabeçalho `traceparent`)
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = trace_id.into();
+        self
+    }
+
+    /// Inclui a mensagem de erros 5xx no corpo (apenas para depuração)
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        classify(&self.error).0
+    }
+
+    /// Corpo problem+json do erro
+    pub fn problem(&self) -> ProblemDetails {
+        let (status, title) = classify(&self.error);
+        let code = self.error.error_code();
+        let errors = match &self.error {
+            OrchestratorError::ValidationError { field, message, .. } => {
+                vec![FieldError { field: field.clone(), message: message.clone() }]
+            }
+            _ => Vec::new(),
+        };
+        let detail = (!status.is_server_error() || self.debug).then(|| self.error.to_string());
+        ProblemDetails {
+            type_uri: problem_type(PROBLEM_TYPE_PREFIX, code),
+            title: title.to_string(),
+            status: status.as_u16(),
+            detail,
+            code: code.to_string(),
+            trace_id: Some(self.trace_id.clone()),
+            errors,
+        }
+    }
+
+    /// Resposta HTTP com o corpo problem+json
+    pub fn into_response(self) -> Response<Body> {
+        let status = self.status();
+        if status.is_server_error() {
+            error!(trace_id = %self.trace_id, code = self.error.error_code(), "{}", self.error);
+        }
+        let body = serde_json::to_vec(&self.problem()).expect("ProblemDetails serializável");
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, PROBLEM_JSON)
+            .body(Body::from(body))
+            .expect("resposta válida")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{ErrorContext, ErrorKind, RecoveryStrategy};
+    use crate::layers::ExecutionLayer;
+
+    fn runtime_kind() -> ErrorKind {
+        ErrorKind::Runtime { component: "executor".to_string(), operation: "run".to_string(), cause: "x".to_string() }
+    }
+
+    /// Uma instância de cada variante; o `match` falha ao compilar quando surge outra
+    fn every_variant() -> Vec<OrchestratorError> {
+        let all = vec![
+            OrchestratorError::validation("tasks[0].name", "required", "", "name is required"),
+            OrchestratorError::RuntimeError {
+                component: "executor".to_string(),
+                message: "worker crashed".to_string(),
+                kind: runtime_kind(),
+                context: ErrorContext::new("run", "executor"),
+                retry_info: None,
+            },
+            OrchestratorError::certificate("cluster", "https://node:8443", "unknown issuer"),
+            OrchestratorError::PanicError {
+                reason: "boom".to_string(),
+                kind: runtime_kind(),
+                context: ErrorContext::new("run", "executor"),
+                recovery_strategy: RecoveryStrategy::Restart { component: "executor".to_string(), graceful: true },
+            },
+            OrchestratorError::TaskNotFound(Uuid::nil()),
+            OrchestratorError::CyclicDependency,
+            OrchestratorError::ResourceLimitExceeded("memory".to_string()),
+            OrchestratorError::NoActiveNodes,
+            OrchestratorError::LayerNotAvailable(ExecutionLayer::QuantumSim),
+            OrchestratorError::ModelNotFound("m".to_string()),
+            OrchestratorError::InsufficientData,
+            OrchestratorError::ConfigurationError("c".to_string()),
+            OrchestratorError::SerializationError(serde_json::from_str::<u8>("x").unwrap_err()),
+            OrchestratorError::IoError(std::io::Error::new(std::io::ErrorKind::Other, "disk")),
+            OrchestratorError::DatabaseError("/var/lib/db locked".to_string()),
+            OrchestratorError::AuthenticationError("a".to_string()),
+            OrchestratorError::AuthorizationError("role viewer cannot cancel".to_string()),
+            OrchestratorError::Timeout("dispatch".to_string()),
+            OrchestratorError::InvalidState("s".to_string()),
+            OrchestratorError::UnsupportedOperation("u".to_string()),
+            OrchestratorError::UnsupportedFormatVersion { found: 9, supported: 1 },
+            OrchestratorError::ConsciousnessError("c".to_string()),
+            OrchestratorError::QuantumError("q".to_string()),
+            OrchestratorError::InternalError("i".to_string()),
+            OrchestratorError::ExternalError(anyhow::anyhow!("e")),
+        ];
+        for error in &all {
+            match error {
+                OrchestratorError::ValidationError { .. }
+                | OrchestratorError::RuntimeError { .. }
+                | OrchestratorError::ExternalServiceError { .. }
+                | OrchestratorError::PanicError { .. }
+                | OrchestratorError::TaskNotFound(_)
+                | OrchestratorError::CyclicDependency
+                | OrchestratorError::ResourceLimitExceeded(_)
+                | OrchestratorError::NoActiveNodes
+                | OrchestratorError::LayerNotAvailable(_)
+                | OrchestratorError::ModelNotFound(_)
+                | OrchestratorError::InsufficientData
+                | OrchestratorError::ConfigurationError(_)
+                | OrchestratorError::SerializationError(_)
+                | OrchestratorError::IoError(_)
+                | OrchestratorError::NetworkError(_)
+                | OrchestratorError::DatabaseError(_)
+                | OrchestratorError::AuthenticationError(_)
+                | OrchestratorError::AuthorizationError(_)
+                | OrchestratorError::Timeout(_)
+                | OrchestratorError::InvalidState(_)
+                | OrchestratorError::UnsupportedOperation(_)
+                | OrchestratorError::UnsupportedFormatVersion { .. }
+                | OrchestratorError::ConsciousnessError(_)
+                | OrchestratorError::QuantumError(_)
+                | OrchestratorError::InternalError(_)
+                | OrchestratorError::ExternalError(_) => {}
+            }
+        }
+        all
+    }
+
+    #[test]
+    fn test_every_variant_has_a_distinct_stable_type() {
+        let problems: Vec<ProblemDetails> = every_variant().into_iter().map(|e| ApiError::new(e).problem()).collect();
+        let types: std::collections::HashSet<&str> = problems.iter().map(|p| p.type_uri.as_str()).collect();
+        assert_eq!(types.len(), problems.len());
+        for problem in &problems {
+            assert!(problem.type_uri.starts_with(PROBLEM_TYPE_PREFIX), "{:?}", problem);
+            // 5xx sem depuração não expõe a mensagem interna
+            assert_eq!(problem.detail.is_none(), problem.status >= 500, "{:?}", problem);
+        }
+    }
+
+    async fn body(error: ApiError) -> (StatusCode, String, serde_json::Value) {
+        let response = error.into_response();
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, content_type, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_representative_response_bodies() {
+        let (status, content_type, json) =
+            body(ApiError::new(OrchestratorError::TaskNotFound(Uuid::nil())).with_trace_id("trace-1")).await;
+        assert_eq!((status, content_type.as_str()), (StatusCode::NOT_FOUND, PROBLEM_JSON));
+        assert_eq!(json, serde_json::json!({
+            "type": "urn:arkitect:problem:task-not-found",
+            "title": "Task not found",
+            "status": 404,
+            "detail": "Task not found: 00000000-0000-0000-0000-000000000000",
+            "code": "TASK_NOT_FOUND",
+            "trace_id": "trace-1",
+        }));
+
+        let validation = OrchestratorError::validation("tasks[0].name", "required", "", "name is required");
+        let (status, _, json) = body(ApiError::new(validation).with_trace_id("trace-2")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json, serde_json::json!({
+            "type": "urn:arkitect:problem:validation-error",
+            "title": "Validation failed",
+            "status": 422,
+            "detail": "Validation error in field tasks[0].name: name is required",
+            "code": "VALIDATION_ERROR",
+            "trace_id": "trace-2",
+            "errors": [{ "field": "tasks[0].name", "message": "name is required" }],
+        }));
+
+        let (status, _, json) = body(ApiError::new(OrchestratorError::AuthorizationError("no".to_string()))).await;
+        assert_eq!((status, json["status"].as_u64()), (StatusCode::FORBIDDEN, Some(403)));
+        let (status, _, json) = body(ApiError::new(OrchestratorError::Timeout("dispatch".to_string()))).await;
+        assert_eq!((status, json["code"].as_str()), (StatusCode::GATEWAY_TIMEOUT, Some("TIMEOUT")));
+
+        let internal = || OrchestratorError::DatabaseError("/var/lib/arkitect/state.db locked".to_string());
+        let (status, _, json) = body(ApiError::new(internal()).with_trace_id("trace-3")).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(json, serde_json::json!({
+            "type": "urn:arkitect:problem:database-error",
+            "title": "Internal error",
+            "status": 500,
+            "code": "DATABASE_ERROR",
+            "trace_id": "trace-3",
+        }));
+        let (_, _, json) = body(ApiError::new(internal()).with_debug(true)).await;
+        assert_eq!(json["detail"], "Database error: /var/lib/arkitect/state.db locked");
+    }
+}
@@ -0,0 +1,528 @@
+//! # Quantum Simulation
+//!
+//! Simulador de vetor de estado usado pela camada quântica e cache dos seus
+//! resultados. A amostragem das medições usa um gerador com semente: a mesma
+//! semente, o mesmo circuito e o mesmo modelo de ruído produzem as mesmas
+//! contagens. Resultados de execuções com semente ficam no
+//! [`SimulationCache`] por um TTL, chaveados por [`SimulationKey`].
+//!
+//! O ruído é aplicado na leitura (cada bit medido é trocado com
+//! probabilidade `measurement_error_rate`); `gate_error_rate` entra apenas na
+//! estimativa de fidelidade.
+//!
+//! A simulação roda no pool rayon corrente (`ThreadPool::install` escolhe
+//! outro). Circuitos a partir de [`PARALLEL_QUBITS`] qubits aplicam as portas
+//! em paralelo sobre blocos de amplitudes; os shots são divididos em lotes
+//! de [`SHOT_BATCH`], cada um com seu gerador derivado da semente, de modo
+//! que as contagens não dependem do número de threads.
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::clock::Clock;
+use crate::errors::{OrchestratorError, Result};
+use crate::graph::{CircuitGate, QuantumCircuit};
+use crate::layers::{NoiseModel, QuantumSimulationResult};
+
+/// Amplitude complexa do vetor de estado
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Amplitude {
+    re: f64,
+    im: f64,
+}
+
+impl Amplitude {
+    const ZERO: Amplitude = Amplitude { re: 0.0, im: 0.0 };
+    const ONE: Amplitude = Amplitude { re: 1.0, im: 0.0 };
+
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn times(self, other: Amplitude) -> Self {
+        Self::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+
+    fn plus(self, other: Amplitude) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+
+    fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+/// Matriz 2×2 de uma porta de um qubit
+type Matrix = [[Amplitude; 2]; 2];
+
+/// Operação interpretada a partir de uma [`CircuitGate`]
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Single(Matrix, usize),
+    ControlledX(usize, usize),
+    ControlledZ(usize, usize),
+    Swap(usize, usize),
+    /// `measure`/`barrier`: todos os qubits são medidos ao final
+    Noop,
+}
+
+impl Operation {
+    fn from_gate(gate: &CircuitGate) -> std::result::Result<Self, String> {
+        let arity = match gate.name.as_str() {
+            "cx" | "cnot" | "cz" | "swap" => 2,
+            "measure" | "barrier" => gate.qubits.len(),
+            _ => 1,
+        };
+        if gate.qubits.len() != arity {
+            return Err(format!("gate '{}' acts on {} qubit(s), got {}", gate.name, arity, gate.qubits.len()));
+        }
+        let angle = || match gate.params.as_slice() {
+            [theta] => Ok(*theta),
+            params => Err(format!("gate '{}' takes 1 parameter, got {}", gate.name, params.len())),
+        };
+
+        let frac = std::f64::consts::FRAC_1_SQRT_2;
+        let (zero, one) = (Amplitude::ZERO, Amplitude::ONE);
+        let phase = |theta: f64| Amplitude::new(theta.cos(), theta.sin());
+        let matrix: Matrix = match gate.name.as_str() {
+            "cx" | "cnot" => return Ok(Operation::ControlledX(gate.qubits[0], gate.qubits[1])),
+            "cz" => return Ok(Operation::ControlledZ(gate.qubits[0], gate.qubits[1])),
+            "swap" => return Ok(Operation::Swap(gate.qubits[0], gate.qubits[1])),
+            "measure" | "barrier" => return Ok(Operation::Noop),
+            "id" => [[one, zero], [zero, one]],
+            "h" => {
+                let h = Amplitude::new(frac, 0.0);
+                [[h, h], [h, Amplitude::new(-frac, 0.0)]]
+            }
+            "x" => [[zero, one], [one, zero]],
+            "y" => [[zero, Amplitude::new(0.0, -1.0)], [Amplitude::new(0.0, 1.0), zero]],
+            "z" => [[one, zero], [zero, Amplitude::new(-1.0, 0.0)]],
+            "s" => [[one, zero], [zero, phase(std::f64::consts::FRAC_PI_2)]],
+            "sdg" => [[one, zero], [zero, phase(-std::f64::consts::FRAC_PI_2)]],
+            "t" => [[one, zero], [zero, phase(std::f64::consts::FRAC_PI_4)]],
+            "tdg" => [[one, zero], [zero, phase(-std::f64::consts::FRAC_PI_4)]],
+            "rx" => {
+                let theta = angle()? / 2.0;
+                let (cos, sin) = (Amplitude::new(theta.cos(), 0.0), Amplitude::new(0.0, -theta.sin()));
+                [[cos, sin], [sin, cos]]
+            }
+            "ry" => {
+                let theta = angle()? / 2.0;
+                [
+                    [Amplitude::new(theta.cos(), 0.0), Amplitude::new(-theta.sin(), 0.0)],
+                    [Amplitude::new(theta.sin(), 0.0), Amplitude::new(theta.cos(), 0.0)],
+                ]
+            }
+            "rz" => {
+                let theta = angle()? / 2.0;
+                [[phase(-theta), zero], [zero, phase(theta)]]
+            }
+            name => return Err(format!("unsupported gate '{}'", name)),
+        };
+        Ok(Operation::Single(matrix, gate.qubits[0]))
+    }
+}
+
+/// Circuitos a partir deste tamanho aplicam as portas em paralelo
+pub const PARALLEL_QUBITS: usize = 16;
+
+/// Shots amostrados por lote (e por gerador)
+pub const SHOT_BATCH: u32 = 4096;
+
+/// Vetor de estado de `qubits` qubits; o bit `q` do índice é o qubit `q`
+#[derive(Debug, Clone)]
+struct StateVector {
+    amplitudes: Vec<Amplitude>,
+    parallel: bool,
+}
+
+impl StateVector {
+    fn new(qubits: usize) -> Self {
+        let mut amplitudes = vec![Amplitude::ZERO; 1 << qubits];
+        amplitudes[0] = Amplitude::ONE;
+        Self { amplitudes, parallel: qubits >= PARALLEL_QUBITS }
+    }
+
+    fn apply(&mut self, operation: Operation) {
+        match operation {
+            Operation::Single(matrix, qubit) => self.for_each_pair(qubit, |_, low, high| {
+                let (a, b) = (*low, *high);
+                *low = matrix[0][0].times(a).plus(matrix[0][1].times(b));
+                *high = matrix[1][0].times(a).plus(matrix[1][1].times(b));
+            }),
+            Operation::ControlledX(control, target) => {
+                let control = 1 << control;
+                self.for_each_pair(target, |index, low, high| {
+                    if index & control != 0 {
+                        std::mem::swap(low, high);
+                    }
+                });
+            }
+            Operation::ControlledZ(a, b) => {
+                let both = (1 << a) | (1 << b);
+                let negate = |(index, amplitude): (usize, &mut Amplitude)| {
+                    if index & both == both {
+                        *amplitude = Amplitude::new(-amplitude.re, -amplitude.im);
+                    }
+                };
+                if self.parallel {
+                    self.amplitudes.par_iter_mut().enumerate().for_each(negate);
+                } else {
+                    self.amplitudes.iter_mut().enumerate().for_each(negate);
+                }
+            }
+            Operation::Swap(a, b) => {
+                for operation in [Operation::ControlledX(a, b), Operation::ControlledX(b, a), Operation::ControlledX(a, b)] {
+                    self.apply(operation);
+                }
+            }
+            Operation::Noop => {}
+        }
+    }
+
+    /// Aplica `f(índice, |…0…⟩, |…1…⟩)` a cada par de amplitudes que difere
+    /// apenas no `qubit`; o índice é o do estado com o qubit em 0
+    ///
+    /// Os pares ficam em blocos contíguos de `2^(qubit+1)` amplitudes, que são
+    /// processados em paralelo (e, dentro de blocos grandes, as metades também).
+    fn for_each_pair<F>(&mut self, qubit: usize, f: F)
+    where
+        F: Fn(usize, &mut Amplitude, &mut Amplitude) + Send + Sync,
+    {
+        let half = 1 << qubit;
+        if self.parallel {
+            self.amplitudes.par_chunks_mut(2 * half).enumerate().for_each(|(block, chunk)| {
+                let (low, high) = chunk.split_at_mut(half);
+                low.par_iter_mut()
+                    .zip(high.par_iter_mut())
+                    .enumerate()
+                    .for_each(|(offset, (low, high))| f(block * 2 * half + offset, low, high));
+            });
+        } else {
+            for (block, chunk) in self.amplitudes.chunks_mut(2 * half).enumerate() {
+                let (low, high) = chunk.split_at_mut(half);
+                for (offset, (low, high)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+                    f(block * 2 * half + offset, low, high);
+                }
+            }
+        }
+    }
+
+    /// Probabilidades acumuladas de cada estado da base
+    fn cumulative_probabilities(&self) -> Vec<f64> {
+        self.amplitudes
+            .iter()
+            .scan(0.0, |total, amplitude| {
+                *total += amplitude.norm_sqr();
+                Some(*total)
+            })
+            .collect()
+    }
+}
+
+/// Contagens e métricas de uma simulação
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    /// Contagem por string de bits (o caractere `q` é o qubit `q`)
+    pub counts: BTreeMap<String, u32>,
+    pub gate_count: usize,
+    pub circuit_depth: usize,
+    /// Estimativa a partir das taxas de erro do modelo de ruído
+    pub fidelity: f64,
+    /// Threads do pool rayon em que a simulação rodou
+    pub threads: usize,
+}
+
+/// Simula o circuito e amostra `circuit.shots` medições com a semente dada
+///
+/// O resultado é o mesmo para qualquer número de threads.
+pub fn simulate(circuit: &QuantumCircuit, noise: &NoiseModel, seed: u64) -> Result<Simulation> {
+    let gates = circuit.parse_gates()?;
+    let operations = gates
+        .iter()
+        .enumerate()
+        .map(|(position, gate)| {
+            Operation::from_gate(gate).map_err(|message| {
+                OrchestratorError::validation(
+                    &format!("configuration.quantum_circuit.gates[{}]", position), "semantics", &circuit.gates[position], message,
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut state = StateVector::new(circuit.qubits);
+    for operation in &operations {
+        state.apply(*operation);
+    }
+
+    let cumulative = state.cumulative_probabilities();
+    let batches = circuit.shots.div_ceil(SHOT_BATCH);
+    let outcomes = (0..batches)
+        .into_par_iter()
+        .map(|batch| {
+            let shots = SHOT_BATCH.min(circuit.shots - batch * SHOT_BATCH);
+            sample_batch(&cumulative, circuit.qubits, noise, shots, stream_seed(seed, batch))
+        })
+        .reduce(HashMap::new, |mut merged, batch| {
+            for (outcome, count) in batch {
+                *merged.entry(outcome).or_insert(0) += count;
+            }
+            merged
+        });
+    let counts = outcomes
+        .into_iter()
+        .map(|(outcome, count)| (bitstring(outcome, circuit.qubits), count))
+        .collect();
+
+    let fidelity = (1.0 - noise.gate_error_rate).clamp(0.0, 1.0).powi(gates.len() as i32)
+        * (1.0 - noise.measurement_error_rate).clamp(0.0, 1.0).powi(circuit.qubits as i32);
+    Ok(Simulation {
+        counts,
+        gate_count: gates.len(),
+        circuit_depth: circuit_depth(&gates, circuit.qubits),
+        fidelity,
+        threads: rayon::current_num_threads(),
+    })
+}
+
+/// Amostra `shots` medições de um lote com gerador próprio
+fn sample_batch(cumulative: &[f64], qubits: usize, noise: &NoiseModel, shots: u32, seed: u64) -> HashMap<usize, u32> {
+    let total = cumulative.last().copied().unwrap_or(1.0);
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let mut outcomes = HashMap::new();
+    for _ in 0..shots {
+        let draw = rng.f64() * total;
+        let mut outcome = cumulative.partition_point(|&probability| probability <= draw).min(cumulative.len() - 1);
+        if noise.measurement_error_rate > 0.0 {
+            for qubit in 0..qubits {
+                if rng.f64() < noise.measurement_error_rate {
+                    outcome ^= 1 << qubit;
+                }
+            }
+        }
+        *outcomes.entry(outcome).or_insert(0) += 1;
+    }
+    outcomes
+}
+
+/// Semente do gerador de um lote (SplitMix64 sobre a semente e o lote)
+fn stream_seed(seed: u64, batch: u32) -> u64 {
+    let mut z = seed.wrapping_add((batch as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// String de bits de um estado da base
+fn bitstring(outcome: usize, qubits: usize) -> String {
+    (0..qubits).map(|qubit| if outcome >> qubit & 1 == 1 { '1' } else { '0' }).collect()
+}
+
+/// Profundidade: maior número de portas encadeadas sobre algum qubit
+fn circuit_depth(gates: &[CircuitGate], qubits: usize) -> usize {
+    let mut levels = vec![0; qubits];
+    for gate in gates {
+        let level = gate.qubits.iter().map(|&qubit| levels[qubit]).max().unwrap_or(0) + 1;
+        for &qubit in &gate.qubits {
+            levels[qubit] = level;
+        }
+    }
+    levels.into_iter().max().unwrap_or(0)
+}
+
+/// Chave de um resultado em cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SimulationKey {
+    /// Hash do circuito (qubits e portas normalizadas)
+    pub circuit_hash: u64,
+    pub noise_hash: u64,
+    pub shots: u32,
+    pub seed: u64,
+}
+
+impl SimulationKey {
+    /// Chave de uma simulação com semente
+    ///
+    /// As portas entram já interpretadas, então `H 0` e `h  0` têm a mesma
+    /// chave. Os hashes são FNV-1a e não mudam entre versões do compilador.
+    pub fn new(circuit: &QuantumCircuit, noise: &NoiseModel, seed: u64) -> Result<Self> {
+        let mut circuit_hash = Fnv1a::new();
+        circuit_hash.write(&(circuit.qubits as u64).to_le_bytes());
+        for gate in circuit.parse_gates()? {
+            circuit_hash.write(gate.name.as_bytes());
+            for param in &gate.params {
+                circuit_hash.write(&param.to_bits().to_le_bytes());
+            }
+            for qubit in &gate.qubits {
+                circuit_hash.write(&(*qubit as u64).to_le_bytes());
+            }
+            circuit_hash.write(b";");
+        }
+
+        let mut noise_hash = Fnv1a::new();
+        for rate in [noise.gate_error_rate, noise.measurement_error_rate, noise.decoherence_time_ns] {
+            noise_hash.write(&rate.to_bits().to_le_bytes());
+        }
+
+        Ok(Self {
+            circuit_hash: circuit_hash.finish(),
+            noise_hash: noise_hash.finish(),
+            shots: circuit.shots,
+            seed,
+        })
+    }
+}
+
+/// Hash FNV-1a de 64 bits
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Cache de resultados de simulações com semente
+///
+/// Entradas expiram `ttl` depois de gravadas, pelo relógio monotônico; um
+/// TTL zero desativa o cache.
+#[derive(Debug)]
+pub struct SimulationCache {
+    entries: RwLock<HashMap<SimulationKey, (Instant, QuantumSimulationResult)>>,
+    ttl: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl SimulationCache {
+    pub fn new(ttl: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+            clock,
+        }
+    }
+
+    /// Resultado ainda válido para a chave, marcado como `cached`
+    pub async fn get(&self, key: &SimulationKey) -> Option<QuantumSimulationResult> {
+        let entries = self.entries.read().await;
+        let (stored_at, result) = entries.get(key)?;
+        if self.clock.now().duration_since(*stored_at) >= self.ttl {
+            return None;
+        }
+        Some(QuantumSimulationResult { cached: true, ..result.clone() })
+    }
+
+    /// Grava um resultado, descartando as entradas expiradas
+    pub async fn insert(&self, key: SimulationKey, result: QuantumSimulationResult) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let now = self.clock.now();
+        let mut entries = self.entries.write().await;
+        entries.retain(|_, (stored_at, _)| now.duration_since(*stored_at) < self.ttl);
+        entries.insert(key, (now, result));
+    }
+
+    /// Quantidade de entradas gravadas (inclui expiradas ainda não descartadas)
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.read().await.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn noiseless() -> NoiseModel {
+        NoiseModel { gate_error_rate: 0.0, measurement_error_rate: 0.0, decoherence_time_ns: 0.0 }
+    }
+
+    fn bell(shots: u32) -> QuantumCircuit {
+        QuantumCircuit { shots, ..QuantumCircuit::new(2).with_gate("h 0").with_gate("cx 0 1") }
+    }
+
+    #[test]
+    fn test_seeded_bell_sampling_is_deterministic() {
+        let first = simulate(&bell(1000), &noiseless(), 7).unwrap();
+        assert_eq!(first, simulate(&bell(1000), &noiseless(), 7).unwrap());
+        assert_eq!(first.counts.keys().collect::<Vec<_>>(), vec!["00", "11"]);
+        assert_eq!(first.counts.values().sum::<u32>(), 1000);
+        assert_eq!((first.gate_count, first.circuit_depth), (2, 2));
+
+        let swapped = QuantumCircuit::new(3).with_gate("x 0").with_gate("swap 0 2");
+        let counts = simulate(&swapped, &noiseless(), 1).unwrap().counts;
+        assert_eq!(counts, BTreeMap::from([("001".to_string(), 1024)]));
+        assert!(simulate(&QuantumCircuit::new(1).with_gate("foo 0"), &noiseless(), 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cache_entries_expire_after_ttl() {
+        let clock = Arc::new(MockClock::new());
+        let cache = SimulationCache::new(Duration::from_secs(60), clock.clone());
+        let key = SimulationKey::new(&bell(10), &noiseless(), 3).unwrap();
+        let respelled = QuantumCircuit { shots: 10, ..QuantumCircuit::new(2).with_gate("H 0").with_gate("cx  0 1") };
+        assert_eq!(key, SimulationKey::new(&respelled, &noiseless(), 3).unwrap());
+        assert_ne!(key, SimulationKey::new(&bell(10), &noiseless(), 4).unwrap());
+
+        let result = QuantumSimulationResult {
+            qubits_used: 2,
+            gate_count: 2,
+            circuit_depth: 2,
+            measurement_results: vec![0, 0],
+            fidelity: 1.0,
+            execution_time_ns: 1,
+            counts: BTreeMap::from([("00".to_string(), 10)]),
+            shots: 10,
+            seed: 3,
+            cached: false,
+            threads: 1,
+        };
+        cache.insert(key, result).await;
+        assert!(cache.get(&key).await.unwrap().cached);
+
+        clock.advance(Duration::from_secs(60));
+        assert!(cache.get(&key).await.is_none());
+    }
+
+    #[test]
+    fn test_ghz_counts_do_not_depend_on_thread_count() {
+        let qubits = PARALLEL_QUBITS;
+        let ghz = (1..qubits).fold(QuantumCircuit::new(qubits).with_gate("h 0"), |circuit, qubit| {
+            circuit.with_gate(format!("cx {} {}", qubit - 1, qubit))
+        });
+        let ghz = QuantumCircuit { shots: 3 * SHOT_BATCH + 17, ..ghz };
+        let noise = NoiseModel { measurement_error_rate: 0.001, ..noiseless() };
+        let run = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            pool.install(|| simulate(&ghz, &noise, 11)).unwrap()
+        };
+
+        let (single, parallel) = (run(1), run(4));
+        assert_eq!((single.threads, parallel.threads), (1, 4));
+        assert_eq!(single.counts, parallel.counts);
+        assert_eq!(single.counts.values().sum::<u32>(), ghz.shots);
+        let (zeros, ones) = (&"0".repeat(qubits), &"1".repeat(qubits));
+        let correct = single.counts[zeros] + single.counts[ones];
+        assert!(correct as f64 > 0.95 * ghz.shots as f64, "{} of {}", correct, ghz.shots);
+        assert!((single.counts[zeros] as f64 / correct as f64 - 0.5).abs() < 0.02);
+    }
+}
@@ -0,0 +1,412 @@
+//! # Rule Conditions
+//!
+//! Linguagem de expressões das regras da base de conhecimento. Uma condição
+//! compara campos do evento e das métricas do sistema:
+//!
+//! ```text
+//! event.severity == "High" && metrics.tasks.failed_tasks > 10
+//! ```
+//!
+//! Suporta literais (números, strings entre aspas, `true`/`false`), caminhos
+//! iniciados por `event` ou `metrics`, comparações (`==`, `!=`, `<`, `<=`,
+//! `>`, `>=`), `!`, `&&`, `||` e parênteses. Erros de sintaxe indicam a
+//! coluna (a partir de 1) do problema.
+//!
+//! A mesma linguagem expressa as condições de tarefas
+//! ([`Condition::parse_task_condition`]), cujos caminhos partem de
+//! `parents`, o status e a saída das dependências:
+//!
+//! ```text
+//! parents.fetch.status == "Completed" && parents.fetch.output.rows > 0
+//! ```
+
+use serde_json::Value;
+
+use crate::errors::{OrchestratorError, Result};
+
+/// Raízes aceitas nos caminhos
+const ROOTS: &[&str] = &["event", "metrics"];
+
+/// Raízes aceitas nas condições de tarefas
+pub const TASK_ROOTS: &[&str] = &["parents"];
+
+/// Profundidade máxima de aninhamento (parênteses e `!`)
+const MAX_DEPTH: usize = 64;
+
+/// Condição compilada
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Path(Vec<String>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Dot,
+    Not,
+    And,
+    Or,
+    Compare(CompareOp),
+    LParen,
+    RParen,
+}
+
+impl Condition {
+    /// Compila uma condição; erros indicam a coluna do problema
+    pub fn parse(source: &str) -> Result<Self> {
+        Self::parse_with_roots(source, "rule.condition", ROOTS)
+    }
+
+    /// Compila a condição de uma tarefa (caminhos a partir de `parents`)
+    pub fn parse_task_condition(source: &str) -> Result<Self> {
+        Self::parse_with_roots(source, "task.condition", TASK_ROOTS)
+    }
+
+    fn parse_with_roots(source: &str, field: &str, roots: &'static [&'static str]) -> Result<Self> {
+        let error = |column: usize, message: String| {
+            OrchestratorError::validation(field, "syntax", source, format!("{} at column {}", message, column))
+        };
+
+        let tokens = tokenize(source).map_err(|(column, message)| error(column, message))?;
+        let mut parser = Parser { tokens, position: 0, end: source.chars().count() + 1, depth: 0, roots };
+        let expr = parser.or().map_err(|(column, message)| error(column, message))?;
+        if let Some((column, token)) = parser.tokens.get(parser.position) {
+            return Err(error(*column, format!("unexpected {:?}", token)));
+        }
+        Ok(Self { source: source.to_string(), expr })
+    }
+
+    /// Texto original da condição
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Caminhos referenciados pela condição, em ordem de aparição
+    pub fn paths(&self) -> Vec<&[String]> {
+        let mut paths = Vec::new();
+        collect_paths(&self.expr, &mut paths);
+        paths
+    }
+
+    /// Avalia a condição sobre `{"event": ..., "metrics": ...}`
+    ///
+    /// Caminhos inexistentes valem `null`; comparações de ordem entre tipos
+    /// diferentes são falsas.
+    pub fn evaluate(&self, context: &Value) -> bool {
+        truthy(&eval(&self.expr, context))
+    }
+}
+
+fn tokenize(source: &str) -> std::result::Result<Vec<(usize, Token)>, (usize, String)> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, width) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '.' => (Token::Dot, 1),
+            '&' if next == Some('&') => (Token::And, 2),
+            '|' if next == Some('|') => (Token::Or, 2),
+            '=' if next == Some('=') => (Token::Compare(CompareOp::Eq), 2),
+            '!' if next == Some('=') => (Token::Compare(CompareOp::Ne), 2),
+            '!' => (Token::Not, 1),
+            '<' if next == Some('=') => (Token::Compare(CompareOp::Le), 2),
+            '<' => (Token::Compare(CompareOp::Lt), 1),
+            '>' if next == Some('=') => (Token::Compare(CompareOp::Ge), 2),
+            '>' => (Token::Compare(CompareOp::Gt), 1),
+            '"' => {
+                let mut value = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return Err((column, "unterminated string".to_string())),
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(j + 1) {
+                                Some(&escaped @ ('"' | '\\')) => value.push(escaped),
+                                _ => return Err((j + 1, "invalid escape".to_string())),
+                            }
+                            j += 2;
+                        }
+                        Some(&other) => {
+                            value.push(other);
+                            j += 1;
+                        }
+                    }
+                }
+                (Token::Str(value), j + 1 - i)
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.map_or(false, |n| n.is_ascii_digit())) => {
+                let mut j = i + 1;
+                while chars.get(j).map_or(false, |c| c.is_ascii_digit() || *c == '.') {
+                    j += 1;
+                }
+                let text: String = chars[i..j].iter().collect();
+                let number = text.parse::<f64>().map_err(|_| (column, format!("invalid number '{}'", text)))?;
+                (Token::Number(number), j - i)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut j = i + 1;
+                while chars.get(j).map_or(false, |c| c.is_ascii_alphanumeric() || *c == '_') {
+                    j += 1;
+                }
+                (Token::Ident(chars[i..j].iter().collect()), j - i)
+            }
+            other => return Err((column, format!("unexpected character '{}'", other))),
+        };
+        tokens.push((column, token));
+        i += width;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    /// Coluna reportada para erros no fim da entrada
+    end: usize,
+    depth: usize,
+    roots: &'static [&'static str],
+}
+
+type ParseResult<T> = std::result::Result<T, (usize, String)>;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens.get(self.position).map_or(self.end, |(column, _)| *column)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).map(|(_, token)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err((self.column(), "expression nested too deeply".to_string()));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn or(&mut self) -> ParseResult<Expr> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> ParseResult<Expr> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.advance();
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> ParseResult<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.advance();
+            return self.nested(|parser| Ok(Expr::Not(Box::new(parser.unary()?))));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> ParseResult<Expr> {
+        let left = self.primary()?;
+        if let Some(Token::Compare(op)) = self.peek().cloned() {
+            self.advance();
+            let right = self.primary()?;
+            return Ok(Expr::Compare(Box::new(left), op, Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> ParseResult<Expr> {
+        let column = self.column();
+        match self.advance() {
+            Some(Token::Number(number)) => Ok(Expr::Literal(serde_json::json!(number))),
+            Some(Token::Str(value)) => Ok(Expr::Literal(Value::String(value))),
+            Some(Token::Ident(name)) if name == "true" || name == "false" => Ok(Expr::Literal(Value::Bool(name == "true"))),
+            Some(Token::Ident(root)) => {
+                if !self.roots.contains(&root.as_str()) {
+                    return Err((column, format!("unknown root '{}' (expected one of {:?})", root, self.roots)));
+                }
+                let mut path = vec![root];
+                while self.peek() == Some(&Token::Dot) {
+                    self.advance();
+                    let column = self.column();
+                    match self.advance() {
+                        Some(Token::Ident(segment)) => path.push(segment),
+                        _ => return Err((column, "expected field name after '.'".to_string())),
+                    }
+                }
+                Ok(Expr::Path(path))
+            }
+            Some(Token::LParen) => {
+                let expr = self.nested(|parser| parser.or())?;
+                let column = self.column();
+                match self.advance() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err((column, "expected ')'".to_string())),
+                }
+            }
+            Some(token) => Err((column, format!("unexpected {:?}", token))),
+            None => Err((column, "unexpected end of condition".to_string())),
+        }
+    }
+}
+
+fn collect_paths<'a>(expr: &'a Expr, paths: &mut Vec<&'a [String]>) {
+    match expr {
+        Expr::Literal(_) => {}
+        Expr::Path(path) => paths.push(path),
+        Expr::Not(inner) => collect_paths(inner, paths),
+        Expr::And(left, right) | Expr::Or(left, right) | Expr::Compare(left, _, right) => {
+            collect_paths(left, paths);
+            collect_paths(right, paths);
+        }
+    }
+}
+
+fn eval(expr: &Expr, context: &Value) -> Value {
+    match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Path(path) => path.iter().fold(context, |value, segment| &value[segment.as_str()]).clone(),
+        Expr::Not(inner) => Value::Bool(!truthy(&eval(inner, context))),
+        Expr::And(left, right) => Value::Bool(truthy(&eval(left, context)) && truthy(&eval(right, context))),
+        Expr::Or(left, right) => Value::Bool(truthy(&eval(left, context)) || truthy(&eval(right, context))),
+        Expr::Compare(left, op, right) => Value::Bool(compare(&eval(left, context), *op, &eval(right, context))),
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    use std::cmp::Ordering;
+
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match op {
+        CompareOp::Eq => ordering.map_or(left == right, |o| o == Ordering::Equal),
+        CompareOp::Ne => ordering.map_or(left != right, |o| o != Ordering::Equal),
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().map_or(false, |n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_evaluates_event_and_metrics_paths() {
+        let condition = Condition::parse(r#"event.severity == "High" && metrics.tasks.failed_tasks > 10"#).unwrap();
+        let context = |severity: &str, failed: u64| json!({
+            "event": { "severity": severity, "data": {} },
+            "metrics": { "tasks": { "failed_tasks": failed } },
+        });
+        assert!(condition.evaluate(&context("High", 11)));
+        assert!(!condition.evaluate(&context("High", 10)));
+        assert!(!condition.evaluate(&context("Low", 50)));
+
+        let missing = Condition::parse("!(event.data.retry >= 3) || event.data.name != \"x\"").unwrap();
+        assert!(missing.evaluate(&json!({ "event": {} })));
+    }
+
+    #[test]
+    fn test_malformed_conditions_report_column() {
+        let cases = [
+            ("event.severity ==", "end of condition at column 18"),
+            ("event.severity = \"High\"", "unexpected character '=' at column 16"),
+            ("(event.x > 1", "expected ')' at column 13"),
+            ("task.x > 1", "unknown root 'task'"),
+            ("event. > 1", "expected field name after '.' at column 8"),
+            ("event.x == \"open", "unterminated string at column 12"),
+            ("event.x > 1 1", "unexpected Number(1.0) at column 13"),
+        ];
+        for (source, expected) in cases {
+            match Condition::parse(source) {
+                Err(OrchestratorError::ValidationError { message, .. }) => {
+                    assert!(message.contains(expected), "{:?}: {}", source, message)
+                }
+                other => panic!("{:?}: expected validation error, got {:?}", source, other),
+            }
+        }
+        assert!(Condition::parse(&format!("{}event.x{}", "(".repeat(1000), ")".repeat(1000))).is_err());
+    }
+
+    #[test]
+    fn test_task_conditions_use_parent_roots() {
+        let condition = Condition::parse_task_condition(r#"parents.fetch.status == "Completed" && parents.fetch.output.rows > 0"#).unwrap();
+        let paths: Vec<_> = condition.paths().into_iter().map(|path| path.join(".")).collect();
+        assert_eq!(paths, ["parents.fetch.status", "parents.fetch.output.rows"]);
+        assert!(condition.evaluate(&json!({ "parents": { "fetch": { "status": "Completed", "output": { "rows": 2 } } } })));
+
+        match Condition::parse_task_condition("event.severity == \"High\"") {
+            Err(OrchestratorError::ValidationError { field, message, .. }) => {
+                assert_eq!(field, "task.condition");
+                assert!(message.contains("unknown root 'event'"), "{}", message);
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+}
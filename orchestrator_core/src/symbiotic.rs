@@ -9,9 +9,14 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::clock::{Clock, SystemClock};
 use crate::errors::{OrchestratorError, Result};
-use crate::graph::{TaskId, TaskNode, TaskMesh};
+use crate::graph::{TaskId, TaskNode, TaskMesh, TaskPriority, TaskType};
 use crate::layers::{ExecutionLayer, TaskExecutionResult};
+use crate::metrics::SystemMetrics;
+use crate::rules::Condition;
+
+pub mod federation;
 
 /// Estado da consciência simbiótica
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,12 +53,30 @@ pub enum AwarenessLevel {
 /// Estado da mente coletiva
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollectiveState {
+    /// Fração do conhecimento anunciado pelos peers da federação já presente
+    /// localmente (ver [`federation`])
     pub synchronization_level: f64,
     pub coherence_index: f64,
     pub shared_insights: Vec<Insight>,
     pub collective_memory: Vec<CollectiveExperience>,
+    /// Sequência da última mudança relevante em `shared_insights`
+    #[serde(default)]
+    pub insight_seq: u64,
 }
 
+/// Confiança de um insight observado pela primeira vez
+const INSIGHT_INITIAL_CONFIDENCE: f64 = 0.5;
+/// Teto da confiança, alcançado apenas assintoticamente
+const INSIGHT_MAX_CONFIDENCE: f64 = 0.95;
+/// Fração da distância ao teto ganha a cada nova evidência
+const INSIGHT_REINFORCEMENT: f64 = 0.1;
+/// Meia-vida da confiança sem novas evidências
+const INSIGHT_HALF_LIFE_SECS: f64 = 600.0;
+/// Insights abaixo desta confiança são descartados
+const INSIGHT_CONFIDENCE_FLOOR: f64 = 0.1;
+/// Variação mínima de confiança para o insight ser republicado
+const INSIGHT_MATERIAL_CHANGE: f64 = 0.05;
+
 /// Insight reconhecido pelo sistema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Insight {
@@ -63,6 +86,100 @@ pub struct Insight {
     pub impact_score: f64,
     pub source: InsightSource,
     pub created_at: DateTime<Utc>,
+    /// Chave semântica: fonte + descrição normalizada
+    #[serde(default)]
+    pub key: String,
+    /// Número de observações que sustentam o insight
+    #[serde(default)]
+    pub evidence_count: u32,
+    /// Última atualização da confiança (reforço ou decaimento)
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+    /// Sequência da última publicação (ver `CollectiveState::insight_seq`)
+    #[serde(default)]
+    pub seq: u64,
+    /// Confiança na última publicação
+    #[serde(default)]
+    published_confidence: f64,
+}
+
+impl Insight {
+    /// Cria um insight com a confiança inicial
+    pub fn new(source: InsightSource, description: impl Into<String>, impact_score: f64, now: DateTime<Utc>) -> Self {
+        let description = description.into();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            key: Self::semantic_key(&source, &description),
+            description,
+            confidence: INSIGHT_INITIAL_CONFIDENCE,
+            impact_score,
+            source,
+            created_at: now,
+            evidence_count: 1,
+            updated_at: now,
+            seq: 0,
+            published_confidence: 0.0,
+        }
+    }
+
+    /// Fonte + descrição sem caixa, pontuação e números
+    pub fn semantic_key(source: &InsightSource, description: &str) -> String {
+        let words: Vec<String> = description
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| if word.chars().all(|c| c.is_ascii_digit()) { "#".to_string() } else { word.to_lowercase() })
+            .collect();
+        format!("{:?}:{}", source, words.join(" "))
+    }
+
+    /// Aplica o decaimento exponencial desde a última atualização
+    fn decay(&mut self, now: DateTime<Utc>) {
+        let elapsed = (now - self.updated_at).num_milliseconds().max(0) as f64 / 1000.0;
+        if elapsed > 0.0 {
+            self.confidence *= 0.5f64.powf(elapsed / INSIGHT_HALF_LIFE_SECS);
+            self.updated_at = now;
+        }
+    }
+
+    /// Incorpora uma nova evidência
+    fn reinforce(&mut self, now: DateTime<Utc>) {
+        self.decay(now);
+        self.confidence += (INSIGHT_MAX_CONFIDENCE - self.confidence) * INSIGHT_REINFORCEMENT;
+        self.evidence_count = self.evidence_count.saturating_add(1);
+        self.updated_at = now;
+    }
+}
+
+impl CollectiveState {
+    /// Registra uma observação, reforçando o insight equivalente se existir
+    fn observe_insight(&mut self, candidate: Insight) {
+        match self.shared_insights.iter_mut().find(|insight| insight.key == candidate.key) {
+            Some(existing) => existing.reinforce(candidate.updated_at),
+            None => self.shared_insights.push(candidate),
+        }
+    }
+
+    /// Decai os insights, descarta os fracos e publica as mudanças relevantes
+    fn refresh_insights(&mut self, now: DateTime<Utc>) {
+        self.shared_insights.retain_mut(|insight| {
+            insight.decay(now);
+            insight.confidence >= INSIGHT_CONFIDENCE_FLOOR
+        });
+        for insight in &mut self.shared_insights {
+            if insight.seq == 0 || (insight.confidence - insight.published_confidence).abs() >= INSIGHT_MATERIAL_CHANGE {
+                self.insight_seq += 1;
+                insight.seq = self.insight_seq;
+                insight.published_confidence = insight.confidence;
+            }
+        }
+    }
+
+    /// Insights novos ou com mudança relevante após a sequência `since`
+    fn insights_since(&self, since: u64) -> Vec<Insight> {
+        let mut insights: Vec<Insight> = self.shared_insights.iter().filter(|insight| insight.seq > since).cloned().collect();
+        insights.sort_by_key(|insight| insight.seq);
+        insights
+    }
 }
 
 /// Fonte do insight
@@ -98,6 +215,9 @@ pub struct Pattern {
     pub last_seen: DateTime<Utc>,
     pub triggers: Vec<String>,
     pub effects: Vec<String>,
+    /// Instâncias da federação que contribuíram para o padrão
+    #[serde(default)]
+    pub contributors: Vec<String>,
 }
 
 /// Tipos de padrões
@@ -118,6 +238,9 @@ pub struct KnowledgeBase {
     pub relationships: Vec<Relationship>,
     pub rules: Vec<Rule>,
     pub heuristics: Vec<Heuristic>,
+    /// Como as regras ativas são avaliadas a cada evento
+    #[serde(default)]
+    pub rule_match_mode: RuleMatchMode,
 }
 
 /// Conceito na base de conhecimento
@@ -142,14 +265,58 @@ pub struct Relationship {
 }
 
 /// Regra do sistema
+///
+/// `condition` segue a linguagem de [`crate::rules`]; quando verdadeira para
+/// um evento, a regra emite `action`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rule {
     pub id: String,
     pub condition: String,
-    pub action: String,
+    pub action: ActionSpec,
+    /// Regras de maior prioridade são avaliadas primeiro
     pub priority: u32,
     pub active: bool,
+    /// Fração dos resultados registrados que foram bem-sucedidos
     pub success_rate: f64,
+    /// Número de eventos em que a regra disparou
+    #[serde(default)]
+    pub fire_count: u64,
+    /// Número de resultados registrados em `record_rule_outcome`
+    #[serde(default)]
+    pub outcome_count: u64,
+}
+
+impl Rule {
+    /// Cria uma regra ativa
+    pub fn new(condition: impl Into<String>, action: ActionSpec, priority: u32) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            condition: condition.into(),
+            action,
+            priority,
+            active: true,
+            success_rate: 0.0,
+            fire_count: 0,
+            outcome_count: 0,
+        }
+    }
+}
+
+/// Modo de avaliação das regras
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleMatchMode {
+    /// Apenas a regra de maior prioridade que casar dispara
+    #[default]
+    FirstMatch,
+    /// Todas as regras que casarem disparam
+    AllMatches,
+}
+
+/// Ação emitida por uma regra
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggeredAction {
+    pub rule_id: String,
+    pub action: ActionSpec,
 }
 
 /// Heurística
@@ -217,16 +384,27 @@ pub struct ConsolidatedLearning {
     pub applicability: Vec<String>,
     pub confidence: f64,
     pub derived_from: Vec<String>, // IDs dos episódios
+    /// Instâncias da federação que contribuíram para o aprendizado
+    #[serde(default)]
+    pub contributors: Vec<String>,
 }
 
+/// Validade de uma recomendação a partir da emissão
+pub const RECOMMENDATION_TTL_MINUTES: i64 = 30;
+
 /// Sistema de consciência simbiótica principal
 #[derive(Debug)]
 pub struct SymbioticConsciousness {
     state: Arc<RwLock<ConsciousnessState>>,
+    /// Recomendações emitidas e ainda não aplicadas, por ID
+    recommendations: Arc<RwLock<HashMap<String, Recommendation>>>,
     evolution_engine: EvolutionEngine,
     pattern_recognizer: PatternRecognizer,
     decision_maker: DecisionMaker,
     memory_manager: MemoryManager,
+    clock: Arc<dyn Clock>,
+    /// Últimas métricas do sistema, expostas às condições das regras
+    metrics: Arc<RwLock<serde_json::Value>>,
 }
 
 impl SymbioticConsciousness {
@@ -235,10 +413,11 @@ impl SymbioticConsciousness {
         let initial_state = ConsciousnessState {
             awareness_level: AwarenessLevel::Basic,
             collective_state: CollectiveState {
-                synchronization_level: 0.5,
+                synchronization_level: 0.0,
                 coherence_index: 0.5,
                 shared_insights: Vec::new(),
                 collective_memory: Vec::new(),
+                insight_seq: 0,
             },
             recognized_patterns: Vec::new(),
             knowledge_base: KnowledgeBase {
@@ -246,6 +425,7 @@ impl SymbioticConsciousness {
                 relationships: Vec::new(),
                 rules: Vec::new(),
                 heuristics: Vec::new(),
+                rule_match_mode: RuleMatchMode::default(),
             },
             episodic_memory: EpisodicMemory {
                 episodes: VecDeque::new(),
@@ -257,16 +437,26 @@ impl SymbioticConsciousness {
 
         Self {
             state: Arc::new(RwLock::new(initial_state)),
+            recommendations: Arc::new(RwLock::new(HashMap::new())),
             evolution_engine: EvolutionEngine::new(),
             pattern_recognizer: PatternRecognizer::new(),
             decision_maker: DecisionMaker::new(),
             memory_manager: MemoryManager::new(),
+            clock: Arc::new(SystemClock),
+            metrics: Arc::new(RwLock::new(serde_json::Value::Null)),
         }
     }
 
+    /// Usa o relógio indicado para o ciclo de vida dos insights
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Processa evento do sistema
     pub async fn process_event(&self, event: SystemEvent) -> Result<ConsciousnessResponse> {
         let mut state = self.state.write().await;
+        let insight_seq = state.collective_state.insight_seq;
         
         // Reconhece padrões no evento
         let patterns = self.pattern_recognizer.analyze_event(&event, &state).await?;
@@ -288,45 +478,222 @@ impl SymbioticConsciousness {
         
         state.last_updated = Utc::now();
         
+        self.extract_insights(&mut state, &event).await;
+        let triggered_actions = self.evaluate_rules(&mut state, &event).await;
+        
         Ok(ConsciousnessResponse {
             decision,
-            insights: self.extract_insights(&state).await,
+            triggered_actions,
+            insights: state.collective_state.insights_since(insight_seq),
+            insight_seq: state.collective_state.insight_seq,
             awareness_level: state.awareness_level.clone(),
             recommendations: self.generate_recommendations(&state).await,
         })
     }
     
-    /// Extrai insights do estado atual
-    async fn extract_insights(&self, state: &ConsciousnessState) -> Vec<Insight> {
+    /// Extrai insights do evento e atualiza o ciclo de vida dos existentes
+    async fn extract_insights(&self, state: &mut ConsciousnessState, _event: &SystemEvent) {
+        let now = self.clock.wall();
         // Implementação simplificada
-        vec![
-            Insight {
-                id: uuid::Uuid::new_v4().to_string(),
-                description: "System performance optimization opportunity detected".to_string(),
-                confidence: 0.8,
-                impact_score: 0.7,
-                source: InsightSource::PerformanceAnalysis,
-                created_at: Utc::now(),
+        state.collective_state.observe_insight(Insight::new(
+            InsightSource::PerformanceAnalysis,
+            "System performance optimization opportunity detected",
+            0.7,
+            now,
+        ));
+        state.collective_state.refresh_insights(now);
+    }
+    
+    /// Avalia as regras ativas contra o evento, em ordem de prioridade
+    async fn evaluate_rules(&self, state: &mut ConsciousnessState, event: &SystemEvent) -> Vec<TriggeredAction> {
+        if !state.knowledge_base.rules.iter().any(|rule| rule.active) {
+            return Vec::new();
+        }
+        let context = serde_json::json!({
+            "event": rule_context(event),
+            "metrics": self.metrics.read().await.clone(),
+        });
+        
+        let mode = state.knowledge_base.rule_match_mode;
+        let rules = &mut state.knowledge_base.rules;
+        let mut order: Vec<usize> = (0..rules.len()).filter(|&i| rules[i].active).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(rules[i].priority));
+        
+        let mut triggered = Vec::new();
+        for i in order {
+            let rule = &mut rules[i];
+            // Condições foram validadas em `add_rule`; estados antigos podem não ter sido
+            let matched = match Condition::parse(&rule.condition) {
+                Ok(condition) => condition.evaluate(&context),
+                Err(e) => {
+                    tracing::warn!("Skipping rule {} with invalid condition: {}", rule.id, e);
+                    false
+                }
+            };
+            if !matched {
+                continue;
             }
-        ]
+            rule.fire_count += 1;
+            triggered.push(TriggeredAction { rule_id: rule.id.clone(), action: rule.action.clone() });
+            if mode == RuleMatchMode::FirstMatch {
+                break;
+            }
+        }
+        triggered
+    }
+    
+    /// Adiciona uma regra à base de conhecimento
+    ///
+    /// A condição é compilada aqui: erros de sintaxe indicam a coluna.
+    pub async fn add_rule(&self, rule: Rule) -> Result<String> {
+        Condition::parse(&rule.condition)?;
+        let mut state = self.state.write().await;
+        if state.knowledge_base.rules.iter().any(|existing| existing.id == rule.id) {
+            return Err(OrchestratorError::validation("rule.id", "unique", &rule.id, "duplicate rule id"));
+        }
+        let id = rule.id.clone();
+        state.knowledge_base.rules.push(rule);
+        Ok(id)
+    }
+    
+    /// Regras cadastradas, em ordem de avaliação
+    pub async fn list_rules(&self) -> Vec<Rule> {
+        let mut rules = self.state.read().await.knowledge_base.rules.clone();
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+        rules
+    }
+    
+    /// Ativa ou desativa uma regra
+    pub async fn set_rule_active(&self, id: &str, active: bool) -> Result<()> {
+        self.with_rule(id, |rule| rule.active = active).await
+    }
+    
+    /// Define se dispara só a primeira regra que casar ou todas
+    pub async fn set_rule_match_mode(&self, mode: RuleMatchMode) {
+        self.state.write().await.knowledge_base.rule_match_mode = mode;
+    }
+    
+    /// Registra o resultado da ação emitida por uma regra
+    pub async fn record_rule_outcome(&self, id: &str, success: bool) -> Result<()> {
+        self.with_rule(id, |rule| {
+            rule.outcome_count += 1;
+            let sample = if success { 1.0 } else { 0.0 };
+            rule.success_rate += (sample - rule.success_rate) / rule.outcome_count as f64;
+        }).await
+    }
+    
+    async fn with_rule(&self, id: &str, update: impl FnOnce(&mut Rule)) -> Result<()> {
+        let mut state = self.state.write().await;
+        let rule = state.knowledge_base.rules.iter_mut().find(|rule| rule.id == id)
+            .ok_or_else(|| OrchestratorError::InvalidState(format!("Unknown rule {}", id)))?;
+        update(rule);
+        Ok(())
+    }
+    
+    /// Atualiza as métricas visíveis às condições (`metrics.*`)
+    pub async fn observe_metrics(&self, metrics: &SystemMetrics) {
+        match serde_json::to_value(metrics) {
+            Ok(value) => *self.metrics.write().await = value,
+            Err(e) => tracing::warn!("Failed to expose metrics to rules: {}", e),
+        }
+    }
+    
+    /// Insights novos ou alterados de forma relevante após `since`
+    ///
+    /// Aplica o decaimento pendente antes da consulta. Com `None`, retorna
+    /// todos os insights vivos.
+    pub async fn drain_insights(&self, since: Option<u64>) -> Vec<Insight> {
+        let mut state = self.state.write().await;
+        state.collective_state.refresh_insights(self.clock.wall());
+        state.collective_state.insights_since(since.unwrap_or(0))
     }
     
     /// Gera recomendações baseadas no estado
     async fn generate_recommendations(&self, state: &ConsciousnessState) -> Vec<Recommendation> {
-        vec![
-            Recommendation {
-                id: uuid::Uuid::new_v4().to_string(),
-                title: "Optimize task scheduling".to_string(),
-                description: "Consider using quantum simulation layer for CPU-intensive tasks".to_string(),
-                priority: RecommendationPriority::Medium,
-                confidence: 0.75,
-                estimated_impact: 0.6,
-                actions: vec![
-                    "Switch to QuantumSim layer for large tasks".to_string(),
-                    "Implement adaptive load balancing".to_string(),
-                ],
-            }
-        ]
+        let recommendation = Recommendation::new(
+            "Optimize task scheduling",
+            "Consider using quantum simulation layer for CPU-intensive tasks",
+            vec![ActionSpec::SwitchLayer {
+                task_filter: TaskFilter { task_type: Some(TaskType::ExtraLarge), ..Default::default() },
+                layer: ExecutionLayer::QuantumSim,
+            }],
+        )
+        .with_priority(RecommendationPriority::Medium)
+        .with_scores(0.75, 0.6);
+        vec![self.recommend(recommendation).await]
+    }
+    
+    /// Registra uma recomendação para aplicação posterior
+    ///
+    /// Enquanto houver uma recomendação válida com o mesmo título, ela é
+    /// devolvida no lugar da nova, evitando duplicatas a cada evento.
+    pub async fn recommend(&self, recommendation: Recommendation) -> Recommendation {
+        let now = Utc::now();
+        let mut recommendations = self.recommendations.write().await;
+        recommendations.retain(|_, existing| !existing.is_expired(now));
+        
+        if let Some(existing) = recommendations.values().find(|existing| existing.title == recommendation.title) {
+            return existing.clone();
+        }
+        recommendations.insert(recommendation.id.clone(), recommendation.clone());
+        recommendation
+    }
+    
+    /// Recomendação pendente pelo ID (inclusive expiradas ainda não removidas)
+    pub async fn recommendation(&self, id: &str) -> Option<Recommendation> {
+        self.recommendations.read().await.get(id).cloned()
+    }
+    
+    /// Registra a aceitação ou rejeição de uma recomendação
+    ///
+    /// O resultado é processado como evento, alimentando padrões, memória e
+    /// evolução. Recomendações aceitas deixam de estar pendentes.
+    pub async fn record_recommendation_feedback(&self, id: &str, accepted: bool, detail: &str) -> Result<()> {
+        let recommendation = if accepted {
+            self.recommendations.write().await.remove(id)
+        } else {
+            self.recommendations.read().await.get(id).cloned()
+        };
+        
+        let mut data = serde_json::json!({
+            "recommendation_id": id,
+            "detail": detail,
+        });
+        if let Some(recommendation) = recommendation {
+            data["title"] = serde_json::Value::String(recommendation.title);
+            data["confidence"] = serde_json::json!(recommendation.confidence);
+        }
+        
+        let event_type = if accepted { "recommendation_accepted" } else { "recommendation_rejected" };
+        let event = SystemEvent::new(EventType::from_name(event_type), None, data)
+            .with_source(EVENT_SOURCE);
+        self.process_event(event).await?;
+        Ok(())
+    }
+    
+    /// Decide a camada de execução de uma tarefa
+    ///
+    /// `candidates` vem em ordem de preferência e contém apenas camadas
+    /// disponíveis. O roteamento é processado como um evento comum, alimentando
+    /// padrões, memória episódica e evolução.
+    pub async fn route_task(&self, task: &TaskNode, candidates: &[ExecutionLayer]) -> Result<Decision> {
+        let chosen = candidates.first().ok_or_else(|| {
+            OrchestratorError::InvalidState(format!("No execution layer available for task {}", task.id))
+        })?;
+        
+        let data = serde_json::json!({
+            "task_id": task.id.to_string(),
+            "task_name": task.name,
+            "candidates": candidates.iter().map(|l| l.as_str()).collect::<Vec<_>>(),
+        });
+        let event = SystemEvent::new(EventType::Custom("task_routing".to_string()), Some(task.id), data)
+            .with_source(EVENT_SOURCE);
+        
+        let mut decision = self.process_event(event).await?.decision;
+        decision.decision_type = "task_routing".to_string();
+        decision.parameters.insert("layer".to_string(), serde_json::Value::String(chosen.as_str().to_string()));
+        decision.rationale = format!("Preferred available layer among {} candidates", candidates.len());
+        Ok(decision)
     }
     
     /// Obtém estado atual da consciência
@@ -342,30 +709,41 @@ impl SymbioticConsciousness {
     }
 }
 
-/// Evento do sistema
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SystemEvent {
-    pub event_type: String,
-    pub data: HashMap<String, serde_json::Value>,
-    pub timestamp: DateTime<Utc>,
-    pub source: String,
-    pub severity: EventSeverity,
-}
+pub use system_events::{EventSeverity, EventType};
 
-/// Severidade do evento
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum EventSeverity {
-    Low,
-    Medium,
-    High,
-    Critical,
+/// Evento do sistema, no formato compartilhado com o TaskMesh
+pub type SystemEvent = system_events::SystemEvent<TaskId>;
+
+/// Origem dos eventos emitidos por este crate
+pub const EVENT_SOURCE: &str = "orchestrator_core";
+
+/// Evento como as regras o enxergam em `event.*`
+///
+/// Mantém o formato anterior à unificação: `event_type` é o nome textual
+/// (`"task_failed"`) e `timestamp` é RFC 3339, para que condições existentes
+/// continuem casando.
+fn rule_context(event: &SystemEvent) -> serde_json::Value {
+    serde_json::json!({
+        "event_type": event.event_type.name(),
+        "timestamp": event.datetime().to_rfc3339(),
+        "severity": event.severity,
+        "source": event.source,
+        "task_id": event.task_id,
+        "sequence": event.sequence,
+        "data": event.data,
+    })
 }
 
 /// Resposta da consciência
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsciousnessResponse {
     pub decision: Decision,
+    /// Ações emitidas pelas regras que casaram com o evento
+    pub triggered_actions: Vec<TriggeredAction>,
+    /// Insights novos ou alterados de forma relevante por este evento
     pub insights: Vec<Insight>,
+    /// Sequência para consultas posteriores em `drain_insights`
+    pub insight_seq: u64,
     pub awareness_level: AwarenessLevel,
     pub recommendations: Vec<Recommendation>,
 }
@@ -398,7 +776,83 @@ pub struct Recommendation {
     pub priority: RecommendationPriority,
     pub confidence: f64,
     pub estimated_impact: f64,
-    pub actions: Vec<String>,
+    /// Ações executáveis por `OrchestratorCore::apply_recommendation`
+    pub actions: Vec<ActionSpec>,
+    /// Após este instante a recomendação não pode mais ser aplicada
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Recommendation {
+    /// Cria uma recomendação válida por `RECOMMENDATION_TTL_MINUTES`
+    pub fn new(title: impl Into<String>, description: impl Into<String>, actions: Vec<ActionSpec>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: title.into(),
+            description: description.into(),
+            priority: RecommendationPriority::Medium,
+            confidence: 0.5,
+            estimated_impact: 0.5,
+            actions,
+            expires_at: Utc::now() + chrono::Duration::minutes(RECOMMENDATION_TTL_MINUTES),
+        }
+    }
+    
+    pub fn with_priority(mut self, priority: RecommendationPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+    
+    pub fn with_scores(mut self, confidence: f64, estimated_impact: f64) -> Self {
+        self.confidence = confidence;
+        self.estimated_impact = estimated_impact;
+        self
+    }
+    
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+    
+    /// Verifica se a recomendação expirou
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Ação executável de uma recomendação
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionSpec {
+    /// Roteia as tarefas que casam com o filtro para a camada indicada
+    SwitchLayer { task_filter: TaskFilter, layer: ExecutionLayer },
+    /// Ajusta o número máximo de tarefas em paralelo
+    AdjustConcurrency { delta: i32 },
+    /// Habilita execução redundante para tarefas com a tag
+    EnableHedging { tag: String },
+    /// Cria um snapshot do grafo de tarefas
+    CreateCheckpoint,
+    /// Ação livre, interpretada por integrações externas
+    Custom(serde_json::Value),
+}
+
+/// Seleção de tarefas; campos ausentes aceitam qualquer valor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<TaskType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<TaskPriority>,
+}
+
+impl TaskFilter {
+    /// Verifica se a tarefa satisfaz todos os critérios do filtro
+    pub fn matches(&self, task: &TaskNode) -> bool {
+        self.tag.as_ref().map_or(true, |tag| task.tags.contains(tag))
+            && self.task_type.as_ref().map_or(true, |task_type| &task.task_type == task_type)
+            && self.priority.as_ref().map_or(true, |priority| &task.priority == priority)
+    }
 }
 
 /// Prioridade da recomendação
@@ -434,14 +888,11 @@ impl EvolutionEngine {
         &self,
         state: &mut ConsciousnessState,
         event: &SystemEvent,
-        decision: &Decision,
+        _decision: &Decision,
     ) {
         // Ajusta nível de consciência baseado na complexidade do evento
         self.adjust_awareness_level(state, event).await;
         
-        // Atualiza sincronização coletiva
-        self.update_collective_synchronization(state, decision).await;
-        
         // Consolida aprendizados
         self.consolidate_learnings(state).await;
     }
@@ -488,13 +939,6 @@ impl EvolutionEngine {
         }
     }
     
-    async fn update_collective_synchronization(&self, state: &mut ConsciousnessState, decision: &Decision) {
-        // Atualiza sincronização baseado na qualidade da decisão
-        let decision_quality = decision.confidence;
-        state.collective_state.synchronization_level = 
-            (state.collective_state.synchronization_level * 0.9 + decision_quality * 0.1).min(1.0);
-    }
-    
     async fn consolidate_learnings(&self, state: &mut ConsciousnessState) {
         // Consolida episódios em aprendizados quando há suficientes episódios
         if state.episodic_memory.episodes.len() >= 10 {
@@ -508,6 +952,7 @@ impl EvolutionEngine {
                     .take(5)
                     .map(|e| e.id.clone())
                     .collect(),
+                contributors: Vec::new(),
             };
             
             state.episodic_memory.consolidated_learnings.push(learning);
@@ -555,7 +1000,7 @@ impl PatternRecognizer {
             .iter()
             .filter(|ep| {
                 ep.context.external_factors.get("event_type") 
-                    == Some(&serde_json::Value::String(event.event_type.clone()))
+                    == Some(&serde_json::Value::String(event.event_type.name().to_string()))
             })
             .count();
             
@@ -568,8 +1013,9 @@ impl PatternRecognizer {
                 confidence: 0.8,
                 frequency: similar_events as u64,
                 last_seen: Utc::now(),
-                triggers: vec![event.event_type.clone()],
+                triggers: vec![event.event_type.name().to_string()],
                 effects: vec!["Resource usage spike".to_string()],
+                contributors: Vec::new(),
             })
         } else {
             None
@@ -675,8 +1121,8 @@ impl MemoryManager {
                     ("memory".to_string(), 0.4),
                 ]),
                 external_factors: HashMap::from([
-                    ("event_type".to_string(), serde_json::Value::String(event.event_type.clone())),
-                    ("timestamp".to_string(), serde_json::Value::String(event.timestamp.to_rfc3339())),
+                    ("event_type".to_string(), serde_json::Value::String(event.event_type.name().to_string())),
+                    ("timestamp".to_string(), serde_json::Value::String(event.datetime().to_rfc3339())),
                 ]),
                 goals: vec!["optimize_performance".to_string(), "minimize_latency".to_string()],
             },
@@ -696,7 +1142,7 @@ impl MemoryManager {
                     impact: 0.5,
                 }
             ],
-            timestamp: event.timestamp,
+            timestamp: event.datetime(),
             importance: self.calculate_importance(event, state).await,
         }
     }
@@ -739,6 +1185,17 @@ impl Default for SymbioticConsciousness {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_consciousness_state_fixture_round_trip() {
+        let fixture = include_str!("../fixtures/compat/consciousness_state.json");
+        let state: ConsciousnessState = serde_json::from_str(fixture).unwrap();
+        assert_eq!(state.awareness_level, AwarenessLevel::Cognitive);
+        assert_eq!(state.knowledge_base.rule_match_mode, RuleMatchMode::AllMatches);
+
+        let expected: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        assert_eq!(serde_json::to_value(&state).unwrap(), expected);
+    }
+
     #[tokio::test]
     async fn test_consciousness_creation() {
         let consciousness = SymbioticConsciousness::new();
@@ -752,13 +1209,9 @@ mod tests {
     async fn test_event_processing() {
         let consciousness = SymbioticConsciousness::new();
         
-        let event = SystemEvent {
-            event_type: "task_completion".to_string(),
-            data: HashMap::new(),
-            timestamp: Utc::now(),
-            source: "orchestrator".to_string(),
-            severity: EventSeverity::Medium,
-        };
+        let event = SystemEvent::new(EventType::Custom("task_completion".to_string()), None, serde_json::json!({}))
+            .with_severity(EventSeverity::Medium)
+            .with_source("orchestrator");
         
         let response = consciousness.process_event(event).await;
         assert!(response.is_ok());
@@ -783,5 +1236,88 @@ mod tests {
         let evolved_state = consciousness.get_state().await;
         assert_eq!(evolved_state.awareness_level, AwarenessLevel::Cognitive);
     }
+    
+    #[tokio::test]
+    async fn test_repeated_events_reinforce_single_insight_then_decay() {
+        use crate::clock::MockClock;
+        
+        let clock = Arc::new(MockClock::new());
+        let consciousness = SymbioticConsciousness::new().with_clock(clock.clone());
+        let event = SystemEvent::new(EventType::Custom("task_completion".to_string()), None, serde_json::json!({}))
+            .with_source("orchestrator");
+        
+        let mut confidence = 0.0;
+        let mut published = 0;
+        for _ in 0..100 {
+            let response = consciousness.process_event(event.clone()).await.unwrap();
+            published += response.insights.len();
+            
+            let insights = consciousness.get_state().await.collective_state.shared_insights;
+            assert_eq!(insights.len(), 1);
+            assert!(insights[0].confidence > confidence);
+            assert!(insights[0].confidence < INSIGHT_MAX_CONFIDENCE);
+            confidence = insights[0].confidence;
+        }
+        assert!(published < 100, "only material changes are republished");
+        assert_eq!(consciousness.get_state().await.collective_state.shared_insights[0].evidence_count, 100);
+        
+        let seq = consciousness.drain_insights(None).await[0].seq;
+        assert!(consciousness.drain_insights(Some(seq)).await.is_empty());
+        
+        // Sem novas evidências a confiança cai até o descarte
+        loop {
+            clock.advance(std::time::Duration::from_secs(300));
+            let insights = consciousness.drain_insights(None).await;
+            let Some(insight) = insights.first() else { break };
+            assert!(insight.confidence < confidence);
+            confidence = insight.confidence;
+        }
+        assert!(confidence < INSIGHT_CONFIDENCE_FLOOR * 2.0);
+    }
+    
+    #[tokio::test]
+    async fn test_rule_fires_once_per_critical_event() {
+        let consciousness = SymbioticConsciousness::new();
+        let event = |severity: EventSeverity| {
+            SystemEvent::new(EventType::TaskFailed, None, serde_json::json!({}))
+                .with_severity(severity)
+                .with_source("orchestrator")
+        };
+        
+        let bad = consciousness.add_rule(Rule::new("event.severity == ", ActionSpec::CreateCheckpoint, 1)).await;
+        assert!(matches!(bad, Err(OrchestratorError::ValidationError { message, .. }) if message.contains("column 19")));
+        
+        let checkpoint = consciousness.add_rule(
+            Rule::new(r#"event.severity == "Critical""#, ActionSpec::CreateCheckpoint, 10)
+        ).await.unwrap();
+        let concurrency = consciousness.add_rule(
+            Rule::new(r#"event.event_type == "task_failed""#, ActionSpec::AdjustConcurrency { delta: -1 }, 5)
+        ).await.unwrap();
+        
+        let mut emitted = 0;
+        for severity in [EventSeverity::Critical, EventSeverity::Low, EventSeverity::Critical] {
+            let response = consciousness.process_event(event(severity)).await.unwrap();
+            emitted += response.triggered_actions.iter().filter(|t| t.action == ActionSpec::CreateCheckpoint).count();
+            // Primeira regra que casa vence
+            assert_eq!(response.triggered_actions.len(), 1);
+        }
+        assert_eq!(emitted, 2);
+        
+        consciousness.set_rule_match_mode(RuleMatchMode::AllMatches).await;
+        let response = consciousness.process_event(event(EventSeverity::Critical)).await.unwrap();
+        let fired: Vec<&str> = response.triggered_actions.iter().map(|t| t.rule_id.as_str()).collect();
+        assert_eq!(fired, vec![checkpoint.as_str(), concurrency.as_str()]);
+        
+        consciousness.set_rule_active(&checkpoint, false).await.unwrap();
+        let response = consciousness.process_event(event(EventSeverity::Critical)).await.unwrap();
+        assert_eq!(response.triggered_actions.len(), 1);
+        
+        consciousness.record_rule_outcome(&checkpoint, true).await.unwrap();
+        consciousness.record_rule_outcome(&checkpoint, false).await.unwrap();
+        let rules = consciousness.list_rules().await;
+        assert_eq!(rules[0].id, checkpoint);
+        assert_eq!(rules[0].fire_count, 3);
+        assert_eq!(rules[0].success_rate, 0.5);
+        assert!(consciousness.set_rule_active("missing", true).await.is_err());
+    }
 }
-
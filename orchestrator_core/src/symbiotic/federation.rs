@@ -0,0 +1,500 @@
+//! Federação entre instâncias da consciência simbiótica
+//!
+//! Instâncias trocam aprendizados consolidados e padrões de alta confiança
+//! por um [`FederationTransport`] plugável: canal em processo para testes e
+//! instâncias no mesmo binário, ou HTTP push/pull entre nós. Cada item é
+//! identificado por uma chave semântica; itens com a mesma chave são
+//! mesclados ponderando pela confiança, e a lista de `contributors` registra
+//! a proveniência.
+//!
+//! `synchronization_level` passa a ser a fração das chaves anunciadas pelos
+//! peers que já existe localmente.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::{debug, warn};
+
+use super::{ConsciousnessState, ConsolidatedLearning, Pattern, SymbioticConsciousness};
+use crate::background::BackgroundTasks;
+use crate::errors::{OrchestratorError, Result};
+
+/// Configuração da federação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FederationConfig {
+    /// Identificador desta instância, registrado como contribuidor
+    pub instance_id: String,
+    /// Intervalo entre rodadas de gossip
+    pub gossip_interval_secs: u64,
+    /// Origens aceitas; vazio aceita qualquer peer
+    pub allowed_peers: Vec<String>,
+    /// Tamanho máximo de um payload serializado, enviado ou recebido
+    pub max_payload_bytes: usize,
+    /// Confiança mínima para um padrão ser compartilhado
+    pub min_pattern_confidence: f64,
+}
+
+impl Default for FederationConfig {
+    fn default() -> Self {
+        Self {
+            instance_id: uuid::Uuid::new_v4().to_string(),
+            gossip_interval_secs: 30,
+            allowed_peers: Vec::new(),
+            max_payload_bytes: 256 * 1024,
+            min_pattern_confidence: 0.8,
+        }
+    }
+}
+
+impl FederationConfig {
+    pub fn new(instance_id: impl Into<String>) -> Self {
+        Self { instance_id: instance_id.into(), ..Self::default() }
+    }
+}
+
+/// Conhecimento trocado em uma rodada de gossip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FederationPayload {
+    pub origin: String,
+    pub sent_at: DateTime<Utc>,
+    pub learnings: Vec<ConsolidatedLearning>,
+    pub patterns: Vec<Pattern>,
+    /// Chaves de todo o conhecimento compartilhável da origem, inclusive o
+    /// que não coube no payload
+    pub advertised: Vec<String>,
+}
+
+/// Chave semântica de um aprendizado
+pub fn learning_key(learning: &ConsolidatedLearning) -> String {
+    format!("learning:{}", learning.summary)
+}
+
+/// Chave semântica de um padrão
+pub fn pattern_key(pattern: &Pattern) -> String {
+    format!("pattern:{:?}:{}", pattern.pattern_type, pattern.name)
+}
+
+/// Confiança combinada, ponderada pela própria confiança de cada lado
+fn merge_confidence(local: f64, remote: f64) -> f64 {
+    let total = local + remote;
+    if total <= 0.0 {
+        return 0.0;
+    }
+    ((local * local + remote * remote) / total).clamp(0.0, 1.0)
+}
+
+fn merge_contributors(target: &mut Vec<String>, source: &[String]) {
+    for contributor in source {
+        if !target.contains(contributor) {
+            target.push(contributor.clone());
+        }
+    }
+}
+
+/// Transporte dos payloads serializados
+#[async_trait]
+pub trait FederationTransport: Send + Sync + std::fmt::Debug {
+    /// Envia um payload aos peers
+    async fn push(&self, payload: Vec<u8>) -> Result<()>;
+    /// Coleta os payloads recebidos desde a última chamada
+    async fn pull(&self) -> Result<Vec<Vec<u8>>>;
+}
+
+/// Transporte em processo sobre canais tokio
+#[derive(Debug)]
+pub struct ChannelTransport {
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+    rx: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+impl ChannelTransport {
+    /// Par de transportes conectados entre si
+    pub fn pair() -> (Self, Self) {
+        let (a_tx, a_rx) = mpsc::unbounded_channel();
+        let (b_tx, b_rx) = mpsc::unbounded_channel();
+        (
+            Self { tx: a_tx, rx: Mutex::new(b_rx) },
+            Self { tx: b_tx, rx: Mutex::new(a_rx) },
+        )
+    }
+}
+
+#[async_trait]
+impl FederationTransport for ChannelTransport {
+    async fn push(&self, payload: Vec<u8>) -> Result<()> {
+        self.tx
+            .send(payload)
+            .map_err(|_| OrchestratorError::InvalidState("Federation peer disconnected".to_string()))
+    }
+
+    async fn pull(&self) -> Result<Vec<Vec<u8>>> {
+        let mut rx = self.rx.lock().await;
+        let mut received = Vec::new();
+        while let Ok(payload) = rx.try_recv() {
+            received.push(payload);
+        }
+        Ok(received)
+    }
+}
+
+/// Transporte HTTP: `POST`/`GET {peer}/federation/payload`
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: reqwest::Client,
+    peers: Vec<String>,
+}
+
+impl HttpTransport {
+    pub fn new(peers: Vec<String>) -> Self {
+        Self { client: reqwest::Client::new(), peers }
+    }
+
+    fn endpoint(peer: &str) -> String {
+        format!("{}/federation/payload", peer.trim_end_matches('/'))
+    }
+}
+
+#[async_trait]
+impl FederationTransport for HttpTransport {
+    async fn push(&self, payload: Vec<u8>) -> Result<()> {
+        for peer in &self.peers {
+            let result = self
+                .client
+                .post(Self::endpoint(peer))
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(payload.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            // Um peer fora do ar não impede o envio aos demais
+            if let Err(e) = result {
+                warn!("Federation push to {} failed: {}", peer, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn pull(&self) -> Result<Vec<Vec<u8>>> {
+        let mut received = Vec::new();
+        for peer in &self.peers {
+            let response = match self.client.get(Self::endpoint(peer)).send().await.and_then(|r| r.error_for_status()) {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("Federation pull from {} failed: {}", peer, e);
+                    continue;
+                }
+            };
+            received.push(response.bytes().await?.to_vec());
+        }
+        Ok(received)
+    }
+}
+
+/// Federação de uma consciência com seus peers
+#[derive(Debug)]
+pub struct Federation {
+    config: FederationConfig,
+    consciousness: Arc<SymbioticConsciousness>,
+    transport: Arc<dyn FederationTransport>,
+    /// Última lista de chaves anunciada por cada peer
+    advertised: RwLock<HashMap<String, HashSet<String>>>,
+}
+
+impl Federation {
+    pub fn new(
+        config: FederationConfig,
+        consciousness: Arc<SymbioticConsciousness>,
+        transport: Arc<dyn FederationTransport>,
+    ) -> Self {
+        Self { config, consciousness, transport, advertised: RwLock::new(HashMap::new()) }
+    }
+
+    pub fn config(&self) -> &FederationConfig {
+        &self.config
+    }
+
+    /// Monta o payload com o conhecimento compartilhável
+    ///
+    /// Se o payload exceder `max_payload_bytes`, os itens de menor confiança
+    /// são descartados; as chaves continuam anunciadas.
+    pub async fn export(&self) -> Result<FederationPayload> {
+        let state = self.consciousness.state.read().await;
+
+        let mut learnings: HashMap<String, ConsolidatedLearning> = HashMap::new();
+        for learning in &state.episodic_memory.consolidated_learnings {
+            let key = learning_key(learning);
+            if learnings.get(&key).map_or(true, |kept| kept.confidence < learning.confidence) {
+                learnings.insert(key, learning.clone());
+            }
+        }
+        let mut patterns: HashMap<String, Pattern> = HashMap::new();
+        for pattern in state
+            .recognized_patterns
+            .iter()
+            .filter(|p| p.confidence >= self.config.min_pattern_confidence)
+        {
+            let key = pattern_key(pattern);
+            if patterns.get(&key).map_or(true, |kept| kept.confidence < pattern.confidence) {
+                patterns.insert(key, pattern.clone());
+            }
+        }
+        drop(state);
+
+        let mut advertised: Vec<String> = learnings.keys().chain(patterns.keys()).cloned().collect();
+        advertised.sort();
+
+        let mut learnings: Vec<ConsolidatedLearning> = learnings.into_values().collect();
+        let mut patterns: Vec<Pattern> = patterns.into_values().collect();
+        for learning in &mut learnings {
+            merge_contributors(&mut learning.contributors, std::slice::from_ref(&self.config.instance_id));
+        }
+        for pattern in &mut patterns {
+            merge_contributors(&mut pattern.contributors, std::slice::from_ref(&self.config.instance_id));
+        }
+        // Maior confiança no fim: `pop` descarta primeiro os mais fracos
+        learnings.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        patterns.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+        let mut payload = FederationPayload {
+            origin: self.config.instance_id.clone(),
+            sent_at: self.consciousness.clock.wall(),
+            learnings,
+            patterns,
+            advertised,
+        };
+        while serde_json::to_vec(&payload)?.len() > self.config.max_payload_bytes {
+            let weakest_learning = payload.learnings.last().map(|l| l.confidence);
+            let weakest_pattern = payload.patterns.last().map(|p| p.confidence);
+            match (weakest_learning, weakest_pattern) {
+                (Some(l), Some(p)) if l <= p => {
+                    payload.learnings.pop();
+                }
+                (Some(_), None) => {
+                    payload.learnings.pop();
+                }
+                (_, Some(_)) => {
+                    payload.patterns.pop();
+                }
+                (None, None) => {
+                    return Err(OrchestratorError::ResourceLimitExceeded(format!(
+                        "Federation payload exceeds {} bytes even without items",
+                        self.config.max_payload_bytes
+                    )))
+                }
+            }
+        }
+        Ok(payload)
+    }
+
+    /// Envia o conhecimento local aos peers
+    pub async fn push(&self) -> Result<()> {
+        let payload = self.export().await?;
+        self.transport.push(serde_json::to_vec(&payload)?).await
+    }
+
+    /// Recebe e mescla os payloads dos peers; retorna quantos foram aceitos
+    pub async fn pull(&self) -> Result<usize> {
+        let mut accepted = 0;
+        for bytes in self.transport.pull().await? {
+            match self.receive(&bytes).await {
+                Ok(true) => accepted += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Federation payload rejected: {}", e),
+            }
+        }
+        if accepted > 0 {
+            self.update_synchronization().await;
+        }
+        Ok(accepted)
+    }
+
+    /// Valida e mescla um payload serializado
+    ///
+    /// Retorna `false` para payloads da própria instância.
+    pub async fn receive(&self, bytes: &[u8]) -> Result<bool> {
+        if bytes.len() > self.config.max_payload_bytes {
+            return Err(OrchestratorError::ResourceLimitExceeded(format!(
+                "Federation payload of {} bytes exceeds {}",
+                bytes.len(),
+                self.config.max_payload_bytes
+            )));
+        }
+        let payload: FederationPayload = serde_json::from_slice(bytes)?;
+        if payload.origin == self.config.instance_id {
+            return Ok(false);
+        }
+        if !self.config.allowed_peers.is_empty() && !self.config.allowed_peers.contains(&payload.origin) {
+            return Err(OrchestratorError::AuthorizationError(format!(
+                "Federation peer {} is not allowed",
+                payload.origin
+            )));
+        }
+
+        let mut state = self.consciousness.state.write().await;
+        let merged = merge_payload(&mut state, &payload);
+        state.last_updated = self.consciousness.clock.wall();
+        drop(state);
+
+        debug!("Merged {} federated items from {}", merged, payload.origin);
+        self.advertised
+            .write()
+            .await
+            .insert(payload.origin, payload.advertised.into_iter().collect());
+        Ok(true)
+    }
+
+    /// Uma rodada completa de gossip: envia e depois recebe
+    pub async fn gossip_once(&self) -> Result<usize> {
+        self.push().await?;
+        self.pull().await
+    }
+
+    /// Inicia o gossip periódico
+    pub fn start(self: &Arc<Self>, background: &BackgroundTasks) {
+        let federation = self.clone();
+        let token = background.token();
+        let period = Duration::from_secs(self.config.gossip_interval_secs.max(1));
+        background.spawn("federation.gossip", async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = interval.tick() => {
+                        if let Err(e) = federation.gossip_once().await {
+                            warn!("Federation gossip failed: {}", e);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Fração das chaves anunciadas pelos peers presentes localmente
+    ///
+    /// `None` enquanto nenhum peer anunciou conhecimento.
+    pub async fn synchronization_level(&self) -> Option<f64> {
+        let advertised = self.advertised.read().await;
+        let shared: HashSet<&String> = advertised.values().flatten().collect();
+        if shared.is_empty() {
+            return None;
+        }
+        let local = local_keys(&*self.consciousness.state.read().await);
+        let present = shared.iter().filter(|key| local.contains(**key)).count();
+        Some(present as f64 / shared.len() as f64)
+    }
+
+    async fn update_synchronization(&self) {
+        if let Some(level) = self.synchronization_level().await {
+            self.consciousness.state.write().await.collective_state.synchronization_level = level;
+        }
+    }
+}
+
+fn local_keys(state: &ConsciousnessState) -> HashSet<String> {
+    state
+        .episodic_memory
+        .consolidated_learnings
+        .iter()
+        .map(learning_key)
+        .chain(state.recognized_patterns.iter().map(pattern_key))
+        .collect()
+}
+
+/// Mescla o payload no estado; retorna quantos itens foram mesclados
+fn merge_payload(state: &mut ConsciousnessState, payload: &FederationPayload) -> usize {
+    for remote in &payload.learnings {
+        let key = learning_key(remote);
+        let learnings = &mut state.episodic_memory.consolidated_learnings;
+        match learnings.iter_mut().find(|local| learning_key(local) == key) {
+            Some(local) => {
+                let confidence = merge_confidence(local.confidence, remote.confidence);
+                let mut contributors = std::mem::take(&mut local.contributors);
+                merge_contributors(&mut contributors, &remote.contributors);
+                if remote.confidence > local.confidence {
+                    local.applicability = remote.applicability.clone();
+                    local.derived_from = remote.derived_from.clone();
+                }
+                local.confidence = confidence;
+                local.contributors = contributors;
+            }
+            None => learnings.push(remote.clone()),
+        }
+    }
+
+    for remote in &payload.patterns {
+        let key = pattern_key(remote);
+        match state.recognized_patterns.iter_mut().find(|local| pattern_key(local) == key) {
+            Some(local) => {
+                let confidence = merge_confidence(local.confidence, remote.confidence);
+                let mut contributors = std::mem::take(&mut local.contributors);
+                merge_contributors(&mut contributors, &remote.contributors);
+                if remote.confidence > local.confidence {
+                    local.description = remote.description.clone();
+                    local.triggers = remote.triggers.clone();
+                    local.effects = remote.effects.clone();
+                }
+                local.confidence = confidence;
+                local.frequency = local.frequency.max(remote.frequency);
+                local.last_seen = local.last_seen.max(remote.last_seen);
+                local.contributors = contributors;
+            }
+            None => state.recognized_patterns.push(remote.clone()),
+        }
+    }
+
+    payload.learnings.len() + payload.patterns.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbiotic::{EventSeverity, EventType, SystemEvent};
+
+    fn resource_event() -> SystemEvent {
+        SystemEvent::new(EventType::Custom("resource_spike".to_string()), None, serde_json::json!({}))
+            .with_severity(EventSeverity::Medium)
+            .with_source("test")
+    }
+
+    #[tokio::test]
+    async fn test_pattern_propagates_between_in_process_instances() {
+        let (left, right) = ChannelTransport::pair();
+        let teacher = Arc::new(SymbioticConsciousness::new());
+        let student = Arc::new(SymbioticConsciousness::new());
+        let mut student_config = FederationConfig::new("student");
+        student_config.allowed_peers = vec!["teacher".to_string()];
+        let teacher_federation = Federation::new(FederationConfig::new("teacher"), teacher.clone(), Arc::new(left));
+        let student_federation = Federation::new(student_config, student.clone(), Arc::new(right));
+
+        for _ in 0..4 {
+            teacher.process_event(resource_event()).await.unwrap();
+        }
+        let taught = teacher.get_state().await.recognized_patterns;
+        assert!(taught.iter().any(|p| p.confidence >= 0.8));
+        let before = student.get_state().await.collective_state.synchronization_level;
+
+        teacher_federation.push().await.unwrap();
+        assert_eq!(student_federation.pull().await.unwrap(), 1);
+
+        let state = student.get_state().await;
+        let received = state
+            .recognized_patterns
+            .iter()
+            .find(|p| p.confidence >= 0.8)
+            .expect("padrão federado");
+        assert_eq!(received.contributors, vec!["teacher".to_string()]);
+        assert!(state.collective_state.synchronization_level > before);
+        assert_eq!(student_federation.synchronization_level().await, Some(1.0));
+
+        // Origem fora da allow-list é rejeitada
+        let (stranger, _) = ChannelTransport::pair();
+        let outsider = Federation::new(FederationConfig::new("outsider"), teacher.clone(), Arc::new(stranger));
+        let bytes = serde_json::to_vec(&outsider.export().await.unwrap()).unwrap();
+        assert!(student_federation.receive(&bytes).await.is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Tarefas em background com concorrência estruturada
+//!
+//! Usado pelo `task_mesh_core` e pelo `orchestrator_core`: todos os loops de
+//! background dos dois crates são criados via [`BackgroundTasks::spawn`], que
+//! os registra em um `TaskTracker` compartilhado. O shutdown cancela o token
+//! comum e aguarda todos os loops terminarem, com timeout; loops que não
+//! pararam são reportados pelo nome.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, error};
+
+/// Conjunto de tarefas de background com cancelamento comum
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundTasks {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    tracker: TaskTracker,
+    token: Mutex<CancellationToken>,
+    /// Loops vivos, por ID, para diagnóstico de shutdown
+    running: Mutex<HashMap<u64, &'static str>>,
+    next_id: AtomicU64,
+}
+
+/// Remove o loop do registro quando ele termina (inclusive por pânico)
+struct Registration {
+    inner: Arc<Inner>,
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.inner.running.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl BackgroundTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token cancelado no shutdown; loops devem observá-lo
+    pub fn token(&self) -> CancellationToken {
+        self.inner.token.lock().unwrap().clone()
+    }
+
+    /// Inicia um loop registrado no tracker
+    pub fn spawn<F>(&self, name: &'static str, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        self.inner.running.lock().unwrap().insert(id, name);
+        let registration = Registration { inner: self.inner.clone(), id };
+
+        debug!("Iniciando tarefa de background {}", name);
+        self.inner.tracker.spawn(async move {
+            let _registration = registration;
+            future.await
+        })
+    }
+
+    /// Número de tarefas de background vivas
+    pub fn count(&self) -> usize {
+        self.inner.tracker.len()
+    }
+
+    /// Cancela todos os loops e aguarda o término por até `timeout`
+    ///
+    /// Retorna os nomes dos loops que não pararam a tempo.
+    pub async fn shutdown(&self, timeout: Duration) -> Vec<&'static str> {
+        self.token().cancel();
+        self.inner.tracker.close();
+
+        if tokio::time::timeout(timeout, self.inner.tracker.wait()).await.is_ok() {
+            return Vec::new();
+        }
+
+        let mut lingering: Vec<&'static str> = self.inner.running.lock().unwrap().values().copied().collect();
+        lingering.sort_unstable();
+        for name in &lingering {
+            error!("Tarefa de background {} não parou em {:?}", name, timeout);
+        }
+        lingering
+    }
+
+    /// Prepara para um novo início após `shutdown`
+    pub fn reset(&self) {
+        let mut token = self.inner.token.lock().unwrap();
+        if token.is_cancelled() {
+            *token = CancellationToken::new();
+        }
+        self.inner.tracker.reopen();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_reports_loops_ignoring_cancellation() {
+        let background = BackgroundTasks::new();
+        let token = background.token();
+        background.spawn("cooperativo", async move { token.cancelled().await });
+        background.spawn("teimoso", std::future::pending::<()>());
+        assert_eq!(background.count(), 2);
+
+        let lingering = background.shutdown(Duration::from_millis(50)).await;
+        assert_eq!(lingering, vec!["teimoso"]);
+        assert_eq!(background.count(), 1);
+    }
+}
@@ -0,0 +1,396 @@
+//! # System Events
+//!
+//! Formato canônico dos eventos do sistema, compartilhado pelo
+//! `task_mesh_core` (executor, barramento e StateStores) e pelo
+//! `orchestrator_core` (consciência simbiótica). Cada [`SystemEvent`] traz o
+//! tipo, a severidade, a origem, a tarefa relacionada, dados estruturados e
+//! uma sequência monotônica atribuída na criação ([`SystemEvent::new`]).
+//!
+//! A sequência é do processo. Para que continue crescendo após um reinício,
+//! quem abre um armazenamento de eventos chama [`seed_sequence`] com o maior
+//! valor gravado; só assim ordenar eventos persistidos por ela é seguro.
+//!
+//! O tipo do ID da tarefa é um parâmetro (`Uuid` por padrão; o TaskMesh usa
+//! o seu `TaskId`, que serializa como o `Uuid`): os dois crates gravam e
+//! leem o mesmo JSON, e [`SystemEvent::map_task_id`] troca o tipo sem perda.
+//!
+//! Registros gravados antes da unificação continuam legíveis:
+//!
+//! - eventos do TaskMesh (sem `sequence`, `severity` e `source`) recebem os
+//!   valores padrão; campos com valor padrão são omitidos na serialização,
+//!   de modo que esses registros são regravados idênticos;
+//! - eventos do orchestrator (tipo como texto, `timestamp` RFC 3339) têm o
+//!   tipo resolvido por [`EventType::from_name`].
+//!
+//! Os loops de background dos dois crates também vivem aqui, em
+//! [`background`], assim como o corpo problem+json dos erros, em [`problem`].
+
+pub mod background;
+pub mod problem;
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+/// Evento do sistema
+///
+/// O limite explícito evita que o `default` de `task_id` exija `I: Default`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "I: Deserialize<'de>"))]
+pub struct SystemEvent<I = Uuid> {
+    /// Sequência monotônica no processo que criou o evento, semeada com o
+    /// maior valor persistido (ver [`seed_sequence`]); 0 em registros antigos
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub sequence: u64,
+    /// Timestamp do evento
+    #[serde(deserialize_with = "stored_timestamp")]
+    pub timestamp: SystemTime,
+    /// Tipo do evento
+    #[serde(deserialize_with = "stored_event_type")]
+    pub event_type: EventType,
+    /// ID da tarefa relacionada (se aplicável)
+    #[serde(default)]
+    pub task_id: Option<I>,
+    /// Severidade do evento
+    #[serde(default, skip_serializing_if = "EventSeverity::is_default")]
+    pub severity: EventSeverity,
+    /// Componente que emitiu o evento (vazio se não informado)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub source: String,
+    /// Dados do evento
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl<I> SystemEvent<I> {
+    /// Cria um evento agora, com a próxima sequência e a severidade padrão do tipo
+    pub fn new(event_type: EventType, task_id: Option<I>, data: serde_json::Value) -> Self {
+        Self {
+            sequence: next_sequence(),
+            timestamp: SystemTime::now(),
+            severity: event_type.default_severity(),
+            event_type,
+            task_id,
+            source: String::new(),
+            data,
+        }
+    }
+
+    /// Define o instante do evento
+    pub fn at(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn with_severity(mut self, severity: EventSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Timestamp como data UTC
+    pub fn datetime(&self) -> DateTime<Utc> {
+        self.timestamp.into()
+    }
+
+    /// Mesmo evento com outro tipo de ID de tarefa
+    pub fn map_task_id<J>(self, map: impl FnOnce(I) -> J) -> SystemEvent<J> {
+        SystemEvent {
+            sequence: self.sequence,
+            timestamp: self.timestamp,
+            event_type: self.event_type,
+            task_id: self.task_id.map(map),
+            severity: self.severity,
+            source: self.source,
+            data: self.data,
+        }
+    }
+}
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Próximo valor da sequência de eventos do processo (começa em 1)
+pub fn next_sequence() -> u64 {
+    SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+/// Garante que as próximas sequências sejam maiores que `persisted_max`
+///
+/// Chamado ao abrir um armazenamento com o maior valor já gravado; nunca
+/// faz a sequência voltar.
+pub fn seed_sequence(persisted_max: u64) {
+    SEQUENCE.fetch_max(persisted_max, Ordering::SeqCst);
+}
+
+/// Severidade do evento
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EventSeverity {
+    #[default]
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl EventSeverity {
+    fn is_default(&self) -> bool {
+        *self == EventSeverity::Low
+    }
+}
+
+/// Tipos de eventos do sistema
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventType {
+    TaskSubmitted,
+    TaskScheduled,
+    TaskStarted,
+    TaskCompleted,
+    TaskFailed,
+    TaskCancelled,
+    CheckpointCreated,
+    CheckpointRestored,
+    /// Checkpoint lento: engine passou para a estratégia incremental
+    CheckpointDegraded,
+    WorkerStarted,
+    WorkerStopped,
+    SystemStarted,
+    SystemStopped,
+    /// Configuração alterada em execução (heurística, concorrência)
+    ConfigurationChanged,
+    /// Tarefa atingiu o limiar de alerta do SLA
+    SlaWarning,
+    /// Prazo do SLA vencido sem conclusão
+    SlaBreach,
+    /// Tarefa presa em execução detectada pelo watchdog
+    TaskStuck,
+    /// Progresso reportado por uma tarefa em execução
+    TaskProgress,
+    /// Lote de tarefas terminais removido pela coleta
+    TasksCollected,
+    /// Prioridade de uma tarefa enfileirada alterada
+    TaskReprioritized,
+    /// Evento definido pela aplicação
+    Custom(String),
+    /// Tipo persistido que esta versão não reconhece (preservado como texto)
+    Unknown(String),
+}
+
+/// Tipos predefinidos e seus nomes textuais
+const NAMED: &[(EventType, &str)] = &[
+    (EventType::TaskSubmitted, "task_submitted"),
+    (EventType::TaskScheduled, "task_scheduled"),
+    (EventType::TaskStarted, "task_started"),
+    (EventType::TaskCompleted, "task_completed"),
+    (EventType::TaskFailed, "task_failed"),
+    (EventType::TaskCancelled, "task_cancelled"),
+    (EventType::CheckpointCreated, "checkpoint_created"),
+    (EventType::CheckpointRestored, "checkpoint_restored"),
+    (EventType::CheckpointDegraded, "checkpoint_degraded"),
+    (EventType::WorkerStarted, "worker_started"),
+    (EventType::WorkerStopped, "worker_stopped"),
+    (EventType::SystemStarted, "system_started"),
+    (EventType::SystemStopped, "system_stopped"),
+    (EventType::ConfigurationChanged, "configuration_changed"),
+    (EventType::SlaWarning, "sla_warning"),
+    (EventType::SlaBreach, "sla_breach"),
+    (EventType::TaskStuck, "task_stuck"),
+    (EventType::TaskProgress, "task_progress"),
+    (EventType::TasksCollected, "tasks_collected"),
+    (EventType::TaskReprioritized, "task_reprioritized"),
+];
+
+impl EventType {
+    /// Converte o formato legado (saída de `Debug`) usado antes da
+    /// serialização via serde. Valores não reconhecidos viram `Unknown`.
+    pub fn parse_legacy(raw: &str) -> Self {
+        match raw {
+            "TaskSubmitted" => EventType::TaskSubmitted,
+            "TaskScheduled" => EventType::TaskScheduled,
+            "TaskStarted" => EventType::TaskStarted,
+            "TaskCompleted" => EventType::TaskCompleted,
+            "TaskFailed" => EventType::TaskFailed,
+            "TaskCancelled" => EventType::TaskCancelled,
+            "CheckpointCreated" => EventType::CheckpointCreated,
+            "CheckpointRestored" => EventType::CheckpointRestored,
+            "WorkerStarted" => EventType::WorkerStarted,
+            "WorkerStopped" => EventType::WorkerStopped,
+            "SystemStarted" => EventType::SystemStarted,
+            "SystemStopped" => EventType::SystemStopped,
+            other => other
+                .strip_prefix("Custom(\"")
+                .and_then(|rest| rest.strip_suffix("\")"))
+                .map(|name| EventType::Custom(name.to_string()))
+                .unwrap_or_else(|| EventType::Unknown(other.to_string())),
+        }
+    }
+
+    /// Lê o tipo armazenado, aceitando tanto o formato serde quanto o legado
+    pub fn from_stored(raw: &str) -> Self {
+        serde_json::from_str(raw).unwrap_or_else(|_| Self::parse_legacy(raw))
+    }
+
+    /// Nome textual do tipo (`task_failed`), usado pelo orchestrator e pelas regras
+    ///
+    /// Tipos `Custom` e `Unknown` usam o próprio texto.
+    pub fn name(&self) -> &str {
+        match self {
+            EventType::Custom(name) | EventType::Unknown(name) => name,
+            builtin => NAMED
+                .iter()
+                .find(|(event_type, _)| event_type == builtin)
+                .map(|(_, name)| *name)
+                .expect("todo tipo predefinido tem nome"),
+        }
+    }
+
+    /// Tipo com o nome textual indicado; nomes não predefinidos viram `Custom`
+    pub fn from_name(name: &str) -> Self {
+        NAMED
+            .iter()
+            .find(|(_, known)| *known == name)
+            .map(|(event_type, _)| event_type.clone())
+            .unwrap_or_else(|| EventType::Custom(name.to_string()))
+    }
+
+    /// Severidade atribuída por [`SystemEvent::new`]
+    pub fn default_severity(&self) -> EventSeverity {
+        match self {
+            EventType::TaskFailed | EventType::SlaBreach | EventType::TaskStuck => EventSeverity::High,
+            EventType::TaskCancelled | EventType::CheckpointDegraded | EventType::SlaWarning => EventSeverity::Medium,
+            _ => EventSeverity::Low,
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// `SystemTime` (TaskMesh) ou texto RFC 3339 (orchestrator)
+fn stored_timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        SystemTime(SystemTime),
+        DateTime(DateTime<Utc>),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::SystemTime(timestamp) => timestamp,
+        Stored::DateTime(datetime) => datetime.into(),
+    })
+}
+
+/// Variante serde (TaskMesh) ou nome textual (orchestrator)
+fn stored_event_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EventType, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Variant(EventType),
+        Name(String),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Variant(event_type) => event_type,
+        Stored::Name(name) => EventType::from_name(&name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn all_event_types() -> Vec<EventType> {
+        let mut types: Vec<EventType> = NAMED.iter().map(|(event_type, _)| event_type.clone()).collect();
+        types.push(EventType::Custom("deploy".to_string()));
+        types.push(EventType::Unknown("Removed".to_string()));
+        types
+    }
+
+    #[test]
+    fn test_every_event_type_round_trips() {
+        for event_type in all_event_types() {
+            let event: SystemEvent = SystemEvent::new(event_type.clone(), Some(Uuid::nil()), json!({ "k": 1 }))
+                .with_severity(EventSeverity::Critical)
+                .with_source("test");
+            let json = serde_json::to_string(&event).unwrap();
+            assert_eq!(serde_json::from_str::<SystemEvent>(&json).unwrap(), event, "{}", json);
+
+            let stored = serde_json::to_string(&event_type).unwrap();
+            assert_eq!(EventType::from_stored(&stored), event_type);
+            if !matches!(event_type, EventType::Unknown(_)) {
+                assert_eq!(EventType::from_name(event_type.name()), event_type);
+            }
+        }
+    }
+
+    #[test]
+    fn test_previously_stored_events_still_deserialize() {
+        // Evento do TaskMesh anterior à unificação: regravado idêntico
+        let task_mesh = json!({
+            "timestamp": { "secs_since_epoch": 1700000060, "nanos_since_epoch": 500000000 },
+            "event_type": { "Custom": "deploy" },
+            "task_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "data": { "exit_code": 0 },
+        });
+        let event: SystemEvent = serde_json::from_value(task_mesh.clone()).unwrap();
+        assert_eq!(event.clone().map_task_id(|id| id.to_string()).map_task_id(|id| id.parse().unwrap()), event);
+        assert_eq!((event.sequence, event.severity, event.source.as_str()), (0, EventSeverity::Low, ""));
+        assert_eq!(serde_json::to_value(&event).unwrap(), task_mesh);
+
+        // Evento da consciência do orchestrator
+        let orchestrator = json!({
+            "event_type": "task_failed",
+            "data": { "task_id": "67e55044-10b1-426f-9247-bb680e5fe0c8" },
+            "timestamp": "2023-11-14T22:14:20.500Z",
+            "source": "orchestrator_core",
+            "severity": "Critical",
+        });
+        let event: SystemEvent = serde_json::from_value(orchestrator).unwrap();
+        assert_eq!(event.event_type, EventType::TaskFailed);
+        assert_eq!(event.severity, EventSeverity::Critical);
+        assert_eq!(event.source, "orchestrator_core");
+        assert_eq!(event.timestamp, SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1_700_000_060_500));
+        assert_eq!(event.task_id, None);
+        let custom: SystemEvent = serde_json::from_value(json!({
+            "event_type": "budget_exceeded", "data": {}, "timestamp": "2023-11-14T22:14:20Z",
+            "source": "orchestrator_core", "severity": "High",
+        })).unwrap();
+        assert_eq!(custom.event_type, EventType::Custom("budget_exceeded".to_string()));
+    }
+
+    #[test]
+    fn test_sequence_is_monotonic() {
+        let first: SystemEvent = SystemEvent::new(EventType::TaskStarted, None, json!({}));
+        let second: SystemEvent = SystemEvent::new(EventType::TaskFailed, None, json!({}));
+        assert!(first.sequence > 0 && second.sequence > first.sequence);
+        assert_eq!(second.severity, EventSeverity::High);
+    }
+
+    #[test]
+    fn test_seed_continues_after_persisted_max() {
+        let before = next_sequence();
+        seed_sequence(before + 1_000);
+        let seeded = next_sequence();
+        assert!(seeded > before + 1_000);
+
+        // Semear com um valor menor não faz a sequência voltar
+        seed_sequence(1);
+        assert!(next_sequence() > seeded);
+    }
+}
@@ -0,0 +1,39 @@
+//! Corpo das respostas de erro no formato problem+json (RFC 7807)
+//!
+//! Compartilhado pelo `task_mesh_core` e pelo `orchestrator_core`: cada
+//! crate mapeia os seus erros para [`ProblemDetails`] com o próprio prefixo
+//! de `type` (ver [`problem_type`]).
+
+use serde::{Deserialize, Serialize};
+
+/// Content-Type das respostas de erro
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Campo rejeitado em um erro de validação
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Corpo `application/problem+json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Código estável do erro (ex.: `TASK_NOT_FOUND`)
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// URI de `type` de um código de erro: `TASK_NOT_FOUND` vira `<prefixo>task-not-found`
+pub fn problem_type(prefix: &str, code: &str) -> String {
+    format!("{}{}", prefix, code.to_ascii_lowercase().replace('_', "-"))
+}
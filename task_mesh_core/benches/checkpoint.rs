@@ -0,0 +1,56 @@
+//! Criação e restauração de checkpoints com 10k tarefas
+
+mod support;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use task_mesh_core::state_store::{MemoryStateStore, SqliteStateStore};
+use task_mesh_core::StateStore;
+
+const TASKS: usize = 10_000;
+
+async fn populated(store: Arc<dyn StateStore>) -> Arc<dyn StateStore> {
+    for task in support::wide(TASKS) {
+        store.store_task(&task).await.unwrap();
+    }
+    store
+}
+
+fn bench_checkpoint(c: &mut Criterion) {
+    let runtime = support::runtime();
+    let backends: Vec<(&str, Arc<dyn StateStore>)> = runtime.block_on(async {
+        vec![
+            ("memory", populated(Arc::new(MemoryStateStore::new().await.unwrap())).await),
+            ("sqlite", populated(Arc::new(SqliteStateStore::new("sqlite::memory:").await.unwrap())).await),
+        ]
+    });
+    let sequence = AtomicU64::new(0);
+
+    let mut group = c.benchmark_group("checkpoint_10k");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(TASKS as u64));
+    for (name, store) in &backends {
+        group.bench_function(format!("{}/create", name), |b| {
+            b.to_async(&runtime).iter(|| {
+                let id = format!("bench_{}", sequence.fetch_add(1, Ordering::Relaxed));
+                let store = store.clone();
+                async move { store.create_checkpoint(&id).await.unwrap() }
+            })
+        });
+
+        let id = format!("{}_restore", name);
+        runtime.block_on(store.create_checkpoint(&id)).unwrap();
+        group.bench_function(format!("{}/restore", name), |b| {
+            b.to_async(&runtime).iter(|| {
+                let (store, id) = (store.clone(), id.clone());
+                async move { store.restore_checkpoint(&id).await.unwrap() }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_checkpoint);
+criterion_main!(benches);
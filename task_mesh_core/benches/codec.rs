@@ -0,0 +1,42 @@
+//! Tamanho e tempo de codificação/decodificação de um estado com 10k tarefas,
+//! para cada combinação de codec e compressão
+
+mod support;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use task_mesh_core::codec::CodecConfig;
+use task_mesh_core::Task;
+
+const TASKS: usize = 10_000;
+
+fn label(config: &CodecConfig) -> String {
+    format!("{}{}", config.codec.extension(), config.compression.extension())
+}
+
+fn bench_codec(c: &mut Criterion) {
+    let tasks = support::wide(TASKS);
+
+    // O criterion não mede tamanho: os bytes de cada combinação vão para a saída
+    for config in CodecConfig::all() {
+        let size = config.encode(&tasks).unwrap().len();
+        println!("codec_10k/{:<10} {:>10} bytes", label(&config), size);
+    }
+
+    let mut group = c.benchmark_group("codec_10k");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(TASKS as u64));
+    for config in CodecConfig::all() {
+        group.bench_with_input(BenchmarkId::new("encode", label(&config)), &config, |b, config| {
+            b.iter(|| config.encode(&tasks).unwrap())
+        });
+
+        let blob = config.encode(&tasks).unwrap();
+        group.bench_with_input(BenchmarkId::new("decode", label(&config)), &blob, |b, blob| {
+            b.iter(|| CodecConfig::decode::<Vec<Task>>(blob).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_codec);
+criterion_main!(benches);
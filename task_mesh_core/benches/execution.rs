@@ -0,0 +1,65 @@
+//! Execução ponta a ponta de 1k tarefas `Exec` triviais
+
+mod support;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use task_mesh_core::executor::ExecutorConfig;
+use task_mesh_core::state_store::MemoryStateStore;
+use task_mesh_core::{ErrorHandler, RetryPolicy, StateStore, TaskExecutor, TaskId};
+
+const TASKS: usize = 1_000;
+
+/// Aguarda todas as tarefas chegarem a um status final
+async fn wait_all_final(store: &dyn StateStore, task_ids: &[TaskId]) {
+    for task_id in task_ids {
+        while !store.get_task_status(task_id).await.unwrap().is_final() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+}
+
+fn bench_execute_trivial(c: &mut Criterion) {
+    let runtime = support::runtime();
+    let store = Arc::new(runtime.block_on(MemoryStateStore::new()).unwrap());
+    let config = ExecutorConfig {
+        // Status gravado imediatamente: a espera observa o fim real da execução
+        write_behind: false,
+        enable_detailed_metrics: false,
+        ..ExecutorConfig::default()
+    };
+    let executor = runtime.block_on(async {
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = Arc::new(TaskExecutor::with_config(config, store.clone(), error_handler).await.unwrap());
+        executor.start().await.unwrap();
+        executor
+    });
+
+    let mut group = c.benchmark_group("execute_exec_1k");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(TASKS as u64));
+    group.bench_function("true", |b| {
+        b.to_async(&runtime).iter_batched(
+            || support::wide(TASKS),
+            |tasks| {
+                let (executor, store) = (executor.clone(), store.clone());
+                async move {
+                    let mut task_ids = Vec::with_capacity(tasks.len());
+                    for task in tasks {
+                        task_ids.push(executor.execute_task(task).await.unwrap());
+                    }
+                    wait_all_final(store.as_ref(), &task_ids).await;
+                }
+            },
+            criterion::BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+
+    runtime.block_on(executor.shutdown()).unwrap();
+}
+
+criterion_group!(benches, bench_execute_trivial);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+//! Latência de uma função Rust vazia: loop de comandos vs. execução inline
+
+mod support;
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use task_mesh_core::execution_hint::ExecutionHint;
+use task_mesh_core::executor::ExecutorConfig;
+use task_mesh_core::state_store::MemoryStateStore;
+use task_mesh_core::{ErrorHandler, FnContext, RetryPolicy, Task, TaskDefinition, TaskExecutor};
+
+fn noop_task(hint: ExecutionHint) -> Task {
+    let definition = TaskDefinition::RustFunction {
+        function_name: "noop".to_string(),
+        args: serde_json::Value::Null,
+    };
+    Task::new("noop".to_string(), definition, vec![]).with_execution_hint(hint)
+}
+
+fn bench_noop_function(c: &mut Criterion) {
+    let runtime = support::runtime();
+    let store = Arc::new(runtime.block_on(MemoryStateStore::new()).unwrap());
+    let config = ExecutorConfig {
+        max_workers: 1,
+        enable_detailed_metrics: false,
+        ..ExecutorConfig::default()
+    };
+    let executor = runtime.block_on(async {
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = Arc::new(TaskExecutor::with_config(config, store, error_handler).await.unwrap());
+        executor.functions().register("noop", |_ctx: FnContext, _args| async move { Ok(serde_json::Value::Null) });
+        executor.start().await.unwrap();
+        executor
+    });
+
+    let mut group = c.benchmark_group("noop_function");
+    for (name, hint) in [("queued", ExecutionHint::Default), ("inline", ExecutionHint::Inline)] {
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| {
+                let executor = executor.clone();
+                async move {
+                    executor.execute_task(noop_task(hint)).await.unwrap();
+                    // A execução inline já terminou; a enfileirada termina no loop de comandos
+                    while executor.queued_count() > 0 {
+                        tokio::task::yield_now().await;
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+
+    runtime.block_on(executor.shutdown()).unwrap();
+}
+
+criterion_group!(benches, bench_noop_function);
+criterion_main!(benches);
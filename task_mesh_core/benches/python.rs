@@ -0,0 +1,57 @@
+//! Latência por tarefa `PythonScript`: um interpretador por tarefa vs. pool
+
+mod support;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use task_mesh_core::executor::ExecutorConfig;
+use task_mesh_core::python_pool::PythonPoolConfig;
+use task_mesh_core::state_store::MemoryStateStore;
+use task_mesh_core::{ErrorHandler, RetryPolicy, StateStore, Task, TaskDefinition, TaskExecutor};
+
+/// Script com um import típico, pago a cada tarefa sem o pool
+const SCRIPT: &str = "import json\nprint(json.dumps({'ok': True}))";
+
+fn bench_python_task(c: &mut Criterion) {
+    let runtime = support::runtime();
+    let mut group = c.benchmark_group("python_task");
+    group.sample_size(20);
+
+    for (name, python_pool) in [("spawn", None), ("pool", Some(PythonPoolConfig::default()))] {
+        let store = Arc::new(runtime.block_on(MemoryStateStore::new()).unwrap());
+        let config = ExecutorConfig {
+            max_workers: 1,
+            write_behind: false,
+            enable_detailed_metrics: false,
+            python_pool,
+            ..ExecutorConfig::default()
+        };
+        let executor = runtime.block_on(async {
+            let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+            let executor = Arc::new(TaskExecutor::with_config(config, store.clone(), error_handler).await.unwrap());
+            executor.start().await.unwrap();
+            executor
+        });
+
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| {
+                let (executor, store) = (executor.clone(), store.clone());
+                async move {
+                    let task = Task::new("python".to_string(), TaskDefinition::python_script(SCRIPT), vec![]);
+                    let task_id = executor.execute_task(task).await.unwrap();
+                    while !store.get_task_status(&task_id).await.unwrap().is_final() {
+                        tokio::time::sleep(Duration::from_millis(1)).await;
+                    }
+                }
+            })
+        });
+
+        runtime.block_on(executor.shutdown()).unwrap();
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_python_task);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+//! `Scheduler::get_next_task` com 10k itens na fila
+//!
+//! Uma fila larga (todas as tarefas prontas) mede o custo da heurística; uma
+//! cadeia (apenas uma tarefa pronta) mede a varredura no pior caso.
+
+mod support;
+
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use task_mesh_core::{ResourceAllocation, Scheduler, SchedulingHeuristic, Task};
+
+const QUEUED: usize = 10_000;
+/// Chamadas medidas por fila antes de reconstruí-la (mantém ~10k itens)
+const CALLS_PER_QUEUE: u64 = 1_000;
+
+fn heuristics() -> Vec<(&'static str, SchedulingHeuristic)> {
+    vec![
+        ("fifo", SchedulingHeuristic::FIFO),
+        ("lifo", SchedulingHeuristic::LIFO),
+        ("priority", SchedulingHeuristic::Priority),
+        ("shortest_job_first", SchedulingHeuristic::ShortestJobFirst),
+        ("earliest_deadline_first", SchedulingHeuristic::EarliestDeadlineFirst),
+        ("critical_ratio", SchedulingHeuristic::CriticalRatio),
+        ("default", SchedulingHeuristic::default()),
+    ]
+}
+
+async fn queued_scheduler(heuristic: SchedulingHeuristic, tasks: Vec<Task>) -> Scheduler {
+    let scheduler = Scheduler::new(heuristic);
+    for task in tasks {
+        scheduler.schedule_task(task).await.unwrap();
+    }
+    scheduler
+}
+
+/// Mede `iters` chamadas, reconstruindo a fila (fora da medição) a cada lote
+async fn measure_next_task(iters: u64, heuristic: &SchedulingHeuristic, build: fn(usize) -> Vec<Task>) -> Duration {
+    let resources = ResourceAllocation::default();
+    let mut elapsed = Duration::ZERO;
+    let mut remaining = iters;
+    while remaining > 0 {
+        let scheduler = queued_scheduler(heuristic.clone(), build(QUEUED)).await;
+        let calls = remaining.min(CALLS_PER_QUEUE);
+        let started = Instant::now();
+        for _ in 0..calls {
+            criterion::black_box(scheduler.get_next_task(&resources).await);
+        }
+        elapsed += started.elapsed();
+        remaining -= calls;
+    }
+    elapsed
+}
+
+fn bench_get_next_task(c: &mut Criterion) {
+    let runtime = support::runtime();
+
+    let mut group = c.benchmark_group("scheduler_get_next_task_10k");
+    group.sample_size(20);
+    for (name, heuristic) in heuristics() {
+        group.bench_with_input(BenchmarkId::new("wide", name), &heuristic, |b, heuristic| {
+            b.to_async(&runtime).iter_custom(|iters| measure_next_task(iters, heuristic, support::wide))
+        });
+    }
+    let heuristic = SchedulingHeuristic::default();
+    group.bench_with_input(BenchmarkId::new("chain", "default"), &heuristic, |b, heuristic| {
+        b.to_async(&runtime).iter_custom(|iters| measure_next_task(iters, heuristic, support::chain))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_next_task);
+criterion_main!(benches);
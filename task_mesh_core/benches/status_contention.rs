@@ -0,0 +1,125 @@
+//! Latência de `get_task_status` sob escritas concorrentes
+//!
+//! Compara o `MemoryStateStore` fragmentado com um mapa atrás de um único
+//! `RwLock` (a implementação anterior) e imprime o p99 de cada um.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use task_mesh_core::state_store::{MemoryStateStore, StateStore};
+use task_mesh_core::{TaskId, TaskStatus};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+const TASKS: usize = 1024;
+const WRITERS: usize = 16;
+const SAMPLES: usize = 20_000;
+
+/// Mapa de status atrás de um único lock, como antes da fragmentação
+#[derive(Default)]
+struct SingleLockStatus {
+    statuses: RwLock<HashMap<TaskId, TaskStatus>>,
+}
+
+/// Operações comparadas
+trait StatusMap: Send + Sync + 'static {
+    fn update(&self, task_id: TaskId, status: TaskStatus) -> impl std::future::Future<Output = ()> + Send;
+    fn get(&self, task_id: &TaskId) -> impl std::future::Future<Output = TaskStatus> + Send;
+}
+
+impl StatusMap for SingleLockStatus {
+    async fn update(&self, task_id: TaskId, status: TaskStatus) {
+        self.statuses.write().await.insert(task_id, status);
+    }
+
+    async fn get(&self, task_id: &TaskId) -> TaskStatus {
+        self.statuses.read().await.get(task_id).cloned().unwrap_or(TaskStatus::Pending)
+    }
+}
+
+impl StatusMap for MemoryStateStore {
+    async fn update(&self, task_id: TaskId, status: TaskStatus) {
+        self.update_task_status(&task_id, status).await.unwrap();
+    }
+
+    async fn get(&self, task_id: &TaskId) -> TaskStatus {
+        self.get_task_status(task_id).await.unwrap()
+    }
+}
+
+fn running(worker: usize) -> TaskStatus {
+    TaskStatus::Running { started_at: SystemTime::now(), worker_id: format!("worker_{}", worker), process: None, progress: None }
+}
+
+/// Inicia escritores contínuos até o token ser cancelado
+fn spawn_writers<M: StatusMap>(map: &Arc<M>, task_ids: &Arc<Vec<TaskId>>, stop: &CancellationToken) {
+    for worker in 0..WRITERS {
+        let (map, task_ids, stop) = (map.clone(), task_ids.clone(), stop.clone());
+        tokio::spawn(async move {
+            let mut i = worker;
+            while !stop.is_cancelled() {
+                map.update(task_ids[i % TASKS], running(worker)).await;
+                i += WRITERS;
+                tokio::task::yield_now().await;
+            }
+        });
+    }
+}
+
+/// p99 da latência de leitura com escritores ativos
+async fn read_p99<M: StatusMap>(map: Arc<M>, task_ids: Arc<Vec<TaskId>>) -> Duration {
+    let stop = CancellationToken::new();
+    spawn_writers(&map, &task_ids, &stop);
+
+    let mut latencies = Vec::with_capacity(SAMPLES);
+    for i in 0..SAMPLES {
+        let started = Instant::now();
+        map.get(&task_ids[(i * 31) % TASKS]).await;
+        latencies.push(started.elapsed());
+    }
+    stop.cancel();
+
+    latencies.sort_unstable();
+    latencies[SAMPLES * 99 / 100]
+}
+
+fn bench_status_contention(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(8).enable_all().build().unwrap();
+    let task_ids: Arc<Vec<TaskId>> = Arc::new((0..TASKS).map(|_| TaskId::new_v4()).collect());
+    let sharded = Arc::new(runtime.block_on(MemoryStateStore::new()).unwrap());
+    let single = Arc::new(SingleLockStatus::default());
+
+    let sharded_p99 = runtime.block_on(read_p99(sharded.clone(), task_ids.clone()));
+    let single_p99 = runtime.block_on(read_p99(single.clone(), task_ids.clone()));
+    println!("p99 get_task_status: fragmentado {:?}, lock único {:?}", sharded_p99, single_p99);
+
+    let mut group = c.benchmark_group("get_task_status_under_writes");
+    let stop = CancellationToken::new();
+    runtime.block_on(async {
+        spawn_writers(&sharded, &task_ids, &stop);
+        spawn_writers(&single, &task_ids, &stop);
+    });
+    group.bench_function("sharded", |b| {
+        let mut i = 0;
+        b.to_async(&runtime).iter(|| {
+            i += 31;
+            let (sharded, task_id) = (sharded.clone(), task_ids[i % TASKS]);
+            async move { sharded.get(&task_id).await }
+        })
+    });
+    group.bench_function("single_lock", |b| {
+        let mut i = 0;
+        b.to_async(&runtime).iter(|| {
+            i += 31;
+            let (single, task_id) = (single.clone(), task_ids[i % TASKS]);
+            async move { single.get(&task_id).await }
+        })
+    });
+    group.finish();
+    stop.cancel();
+}
+
+criterion_group!(benches, bench_status_contention);
+criterion_main!(benches);
@@ -0,0 +1,176 @@
+//! Benchmark do caminho de submissão com payloads grandes
+//!
+//! Compara o encanamento antigo (uma cópia da tarefa por etapa: registro,
+//! fila do scheduler, canal de comandos e mapa de execução) com a tarefa
+//! compartilhada via `SharedTask`, contando alocações com um alocador global.
+//!
+//! Também mede a vazão de submissão com persistência da tarefa em cada
+//! backend (`MemoryStateStore` e SQLite em memória).
+
+mod support;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use task_mesh_core::state_store::MemoryStateStore;
+use task_mesh_core::{SharedTask, StateStore, Task, TaskDefinition, TaskMeshConfig, TaskMeshCore};
+
+/// Alocador que conta alocações e bytes alocados
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Etapas do caminho de submissão que recebem a tarefa
+const STAGES: usize = 4;
+
+const PAYLOAD_SIZE: usize = 1024 * 1024;
+
+fn payload_task(script: &Arc<str>) -> Task {
+    Task::new("payload".to_string(), TaskDefinition::python_script(script.clone()), vec![])
+        .with_metadata("origem".to_string(), "bench".to_string())
+}
+
+/// Cópia profunda, como o encanamento fazia antes de `SharedTask`
+fn deep_copy(task: &Task) -> Task {
+    let mut copy = task.clone();
+    if let TaskDefinition::PythonScript { script, .. } = &mut copy.definition {
+        *script = Arc::from(script.to_string());
+    }
+    copy
+}
+
+/// Alocações (quantidade, bytes) feitas por `f`
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (usize, usize, T) {
+    let (count, bytes) = (ALLOCATIONS.load(Ordering::SeqCst), ALLOCATED_BYTES.load(Ordering::SeqCst));
+    let value = f();
+    (
+        ALLOCATIONS.load(Ordering::SeqCst) - count,
+        ALLOCATED_BYTES.load(Ordering::SeqCst) - bytes,
+        value,
+    )
+}
+
+/// Compara a submissão real com a linha de base do encanamento antigo
+///
+/// A linha de base é a mesma `submit_task` somada à cópia profunda por
+/// etapa que o caminho fazia antes de `SharedTask`. O backend em memória
+/// evita que a serialização da persistência domine a contagem.
+fn assert_fewer_allocations(runtime: &tokio::runtime::Runtime, core: &TaskMeshCore, script: &Arc<str>) {
+    let task = payload_task(script);
+    let (shared, shared_bytes, _) = count_allocations(|| runtime.block_on(core.submit_task(task)).unwrap());
+
+    let task = payload_task(script);
+    let (baseline, baseline_bytes, copies) = count_allocations(|| {
+        let copies = (0..STAGES).map(|_| deep_copy(&task)).collect::<Vec<_>>();
+        runtime.block_on(core.submit_task(task)).unwrap();
+        copies
+    });
+    drop(copies);
+
+    println!(
+        "alocações por submissão: linha de base {} ({} bytes), submit_task {} ({} bytes)",
+        baseline, baseline_bytes, shared, shared_bytes
+    );
+    assert!(
+        baseline_bytes >= shared_bytes * 3,
+        "esperado ≥3× menos bytes alocados: linha de base {} vs submit_task {}",
+        baseline_bytes,
+        shared_bytes
+    );
+}
+
+fn bench_submit(c: &mut Criterion) {
+    let script: Arc<str> = Arc::from(format!("# {}\nprint('ok')\n", "x".repeat(PAYLOAD_SIZE)));
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let memory = runtime.block_on(async {
+        let mut core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        core.state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        core
+    });
+    assert_fewer_allocations(&runtime, &memory, &script);
+
+    let core = runtime.block_on(TaskMeshCore::new(TaskMeshConfig::default())).unwrap();
+    let core = &core;
+
+    let mut group = c.benchmark_group("submit_1mb");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("copy_per_stage", |b| {
+        b.iter_batched(
+            || payload_task(&script),
+            |task| (0..STAGES).map(|_| deep_copy(&task)).collect::<Vec<_>>(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("shared", |b| {
+        b.iter_batched(
+            || payload_task(&script),
+            |task| {
+                let task: SharedTask = Arc::new(task);
+                (0..STAGES).map(|_| task.clone()).collect::<Vec<_>>()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("submit_task", |b| {
+        b.to_async(&runtime).iter_batched(
+            || payload_task(&script),
+            |task| async move { core.submit_task(task).await.unwrap() },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+/// Submissão com persistência: `store_task` no backend seguido de `submit_task`
+fn bench_submit_backends(c: &mut Criterion) {
+    let runtime = support::runtime();
+    let cores: Vec<(&str, TaskMeshCore)> = runtime.block_on(async {
+        let sqlite = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let mut memory = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        memory.state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        vec![("memory", memory), ("sqlite", sqlite)]
+    });
+
+    let mut group = c.benchmark_group("submit_throughput");
+    group.throughput(Throughput::Elements(1));
+    for (name, core) in &cores {
+        group.bench_function(*name, |b| {
+            b.to_async(&runtime).iter_batched(
+                || support::trivial("submit", vec![]),
+                |task| async move {
+                    core.state_store.store_task(&task).await.unwrap();
+                    core.submit_task(task).await.unwrap()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_submit, bench_submit_backends);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+//! Utilitários compartilhados pelos benchmarks
+//!
+//! Construtores de DAGs sintéticos. Cada função devolve as tarefas em ordem
+//! topológica, prontas para submissão.
+
+#![allow(dead_code)]
+
+use task_mesh_core::{Task, TaskDefinition, TaskId};
+
+/// Tarefa trivial (`true` executado sem shell)
+pub fn trivial(name: impl Into<String>, dependencies: Vec<TaskId>) -> Task {
+    Task::new(name.into(), TaskDefinition::exec("true", Vec::<String>::new()), dependencies)
+}
+
+/// Cadeia `t0 -> t1 -> ... -> t(n-1)`: apenas uma tarefa pronta por vez
+pub fn chain(n: usize) -> Vec<Task> {
+    let mut tasks: Vec<Task> = Vec::with_capacity(n);
+    for i in 0..n {
+        let dependencies = tasks.last().map(|previous| vec![previous.id]).unwrap_or_default();
+        tasks.push(trivial(format!("chain_{}", i), dependencies));
+    }
+    tasks
+}
+
+/// `n` tarefas independentes: todas prontas desde o início
+pub fn wide(n: usize) -> Vec<Task> {
+    (0..n).map(|i| trivial(format!("wide_{}", i), vec![])).collect()
+}
+
+/// Losango: raiz, `width` tarefas intermediárias e um sumidouro
+pub fn diamond(width: usize) -> Vec<Task> {
+    let root = trivial("diamond_root", vec![]);
+    let middle: Vec<Task> = (0..width)
+        .map(|i| trivial(format!("diamond_{}", i), vec![root.id]))
+        .collect();
+    let sink = trivial("diamond_sink", middle.iter().map(|task| task.id).collect());
+
+    let mut tasks = Vec::with_capacity(width + 2);
+    tasks.push(root);
+    tasks.extend(middle);
+    tasks.push(sink);
+    tasks
+}
+
+/// Runtime multi-thread para os benchmarks assíncronos
+pub fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap()
+}
@@ -0,0 +1,151 @@
+//! Aliases legíveis de tarefas
+//!
+//! Um alias identifica uma tarefa dentro de um namespace (metadado
+//! [`NAMESPACE_KEY`]); tarefas sem namespace ficam em [`DEFAULT_NAMESPACE`].
+//! Onde um `TaskId` é aceito, um [`TaskRef`] também pode ser informado como
+//! `alias` ou `namespace/alias`.
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::NAMESPACE_KEY;
+use crate::types::*;
+
+/// Namespace das tarefas sem o metadado `namespace`
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Tamanho máximo de um alias
+pub const MAX_ALIAS_LEN: usize = 64;
+
+/// Valida um alias: minúsculas, dígitos e hífens, até 64 caracteres
+///
+/// Aliases com formato de UUID são recusados para que a resolução de um
+/// [`TaskRef`] nunca seja ambígua.
+pub fn validate_alias(alias: &str) -> TaskMeshResult<()> {
+    let invalid = |reason: &str| {
+        TaskMeshError::Configuration(format!("Alias inválido '{}': {}", alias, reason))
+    };
+    if alias.is_empty() || alias.len() > MAX_ALIAS_LEN {
+        return Err(invalid("deve ter de 1 a 64 caracteres"));
+    }
+    if !alias.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(invalid("use apenas letras minúsculas, dígitos e hífens"));
+    }
+    if alias.starts_with('-') || alias.ends_with('-') {
+        return Err(invalid("não pode começar nem terminar com hífen"));
+    }
+    if alias.parse::<TaskId>().is_ok() {
+        return Err(invalid("não pode ter formato de UUID"));
+    }
+    Ok(())
+}
+
+/// Separa `namespace/alias`; sem namespace explícito, usa `namespace`
+pub fn split_alias<'a>(qualified: &'a str, namespace: &'a str) -> (&'a str, &'a str) {
+    qualified.rsplit_once('/').unwrap_or((namespace, qualified))
+}
+
+/// Namespace de uma tarefa
+pub fn namespace_of(task: &Task) -> &str {
+    task.metadata.get(NAMESPACE_KEY).map(String::as_str).unwrap_or(DEFAULT_NAMESPACE)
+}
+
+/// Referência a uma tarefa por ID ou por alias
+///
+/// Em texto, UUIDs viram `Id`; qualquer outro valor é um alias, qualificado
+/// opcionalmente pelo namespace (`etl/nightly-etl`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TaskRef {
+    Id(TaskId),
+    Alias(String),
+}
+
+impl TaskRef {
+    /// Namespace e alias de uma referência por alias
+    ///
+    /// Aliases não qualificados são procurados em `namespace`.
+    pub fn alias_in<'a>(&'a self, namespace: &'a str) -> Option<(&'a str, &'a str)> {
+        match self {
+            TaskRef::Id(_) => None,
+            TaskRef::Alias(qualified) => Some(split_alias(qualified, namespace)),
+        }
+    }
+}
+
+impl FromStr for TaskRef {
+    type Err = TaskMeshError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(id) = s.parse::<TaskId>() {
+            return Ok(TaskRef::Id(id));
+        }
+        let reference = TaskRef::Alias(s.to_string());
+        if let Some((namespace, alias)) = reference.alias_in(DEFAULT_NAMESPACE) {
+            if namespace.is_empty() {
+                return Err(TaskMeshError::Configuration(format!("Namespace vazio em '{}'", s)));
+            }
+            validate_alias(alias)?;
+        }
+        Ok(reference)
+    }
+}
+
+impl TryFrom<String> for TaskRef {
+    type Error = TaskMeshError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TaskRef> for String {
+    fn from(reference: TaskRef) -> Self {
+        reference.to_string()
+    }
+}
+
+impl From<TaskId> for TaskRef {
+    fn from(id: TaskId) -> Self {
+        TaskRef::Id(id)
+    }
+}
+
+impl From<&TaskId> for TaskRef {
+    fn from(id: &TaskId) -> Self {
+        TaskRef::Id(*id)
+    }
+}
+
+impl fmt::Display for TaskRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskRef::Id(id) => write!(f, "{}", id),
+            TaskRef::Alias(alias) => write!(f, "{}", alias),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_ref_parsing_and_alias_validation() {
+        let id = TaskId::new_v4();
+        assert_eq!(id.to_string().parse::<TaskRef>().unwrap(), TaskRef::Id(id));
+
+        let plain: TaskRef = "nightly-etl".parse().unwrap();
+        assert_eq!(plain.alias_in(DEFAULT_NAMESPACE), Some((DEFAULT_NAMESPACE, "nightly-etl")));
+        let qualified: TaskRef = "data/nightly-etl".parse().unwrap();
+        assert_eq!(qualified.alias_in(DEFAULT_NAMESPACE), Some(("data", "nightly-etl")));
+
+        let too_long = "a".repeat(MAX_ALIAS_LEN + 1);
+        for bad in ["Nightly", "-etl", "etl_nightly", too_long.as_str(), "/etl"] {
+            assert!(bad.parse::<TaskRef>().is_err(), "{} deveria ser recusado", bad);
+        }
+        assert!(validate_alias(&id.to_string()).is_err());
+    }
+}
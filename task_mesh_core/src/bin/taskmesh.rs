@@ -0,0 +1,575 @@
+//! CLI do TaskMesh Core
+//!
+//! Uso:
+//!   taskmesh report [--database-url URL] [--format json|ascii|html] [--output ARQUIVO] <task_id|alias>...
+//!   taskmesh status [--database-url URL] --task <task_id|alias>
+//!   taskmesh list [--database-url URL] [--search TEXTO] [--limit N]
+//!   taskmesh queue [--database-url URL] [--json] [--task <task_id|alias>]
+//!   taskmesh bump [--database-url URL] <task_id|alias> <low|normal|high|critical|0-100>
+//!   taskmesh tune [--database-url URL] [--heuristic fifo|lifo|priority|sjf|edf|critical_ratio|hybrid] [--max-concurrency N]
+//!   taskmesh group status [--database-url URL] <group_id>
+//!   taskmesh group list [--database-url URL]
+//!   taskmesh migrate [--database-url URL] [--check]
+//!   taskmesh doctor [--database-url URL] [--redis-url URL] [--data-dir DIR] [--json]
+//!   taskmesh eval <trace.json> [--heuristics fifo,priority,critical_ratio] [--json]
+//!   taskmesh export [--database-url URL] [--since 30d] [--namespace NS]... [--format csv|parquet] [--batch-size N] <diretório>
+//!   taskmesh snapshot diff [--config ARQUIVO] [--json] <snapshot_a> <snapshot_b>
+
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use task_mesh_core::scheduler::evaluation::WorkloadTrace;
+use task_mesh_core::{
+    migrations, CheckStatus, Mode, PreflightReport, Priority, ReportFormat, Scheduler, SchedulingHeuristic,
+    TaskMeshConfig, TaskMeshCore, TaskMeshError, TaskRef,
+};
+
+const USAGE: &str = "uso:
+  taskmesh report [--database-url URL] [--format json|ascii|html] [--output ARQUIVO] <task_id|alias>...
+  taskmesh status [--database-url URL] --task <task_id|alias>
+  taskmesh list [--database-url URL] [--search TEXTO] [--limit N]
+  taskmesh queue [--database-url URL] [--json] [--task <task_id|alias>]
+  taskmesh bump [--database-url URL] <task_id|alias> <low|normal|high|critical|0-100>
+  taskmesh tune [--database-url URL] [--heuristic fifo|lifo|priority|sjf|edf|critical_ratio|hybrid] [--max-concurrency N]
+  taskmesh group status [--database-url URL] <group_id>
+  taskmesh group list [--database-url URL]
+  taskmesh migrate [--database-url URL] [--check]
+  taskmesh doctor [--database-url URL] [--redis-url URL] [--data-dir DIR] [--json]
+  taskmesh eval <trace.json> [--heuristics fifo,priority,critical_ratio] [--json]
+  taskmesh export [--database-url URL] [--since 30d] [--namespace NS]... [--format csv|parquet] [--batch-size N] <diretório>
+  taskmesh snapshot diff [--config ARQUIVO] [--json] <snapshot_a> <snapshot_b>";
+
+/// Heurísticas comparadas quando `--heuristics` é omitido
+const DEFAULT_EVAL_HEURISTICS: &str = "fifo,priority,shortest_job_first,earliest_deadline_first,critical_ratio,hybrid";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("report") => run_report(&args[1..]).await,
+        Some("status") => run_status(&args[1..]).await,
+        Some("list") => run_list(&args[1..]).await,
+        Some("queue") => run_queue(&args[1..]).await,
+        Some("bump") => run_bump(&args[1..]).await,
+        Some("tune") => run_tune(&args[1..]).await,
+        Some("group") => run_group(&args[1..]).await,
+        Some("migrate") => run_migrate(&args[1..]).await,
+        Some("doctor") => run_doctor(&args[1..]).await,
+        Some("eval") => run_eval(&args[1..]),
+        Some("export") => run_export(&args[1..]).await,
+        Some("snapshot") => run_snapshot(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("erro: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Subcomando `report`
+async fn run_report(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut config = TaskMeshConfig::default();
+    let mut format = ReportFormat::AsciiGantt;
+    let mut output = None;
+    let mut task_refs: Vec<TaskRef> = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            "--format" => format = ReportFormat::from_str(&next_value(&mut iter, arg)?)?,
+            "--output" => output = Some(next_value(&mut iter, arg)?),
+            reference => task_refs.push(reference.parse()?),
+        }
+    }
+
+    if task_refs.is_empty() {
+        return Err(TaskMeshError::Configuration(USAGE.to_string()));
+    }
+
+    let core = TaskMeshCore::new(config).await?;
+    let mut task_ids = Vec::with_capacity(task_refs.len());
+    for reference in task_refs {
+        task_ids.push(core.resolve_task(reference).await?);
+    }
+    let rendered = core.generate_report(&task_ids, format).await?;
+
+    match output {
+        Some(path) => std::fs::write(path, rendered)?,
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Subcomando `status`
+async fn run_status(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut config = TaskMeshConfig::default();
+    let mut task = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            "--task" => task = Some(next_value(&mut iter, arg)?.parse::<TaskRef>()?),
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+    let task = task.ok_or_else(|| TaskMeshError::Configuration(USAGE.to_string()))?;
+
+    let core = TaskMeshCore::new(config).await?;
+    let task_id = core.resolve_task(task).await?;
+    println!("{} {}", task_id, core.get_task_status(&task_id).await?);
+    Ok(())
+}
+
+/// Limite padrão de `list`
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+/// Subcomando `list`: tarefas, opcionalmente filtradas por busca textual
+async fn run_list(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut config = TaskMeshConfig::default();
+    let mut search = None;
+    let mut limit = DEFAULT_LIST_LIMIT;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            "--search" => search = Some(next_value(&mut iter, arg)?),
+            "--limit" => {
+                let value = next_value(&mut iter, arg)?;
+                limit = value
+                    .parse::<usize>()
+                    .map_err(|_| TaskMeshError::Configuration(format!("--limit inválido: {}", value)))?;
+            }
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+
+    let core = TaskMeshCore::new(config).await?;
+    let tasks = match search {
+        Some(query) => core.search_tasks(&query, limit).await?,
+        None => core.list_tasks().await?.into_iter().take(limit).collect(),
+    };
+    for task in tasks {
+        let status = core.get_task_status(&task.id).await?;
+        println!("{} {} {}", task.id, task.name, status);
+    }
+    Ok(())
+}
+
+/// Subcomando `queue`: tarefas aguardando despacho e o que as retém
+///
+/// Com `--task`, explica por que a tarefa ainda não executa. A fila é
+/// reconstruída do banco em um core somente leitura.
+async fn run_queue(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut config = TaskMeshConfig { mode: Mode::ReadOnly, ..TaskMeshConfig::default() };
+    let mut json = false;
+    let mut task = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            "--json" => json = true,
+            "--task" => task = Some(next_value(&mut iter, arg)?.parse::<TaskRef>()?),
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+
+    let core = TaskMeshCore::new(config).await?;
+    core.load_queue().await?;
+
+    if let Some(task) = task {
+        let task_id = core.resolve_task(task).await?;
+        let reason = core.why_not_running(&task_id).await?;
+        match (json, reason) {
+            (true, reason) => println!("{}", serde_json::to_string_pretty(&reason)?),
+            (false, Some(reason)) => println!("{} {}", task_id, reason),
+            (false, None) => println!("{} fora da fila ({})", task_id, core.get_task_status(&task_id).await?),
+        }
+        return Ok(());
+    }
+
+    let snapshot = core.queue_snapshot().await;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        return Ok(());
+    }
+    for view in snapshot {
+        let blocked_on: Vec<String> = view.blocked_on.iter().map(|id| id.short()).collect();
+        println!(
+            "{} {} prioridade {} (score {:.2}), na fila há {}s, estimada em {}s, bloqueada por [{}], retida por {}",
+            view.task_id,
+            view.name,
+            view.effective_priority,
+            view.priority_score,
+            view.queued_for.as_secs(),
+            view.estimated_duration.as_secs(),
+            blocked_on.join(", "),
+            view.throttled_by.as_deref().unwrap_or("-"),
+        );
+    }
+    Ok(())
+}
+
+/// Subcomando `bump`: altera a prioridade de uma tarefa ainda na fila
+///
+/// Grava a nova prioridade (e a herdada pelos ancestrais) no banco; um
+/// processo já em execução a aplica ao recarregar a fila.
+async fn run_bump(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut config = TaskMeshConfig::default();
+    let mut positional = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            value => positional.push(value),
+        }
+    }
+    let [task, priority] = positional[..] else {
+        return Err(TaskMeshError::Configuration(USAGE.to_string()));
+    };
+    let task: TaskRef = task.parse()?;
+    let priority = parse_priority(priority)?;
+
+    let core = TaskMeshCore::new(config).await?;
+    core.load_queue().await?;
+    for task_id in core.reprioritize(task, priority).await? {
+        println!("{} prioridade {}", task_id, priority);
+    }
+    Ok(())
+}
+
+/// Subcomando `tune`: troca a heurística de agendamento e a concorrência máxima
+///
+/// Grava os ajustes no banco; um processo já em execução os aplica ao
+/// recarregar a fila, e os próximos inícios partem deles.
+async fn run_tune(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut config = TaskMeshConfig::default();
+    let mut heuristic = None;
+    let mut max_concurrency = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            "--heuristic" => heuristic = Some(SchedulingHeuristic::from_str(&next_value(&mut iter, arg)?)?),
+            "--max-concurrency" => {
+                let value = next_value(&mut iter, arg)?;
+                max_concurrency = Some(value.parse::<usize>().map_err(|_| {
+                    TaskMeshError::Configuration(format!("--max-concurrency inválido: {}", value))
+                })?);
+            }
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+    if heuristic.is_none() && max_concurrency.is_none() {
+        return Err(TaskMeshError::Configuration(USAGE.to_string()));
+    }
+
+    let core = TaskMeshCore::new(config).await?;
+    if let Some(heuristic) = heuristic {
+        core.set_scheduling_heuristic(heuristic.clone()).await?;
+        println!("heurística {:?}", heuristic);
+    }
+    if let Some(limit) = max_concurrency {
+        core.set_max_concurrency(limit).await?;
+        println!("concorrência máxima {}", limit);
+    }
+    Ok(())
+}
+
+/// Prioridade por nome (`low`, `normal`, `high`, `critical`) ou valor de 0 a 100
+fn parse_priority(value: &str) -> Result<Priority, TaskMeshError> {
+    match value.to_ascii_lowercase().as_str() {
+        "low" => Ok(Priority::LOW),
+        "normal" => Ok(Priority::NORMAL),
+        "high" => Ok(Priority::HIGH),
+        "critical" => Ok(Priority::CRITICAL),
+        other => other
+            .parse::<u8>()
+            .map_err(|_| TaskMeshError::Configuration(format!("prioridade inválida: {}", value)))
+            .and_then(Priority::custom),
+    }
+}
+
+/// Subcomando `group`: status agregado de grupos de tarefas
+async fn run_group(args: &[String]) -> Result<(), TaskMeshError> {
+    let action = args.first().map(String::as_str);
+    let mut config = TaskMeshConfig::default();
+    let mut group_id = None;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            value if group_id.is_none() && action == Some("status") => {
+                group_id = Some(value.parse::<uuid::Uuid>().map_err(|e| {
+                    TaskMeshError::Configuration(format!("ID de grupo inválido '{}': {}", value, e))
+                })?)
+            }
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+
+    let core = TaskMeshCore::new(config).await?;
+    let groups = match (action, group_id) {
+        (Some("status"), Some(group_id)) => vec![core
+            .group_status(&group_id)
+            .await?
+            .ok_or_else(|| TaskMeshError::Configuration(format!("grupo não encontrado: {}", group_id)))?],
+        (Some("list"), _) => core.list_groups().await?,
+        _ => return Err(TaskMeshError::Configuration(USAGE.to_string())),
+    };
+    for group in groups {
+        let counts = &group.counts;
+        println!(
+            "{} {} {} (pendentes {}, agendadas {}, executando {}, concluídas {}, falhas {}, aguardando retry {}, canceladas {}, pausadas {})",
+            group.group_id,
+            group.name.as_deref().unwrap_or("-"),
+            group.state,
+            counts.pending,
+            counts.scheduled,
+            counts.running,
+            counts.completed,
+            counts.failed,
+            counts.awaiting_retry,
+            counts.cancelled,
+            counts.paused,
+        );
+    }
+    Ok(())
+}
+
+/// Subcomando `migrate`
+///
+/// Com `--check` apenas lista as migrações pendentes e retorna erro se houver alguma.
+async fn run_migrate(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut database_url = TaskMeshConfig::default().database_url;
+    let mut check_only = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => database_url = next_value(&mut iter, arg)?,
+            "--check" => check_only = true,
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+
+    let report = if database_url.starts_with("postgres") {
+        let pool = sqlx::PgPool::connect(&database_url).await?;
+        migrations::migrate_postgres(&pool, check_only).await?
+    } else {
+        let pool = sqlx::SqlitePool::connect(&database_url).await?;
+        migrations::migrate_sqlite(&pool, check_only).await?
+    };
+
+    println!("versão atual: {}, versão alvo: {}", report.current_version, report.target_version);
+    for (version, description) in &report.pending {
+        println!("  {} {}: {}", if report.applied { "aplicada" } else { "pendente" }, version, description);
+    }
+
+    if check_only && !report.is_up_to_date() {
+        return Err(TaskMeshError::Configuration(format!(
+            "{} migrações pendentes",
+            report.pending.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Subcomando `doctor`: verifica configuração e conectividade
+///
+/// Retorna erro se alguma verificação falhar; avisos não alteram o resultado.
+async fn run_doctor(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut config = TaskMeshConfig::default();
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            "--redis-url" => config.redis_url = Some(next_value(&mut iter, arg)?),
+            "--data-dir" => config.data_dir = Some(next_value(&mut iter, arg)?),
+            "--json" => json = true,
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+
+    let report = match TaskMeshCore::new(config).await {
+        Ok(core) => core.preflight().await,
+        Err(e) => PreflightReport::connection_failure(&e),
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for check in &report.checks {
+            let status = match check.status {
+                CheckStatus::Pass => "ok",
+                CheckStatus::Warn => "aviso",
+                CheckStatus::Fail => "FALHA",
+            };
+            println!("{:<6} {:<12} {}", status, check.id.as_str(), check.detail);
+            if let Some(hint) = &check.hint {
+                println!("{:<19} {}", "", hint);
+            }
+        }
+    }
+
+    if !report.passed() {
+        let failed = report.checks.iter().filter(|check| check.status == CheckStatus::Fail).count();
+        return Err(TaskMeshError::Configuration(format!("{} verificações falharam", failed)));
+    }
+    Ok(())
+}
+
+/// Subcomando `export`: grava o histórico em arquivos CSV/Parquet
+///
+/// Rodar de novo com os mesmos argumentos retoma uma exportação interrompida.
+#[cfg(feature = "export")]
+async fn run_export(args: &[String]) -> Result<(), TaskMeshError> {
+    use task_mesh_core::export::{ExportFormat, ExportRequest};
+    use task_mesh_core::units::DurationSpec;
+
+    let mut config = TaskMeshConfig::default();
+    let mut format = ExportFormat::Csv;
+    let mut since = None;
+    let mut namespaces = Vec::new();
+    let mut batch_size = None;
+    let mut destination = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--database-url" => config.database_url = next_value(&mut iter, arg)?,
+            "--format" => format = next_value(&mut iter, arg)?.parse()?,
+            "--since" => since = Some(next_value(&mut iter, arg)?.parse::<DurationSpec>()?.as_duration()),
+            "--namespace" => namespaces.push(next_value(&mut iter, arg)?),
+            "--batch-size" => {
+                let value = next_value(&mut iter, arg)?;
+                batch_size = Some(value.parse::<usize>().map_err(|_| {
+                    TaskMeshError::Configuration(format!("--batch-size inválido: {}", value))
+                })?);
+            }
+            path => destination = Some(path.to_string()),
+        }
+    }
+    let destination = destination.ok_or_else(|| TaskMeshError::Configuration(USAGE.to_string()))?;
+
+    let mut request = ExportRequest::new(format, destination);
+    request.namespaces = namespaces;
+    request.range.0 = since.map(|age| std::time::SystemTime::now() - age);
+    if let Some(batch_size) = batch_size {
+        request.batch_size = batch_size;
+    }
+
+    let core = TaskMeshCore::new(config).await?;
+    let summary = core.export_history(request).await?;
+    println!(
+        "{} linhas em {} arquivos ({} lotes{})",
+        summary.rows,
+        summary.files,
+        summary.batches,
+        if summary.resumed_from > 0 { format!(", retomado do lote {}", summary.resumed_from) } else { String::new() }
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "export"))]
+async fn run_export(_args: &[String]) -> Result<(), TaskMeshError> {
+    Err(TaskMeshError::Configuration(
+        "taskmesh foi compilado sem a feature `export`".to_string(),
+    ))
+}
+
+/// Binário do orchestrator que implementa o subcomando `snapshot`
+const SNAPSHOT_TOOL: &str = "taskmesh-snapshot";
+
+/// Subcomando `snapshot`: delegado ao `taskmesh-snapshot` do orchestrator_core
+///
+/// Os snapshots do TaskGraph pertencem ao orchestrator; o binário é procurado
+/// ao lado deste executável e, em seguida, no PATH.
+fn run_snapshot(args: &[String]) -> Result<(), TaskMeshError> {
+    let sibling = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(SNAPSHOT_TOOL)))
+        .filter(|path| path.is_file());
+    let program = sibling.map_or_else(|| SNAPSHOT_TOOL.into(), std::ffi::OsString::from);
+
+    let status = std::process::Command::new(&program).args(args).status().map_err(|e| {
+        TaskMeshError::Configuration(format!("não foi possível executar {}: {}", SNAPSHOT_TOOL, e))
+    })?;
+    if !status.success() {
+        return Err(TaskMeshError::Configuration(format!("{} terminou com {}", SNAPSHOT_TOOL, status)));
+    }
+    Ok(())
+}
+
+/// Subcomando `eval`: compara heurísticas sobre um trace gravado
+fn run_eval(args: &[String]) -> Result<(), TaskMeshError> {
+    let mut trace_path = None;
+    let mut heuristics = DEFAULT_EVAL_HEURISTICS.to_string();
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--heuristics" => heuristics = next_value(&mut iter, arg)?,
+            "--json" => json = true,
+            path if trace_path.is_none() => trace_path = Some(path.to_string()),
+            other => {
+                return Err(TaskMeshError::Configuration(format!("argumento desconhecido: {}", other)))
+            }
+        }
+    }
+
+    let trace_path = trace_path.ok_or_else(|| TaskMeshError::Configuration(USAGE.to_string()))?;
+    let trace = WorkloadTrace::load(&trace_path)?;
+    let heuristics = heuristics
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(SchedulingHeuristic::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let report = Scheduler::evaluate(&trace, &heuristics)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_table());
+    }
+    Ok(())
+}
+
+fn next_value<'a>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<String, TaskMeshError> {
+    iter.next()
+        .cloned()
+        .ok_or_else(|| TaskMeshError::Configuration(format!("valor ausente para {}", flag)))
+}
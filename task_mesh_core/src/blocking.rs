@@ -0,0 +1,252 @@
+//! Fachada síncrona para código sem runtime async
+//!
+//! Ferramentas escritas com threads ou rayon usam o [`TaskMeshClient`] em vez
+//! de montar um runtime tokio só para chamar o core. O cliente tem um
+//! runtime `current_thread` próprio, conduzido por uma thread dedicada
+//! (`taskmesh-runtime`) para que os loops de background do core continuem
+//! rodando entre as chamadas; cada método bloqueia a thread chamadora até o
+//! resultado.
+//!
+//! Os métodos não podem ser chamados de dentro de um contexto async (uma
+//! task tokio, um `block_on`): bloquear ali trava o runtime de quem chamou.
+//! Nesse caso retornam [`TaskMeshError::BlockingInAsyncContext`] sem
+//! bloquear; código async deve usar o [`TaskMeshCore`] diretamente.
+//!
+//! ```no_run
+//! use task_mesh_core::blocking::TaskMeshClient;
+//! use task_mesh_core::{Task, TaskDefinition, TaskMeshConfig};
+//!
+//! let client = TaskMeshClient::new(TaskMeshConfig::default())?;
+//! let task = Task::new("relatorio".to_string(), TaskDefinition::command("make report"), vec![]);
+//! let task_id = client.submit_task(task)?;
+//! let status = client.wait_for(task_id, std::time::Duration::from_secs(60))?;
+//! # Ok::<(), task_mesh_core::TaskMeshError>(())
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, Runtime};
+use tokio::sync::oneshot;
+use tracing::{error, warn};
+
+use crate::alias::TaskRef;
+use crate::types::*;
+use crate::{TaskMeshConfig, TaskMeshCore};
+
+/// Intervalo entre consultas de status em [`TaskMeshClient::wait_for`]
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Espera máxima pelas tarefas do runtime no drop
+const RUNTIME_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Cliente síncrono do TaskMesh (ver o módulo)
+pub struct TaskMeshClient {
+    core: Arc<TaskMeshCore>,
+    /// `None` só durante o drop
+    runtime: Option<Arc<Runtime>>,
+    stop: Option<oneshot::Sender<()>>,
+    driver: Option<JoinHandle<()>>,
+}
+
+/// Recusa chamadas bloqueantes de dentro de um runtime tokio
+fn ensure_blocking_allowed(operation: &str) -> TaskMeshResult<()> {
+    match Handle::try_current() {
+        Ok(_) => Err(TaskMeshError::BlockingInAsyncContext(operation.to_string())),
+        Err(_) => Ok(()),
+    }
+}
+
+impl TaskMeshClient {
+    /// Cria e inicia um core com `config`
+    pub fn new(config: TaskMeshConfig) -> TaskMeshResult<Self> {
+        ensure_blocking_allowed("TaskMeshClient::new")?;
+        let runtime = Arc::new(
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        );
+        let core = runtime.block_on(async {
+            let core = TaskMeshCore::new(config).await?;
+            core.start().await?;
+            Ok::<_, TaskMeshError>(Arc::new(core))
+        })?;
+
+        let (stop, stopped) = oneshot::channel::<()>();
+        let driver = {
+            let runtime = runtime.clone();
+            std::thread::Builder::new()
+                .name("taskmesh-runtime".to_string())
+                .spawn(move || {
+                    runtime.block_on(async {
+                        let _ = stopped.await;
+                    })
+                })?
+        };
+        Ok(Self { core, runtime: Some(runtime), stop: Some(stop), driver: Some(driver) })
+    }
+
+    /// Core subjacente, para as operações sem equivalente bloqueante
+    pub fn core(&self) -> &Arc<TaskMeshCore> {
+        &self.core
+    }
+
+    /// Executa `future` no runtime do cliente, bloqueando a thread atual
+    fn block_on<F: Future>(&self, operation: &str, future: F) -> TaskMeshResult<F::Output> {
+        ensure_blocking_allowed(operation)?;
+        let runtime = self.runtime.as_ref().expect("runtime ativo até o drop");
+        Ok(runtime.block_on(future))
+    }
+
+    /// Ver [`TaskMeshCore::submit_task`]
+    pub fn submit_task(&self, task: Task) -> TaskMeshResult<TaskId> {
+        self.block_on("submit_task", self.core.submit_task(task))?
+    }
+
+    /// Ver [`TaskMeshCore::submit_batch`]
+    pub fn submit_batch(&self, tasks: Vec<Task>) -> TaskMeshResult<Vec<TaskId>> {
+        self.block_on("submit_batch", self.core.submit_batch(tasks))?
+    }
+
+    /// Ver [`TaskMeshCore::get_task_status`]
+    pub fn get_task_status(&self, task: impl Into<TaskRef>) -> TaskMeshResult<TaskStatus> {
+        self.block_on("get_task_status", self.core.get_task_status(task))?
+    }
+
+    /// Espera a tarefa chegar a um status final por até `timeout`
+    ///
+    /// Retorna o status final ou, se `timeout` vencer antes, o status atual
+    /// (verifique com [`TaskStatus::is_final`]).
+    pub fn wait_for(&self, task: impl Into<TaskRef>, timeout: Duration) -> TaskMeshResult<TaskStatus> {
+        let task = task.into();
+        self.block_on("wait_for", async {
+            let task_id = self.core.resolve_task(task).await?;
+            let deadline = Instant::now() + timeout;
+            loop {
+                let status = self.core.get_task_status(task_id).await?;
+                if status.is_final() || Instant::now() >= deadline {
+                    return Ok(status);
+                }
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                tokio::time::sleep(WAIT_POLL_INTERVAL.min(remaining)).await;
+            }
+        })?
+    }
+
+    /// Ver [`TaskMeshCore::cancel_task`]
+    pub fn cancel_task(&self, task: impl Into<TaskRef>) -> TaskMeshResult<()> {
+        self.block_on("cancel_task", self.core.cancel_task(task))?
+    }
+
+    /// Ver [`TaskMeshCore::list_tasks`]
+    pub fn list_tasks(&self) -> TaskMeshResult<Vec<Task>> {
+        self.block_on("list_tasks", self.core.list_tasks())?
+    }
+
+    /// Ver [`TaskMeshCore::list_tasks_with_tag`]
+    pub fn list_tasks_with_tag(&self, tag: &str) -> TaskMeshResult<Vec<Task>> {
+        self.block_on("list_tasks_with_tag", self.core.list_tasks_with_tag(tag))?
+    }
+
+    /// Ver [`TaskMeshCore::search_tasks`]
+    pub fn search_tasks(&self, query: &str, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        self.block_on("search_tasks", self.core.search_tasks(query, limit))?
+    }
+}
+
+impl Drop for TaskMeshClient {
+    /// Para o core, a thread do runtime e o runtime
+    ///
+    /// Dentro de um contexto async não é possível esperar: o runtime é
+    /// encerrado em segundo plano, sem o shutdown do core.
+    fn drop(&mut self) {
+        let in_async_context = Handle::try_current().is_ok();
+        if !in_async_context {
+            if let Err(e) = self.block_on("drop", self.core.shutdown()).and_then(|result| result) {
+                error!("Erro no shutdown do TaskMeshClient: {}", e);
+            }
+        }
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if let Some(driver) = self.driver.take() {
+            if !in_async_context && driver.join().is_err() {
+                warn!("Thread do runtime do TaskMeshClient terminou com pânico");
+            }
+        }
+        let runtime = self.runtime.take().and_then(|runtime| Arc::try_unwrap(runtime).ok());
+        match runtime {
+            Some(runtime) if in_async_context => runtime.shutdown_background(),
+            Some(runtime) => runtime.shutdown_timeout(RUNTIME_SHUTDOWN_TIMEOUT),
+            None => warn!("Runtime do TaskMeshClient ainda em uso no drop"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> TaskMeshClient {
+        TaskMeshClient::new(TaskMeshConfig { max_workers: 2, ..TaskMeshConfig::default() }).unwrap()
+    }
+
+    fn task(name: &str) -> Task {
+        Task::new(name.to_string(), TaskDefinition::command("true"), vec![]).with_tags(vec!["tool".to_string()])
+    }
+
+    #[test]
+    fn test_client_from_plain_threads() {
+        let client = Arc::new(client());
+        let submitters: Vec<_> = (0..4)
+            .map(|i| {
+                let client = client.clone();
+                std::thread::spawn(move || {
+                    let task_id = client.submit_task(task(&format!("thread-{}", i))).unwrap();
+                    assert_eq!(client.get_task_status(task_id).unwrap(), TaskStatus::Pending);
+                    task_id
+                })
+            })
+            .collect();
+        let ids: Vec<TaskId> = submitters.into_iter().map(|handle| handle.join().unwrap()).collect();
+        let batch = client.submit_batch(vec![task("batch-a"), task("batch-b")]).unwrap();
+        assert_eq!(client.list_tasks().unwrap().len(), 6);
+        assert_eq!(client.list_tasks_with_tag("tool").unwrap().len(), 6);
+
+        // Vence o timeout: devolve o status atual
+        let pending = client.wait_for(batch[0], Duration::from_millis(50)).unwrap();
+        assert!(!pending.is_final());
+
+        let waiter = {
+            let client = client.clone();
+            let task_id = ids[0];
+            std::thread::spawn(move || client.wait_for(task_id, Duration::from_secs(10)).unwrap())
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        client.cancel_task(ids[0]).unwrap();
+        assert!(matches!(waiter.join().unwrap(), TaskStatus::Cancelled { .. }));
+
+        let client = Arc::try_unwrap(client).ok().unwrap();
+        drop(client);
+    }
+
+    #[test]
+    fn test_calls_from_async_context_are_rejected() {
+        let client = client();
+        let task_id = client.submit_task(task("sync")).unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let misuse = client.get_task_status(task_id).unwrap_err();
+            assert!(matches!(misuse, TaskMeshError::BlockingInAsyncContext(ref op) if op == "get_task_status"), "{}", misuse);
+            assert!(matches!(
+                TaskMeshClient::new(TaskMeshConfig::default()),
+                Err(TaskMeshError::BlockingInAsyncContext(_))
+            ));
+        });
+        drop(runtime);
+
+        assert_eq!(client.get_task_status(task_id).unwrap(), TaskStatus::Pending);
+    }
+}
@@ -0,0 +1,268 @@
+//! Limites rígidos de memória e CPU via cgroup v2 (Linux)
+//!
+//! Com `ExecutorConfig::cgroups` configurado, cada processo de tarefa roda em
+//! um cgroup transitório `{parent}/task-{task_id}` com `memory.max` e
+//! `cpu.max` derivados da [`ResourceAllocation`]. O filho entra no cgroup
+//! entre o `fork` e o `exec`, antes de alocar qualquer memória. Quando o
+//! kernel mata o processo por falta de memória (ou ele falha após atingir
+//! `memory.max`), a execução termina em `ResourceLimitExceeded` com o conteúdo
+//! de `memory.events`. O limite de CPU apenas estrangula o processo: não é
+//! tratado como falha.
+//!
+//! O cgroup é removido ao fim da execução; os que sobrarem (processos
+//! ainda saindo, reinícios) são removidos pela varredura periódica. Sem
+//! cgroup v2, sem permissão de escrita em `parent` ou fora do Linux, o
+//! executor segue apenas com os limites contábeis, com um único aviso.
+
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::types::*;
+
+/// Período do `cpu.max`, em microssegundos
+const CPU_PERIOD_US: u64 = 100_000;
+
+/// Cota mínima aceita pelo kernel, em microssegundos
+const CPU_MIN_QUOTA_US: u64 = 1_000;
+
+/// Prefixo dos cgroups criados pelo executor
+const TASK_CGROUP_PREFIX: &str = "task-";
+
+static DEGRADED_WARNING: Once = Once::new();
+
+/// Configuração dos cgroups por tarefa
+#[derive(Debug, Clone)]
+pub struct CgroupConfig {
+    /// cgroup pai, criado se não existir (ex.: `/sys/fs/cgroup/taskmesh.slice`)
+    pub parent: PathBuf,
+    /// Intervalo da varredura de cgroups órfãos
+    pub sweep_interval: Duration,
+}
+
+impl CgroupConfig {
+    /// Configuração padrão para um cgroup pai
+    pub fn new(parent: impl Into<PathBuf>) -> Self {
+        Self { parent: parent.into(), sweep_interval: Duration::from_secs(10 * 60) }
+    }
+}
+
+/// cgroups das tarefas sob o cgroup pai
+#[derive(Debug)]
+pub struct CgroupManager {
+    config: CgroupConfig,
+    /// cgroups com processo em execução
+    active: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl CgroupManager {
+    /// Prepara o cgroup pai, ou `None` se os cgroups não puderem ser usados
+    ///
+    /// A indisponibilidade gera um único aviso por processo.
+    pub fn new(config: CgroupConfig) -> Option<Self> {
+        match Self::prepare(&config.parent) {
+            Ok(()) => {
+                info!("Limites de recursos via cgroup v2 em {}", config.parent.display());
+                Some(Self { config, active: Arc::new(Mutex::new(HashSet::new())) })
+            }
+            Err(reason) => {
+                DEGRADED_WARNING.call_once(|| {
+                    warn!("cgroups indisponíveis ({}); usando apenas limites contábeis", reason);
+                });
+                None
+            }
+        }
+    }
+
+    pub fn config(&self) -> &CgroupConfig {
+        &self.config
+    }
+
+    /// Cria o pai e habilita os controladores de memória e CPU para os filhos
+    fn prepare(parent: &Path) -> Result<(), String> {
+        if !cfg!(target_os = "linux") {
+            return Err("plataforma sem cgroups".to_string());
+        }
+        let root = parent.parent().ok_or_else(|| format!("{} não tem cgroup acima", parent.display()))?;
+        let controllers = std::fs::read_to_string(root.join("cgroup.controllers"))
+            .map_err(|_| format!("{} não é um cgroup v2", root.display()))?;
+        for controller in ["memory", "cpu"] {
+            if !controllers.split_whitespace().any(|available| available == controller) {
+                return Err(format!("controlador {} indisponível em {}", controller, root.display()));
+            }
+        }
+        std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        write_control(parent, "cgroup.subtree_control", "+memory +cpu")
+            .map_err(|e| format!("{}/cgroup.subtree_control: {}", parent.display(), e))
+    }
+
+    /// Cria o cgroup de um processo da tarefa com os limites de `resources`
+    pub fn create(&self, task_id: &TaskId, resources: &ResourceAllocation) -> TaskMeshResult<TaskCgroup> {
+        let path = self.config.parent.join(format!("{}{}", TASK_CGROUP_PREFIX, task_id));
+        if path.exists() {
+            remove_cgroup(&path);
+        }
+        std::fs::create_dir(&path)?;
+        let cgroup = TaskCgroup {
+            procs: CString::new(path.join("cgroup.procs").to_string_lossy().as_bytes())
+                .map_err(|e| TaskMeshError::Internal(format!("Caminho de cgroup inválido: {}", e)))?,
+            memory_max: resources.memory_bytes,
+            path,
+            active: self.active.clone(),
+        };
+        self.active.lock().unwrap().insert(cgroup.path.clone());
+        write_control(&cgroup.path, "memory.max", &resources.memory_bytes.to_string())?;
+        write_control(&cgroup.path, "memory.swap.max", "0").ok();
+        write_control(&cgroup.path, "cpu.max", &cpu_max(resources.cpu_cores))?;
+        debug!("cgroup {} criado", cgroup.path.display());
+        Ok(cgroup)
+    }
+
+    /// Remove cgroups de tarefas sem processo ativo
+    ///
+    /// Retorna o número de cgroups removidos.
+    pub fn sweep(&self) -> TaskMeshResult<usize> {
+        let mut removed = 0;
+        for entry in std::fs::read_dir(&self.config.parent)?.flatten() {
+            let path = entry.path();
+            let ours = entry.file_name().to_str().is_some_and(|name| name.starts_with(TASK_CGROUP_PREFIX));
+            if !ours || !path.is_dir() || self.active.lock().unwrap().contains(&path) {
+                continue;
+            }
+            debug!("Removendo cgroup órfão {}", path.display());
+            if remove_cgroup(&path) {
+                removed += 1;
+            }
+        }
+        info!("Varredura de cgroups: {} removidos", removed);
+        Ok(removed)
+    }
+}
+
+/// cgroup de um processo de tarefa, removido ao ser descartado
+#[derive(Debug)]
+pub struct TaskCgroup {
+    path: PathBuf,
+    /// `cgroup.procs`, preparado para uso entre o `fork` e o `exec`
+    procs: CString,
+    memory_max: u64,
+    active: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl TaskCgroup {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Faz o processo entrar no cgroup antes do `exec`
+    #[cfg(unix)]
+    pub(crate) fn attach(&self, cmd: &mut tokio::process::Command) {
+        let procs = self.procs.clone();
+        // SAFETY: entre o fork e o exec só há open/write/close, sem alocação;
+        // escrever "0" em cgroup.procs move o próprio processo
+        unsafe {
+            cmd.pre_exec(move || {
+                let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+                if fd < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+                let error = std::io::Error::last_os_error();
+                libc::close(fd);
+                if written != 1 {
+                    return Err(error);
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// Contadores de `memory.events` (`oom_kill`, `max`, ...)
+    pub fn memory_events(&self) -> Vec<(String, u64)> {
+        std::fs::read_to_string(self.path.join("memory.events"))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                Some((key.to_string(), value.trim().parse().ok()?))
+            })
+            .collect()
+    }
+
+    /// Erro a reportar se o processo esbarrou em `memory.max`
+    ///
+    /// `succeeded` indica se o processo saiu com sucesso: atingir o limite e
+    /// se recuperar (ex.: liberando cache) não é uma falha.
+    pub fn limit_exceeded(&self, succeeded: bool) -> Option<TaskMeshError> {
+        let events = self.memory_events();
+        let count = |name: &str| events.iter().find(|(key, _)| key == name).map_or(0, |(_, value)| *value);
+        if count("oom_kill") == 0 && (succeeded || count("max") == 0) {
+            return None;
+        }
+        let events: Vec<String> = events.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        Some(TaskMeshError::ResourceLimitExceeded(format!(
+            "memória acima de memory.max ({} bytes); memory.events: {}",
+            self.memory_max,
+            events.join(", ")
+        )))
+    }
+}
+
+impl Drop for TaskCgroup {
+    fn drop(&mut self) {
+        self.active.lock().unwrap().remove(&self.path);
+        if !remove_cgroup(&self.path) {
+            debug!("cgroup {} fica para a varredura", self.path.display());
+        }
+    }
+}
+
+/// Valor de `cpu.max` para `cores` CPUs (`max` = sem limite)
+fn cpu_max(cores: f64) -> String {
+    if cores <= 0.0 || !cores.is_finite() {
+        return format!("max {}", CPU_PERIOD_US);
+    }
+    let quota = ((cores * CPU_PERIOD_US as f64).round() as u64).max(CPU_MIN_QUOTA_US);
+    format!("{} {}", quota, CPU_PERIOD_US)
+}
+
+fn write_control(cgroup: &Path, file: &str, value: &str) -> std::io::Result<()> {
+    std::fs::write(cgroup.join(file), value)
+}
+
+/// Encerra os processos restantes e remove o cgroup; `false` se ainda ocupado
+fn remove_cgroup(path: &Path) -> bool {
+    // cgroup.kill existe a partir do Linux 5.14; antes disso, processos
+    // restantes mantêm o cgroup até a próxima varredura
+    write_control(path, "cgroup.kill", "1").ok();
+    match std::fs::remove_dir(path) {
+        Ok(()) => true,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => true,
+        Err(e) => {
+            debug!("Falha ao remover cgroup {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_max_from_allocation() {
+        assert_eq!(cpu_max(1.0), "100000 100000");
+        assert_eq!(cpu_max(2.5), "250000 100000");
+        assert_eq!(cpu_max(0.001), "1000 100000");
+        assert_eq!(cpu_max(0.0), "max 100000");
+    }
+
+    #[test]
+    fn test_unavailable_parent_degrades() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(CgroupManager::new(CgroupConfig::new(dir.path().join("taskmesh"))).is_none());
+    }
+}
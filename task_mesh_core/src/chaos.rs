@@ -0,0 +1,636 @@
+//! Injeção de falhas para testes de resiliência
+//!
+//! O [`FaultInjector`] sorteia falhas a partir de uma semente fixa, de modo
+//! que a mesma configuração reproduz a mesma sequência de falhas. Ele atua
+//! em dois pontos:
+//!
+//! - [`ChaosStateStore`] envolve o StateStore real e injeta atrasos, erros,
+//!   desconexões simuladas do Redis e descarte de eventos;
+//! - o executor consulta o injetor antes de cada tarefa para simular a queda
+//!   do worker ([`crate::TaskExecutor::with_fault_injector`]).
+//!
+//! Cada falha disparada incrementa um contador ([`FaultCounts`]) para que os
+//! testes verifiquem o que de fato aconteceu.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::manifest::ExecutionManifest;
+use crate::state_store::{CompactionMode, StateStore, StorageStats};
+use crate::types::*;
+
+/// Prefixo das variáveis de ambiente lidas por [`ChaosConfig::from_env`]
+pub const ENV_PREFIX: &str = "TASKMESH_CHAOS_";
+
+/// Configuração das falhas injetadas
+///
+/// Probabilidades vão de 0.0 a 1.0 e são sorteadas a cada operação.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    /// Semente do gerador de números aleatórios
+    pub seed: u64,
+    /// Probabilidade de uma operação do StateStore falhar
+    pub store_error_probability: f64,
+    /// Probabilidade de uma operação do StateStore ser atrasada
+    pub store_delay_probability: f64,
+    /// Atraso injetado (ms)
+    pub store_delay_ms: u64,
+    /// Probabilidade de um evento ser descartado sem erro
+    pub event_drop_probability: f64,
+    /// Probabilidade de uma operação iniciar uma desconexão simulada do Redis
+    pub disconnect_probability: f64,
+    /// Duração da desconexão simulada (ms); nesse intervalo tudo falha
+    pub disconnect_ms: u64,
+    /// Derruba o worker a cada N tarefas iniciadas
+    pub crash_worker_after: Option<u64>,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            store_error_probability: 0.0,
+            store_delay_probability: 0.0,
+            store_delay_ms: 50,
+            event_drop_probability: 0.0,
+            disconnect_probability: 0.0,
+            disconnect_ms: 500,
+            crash_worker_after: None,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Lê a configuração de variáveis `TASKMESH_CHAOS_*`
+    ///
+    /// Retorna `None` se nenhuma variável estiver definida. Reconhece `SEED`,
+    /// `STORE_ERROR`, `STORE_DELAY`, `STORE_DELAY_MS`, `EVENT_DROP`,
+    /// `DISCONNECT`, `DISCONNECT_MS` e `CRASH_WORKER_AFTER`.
+    pub fn from_env() -> Option<TaskMeshResult<Self>> {
+        Self::from_vars(std::env::vars())
+    }
+
+    fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Option<TaskMeshResult<Self>> {
+        let vars: Vec<(String, String)> = vars
+            .into_iter()
+            .filter_map(|(key, value)| key.strip_prefix(ENV_PREFIX).map(|key| (key.to_string(), value)))
+            .collect();
+        if vars.is_empty() {
+            return None;
+        }
+
+        let mut config = Self::default();
+        for (key, value) in vars {
+            let invalid = |e: &dyn std::fmt::Display| {
+                TaskMeshError::Configuration(format!("{}{}={} inválido: {}", ENV_PREFIX, key, value, e))
+            };
+            let result = match key.as_str() {
+                "SEED" => value.parse::<u64>().map(|v| config.seed = v).map_err(|e| invalid(&e)),
+                "STORE_ERROR" => value.parse::<f64>().map(|v| config.store_error_probability = v).map_err(|e| invalid(&e)),
+                "STORE_DELAY" => value.parse::<f64>().map(|v| config.store_delay_probability = v).map_err(|e| invalid(&e)),
+                "STORE_DELAY_MS" => value.parse::<u64>().map(|v| config.store_delay_ms = v).map_err(|e| invalid(&e)),
+                "EVENT_DROP" => value.parse::<f64>().map(|v| config.event_drop_probability = v).map_err(|e| invalid(&e)),
+                "DISCONNECT" => value.parse::<f64>().map(|v| config.disconnect_probability = v).map_err(|e| invalid(&e)),
+                "DISCONNECT_MS" => value.parse::<u64>().map(|v| config.disconnect_ms = v).map_err(|e| invalid(&e)),
+                "CRASH_WORKER_AFTER" => value.parse::<u64>().map(|v| config.crash_worker_after = Some(v)).map_err(|e| invalid(&e)),
+                _ => Err(invalid(&"variável desconhecida")),
+            };
+            if let Err(e) = result {
+                return Some(Err(e));
+            }
+        }
+        Some(config.validate().map(|()| config))
+    }
+
+    /// Verifica se as probabilidades estão entre 0 e 1
+    pub fn validate(&self) -> TaskMeshResult<()> {
+        let probabilities = [
+            ("store_error_probability", self.store_error_probability),
+            ("store_delay_probability", self.store_delay_probability),
+            ("event_drop_probability", self.event_drop_probability),
+            ("disconnect_probability", self.disconnect_probability),
+        ];
+        for (name, value) in probabilities {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(TaskMeshError::Configuration(format!(
+                    "{} deve estar entre 0 e 1 (recebido {})", name, value
+                )));
+            }
+        }
+        if self.crash_worker_after == Some(0) {
+            return Err(TaskMeshError::Configuration("crash_worker_after deve ser maior que zero".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Contagem das falhas disparadas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FaultCounts {
+    pub store_errors: u64,
+    pub store_delays: u64,
+    pub events_dropped: u64,
+    pub disconnects: u64,
+    pub worker_crashes: u64,
+}
+
+/// Sorteia e contabiliza falhas injetadas
+pub struct FaultInjector {
+    config: ChaosConfig,
+    rng: Mutex<StdRng>,
+    enabled: AtomicBool,
+    disconnected_until: Mutex<Option<Instant>>,
+    tasks_started: AtomicU64,
+    store_errors: AtomicU64,
+    store_delays: AtomicU64,
+    events_dropped: AtomicU64,
+    disconnects: AtomicU64,
+    worker_crashes: AtomicU64,
+}
+
+impl FaultInjector {
+    pub fn new(config: ChaosConfig) -> TaskMeshResult<Self> {
+        config.validate()?;
+        Ok(Self {
+            rng: Mutex::new(StdRng::seed_from_u64(config.seed)),
+            config,
+            enabled: AtomicBool::new(true),
+            disconnected_until: Mutex::new(None),
+            tasks_started: AtomicU64::new(0),
+            store_errors: AtomicU64::new(0),
+            store_delays: AtomicU64::new(0),
+            events_dropped: AtomicU64::new(0),
+            disconnects: AtomicU64::new(0),
+            worker_crashes: AtomicU64::new(0),
+        })
+    }
+
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    /// Liga ou desliga a injeção (ex.: para verificar o estado final)
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Falhas disparadas até agora
+    pub fn counts(&self) -> FaultCounts {
+        FaultCounts {
+            store_errors: self.store_errors.load(Ordering::Relaxed),
+            store_delays: self.store_delays.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+            disconnects: self.disconnects.load(Ordering::Relaxed),
+            worker_crashes: self.worker_crashes.load(Ordering::Relaxed),
+        }
+    }
+
+    fn roll(&self, probability: f64) -> bool {
+        probability > 0.0
+            && self.enabled.load(Ordering::SeqCst)
+            && self.rng.lock().unwrap().gen_bool(probability)
+    }
+
+    /// Aplica as falhas de StateStore antes de uma operação
+    pub async fn before_store_op(&self, operation: &str) -> TaskMeshResult<()> {
+        if !self.enabled.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let disconnected = {
+            let mut until = self.disconnected_until.lock().unwrap();
+            if until.is_some_and(|until| Instant::now() >= until) {
+                *until = None;
+            }
+            if until.is_none() && self.roll(self.config.disconnect_probability) {
+                self.disconnects.fetch_add(1, Ordering::Relaxed);
+                *until = Some(Instant::now() + Duration::from_millis(self.config.disconnect_ms));
+            }
+            until.is_some()
+        };
+        if disconnected {
+            debug!("Falha injetada: desconexão em {}", operation);
+            return Err(TaskMeshError::Redis(redis::RedisError::from((
+                redis::ErrorKind::IoError,
+                "conexão perdida (falha injetada)",
+            ))));
+        }
+
+        if self.roll(self.config.store_delay_probability) {
+            self.store_delays.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(self.config.store_delay_ms)).await;
+        }
+        if self.roll(self.config.store_error_probability) {
+            self.store_errors.fetch_add(1, Ordering::Relaxed);
+            debug!("Falha injetada: erro em {}", operation);
+            return Err(TaskMeshError::Internal(format!("Falha injetada em {}", operation)));
+        }
+        Ok(())
+    }
+
+    /// Sorteia o descarte de um evento
+    pub fn drop_event(&self) -> bool {
+        let dropped = self.roll(self.config.event_drop_probability);
+        if dropped {
+            self.events_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        dropped
+    }
+
+    /// Indica se o worker deve cair ao iniciar a próxima tarefa
+    pub fn crash_worker(&self) -> bool {
+        let Some(every) = self.config.crash_worker_after else { return false };
+        if !self.enabled.load(Ordering::SeqCst) {
+            return false;
+        }
+        let started = self.tasks_started.fetch_add(1, Ordering::Relaxed) + 1;
+        let crash = started % every == 0;
+        if crash {
+            self.worker_crashes.fetch_add(1, Ordering::Relaxed);
+        }
+        crash
+    }
+}
+
+/// StateStore que injeta falhas antes de delegar ao backend real
+pub struct ChaosStateStore {
+    inner: Arc<dyn StateStore>,
+    injector: Arc<FaultInjector>,
+}
+
+impl ChaosStateStore {
+    pub fn new(inner: Arc<dyn StateStore>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+
+    /// Backend real, sem injeção de falhas
+    pub fn inner(&self) -> &Arc<dyn StateStore> {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl StateStore for ChaosStateStore {
+    async fn store_task(&self, task: &Task) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_task").await?;
+        self.inner.store_task(task).await
+    }
+
+    async fn get_task(&self, task_id: &TaskId) -> TaskMeshResult<Option<Task>> {
+        self.injector.before_store_op("get_task").await?;
+        self.inner.get_task(task_id).await
+    }
+
+    async fn remove_task(&self, task_id: &TaskId) -> TaskMeshResult<()> {
+        self.injector.before_store_op("remove_task").await?;
+        self.inner.remove_task(task_id).await
+    }
+
+    async fn update_task_status(&self, task_id: &TaskId, status: TaskStatus) -> TaskMeshResult<()> {
+        self.injector.before_store_op("update_task_status").await?;
+        self.inner.update_task_status(task_id, status).await
+    }
+
+    async fn get_task_status(&self, task_id: &TaskId) -> TaskMeshResult<TaskStatus> {
+        self.injector.before_store_op("get_task_status").await?;
+        self.inner.get_task_status(task_id).await
+    }
+
+    async fn get_status_history(&self, task_id: &TaskId) -> TaskMeshResult<Vec<StatusTransition>> {
+        self.injector.before_store_op("get_status_history").await?;
+        self.inner.get_status_history(task_id).await
+    }
+
+    async fn list_tasks(&self) -> TaskMeshResult<Vec<Task>> {
+        self.injector.before_store_op("list_tasks").await?;
+        self.inner.list_tasks().await
+    }
+
+    async fn list_tasks_page(&self, after: Option<&TaskId>, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        self.injector.before_store_op("list_tasks_page").await?;
+        self.inner.list_tasks_page(after, limit).await
+    }
+
+    async fn list_tasks_by_status(&self, status_filter: &[TaskStatus]) -> TaskMeshResult<Vec<Task>> {
+        self.injector.before_store_op("list_tasks_by_status").await?;
+        self.inner.list_tasks_by_status(status_filter).await
+    }
+
+    async fn search_tasks(&self, query: &str, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        self.injector.before_store_op("search_tasks").await?;
+        self.inner.search_tasks(query, limit).await
+    }
+
+    async fn store_event(&self, event: &SystemEvent) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_event").await?;
+        if self.injector.drop_event() {
+            return Ok(());
+        }
+        self.inner.store_event(event).await
+    }
+
+    async fn query_events(&self, query: &EventQuery) -> TaskMeshResult<EventPage> {
+        self.injector.before_store_op("query_events").await?;
+        self.inner.query_events(query).await
+    }
+
+    async fn store_metrics(&self, task_id: &TaskId, metrics: &ExecutionMetrics) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_metrics").await?;
+        self.inner.store_metrics(task_id, metrics).await
+    }
+
+    async fn get_metrics(&self, task_id: &TaskId) -> TaskMeshResult<Option<ExecutionMetrics>> {
+        self.injector.before_store_op("get_metrics").await?;
+        self.inner.get_metrics(task_id).await
+    }
+
+    async fn store_model(&self, model: &ModelRecord) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_model").await?;
+        self.inner.store_model(model).await
+    }
+
+    async fn list_model_versions(&self, name: &str) -> TaskMeshResult<Vec<ModelRecord>> {
+        self.injector.before_store_op("list_model_versions").await?;
+        self.inner.list_model_versions(name).await
+    }
+
+    async fn store_attempt(&self, attempt: &AttemptRecord) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_attempt").await?;
+        self.inner.store_attempt(attempt).await
+    }
+
+    async fn list_attempts(&self, task_id: &TaskId) -> TaskMeshResult<Vec<AttemptRecord>> {
+        self.injector.before_store_op("list_attempts").await?;
+        self.inner.list_attempts(task_id).await
+    }
+
+    async fn store_manifest(&self, manifest: &ExecutionManifest) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_manifest").await?;
+        self.inner.store_manifest(manifest).await
+    }
+
+    async fn list_manifests(&self, task_id: &TaskId) -> TaskMeshResult<Vec<ExecutionManifest>> {
+        self.injector.before_store_op("list_manifests").await?;
+        self.inner.list_manifests(task_id).await
+    }
+
+    async fn register_alias(&self, namespace: &str, alias: &str, task_id: &TaskId) -> TaskMeshResult<()> {
+        self.injector.before_store_op("register_alias").await?;
+        self.inner.register_alias(namespace, alias, task_id).await
+    }
+
+    async fn resolve_alias(&self, namespace: &str, alias: &str) -> TaskMeshResult<Option<TaskId>> {
+        self.injector.before_store_op("resolve_alias").await?;
+        self.inner.resolve_alias(namespace, alias).await
+    }
+
+    async fn put_setting(&self, key: &str, value: Option<&str>) -> TaskMeshResult<()> {
+        self.injector.before_store_op("put_setting").await?;
+        self.inner.put_setting(key, value).await
+    }
+
+    async fn get_setting(&self, key: &str) -> TaskMeshResult<Option<String>> {
+        self.injector.before_store_op("get_setting").await?;
+        self.inner.get_setting(key).await
+    }
+
+    async fn create_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
+        self.injector.before_store_op("create_checkpoint").await?;
+        self.inner.create_checkpoint(checkpoint_id).await
+    }
+
+    async fn create_incremental_checkpoint(&self, checkpoint_id: &str, since: SystemTime) -> TaskMeshResult<()> {
+        self.injector.before_store_op("create_incremental_checkpoint").await?;
+        self.inner.create_incremental_checkpoint(checkpoint_id, since).await
+    }
+
+    async fn restore_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
+        self.injector.before_store_op("restore_checkpoint").await?;
+        self.inner.restore_checkpoint(checkpoint_id).await
+    }
+
+    async fn restore_checkpoint_with_fallback(&self, checkpoint_id: &str, fallback: bool) -> TaskMeshResult<String> {
+        self.injector.before_store_op("restore_checkpoint_with_fallback").await?;
+        self.inner.restore_checkpoint_with_fallback(checkpoint_id, fallback).await
+    }
+
+    async fn list_checkpoints(&self) -> TaskMeshResult<Vec<String>> {
+        self.injector.before_store_op("list_checkpoints").await?;
+        self.inner.list_checkpoints().await
+    }
+
+    async fn verify_checkpoints(&self) -> TaskMeshResult<Vec<String>> {
+        self.injector.before_store_op("verify_checkpoints").await?;
+        self.inner.verify_checkpoints().await
+    }
+
+    async fn last_checkpoint_at(&self) -> TaskMeshResult<Option<SystemTime>> {
+        self.injector.before_store_op("last_checkpoint_at").await?;
+        self.inner.last_checkpoint_at().await
+    }
+
+    async fn ping(&self) -> TaskMeshResult<()> {
+        self.injector.before_store_op("ping").await?;
+        self.inner.ping().await
+    }
+
+    async fn schema_version(&self) -> TaskMeshResult<Option<(u32, u32)>> {
+        self.injector.before_store_op("schema_version").await?;
+        self.inner.schema_version().await
+    }
+    
+    async fn append_event(&self, event: &SystemEvent) -> TaskMeshResult<Option<u64>> {
+        self.injector.before_store_op("append_event").await?;
+        if self.injector.drop_event() {
+            return Ok(None);
+        }
+        self.inner.append_event(event).await
+    }
+
+    async fn events_after(&self, after_seq: u64, limit: usize) -> TaskMeshResult<Vec<(u64, SystemEvent)>> {
+        self.injector.before_store_op("events_after").await?;
+        self.inner.events_after(after_seq, limit).await
+    }
+
+    async fn stats(&self) -> TaskMeshResult<StorageStats> {
+        self.injector.before_store_op("stats").await?;
+        self.inner.stats().await
+    }
+
+    async fn compact(&self, mode: CompactionMode) -> TaskMeshResult<()> {
+        self.injector.before_store_op("compact").await?;
+        self.inner.compact(mode).await
+    }
+
+    async fn shrink_pool(&self, keep: u32) -> TaskMeshResult<()> {
+        self.inner.shrink_pool(keep).await
+    }
+
+    fn open_connections(&self) -> Option<u32> {
+        self.inner.open_connections()
+    }
+
+    async fn rewrap_namespace(&self, namespace: &str, key_id: &str) -> TaskMeshResult<u64> {
+        self.injector.before_store_op("rewrap_namespace").await?;
+        self.inner.rewrap_namespace(namespace, key_id).await
+    }
+
+    async fn cleanup_old_data(&self, retention_days: u32) -> TaskMeshResult<()> {
+        self.injector.before_store_op("cleanup_old_data").await?;
+        self.inner.cleanup_old_data(retention_days).await
+    }
+
+    async fn store_events(&self, events: &[SystemEvent]) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_events").await?;
+        let kept: Vec<SystemEvent> = events
+            .iter()
+            .filter(|_| !self.injector.drop_event())
+            .cloned()
+            .collect();
+        self.inner.store_events(&kept).await
+    }
+
+    async fn update_task_statuses(&self, updates: &[(TaskId, TaskStatus)]) -> TaskMeshResult<()> {
+        self.injector.before_store_op("update_task_statuses").await?;
+        self.inner.update_task_statuses(updates).await
+    }
+
+    async fn store_tasks(&self, tasks: &[Task]) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_tasks").await?;
+        self.inner.store_tasks(tasks).await
+    }
+
+    async fn import_tasks(&self, records: &[(Task, TaskStatus)]) -> TaskMeshResult<()> {
+        self.injector.before_store_op("import_tasks").await?;
+        self.inner.import_tasks(records).await
+    }
+
+    async fn store_metrics_batch(&self, metrics: &[(TaskId, ExecutionMetrics)]) -> TaskMeshResult<()> {
+        self.injector.before_store_op("store_metrics_batch").await?;
+        self.inner.store_metrics_batch(metrics).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    #[test]
+    fn test_same_seed_fires_same_faults() {
+        let config = ChaosConfig {
+            seed: 7,
+            store_error_probability: 0.3,
+            crash_worker_after: Some(4),
+            ..ChaosConfig::default()
+        };
+        let run = || {
+            let injector = FaultInjector::new(config.clone()).unwrap();
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            let outcomes: Vec<bool> = (0..100)
+                .map(|_| runtime.block_on(injector.before_store_op("test")).is_err())
+                .collect();
+            let crashes: Vec<bool> = (0..8).map(|_| injector.crash_worker()).collect();
+            (outcomes, crashes, injector.counts())
+        };
+
+        let (first, crashes, counts) = run();
+        assert_eq!(run().0, first);
+        assert_eq!(crashes, [false, false, false, true, false, false, false, true]);
+        assert_eq!(counts.store_errors, first.iter().filter(|failed| **failed).count() as u64);
+        assert_eq!(counts.worker_crashes, 2);
+
+        let vars = [("TASKMESH_CHAOS_STORE_ERROR".to_string(), "1.5".to_string())];
+        assert!(ChaosConfig::from_vars(vars).unwrap().is_err());
+        assert!(ChaosConfig::from_vars(Vec::new()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_workflow_completes_under_store_errors() {
+        let config = TaskMeshConfig {
+            max_workers: 4,
+            strict_durability: true,
+            chaos: Some(ChaosConfig {
+                seed: 2024,
+                store_error_probability: 0.1,
+                ..ChaosConfig::default()
+            }),
+            ..TaskMeshConfig::default()
+        };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        let injector = core.fault_injector.clone().unwrap();
+
+        // 10 cadeias de 5 tarefas
+        let mut tasks: Vec<Task> = Vec::new();
+        for i in 0..50 {
+            let dependencies = if i % 5 == 0 { vec![] } else { vec![tasks[i - 1].id] };
+            tasks.push(Task::new(format!("chaos-{}", i), TaskDefinition::command("true"), dependencies));
+        }
+        let task_ids = core.submit_batch(tasks).await.unwrap();
+        core.start().await.unwrap();
+
+        let resources = ResourceAllocation::default();
+        let mut ready: Vec<TaskId> = Vec::new();
+        let mut completed: HashSet<TaskId> = HashSet::new();
+        let mut attempts: HashMap<TaskId, u32> = HashMap::new();
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while completed.len() < task_ids.len() {
+            assert!(Instant::now() < deadline, "workflow não terminou: {} de 50", completed.len());
+            while let Some(task_id) = core.scheduler.get_next_task(&resources).await {
+                ready.push(task_id);
+            }
+
+            let mut dispatched = Vec::new();
+            let mut rejected = Vec::new();
+            for task_id in ready.drain(..) {
+                let task = core.registry.read().await.get_task(&task_id).cloned().unwrap();
+                *attempts.entry(task_id).or_default() += 1;
+                if core.executor.execute_task(task).await.is_ok() {
+                    dispatched.push(task_id);
+                } else {
+                    rejected.push(task_id);
+                }
+            }
+            ready.extend(rejected);
+            while core.executor.queued_count() > 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+
+            // Tarefas sem status Completed são despachadas de novo
+            for task_id in dispatched {
+                let status = loop {
+                    if let Ok(status) = core.get_task_status(task_id).await {
+                        break status;
+                    }
+                };
+                if matches!(status, TaskStatus::Completed { .. }) {
+                    assert!(completed.insert(task_id), "tarefa {} concluída duas vezes", task_id);
+                    core.scheduler.report_task_completion(task_id, ExecutionMetrics::default()).await;
+                } else {
+                    ready.push(task_id);
+                }
+            }
+        }
+
+        injector.set_enabled(false);
+        for task_id in &task_ids {
+            let history = core.state_store.get_status_history(task_id).await.unwrap();
+            let completions = history
+                .iter()
+                .filter(|transition| matches!(transition.status, TaskStatus::Completed { .. }))
+                .count();
+            assert_eq!(completions, 1, "tarefa {} concluída {} vezes", task_id, completions);
+        }
+        assert!(injector.counts().store_errors > 0);
+        assert!(attempts.values().any(|attempts| *attempts > 1));
+        core.shutdown().await.unwrap();
+    }
+}
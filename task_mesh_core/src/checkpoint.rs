@@ -0,0 +1,792 @@
+//! Engine de checkpoints periódicos
+//!
+//! Os checkpoints são disparados pela estratégia configurada (intervalo,
+//! contagem de tarefas concluídas, o que vier primeiro, ou apenas no
+//! shutdown). Gatilhos que vencem juntos geram um único checkpoint. O engine
+//! evita competir com o executor: se a carga (tarefas em execução ou
+//! latência de despacho) passar dos limites, o checkpoint é adiado por até
+//! `max_defer`, depois do qual é gravado de qualquer forma. Se uma gravação
+//! demorar mais que `write_budget`, o engine passa para checkpoints
+//! incrementais e emite um evento de aviso.
+//!
+//! `create_checkpoint` é sempre imediato e ignora a carga.
+//!
+//! Enquanto o core está ocioso (ver [`crate::idle`]) o intervalo é
+//! multiplicado por [`CheckpointEngine::set_interval_stretch`].
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use crate::background::BackgroundTasks;
+use crate::event_bus::{EventBus, Subscription};
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Adiamento máximo padrão de estratégias sem intervalo
+const DEFAULT_MAX_DEFER: Duration = Duration::from_secs(60);
+
+/// Quando disparar checkpoints
+///
+/// Todas as estratégias gravam um checkpoint no shutdown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CheckpointStrategy {
+    /// A cada intervalo fixo
+    Interval(Duration),
+    /// A cada `n` tarefas concluídas
+    TaskCount(u32),
+    /// O que vencer primeiro: intervalo ou contagem de tarefas
+    Hybrid { interval: Duration, task_count: u32 },
+    /// Apenas no shutdown
+    OnShutdownOnly,
+}
+
+impl CheckpointStrategy {
+    /// Intervalo do gatilho por tempo, se houver
+    pub fn interval(&self) -> Option<Duration> {
+        match self {
+            CheckpointStrategy::Interval(interval) | CheckpointStrategy::Hybrid { interval, .. } => Some(*interval),
+            _ => None,
+        }
+    }
+
+    /// Tarefas concluídas por checkpoint, se houver
+    pub fn task_count(&self) -> Option<u32> {
+        match self {
+            CheckpointStrategy::TaskCount(count) | CheckpointStrategy::Hybrid { task_count: count, .. } => Some(*count),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> TaskMeshResult<()> {
+        if self.interval().is_some_and(|interval| interval.is_zero()) {
+            return Err(TaskMeshError::Configuration("Intervalo de checkpoint deve ser positivo".to_string()));
+        }
+        if self.task_count() == Some(0) {
+            return Err(TaskMeshError::Configuration("Contagem de tarefas por checkpoint deve ser positiva".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// O que causou um checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckpointTrigger {
+    /// Intervalo da estratégia vencido
+    Interval,
+    /// Contagem de tarefas concluídas atingida
+    TaskCount,
+    /// Shutdown do core
+    Shutdown,
+    /// `create_checkpoint` explícito
+    Manual,
+}
+
+/// Dados do checkpoint mais recente
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    pub checkpoint_id: String,
+    /// Gatilho principal (o primeiro de `triggers`)
+    pub trigger: CheckpointTrigger,
+    /// Todos os gatilhos atendidos por este checkpoint
+    pub triggers: Vec<CheckpointTrigger>,
+    pub mode: CheckpointMode,
+    pub created_at: SystemTime,
+}
+
+/// Fonte de tempo dos gatilhos (substituível em testes)
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// Relógio monotônico do sistema
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Forma de gravação dos checkpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckpointMode {
+    /// Estado completo
+    Full,
+    /// Apenas o que mudou desde o último checkpoint
+    Incremental,
+}
+
+/// Sinal de carga consultado antes de cada checkpoint periódico
+pub trait LoadSignal: Send + Sync {
+    /// Tarefas em execução no momento
+    fn running_task_count(&self) -> usize;
+
+    /// Latência recente de despacho
+    fn dispatch_latency(&self) -> Duration;
+}
+
+/// Configuração do engine
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    pub strategy: CheckpointStrategy,
+    /// Acima deste número de tarefas em execução o checkpoint é adiado
+    pub max_running_tasks: usize,
+    /// Acima desta latência de despacho o checkpoint é adiado
+    pub max_dispatch_latency: Duration,
+    /// Adiamento máximo de um checkpoint
+    pub max_defer: Duration,
+    /// Duração máxima de uma gravação antes de passar para incremental
+    pub write_budget: Duration,
+}
+
+impl CheckpointConfig {
+    /// Configuração padrão com checkpoints a cada `interval`
+    pub fn with_interval(interval: Duration) -> Self {
+        Self::with_strategy(CheckpointStrategy::Interval(interval))
+    }
+
+    /// Configuração padrão para `strategy`
+    ///
+    /// O adiamento máximo é o intervalo da estratégia, quando houver.
+    pub fn with_strategy(strategy: CheckpointStrategy) -> Self {
+        Self {
+            max_defer: strategy.interval().unwrap_or(DEFAULT_MAX_DEFER),
+            strategy,
+            max_running_tasks: num_cpus::get(),
+            max_dispatch_latency: Duration::from_millis(500),
+            write_budget: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Estatísticas de checkpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointStats {
+    /// Gravados no momento previsto
+    pub on_time: u64,
+    /// Adiados por carga (contados uma vez por checkpoint)
+    pub deferred: u64,
+    /// Descartados porque a gravação anterior ainda não tinha terminado
+    pub skipped: u64,
+    /// Criados explicitamente (`create_checkpoint` e shutdown)
+    pub forced: u64,
+    /// Forma de gravação atual
+    pub mode: CheckpointMode,
+    /// Duração da última gravação
+    pub last_write: Option<Duration>,
+}
+
+/// Resultado de uma verificação periódica
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickOutcome {
+    /// Checkpoint gravado no momento previsto
+    Written,
+    /// Checkpoint gravado após adiamento
+    WrittenLate,
+    /// Checkpoint adiado por carga
+    Deferred,
+    /// Gravação anterior ainda em andamento
+    Skipped,
+}
+
+/// Progresso dos gatilhos desde o último checkpoint
+#[derive(Debug)]
+struct TriggerState {
+    last_checkpoint: Instant,
+    completed: u32,
+}
+
+/// Engine de checkpoints
+pub struct CheckpointEngine {
+    state_store: Arc<dyn StateStore>,
+    config: CheckpointConfig,
+    clock: Arc<dyn Clock>,
+    /// Fonte dos eventos de conclusão para gatilhos por contagem
+    event_bus: Option<Arc<EventBus>>,
+    triggers: Mutex<TriggerState>,
+    last_info: Mutex<Option<CheckpointInfo>>,
+    load_signal: std::sync::RwLock<Option<Arc<dyn LoadSignal>>>,
+    stats: Mutex<CheckpointStats>,
+    /// Início do adiamento do checkpoint pendente
+    deferred_since: Mutex<Option<Instant>>,
+    /// Momento do último checkpoint (base dos incrementais)
+    last_checkpoint: Mutex<Option<SystemTime>>,
+    writing: AtomicBool,
+    sequence: AtomicU64,
+    /// Multiplicador do intervalo (1 fora da ociosidade)
+    interval_stretch: AtomicU32,
+    /// Acorda o loop periódico para recalcular a espera
+    rearm: Arc<Notify>,
+    background: BackgroundTasks,
+    /// Token do loop periódico atual (filho do token de background)
+    shutdown: std::sync::Mutex<CancellationToken>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl CheckpointEngine {
+    /// Cria engine com checkpoints a cada `interval_secs` segundos
+    pub fn new(state_store: Arc<dyn StateStore>, interval_secs: u64) -> Self {
+        Self::with_config(state_store, CheckpointConfig::with_interval(Duration::from_secs(interval_secs)))
+    }
+
+    /// Cria engine com configuração personalizada
+    pub fn with_config(state_store: Arc<dyn StateStore>, config: CheckpointConfig) -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        Self {
+            state_store,
+            config,
+            triggers: Mutex::new(TriggerState { last_checkpoint: clock.now(), completed: 0 }),
+            clock,
+            event_bus: None,
+            last_info: Mutex::new(None),
+            load_signal: std::sync::RwLock::new(None),
+            stats: Mutex::new(CheckpointStats {
+                on_time: 0,
+                deferred: 0,
+                skipped: 0,
+                forced: 0,
+                mode: CheckpointMode::Full,
+                last_write: None,
+            }),
+            deferred_since: Mutex::new(None),
+            last_checkpoint: Mutex::new(None),
+            writing: AtomicBool::new(false),
+            sequence: AtomicU64::new(0),
+            interval_stretch: AtomicU32::new(1),
+            rearm: Arc::new(Notify::new()),
+            background: BackgroundTasks::new(),
+            shutdown: std::sync::Mutex::new(CancellationToken::new()),
+            handle: Mutex::new(None),
+        }
+    }
+
+    /// Registra o loop periódico em tarefas de background compartilhadas
+    pub fn with_background(mut self, background: BackgroundTasks) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Usa `clock` para intervalos e adiamentos
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.triggers.get_mut().last_checkpoint = clock.now();
+        self.clock = clock;
+        self
+    }
+
+    /// Assina as conclusões de tarefas do barramento (gatilhos por contagem)
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Define a fonte de carga consultada antes dos checkpoints periódicos
+    pub fn set_load_signal(&self, signal: Arc<dyn LoadSignal>) {
+        *self.load_signal.write().unwrap() = Some(signal);
+    }
+
+    /// Multiplica o intervalo da estratégia por `factor` (1 restaura)
+    ///
+    /// A espera em curso é recalculada imediatamente. Gatilhos por contagem
+    /// não mudam.
+    pub fn set_interval_stretch(&self, factor: u32) {
+        let previous = self.interval_stretch.swap(factor.max(1), Ordering::SeqCst);
+        if previous != factor.max(1) {
+            self.rearm.notify_one();
+        }
+    }
+
+    /// Multiplicador atual do intervalo
+    pub fn interval_stretch(&self) -> u32 {
+        self.interval_stretch.load(Ordering::SeqCst)
+    }
+
+    /// Intervalo da estratégia com o multiplicador aplicado
+    fn effective_interval(&self) -> Option<Duration> {
+        let interval = self.config.strategy.interval()?;
+        Some(interval.saturating_mul(self.interval_stretch()))
+    }
+
+    /// Inicia os checkpoints da estratégia configurada
+    ///
+    /// Estratégias por contagem exigem o barramento de eventos.
+    pub async fn start(self: &Arc<Self>) -> TaskMeshResult<()> {
+        let strategy = self.config.strategy.clone();
+        strategy.validate()?;
+        let mut completions = match (strategy.task_count(), &self.event_bus) {
+            (None, _) => None,
+            (Some(_), Some(event_bus)) => Some(event_bus.subscribe(EventQuery {
+                event_types: vec![EventType::TaskCompleted],
+                ..EventQuery::default()
+            })),
+            (Some(_), None) => {
+                return Err(TaskMeshError::Configuration(
+                    "Checkpoint por contagem de tarefas requer o barramento de eventos".to_string(),
+                ));
+            }
+        };
+        if strategy == CheckpointStrategy::OnShutdownOnly {
+            info!("CheckpointEngine: checkpoints apenas no shutdown");
+            return Ok(());
+        }
+        // Enquanto adiado, verifica novamente em intervalos menores
+        let recheck = (strategy.interval().unwrap_or(DEFAULT_MAX_DEFER) / 4).max(Duration::from_millis(10));
+        info!("Iniciando CheckpointEngine ({:?})", strategy);
+
+        let engine = Arc::downgrade(self);
+        let shutdown = self.background.token().child_token();
+        *self.shutdown.lock().unwrap() = shutdown.clone();
+        let mut wait = self.until_next_interval().await;
+        let rearm = self.rearm.clone();
+        let handle = self.background.spawn("checkpoint.periodic", async move {
+            loop {
+                let completion = tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = sleep_or_pending(wait) => None,
+                    _ = rearm.notified() => None,
+                    open = next_completion(&mut completions) => Some(open),
+                };
+                if completion == Some(false) {
+                    warn!("Barramento de eventos encerrado; checkpoints por contagem desativados");
+                    completions = None;
+                    continue;
+                }
+                let Some(engine) = engine.upgrade() else { break };
+                let outcome = match completion {
+                    Some(_) => engine.on_task_completed().await,
+                    None => engine.poll().await,
+                };
+                wait = match outcome {
+                    Ok(Some(TickOutcome::Deferred)) => Some(recheck),
+                    Ok(_) => engine.until_next_interval().await,
+                    Err(e) => {
+                        error!("Erro no checkpoint periódico: {}", e);
+                        Some(strategy.interval().unwrap_or(recheck))
+                    }
+                };
+            }
+        });
+        *self.handle.lock().await = Some(handle);
+        Ok(())
+    }
+
+    /// Para os checkpoints periódicos
+    pub async fn stop(&self) -> TaskMeshResult<()> {
+        self.shutdown.lock().unwrap().cancel();
+        if let Some(handle) = self.handle.lock().await.take() {
+            let _ = handle.await;
+        }
+        Ok(())
+    }
+
+    /// Avalia os gatilhos da estratégia e dispara o checkpoint se algum venceu
+    ///
+    /// Gatilhos vencidos ao mesmo tempo geram um único checkpoint. Retorna
+    /// `None` se nenhum venceu.
+    pub async fn poll(&self) -> TaskMeshResult<Option<TickOutcome>> {
+        let due = {
+            let state = self.triggers.lock().await;
+            let mut due = Vec::new();
+            if self.effective_interval().is_some_and(|interval| {
+                self.clock.now().saturating_duration_since(state.last_checkpoint) >= interval
+            }) {
+                due.push(CheckpointTrigger::Interval);
+            }
+            if self.config.strategy.task_count().is_some_and(|count| state.completed >= count) {
+                due.push(CheckpointTrigger::TaskCount);
+            }
+            due
+        };
+        if due.is_empty() {
+            return Ok(None);
+        }
+        self.fire(due).await.map(Some)
+    }
+
+    /// Conta uma tarefa concluída e avalia os gatilhos
+    pub async fn on_task_completed(&self) -> TaskMeshResult<Option<TickOutcome>> {
+        {
+            let mut state = self.triggers.lock().await;
+            state.completed = state.completed.saturating_add(1);
+        }
+        self.poll().await
+    }
+
+    /// Verificação periódica: grava, adia ou descarta o checkpoint
+    pub async fn tick(&self) -> TaskMeshResult<TickOutcome> {
+        self.fire(vec![CheckpointTrigger::Interval]).await
+    }
+
+    /// Dispara um checkpoint por `triggers`, respeitando a carga
+    async fn fire(&self, triggers: Vec<CheckpointTrigger>) -> TaskMeshResult<TickOutcome> {
+        if self.writing.load(Ordering::SeqCst) {
+            self.stats.lock().await.skipped += 1;
+            debug!("Checkpoint descartado: gravação anterior em andamento");
+            return Ok(TickOutcome::Skipped);
+        }
+
+        let mut deferred_since = self.deferred_since.lock().await;
+        let late = match *deferred_since {
+            Some(since) if self.clock.now().saturating_duration_since(since) < self.config.max_defer && self.is_busy() => {
+                return Ok(TickOutcome::Deferred);
+            }
+            Some(_) => true,
+            None if self.is_busy() => {
+                *deferred_since = Some(self.clock.now());
+                self.stats.lock().await.deferred += 1;
+                debug!("Checkpoint adiado: executor sob carga");
+                return Ok(TickOutcome::Deferred);
+            }
+            None => false,
+        };
+        *deferred_since = None;
+        drop(deferred_since);
+
+        self.write(&triggers).await?;
+        if late {
+            Ok(TickOutcome::WrittenLate)
+        } else {
+            self.stats.lock().await.on_time += 1;
+            Ok(TickOutcome::Written)
+        }
+    }
+
+    /// Cria um checkpoint imediatamente, independente da carga
+    pub async fn create_checkpoint(&self) -> TaskMeshResult<()> {
+        self.write(&[CheckpointTrigger::Manual]).await?;
+        self.stats.lock().await.forced += 1;
+        Ok(())
+    }
+
+    /// Checkpoint final do shutdown (gravado em qualquer estratégia)
+    pub async fn checkpoint_on_shutdown(&self) -> TaskMeshResult<()> {
+        self.write(&[CheckpointTrigger::Shutdown]).await?;
+        self.stats.lock().await.forced += 1;
+        Ok(())
+    }
+
+    /// Checkpoint mais recente gravado por este engine e o que o causou
+    pub async fn last_checkpoint_info(&self) -> Option<CheckpointInfo> {
+        self.last_info.lock().await.clone()
+    }
+
+    /// Restaura estado a partir de checkpoint
+    ///
+    /// Com `fallback`, um checkpoint corrompido é substituído pelo anterior
+    /// íntegro. Retorna o ID efetivamente restaurado.
+    pub async fn restore_checkpoint(&self, checkpoint_id: &str, fallback: bool) -> TaskMeshResult<String> {
+        let restored = self.state_store.restore_checkpoint_with_fallback(checkpoint_id, fallback).await?;
+        self.state_store.store_event(&SystemEvent::new(
+            EventType::CheckpointRestored,
+            None,
+            serde_json::json!({ "checkpoint_id": restored, "requested": checkpoint_id }),
+        )).await?;
+        Ok(restored)
+    }
+
+    /// Estatísticas acumuladas
+    pub async fn stats(&self) -> CheckpointStats {
+        self.stats.lock().await.clone()
+    }
+
+    /// Indica se a carga atual passa dos limites configurados
+    fn is_busy(&self) -> bool {
+        let signal = self.load_signal.read().unwrap();
+        signal.as_ref().is_some_and(|signal| {
+            signal.running_task_count() > self.config.max_running_tasks
+                || signal.dispatch_latency() > self.config.max_dispatch_latency
+        })
+    }
+
+    /// Tempo até o vencimento do gatilho por intervalo
+    async fn until_next_interval(&self) -> Option<Duration> {
+        let interval = self.effective_interval()?;
+        let elapsed = self.clock.now().saturating_duration_since(self.triggers.lock().await.last_checkpoint);
+        Some(interval.saturating_sub(elapsed))
+    }
+
+    /// Grava um checkpoint e ajusta a forma de gravação pela duração
+    ///
+    /// Qualquer checkpoint gravado reinicia os gatilhos da estratégia.
+    async fn write(&self, triggers: &[CheckpointTrigger]) -> TaskMeshResult<()> {
+        self.writing.store(true, Ordering::SeqCst);
+        let result = self.write_inner(triggers).await;
+        self.writing.store(false, Ordering::SeqCst);
+        if result.is_ok() {
+            *self.triggers.lock().await = TriggerState { last_checkpoint: self.clock.now(), completed: 0 };
+        }
+        result
+    }
+
+    async fn write_inner(&self, triggers: &[CheckpointTrigger]) -> TaskMeshResult<()> {
+        let checkpoint_id = format!(
+            "checkpoint_{}_{}",
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis(),
+            self.sequence.fetch_add(1, Ordering::SeqCst)
+        );
+        let mode = self.stats.lock().await.mode;
+        let mut last_checkpoint = self.last_checkpoint.lock().await;
+        let started_at = SystemTime::now();
+        let started = Instant::now();
+
+        match (mode, *last_checkpoint) {
+            (CheckpointMode::Incremental, Some(since)) => {
+                self.state_store.create_incremental_checkpoint(&checkpoint_id, since).await?
+            }
+            _ => self.state_store.create_checkpoint(&checkpoint_id).await?,
+        }
+        let elapsed = started.elapsed();
+        *last_checkpoint = Some(started_at);
+        drop(last_checkpoint);
+        *self.last_info.lock().await = Some(CheckpointInfo {
+            checkpoint_id: checkpoint_id.clone(),
+            trigger: triggers[0],
+            triggers: triggers.to_vec(),
+            mode,
+            created_at: started_at,
+        });
+
+        let switched = {
+            let mut stats = self.stats.lock().await;
+            stats.last_write = Some(elapsed);
+            let switched = stats.mode == CheckpointMode::Full && elapsed > self.config.write_budget;
+            if switched {
+                stats.mode = CheckpointMode::Incremental;
+            }
+            switched
+        };
+
+        self.state_store.store_event(&SystemEvent::new(
+            EventType::CheckpointCreated,
+            None,
+            serde_json::json!({
+                "checkpoint_id": checkpoint_id,
+                "mode": mode,
+                "duration_ms": elapsed.as_millis() as u64,
+                "triggers": triggers,
+            }),
+        )).await?;
+
+        if switched {
+            warn!(
+                "Checkpoint {} levou {:?} (limite {:?}); passando para checkpoints incrementais",
+                checkpoint_id, elapsed, self.config.write_budget
+            );
+            self.state_store.store_event(&SystemEvent::new(
+                EventType::CheckpointDegraded,
+                None,
+                serde_json::json!({
+                    "checkpoint_id": checkpoint_id,
+                    "duration_ms": elapsed.as_millis() as u64,
+                    "budget_ms": self.config.write_budget.as_millis() as u64,
+                    "mode": CheckpointMode::Incremental,
+                }),
+            )).await?;
+        }
+
+        debug!("Checkpoint {} gravado em {:?}", checkpoint_id, elapsed);
+        Ok(())
+    }
+}
+
+/// Aguarda `wait`, ou para sempre sem intervalo
+async fn sleep_or_pending(wait: Option<Duration>) {
+    match wait {
+        Some(wait) => tokio::time::sleep(wait).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Próxima conclusão de tarefa; `false` quando o barramento foi encerrado
+async fn next_completion(completions: &mut Option<Subscription>) -> bool {
+    match completions {
+        Some(subscription) => matches!(subscription.recv().await, Ok(Some(_))),
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_store::MemoryStateStore;
+
+    /// Sinal de carga controlado pelo teste
+    #[derive(Default)]
+    struct MockLoad {
+        running: std::sync::atomic::AtomicUsize,
+    }
+
+    impl LoadSignal for MockLoad {
+        fn running_task_count(&self) -> usize {
+            self.running.load(Ordering::SeqCst)
+        }
+
+        fn dispatch_latency(&self) -> Duration {
+            Duration::ZERO
+        }
+    }
+
+    /// Relógio avançado manualmente pelo teste
+    struct ManualClock {
+        base: Instant,
+        offset: std::sync::Mutex<Duration>,
+    }
+
+    impl ManualClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self { base: Instant::now(), offset: std::sync::Mutex::new(Duration::ZERO) })
+        }
+
+        fn advance(&self, by: Duration) {
+            *self.offset.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            self.base + *self.offset.lock().unwrap()
+        }
+    }
+
+    async fn strategy_engine(strategy: CheckpointStrategy) -> (Arc<MemoryStateStore>, CheckpointEngine, Arc<ManualClock>) {
+        let store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let clock = ManualClock::new();
+        let engine = CheckpointEngine::with_config(store.clone(), CheckpointConfig::with_strategy(strategy))
+            .with_clock(clock.clone());
+        (store, engine, clock)
+    }
+
+    async fn engine(max_defer: Duration, write_budget: Duration) -> (Arc<MemoryStateStore>, CheckpointEngine, Arc<MockLoad>) {
+        let store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let config = CheckpointConfig {
+            max_running_tasks: 4,
+            max_defer,
+            write_budget,
+            ..CheckpointConfig::with_interval(Duration::from_secs(60))
+        };
+        let engine = CheckpointEngine::with_config(store.clone(), config);
+        let load = Arc::new(MockLoad::default());
+        engine.set_load_signal(load.clone());
+        (store, engine, load)
+    }
+
+    #[tokio::test]
+    async fn test_defers_under_load_but_forced_checkpoint_runs() {
+        let (store, engine, load) = engine(Duration::from_secs(60), Duration::from_secs(60)).await;
+        load.running.store(10, Ordering::SeqCst);
+
+        assert_eq!(engine.tick().await.unwrap(), TickOutcome::Deferred);
+        assert_eq!(engine.tick().await.unwrap(), TickOutcome::Deferred);
+        assert!(store.list_checkpoints().await.unwrap().is_empty());
+
+        // Checkpoint explícito ignora a carga
+        engine.create_checkpoint().await.unwrap();
+        assert_eq!(store.list_checkpoints().await.unwrap().len(), 1);
+
+        // Carga normalizada: o checkpoint adiado é gravado
+        load.running.store(1, Ordering::SeqCst);
+        assert_eq!(engine.tick().await.unwrap(), TickOutcome::WrittenLate);
+        assert_eq!(engine.tick().await.unwrap(), TickOutcome::Written);
+
+        let stats = engine.stats().await;
+        assert_eq!((stats.on_time, stats.deferred, stats.skipped, stats.forced), (1, 1, 0, 1));
+        assert_eq!(store.list_checkpoints().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_max_defer_and_slow_write_switch_to_incremental() {
+        let (store, engine, load) = engine(Duration::ZERO, Duration::ZERO).await;
+        load.running.store(10, Ordering::SeqCst);
+
+        // Adiamento máximo esgotado: grava mesmo sob carga
+        assert_eq!(engine.tick().await.unwrap(), TickOutcome::Deferred);
+        assert_eq!(engine.tick().await.unwrap(), TickOutcome::WrittenLate);
+
+        let stats = engine.stats().await;
+        assert_eq!(stats.mode, CheckpointMode::Incremental);
+        let events = store.get_events(None, None).await.unwrap();
+        assert!(events.iter().any(|e| e.event_type == EventType::CheckpointDegraded));
+    }
+
+
+    #[tokio::test]
+    async fn test_interval_and_shutdown_only_strategies_follow_clock() {
+        let (store, engine, clock) = strategy_engine(CheckpointStrategy::Interval(Duration::from_secs(60))).await;
+        assert_eq!(engine.poll().await.unwrap(), None);
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(engine.on_task_completed().await.unwrap(), None);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(engine.poll().await.unwrap(), Some(TickOutcome::Written));
+        assert_eq!(engine.poll().await.unwrap(), None);
+        assert_eq!(engine.last_checkpoint_info().await.unwrap().trigger, CheckpointTrigger::Interval);
+        assert_eq!(store.list_checkpoints().await.unwrap().len(), 1);
+
+        let (store, engine, clock) = strategy_engine(CheckpointStrategy::OnShutdownOnly).await;
+        clock.advance(Duration::from_secs(86_400));
+        for _ in 0..1_000 {
+            assert_eq!(engine.on_task_completed().await.unwrap(), None);
+        }
+        assert!(engine.last_checkpoint_info().await.is_none());
+        engine.checkpoint_on_shutdown().await.unwrap();
+        assert_eq!(engine.last_checkpoint_info().await.unwrap().triggers, vec![CheckpointTrigger::Shutdown]);
+        assert_eq!(store.list_checkpoints().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_fires_on_first_trigger_and_coalesces_simultaneous_ones() {
+        let strategy = CheckpointStrategy::Hybrid { interval: Duration::from_secs(600), task_count: 3 };
+        let (store, engine, clock) = strategy_engine(strategy).await;
+
+        // Contagem vence primeiro
+        assert_eq!(engine.on_task_completed().await.unwrap(), None);
+        assert_eq!(engine.on_task_completed().await.unwrap(), None);
+        assert_eq!(engine.on_task_completed().await.unwrap(), Some(TickOutcome::Written));
+        assert_eq!(engine.last_checkpoint_info().await.unwrap().trigger, CheckpointTrigger::TaskCount);
+
+        // Intervalo vence primeiro; o checkpoint zera a contagem
+        engine.on_task_completed().await.unwrap();
+        clock.advance(Duration::from_secs(600));
+        assert_eq!(engine.poll().await.unwrap(), Some(TickOutcome::Written));
+        assert_eq!(engine.last_checkpoint_info().await.unwrap().trigger, CheckpointTrigger::Interval);
+        assert_eq!(engine.on_task_completed().await.unwrap(), None);
+        assert_eq!(engine.on_task_completed().await.unwrap(), None);
+
+        // Os dois vencem juntos: um único checkpoint
+        clock.advance(Duration::from_secs(600));
+        assert_eq!(engine.on_task_completed().await.unwrap(), Some(TickOutcome::Written));
+        let info = engine.last_checkpoint_info().await.unwrap();
+        assert_eq!(info.triggers, vec![CheckpointTrigger::Interval, CheckpointTrigger::TaskCount]);
+        assert_eq!(engine.poll().await.unwrap(), None);
+        assert_eq!(store.list_checkpoints().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_task_count_strategy_consumes_completion_events() {
+        let store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let event_bus = Arc::new(EventBus::new(store.clone()));
+        let config = CheckpointConfig::with_strategy(CheckpointStrategy::TaskCount(2));
+        let unwired = Arc::new(CheckpointEngine::with_config(store.clone(), config.clone()));
+        assert!(matches!(unwired.start().await, Err(TaskMeshError::Configuration(_))));
+
+        let engine = Arc::new(CheckpointEngine::with_config(store.clone(), config).with_event_bus(event_bus.clone()));
+        engine.start().await.unwrap();
+        for event_type in [EventType::TaskCompleted, EventType::TaskFailed, EventType::TaskCompleted, EventType::TaskCompleted] {
+            event_bus.publish(SystemEvent::new(event_type, Some(TaskId::new_v4()), serde_json::Value::Null)).await.unwrap();
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while store.list_checkpoints().await.unwrap().is_empty() {
+            assert!(Instant::now() < deadline, "checkpoint por contagem não gravado");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        engine.stop().await.unwrap();
+        assert_eq!(store.list_checkpoints().await.unwrap().len(), 1);
+        assert_eq!(engine.last_checkpoint_info().await.unwrap().trigger, CheckpointTrigger::TaskCount);
+    }
+}
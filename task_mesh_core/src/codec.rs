@@ -0,0 +1,245 @@
+//! Codecs de serialização e compressão para dados persistidos
+//!
+//! Um blob codificado começa com um cabeçalho de 6 bytes, [`CODEC_MAGIC`]
+//! seguido do codec e da compressão usados, de modo que a leitura detecta o
+//! formato sem depender da configuração atual:
+//!
+//! ```text
+//! | T M C D | codec (u8) | compressão (u8) | conteúdo comprimido ... |
+//! ```
+//!
+//! Blobs sem o cabeçalho são tratados pelo chamador no formato anterior
+//! (checkpoints em bincode puro, ver [`crate::compat`]).
+
+use std::io::{Read, Write};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+
+/// Assinatura dos blobs com cabeçalho de codec
+pub const CODEC_MAGIC: [u8; 4] = *b"TMCD";
+
+const CODEC_HEADER_LEN: usize = CODEC_MAGIC.len() + 2;
+
+/// Nível padrão do zstd (o mesmo da CLI `zstd`)
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Formato de serialização
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Codec {
+    /// Compacto e rápido, legível só a partir de Rust
+    Bincode,
+    /// Legível por humanos e por qualquer linguagem
+    Json,
+    /// Binário autodescritivo (RFC 8949), legível por qualquer linguagem
+    Cbor,
+}
+
+/// Compressão aplicada sobre o conteúdo serializado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    None,
+    Gzip,
+    /// Nível de 1 (mais rápido) a 22 (menor)
+    Zstd { level: i32 },
+}
+
+/// Codec e compressão escolhidos para gravação
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodecConfig {
+    pub codec: Codec,
+    pub compression: Compression,
+}
+
+impl Default for CodecConfig {
+    /// Bincode sem compressão, o formato histórico dos checkpoints
+    fn default() -> Self {
+        Self { codec: Codec::Bincode, compression: Compression::None }
+    }
+}
+
+impl CodecConfig {
+    /// Combinação recomendada para snapshots: CBOR com zstd
+    pub fn recommended() -> Self {
+        Self { codec: Codec::Cbor, compression: Compression::Zstd { level: DEFAULT_ZSTD_LEVEL } }
+    }
+
+    /// Todas as combinações de codec e compressão (zstd no nível padrão)
+    pub fn all() -> Vec<Self> {
+        let compressions = [Compression::None, Compression::Gzip, Compression::Zstd { level: DEFAULT_ZSTD_LEVEL }];
+        [Codec::Bincode, Codec::Json, Codec::Cbor]
+            .into_iter()
+            .flat_map(|codec| compressions.into_iter().map(move |compression| Self { codec, compression }))
+            .collect()
+    }
+
+    /// Serializa `value` e grava o blob com cabeçalho
+    pub fn encode<T: Serialize>(&self, value: &T) -> TaskMeshResult<Vec<u8>> {
+        self.wrap(&self.codec.serialize(value)?)
+    }
+
+    /// Comprime um conteúdo já serializado com `self.codec` e grava o cabeçalho
+    pub fn wrap(&self, payload: &[u8]) -> TaskMeshResult<Vec<u8>> {
+        let mut data = Vec::with_capacity(CODEC_HEADER_LEN + payload.len());
+        data.extend_from_slice(&CODEC_MAGIC);
+        data.push(self.codec.tag());
+        data.push(self.compression.tag());
+        self.compression.compress(payload, &mut data)?;
+        Ok(data)
+    }
+
+    /// Separa o cabeçalho de `data` e descomprime o conteúdo
+    ///
+    /// Retorna `None` se o blob não tem cabeçalho (formato anterior). O nível
+    /// do zstd não é gravado e volta como [`DEFAULT_ZSTD_LEVEL`].
+    pub fn unwrap(data: &[u8]) -> TaskMeshResult<Option<(Self, Vec<u8>)>> {
+        let Some(header) = data.strip_prefix(&CODEC_MAGIC) else {
+            return Ok(None);
+        };
+        let (&[codec, compression], body) = header.split_at(2.min(header.len())) else {
+            return Err(TaskMeshError::Internal("Cabeçalho de codec truncado".to_string()));
+        };
+        let config = Self { codec: Codec::from_tag(codec)?, compression: Compression::from_tag(compression)? };
+        let payload = config.compression.decompress(body)?;
+        Ok(Some((config, payload)))
+    }
+
+    /// Lê um blob com cabeçalho, qualquer que seja o codec em que foi gravado
+    pub fn decode<T: DeserializeOwned>(data: &[u8]) -> TaskMeshResult<T> {
+        let (config, payload) = Self::unwrap(data)?
+            .ok_or_else(|| TaskMeshError::Internal("Blob sem cabeçalho de codec".to_string()))?;
+        config.codec.deserialize(&payload)
+    }
+}
+
+impl Codec {
+    pub fn serialize<T: Serialize>(&self, value: &T) -> TaskMeshResult<Vec<u8>> {
+        let encoded = match self {
+            Codec::Bincode => bincode::serialize(value).map_err(|e| e.to_string()),
+            Codec::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            Codec::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(value, &mut buffer).map(|()| buffer).map_err(|e| e.to_string())
+            }
+        };
+        encoded.map_err(|e| TaskMeshError::Internal(format!("Erro de serialização ({:?}): {}", self, e)))
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(&self, data: &[u8]) -> TaskMeshResult<T> {
+        let decoded = match self {
+            Codec::Bincode => bincode::deserialize(data).map_err(|e| e.to_string()),
+            Codec::Json => serde_json::from_slice(data).map_err(|e| e.to_string()),
+            Codec::Cbor => ciborium::from_reader(data).map_err(|e| e.to_string()),
+        };
+        decoded.map_err(|e| TaskMeshError::Internal(format!("Erro de desserialização ({:?}): {}", self, e)))
+    }
+
+    /// Extensão de arquivo usual do formato
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Codec::Bincode => "bin",
+            Codec::Json => "json",
+            Codec::Cbor => "cbor",
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            Codec::Bincode => 0,
+            Codec::Json => 1,
+            Codec::Cbor => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> TaskMeshResult<Self> {
+        match tag {
+            0 => Ok(Codec::Bincode),
+            1 => Ok(Codec::Json),
+            2 => Ok(Codec::Cbor),
+            other => Err(TaskMeshError::Internal(format!("Codec desconhecido: {}", other))),
+        }
+    }
+}
+
+impl Compression {
+    /// Sufixo de arquivo usual (`""` sem compressão)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd { .. } => ".zst",
+        }
+    }
+
+    fn compress(&self, payload: &[u8], out: &mut Vec<u8>) -> TaskMeshResult<()> {
+        match self {
+            Compression::None => out.extend_from_slice(payload),
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(out, flate2::Compression::default());
+                encoder.write_all(payload)?;
+                encoder.finish()?;
+            }
+            Compression::Zstd { level } => zstd::stream::copy_encode(payload, out, *level)?,
+        }
+        Ok(())
+    }
+
+    fn decompress(&self, body: &[u8]) -> TaskMeshResult<Vec<u8>> {
+        let mut payload = Vec::new();
+        match self {
+            Compression::None => payload.extend_from_slice(body),
+            Compression::Gzip => {
+                flate2::read::GzDecoder::new(body).read_to_end(&mut payload)?;
+            }
+            Compression::Zstd { .. } => zstd::stream::copy_decode(body, &mut payload)?,
+        }
+        Ok(payload)
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Gzip => 1,
+            Compression::Zstd { .. } => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> TaskMeshResult<Self> {
+        match tag {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Gzip),
+            2 => Ok(Compression::Zstd { level: DEFAULT_ZSTD_LEVEL }),
+            other => Err(TaskMeshError::Internal(format!("Compressão desconhecida: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_combination_round_trips_and_is_detected() {
+        let tasks: Vec<Task> = (0..50)
+            .map(|i| Task::new(format!("task-{}", i), TaskDefinition::command("true"), vec![]))
+            .collect();
+        for config in CodecConfig::all() {
+            let blob = config.encode(&tasks).unwrap();
+            let (detected, _) = CodecConfig::unwrap(&blob).unwrap().unwrap();
+            assert_eq!(detected, config);
+            let decoded: Vec<Task> = CodecConfig::decode(&blob).unwrap();
+            assert_eq!(decoded.len(), tasks.len(), "{:?}", config);
+            assert_eq!(decoded[7].id, tasks[7].id, "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn test_headerless_and_unknown_blobs() {
+        assert!(CodecConfig::unwrap(b"not a codec blob").unwrap().is_none());
+        assert!(CodecConfig::unwrap(b"TMCD\x09\x00{}").is_err());
+        assert!(CodecConfig::unwrap(b"TMCD").is_err());
+    }
+}
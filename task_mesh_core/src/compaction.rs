@@ -0,0 +1,127 @@
+//! Compactação e estatísticas do armazenamento de estado
+//!
+//! Linhas removidas pela coleta ([`crate::gc`]) e pela limpeza de eventos
+//! deixam páginas livres que o SQLite não devolve ao sistema sozinho: o
+//! arquivo só cresce. A [`StorageMaintenance`] roda periodicamente (ver
+//! [`StorageMaintenanceConfig::interval_ms`]) a compactação incremental,
+//! que libera as páginas livres e atualiza as estatísticas do planejador
+//! sem reescrever o banco, e publica tamanho do arquivo, páginas e páginas
+//! livres em gauges Prometheus (feature `metrics`).
+//!
+//! A compactação completa (`VACUUM`) reescreve o banco e segura a trava de
+//! escrita até terminar; só roda sob demanda, via
+//! [`crate::TaskMeshCore::compact_storage`], com o despacho pausado.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::state_store::{CompactionMode, StateStore, StorageStats};
+use crate::types::*;
+
+/// Configuração da manutenção periódica
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageMaintenanceConfig {
+    /// Intervalo da compactação incremental (ms); `None` desativa o loop
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+}
+
+/// Resultado de uma compactação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub mode: CompactionMode,
+    pub before: StorageStats,
+    pub after: StorageStats,
+    pub duration: Duration,
+}
+
+impl CompactionReport {
+    /// Bytes devolvidos ao sistema, quando o backend informa o tamanho do arquivo
+    pub fn reclaimed_bytes(&self) -> Option<u64> {
+        Some(self.before.file_bytes?.saturating_sub(self.after.file_bytes?))
+    }
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct StorageMetrics {
+    file_bytes: prometheus::IntGauge,
+    pages: prometheus::IntGauge,
+    freelist_pages: prometheus::IntGauge,
+}
+
+/// Compactação e estatísticas de um StateStore
+pub struct StorageMaintenance {
+    store: Arc<dyn StateStore>,
+    #[cfg(feature = "metrics")]
+    metrics: StorageMetrics,
+}
+
+impl StorageMaintenance {
+    pub fn new(store: Arc<dyn StateStore>) -> Self {
+        Self {
+            store,
+            #[cfg(feature = "metrics")]
+            metrics: StorageMetrics {
+                file_bytes: prometheus::IntGauge::new(
+                    "taskmesh_storage_file_bytes",
+                    "Tamanho do arquivo do banco de estado",
+                ).expect("nome de métrica válido"),
+                pages: prometheus::IntGauge::new(
+                    "taskmesh_storage_pages",
+                    "Páginas do banco de estado",
+                ).expect("nome de métrica válido"),
+                freelist_pages: prometheus::IntGauge::new(
+                    "taskmesh_storage_freelist_pages",
+                    "Páginas livres do banco de estado, recuperáveis por compactação",
+                ).expect("nome de métrica válido"),
+            },
+        }
+    }
+
+    /// Registra os gauges do armazenamento em um registry Prometheus
+    #[cfg(feature = "metrics")]
+    pub fn register_metrics(&self, registry: &prometheus::Registry) -> TaskMeshResult<()> {
+        registry
+            .register(Box::new(self.metrics.file_bytes.clone()))
+            .and_then(|_| registry.register(Box::new(self.metrics.pages.clone())))
+            .and_then(|_| registry.register(Box::new(self.metrics.freelist_pages.clone())))
+            .map_err(|e| TaskMeshError::Internal(format!("Erro ao registrar métricas do armazenamento: {}", e)))
+    }
+
+    /// Lê as estatísticas do StateStore e atualiza os gauges
+    pub async fn refresh(&self) -> TaskMeshResult<StorageStats> {
+        let stats = self.store.stats().await?;
+        #[cfg(feature = "metrics")]
+        {
+            let gauges = [
+                (&self.metrics.file_bytes, stats.file_bytes),
+                (&self.metrics.pages, stats.page_count),
+                (&self.metrics.freelist_pages, stats.freelist_pages),
+            ];
+            for (gauge, value) in gauges {
+                if let Some(value) = value {
+                    gauge.set(value as i64);
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Compacta o armazenamento e relata as estatísticas antes e depois
+    pub async fn compact(&self, mode: CompactionMode) -> TaskMeshResult<CompactionReport> {
+        let before = self.refresh().await?;
+        let started = Instant::now();
+        self.store.compact(mode).await?;
+        let duration = started.elapsed();
+        let after = self.refresh().await?;
+
+        let report = CompactionReport { mode, before, after, duration };
+        if let Some(reclaimed) = report.reclaimed_bytes().filter(|bytes| *bytes > 0) {
+            info!("Compactação {:?} liberou {} KiB em {:?}", mode, reclaimed / 1024, duration);
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,268 @@
+//! Compatibilidade dos formatos persistidos
+//!
+//! Tarefas, status e eventos ficam gravados no SQLite/Redis em JSON, e os
+//! checkpoints em bincode (SQLite, memória; outro codec com
+//! `checkpoint_codec`, ver [`crate::codec`]) ou JSON (Redis). Dados gravados
+//! por uma versão precisam continuar legíveis pelas seguintes, então toda
+//! mudança nesses tipos segue esta política:
+//!
+//! - **Campo novo**: sempre com `#[serde(default)]`. O bincode não tolera
+//!   campos novos, então o checkpoint incrementa [`FORMAT_VERSION`] e
+//!   ganha um layout legado em `CheckpointData::decode` (ver `TaskV1`,
+//!   `TaskV2`, ..., `TaskV5`, `UngroupedTask`, `LegacyTask`).
+//! - **Campo renomeado**: o nome antigo continua aceito via
+//!   `#[serde(alias = "...")]`; variantes de enum também.
+//! - **Campo removido ou tipo alterado**: incrementa [`FORMAT_VERSION`] e
+//!   mantém a leitura da versão anterior. Versões acima da suportada são
+//!   recusadas com [`TaskMeshError::UnsupportedFormatVersion`], nunca lidas
+//!   pela metade.
+//!
+//! As fixtures em `fixtures/compat/` não devem ser editadas: os testes abaixo
+//! desserializam e re-serializam as da versão atual (maior sufixo `_vN`),
+//! de modo que remover ou renomear um campo quebra o build em vez dos dados
+//! já persistidos, e verificam que as das versões anteriores continuam
+//! legíveis. Um novo formato ganha fixtures novas ao lado das antigas.
+
+use crate::types::{TaskMeshError, TaskMeshResult};
+
+/// Versão do formato dos checkpoints; incrementada a cada mudança incompatível
+///
+/// Checkpoints anteriores ao versionamento são lidos como versão 0.
+///
+/// - 1: cabeçalho de versão nos checkpoints em bincode;
+/// - 2: `Task::sidecars`;
+/// - 3: `Task::concurrency_group`;
+/// - 4: `Task::hooks`;
+/// - 5: `Task::sla`;
+/// - 6: `Task::forced_outcome`.
+pub const FORMAT_VERSION: u32 = 6;
+
+/// Recusa dados gravados por uma versão de formato mais nova que esta
+pub fn check_format_version(found: u32) -> TaskMeshResult<()> {
+    if found > FORMAT_VERSION {
+        return Err(TaskMeshError::UnsupportedFormatVersion { found, supported: FORMAT_VERSION });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_store::{CheckpointData, TaskV1, TaskV2, TaskV3, TaskV4, TaskV5};
+    use crate::types::{SystemEvent, Task, TaskStatus};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    const TASK_JSON: &str = include_str!("../fixtures/compat/task_v6.json");
+    const TASK_BIN: &[u8] = include_bytes!("../fixtures/compat/task_v6.bin");
+    const TASK_STATUS_JSON: &str = include_str!("../fixtures/compat/task_status.json");
+    const SYSTEM_EVENT_JSON: &str = include_str!("../fixtures/compat/system_event.json");
+    const CHECKPOINT_JSON: &str = include_str!("../fixtures/compat/checkpoint_v6.json");
+    const CHECKPOINT_BIN: &[u8] = include_bytes!("../fixtures/compat/checkpoint_v6.bin");
+
+    const TASK_V5_JSON: &str = include_str!("../fixtures/compat/task_v5.json");
+    const TASK_V5_BIN: &[u8] = include_bytes!("../fixtures/compat/task_v5.bin");
+    const CHECKPOINT_V5_JSON: &str = include_str!("../fixtures/compat/checkpoint_v5.json");
+    const CHECKPOINT_V5_BIN: &[u8] = include_bytes!("../fixtures/compat/checkpoint_v5.bin");
+
+    const TASK_V4_JSON: &str = include_str!("../fixtures/compat/task_v4.json");
+    const TASK_V4_BIN: &[u8] = include_bytes!("../fixtures/compat/task_v4.bin");
+    const CHECKPOINT_V4_JSON: &str = include_str!("../fixtures/compat/checkpoint_v4.json");
+    const CHECKPOINT_V4_BIN: &[u8] = include_bytes!("../fixtures/compat/checkpoint_v4.bin");
+
+    const TASK_V3_JSON: &str = include_str!("../fixtures/compat/task_v3.json");
+    const TASK_V3_BIN: &[u8] = include_bytes!("../fixtures/compat/task_v3.bin");
+    const CHECKPOINT_V3_JSON: &str = include_str!("../fixtures/compat/checkpoint_v3.json");
+    const CHECKPOINT_V3_BIN: &[u8] = include_bytes!("../fixtures/compat/checkpoint_v3.bin");
+
+    const TASK_V2_JSON: &str = include_str!("../fixtures/compat/task_v2.json");
+    const TASK_V2_BIN: &[u8] = include_bytes!("../fixtures/compat/task_v2.bin");
+    const CHECKPOINT_V2_JSON: &str = include_str!("../fixtures/compat/checkpoint_v2.json");
+    const CHECKPOINT_V2_BIN: &[u8] = include_bytes!("../fixtures/compat/checkpoint_v2.bin");
+
+    const TASK_V1_JSON: &str = include_str!("../fixtures/compat/task.json");
+    const TASK_V1_BIN: &[u8] = include_bytes!("../fixtures/compat/task.bin");
+    const CHECKPOINT_V1_JSON: &str = include_str!("../fixtures/compat/checkpoint.json");
+    const CHECKPOINT_V1_BIN: &[u8] = include_bytes!("../fixtures/compat/checkpoint.bin");
+
+    /// Lê a fixture e verifica que a re-serialização preserva todos os campos
+    fn assert_json_round_trip<T: Serialize + DeserializeOwned>(fixture: &str) -> T {
+        let value: T = serde_json::from_str(fixture).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        assert_eq!(serde_json::to_value(&value).unwrap(), expected);
+        value
+    }
+
+    /// Verifica que `actual` contém todos os campos de `expected`, com os
+    /// mesmos valores (campos novos são ignorados)
+    fn assert_fields_preserved(expected: &serde_json::Value, actual: &serde_json::Value, path: &str) {
+        match (expected, actual) {
+            (serde_json::Value::Object(expected), serde_json::Value::Object(actual)) => {
+                for (key, field) in expected {
+                    let actual = actual.get(key).unwrap_or_else(|| panic!("campo {}.{} ausente", path, key));
+                    assert_fields_preserved(field, actual, &format!("{}.{}", path, key));
+                }
+            }
+            (serde_json::Value::Array(expected), serde_json::Value::Array(actual)) => {
+                assert_eq!(expected.len(), actual.len(), "campo {}", path);
+                for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                    assert_fields_preserved(expected, actual, &format!("{}[{}]", path, index));
+                }
+            }
+            _ => assert_eq!(expected, actual, "campo {}", path),
+        }
+    }
+
+    /// Lê a fixture de uma versão anterior e verifica que a re-serialização
+    /// preserva os campos que ela tinha
+    fn assert_json_fields_preserved<T: Serialize + DeserializeOwned>(fixture: &str) -> T {
+        let value: T = serde_json::from_str(fixture).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        assert_fields_preserved(&expected, &serde_json::to_value(&value).unwrap(), "");
+        value
+    }
+
+    #[test]
+    fn test_json_fixtures_round_trip() {
+        let task: Task = assert_json_round_trip(TASK_JSON);
+        assert_eq!(task.id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        assert_eq!(task.alias.as_deref(), Some("report"));
+        assert_eq!(task.sidecars[0].name, "proxy");
+        assert_eq!(task.concurrency_group.as_deref(), Some("reports"));
+        assert_eq!(task.hooks[0].on, crate::hooks::HookPhase::Finally);
+        assert_eq!(task.sla.as_ref().unwrap().deadline, crate::sla::SlaDeadline::WithinMs(3_600_000));
+        assert_eq!(task.forced_outcome.as_ref().unwrap().exit_code, Some(7));
+
+        let statuses: Vec<TaskStatus> = assert_json_round_trip(TASK_STATUS_JSON);
+        assert_eq!(statuses.len(), 4);
+        assert_eq!(statuses[0], TaskStatus::Pending);
+
+        let event: SystemEvent = assert_json_round_trip(SYSTEM_EVENT_JSON);
+        assert_eq!(event.task_id, Some(task.id));
+
+        let checkpoint: CheckpointData = assert_json_round_trip(CHECKPOINT_JSON);
+        assert_eq!(checkpoint.format_version, FORMAT_VERSION);
+        assert_eq!(checkpoint.tasks[0].id, task.id);
+    }
+
+    #[test]
+    fn test_bincode_fixtures_round_trip() {
+        let task: Task = bincode::deserialize(TASK_BIN).unwrap();
+        assert_eq!(task.name, "nightly-report");
+        assert_eq!(bincode::serialize(&task).unwrap(), TASK_BIN);
+
+        let checkpoint = CheckpointData::decode("fixture", CHECKPOINT_BIN).unwrap();
+        assert_eq!(checkpoint.format_version, FORMAT_VERSION);
+        assert_eq!(checkpoint.tasks[0].id, task.id);
+        assert_eq!(checkpoint.encode().unwrap(), CHECKPOINT_BIN);
+    }
+
+    #[test]
+    fn test_previous_format_fixtures_are_read() {
+        let task: Task = assert_json_fields_preserved(TASK_V1_JSON);
+        assert!(task.sidecars.is_empty());
+
+        let checkpoint: CheckpointData = assert_json_fields_preserved(CHECKPOINT_V1_JSON);
+        assert_eq!(checkpoint.format_version, 1);
+        assert!(checkpoint.tasks[0].sidecars.is_empty());
+
+        let task: Task = bincode::deserialize::<TaskV1>(TASK_V1_BIN).unwrap().into();
+        assert_eq!(task.name, "nightly-report");
+        assert!(task.sidecars.is_empty());
+
+        let checkpoint = CheckpointData::decode("fixture", CHECKPOINT_V1_BIN).unwrap();
+        assert_eq!(checkpoint.format_version, 1);
+        assert_eq!(checkpoint.tasks[0].id, task.id);
+        assert!(checkpoint.tasks[0].sidecars.is_empty());
+
+        let task: Task = assert_json_fields_preserved(TASK_V2_JSON);
+        assert_eq!(task.sidecars.len(), 1);
+        assert_eq!(task.concurrency_group, None);
+
+        let checkpoint: CheckpointData = assert_json_fields_preserved(CHECKPOINT_V2_JSON);
+        assert_eq!(checkpoint.format_version, 2);
+        assert_eq!(checkpoint.tasks[0].concurrency_group, None);
+
+        let task: Task = bincode::deserialize::<TaskV2>(TASK_V2_BIN).unwrap().into();
+        assert_eq!(task.sidecars[0].name, "proxy");
+        assert_eq!(task.concurrency_group, None);
+
+        let checkpoint = CheckpointData::decode("fixture", CHECKPOINT_V2_BIN).unwrap();
+        assert_eq!(checkpoint.format_version, 2);
+        assert_eq!(checkpoint.tasks[0].sidecars[0].name, "proxy");
+        assert_eq!(checkpoint.tasks[0].concurrency_group, None);
+
+        let task: Task = assert_json_fields_preserved(TASK_V3_JSON);
+        assert_eq!(task.concurrency_group.as_deref(), Some("reports"));
+        assert!(task.hooks.is_empty());
+
+        let checkpoint: CheckpointData = assert_json_fields_preserved(CHECKPOINT_V3_JSON);
+        assert_eq!(checkpoint.format_version, 3);
+        assert!(checkpoint.tasks[0].hooks.is_empty());
+
+        let task: Task = bincode::deserialize::<TaskV3>(TASK_V3_BIN).unwrap().into();
+        assert_eq!(task.concurrency_group.as_deref(), Some("reports"));
+        assert!(task.hooks.is_empty());
+
+        let checkpoint = CheckpointData::decode("fixture", CHECKPOINT_V3_BIN).unwrap();
+        assert_eq!(checkpoint.format_version, 3);
+        assert_eq!(checkpoint.tasks[0].concurrency_group.as_deref(), Some("reports"));
+        assert!(checkpoint.tasks[0].hooks.is_empty());
+
+        let task: Task = assert_json_fields_preserved(TASK_V4_JSON);
+        assert_eq!(task.hooks.len(), 1);
+        assert_eq!(task.sla, None);
+
+        let checkpoint: CheckpointData = assert_json_fields_preserved(CHECKPOINT_V4_JSON);
+        assert_eq!(checkpoint.format_version, 4);
+        assert_eq!(checkpoint.tasks[0].sla, None);
+
+        let task: Task = bincode::deserialize::<TaskV4>(TASK_V4_BIN).unwrap().into();
+        assert_eq!(task.hooks[0].on, crate::hooks::HookPhase::Finally);
+        assert_eq!(task.sla, None);
+
+        let checkpoint = CheckpointData::decode("fixture", CHECKPOINT_V4_BIN).unwrap();
+        assert_eq!(checkpoint.format_version, 4);
+        assert_eq!(checkpoint.tasks[0].hooks.len(), 1);
+        assert_eq!(checkpoint.tasks[0].sla, None);
+
+        let task: Task = assert_json_fields_preserved(TASK_V5_JSON);
+        assert!(task.sla.is_some());
+        assert_eq!(task.forced_outcome, None);
+
+        let checkpoint: CheckpointData = assert_json_fields_preserved(CHECKPOINT_V5_JSON);
+        assert_eq!(checkpoint.format_version, 5);
+        assert_eq!(checkpoint.tasks[0].forced_outcome, None);
+
+        let task: Task = bincode::deserialize::<TaskV5>(TASK_V5_BIN).unwrap().into();
+        assert_eq!(task.sla.as_ref().map(|sla| sla.warn_at), Some(80));
+        assert_eq!(task.forced_outcome, None);
+
+        let checkpoint = CheckpointData::decode("fixture", CHECKPOINT_V5_BIN).unwrap();
+        assert_eq!(checkpoint.format_version, 5);
+        assert!(checkpoint.tasks[0].sla.is_some());
+        assert_eq!(checkpoint.tasks[0].forced_outcome, None);
+    }
+
+    #[test]
+    fn test_newer_format_version_is_rejected() {
+        let mut blob = CHECKPOINT_BIN.to_vec();
+        blob[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        match CheckpointData::decode("fixture", &blob) {
+            Err(TaskMeshError::UnsupportedFormatVersion { found, supported }) => {
+                assert_eq!(found, FORMAT_VERSION + 1);
+                assert_eq!(supported, FORMAT_VERSION);
+            }
+            other => panic!("esperava UnsupportedFormatVersion, obtido {:?}", other.map(|_| ())),
+        }
+
+        let json = CHECKPOINT_JSON.replace(
+            &format!("\"format_version\": {}", FORMAT_VERSION),
+            &format!("\"format_version\": {}", FORMAT_VERSION + 1),
+        );
+        assert!(matches!(
+            CheckpointData::from_json("fixture", &json),
+            Err(TaskMeshError::UnsupportedFormatVersion { found, .. }) if found == FORMAT_VERSION + 1
+        ));
+    }
+}
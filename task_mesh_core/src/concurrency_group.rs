@@ -0,0 +1,214 @@
+//! Grupos de concorrência: exclusão mútua entre tarefas que disputam um recurso
+//!
+//! Tarefas com o mesmo `Task::concurrency_group` (ex.: migrações do mesmo
+//! banco) executam no máximo `limite` por vez, 1 salvo configuração. A vaga
+//! pertence à tarefa, não à tentativa: continua ocupada durante o backoff de
+//! um retry e só é liberada quando a tarefa chega a um estado terminal.
+//!
+//! Membros à espera recebem a vaga por prioridade e, na mesma prioridade, por
+//! ordem de chegada. O scheduler ocupa a vaga ao despachar (e deixa na fila
+//! os membros de um grupo cheio); o executor a ocupa ao executar tarefas
+//! submetidas diretamente, aguardando se preciso. As vagas vivem na memória
+//! do processo: instâncias que compartilham um StateStore não se excluem.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::types::*;
+
+impl Task {
+    /// Coloca a tarefa em um grupo de concorrência
+    pub fn with_concurrency_group(mut self, group: impl Into<String>) -> Self {
+        self.concurrency_group = Some(group.into());
+        self
+    }
+}
+
+/// Vagas ocupadas e espera de cada grupo
+#[derive(Default)]
+struct GroupState {
+    holders: HashSet<TaskId>,
+    waiters: Vec<Waiter>,
+    next_seq: u64,
+}
+
+/// Tarefa aguardando uma vaga no executor
+struct Waiter {
+    task_id: TaskId,
+    priority: Priority,
+    seq: u64,
+    grant: oneshot::Sender<()>,
+}
+
+/// Vagas dos grupos de concorrência, compartilhadas por scheduler e executor
+#[derive(Default)]
+pub struct ConcurrencyGroups {
+    /// Tarefas simultâneas por grupo (ausente = 1)
+    limits: HashMap<String, usize>,
+    groups: Mutex<HashMap<String, GroupState>>,
+}
+
+impl ConcurrencyGroups {
+    pub fn new(limits: HashMap<String, usize>) -> Self {
+        Self { limits, groups: Mutex::new(HashMap::new()) }
+    }
+
+    /// Tarefas simultâneas permitidas no grupo
+    pub fn limit(&self, group: &str) -> usize {
+        self.limits.get(group).copied().unwrap_or(1).max(1)
+    }
+
+    /// Ocupa uma vaga do grupo sem esperar
+    ///
+    /// Retorna `true` se a tarefa já tinha a vaga (retentativa) ou se havia
+    /// uma livre sem ninguém à espera.
+    pub fn try_acquire(&self, group: &str, task_id: &TaskId) -> bool {
+        let mut groups = self.groups.lock().unwrap();
+        let state = groups.entry(group.to_string()).or_default();
+        if state.holders.contains(task_id) {
+            return true;
+        }
+        if state.holders.len() < self.limit(group) && state.waiters.is_empty() {
+            state.holders.insert(*task_id);
+            return true;
+        }
+        false
+    }
+
+    /// Ocupa uma vaga do grupo da tarefa, esperando a vez se estiver cheio
+    ///
+    /// Retorna `false` se a espera foi encerrada por [`Self::release`] (a
+    /// tarefa foi cancelada antes de obter a vaga). Tarefas sem grupo passam
+    /// direto.
+    pub async fn acquire(&self, task: &Task) -> bool {
+        let Some(group) = task.concurrency_group.as_deref() else { return true };
+        let granted = {
+            let mut groups = self.groups.lock().unwrap();
+            let state = groups.entry(group.to_string()).or_default();
+            if state.holders.contains(&task.id)
+                || (state.holders.len() < self.limit(group) && state.waiters.is_empty())
+            {
+                state.holders.insert(task.id);
+                return true;
+            }
+            let (grant, granted) = oneshot::channel();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiters.push(Waiter { task_id: task.id, priority: task.priority, seq, grant });
+            granted
+        };
+        debug!(task = %task.id.short(), "Aguardando vaga no grupo de concorrência '{}'", group);
+        granted.await.is_ok()
+    }
+
+    /// Libera a vaga (ou a espera) da tarefa em todos os grupos
+    ///
+    /// Chamado em estados terminais; não faz nada para tarefas sem vaga.
+    pub fn release(&self, task_id: &TaskId) {
+        let mut groups = self.groups.lock().unwrap();
+        for (group, state) in groups.iter_mut() {
+            state.waiters.retain(|waiter| waiter.task_id != *task_id);
+            if state.holders.remove(task_id) {
+                self.grant_waiters(group, state);
+            }
+        }
+        groups.retain(|_, state| !state.holders.is_empty() || !state.waiters.is_empty());
+    }
+
+    /// Libera a vaga da tarefa quando o guard for descartado
+    pub fn release_on_drop(&self, task_id: TaskId) -> GroupRelease<'_> {
+        GroupRelease { groups: self, task_id }
+    }
+
+    /// Passa as vagas livres aos próximos da espera
+    fn grant_waiters(&self, group: &str, state: &mut GroupState) {
+        while state.holders.len() < self.limit(group) && !state.waiters.is_empty() {
+            let next = state.waiters.iter()
+                .enumerate()
+                .max_by_key(|(_, waiter)| (waiter.priority, std::cmp::Reverse(waiter.seq)))
+                .map(|(index, _)| index)
+                .unwrap_or(0);
+            let waiter = state.waiters.remove(next);
+            // Espera abandonada: a vaga segue para o próximo
+            if waiter.grant.send(()).is_ok() {
+                state.holders.insert(waiter.task_id);
+            }
+        }
+    }
+
+    /// Se o grupo está cheio para a tarefa, a ocupação como `(ocupadas, limite)`
+    pub fn blocked(&self, group: &str, task_id: &TaskId) -> Option<(usize, usize)> {
+        let groups = self.groups.lock().unwrap();
+        let held = groups.get(group).map_or(0, |state| state.holders.len());
+        let limit = self.limit(group);
+        let holds = groups.get(group).is_some_and(|state| state.holders.contains(task_id));
+        (!holds && held >= limit).then_some((held, limit))
+    }
+}
+
+/// Libera a vaga da tarefa ao sair de escopo (ver [`ConcurrencyGroups::release_on_drop`])
+pub struct GroupRelease<'a> {
+    groups: &'a ConcurrencyGroups,
+    task_id: TaskId,
+}
+
+impl GroupRelease<'_> {
+    /// Mantém a vaga ocupada (a tarefa volta para um retry)
+    pub fn keep(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for GroupRelease<'_> {
+    fn drop(&mut self) {
+        self.groups.release(&self.task_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn member(name: &str, priority: Priority) -> Task {
+        Task::new(name.to_string(), TaskDefinition::command("true"), vec![])
+            .with_priority(priority)
+            .with_concurrency_group("db")
+    }
+
+    #[tokio::test]
+    async fn test_waiters_served_by_priority_then_arrival() {
+        let groups = Arc::new(ConcurrencyGroups::default());
+        let holder = member("holder", Priority::NORMAL);
+        assert!(groups.acquire(&holder).await);
+        assert!(groups.acquire(&holder).await, "a vaga é reentrante para a própria tarefa");
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut handles = Vec::new();
+        for task in [member("a", Priority::NORMAL), member("b", Priority::HIGH), member("c", Priority::NORMAL)] {
+            let (groups, tx) = (groups.clone(), tx.clone());
+            handles.push(tokio::spawn(async move {
+                assert!(groups.acquire(&task).await);
+                tx.send(task.name.clone()).unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                groups.release(&task.id);
+            }));
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(groups.blocked("db", &TaskId::new_v4()), Some((1, 1)));
+        groups.release(&holder.id);
+
+        let mut order = Vec::new();
+        for _ in 0..3 {
+            order.push(rx.recv().await.unwrap());
+        }
+        assert_eq!(order, ["b", "a", "c"]);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(groups.blocked("db", &TaskId::new_v4()), None);
+    }
+}
@@ -0,0 +1,71 @@
+//! Fila de mensagens mortas (DLQ) das falhas sem retry
+//!
+//! Falhas decididas por uma regra `NoRetry` (ver [`crate::retry_rules`]) não
+//! voltam ao scheduler: o executor as registra aqui, com a decisão que as
+//! trouxe, para inspeção e reprocessamento manual. A fila é mantida em
+//! memória e guarda as últimas [`DEAD_LETTER_CAPACITY`] falhas; o evento
+//! `TaskFailed` correspondente leva `"dead_letter": true`.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+
+use crate::retry_rules::PolicyDecision;
+use crate::types::*;
+
+/// Falhas mantidas na fila (as mais antigas são descartadas)
+pub const DEAD_LETTER_CAPACITY: usize = 10_000;
+
+/// Falha enviada à fila
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub task_id: TaskId,
+    pub task_name: String,
+    pub error: String,
+    pub failed_at: SystemTime,
+    /// Decisão de retry que encaminhou a falha
+    pub decision: PolicyDecision,
+}
+
+/// Fila de mensagens mortas do executor
+#[derive(Debug, Default)]
+pub struct DeadLetterQueue {
+    entries: Mutex<VecDeque<DeadLetter>>,
+}
+
+impl DeadLetterQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acrescenta a falha, substituindo uma anterior da mesma tarefa
+    pub fn push(&self, letter: DeadLetter) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|entry| entry.task_id != letter.task_id);
+        if entries.len() == DEAD_LETTER_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(letter);
+    }
+
+    /// Falhas na fila, da mais antiga à mais recente
+    pub fn list(&self) -> Vec<DeadLetter> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Retira a falha da tarefa (ex.: para reprocessá-la)
+    pub fn remove(&self, task_id: &TaskId) -> Option<DeadLetter> {
+        let mut entries = self.entries.lock().unwrap();
+        let position = entries.iter().position(|entry| entry.task_id == *task_id)?;
+        entries.remove(position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
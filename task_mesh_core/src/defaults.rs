@@ -0,0 +1,205 @@
+//! Tags e metadados padrão por namespace e por workflow
+//!
+//! Um conjunto [`TaskDefaults`] é mesclado em cada tarefa na submissão. Os
+//! do namespace vêm de `TaskMeshConfig::namespace_defaults` e os do
+//! workflow, do bloco `defaults:` do arquivo. A precedência é tarefa >
+//! workflow > namespace: um padrão só preenche o que a tarefa (ou uma fonte
+//! mais forte) não definiu. Os limites de `metadata_limits` valem para o
+//! resultado da mesclagem.
+//!
+//! A origem de cada valor herdado fica no metadado [`DEFAULTS_SOURCES_KEY`]
+//! ([`DefaultsProvenance`]) e é copiada para o manifesto de cada tentativa.
+//! Tarefas criadas por geradores e hooks só recebem os padrões das fontes
+//! com `propagate_to_generated`, copiados da tarefa que as originou.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::GENERATED_BY_KEY;
+use crate::types::*;
+use crate::validation::normalize_tag;
+
+/// Metadado com a origem dos valores herdados (JSON de [`DefaultsProvenance`])
+pub const DEFAULTS_SOURCES_KEY: &str = "defaults_sources";
+
+/// Tags e metadados aplicados às tarefas de um namespace ou workflow
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskDefaults {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// Estende os padrões às tarefas geradas e aos hooks
+    #[serde(default)]
+    pub propagate_to_generated: bool,
+}
+
+/// Fonte de um valor herdado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultSource {
+    Workflow,
+    Namespace,
+}
+
+/// Origem das tags e metadados herdados por uma tarefa
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefaultsProvenance {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, DefaultSource>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, DefaultSource>,
+    /// Fontes cujos valores passam às tarefas geradas e aos hooks
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub propagate: BTreeSet<DefaultSource>,
+}
+
+impl DefaultsProvenance {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.metadata.is_empty()
+    }
+
+    fn store(&self, task: &mut Task) {
+        if self.is_empty() {
+            return;
+        }
+        let json = serde_json::to_string(self).expect("DefaultsProvenance serializável");
+        task.metadata.insert(DEFAULTS_SOURCES_KEY.to_string(), json);
+    }
+}
+
+/// Origem dos valores herdados pela tarefa, se houver
+///
+/// Um metadado inválido é tratado como ausente.
+pub fn provenance(task: &Task) -> Option<DefaultsProvenance> {
+    task.metadata.get(DEFAULTS_SOURCES_KEY).and_then(|json| serde_json::from_str(json).ok())
+}
+
+/// Acrescenta a tag se nenhuma tag equivalente existe; retorna se acrescentou
+fn add_tag(task: &mut Task, tag: &str) -> bool {
+    if tag.is_empty() || task.tags.iter().any(|existing| normalize_tag(existing) == tag) {
+        return false;
+    }
+    task.tags.push(tag.to_string());
+    true
+}
+
+impl TaskDefaults {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.metadata.is_empty()
+    }
+
+    /// Preenche as tags e metadados que a tarefa ainda não tem
+    ///
+    /// Tarefas geradas só recebem os padrões com `propagate_to_generated`.
+    pub fn apply(&self, task: &mut Task, source: DefaultSource) {
+        if self.is_empty() || (task.metadata.contains_key(GENERATED_BY_KEY) && !self.propagate_to_generated) {
+            return;
+        }
+        let mut sources = provenance(task).unwrap_or_default();
+        for tag in &self.tags {
+            let tag = normalize_tag(tag);
+            if add_tag(task, &tag) {
+                sources.tags.insert(tag, source);
+            }
+        }
+        let mut keys: Vec<&String> = self.metadata.keys().collect();
+        keys.sort();
+        for key in keys {
+            if key != DEFAULTS_SOURCES_KEY && !task.metadata.contains_key(key) {
+                task.metadata.insert(key.clone(), self.metadata[key].clone());
+                sources.metadata.insert(key.clone(), source);
+            }
+        }
+        if self.propagate_to_generated {
+            sources.propagate.insert(source);
+        }
+        sources.store(task);
+    }
+}
+
+/// Copia para `child` os padrões propagáveis herdados por `parent`
+///
+/// Usado na expansão de geradores e ao montar as tarefas de hooks; valores
+/// próprios de `child` prevalecem.
+pub fn inherit(parent: &Task, child: &mut Task) {
+    let Some(parent_sources) = provenance(parent) else { return };
+    if parent_sources.propagate.is_empty() {
+        return;
+    }
+    let mut sources = provenance(child).unwrap_or_default();
+    for (tag, source) in &parent_sources.tags {
+        if !parent_sources.propagate.contains(source) {
+            continue;
+        }
+        // Geradores já copiam as próprias tags: registra a origem se não veio da tarefa
+        if add_tag(child, tag) || !sources.tags.contains_key(tag) {
+            sources.tags.insert(tag.clone(), *source);
+        }
+    }
+    for (key, source) in &parent_sources.metadata {
+        let Some(value) = parent.metadata.get(key) else { continue };
+        if parent_sources.propagate.contains(source) && !child.metadata.contains_key(key) {
+            child.metadata.insert(key.clone(), value.clone());
+            sources.metadata.insert(key.clone(), *source);
+        }
+    }
+    sources.propagate.extend(parent_sources.propagate.iter().copied());
+    sources.store(child);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults(tags: &[&str], metadata: &[(&str, &str)], propagate_to_generated: bool) -> TaskDefaults {
+        TaskDefaults {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            metadata: metadata.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+            propagate_to_generated,
+        }
+    }
+
+    #[test]
+    fn test_precedence_task_over_workflow_over_namespace() {
+        let mut task = Task::new("t".to_string(), TaskDefinition::command("true"), vec![])
+            .with_tags(vec!["Team:Payments".to_string()])
+            .with_metadata("owner".to_string(), "ana".to_string());
+        let workflow = defaults(&["team:payments", "tier:batch"], &[("owner", "wf"), ("cost-center", "123")], false);
+        let namespace = defaults(&["env:prod"], &[("owner", "ns"), ("cost-center", "999"), ("region", "sa")], false);
+        workflow.apply(&mut task, DefaultSource::Workflow);
+        namespace.apply(&mut task, DefaultSource::Namespace);
+
+        assert_eq!(task.metadata["owner"], "ana");
+        assert_eq!(task.metadata["cost-center"], "123");
+        assert_eq!(task.metadata["region"], "sa");
+        assert_eq!(task.tags, vec!["Team:Payments", "tier:batch", "env:prod"]);
+        let sources = provenance(&task).unwrap();
+        assert_eq!(sources.metadata.get("owner"), None);
+        assert_eq!(sources.metadata["cost-center"], DefaultSource::Workflow);
+        assert_eq!(sources.metadata["region"], DefaultSource::Namespace);
+        assert_eq!(sources.tags.keys().collect::<Vec<_>>(), vec!["env:prod", "tier:batch"]);
+    }
+
+    #[test]
+    fn test_only_propagated_sources_reach_children() {
+        let mut parent = Task::new("gen".to_string(), TaskDefinition::command("true"), vec![]);
+        defaults(&["team:payments"], &[("cost-center", "123")], true).apply(&mut parent, DefaultSource::Workflow);
+        defaults(&["env:prod"], &[("region", "sa")], false).apply(&mut parent, DefaultSource::Namespace);
+
+        let mut child = Task::new("child".to_string(), TaskDefinition::command("true"), vec![parent.id])
+            .with_metadata("cost-center".to_string(), "own".to_string())
+            .with_metadata(GENERATED_BY_KEY.to_string(), parent.id.to_string());
+        inherit(&parent, &mut child);
+        assert_eq!(child.tags, vec!["team:payments"]);
+        assert_eq!(child.metadata["cost-center"], "own");
+        assert!(!child.metadata.contains_key("region"));
+
+        // O namespace sem propagação não alcança a tarefa gerada nem na submissão
+        defaults(&["env:prod"], &[("region", "sa")], false).apply(&mut child, DefaultSource::Namespace);
+        assert!(!child.metadata.contains_key("region"));
+        let sources = provenance(&child).unwrap();
+        assert_eq!(sources.tags["team:payments"], DefaultSource::Workflow);
+        assert_eq!(sources.propagate, BTreeSet::from([DefaultSource::Workflow]));
+    }
+}
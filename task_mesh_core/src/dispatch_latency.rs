@@ -0,0 +1,138 @@
+//! Latência entre a tarefa ficar pronta e começar a executar
+//!
+//! Sob carga, tarefas prontas podem esperar na fila ou por um worker. O
+//! [`Scheduler`](crate::Scheduler) marca, ao retirar a tarefa da fila, quando
+//! ela ficou pronta (dependências concluídas ou fim do backoff) e quando foi
+//! retirada; o executor completa com o início no worker. Os quatro marcos
+//! ([`DispatchTimes`]) ficam na tentativa e na linha do tempo da tarefa, e
+//! as duas diferenças alimentam:
+//!
+//! - atraso do scheduler (pronta → retirada da fila):
+//!   `orchestrator_scheduler_lag_seconds`;
+//! - atraso de despacho (retirada → início no worker):
+//!   `orchestrator_dispatch_lag_seconds`;
+//!
+//! além dos percentis de [`LatencyStats`], calculados sobre as últimas
+//! [`LATENCY_WINDOW`] tentativas. Os histogramas exigem a feature `metrics`.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::features::percentile;
+use crate::types::*;
+
+/// Tentativas consideradas nos percentis
+pub const LATENCY_WINDOW: usize = 1024;
+
+/// Percentis dos atrasos nas últimas tentativas
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// Tentativas na janela
+    pub samples: usize,
+    pub scheduler_lag_p50: Option<Duration>,
+    pub scheduler_lag_p95: Option<Duration>,
+    pub dispatch_lag_p50: Option<Duration>,
+    pub dispatch_lag_p95: Option<Duration>,
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct LatencyMetrics {
+    scheduler_lag: prometheus::Histogram,
+    dispatch_lag: prometheus::Histogram,
+}
+
+/// Marcos de despacho entre o scheduler e o executor
+pub struct DispatchLatency {
+    /// Tarefas retiradas da fila: (pronta, retirada)
+    scheduled: DashMap<TaskId, (SystemTime, SystemTime)>,
+    /// (atraso do scheduler, atraso de despacho) das últimas tentativas
+    window: Mutex<VecDeque<(Duration, Duration)>>,
+    #[cfg(feature = "metrics")]
+    metrics: LatencyMetrics,
+}
+
+impl Default for DispatchLatency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DispatchLatency {
+    pub fn new() -> Self {
+        Self {
+            scheduled: DashMap::new(),
+            window: Mutex::new(VecDeque::with_capacity(LATENCY_WINDOW)),
+            #[cfg(feature = "metrics")]
+            metrics: LatencyMetrics {
+                scheduler_lag: prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+                    "orchestrator_scheduler_lag_seconds",
+                    "Tempo entre a tarefa ficar pronta e ser retirada da fila",
+                )).expect("nome de métrica válido"),
+                dispatch_lag: prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+                    "orchestrator_dispatch_lag_seconds",
+                    "Tempo entre a retirada da fila e o início no worker",
+                )).expect("nome de métrica válido"),
+            },
+        }
+    }
+
+    /// Registra os histogramas de latência em um registry Prometheus
+    #[cfg(feature = "metrics")]
+    pub fn register_metrics(&self, registry: &prometheus::Registry) -> TaskMeshResult<()> {
+        registry
+            .register(Box::new(self.metrics.scheduler_lag.clone()))
+            .and_then(|_| registry.register(Box::new(self.metrics.dispatch_lag.clone())))
+            .map_err(|e| TaskMeshError::Internal(format!("Erro ao registrar métricas de despacho: {}", e)))
+    }
+
+    /// Tarefa retirada da fila em `scheduled_at`, pronta desde `ready_at`
+    pub fn mark_scheduled(&self, task_id: TaskId, ready_at: SystemTime, scheduled_at: SystemTime) {
+        self.scheduled.insert(task_id, (ready_at.min(scheduled_at), scheduled_at));
+    }
+
+    /// Completa os marcos com o início no worker e registra os atrasos
+    ///
+    /// Retorna `None` para tarefas que não passaram pela fila (execução
+    /// direta no executor).
+    pub fn take(&self, task: &Task, started_at: SystemTime) -> Option<DispatchTimes> {
+        let (_, (ready_at, scheduled_at)) = self.scheduled.remove(&task.id)?;
+        let times = DispatchTimes {
+            submitted_at: task.created_at.min(ready_at),
+            ready_at,
+            scheduled_at,
+            started_at: started_at.max(scheduled_at),
+        };
+        let (scheduler_lag, dispatch_lag) = (times.scheduler_lag(), times.dispatch_lag());
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics.scheduler_lag.observe(scheduler_lag.as_secs_f64());
+            self.metrics.dispatch_lag.observe(dispatch_lag.as_secs_f64());
+        }
+        let mut window = self.window.lock().unwrap();
+        if window.len() == LATENCY_WINDOW {
+            window.pop_front();
+        }
+        window.push_back((scheduler_lag, dispatch_lag));
+        Some(times)
+    }
+
+    /// Percentis da janela atual
+    pub fn stats(&self) -> LatencyStats {
+        let window = self.window.lock().unwrap();
+        let mut scheduler_lags: Vec<Duration> = window.iter().map(|(lag, _)| *lag).collect();
+        let mut dispatch_lags: Vec<Duration> = window.iter().map(|(_, lag)| *lag).collect();
+        scheduler_lags.sort();
+        dispatch_lags.sort();
+        LatencyStats {
+            samples: window.len(),
+            scheduler_lag_p50: percentile(&scheduler_lags, 0.5),
+            scheduler_lag_p95: percentile(&scheduler_lags, 0.95),
+            dispatch_lag_p50: percentile(&dispatch_lags, 0.5),
+            dispatch_lag_p95: percentile(&dispatch_lags, 0.95),
+        }
+    }
+}
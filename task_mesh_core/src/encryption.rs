@@ -0,0 +1,310 @@
+//! Cifragem das tarefas em repouso, por namespace
+//!
+//! Tenants que compartilham o banco podem exigir que definições (scripts,
+//! corpos HTTP) e metadados das suas tarefas não fiquem em claro. Cada
+//! namespace cifrado tem uma chave de dados AES-256-GCM, identificada por um
+//! `key_id` e obtida de um [`KeyProvider`] (por padrão, das chaves da
+//! [`EncryptionConfig`]). O SQLite grava a definição e os metadados
+//! cifrados na coluna `sealed`, com o `key_id` ao lado; nome, tags, alias,
+//! grupo e status continuam em claro, e listagens e busca textual usam só
+//! essas colunas.
+//!
+//! Um blob cifrado tem o formato:
+//!
+//! ```text
+//! | versão (u8) | nonce (12 bytes) | JSON cifrado + tag (16 bytes) |
+//! ```
+//!
+//! O ID da tarefa entra como dado associado: um blob copiado para outra
+//! linha não decifra. A troca de chave de um namespace é feita por
+//! [`crate::StateStore::rewrap_namespace`], que regrava as tarefas em lotes.
+//!
+//! Tentativas (status e resultado, com a saída do processo) e manifestos de
+//! execução (argv) das tarefas cifradas também são cifrados, com a mesma
+//! chave, via [`PayloadCipher::seal_record`]; as colunas em claro guardam
+//! versões sem saída e sem argv.
+//!
+//! Checkpoints e eventos não são cifrados; tenants com essa exigência devem
+//! manter os checkpoints fora do banco compartilhado.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::hooks::HookSpec;
+use crate::sidecar::SidecarSpec;
+use crate::types::*;
+
+/// Versão do formato dos blobs cifrados
+const SEALED_VERSION: u8 = 1;
+
+/// Tamanho das chaves de dados (AES-256)
+pub const DATA_KEY_LEN: usize = 32;
+
+/// Prefixo de chaves lidas de variáveis de ambiente na configuração
+const ENV_PREFIX: &str = "env:";
+
+/// Configuração da cifragem por namespace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// Chaves de dados por `key_id`: 64 dígitos hexadecimais ou `env:VARIAVEL`
+    #[serde(default)]
+    pub keys: HashMap<String, String>,
+    /// `key_id` atual de cada namespace cifrado
+    #[serde(default)]
+    pub namespaces: HashMap<String, String>,
+}
+
+/// Fonte das chaves de dados (ex.: um KMS)
+pub trait KeyProvider: Send + Sync {
+    /// Material da chave `key_id` ([`DATA_KEY_LEN`] bytes)
+    fn data_key(&self, key_id: &str) -> TaskMeshResult<Vec<u8>>;
+}
+
+/// Chaves fixas, da configuração
+pub struct StaticKeyProvider {
+    keys: HashMap<String, Vec<u8>>,
+}
+
+impl StaticKeyProvider {
+    pub fn new(keys: HashMap<String, Vec<u8>>) -> Self {
+        Self { keys }
+    }
+
+    /// Decodifica as chaves da configuração
+    pub fn from_config(config: &EncryptionConfig) -> TaskMeshResult<Self> {
+        let keys = config
+            .keys
+            .iter()
+            .map(|(key_id, value)| Ok((key_id.clone(), decode_key(key_id, value)?)))
+            .collect::<TaskMeshResult<_>>()?;
+        Ok(Self { keys })
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn data_key(&self, key_id: &str) -> TaskMeshResult<Vec<u8>> {
+        self.keys
+            .get(key_id)
+            .cloned()
+            .ok_or_else(|| TaskMeshError::Configuration(format!("Chave de dados '{}' desconhecida", key_id)))
+    }
+}
+
+/// Lê uma chave em hexadecimal, direto ou de uma variável de ambiente
+fn decode_key(key_id: &str, value: &str) -> TaskMeshResult<Vec<u8>> {
+    let hex = match value.strip_prefix(ENV_PREFIX) {
+        Some(var) => std::env::var(var).map_err(|_| {
+            TaskMeshError::Configuration(format!("Chave '{}': variável de ambiente {} ausente", key_id, var))
+        })?,
+        None => value.to_string(),
+    };
+    let hex = hex.trim();
+    let invalid = || {
+        TaskMeshError::Configuration(format!(
+            "Chave '{}' inválida: esperados {} dígitos hexadecimais",
+            key_id,
+            DATA_KEY_LEN * 2
+        ))
+    };
+    if hex.len() != DATA_KEY_LEN * 2 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Campos cifrados de uma tarefa
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SealedFields {
+    pub definition: TaskDefinition,
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub sidecars: Vec<SidecarSpec>,
+    #[serde(default)]
+    pub hooks: Vec<HookSpec>,
+}
+
+/// Cifra e decifra os campos sensíveis das tarefas
+pub struct PayloadCipher {
+    provider: Arc<dyn KeyProvider>,
+    /// `key_id` atual de cada namespace
+    namespaces: RwLock<HashMap<String, String>>,
+    keys: Mutex<HashMap<String, Arc<LessSafeKey>>>,
+    rng: SystemRandom,
+}
+
+impl PayloadCipher {
+    /// Cifra os namespaces de `namespaces` (namespace → `key_id`)
+    ///
+    /// Falha se alguma chave não puder ser obtida do `provider`.
+    pub fn new(provider: Arc<dyn KeyProvider>, namespaces: HashMap<String, String>) -> TaskMeshResult<Self> {
+        let cipher = Self {
+            provider,
+            namespaces: RwLock::new(HashMap::new()),
+            keys: Mutex::new(HashMap::new()),
+            rng: SystemRandom::new(),
+        };
+        for (namespace, key_id) in namespaces {
+            cipher.set_namespace_key(&namespace, &key_id)?;
+        }
+        Ok(cipher)
+    }
+
+    /// Cifragem com as chaves da configuração
+    pub fn from_config(config: &EncryptionConfig) -> TaskMeshResult<Self> {
+        let provider = Arc::new(StaticKeyProvider::from_config(config)?);
+        Self::new(provider, config.namespaces.clone())
+    }
+
+    /// `key_id` atual do namespace (`None` se não é cifrado)
+    pub fn key_id_for(&self, namespace: &str) -> Option<String> {
+        self.namespaces.read().unwrap().get(namespace).cloned()
+    }
+
+    /// Passa a cifrar as gravações do namespace com `key_id`
+    pub fn set_namespace_key(&self, namespace: &str, key_id: &str) -> TaskMeshResult<()> {
+        self.key(key_id)?;
+        self.namespaces.write().unwrap().insert(namespace.to_string(), key_id.to_string());
+        Ok(())
+    }
+
+    /// Chave pronta para uso, obtida do provider na primeira vez
+    fn key(&self, key_id: &str) -> TaskMeshResult<Arc<LessSafeKey>> {
+        if let Some(key) = self.keys.lock().unwrap().get(key_id) {
+            return Ok(key.clone());
+        }
+        let material = self.provider.data_key(key_id)?;
+        let unbound = UnboundKey::new(&AES_256_GCM, &material).map_err(|_| {
+            TaskMeshError::Configuration(format!("Chave '{}' deve ter {} bytes", key_id, DATA_KEY_LEN))
+        })?;
+        let key = Arc::new(LessSafeKey::new(unbound));
+        self.keys.lock().unwrap().insert(key_id.to_string(), key.clone());
+        Ok(key)
+    }
+
+    /// Cifra a definição, os metadados, os sidecars e os hooks da tarefa com `key_id`
+    pub fn seal(&self, key_id: &str, task: &Task) -> TaskMeshResult<Vec<u8>> {
+        let fields = SealedFields {
+            definition: task.definition.clone(),
+            metadata: task.metadata.clone(),
+            sidecars: task.sidecars.clone(),
+            hooks: task.hooks.clone(),
+        };
+        self.seal_bytes(key_id, task.id.as_uuid().as_bytes(), serde_json::to_vec(&fields)?)
+            .map_err(|e| describe(e, || format!("Falha ao cifrar a tarefa {}", task.id)))
+    }
+
+    /// Decifra os campos de uma tarefa gravados por [`Self::seal`]
+    pub fn open(&self, key_id: &str, task_id: &TaskId, sealed: &[u8]) -> TaskMeshResult<SealedFields> {
+        let plaintext = self.open_bytes(key_id, task_id.as_uuid().as_bytes(), sealed)
+            .map_err(|e| describe(e, || format!("Falha ao decifrar a tarefa {} (chave '{}')", task_id, key_id)))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Cifra um registro derivado da tarefa (tentativa, manifesto...) com `key_id`
+    ///
+    /// `label` identifica o registro (ex.: `attempt:2`) e entra no dado
+    /// associado junto com o ID da tarefa: o blob não decifra em outra linha
+    /// nem como outro tipo de registro.
+    pub fn seal_record<T: Serialize>(&self, key_id: &str, task_id: &TaskId, label: &str, value: &T) -> TaskMeshResult<Vec<u8>> {
+        self.seal_bytes(key_id, &record_aad(task_id, label), serde_json::to_vec(value)?)
+            .map_err(|e| describe(e, || format!("Falha ao cifrar {} da tarefa {}", label, task_id)))
+    }
+
+    /// Decifra um registro gravado por [`Self::seal_record`]
+    pub fn open_record<T: DeserializeOwned>(&self, key_id: &str, task_id: &TaskId, label: &str, sealed: &[u8]) -> TaskMeshResult<T> {
+        let plaintext = self.open_bytes(key_id, &record_aad(task_id, label), sealed)
+            .map_err(|e| describe(e, || format!("Falha ao decifrar {} da tarefa {} (chave '{}')", label, task_id, key_id)))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn seal_bytes(&self, key_id: &str, aad: &[u8], mut in_out: Vec<u8>) -> TaskMeshResult<Vec<u8>> {
+        let key = self.key(key_id)?;
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| TaskMeshError::Internal("Falha ao gerar nonce".to_string()))?;
+
+        key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad), &mut in_out)
+            .map_err(|_| TaskMeshError::Internal("Falha ao cifrar".to_string()))?;
+
+        let mut sealed = Vec::with_capacity(1 + NONCE_LEN + in_out.len());
+        sealed.push(SEALED_VERSION);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&in_out);
+        Ok(sealed)
+    }
+
+    fn open_bytes(&self, key_id: &str, aad: &[u8], sealed: &[u8]) -> TaskMeshResult<Vec<u8>> {
+        let corrupted = || TaskMeshError::Internal("Falha ao decifrar".to_string());
+        let (version, rest) = sealed.split_first().ok_or_else(corrupted)?;
+        if *version != SEALED_VERSION {
+            return Err(TaskMeshError::UnsupportedFormatVersion { found: *version as u32, supported: SEALED_VERSION as u32 });
+        }
+        if rest.len() < NONCE_LEN {
+            return Err(corrupted());
+        }
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| corrupted())?;
+
+        let key = self.key(key_id)?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext_len = key
+            .open_in_place(nonce, Aad::from(aad), &mut in_out)
+            .map_err(|_| corrupted())?
+            .len();
+        in_out.truncate(plaintext_len);
+        Ok(in_out)
+    }
+}
+
+/// Troca a mensagem genérica de falha de [`PayloadCipher::seal_bytes`] e
+/// [`PayloadCipher::open_bytes`] por uma que identifica o dado
+fn describe(error: TaskMeshError, message: impl FnOnce() -> String) -> TaskMeshError {
+    match error {
+        TaskMeshError::Internal(_) => TaskMeshError::Internal(message()),
+        error => error,
+    }
+}
+
+/// Dado associado de um registro: ID da tarefa seguido do rótulo
+fn record_aad(task_id: &TaskId, label: &str) -> Vec<u8> {
+    let mut aad = task_id.as_uuid().as_bytes().to_vec();
+    aad.extend_from_slice(label.as_bytes());
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_is_bound_to_key_and_task() {
+        let key = "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
+        let config = EncryptionConfig {
+            keys: HashMap::from([("k1".to_string(), key.to_string()), ("k2".to_string(), key.replace('0', "f"))]),
+            namespaces: HashMap::from([("tenant".to_string(), "k1".to_string())]),
+        };
+        let cipher = PayloadCipher::from_config(&config).unwrap();
+        let task = Task::new("deploy".to_string(), TaskDefinition::command("echo segredo"), vec![]);
+
+        let sealed = cipher.seal("k1", &task).unwrap();
+        assert!(!sealed.windows(7).any(|w| w == b"segredo"));
+        let fields = cipher.open("k1", &task.id, &sealed).unwrap();
+        assert_eq!(serde_json::to_value(&fields.definition).unwrap(), serde_json::to_value(&task.definition).unwrap());
+
+        assert!(cipher.open("k2", &task.id, &sealed).is_err());
+        assert!(cipher.open("k1", &TaskId::new_v4(), &sealed).is_err());
+
+        let short = EncryptionConfig { keys: HashMap::from([("k".to_string(), "abcd".to_string())]), ..Default::default() };
+        assert!(matches!(PayloadCipher::from_config(&short), Err(TaskMeshError::Configuration(_))));
+        let unknown = EncryptionConfig { namespaces: HashMap::from([("t".to_string(), "nada".to_string())]), ..Default::default() };
+        assert!(matches!(PayloadCipher::from_config(&unknown), Err(TaskMeshError::Configuration(_))));
+    }
+}
@@ -0,0 +1,393 @@
+//! Barramento de eventos
+//!
+//! O [`EventBus`] grava cada evento no `StateStore` e o entrega aos
+//! assinantes. Cada assinante tem uma fila limitada e um filtro
+//! ([`EventQuery`]) avaliado na publicação, de modo que eventos que não
+//! interessam nem chegam à fila. Um assinante lento não bloqueia a
+//! publicação: eventos que não cabem na sua fila são contados como atraso
+//! (`lagged`) em vez de sumirem em silêncio.
+//!
+//! Assinaturas com replay ([`EventBus::subscribe_with_replay`]) leem primeiro
+//! o histórico do store a partir de uma sequência e passam para a entrega ao
+//! vivo sem lacunas nem duplicatas; eventos perdidos por atraso são
+//! recuperados do store.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Capacidade padrão da fila de cada assinante
+pub const DEFAULT_SUBSCRIBER_CAPACITY: usize = 1024;
+
+/// Eventos lidos do store por consulta durante o replay
+const REPLAY_PAGE: usize = 500;
+
+/// Evento entregue pelo barramento
+#[derive(Debug, Clone)]
+pub struct BusEvent {
+    /// Sequência no store (`None` em backends sem sequência)
+    pub seq: Option<u64>,
+    pub event: SystemEvent,
+}
+
+/// Contadores de um assinante
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriberStats {
+    pub id: u64,
+    /// Eventos colocados na fila do assinante
+    pub delivered: u64,
+    /// Eventos descartados por fila cheia
+    pub lagged: u64,
+}
+
+/// Estado do barramento
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventBusStats {
+    pub published: u64,
+    pub subscribers: usize,
+    pub lagged_events_total: u64,
+    pub per_subscriber: Vec<SubscriberStats>,
+}
+
+#[derive(Debug, Default)]
+struct SubscriberCounters {
+    delivered: AtomicU64,
+    lagged: AtomicU64,
+}
+
+#[derive(Debug)]
+struct SubscriberSlot {
+    id: u64,
+    filter: EventQuery,
+    tx: mpsc::Sender<BusEvent>,
+    counters: Arc<SubscriberCounters>,
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct BusMetrics {
+    subscribers: prometheus::IntGauge,
+    lagged: prometheus::IntCounter,
+}
+
+/// Barramento de eventos sobre o `StateStore`
+pub struct EventBus {
+    store: Arc<dyn StateStore>,
+    capacity: usize,
+    /// Serializa gravação e entrega: a ordem ao vivo é a ordem de sequência
+    publish_lock: Mutex<()>,
+    subscribers: StdMutex<Vec<SubscriberSlot>>,
+    next_subscriber: AtomicU64,
+    published: AtomicU64,
+    lagged_total: AtomicU64,
+    #[cfg(feature = "metrics")]
+    metrics: BusMetrics,
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("capacity", &self.capacity)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl EventBus {
+    pub fn new(store: Arc<dyn StateStore>) -> Self {
+        Self::with_capacity(store, DEFAULT_SUBSCRIBER_CAPACITY)
+    }
+
+    /// Cria o barramento com filas de `capacity` eventos por assinante
+    pub fn with_capacity(store: Arc<dyn StateStore>, capacity: usize) -> Self {
+        Self {
+            store,
+            capacity: capacity.max(1),
+            publish_lock: Mutex::new(()),
+            subscribers: StdMutex::new(Vec::new()),
+            next_subscriber: AtomicU64::new(1),
+            published: AtomicU64::new(0),
+            lagged_total: AtomicU64::new(0),
+            #[cfg(feature = "metrics")]
+            metrics: BusMetrics {
+                subscribers: prometheus::IntGauge::new(
+                    "taskmesh_event_bus_subscribers",
+                    "Assinantes ativos do barramento de eventos",
+                ).expect("nome de métrica válido"),
+                lagged: prometheus::IntCounter::new(
+                    "taskmesh_event_bus_lagged_events_total",
+                    "Eventos descartados por filas de assinantes cheias",
+                ).expect("nome de métrica válido"),
+            },
+        }
+    }
+
+    /// Registra os gauges do barramento em um registry Prometheus
+    #[cfg(feature = "metrics")]
+    pub fn register_metrics(&self, registry: &prometheus::Registry) -> TaskMeshResult<()> {
+        registry
+            .register(Box::new(self.metrics.subscribers.clone()))
+            .and_then(|_| registry.register(Box::new(self.metrics.lagged.clone())))
+            .map_err(|e| TaskMeshError::Internal(format!("Erro ao registrar métricas do barramento: {}", e)))
+    }
+
+    /// Grava o evento e o entrega aos assinantes cujo filtro ele atende
+    pub async fn publish(&self, event: SystemEvent) -> TaskMeshResult<Option<u64>> {
+        let _order = self.publish_lock.lock().await;
+        self.append_and_deliver(event).await
+    }
+
+    /// Publica um lote em ordem, tomando a ordem de publicação uma única vez
+    ///
+    /// Usado pelo buffer write-behind do executor.
+    pub async fn publish_batch(&self, events: Vec<SystemEvent>) -> TaskMeshResult<()> {
+        let _order = self.publish_lock.lock().await;
+        for event in events {
+            self.append_and_deliver(event).await?;
+        }
+        Ok(())
+    }
+
+    /// Grava e entrega um evento; exige `publish_lock`
+    async fn append_and_deliver(&self, event: SystemEvent) -> TaskMeshResult<Option<u64>> {
+        let seq = self.store.append_event(&event).await?;
+        self.published.fetch_add(1, Ordering::SeqCst);
+
+        let bus_event = BusEvent { seq, event };
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|slot| {
+            if !slot.filter.matches(&bus_event.event) {
+                return !slot.tx.is_closed();
+            }
+            match slot.tx.try_send(bus_event.clone()) {
+                Ok(()) => {
+                    slot.counters.delivered.fetch_add(1, Ordering::SeqCst);
+                    true
+                }
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    slot.counters.lagged.fetch_add(1, Ordering::SeqCst);
+                    self.lagged_total.fetch_add(1, Ordering::SeqCst);
+                    #[cfg(feature = "metrics")]
+                    self.metrics.lagged.inc();
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            }
+        });
+        #[cfg(feature = "metrics")]
+        self.metrics.subscribers.set(subscribers.len() as i64);
+        Ok(seq)
+    }
+
+    /// Assina os eventos publicados a partir de agora
+    pub fn subscribe(&self, filter: EventQuery) -> Subscription {
+        let (tx, rx) = mpsc::channel(self.capacity);
+        let counters = Arc::new(SubscriberCounters::default());
+        let id = self.next_subscriber.fetch_add(1, Ordering::SeqCst);
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.push(SubscriberSlot { id, filter, tx, counters: counters.clone() });
+        #[cfg(feature = "metrics")]
+        self.metrics.subscribers.set(subscribers.len() as i64);
+        Subscription { id, rx, counters, replay: None }
+    }
+
+    /// Assina a partir da sequência `from_seq` (inclusiva): histórico do
+    /// store primeiro, depois eventos ao vivo
+    ///
+    /// Retorna `Configuration` se o backend não numera eventos.
+    pub async fn subscribe_with_replay(&self, from_seq: u64, filter: EventQuery) -> TaskMeshResult<Subscription> {
+        // Assina antes de ler o histórico: o que for publicado durante a
+        // leitura fica na fila e é deduplicado pela sequência
+        let mut subscription = self.subscribe(filter.clone());
+        let mut replay = Replay {
+            store: self.store.clone(),
+            filter,
+            last_seq: from_seq.saturating_sub(1),
+            backlog: VecDeque::new(),
+            catching_up: true,
+            lag_seen: 0,
+        };
+        replay.fetch_page().await?;
+        subscription.replay = Some(replay);
+        Ok(subscription)
+    }
+
+    /// Contadores do barramento e de cada assinante ativo
+    pub fn stats(&self) -> EventBusStats {
+        let subscribers = self.subscribers.lock().unwrap();
+        let active: Vec<&SubscriberSlot> = subscribers.iter().filter(|slot| !slot.tx.is_closed()).collect();
+        EventBusStats {
+            published: self.published.load(Ordering::SeqCst),
+            subscribers: active.len(),
+            lagged_events_total: self.lagged_total.load(Ordering::SeqCst),
+            per_subscriber: active
+                .iter()
+                .map(|slot| SubscriberStats {
+                    id: slot.id,
+                    delivered: slot.counters.delivered.load(Ordering::SeqCst),
+                    lagged: slot.counters.lagged.load(Ordering::SeqCst),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Estado do replay de uma assinatura
+struct Replay {
+    store: Arc<dyn StateStore>,
+    filter: EventQuery,
+    /// Última sequência entregue (ou pulada pelo filtro)
+    last_seq: u64,
+    backlog: VecDeque<BusEvent>,
+    /// Lendo do store; a fila ao vivo só é consumida ao alcançar o fim
+    catching_up: bool,
+    /// Atraso já compensado com leitura do store
+    lag_seen: u64,
+}
+
+impl Replay {
+    /// Lê a próxima página do store; `false` quando o histórico acabou
+    async fn fetch_page(&mut self) -> TaskMeshResult<bool> {
+        let page = self.store.events_after(self.last_seq, REPLAY_PAGE).await?;
+        if page.is_empty() {
+            return Ok(false);
+        }
+        for (seq, event) in page {
+            self.last_seq = seq;
+            if self.filter.matches(&event) {
+                self.backlog.push_back(BusEvent { seq: Some(seq), event });
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Assinatura do barramento; cancelada ao ser descartada
+pub struct Subscription {
+    id: u64,
+    rx: mpsc::Receiver<BusEvent>,
+    counters: Arc<SubscriberCounters>,
+    replay: Option<Replay>,
+}
+
+impl Subscription {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Eventos descartados por fila cheia
+    ///
+    /// Em assinaturas com replay esses eventos são relidos do store.
+    pub fn lagged(&self) -> u64 {
+        self.counters.lagged.load(Ordering::SeqCst)
+    }
+
+    /// Próximo evento; `None` quando o barramento foi descartado
+    pub async fn recv(&mut self) -> TaskMeshResult<Option<BusEvent>> {
+        loop {
+            if let Some(replay) = &mut self.replay {
+                let lagged = self.counters.lagged.load(Ordering::SeqCst);
+                if lagged > replay.lag_seen {
+                    replay.lag_seen = lagged;
+                    replay.catching_up = true;
+                }
+                if replay.catching_up {
+                    if let Some(event) = replay.backlog.pop_front() {
+                        return Ok(Some(event));
+                    }
+                    if replay.fetch_page().await? {
+                        continue;
+                    }
+                    replay.catching_up = false;
+                }
+            }
+
+            let Some(event) = self.rx.recv().await else {
+                return Ok(None);
+            };
+            if let (Some(replay), Some(seq)) = (&mut self.replay, event.seq) {
+                // Atraso ocorrido antes deste evento: relê do store a partir da
+                // última sequência entregue, que inclui o descartado
+                if self.counters.lagged.load(Ordering::SeqCst) > replay.lag_seen || seq <= replay.last_seq {
+                    continue;
+                }
+                replay.last_seq = seq;
+            }
+            return Ok(Some(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_store::MemoryStateStore;
+
+    fn numbered(n: u64) -> SystemEvent {
+        SystemEvent::new(EventType::Custom("tick".to_string()), None, serde_json::json!({ "n": n }))
+    }
+
+    async fn next_n(subscription: &mut Subscription) -> u64 {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(2), subscription.recv())
+            .await
+            .expect("evento não chegou")
+            .unwrap()
+            .unwrap();
+        event.event.data["n"].as_u64().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replay_then_live_delivers_each_event_once_in_order() {
+        let store: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new().await.unwrap());
+        let bus = Arc::new(EventBus::new(store));
+        for n in 1..=5 {
+            bus.publish(numbered(n)).await.unwrap();
+        }
+
+        let mut subscription = bus.subscribe_with_replay(2, EventQuery::default()).await.unwrap();
+        // Publicado durante a passagem: já está no store e na fila ao vivo
+        bus.publish(numbered(6)).await.unwrap();
+        assert_eq!(next_n(&mut subscription).await, 2);
+        bus.publish(numbered(7)).await.unwrap();
+
+        let mut received = vec![2];
+        for _ in 0..5 {
+            received.push(next_n(&mut subscription).await);
+        }
+        bus.publish(numbered(8)).await.unwrap();
+        received.push(next_n(&mut subscription).await);
+        assert_eq!(received, (2..=8).collect::<Vec<_>>());
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(50), subscription.recv()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_lag_is_counted_and_recovered_on_replay() {
+        let store: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new().await.unwrap());
+        let bus = EventBus::with_capacity(store, 2);
+        let mut live = bus.subscribe(EventQuery::default());
+        let mut replayed = bus.subscribe_with_replay(1, EventQuery::default()).await.unwrap();
+        let filtered = bus.subscribe(EventQuery { event_types: vec![EventType::TaskFailed], ..EventQuery::default() });
+
+        for n in 1..=5 {
+            bus.publish(numbered(n)).await.unwrap();
+        }
+
+        let stats = bus.stats();
+        assert_eq!((stats.published, stats.subscribers, stats.lagged_events_total), (5, 3, 6));
+        assert_eq!(stats.per_subscriber.iter().find(|s| s.id == filtered.id()).unwrap().delivered, 0);
+        assert_eq!(live.lagged(), 3);
+
+        // O assinante simples perde 3..=5; o com replay os relê do store
+        assert_eq!((next_n(&mut live).await, next_n(&mut live).await), (1, 2));
+        let mut received = Vec::new();
+        for _ in 0..5 {
+            received.push(next_n(&mut replayed).await);
+        }
+        assert_eq!(received, vec![1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Execução inline de tarefas triviais
+//!
+//! Por padrão, [`TaskExecutor::execute_task`](crate::TaskExecutor::execute_task)
+//! envia a tarefa ao loop de comandos e retorna; a execução acontece depois,
+//! em outra task do runtime. Para funções e programas de microssegundos, a
+//! ida e volta pelo loop (e a criação do scratch) custa mais que a própria
+//! tarefa. Com [`ExecutionHint::Inline`] no metadado [`EXECUTION_HINT_KEY`]
+//! (ver [`Task::with_execution_hint`]), o executor roda a tarefa no próprio
+//! `execute_task`, que só retorna quando ela termina:
+//!
+//! - o grupo de concorrência, o semáforo e o worker são obtidos como no
+//!   caminho normal, e a tarefa pode ser cancelada enquanto roda;
+//! - status, eventos, tentativas e métricas são os mesmos, gravados pelo
+//!   buffer write-behind quando habilitado;
+//! - não há diretório scratch nem arquivo de progresso: a tarefa usa
+//!   `default_working_dir`.
+//!
+//! Só `RustFunction` e `Exec` sem sidecars e sem hooks (da tarefa ou do
+//! executor) são elegíveis; as demais seguem pelo loop de comandos mesmo
+//! com a dica.
+
+use serde::{Deserialize, Serialize};
+
+use crate::hooks::HookSpec;
+use crate::types::*;
+use crate::validation::Violation;
+
+/// Metadado com a dica de execução (`default` ou `inline`)
+pub const EXECUTION_HINT_KEY: &str = "execution_hint";
+
+/// Onde o executor roda a tarefa
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionHint {
+    /// Loop de comandos do executor
+    #[default]
+    Default,
+    /// No próprio `execute_task`, quando a tarefa é elegível
+    Inline,
+}
+
+impl ExecutionHint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionHint::Default => "default",
+            ExecutionHint::Inline => "inline",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "default" => Some(ExecutionHint::Default),
+            "inline" => Some(ExecutionHint::Inline),
+            _ => None,
+        }
+    }
+}
+
+impl Task {
+    /// Define onde o executor roda a tarefa
+    pub fn with_execution_hint(mut self, hint: ExecutionHint) -> Self {
+        self.metadata.insert(EXECUTION_HINT_KEY.to_string(), hint.as_str().to_string());
+        self
+    }
+
+    /// Dica de execução da tarefa (valores desconhecidos valem `Default`)
+    pub fn execution_hint(&self) -> ExecutionHint {
+        self.metadata
+            .get(EXECUTION_HINT_KEY)
+            .and_then(|value| ExecutionHint::parse(value))
+            .unwrap_or_default()
+    }
+}
+
+/// Motivo que impede a execução inline da própria tarefa, se houver
+fn ineligibility(task: &Task) -> Option<&'static str> {
+    if !matches!(task.definition, TaskDefinition::RustFunction { .. } | TaskDefinition::Exec { .. }) {
+        return Some("apenas tarefas RustFunction e Exec executam inline");
+    }
+    if !task.sidecars.is_empty() {
+        return Some("tarefas com sidecars não executam inline");
+    }
+    if !task.hooks.is_empty() {
+        return Some("tarefas com hooks não executam inline");
+    }
+    None
+}
+
+/// Indica se o executor deve rodar a tarefa inline
+///
+/// `executor_hooks` são os hooks configurados para todas as tarefas.
+pub fn runs_inline(task: &Task, executor_hooks: &[HookSpec]) -> bool {
+    task.execution_hint() == ExecutionHint::Inline && executor_hooks.is_empty() && ineligibility(task).is_none()
+}
+
+/// Verifica o metadado de dica de execução da tarefa
+///
+/// Os campos das violações recebem `prefix` (ex.: `tasks[2].`).
+pub fn validate(task: &Task, prefix: &str) -> Vec<Violation> {
+    let field = format!("{}metadata.{}", prefix, EXECUTION_HINT_KEY);
+    let Some(value) = task.metadata.get(EXECUTION_HINT_KEY) else { return Vec::new() };
+    let message = match ExecutionHint::parse(value) {
+        None => format!("'{}' inválido (use default ou inline)", value),
+        Some(ExecutionHint::Inline) => match ineligibility(task) {
+            Some(reason) => reason.to_string(),
+            None => return Vec::new(),
+        },
+        Some(ExecutionHint::Default) => return Vec::new(),
+    };
+    vec![Violation { field, message }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::HookPhase;
+    use crate::sidecar::SidecarSpec;
+
+    fn function_task() -> Task {
+        let definition = TaskDefinition::RustFunction { function_name: "noop".to_string(), args: serde_json::Value::Null };
+        Task::new("noop".to_string(), definition, vec![])
+    }
+
+    #[test]
+    fn test_only_plain_functions_and_programs_run_inline() {
+        let inline = function_task().with_execution_hint(ExecutionHint::Inline);
+        assert_eq!(inline.execution_hint(), ExecutionHint::Inline);
+        assert!(runs_inline(&inline, &[]));
+        assert!(validate(&inline, "").is_empty());
+        assert!(!runs_inline(&function_task(), &[]));
+
+        let hook = HookSpec::new(HookPhase::Before, TaskDefinition::command("true"));
+        assert!(!runs_inline(&inline, &[hook.clone()]));
+        let with_hook = inline.clone().with_hook(hook);
+        assert!(!runs_inline(&with_hook, &[]));
+        assert_eq!(validate(&with_hook, "tasks[1].")[0].field, "tasks[1].metadata.execution_hint");
+        let with_sidecar = inline.with_sidecar(SidecarSpec::new("db", "sleep 60"));
+        assert!(!runs_inline(&with_sidecar, &[]));
+
+        let command = Task::new("sh".to_string(), TaskDefinition::command("true"), vec![])
+            .with_execution_hint(ExecutionHint::Inline);
+        assert!(!runs_inline(&command, &[]));
+        assert!(validate(&command, "")[0].message.contains("RustFunction"));
+
+        let mut unknown = function_task();
+        unknown.metadata.insert(EXECUTION_HINT_KEY.to_string(), "fast".to_string());
+        assert_eq!(unknown.execution_hint(), ExecutionHint::Default);
+        assert!(validate(&unknown, "")[0].message.contains("'fast'"));
+    }
+}
@@ -1,21 +1,77 @@
 //! Executor assíncrono de tarefas com suporte a Tokio e Rayon
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, SystemTime, Instant};
 use tokio::process::Command;
-use tokio::sync::{RwLock, mpsc, Semaphore};
+use tokio::sync::{Mutex, RwLock, mpsc, Semaphore, SemaphorePermit};
 use tokio::time::timeout;
+use dashmap::DashMap;
 use futures::future::try_join_all;
 use rayon::prelude::*;
-use tracing::{debug, error, info, warn, instrument};
+use tracing::{debug, error, info, warn, Instrument};
 
 use crate::types::*;
 use crate::state_store::StateStore;
 use crate::error_handler::ErrorHandler;
+use crate::process_metrics::{self, ProcessSampler};
+use crate::progress::{ProgressFile, ProgressReporter, PROGRESS_ENV};
+use crate::functions::{FnContext, FunctionRegistry, DEFAULT_CANCEL_GRACE_PERIOD};
+use crate::python_pool::{PythonPool, PythonPoolConfig};
+use crate::manifest::{self, ExecutionManifest, ToolVersions};
+use crate::log_store::{LogStore, LogStoreConfig, LogStream};
+use crate::process_controls;
+use crate::sidecar::Sidecars;
+use crate::hooks::{self, HookPhase, HookRun, HookSpec};
+use crate::execution_hint;
+use crate::defaults;
+use crate::status_cache::StatusCache;
+#[cfg(feature = "cgroups")]
+use crate::cgroups::{CgroupConfig, CgroupManager, TaskCgroup};
+use crate::scratch::{ScratchConfig, ScratchSpace, SCRATCH_ENV, WORKING_DIR_KEY};
+use crate::checkpoint::LoadSignal;
+use crate::concurrency_group::{ConcurrencyGroups, GroupRelease};
+use crate::dispatch_latency::DispatchLatency;
+use crate::dead_letter::{DeadLetter, DeadLetterQueue};
+use crate::retry_rules::{PolicyDecision, RetryDirective, RetryRules};
+use crate::background::BackgroundTasks;
+use crate::event_bus::EventBus;
+use crate::logging;
+use crate::scheduler::{self, Scheduler};
 use crate::TaskMeshResult;
 
+/// Worker registrado no status `Running` de tarefas ainda na fila do executor
+pub const PENDING_WORKER_ID: &str = "pending";
+
+/// Metadado com o comando que retoma a tarefa a partir do seu checkpoint
+///
+/// `{checkpoint_id}` é substituído pelo checkpoint da tarefa.
+pub const RESUME_COMMAND_KEY: &str = "resume_command";
+
+/// Variável de ambiente com o checkpoint de tarefas retomáveis
+pub const CHECKPOINT_ENV: &str = "TASKMESH_CHECKPOINT_ID";
+
+/// Intervalo de leitura do arquivo de progresso
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Checkpoint de uma tarefa retomável (estável entre execuções)
+pub fn resume_checkpoint_id(task_id: &TaskId) -> String {
+    format!("task-{}", task_id)
+}
+
+/// Tarefa que retoma `task` pelo comando de `RESUME_COMMAND_KEY`
+///
+/// `None` se a tarefa não declara comando de retomada.
+pub fn resume_task(task: &Task) -> Option<Task> {
+    let template = task.metadata.get(RESUME_COMMAND_KEY)?;
+    let command = template.replace("{checkpoint_id}", &resume_checkpoint_id(&task.id));
+    let mut resumed = task.clone();
+    resumed.definition = TaskDefinition::Command { command, shell: None };
+    Some(resumed)
+}
+
 /// Executor principal de tarefas
 pub struct TaskExecutor {
     /// Pool de workers
@@ -30,15 +86,90 @@ pub struct TaskExecutor {
     /// Semáforo para controle de concorrência
     concurrency_semaphore: Arc<Semaphore>,
     
+    /// Limite de concorrência atual
+    max_concurrency: AtomicUsize,
+    
+    /// Permissões a descartar quando tarefas em execução terminarem
+    permit_debt: AtomicUsize,
+    
+    /// Média móvel da espera por permissão e worker (microssegundos)
+    dispatch_latency_us: AtomicU64,
+    
     /// Canal de comandos
     command_tx: mpsc::UnboundedSender<ExecutorCommand>,
     command_rx: Arc<RwLock<Option<mpsc::UnboundedReceiver<ExecutorCommand>>>>,
     
     /// Tarefas em execução
-    running_tasks: Arc<RwLock<HashMap<TaskId, RunningTaskInfo>>>,
+    running_tasks: Arc<DashMap<TaskId, RunningTaskInfo>>,
+    
+    /// Tarefas enviadas ao loop de comandos e ainda não processadas
+    queued_tasks: AtomicUsize,
+    
+    /// Tarefas marcadas como pendentes e ainda sem worker, com o motivo do
+    /// cancelamento pedido enquanto aguardam
+    awaiting_worker: DashMap<TaskId, Option<CancelReason>>,
+    
+    /// Logs de tarefas em arquivo (ausente quando `log_dir` não está configurado)
+    log_store: Option<Arc<LogStore>>,
+    
+    /// Diretórios temporários por tarefa (ausente quando `scratch` não está configurado)
+    scratch: Option<Arc<ScratchSpace>>,
+    
+    /// cgroups por tarefa (ausente sem `cgroups` configurado ou disponível)
+    #[cfg(feature = "cgroups")]
+    cgroups: Option<Arc<CgroupManager>>,
+    
+    /// Buffer write-behind (ausente quando `write_behind` está desabilitado)
+    write_buffer: Option<Arc<WriteBehindBuffer>>,
+    
+    /// Funções Rust (e módulos WASM) chamadas por `TaskDefinition::RustFunction`
+    functions: Arc<FunctionRegistry>,
+    
+    /// Runners Python pré-iniciados (ausente quando `python_pool` não está configurado)
+    python_pool: Option<Arc<PythonPool>>,
     
     /// Configuração
     config: ExecutorConfig,
+    
+    /// Tarefas de background compartilhadas
+    background: BackgroundTasks,
+    
+    /// Barramento de eventos; quando presente, eventos passam por ele
+    event_bus: Option<Arc<EventBus>>,
+    
+    /// Reagendamento de falhas recuperáveis (ausente: falhas são finais)
+    retries: Option<RetryScheduling>,
+    
+    /// Retentativas já feitas por tarefa
+    retry_attempts: DashMap<TaskId, u32>,
+    
+    /// Falhas encaminhadas por regras `NoRetry`
+    dead_letters: DeadLetterQueue,
+    
+    /// Vagas dos grupos de concorrência (mantidas entre retentativas)
+    concurrency_groups: Arc<ConcurrencyGroups>,
+    
+    /// Marcos de despacho registrados pelo scheduler
+    dispatch_marks: Arc<DispatchLatency>,
+    
+    /// Cache de status do core, atualizado a cada transição
+    status_cache: Option<Arc<StatusCache>>,
+    
+    /// Versões de interpretadores e shells deste host, para os manifestos
+    tool_versions: ToolVersions,
+    
+    /// Token dos loops da execução atual (filho do token de background)
+    loop_token: std::sync::Mutex<tokio_util::sync::CancellationToken>,
+    
+    /// Injetor de falhas (quedas de worker)
+    #[cfg(feature = "chaos")]
+    fault_injector: Option<Arc<crate::chaos::FaultInjector>>,
+}
+
+/// Regras de retry e scheduler que redespacha as retentativas
+struct RetryScheduling {
+    scheduler: Arc<Scheduler>,
+    rules: RetryRules,
 }
 
 /// Configuração do executor
@@ -52,10 +183,46 @@ pub struct ExecutorConfig {
     pub log_buffer_size: usize,
     /// Habilitar métricas detalhadas
     pub enable_detailed_metrics: bool,
+    /// Intervalo de amostragem de CPU/memória/IO dos processos
+    pub metrics_sample_interval: Duration,
     /// Intervalo de heartbeat
     pub heartbeat_interval: Duration,
     /// Diretório de trabalho padrão
     pub default_working_dir: String,
+    /// Plataforma alvo (shell, interpretador, cancelamento)
+    pub platform: TargetPlatform,
+    /// Interpretador Python (padrão depende da plataforma)
+    pub python_interpreter: Option<String>,
+    /// Runners Python pré-iniciados (None inicia um interpretador por tarefa)
+    pub python_pool: Option<PythonPoolConfig>,
+    /// Agrupar escritas de status/eventos/métricas antes de persistir
+    ///
+    /// Desabilite para durabilidade estrita (cada transição é gravada
+    /// imediatamente no StateStore).
+    pub write_behind: bool,
+    /// Número de escritas pendentes que força um flush
+    pub write_behind_batch_size: usize,
+    /// Intervalo máximo entre flushes
+    pub write_behind_interval: Duration,
+    /// Diretório para logs por tarefa (None mantém a saída apenas inline)
+    pub log_dir: Option<std::path::PathBuf>,
+    /// Tamanho máximo de stdout+stderr mantido inline no TaskResult
+    pub inline_output_limit: usize,
+    /// Diretórios temporários por tarefa (None usa `default_working_dir`)
+    pub scratch: Option<ScratchConfig>,
+    /// Limites rígidos via cgroup v2 (None mantém apenas os limites contábeis)
+    #[cfg(feature = "cgroups")]
+    pub cgroups: Option<CgroupConfig>,
+    /// Intervalo de verificação de processos reanexados após um reinício
+    pub reattach_poll_interval: Duration,
+    /// Intervalo mínimo entre gravações de progresso (status e eventos)
+    pub progress_interval: Duration,
+    /// Tolerância entre o cancelamento e o aborto de funções que não cooperam
+    pub cancel_grace_period: Duration,
+    /// Hooks de todas as tarefas, antes dos hooks próprios de cada uma
+    pub hooks: Vec<HookSpec>,
+    /// Honra o metadado de resultado forçado (ver [`crate::forced_outcome`])
+    pub allow_forced_outcomes: bool,
 }
 
 impl Default for ExecutorConfig {
@@ -65,17 +232,139 @@ impl Default for ExecutorConfig {
             default_timeout: Duration::from_secs(3600), // 1 hora
             log_buffer_size: 1024 * 1024, // 1MB
             enable_detailed_metrics: true,
+            metrics_sample_interval: Duration::from_millis(100),
             heartbeat_interval: Duration::from_secs(30),
             default_working_dir: std::env::temp_dir().to_string_lossy().to_string(),
+            platform: TargetPlatform::host(),
+            python_interpreter: None,
+            python_pool: None,
+            write_behind: true,
+            write_behind_batch_size: 256,
+            write_behind_interval: Duration::from_millis(50),
+            log_dir: None,
+            inline_output_limit: 64 * 1024, // 64KB
+            scratch: None,
+            #[cfg(feature = "cgroups")]
+            cgroups: None,
+            reattach_poll_interval: Duration::from_millis(500),
+            progress_interval: Duration::from_secs(1),
+            cancel_grace_period: DEFAULT_CANCEL_GRACE_PERIOD,
+            hooks: Vec::new(),
+            allow_forced_outcomes: false,
         }
     }
 }
 
+/// Plataforma alvo da execução
+///
+/// Concentra as diferenças entre Unix e Windows para que possam ser testadas
+/// em qualquer host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetPlatform {
+    Unix,
+    Windows,
+}
+
+impl TargetPlatform {
+    /// Plataforma do host atual
+    pub fn host() -> Self {
+        if cfg!(windows) { TargetPlatform::Windows } else { TargetPlatform::Unix }
+    }
+    
+    /// Programa e argumentos para executar um comando via shell
+    pub fn shell_command(&self, command: &str) -> (&'static str, Vec<String>) {
+        match self {
+            TargetPlatform::Unix => ("sh", vec!["-c".to_string(), command.to_string()]),
+            TargetPlatform::Windows => ("cmd", vec!["/C".to_string(), command.to_string()]),
+        }
+    }
+    
+    /// Interpretador Python padrão
+    pub fn python_interpreter(&self) -> &'static str {
+        match self {
+            TargetPlatform::Unix => "python3",
+            TargetPlatform::Windows => "py",
+        }
+    }
+    
+    /// Comando que encerra um processo e todos os seus descendentes
+    ///
+    /// No Unix o processo é criado em grupo próprio e o grupo inteiro recebe SIGKILL.
+    pub fn kill_tree_command(&self, pid: u32) -> (&'static str, Vec<String>) {
+        match self {
+            TargetPlatform::Unix => ("kill", vec!["-KILL".to_string(), "--".to_string(), format!("-{}", pid)]),
+            TargetPlatform::Windows => ("taskkill", vec!["/T".to_string(), "/F".to_string(), "/PID".to_string(), pid.to_string()]),
+        }
+    }
+    
+    /// Combina variáveis de ambiente; no Windows os nomes não diferenciam
+    /// maiúsculas de minúsculas, então `Path` em `overrides` substitui `PATH`
+    pub fn merge_env(
+        &self,
+        base: &HashMap<String, String>,
+        overrides: &HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let mut merged = base.clone();
+        if *self == TargetPlatform::Windows {
+            merged.retain(|key, _| !overrides.keys().any(|o| o.eq_ignore_ascii_case(key)));
+        }
+        merged.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+        merged
+    }
+}
+
+/// Argumentos que antecedem o comando para cada shell suportado
+pub(crate) fn shell_args(shell: &str) -> &'static [&'static str] {
+    // Separadores dos dois estilos: o caminho pode vir de outra plataforma
+    let file_name = shell.rsplit(['/', '\\']).next().unwrap_or(shell).to_ascii_lowercase();
+    let name = file_name.strip_suffix(".exe").unwrap_or(&file_name);
+    match name {
+        "pwsh" | "powershell" => &["-NoProfile", "-NonInteractive", "-Command"],
+        "cmd" => &["/C"],
+        _ => &["-c"],
+    }
+}
+
+/// Localiza um programa no PATH (ou verifica um caminho explícito)
+pub fn resolve_program(program: &str) -> Option<std::path::PathBuf> {
+    let candidate = std::path::Path::new(program);
+    if candidate.components().count() > 1 {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+    
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+            .split(';')
+            .map(str::to_string)
+            .chain(std::iter::once(String::new()))
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+    
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        extensions.iter()
+            .map(|ext| dir.join(format!("{}{}", program, ext)))
+            .find(|full| full.is_file())
+    })
+}
+
+/// Encerra a árvore de processos iniciada por `pid`
+pub(crate) async fn kill_process_tree(platform: TargetPlatform, pid: u32) {
+    let (program, args) = platform.kill_tree_command(pid);
+    match Command::new(program).args(&args).stdout(Stdio::null()).stderr(Stdio::null()).status().await {
+        Ok(status) if status.success() => debug!("Árvore de processos {} encerrada", pid),
+        Ok(status) => warn!("Falha ao encerrar árvore de processos {}: {}", pid, status),
+        Err(e) => warn!("Erro ao executar {} para o processo {}: {}", program, pid, e),
+    }
+}
+
 /// Comandos do executor
 #[derive(Debug)]
 enum ExecutorCommand {
-    ExecuteTask(TaskId, Task),
-    CancelTask(TaskId),
+    ExecuteTask(TaskId, SharedTask),
     PauseTask(TaskId),
     ResumeTask(TaskId),
     UpdateResources(TaskId, ResourceAllocation),
@@ -90,12 +379,159 @@ struct RunningTaskInfo {
     started_at: SystemTime,
     context: ExecutionContext,
     cancel_token: Option<tokio_util::sync::CancellationToken>,
+    /// Motivo do cancelamento, preenchido por `handle_cancel_task`
+    cancel_reason: Arc<std::sync::OnceLock<CancelReason>>,
+    /// Tarefa executada por um único processo (pode ser reanexada)
+    reattachable: bool,
+    /// Processo da tarefa, depois de iniciado
+    process: Option<ProcessHandle>,
+}
+
+/// Descrição de uma execução que falhou (erro ou código de saída)
+fn failure_message(result: &TaskMeshResult<TaskResult>) -> String {
+    match result {
+        Ok(task_result) => format!("Código de saída {}", task_result.exit_code),
+        Err(error) => error.to_string(),
+    }
+}
+
+/// Progresso mais recente do arquivo da execução, se houver
+async fn read_progress(file: &mut Option<ProgressFile>) -> Option<TaskProgress> {
+    let update = file.as_mut()?.read_latest().await?;
+    Some(update.into_progress(SystemTime::now()))
+}
+
+/// Bytes finais de stdout/stderr mantidos inline quando a saída vai para arquivo
+const INLINE_TAIL_BYTES: usize = 1024;
+
+/// Últimos `max_bytes` de uma saída, respeitando limites de caractere UTF-8
+fn output_tail(output: &str, max_bytes: usize) -> String {
+    if output.len() <= max_bytes {
+        return output.to_string();
+    }
+    let mut start = output.len() - max_bytes;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    output[start..].to_string()
+}
+
+/// Buffer write-behind para escritas no StateStore
+///
+/// Acumula transições de status, eventos e métricas e as persiste em lote
+/// quando o tamanho máximo é atingido, no intervalo configurado ou em flush
+/// forçado (shutdown/checkpoint). Com barramento, os eventos do lote são
+/// gravados e entregues aos assinantes no flush.
+struct WriteBehindBuffer {
+    state_store: Arc<dyn StateStore>,
+    event_bus: Option<Arc<EventBus>>,
+    pending: Mutex<PendingWrites>,
+    batch_size: usize,
+}
+
+/// Escritas pendentes do buffer
+#[derive(Default)]
+struct PendingWrites {
+    statuses: Vec<(TaskId, TaskStatus)>,
+    events: Vec<SystemEvent>,
+    metrics: Vec<(TaskId, ExecutionMetrics)>,
+}
+
+impl PendingWrites {
+    fn len(&self) -> usize {
+        self.statuses.len() + self.events.len() + self.metrics.len()
+    }
+}
+
+impl WriteBehindBuffer {
+    fn new(state_store: Arc<dyn StateStore>, batch_size: usize) -> Self {
+        Self {
+            state_store,
+            event_bus: None,
+            pending: Mutex::new(PendingWrites::default()),
+            batch_size: batch_size.max(1),
+        }
+    }
+    
+    /// Publica os eventos pelo barramento no flush, em vez de só gravá-los
+    fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    /// Enfileira uma atualização de status
+    async fn push_status(&self, task_id: TaskId, status: TaskStatus) -> TaskMeshResult<()> {
+        let full = {
+            let mut pending = self.pending.lock().await;
+            pending.statuses.push((task_id, status));
+            pending.len() >= self.batch_size
+        };
+        if full { self.flush().await } else { Ok(()) }
+    }
+    
+    /// Enfileira um evento
+    async fn push_event(&self, event: SystemEvent) -> TaskMeshResult<()> {
+        let full = {
+            let mut pending = self.pending.lock().await;
+            pending.events.push(event);
+            pending.len() >= self.batch_size
+        };
+        if full { self.flush().await } else { Ok(()) }
+    }
+    
+    /// Enfileira métricas de uma tarefa
+    async fn push_metrics(&self, task_id: TaskId, metrics: ExecutionMetrics) -> TaskMeshResult<()> {
+        let full = {
+            let mut pending = self.pending.lock().await;
+            pending.metrics.push((task_id, metrics));
+            pending.len() >= self.batch_size
+        };
+        if full { self.flush().await } else { Ok(()) }
+    }
+    
+    /// Persiste todas as escritas pendentes
+    async fn flush(&self) -> TaskMeshResult<()> {
+        let pending = std::mem::take(&mut *self.pending.lock().await);
+        if pending.len() == 0 {
+            return Ok(());
+        }
+        
+        debug!("Flush write-behind: {} escritas", pending.len());
+        if !pending.statuses.is_empty() {
+            self.state_store.update_task_statuses(&pending.statuses).await?;
+        }
+        if !pending.events.is_empty() {
+            match &self.event_bus {
+                Some(event_bus) => event_bus.publish_batch(pending.events).await?,
+                None => self.state_store.store_events(&pending.events).await?,
+            }
+        }
+        if !pending.metrics.is_empty() {
+            self.state_store.store_metrics_batch(&pending.metrics).await?;
+        }
+        Ok(())
+    }
+}
+
+impl LoadSignal for TaskExecutor {
+    fn running_task_count(&self) -> usize {
+        // Permissões em uso (leitura síncrona, sem o lock de `running_tasks`)
+        self.max_concurrency().saturating_sub(self.concurrency_semaphore.available_permits())
+    }
+    
+    fn dispatch_latency(&self) -> Duration {
+        TaskExecutor::dispatch_latency(self)
+    }
 }
 
 /// Pool de workers
+///
+/// Workers com índice acima do tamanho ativo estão em drenagem: terminam a
+/// tarefa atual, são parados e só voltam a ser usados se o pool crescer.
 struct WorkerPool {
-    workers: Vec<Worker>,
-    available_workers: Arc<RwLock<Vec<usize>>>,
+    workers: RwLock<Vec<Worker>>,
+    busy_workers: RwLock<HashSet<usize>>,
+    active: AtomicUsize,
 }
 
 /// Worker individual
@@ -111,7 +547,7 @@ struct Worker {
 #[derive(Debug)]
 struct WorkerTask {
     task_id: TaskId,
-    task: Task,
+    task: SharedTask,
     context: ExecutionContext,
     result_tx: mpsc::UnboundedSender<TaskExecutionResult>,
 }
@@ -150,28 +586,219 @@ impl TaskExecutor {
         let (command_tx, command_rx) = mpsc::unbounded_channel();
         let worker_pool = Arc::new(WorkerPool::new(config.max_workers).await?);
         let concurrency_semaphore = Arc::new(Semaphore::new(config.max_workers));
+        let log_store = match &config.log_dir {
+            Some(dir) => Some(Arc::new(LogStore::new(LogStoreConfig::new(dir)).await?)),
+            None => None,
+        };
+        let scratch = match &config.scratch {
+            Some(scratch_config) => Some(Arc::new(ScratchSpace::new(scratch_config.clone()).await?)),
+            None => None,
+        };
+        #[cfg(feature = "cgroups")]
+        let cgroups = config.cgroups.clone().and_then(CgroupManager::new).map(Arc::new);
+        let python_pool = config.python_pool.clone().map(|pool_config| {
+            let interpreter = config.python_interpreter.clone()
+                .unwrap_or_else(|| config.platform.python_interpreter().to_string());
+            Arc::new(PythonPool::new(pool_config, interpreter))
+        });
+        let write_buffer = config.write_behind.then(|| {
+            Arc::new(WriteBehindBuffer::new(state_store.clone(), config.write_behind_batch_size))
+        });
         
         Ok(Self {
             worker_pool,
             state_store,
             error_handler,
             concurrency_semaphore,
+            max_concurrency: AtomicUsize::new(config.max_workers),
+            permit_debt: AtomicUsize::new(0),
+            dispatch_latency_us: AtomicU64::new(0),
             command_tx,
             command_rx: Arc::new(RwLock::new(Some(command_rx))),
-            running_tasks: Arc::new(RwLock::new(HashMap::new())),
+            running_tasks: Arc::new(DashMap::new()),
+            queued_tasks: AtomicUsize::new(0),
+            awaiting_worker: DashMap::new(),
+            log_store,
+            scratch,
+            #[cfg(feature = "cgroups")]
+            cgroups,
+            write_buffer,
+            functions: Arc::new(FunctionRegistry::new()),
+            python_pool,
             config,
+            background: BackgroundTasks::new(),
+            event_bus: None,
+            retries: None,
+            retry_attempts: DashMap::new(),
+            dead_letters: DeadLetterQueue::new(),
+            concurrency_groups: Arc::new(ConcurrencyGroups::default()),
+            dispatch_marks: Arc::new(DispatchLatency::new()),
+            status_cache: None,
+            tool_versions: ToolVersions::new(),
+            loop_token: std::sync::Mutex::new(tokio_util::sync::CancellationToken::new()),
+            #[cfg(feature = "chaos")]
+            fault_injector: None,
         })
     }
     
+    /// Registra os loops do executor em tarefas de background compartilhadas
+    pub fn with_background(mut self, background: BackgroundTasks) -> Self {
+        self.background = background;
+        self
+    }
+    
+    /// Publica os eventos de execução no barramento
+    ///
+    /// Eventos deixam de passar pelo buffer write-behind: o barramento precisa
+    /// da sequência gravada para entregar a assinantes com replay.
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        // Buffer ainda vazio: o executor não foi iniciado
+        self.write_buffer = self.write_buffer.take().map(|buffer| {
+            Arc::new(WriteBehindBuffer::new(self.state_store.clone(), buffer.batch_size).with_event_bus(event_bus.clone()))
+        });
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    /// Usa um registro de funções compartilhado com outros componentes
+    pub fn with_functions(mut self, functions: Arc<FunctionRegistry>) -> Self {
+        self.functions = functions;
+        self
+    }
+    
+    /// Reagenda falhas recuperáveis segundo `rules` (ou uma única `RetryPolicy`)
+    ///
+    /// A tarefa vai para `AwaitingRetry` e volta ao `scheduler`, que só a
+    /// libera após o backoff; o worker retorna ao pool na hora, em vez de
+    /// dormir durante a espera.
+    pub fn with_retries(mut self, scheduler: Arc<Scheduler>, rules: impl Into<RetryRules>) -> Self {
+        self.retries = Some(RetryScheduling { scheduler, rules: rules.into() });
+        self
+    }
+    
+    /// Decisão de retry para a falha da tarefa (`None` sem retries configurados)
+    pub fn explain_failure(&self, task: &Task, failure: &TaskMeshResult<TaskResult>) -> Option<PolicyDecision> {
+        self.retries.as_ref().map(|retries| retries.rules.explain(failure, task))
+    }
+    
+    /// Falhas sem retry enviadas à fila de mensagens mortas (ver [`crate::dead_letter`])
+    pub fn dead_letters(&self) -> &DeadLetterQueue {
+        &self.dead_letters
+    }
+    
+    /// Compartilha as vagas dos grupos de concorrência (com o scheduler)
+    pub fn with_concurrency_groups(mut self, groups: Arc<ConcurrencyGroups>) -> Self {
+        self.concurrency_groups = groups;
+        self
+    }
+    
+    /// Compartilha os marcos de despacho (com o scheduler)
+    pub fn with_dispatch_latency(mut self, latency: Arc<DispatchLatency>) -> Self {
+        self.dispatch_marks = latency;
+        self
+    }
+    
+    /// Mantém o cache de status do core em dia com as transições deste executor
+    pub fn with_status_cache(mut self, cache: Arc<StatusCache>) -> Self {
+        self.status_cache = Some(cache);
+        self
+    }
+    
+    /// Injeta quedas de worker sorteadas pelo injetor
+    #[cfg(feature = "chaos")]
+    pub fn with_fault_injector(mut self, injector: Arc<crate::chaos::FaultInjector>) -> Self {
+        self.fault_injector = Some(injector);
+        self
+    }
+    
     /// Inicia o executor
-    pub async fn start(&self) -> TaskMeshResult<()> {
+    pub async fn start(self: &Arc<Self>) -> TaskMeshResult<()> {
         info!("Iniciando TaskExecutor");
         
+        let token = self.background.token().child_token();
+        *self.loop_token.lock().unwrap() = token.clone();
+        
         // Iniciar workers
         self.worker_pool.start_all().await?;
         
         // Iniciar loop de comando
-        self.start_command_loop().await;
+        self.start_command_loop(token.clone()).await?;
+        
+        // Iniciar varredura periódica dos diretórios temporários
+        if let Some(scratch) = &self.scratch {
+            let mut ticker = tokio::time::interval(scratch.config().sweep_interval);
+            let scratch = Arc::downgrade(scratch);
+            let token = token.clone();
+            self.background.spawn("executor.scratch_sweep", async move {
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = ticker.tick() => {}
+                    }
+                    let Some(scratch) = scratch.upgrade() else { break };
+                    if let Err(e) = scratch.sweep().await {
+                        warn!("Erro na varredura de scratch: {}", e);
+                    }
+                }
+            });
+        }
+        
+        // Aquecer os runners Python e verificá-los periodicamente
+        if let Some(pool) = &self.python_pool {
+            pool.warm();
+            let mut ticker = tokio::time::interval(pool.config().health_check_interval());
+            let pool = Arc::downgrade(pool);
+            let token = token.clone();
+            self.background.spawn("executor.python_pool", async move {
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = ticker.tick() => {}
+                    }
+                    let Some(pool) = pool.upgrade() else { break };
+                    pool.health_check().await;
+                }
+            });
+        }
+        
+        // Iniciar varredura periódica dos cgroups órfãos
+        #[cfg(feature = "cgroups")]
+        if let Some(cgroups) = &self.cgroups {
+            let mut ticker = tokio::time::interval(cgroups.config().sweep_interval);
+            let cgroups = Arc::downgrade(cgroups);
+            let token = token.clone();
+            self.background.spawn("executor.cgroup_sweep", async move {
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = ticker.tick() => {}
+                    }
+                    let Some(cgroups) = cgroups.upgrade() else { break };
+                    let swept = tokio::task::spawn_blocking(move || cgroups.sweep()).await;
+                    if let Ok(Err(e)) = swept {
+                        warn!("Erro na varredura de cgroups: {}", e);
+                    }
+                }
+            });
+        }
+        
+        // Iniciar flush periódico do buffer write-behind
+        if let Some(buffer) = &self.write_buffer {
+            let buffer = Arc::downgrade(buffer);
+            let mut ticker = tokio::time::interval(self.config.write_behind_interval);
+            self.background.spawn("executor.write_behind", async move {
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = ticker.tick() => {}
+                    }
+                    let Some(buffer) = buffer.upgrade() else { break };
+                    if let Err(e) = buffer.flush().await {
+                        error!("Erro no flush write-behind: {}", e);
+                    }
+                }
+            });
+        }
         
         info!("TaskExecutor iniciado");
         Ok(())
@@ -182,64 +809,102 @@ impl TaskExecutor {
         info!("Parando TaskExecutor");
         
         // Cancelar todas as tarefas em execução
-        let running_tasks = self.running_tasks.read().await;
-        for (task_id, task_info) in running_tasks.iter() {
-            if let Some(cancel_token) = &task_info.cancel_token {
+        for entry in self.running_tasks.iter() {
+            if let Some(cancel_token) = &entry.cancel_token {
                 cancel_token.cancel();
             }
-            warn!("Cancelando tarefa em execução: {}", task_id);
+            warn!("Cancelando tarefa em execução: {}", entry.key());
         }
-        drop(running_tasks);
         
         // Parar workers
         self.worker_pool.stop_all().await?;
         
-        // Enviar comando de shutdown
-        if let Err(e) = self.command_tx.send(ExecutorCommand::Shutdown) {
-            error!("Erro ao enviar comando de shutdown: {}", e);
+        if let Some(pool) = &self.python_pool {
+            pool.close();
         }
         
+        // Persistir escritas pendentes
+        self.flush_pending_writes().await?;
+        
+        // Encerrar loops de comando e flush
+        self.loop_token.lock().unwrap().cancel();
+        
         info!("TaskExecutor parado");
         Ok(())
     }
     
     /// Executa uma tarefa
-    #[instrument(skip(self, task), fields(task_id = %task.id, task_name = %task.name))]
-    pub async fn execute_task(&self, task: Task) -> TaskMeshResult<TaskId> {
+    ///
+    /// Em geral retorna assim que a tarefa é enviada ao loop de comandos.
+    /// Tarefas elegíveis com [`ExecutionHint::Inline`](crate::execution_hint::ExecutionHint)
+    /// executam aqui mesmo e o retorno marca o fim da execução.
+    pub async fn execute_task(&self, task: impl Into<SharedTask>) -> TaskMeshResult<TaskId> {
+        let task: SharedTask = task.into();
+        let span = logging::task_span(&task);
+        if execution_hint::runs_inline(&task, &self.config.hooks) {
+            return self.execute_inline(task).instrument(span).await;
+        }
+        self.enqueue_task(task).instrument(span).await
+    }
+    
+    async fn enqueue_task(&self, task: SharedTask) -> TaskMeshResult<TaskId> {
         let task_id = task.id;
-        debug!("Executando tarefa: {} ({})", task.name, task_id);
+        debug!("Executando tarefa: {}", task.name);
+        self.mark_pending(&task_id).await?;
         
+        // Enviar comando de execução
+        self.queued_tasks.fetch_add(1, Ordering::SeqCst);
+        if let Err(e) = self.command_tx.send(ExecutorCommand::ExecuteTask(task_id, task)) {
+            self.queued_tasks.fetch_sub(1, Ordering::SeqCst);
+            return Err(TaskMeshError::Internal(format!("Erro ao enviar comando: {}", e)));
+        }
+        
+        Ok(task_id)
+    }
+    
+    /// Executa a tarefa no chamador, sem passar pelo loop de comandos
+    ///
+    /// Ver [`crate::execution_hint`]. Erros de infraestrutura (sem worker,
+    /// StateStore) voltam ao chamador; falhas da tarefa são registradas como
+    /// no caminho normal.
+    async fn execute_inline(&self, task: SharedTask) -> TaskMeshResult<TaskId> {
+        let task_id = task.id;
+        debug!("Executando tarefa inline: {}", task.name);
+        self.mark_pending(&task_id).await?;
+        self.dispatch(task_id, task, true).await?;
+        Ok(task_id)
+    }
+    
+    /// Marca a tarefa como em execução, ainda sem worker
+    async fn mark_pending(&self, task_id: &TaskId) -> TaskMeshResult<()> {
         // Verificar se tarefa já está em execução
-        if self.running_tasks.read().await.contains_key(&task_id) {
+        if self.running_tasks.contains_key(task_id) {
             return Err(TaskMeshError::Internal(
                 format!("Tarefa {} já está em execução", task_id)
             ));
         }
+        self.awaiting_worker.insert(*task_id, None);
         
         // Atualizar status para execução
-        self.state_store.update_task_status(
-            &task_id,
+        self.record_status(
+            task_id,
             TaskStatus::Running {
                 started_at: SystemTime::now(),
-                worker_id: "pending".to_string(),
+                worker_id: PENDING_WORKER_ID.to_string(),
+                process: None,
+                progress: None,
             },
-        ).await?;
-        
-        // Enviar comando de execução
-        self.command_tx.send(ExecutorCommand::ExecuteTask(task_id, task))
-            .map_err(|e| TaskMeshError::Internal(format!("Erro ao enviar comando: {}", e)))?;
-        
-        Ok(task_id)
+        ).await
     }
     
-    /// Cancela uma tarefa
-    pub async fn cancel_task(&self, task_id: &TaskId) -> TaskMeshResult<()> {
-        debug!("Cancelando tarefa: {}", task_id);
-        
-        self.command_tx.send(ExecutorCommand::CancelTask(*task_id))
-            .map_err(|e| TaskMeshError::Internal(format!("Erro ao enviar comando: {}", e)))?;
-        
-        Ok(())
+    /// Cancela uma tarefa em execução ou aguardando worker
+    ///
+    /// Não passa pelo loop de comandos, que fica ocupado enquanto executa uma
+    /// tarefa.
+    pub async fn cancel_task(&self, task_id: &TaskId, reason: CancelReason) -> TaskMeshResult<()> {
+        debug!("Cancelando tarefa: {} ({})", task_id, reason);
+        
+        self.handle_cancel_task(*task_id, reason).await
     }
     
     /// Pausa uma tarefa
@@ -262,30 +927,253 @@ impl TaskExecutor {
         Ok(())
     }
     
+    /// Limite de concorrência atual
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency.load(Ordering::SeqCst)
+    }
+    
+    /// Altera o limite de concorrência sem reiniciar o executor
+    ///
+    /// Aumentos valem imediatamente. Reduções descartam as permissões ociosas
+    /// e, para o restante, esperam as tarefas em execução terminarem: nenhuma
+    /// tarefa em andamento é cancelada. Retorna o limite anterior.
+    pub async fn set_max_concurrency(&self, limit: usize) -> TaskMeshResult<usize> {
+        if limit == 0 {
+            return Err(TaskMeshError::Configuration(
+                "Concorrência máxima deve ser maior que zero".to_string()
+            ));
+        }
+        
+        let previous = self.max_concurrency.swap(limit, Ordering::SeqCst);
+        if limit > previous {
+            // Quita primeiro reduções ainda pendentes
+            let growth = limit - previous;
+            let repaid = self.take_permit_debt(growth);
+            self.concurrency_semaphore.add_permits(growth - repaid);
+        } else if limit < previous {
+            let shrink = previous - limit;
+            let idle = shrink.min(self.concurrency_semaphore.available_permits());
+            let forgotten = match self.concurrency_semaphore.try_acquire_many(idle as u32) {
+                Ok(permits) => {
+                    permits.forget();
+                    idle
+                }
+                Err(_) => 0,
+            };
+            self.permit_debt.fetch_add(shrink - forgotten, Ordering::SeqCst);
+        }
+        
+        self.worker_pool.resize(limit).await?;
+        
+        self.record_event(SystemEvent::new(
+            EventType::ConfigurationChanged,
+            None,
+            serde_json::json!({ "max_concurrency": limit, "previous": previous }),
+        )).await?;
+        
+        info!("Concorrência máxima alterada de {} para {}", previous, limit);
+        Ok(previous)
+    }
+    
+    /// Ajusta o número de workers ativos sem mudar o limite de concorrência
+    ///
+    /// Usado enquanto ocioso (ver [`crate::idle`]): com menos workers que o
+    /// limite, tarefas recebidas ainda usam os workers parados. Para voltar
+    /// ao normal, use [`Self::max_concurrency`].
+    pub async fn scale_workers(&self, size: usize) -> TaskMeshResult<()> {
+        self.worker_pool.resize(size.min(self.max_concurrency())).await
+    }
+    
+    /// Número de workers que não estão parados
+    pub async fn active_worker_count(&self) -> usize {
+        self.worker_pool.running_count().await
+    }
+    
+    /// Abate até `max` permissões da dívida de redução, retornando o abatido
+    fn take_permit_debt(&self, max: usize) -> usize {
+        let mut taken = 0;
+        let _ = self.permit_debt.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |debt| {
+            taken = debt.min(max);
+            Some(debt - taken)
+        });
+        taken
+    }
+    
+    /// Devolve a permissão de uma tarefa, descartando-a se há redução pendente
+    fn release_permit(&self, permit: SemaphorePermit<'_>) {
+        if self.take_permit_debt(1) == 1 {
+            permit.forget();
+        }
+    }
+    
+    /// Espera recente (média móvel) entre receber uma tarefa e obter um worker
+    pub fn dispatch_latency(&self) -> Duration {
+        Duration::from_micros(self.dispatch_latency_us.load(Ordering::Relaxed))
+    }
+    
+    /// Atualiza a média móvel da latência de despacho (peso 1/8 para a nova amostra)
+    fn record_dispatch_latency(&self, latency: Duration) {
+        let sample = latency.as_micros() as u64;
+        let _ = self.dispatch_latency_us.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |avg| {
+            Some(avg - avg / 8 + sample / 8)
+        });
+    }
+    
+    /// Configuração do executor
+    pub fn config(&self) -> &ExecutorConfig {
+        &self.config
+    }
+    
+    /// Número de tarefas em execução
+    pub async fn running_count(&self) -> usize {
+        self.running_tasks.len()
+    }
+    
+    /// Descarta a contagem de retries da tarefa
+    ///
+    /// Para tarefas finalizadas fora do executor, como as canceladas
+    /// enquanto aguardavam o retry.
+    pub fn forget_retries(&self, task_id: &TaskId) {
+        self.retry_attempts.remove(task_id);
+    }
+    
+    /// Indica se a tarefa está em execução em algum worker deste executor
+    pub fn is_running(&self, task_id: &TaskId) -> bool {
+        self.running_tasks.contains_key(task_id)
+    }
+    
+    /// Tarefas enviadas com `execute_task` cujo processamento não terminou
+    ///
+    /// Chega a zero quando toda tarefa enviada terminou ou falhou ao registrar
+    /// seu resultado.
+    pub fn queued_count(&self) -> usize {
+        self.queued_tasks.load(Ordering::SeqCst)
+    }
+    
+    /// Número de escritas aguardando o flush do buffer write-behind
+    pub async fn pending_write_count(&self) -> usize {
+        match &self.write_buffer {
+            Some(buffer) => buffer.pending.lock().await.len(),
+            None => 0,
+        }
+    }
+    
     /// Obtém informações dos workers
     pub async fn get_worker_info(&self) -> Vec<WorkerInfo> {
         self.worker_pool.get_all_worker_info().await
     }
     
+    /// Armazenamento de logs por tarefa (se configurado)
+    pub fn log_store(&self) -> Option<&Arc<LogStore>> {
+        self.log_store.as_ref()
+    }
+    
+    /// Registro das funções executadas por `TaskDefinition::RustFunction`
+    pub fn functions(&self) -> &Arc<FunctionRegistry> {
+        &self.functions
+    }
+    
+    /// Runners Python pré-iniciados (se configurados)
+    pub fn python_pool(&self) -> Option<&Arc<PythonPool>> {
+        self.python_pool.as_ref()
+    }
+    
+    /// Diretórios temporários por tarefa (se configurados)
+    pub fn scratch(&self) -> Option<&Arc<ScratchSpace>> {
+        self.scratch.as_ref()
+    }
+    
+    /// cgroups por tarefa (se configurados e disponíveis)
+    #[cfg(feature = "cgroups")]
+    pub fn cgroups(&self) -> Option<&Arc<CgroupManager>> {
+        self.cgroups.as_ref()
+    }
+    
+    /// Grava a saída da tarefa no LogStore e, acima do limite inline,
+    /// substitui stdout/stderr por uma referência ao arquivo
+    async fn offload_output(&self, task_id: &TaskId, result: &mut TaskResult) -> TaskMeshResult<()> {
+        let Some(log_store) = &self.log_store else { return Ok(()) };
+        
+        log_store.append(task_id, LogStream::Stdout, &result.stdout).await?;
+        let bytes = log_store.append(task_id, LogStream::Stderr, &result.stderr).await?;
+        
+        if result.stdout.len() + result.stderr.len() > self.config.inline_output_limit {
+            result.stdout = output_tail(&result.stdout, INLINE_TAIL_BYTES);
+            result.stderr = output_tail(&result.stderr, INLINE_TAIL_BYTES);
+            result.log_ref = Some(LogRef {
+                path: log_store.path(task_id).to_string_lossy().to_string(),
+                bytes,
+            });
+        }
+        Ok(())
+    }
+    
+    /// Força a persistência das escritas pendentes no buffer write-behind
+    pub async fn flush_pending_writes(&self) -> TaskMeshResult<()> {
+        match &self.write_buffer {
+            Some(buffer) => buffer.flush().await,
+            None => Ok(()),
+        }
+    }
+    
+    /// Registra uma transição de status (via buffer quando habilitado)
+    ///
+    /// O cache de status é atualizado antes, para que leituras não esperem o
+    /// flush do buffer.
+    async fn record_status(&self, task_id: &TaskId, status: TaskStatus) -> TaskMeshResult<()> {
+        if let Some(cache) = &self.status_cache {
+            cache.record(task_id, &status);
+        }
+        match &self.write_buffer {
+            Some(buffer) => buffer.push_status(*task_id, status).await,
+            None => self.state_store.update_task_status(task_id, status).await,
+        }
+    }
+    
+    /// Registra um evento (via buffer ou barramento, quando configurados)
+    ///
+    /// Com o buffer habilitado, o barramento recebe o evento no flush.
+    async fn record_event(&self, event: SystemEvent) -> TaskMeshResult<()> {
+        match (&self.write_buffer, &self.event_bus) {
+            (Some(buffer), _) => buffer.push_event(event).await,
+            (None, Some(event_bus)) => event_bus.publish(event).await.map(|_| ()),
+            (None, None) => self.state_store.store_event(&event).await,
+        }
+    }
+    
+    /// Registra métricas de execução (via buffer quando habilitado)
+    async fn record_metrics(&self, task_id: &TaskId, metrics: ExecutionMetrics) -> TaskMeshResult<()> {
+        match &self.write_buffer {
+            Some(buffer) => buffer.push_metrics(*task_id, metrics).await,
+            None => self.state_store.store_metrics(task_id, &metrics).await,
+        }
+    }
+    
     /// Inicia loop de processamento de comandos
-    async fn start_command_loop(&self) {
+    ///
+    /// Ao terminar, o receptor de comandos é devolvido ao executor para que
+    /// ele possa ser iniciado novamente.
+    async fn start_command_loop(self: &Arc<Self>, token: tokio_util::sync::CancellationToken) -> TaskMeshResult<()> {
         let mut command_rx = self.command_rx.write().await.take()
-            .expect("Command receiver já foi tomado");
+            .ok_or_else(|| TaskMeshError::Internal("Executor já iniciado".to_string()))?;
         
-        let executor = self.clone_arc();
+        let executor = self.clone();
         
-        tokio::spawn(async move {
-            while let Some(command) = command_rx.recv().await {
+        self.background.spawn("executor.commands", async move {
+            loop {
+                let command = tokio::select! {
+                    _ = token.cancelled() => break,
+                    command = command_rx.recv() => match command {
+                        Some(command) => command,
+                        None => break,
+                    },
+                };
                 match command {
                     ExecutorCommand::ExecuteTask(task_id, task) => {
                         if let Err(e) = executor.handle_execute_task(task_id, task).await {
                             error!("Erro ao executar tarefa {}: {}", task_id, e);
                         }
-                    },
-                    ExecutorCommand::CancelTask(task_id) => {
-                        if let Err(e) = executor.handle_cancel_task(task_id).await {
-                            error!("Erro ao cancelar tarefa {}: {}", task_id, e);
-                        }
+                        executor.queued_tasks.fetch_sub(1, Ordering::SeqCst);
                     },
                     ExecutorCommand::PauseTask(task_id) => {
                         // TODO: Implementar pause
@@ -305,94 +1193,307 @@ impl TaskExecutor {
                     },
                 }
             }
+            *executor.command_rx.write().await = Some(command_rx);
         });
+        Ok(())
     }
     
-    /// Clona referência para Arc
-    fn clone_arc(&self) -> Arc<Self> {
-        // Esta é uma implementação simplificada
-        // Em um cenário real, TaskExecutor deveria ser envolvido em Arc desde o início
-        todo!("Implementar clone_arc adequadamente")
+    /// Lida com execução de tarefa
+    async fn handle_execute_task(&self, task_id: TaskId, task: SharedTask) -> TaskMeshResult<()> {
+        self.dispatch(task_id, task, false).await
     }
     
-    /// Lida com execução de tarefa
-    async fn handle_execute_task(&self, task_id: TaskId, task: Task) -> TaskMeshResult<()> {
+    /// Obtém vaga no grupo, permissão e worker e executa a tarefa
+    ///
+    /// `inline` dispensa o diretório scratch e o arquivo de progresso.
+    async fn dispatch(&self, task_id: TaskId, task: SharedTask, inline: bool) -> TaskMeshResult<()> {
+        // Vaga no grupo de concorrência antes do worker, para não retê-lo na espera.
+        // O guard vem antes da espera: qualquer saída antes de `run_on_worker`
+        // (sem worker, erro no semáforo, future descartado) devolve a vaga.
+        let group_release = self.concurrency_groups.release_on_drop(task_id);
+        if !self.concurrency_groups.acquire(&task).await {
+            debug!("Tarefa {} cancelada aguardando o grupo de concorrência", task_id);
+            self.awaiting_worker.remove(&task_id);
+            return Ok(());
+        }
+        let waiting_since = Instant::now();
+        
         // Adquirir permissão de concorrência
-        let _permit = self.concurrency_semaphore.acquire().await
-            .map_err(|e| TaskMeshError::Internal(format!("Erro ao adquirir semáforo: {}", e)))?;
+        let permit = match self.concurrency_semaphore.acquire().await {
+            Ok(permit) => permit,
+            Err(e) => {
+                self.awaiting_worker.remove(&task_id);
+                return Err(TaskMeshError::Internal(format!("Erro ao adquirir semáforo: {}", e)));
+            }
+        };
         
         // Encontrar worker disponível
-        let worker_id = self.worker_pool.get_available_worker().await
-            .ok_or_else(|| TaskMeshError::ResourceUnavailable(
+        let Some(worker_id) = self.worker_pool.get_available_worker().await else {
+            self.awaiting_worker.remove(&task_id);
+            self.release_permit(permit);
+            return Err(TaskMeshError::ResourceUnavailable(
                 "Nenhum worker disponível".to_string()
-            ))?;
+            ));
+        };
+        self.record_dispatch_latency(waiting_since.elapsed());
         
-        // Criar contexto de execução
-        let context = ExecutionContext {
-            worker_id: worker_id.clone(),
-            working_directory: self.config.default_working_dir.clone(),
+        let span = logging::task_span(&task);
+        span.record("worker_id", worker_id.as_str());
+        let outcome = self.run_on_worker(task_id, task, &worker_id, inline, group_release).instrument(span).await;
+        // `run_on_worker` já a retirou, a menos que tenha falhado antes de executar
+        self.awaiting_worker.remove(&task_id);
+        
+        self.worker_pool.return_worker(&worker_id).await;
+        self.release_permit(permit);
+        outcome
+    }
+    
+    /// Executa a tarefa em um worker já reservado e registra o resultado
+    ///
+    /// A vaga do grupo (`group_release`) só é mantida quando a falha vai para retry.
+    async fn run_on_worker(
+        &self,
+        task_id: TaskId,
+        task: SharedTask,
+        worker_id: &str,
+        inline: bool,
+        group_release: GroupRelease<'_>,
+    ) -> TaskMeshResult<()> {
+        let worker_id = worker_id.to_string();
+        
+        // Criar contexto de execução
+        let mut context = ExecutionContext {
+            worker_id: worker_id.clone(),
+            working_directory: self.config.default_working_dir.clone(),
             environment: std::env::vars().collect(),
-            allocated_resources: ResourceAllocation::default(),
+            allocated_resources: scheduler::requested_resources(&task),
             checkpoint_id: None,
+            progress: None,
+        };
+        process_controls::apply(&task, &mut context.allocated_resources)?;
+        
+        // Diretório temporário isolado, usado como diretório de trabalho
+        let scratch = self.scratch.as_ref().filter(|_| !inline);
+        let scratch_limit = match scratch {
+            Some(scratch) => {
+                let limit = scratch.limit_for(&task)?;
+                let dir = scratch.create(&task_id).await?.to_string_lossy().to_string();
+                context.environment.insert(SCRATCH_ENV.to_string(), dir.clone());
+                context.working_directory = dir;
+                limit
+            }
+            None => None,
+        };
+        if let Some(dir) = task.metadata.get(WORKING_DIR_KEY) {
+            context.working_directory = dir.clone();
+        }
+        if task.metadata.contains_key(RESUME_COMMAND_KEY) {
+            let checkpoint_id = resume_checkpoint_id(&task_id);
+            context.environment.insert(CHECKPOINT_ENV.to_string(), checkpoint_id.clone());
+            context.checkpoint_id = Some(checkpoint_id);
+        }
+        let reattachable = matches!(
+            task.definition,
+            TaskDefinition::Command { .. } | TaskDefinition::Exec { .. } | TaskDefinition::PythonScript { .. }
+        );
+        
+        // Canal de progresso: arquivo para processos, reporter para funções Rust
+        let (reporter, progress_rx) = ProgressReporter::channel();
+        context.progress = Some(reporter);
+        let progress_file = if reattachable && !inline {
+            let file = ProgressFile::create(&task_id).await.map_err(TaskMeshError::Io)?;
+            context.environment.insert(PROGRESS_ENV.to_string(), file.path().to_string_lossy().to_string());
+            Some(file)
+        } else {
+            None
         };
         
+        let attempt = self.retry_attempts.get(&task_id).map_or(0, |attempts| *attempts) + 1;
+        self.record_manifest(&task, attempt, &context).await;
+        
         // Criar token de cancelamento
         let cancel_token = tokio_util::sync::CancellationToken::new();
+        let cancel_reason = Arc::new(std::sync::OnceLock::new());
         
         // Registrar tarefa como em execução
+        let started_at = SystemTime::now();
         let task_info = RunningTaskInfo {
             task_id,
             worker_id: worker_id.clone(),
-            started_at: SystemTime::now(),
+            started_at,
             context: context.clone(),
             cancel_token: Some(cancel_token.clone()),
+            cancel_reason: cancel_reason.clone(),
+            reattachable,
+            process: None,
         };
         
-        self.running_tasks.write().await.insert(task_id, task_info);
+        self.running_tasks.insert(task_id, task_info);
+        
+        // Cancelamento pedido enquanto aguardava worker: a tarefa começa com o
+        // token já cancelado e termina pelo caminho normal de cancelamento
+        if let Some((_, Some(reason))) = self.awaiting_worker.remove(&task_id) {
+            self.cancel_running(&task_id, reason);
+        }
         
-        // Atualizar status
-        self.state_store.update_task_status(
+        // Atualizar status (sem deixar a tarefa presa como em execução)
+        let running = self.record_status(
             &task_id,
             TaskStatus::Running {
-                started_at: SystemTime::now(),
+                started_at,
                 worker_id: worker_id.clone(),
+                process: None,
+                progress: None,
             },
-        ).await?;
+        ).await;
+        if let Err(e) = running {
+            self.running_tasks.remove(&task_id);
+            if scratch.is_some() {
+                self.release_scratch(&task_id, false).await;
+            }
+            return Err(e);
+        }
+        let mut attempt_record = AttemptRecord::started(task_id, attempt, &worker_id, started_at);
+        attempt_record.dispatch = self.dispatch_marks.take(&task, started_at);
+        self.record_attempt(&attempt_record).await;
         
         // Executar tarefa
-        let result = self.execute_task_on_worker(
-            &worker_id,
-            task,
-            context,
-            cancel_token,
-        ).await;
+        let retry_task = task.clone();
+        let hook_runs = std::sync::Mutex::new(Vec::new());
+        let result = if self.injected_worker_crash() {
+            Err(TaskMeshError::ExecutionError(format!("Worker {} caiu (falha injetada)", worker_id)))
+        } else {
+            let execution = self.execute_with_hooks(
+                &worker_id,
+                task,
+                context,
+                cancel_token.clone(),
+                &hook_runs,
+            );
+            let execution = async {
+                match (scratch, scratch_limit) {
+                    (Some(scratch), Some(limit)) => {
+                        tokio::pin!(execution);
+                        tokio::select! {
+                            result = &mut execution => result,
+                            used = scratch.exceeded(&task_id, limit) => {
+                                // Encerrar o processo antes de remover o diretório
+                                cancel_token.cancel();
+                                let _ = execution.await;
+                                Err(TaskMeshError::ResourceLimitExceeded(format!(
+                                    "scratch da tarefa {} com {} bytes (limite {})", task_id, used, limit
+                                )))
+                            }
+                        }
+                    }
+                    _ => execution.await,
+                }
+            };
+            self.track_progress(&task_id, &worker_id, started_at, progress_rx, progress_file, execution).await
+        };
+        
+        let attempt_record = AttemptRecord { hooks: hook_runs.into_inner().unwrap(), ..attempt_record };
+        
+        // Remover da lista de execução; se `handle_cancel_task` já a removeu, o cancelamento venceu
+        let cancel_won = self.running_tasks.remove(&task_id).is_none();
+        
+        // Cancelamento, timeout e limite sempre limpam o diretório
+        let failed = match &result {
+            Ok(task_result) => task_result.exit_code != 0,
+            Err(TaskMeshError::ExecutionTimeout(_) | TaskMeshError::ResourceLimitExceeded(_)) => false,
+            Err(_) => !cancel_token.is_cancelled(),
+        };
+        if scratch.is_some() {
+            self.release_scratch(&task_id, failed).await;
+        }
         
-        // Remover da lista de execução
-        self.running_tasks.write().await.remove(&task_id);
+        // Cancelamento pedido: `Cancelled` é gravado só aqui, com a nota do aborto forçado
+        if let Some(reason) = cancel_reason.get().filter(|_| cancel_won) {
+            let note = match &result {
+                Err(TaskMeshError::CancelledUncooperatively(grace_period)) => {
+                    warn!("Tarefa {} ignorou o cancelamento e foi abortada após {:?}", task_id, grace_period);
+                    Some(CancelNote::CancelledUncooperatively)
+                }
+                _ => None,
+            };
+            self.retry_attempts.remove(&task_id);
+            let status = TaskStatus::Cancelled { cancelled_at: SystemTime::now(), reason: reason.clone(), note };
+            self.record_attempt(&attempt_record.finish(status.clone(), None)).await;
+            self.record_status(&task_id, status).await?;
+            info!("Tarefa {} cancelada", task_id);
+            return Ok(());
+        }
+        if !cancel_token.is_cancelled() && self.schedule_retry(&retry_task, started_at, &result).await? {
+            let now = SystemTime::now();
+            let status = TaskStatus::Failed {
+                started_at,
+                failed_at: now,
+                error: failure_message(&result),
+                retry_count: attempt - 1,
+            };
+            let result = result.ok();
+            self.record_attempt(&attempt_record.finish(status, result)).await;
+            group_release.keep();
+            return Ok(());
+        }
+        let retry_count = self.retry_attempts.remove(&task_id).map_or(0, |(_, attempts)| attempts);
+        let retry_decision = if result.is_err() { self.explain_failure(&retry_task, &result) } else { None };
         
         // Processar resultado
         match result {
-            Ok(task_result) => {
-                self.state_store.update_task_status(
-                    &task_id,
-                    TaskStatus::Completed {
-                        started_at: SystemTime::now(),
-                        completed_at: SystemTime::now(),
-                        result: task_result,
-                    },
-                ).await?;
+            Ok(mut task_result) => {
+                self.offload_output(&task_id, &mut task_result).await?;
+                self.record_metrics(&task_id, task_result.metrics.clone()).await?;
+                let status = TaskStatus::Completed {
+                    started_at,
+                    completed_at: SystemTime::now(),
+                    result: task_result.clone(),
+                };
+                self.record_attempt(&attempt_record.finish(status.clone(), Some(task_result))).await;
+                self.record_status(&task_id, status).await?;
+                self.record_event(SystemEvent::new(
+                    EventType::TaskCompleted,
+                    Some(task_id),
+                    serde_json::json!({ "worker_id": worker_id }),
+                )).await?;
                 info!("Tarefa {} concluída com sucesso", task_id);
             },
             Err(error) => {
-                self.state_store.update_task_status(
-                    &task_id,
-                    TaskStatus::Failed {
-                        started_at: SystemTime::now(),
-                        failed_at: SystemTime::now(),
+                let failed_at = SystemTime::now();
+                let status = TaskStatus::Failed {
+                    started_at,
+                    failed_at,
+                    error: error.to_string(),
+                    retry_count,
+                };
+                self.record_attempt(&attempt_record.finish(status.clone(), None)).await;
+                self.record_status(&task_id, status).await?;
+                
+                // Regra `NoRetry`: a falha vai direto para a fila de mensagens mortas
+                let dead_letter = retry_decision
+                    .clone()
+                    .filter(|decision| matches!(decision.directive, RetryDirective::NoRetry));
+                let dead_lettered = dead_letter.is_some();
+                if let Some(decision) = dead_letter {
+                    self.dead_letters.push(DeadLetter {
+                        task_id,
+                        task_name: retry_task.name.clone(),
                         error: error.to_string(),
-                        retry_count: 0,
-                    },
-                ).await?;
+                        failed_at,
+                        decision,
+                    });
+                    warn!("Tarefa {} enviada à fila de mensagens mortas", task_id);
+                }
+                self.record_event(SystemEvent::new(
+                    EventType::TaskFailed,
+                    Some(task_id),
+                    serde_json::json!({
+                        "worker_id": worker_id,
+                        "error": error.to_string(),
+                        "retry_decision": retry_decision,
+                        "dead_letter": dead_lettered,
+                    }),
+                )).await?;
                 error!("Tarefa {} falhou: {}", task_id, error);
             },
         }
@@ -400,26 +1501,214 @@ impl TaskExecutor {
         Ok(())
     }
     
-    /// Lida com cancelamento de tarefa
-    async fn handle_cancel_task(&self, task_id: TaskId) -> TaskMeshResult<()> {
-        let mut running_tasks = self.running_tasks.write().await;
-        
-        if let Some(task_info) = running_tasks.get(&task_id) {
-            if let Some(cancel_token) = &task_info.cancel_token {
-                cancel_token.cancel();
+    /// Grava o manifesto de reprodutibilidade da tentativa que vai começar
+    ///
+    /// Falhas ao gravar não impedem a execução.
+    async fn record_manifest(&self, task: &Task, attempt: u32, context: &ExecutionContext) {
+        let interpreter = self.python_interpreter();
+        let mut execution_manifest =
+            ExecutionManifest::capture(task, attempt, context, self.config.platform, &interpreter);
+        if let Some(tool) = manifest::probed_tool(&task.definition, &interpreter) {
+            if let Some(version) = self.tool_versions.version(tool).await {
+                execution_manifest.tool_versions.insert(tool.to_string(), version);
             }
-            
-            // Atualizar status
-            self.state_store.update_task_status(
-                &task_id,
-                TaskStatus::Cancelled {
-                    cancelled_at: SystemTime::now(),
-                    reason: "Cancelamento manual".to_string(),
+        }
+        if let Err(e) = self.state_store.store_manifest(&execution_manifest).await {
+            warn!("Falha ao gravar o manifesto da tarefa {} (tentativa {}): {}", task.id, attempt, e);
+        }
+    }
+    
+    /// Grava o registro de uma tentativa; falhas apenas geram aviso
+    async fn record_attempt(&self, attempt: &AttemptRecord) {
+        if let Err(e) = self.state_store.store_attempt(attempt).await {
+            warn!("Falha ao gravar a tentativa {} da tarefa {}: {}", attempt.attempt, attempt.task_id, e);
+        }
+    }
+    
+    /// Encerra a tentativa mais recente da tarefa, se ainda estiver em execução
+    ///
+    /// Usado quando o número da tentativa não está à mão (cancelamento,
+    /// processos reanexados após um reinício).
+    async fn finish_latest_attempt(&self, task_id: &TaskId, status: TaskStatus, result: Option<TaskResult>) {
+        let latest = match self.state_store.list_attempts(task_id).await {
+            Ok(mut attempts) => attempts.pop(),
+            Err(e) => {
+                warn!("Falha ao ler as tentativas da tarefa {}: {}", task_id, e);
+                return;
+            }
+        };
+        if let Some(latest) = latest.filter(|attempt| attempt.status.is_active()) {
+            self.record_attempt(&latest.finish(status, result)).await;
+        }
+    }
+    
+    /// Interpretador Python configurado ou o padrão da plataforma
+    fn python_interpreter(&self) -> String {
+        self.config.python_interpreter.clone()
+            .unwrap_or_else(|| self.config.platform.python_interpreter().to_string())
+    }
+    
+    /// Aguarda `execution` acompanhando o progresso reportado pela tarefa
+    ///
+    /// Atualizações chegam pelo arquivo de progresso (lido a cada
+    /// `PROGRESS_POLL_INTERVAL`) e pelo reporter do contexto. No máximo uma
+    /// por `progress_interval` é gravada; a mais recente ainda pendente é
+    /// gravada quando a execução termina.
+    async fn track_progress<F: std::future::Future>(
+        &self,
+        task_id: &TaskId,
+        worker_id: &str,
+        started_at: SystemTime,
+        mut reporter: tokio::sync::watch::Receiver<Option<TaskProgress>>,
+        mut file: Option<ProgressFile>,
+        execution: F,
+    ) -> F::Output {
+        tokio::pin!(execution);
+        let mut ticker = tokio::time::interval(PROGRESS_POLL_INTERVAL);
+        let mut reporter_open = true;
+        let mut pending: Option<TaskProgress> = None;
+        let mut last_write: Option<Instant> = None;
+        loop {
+            tokio::select! {
+                output = &mut execution => {
+                    if let Some(update) = read_progress(&mut file).await {
+                        pending = Some(update);
+                    }
+                    if let Some(progress) = pending {
+                        self.record_progress(task_id, worker_id, started_at, progress).await;
+                    }
+                    return output;
+                }
+                changed = reporter.changed(), if reporter_open => match changed {
+                    Ok(()) => pending = reporter.borrow_and_update().clone().or(pending),
+                    Err(_) => reporter_open = false,
                 },
-            ).await?;
-            
-            running_tasks.remove(&task_id);
-            info!("Tarefa {} cancelada", task_id);
+                _ = ticker.tick() => {
+                    if let Some(update) = read_progress(&mut file).await {
+                        pending = Some(update);
+                    }
+                }
+            }
+            if last_write.map_or(true, |at| at.elapsed() >= self.config.progress_interval) {
+                if let Some(progress) = pending.take() {
+                    self.record_progress(task_id, worker_id, started_at, progress).await;
+                    last_write = Some(Instant::now());
+                }
+            }
+        }
+    }
+    
+    /// Grava o progresso no status `Running` e publica o evento
+    ///
+    /// Tarefas que já saíram da execução (ex.: canceladas) são ignoradas;
+    /// falhas de gravação apenas geram aviso.
+    async fn record_progress(&self, task_id: &TaskId, worker_id: &str, started_at: SystemTime, progress: TaskProgress) {
+        let Some(process) = self.running_tasks.get(task_id).map(|info| info.process) else { return };
+        let event = SystemEvent::new(
+            EventType::TaskProgress,
+            Some(*task_id),
+            serde_json::json!({ "worker_id": worker_id, "progress": progress }),
+        ).at(progress.updated_at);
+        let status = TaskStatus::Running {
+            started_at,
+            worker_id: worker_id.to_string(),
+            process,
+            progress: Some(progress),
+        };
+        let recorded = match self.record_status(task_id, status).await {
+            Ok(()) => self.record_event(event).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = recorded {
+            warn!("Falha ao gravar o progresso da tarefa {}: {}", task_id, e);
+        }
+    }
+    
+    /// Reagenda a tarefa se a falha é recuperável e ainda há tentativas
+    ///
+    /// Registra `Failed` (com o início da tentativa que falhou) seguido de
+    /// `AwaitingRetry` no histórico. Retorna `false` quando o resultado deve
+    /// ser registrado como final.
+    async fn schedule_retry(
+        &self,
+        task: &SharedTask,
+        started_at: SystemTime,
+        result: &TaskMeshResult<TaskResult>,
+    ) -> TaskMeshResult<bool> {
+        let Some(retries) = &self.retries else { return Ok(false) };
+        let decision = retries.rules.explain(result, task);
+        let Some(policy) = decision.policy().filter(|_| decision.retryable) else { return Ok(false) };
+        let error = failure_message(result);
+        let attempt = self.retry_attempts.get(&task.id).map_or(0, |attempts| *attempts) + 1;
+        if attempt > task.max_retries.min(policy.max_attempts) {
+            return Ok(false);
+        }
+        self.retry_attempts.insert(task.id, attempt);
+        
+        let now = SystemTime::now();
+        let next_attempt_at = now + policy.backoff_strategy.delay(attempt);
+        self.record_status(&task.id, TaskStatus::Failed {
+            started_at,
+            failed_at: now,
+            error: error.clone(),
+            retry_count: attempt - 1,
+        }).await?;
+        self.record_status(&task.id, TaskStatus::AwaitingRetry { attempt, next_attempt_at }).await?;
+        self.record_event(SystemEvent::new(
+            EventType::TaskFailed,
+            Some(task.id),
+            serde_json::json!({
+                "error": error,
+                "retry_attempt": attempt,
+                "next_attempt_at": next_attempt_at,
+                "retry_decision": decision,
+            }),
+        ).at(now)).await?;
+        retries.scheduler.schedule_retry(task.clone(), next_attempt_at).await?;
+        
+        warn!("Tarefa {} falhou ({}); tentativa {} às {:?}", task.id, error, attempt, next_attempt_at);
+        Ok(true)
+    }
+    
+    /// Libera o diretório temporário da tarefa, mantendo-o após falhas se configurado
+    async fn release_scratch(&self, task_id: &TaskId, failed: bool) {
+        let Some(scratch) = &self.scratch else { return };
+        let retain = failed && scratch.config().retain_on_failure;
+        if let Err(e) = scratch.release(task_id, retain).await {
+            warn!("Falha ao remover scratch da tarefa {}: {}", task_id, e);
+        }
+    }
+    
+    /// Sorteia a queda do worker quando há injetor de falhas
+    fn injected_worker_crash(&self) -> bool {
+        #[cfg(feature = "chaos")]
+        if let Some(injector) = &self.fault_injector {
+            return injector.crash_worker();
+        }
+        false
+    }
+    
+    /// Lida com cancelamento de tarefa
+    ///
+    /// Retirar a tarefa de `running_tasks` decide a corrida com o fim da
+    /// execução: só quem a retira grava o estado final. Aqui apenas o motivo é
+    /// registrado e o token cancelado; `Cancelled` é gravado uma única vez pelo
+    /// laço que executa a tarefa, quando ela para. Tarefas ainda sem worker
+    /// guardam o motivo em `awaiting_worker` e são canceladas ao serem pegas.
+    async fn handle_cancel_task(&self, task_id: TaskId, reason: CancelReason) -> TaskMeshResult<()> {
+        if self.cancel_running(&task_id, reason.clone()) {
+            info!("Cancelamento da tarefa {} solicitado", task_id);
+            return Ok(());
+        }
+        if let Some(mut pending) = self.awaiting_worker.get_mut(&task_id) {
+            *pending = Some(reason);
+            info!("Cancelamento da tarefa {} registrado até ela obter um worker", task_id);
+            return Ok(());
+        }
+        // O worker pode tê-la pego entre as duas verificações: a inserção em
+        // `running_tasks` vem antes da retirada de `awaiting_worker`
+        if self.cancel_running(&task_id, reason) {
+            info!("Cancelamento da tarefa {} solicitado", task_id);
         } else {
             warn!("Tarefa {} não encontrada para cancelamento", task_id);
         }
@@ -427,26 +1716,68 @@ impl TaskExecutor {
         Ok(())
     }
     
+    /// Registra o motivo e cancela o token de uma tarefa em `running_tasks`
+    fn cancel_running(&self, task_id: &TaskId, reason: CancelReason) -> bool {
+        // Motivo antes da remoção: quem encontrar a tarefa removida já o vê
+        if let Some(task_info) = self.running_tasks.get(task_id) {
+            let _ = task_info.cancel_reason.set(reason);
+        }
+        let Some((_, task_info)) = self.running_tasks.remove(task_id) else {
+            return false;
+        };
+        if let Some(cancel_token) = &task_info.cancel_token {
+            cancel_token.cancel();
+        }
+        true
+    }
+    
     /// Executa tarefa em worker específico
     async fn execute_task_on_worker(
         &self,
         worker_id: &str,
-        task: Task,
-        context: ExecutionContext,
+        task: SharedTask,
+        mut context: ExecutionContext,
         cancel_token: tokio_util::sync::CancellationToken,
     ) -> TaskMeshResult<TaskResult> {
         let start_time = Instant::now();
         
+        // Resultado forçado no lugar da definição (e dos sidecars)
+        if let Some(outcome) = &task.forced_outcome {
+            if !self.config.allow_forced_outcomes {
+                return Err(TaskMeshError::Configuration(format!(
+                    "Tarefa {} tem resultado forçado, mas allow_forced_outcomes está desligado", task.id
+                )));
+            }
+            info!("Tarefa {} com resultado forçado ({:?}); definição não executada", task.id, outcome.status);
+            let time_limit = context.allocated_resources.time_limit.unwrap_or(self.config.default_timeout);
+            let mut task_result = outcome.produce(&task.id, time_limit, cancel_token).await?;
+            task_result.metrics.execution_time = start_time.elapsed();
+            return Ok(task_result);
+        }
+        
+        // Sidecars prontos antes do comando principal
+        let sidecars = if task.sidecars.is_empty() {
+            None
+        } else {
+            Some(Sidecars::start(&task.sidecars, &mut context, self.config.platform, &cancel_token).await?)
+        };
+        
         // Executar baseado no tipo de tarefa
         let result = match &task.definition {
-            TaskDefinition::Command(command) => {
-                self.execute_command(command, &context, cancel_token).await
+            TaskDefinition::Command { command, shell } => {
+                match shell {
+                    Some(shell) => self.execute_in_shell(&task.id, shell, command, &context, cancel_token).await,
+                    None => self.execute_command(&task.id, command, &context, cancel_token).await,
+                }
+            },
+            TaskDefinition::Exec { program, args } => {
+                self.execute_program(&task.id, program, args, &context, cancel_token).await
             },
             TaskDefinition::PythonScript { script, args, env } => {
-                self.execute_python_script(script, args, env, &context, cancel_token).await
+                self.execute_python_script(&task.id, script, args, env, &context, cancel_token).await
             },
             TaskDefinition::RustFunction { function_name, args } => {
-                self.execute_rust_function(function_name, args, &context, cancel_token).await
+                self.execute_rust_function(&task, function_name, args, &context, cancel_token).await
             },
             TaskDefinition::HttpRequest { method, url, headers, body } => {
                 self.execute_http_request(method, url, headers, body.as_deref(), &context, cancel_token).await
@@ -454,8 +1785,17 @@ impl TaskExecutor {
             TaskDefinition::Workflow { tasks, execution_strategy } => {
                 self.execute_workflow(tasks, execution_strategy, &context, cancel_token).await
             },
+            TaskDefinition::Generator { source, max_fan_out } => {
+                let mut source_task = Task::clone(&task);
+                source_task.definition = (**source).clone();
+                self.execute_generator(worker_id, source_task, *max_fan_out, context, cancel_token).await
+            },
         };
         
+        if let Some(sidecars) = sidecars {
+            self.stop_sidecars(&task.id, sidecars).await;
+        }
+        
         let execution_time = start_time.elapsed();
         
         // Adicionar métricas
@@ -468,48 +1808,255 @@ impl TaskExecutor {
         }
     }
     
+    /// Executa a tarefa entre seus hooks (ver [`crate::hooks`])
+    ///
+    /// Cada hook executado é acrescentado a `runs`. Hooks posteriores e
+    /// finais não são interrompidos pelo cancelamento da tarefa, apenas pelo
+    /// próprio limite de tempo.
+    async fn execute_with_hooks(
+        &self,
+        worker_id: &str,
+        task: SharedTask,
+        context: ExecutionContext,
+        cancel_token: tokio_util::sync::CancellationToken,
+        runs: &std::sync::Mutex<Vec<HookRun>>,
+    ) -> TaskMeshResult<TaskResult> {
+        let mut specs = self.config.hooks.clone();
+        specs.extend(task.hooks.iter().cloned());
+        if specs.is_empty() {
+            return self.execute_task_on_worker(worker_id, task, context, cancel_token).await;
+        }
+        
+        let mut blocked = None;
+        for (label, spec) in hooks::in_phase(&specs, HookPhase::Before) {
+            let run = self.run_hook(worker_id, &task, &label, spec, &context, HashMap::new(), cancel_token.clone()).await;
+            if let Some(error) = Self::hook_failure(&task, &run, spec) {
+                blocked = Some(error);
+            }
+            runs.lock().unwrap().push(run);
+            if blocked.is_some() {
+                break;
+            }
+        }
+        
+        let started = Instant::now();
+        let mut result = match blocked {
+            Some(error) => Err(error),
+            None => self.execute_task_on_worker(worker_id, task.clone(), context.clone(), cancel_token).await,
+        };
+        let summary = hooks::result_env(&result, started.elapsed());
+        let succeeded = matches!(&result, Ok(task_result) if task_result.exit_code == 0);
+        let phase = if succeeded { HookPhase::AfterSuccess } else { HookPhase::AfterFailure };
+        
+        for phase in [phase, HookPhase::Finally] {
+            for (label, spec) in hooks::in_phase(&specs, phase) {
+                let token = tokio_util::sync::CancellationToken::new();
+                let run = self.run_hook(worker_id, &task, &label, spec, &context, summary.clone(), token).await;
+                if let Some(error) = Self::hook_failure(&task, &run, spec) {
+                    if result.is_ok() {
+                        result = Err(error);
+                    }
+                }
+                runs.lock().unwrap().push(run);
+            }
+        }
+        result
+    }
+    
+    /// Executa um hook no contexto da tarefa
+    #[allow(clippy::too_many_arguments)]
+    async fn run_hook(
+        &self,
+        worker_id: &str,
+        task: &Task,
+        label: &str,
+        spec: &HookSpec,
+        context: &ExecutionContext,
+        env: HashMap<String, String>,
+        cancel_token: tokio_util::sync::CancellationToken,
+    ) -> HookRun {
+        let mut hook_context = context.clone();
+        hook_context.progress = None;
+        hook_context.environment.extend(env);
+        hook_context.environment.extend([
+            (hooks::HOOK_PHASE_ENV.to_string(), spec.on.as_str().to_string()),
+            (hooks::HOOK_LABEL_ENV.to_string(), label.to_string()),
+            (hooks::HOOK_TASK_ID_ENV.to_string(), task.id.to_string()),
+            (hooks::HOOK_TASK_NAME_ENV.to_string(), task.name.clone()),
+        ]);
+        // Limite próprio do hook; sem ele, o timeout padrão do executor (nunca o da tarefa)
+        hook_context.allocated_resources.time_limit = spec.timeout_ms.map(Duration::from_millis);
+        
+        // O hook roda como definição avulsa: só herda os padrões propagáveis, nunca hooks
+        let mut hook = Task::new(format!("{} ({})", task.name, label), spec.run.clone(), vec![]);
+        defaults::inherit(task, &mut hook);
+        let started_at = SystemTime::now();
+        let result = self.execute_task_on_worker(worker_id, Arc::new(hook), hook_context, cancel_token).await;
+        let error = match &result {
+            Ok(hook_result) if hook_result.exit_code == 0 => None,
+            Ok(hook_result) => Some(format!("código de saída {}", hook_result.exit_code)),
+            Err(e) => Some(e.to_string()),
+        };
+        HookRun {
+            label: label.to_string(),
+            phase: spec.on,
+            started_at,
+            finished_at: SystemTime::now(),
+            result: result.ok(),
+            error,
+        }
+    }
+    
+    /// Erro que falha a tarefa, se o hook falhou e a política manda
+    fn hook_failure(task: &Task, run: &HookRun, spec: &HookSpec) -> Option<TaskMeshError> {
+        let error = run.error.as_ref()?;
+        if !spec.fails_task() {
+            warn!("Hook {} da tarefa {} falhou: {}", run.label, task.id, error);
+            return None;
+        }
+        Some(TaskMeshError::ExecutionError(format!("Hook {} falhou: {}", run.label, error)))
+    }
+    
+    /// Encerra os sidecars da tarefa e grava a saída de cada um no log
+    async fn stop_sidecars(&self, task_id: &TaskId, sidecars: Sidecars) {
+        let outputs = sidecars.stop().await;
+        let Some(log_store) = &self.log_store else { return };
+        for output in outputs {
+            let section = format!("sidecar:{}", output.name);
+            for (stream, text) in [(LogStream::Stdout, &output.stdout), (LogStream::Stderr, &output.stderr)] {
+                if let Err(e) = log_store.append_section(task_id, &section, stream, text).await {
+                    warn!("Falha ao gravar log do sidecar {} da tarefa {}: {}", output.name, task_id, e);
+                }
+            }
+        }
+    }
+    
     /// Executa comando shell
     async fn execute_command(
         &self,
+        task_id: &TaskId,
         command: &str,
         context: &ExecutionContext,
         cancel_token: tokio_util::sync::CancellationToken,
     ) -> TaskMeshResult<TaskResult> {
         debug!("Executando comando: {}", command);
         
-        let mut cmd = if cfg!(target_os = "windows") {
-            let mut cmd = Command::new("cmd");
-            cmd.args(["/C", command]);
-            cmd
-        } else {
-            let mut cmd = Command::new("sh");
-            cmd.args(["-c", command]);
-            cmd
-        };
+        let (program, args) = self.config.platform.shell_command(command);
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        
+        self.run_process(task_id, cmd, context, cancel_token).await
+    }
+    
+    /// Executa comando em um shell específico
+    async fn execute_in_shell(
+        &self,
+        task_id: &TaskId,
+        shell: &str,
+        command: &str,
+        context: &ExecutionContext,
+        cancel_token: tokio_util::sync::CancellationToken,
+    ) -> TaskMeshResult<TaskResult> {
+        debug!("Executando comando em {}: {}", shell, command);
+        
+        let mut cmd = Command::new(shell);
+        cmd.args(shell_args(shell)).arg(command);
+        
+        self.run_process(task_id, cmd, context, cancel_token).await
+    }
+    
+    /// Executa programa diretamente, sem shell
+    async fn execute_program(
+        &self,
+        task_id: &TaskId,
+        program: &str,
+        args: &[String],
+        context: &ExecutionContext,
+        cancel_token: tokio_util::sync::CancellationToken,
+    ) -> TaskMeshResult<TaskResult> {
+        debug!("Executando programa: {} {:?}", program, args);
+        
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        
+        self.run_process(task_id, cmd, context, cancel_token).await
+    }
+    
+    /// Executa um processo já montado, com timeout, cancelamento e métricas
+    async fn run_process(
+        &self,
+        task_id: &TaskId,
+        mut cmd: Command,
+        context: &ExecutionContext,
+        cancel_token: tokio_util::sync::CancellationToken,
+    ) -> TaskMeshResult<TaskResult> {
+        let platform = self.config.platform;
         
         cmd.current_dir(&context.working_directory)
             .envs(&context.environment)
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        
+        // Processo em grupo próprio para que o cancelamento alcance os filhos
+        #[cfg(unix)]
+        cmd.process_group(0);
+        // Nice, ionice e afinidade de CPU da tarefa
+        process_controls::configure(&mut cmd, &context.allocated_resources);
+        #[cfg(feature = "cgroups")]
+        let cgroup = self.task_cgroup(task_id, context, &mut cmd)?;
         
         let timeout_duration = context.allocated_resources.time_limit
             .unwrap_or(self.config.default_timeout);
         
+        let child = cmd.spawn().map_err(TaskMeshError::Io)?;
+        if let Some(pid) = child.id() {
+            self.record_process(&context.worker_id, pid).await;
+        }
+        
+        // Amostrar CPU/memória/IO do processo enquanto executa
+        let sampler = match child.id() {
+            Some(pid) if self.config.enable_detailed_metrics => {
+                Some(ProcessSampler::spawn(pid, self.config.metrics_sample_interval))
+            }
+            _ => None,
+        };
+        
+        let pid = child.id();
+        
         let result = tokio::select! {
             _ = cancel_token.cancelled() => {
+                if let Some(pid) = pid {
+                    kill_process_tree(platform, pid).await;
+                }
                 return Err(TaskMeshError::ExecutionError(
                     "Tarefa cancelada".to_string()
                 ));
             }
-            result = timeout(timeout_duration, cmd.output()) => {
+            result = timeout(timeout_duration, child.wait_with_output()) => {
                 match result {
                     Ok(Ok(output)) => output,
                     Ok(Err(e)) => return Err(TaskMeshError::Io(e)),
-                    Err(_) => return Err(TaskMeshError::ExecutionTimeout(uuid::Uuid::new_v4())),
+                    Err(_) => {
+                        if let Some(pid) = pid {
+                            kill_process_tree(platform, pid).await;
+                        }
+                        return Err(TaskMeshError::ExecutionTimeout(*task_id));
+                    }
                 }
             }
         };
         
+        #[cfg(feature = "cgroups")]
+        if let Some(error) = cgroup.as_ref().and_then(|cgroup| cgroup.limit_exceeded(result.status.success())) {
+            return Err(error);
+        }
+        
+        let metrics = match sampler {
+            Some(sampler) => sampler.finish().await,
+            None => ExecutionMetrics::default(),
+        };
+        
         let stdout = String::from_utf8_lossy(&result.stdout).to_string();
         let stderr = String::from_utf8_lossy(&result.stderr).to_string();
         let exit_code = result.status.code().unwrap_or(-1);
@@ -519,19 +2066,171 @@ impl TaskExecutor {
             stdout,
             stderr,
             output_data: None,
-            metrics: ExecutionMetrics::default(),
+            metrics,
+            log_ref: None,
         })
     }
     
+    /// Cria o cgroup do processo e faz o filho entrar nele ao iniciar
+    ///
+    /// O cgroup leva o ID da tarefa.
+    #[cfg(feature = "cgroups")]
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    fn task_cgroup(&self, task_id: &TaskId, context: &ExecutionContext, cmd: &mut Command) -> TaskMeshResult<Option<TaskCgroup>> {
+        let Some(cgroups) = &self.cgroups else { return Ok(None) };
+        let cgroup = cgroups.create(task_id, &context.allocated_resources)?;
+        #[cfg(unix)]
+        cgroup.attach(cmd);
+        Ok(Some(cgroup))
+    }
+    
+    /// Grava no status `Running` o processo da tarefa do worker
+    ///
+    /// Permite reanexar a tarefa após um reinício. Só vale para o primeiro
+    /// processo de tarefas de processo único; falhas apenas geram aviso.
+    async fn record_process(&self, worker_id: &str, pid: u32) {
+        let Some(handle) = process_metrics::process_handle(pid) else { return };
+        let running = self.running_tasks
+            .iter_mut()
+            .find(|info| info.worker_id == worker_id && info.reattachable && info.process.is_none())
+            .map(|mut info| {
+                info.process = Some(handle);
+                (info.task_id, info.started_at)
+            });
+        let Some((task_id, started_at)) = running else { return };
+        
+        let status = TaskStatus::Running {
+            started_at,
+            worker_id: worker_id.to_string(),
+            process: Some(handle),
+            progress: None,
+        };
+        if let Err(e) = self.record_status(&task_id, status).await {
+            warn!("Falha ao gravar o processo {} da tarefa {}: {}", pid, task_id, e);
+        }
+    }
+    
+    /// Reanexa uma tarefa cujo processo sobreviveu ao reinício do executor
+    ///
+    /// A tarefa volta a constar como em execução e o processo é verificado a
+    /// cada `reattach_poll_interval`. Como o processo não é filho deste
+    /// executor, o código de saída não pode ser obtido: quando ele termina a
+    /// tarefa é registrada como `Failed` com o código desconhecido, em vez de
+    /// presumir sucesso. Cancelar a tarefa encerra o processo.
+    pub fn reattach(self: &Arc<Self>, task_id: TaskId, started_at: SystemTime, worker_id: String, handle: ProcessHandle) {
+        let cancel_token = tokio_util::sync::CancellationToken::new();
+        let cancel_reason: Arc<std::sync::OnceLock<CancelReason>> = Arc::default();
+        self.running_tasks.insert(task_id, RunningTaskInfo {
+            task_id,
+            worker_id: worker_id.clone(),
+            started_at,
+            context: ExecutionContext {
+                worker_id: worker_id.clone(),
+                working_directory: self.config.default_working_dir.clone(),
+                environment: HashMap::new(),
+                allocated_resources: ResourceAllocation::default(),
+                checkpoint_id: None,
+                progress: None,
+            },
+            cancel_token: Some(cancel_token.clone()),
+            cancel_reason: cancel_reason.clone(),
+            reattachable: true,
+            process: Some(handle),
+        });
+        info!("Tarefa {} reanexada ao processo {}", task_id, handle.pid);
+        
+        let executor = self.clone();
+        let token = self.background.token();
+        let mut ticker = tokio::time::interval(self.config.reattach_poll_interval);
+        self.background.spawn("executor.reattach", async move {
+            let cancelled = loop {
+                tokio::select! {
+                    _ = token.cancelled() => return,
+                    _ = cancel_token.cancelled() => {
+                        kill_process_tree(executor.config.platform, handle.pid).await;
+                        break true;
+                    }
+                    _ = ticker.tick() => {}
+                }
+                if !process_metrics::is_alive(&handle) {
+                    break false;
+                }
+            };
+            // Como no laço de execução, quem retira a tarefa grava o estado final
+            let finished = match (executor.running_tasks.remove(&task_id).is_some(), cancel_reason.get()) {
+                (true, _) if !cancelled => executor.finish_reattached(task_id, started_at, &worker_id).await,
+                (false, Some(reason)) => executor.finish_reattached_cancel(task_id, reason.clone()).await,
+                _ => return,
+            };
+            if let Err(e) = finished {
+                error!("Erro ao encerrar a tarefa reanexada {}: {}", task_id, e);
+            }
+        });
+    }
+    
+    /// Registra o fim de uma tarefa reanexada
+    ///
+    /// Sem o código de saída, o término não pode ser dado como sucesso.
+    async fn finish_reattached(&self, task_id: TaskId, started_at: SystemTime, worker_id: &str) -> TaskMeshResult<()> {
+        let failed_at = SystemTime::now();
+        let error = "Processo reanexado terminou com código de saída desconhecido".to_string();
+        let retry_count = self.retry_attempts.remove(&task_id).map_or(0, |(_, attempts)| attempts);
+        let status = TaskStatus::Failed { started_at, failed_at, error: error.clone(), retry_count };
+        self.finish_latest_attempt(&task_id, status.clone(), None).await;
+        self.record_status(&task_id, status).await?;
+        self.record_event(SystemEvent::new(
+            EventType::TaskFailed,
+            Some(task_id),
+            serde_json::json!({ "worker_id": worker_id, "reattached": true, "error": error }),
+        ).at(failed_at)).await?;
+        warn!("Tarefa reanexada {} terminou com código de saída desconhecido", task_id);
+        Ok(())
+    }
+    
+    /// Registra o cancelamento de uma tarefa reanexada
+    async fn finish_reattached_cancel(&self, task_id: TaskId, reason: CancelReason) -> TaskMeshResult<()> {
+        self.retry_attempts.remove(&task_id);
+        let status = TaskStatus::Cancelled { cancelled_at: SystemTime::now(), reason, note: None };
+        self.finish_latest_attempt(&task_id, status.clone(), None).await;
+        self.record_status(&task_id, status).await?;
+        info!("Tarefa reanexada {} cancelada", task_id);
+        Ok(())
+    }
+    
     /// Executa script Python
+    ///
+    /// Usa um runner do pool quando configurado e disponível; caso contrário
+    /// inicia um interpretador para a tarefa. Tarefas com controles de
+    /// processo ou cgroup próprio sempre ganham um interpretador, pois o
+    /// runner já está em execução e não pode recebê-los.
     async fn execute_python_script(
         &self,
+        task_id: &TaskId,
         script: &str,
         args: &[String],
         env: &HashMap<String, String>,
         context: &ExecutionContext,
         cancel_token: tokio_util::sync::CancellationToken,
     ) -> TaskMeshResult<TaskResult> {
+        #[cfg(feature = "cgroups")]
+        let isolated = self.cgroups.is_some();
+        #[cfg(not(feature = "cgroups"))]
+        let isolated = false;
+        let pool = self.python_pool.as_ref()
+            .filter(|_| !isolated && !process_controls::any(&context.allocated_resources));
+        if let Some(pool) = pool {
+            let environment = self.config.platform.merge_env(&context.environment, env);
+            let time_limit = context.allocated_resources.time_limit
+                .unwrap_or(self.config.default_timeout);
+            let pooled = pool
+                .run(task_id, script, args, &environment, &context.working_directory, &cancel_token, time_limit)
+                .await;
+            match pooled {
+                Some(result) => return result,
+                None => debug!("Pool Python esgotado; iniciando interpretador para a tarefa"),
+            }
+        }
+        
         // Criar arquivo temporário para o script
         let script_file = tempfile::NamedTempFile::new()
             .map_err(TaskMeshError::Io)?;
@@ -539,44 +2238,71 @@ impl TaskExecutor {
         tokio::fs::write(script_file.path(), script).await
             .map_err(TaskMeshError::Io)?;
         
-        let mut command = format!("python {}", script_file.path().display());
-        if !args.is_empty() {
-            command.push(' ');
-            command.push_str(&args.join(" "));
-        }
+        // Interpretador chamado diretamente: caminho e argumentos não passam pelo shell
+        let mut cmd = Command::new(self.python_interpreter());
+        cmd.arg(script_file.path()).args(args);
         
         // Adicionar variáveis de ambiente específicas
-        let mut full_env = context.environment.clone();
-        full_env.extend(env.clone());
-        
         let updated_context = ExecutionContext {
-            environment: full_env,
+            environment: self.config.platform.merge_env(&context.environment, env),
             ..context.clone()
         };
         
-        self.execute_command(&command, &updated_context, cancel_token).await
+        self.run_process(task_id, cmd, &updated_context, cancel_token).await
     }
     
-    /// Executa função Rust
+    /// Executa função Rust registrada
+    ///
+    /// A função recebe um [`FnContext`] com um token filho do cancelamento da
+    /// tarefa; ver [`FunctionRegistry::call`] para tolerância e timeout.
     async fn execute_rust_function(
         &self,
+        task: &Task,
         function_name: &str,
         args: &serde_json::Value,
-        _context: &ExecutionContext,
-        _cancel_token: tokio_util::sync::CancellationToken,
+        context: &ExecutionContext,
+        cancel_token: tokio_util::sync::CancellationToken,
     ) -> TaskMeshResult<TaskResult> {
-        // TODO: Implementar sistema de plugins para funções Rust
-        warn!("Execução de função Rust não implementada: {}", function_name);
+        debug!("Executando função {}", function_name);
+        
+        let ctx = FnContext {
+            task_id: task.id,
+            cancel: cancel_token.child_token(),
+            progress: context.progress.clone().unwrap_or_else(|| ProgressReporter::channel().0),
+            scratch_dir: std::path::PathBuf::from(&context.working_directory),
+            inputs: self.resolve_inputs(&task.dependencies).await,
+        };
+        let time_limit = context.allocated_resources.time_limit
+            .unwrap_or(self.config.default_timeout);
+        let output = self.functions
+            .call(function_name, ctx, args.clone(), time_limit, self.config.cancel_grace_period)
+            .await?;
         
         Ok(TaskResult {
             exit_code: 0,
-            stdout: format!("Função {} chamada com args: {}", function_name, args),
+            stdout: String::new(),
             stderr: String::new(),
-            output_data: Some(args.clone()),
+            output_data: Some(output),
             metrics: ExecutionMetrics::default(),
+            log_ref: None,
         })
     }
     
+    /// `output_data` das dependências concluídas
+    async fn resolve_inputs(&self, dependencies: &[TaskId]) -> HashMap<TaskId, serde_json::Value> {
+        let mut inputs = HashMap::new();
+        for dependency in dependencies {
+            match self.state_store.get_task_status(dependency).await {
+                Ok(TaskStatus::Completed { result: TaskResult { output_data: Some(output), .. }, .. }) => {
+                    inputs.insert(*dependency, output);
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Falha ao ler a saída da dependência {}: {}", dependency, e),
+            }
+        }
+        inputs
+    }
+    
     /// Executa requisição HTTP
     async fn execute_http_request(
         &self,
@@ -614,6 +2340,12 @@ impl TaskExecutor {
         let request = request_builder.build()
             .map_err(|e| TaskMeshError::ExecutionError(format!("Erro ao construir requisição: {}", e)))?;
         
+        // Bytes enviados: linha de requisição + headers + body
+        let request_bytes = request.method().as_str().len()
+            + request.url().as_str().len()
+            + request.headers().iter().map(|(k, v)| k.as_str().len() + v.len() + 4).sum::<usize>()
+            + request.body().and_then(|b| b.as_bytes()).map(|b| b.len()).unwrap_or(0);
+        
         let result = tokio::select! {
             _ = cancel_token.cancelled() => {
                 return Err(TaskMeshError::ExecutionError(
@@ -631,8 +2363,18 @@ impl TaskExecutor {
                     .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
                     .collect();
                 
+                let header_bytes: usize = response.headers()
+                    .iter()
+                    .map(|(k, v)| k.as_str().len() + v.len() + 4)
+                    .sum();
+                
                 let body_text = response.text().await
-                    .map_err(|e| TaskMeshError::ExecutionError(format!("Erro ao ler resposta: {}", e)))?;
+                    .map_err(|e| TaskMeshError::Network(format!("falha ao ler a resposta: {}", e)))?;
+                
+                let metrics = ExecutionMetrics {
+                    network_io: ((header_bytes + body_text.len()) as u64, request_bytes as u64),
+                    ..ExecutionMetrics::default()
+                };
                 
                 let output_data = serde_json::json!({
                     "status": status.as_u16(),
@@ -645,10 +2387,11 @@ impl TaskExecutor {
                     stdout: body_text.clone(),
                     stderr: if status.is_success() { String::new() } else { format!("HTTP {}", status) },
                     output_data: Some(output_data),
-                    metrics: ExecutionMetrics::default(),
+                    metrics,
+                    log_ref: None,
                 })
             },
-            Err(e) => Err(TaskMeshError::ExecutionError(format!("Erro na requisição HTTP: {}", e))),
+            Err(e) => Err(TaskMeshError::Network(format!("requisição HTTP falhou: {}", e))),
         }
     }
     
@@ -675,6 +2418,42 @@ impl TaskExecutor {
         }
     }
     
+    /// Executa a fonte de um gerador e valida as especificações emitidas
+    ///
+    /// As especificações ficam em `output_data.generated`; a inserção das
+    /// tarefas no grafo é feita por `TaskMeshCore::expand_generator`.
+    async fn execute_generator(
+        &self,
+        worker_id: &str,
+        source_task: Task,
+        max_fan_out: Option<usize>,
+        context: ExecutionContext,
+        cancel_token: tokio_util::sync::CancellationToken,
+    ) -> TaskMeshResult<TaskResult> {
+        debug!("Executando gerador {}", source_task.id);
+        
+        let mut result = Box::pin(self.execute_task_on_worker(
+            worker_id,
+            Arc::new(source_task),
+            context,
+            cancel_token,
+        )).await?;
+        if result.exit_code != 0 {
+            return Err(TaskMeshError::ExecutionError(format!(
+                "Fonte do gerador terminou com código {}", result.exit_code
+            )));
+        }
+        
+        let specs = crate::generator::parse_specs(&result)?;
+        crate::generator::check_fan_out(
+            specs.len(),
+            max_fan_out.unwrap_or(crate::generator::DEFAULT_MAX_FAN_OUT),
+        )?;
+        
+        result.output_data = Some(serde_json::json!({ "generated": specs }));
+        Ok(result)
+    }
+    
     /// Executa workflow sequencial
     async fn execute_sequential_workflow(
         &self,
@@ -693,12 +2472,12 @@ impl TaskExecutor {
                 ));
             }
             
-            let result = self.execute_task_on_worker(
+            let result = Box::pin(self.execute_task_on_worker(
                 &context.worker_id,
-                task.clone(),
+                Arc::new(task.clone()),
                 context.clone(),
                 cancel_token.clone(),
-            ).await?;
+            )).await?;
             
             total_stdout.push_str(&result.stdout);
             total_stdout.push('\n');
@@ -720,6 +2499,7 @@ impl TaskExecutor {
             stderr: total_stderr,
             output_data: Some(output_data),
             metrics: ExecutionMetrics::default(),
+            log_ref: None,
         })
     }
     
@@ -731,12 +2511,12 @@ impl TaskExecutor {
         cancel_token: tokio_util::sync::CancellationToken,
     ) -> TaskMeshResult<TaskResult> {
         let futures: Vec<_> = tasks.iter().map(|task| {
-            self.execute_task_on_worker(
+            Box::pin(self.execute_task_on_worker(
                 &context.worker_id,
-                task.clone(),
+                Arc::new(task.clone()),
                 context.clone(),
                 cancel_token.clone(),
-            )
+            ))
         }).collect();
         
         let results = try_join_all(futures).await?;
@@ -763,6 +2543,7 @@ impl TaskExecutor {
             stderr: total_stderr,
             output_data: Some(output_data),
             metrics: ExecutionMetrics::default(),
+            log_ref: None,
         })
     }
     
@@ -783,23 +2564,21 @@ impl WorkerPool {
     /// Cria um novo pool de workers
     async fn new(max_workers: usize) -> TaskMeshResult<Self> {
         let mut workers = Vec::with_capacity(max_workers);
-        let mut available_workers = Vec::with_capacity(max_workers);
         
         for i in 0..max_workers {
-            let worker = Worker::new(format!("worker_{}", i)).await?;
-            available_workers.push(i);
-            workers.push(worker);
+            workers.push(Worker::new(format!("worker_{}", i)).await?);
         }
         
         Ok(Self {
-            workers,
-            available_workers: Arc::new(RwLock::new(available_workers)),
+            workers: RwLock::new(workers),
+            busy_workers: RwLock::new(HashSet::new()),
+            active: AtomicUsize::new(max_workers),
         })
     }
     
     /// Inicia todos os workers
     async fn start_all(&self) -> TaskMeshResult<()> {
-        for worker in &self.workers {
+        for worker in self.workers.read().await.iter() {
             worker.start().await?;
         }
         Ok(())
@@ -807,33 +2586,80 @@ impl WorkerPool {
     
     /// Para todos os workers
     async fn stop_all(&self) -> TaskMeshResult<()> {
-        for worker in &self.workers {
+        for worker in self.workers.read().await.iter() {
             worker.stop().await?;
         }
         Ok(())
     }
     
-    /// Obtém worker disponível
-    async fn get_available_worker(&self) -> Option<String> {
-        let mut available = self.available_workers.write().await;
-        if let Some(worker_idx) = available.pop() {
-            Some(self.workers[worker_idx].id.clone())
-        } else {
-            None
+    /// Ajusta o número de workers ativos
+    ///
+    /// Cria workers que faltam e para os excedentes ociosos; os excedentes
+    /// ocupados são parados quando devolvidos.
+    async fn resize(&self, size: usize) -> TaskMeshResult<()> {
+        let mut workers = self.workers.write().await;
+        for i in workers.len()..size {
+            let worker = Worker::new(format!("worker_{}", i)).await?;
+            worker.start().await?;
+            workers.push(worker);
         }
+        
+        let previous = self.active.swap(size, Ordering::SeqCst);
+        let busy = self.busy_workers.read().await;
+        for (index, worker) in workers.iter().enumerate() {
+            if index < size && index >= previous {
+                worker.start().await?;
+            } else if index >= size && !busy.contains(&index) {
+                worker.stop().await?;
+            }
+        }
+        
+        debug!("Pool de workers redimensionado de {} para {}", previous, size);
+        Ok(())
+    }
+    
+    /// Obtém worker disponível
+    ///
+    /// Prefere workers ativos; um worker em drenagem ocioso só é usado se
+    /// todos os ativos estiverem ocupados (a concorrência é limitada pelo semáforo).
+    async fn get_available_worker(&self) -> Option<String> {
+        let workers = self.workers.read().await;
+        let mut busy = self.busy_workers.write().await;
+        let active = self.active.load(Ordering::SeqCst).min(workers.len());
+        
+        let index = (0..active)
+            .chain(active..workers.len())
+            .find(|index| !busy.contains(index))?;
+        busy.insert(index);
+        Some(workers[index].id.clone())
     }
     
     /// Retorna worker para pool
     async fn return_worker(&self, worker_id: &str) {
-        if let Some(worker_idx) = self.workers.iter().position(|w| w.id == worker_id) {
-            self.available_workers.write().await.push(worker_idx);
+        let workers = self.workers.read().await;
+        if let Some(worker_idx) = workers.iter().position(|w| w.id == worker_id) {
+            self.busy_workers.write().await.remove(&worker_idx);
+            if worker_idx >= self.active.load(Ordering::SeqCst) {
+                let _ = workers[worker_idx].stop().await;
+            }
         }
     }
     
+    /// Número de workers que não estão parados
+    async fn running_count(&self) -> usize {
+        let mut running = 0;
+        for worker in self.workers.read().await.iter() {
+            if *worker.status.read().await != WorkerStatus::Stopped {
+                running += 1;
+            }
+        }
+        running
+    }
+    
     /// Obtém informações de todos os workers
     async fn get_all_worker_info(&self) -> Vec<WorkerInfo> {
         let mut info = Vec::new();
-        for worker in &self.workers {
+        for worker in self.workers.read().await.iter() {
             info.push(worker.info.read().await.clone());
         }
         info
@@ -883,6 +2709,9 @@ impl Worker {
 mod tests {
     use super::*;
     use crate::state_store::MemoryStateStore;
+    use crate::hooks::HookFailurePolicy;
+    use crate::forced_outcome::{ForcedOutcome, ForcedStatus};
+    use crate::execution_hint::ExecutionHint;
     
     #[tokio::test]
     async fn test_executor_creation() {
@@ -901,12 +2730,1274 @@ mod tests {
         
         let task = Task::new(
             "test_command".to_string(),
-            TaskDefinition::Command("echo hello".to_string()),
+            TaskDefinition::command("echo hello"),
             vec![],
         );
         
         let result = executor.execute_task(task).await;
         assert!(result.is_ok());
     }
-}
+    
+    #[tokio::test]
+    async fn test_write_behind_buffer_flush() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let buffer = WriteBehindBuffer::new(state_store.clone(), 3);
+        let task_id = TaskId::new_v4();
+        
+        buffer.push_status(task_id, TaskStatus::Scheduled).await.unwrap();
+        assert_eq!(state_store.get_task_status(&task_id).await.unwrap(), TaskStatus::Pending);
+        
+        // Flush forçado persiste as escritas pendentes
+        buffer.flush().await.unwrap();
+        assert_eq!(state_store.get_task_status(&task_id).await.unwrap(), TaskStatus::Scheduled);
+        
+        // Atingir o tamanho do lote dispara flush automático
+        buffer.push_metrics(task_id, ExecutionMetrics::default()).await.unwrap();
+        buffer.push_status(task_id, TaskStatus::Pending).await.unwrap();
+        assert!(state_store.get_metrics(&task_id).await.unwrap().is_none());
+        buffer.push_status(task_id, TaskStatus::Scheduled).await.unwrap();
+        assert!(state_store.get_metrics(&task_id).await.unwrap().is_some());
+        assert_eq!(state_store.get_task_status(&task_id).await.unwrap(), TaskStatus::Scheduled);
+    }
+    
+    #[tokio::test]
+    async fn test_write_behind_buffer_publishes_events_on_flush() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let event_bus = Arc::new(EventBus::new(state_store.clone()));
+        let buffer = WriteBehindBuffer::new(state_store.clone(), 16).with_event_bus(event_bus.clone());
+        let mut subscription = event_bus.subscribe(EventQuery::default());
+        
+        buffer.push_event(SystemEvent::new(EventType::TaskStarted, None, serde_json::json!({}))).await.unwrap();
+        buffer.push_event(SystemEvent::new(EventType::TaskCompleted, None, serde_json::json!({}))).await.unwrap();
+        assert_eq!(event_bus.stats().published, 0);
+        
+        buffer.flush().await.unwrap();
+        let first = subscription.recv().await.unwrap().unwrap();
+        let second = subscription.recv().await.unwrap().unwrap();
+        assert_eq!((first.event.event_type, second.event.event_type), (EventType::TaskStarted, EventType::TaskCompleted));
+        assert_eq!(state_store.get_events(None, None).await.unwrap().len(), 2);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_command_collects_process_metrics() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = TaskExecutor::new(1, state_store, error_handler).await.unwrap();
+        
+        let context = ExecutionContext {
+            worker_id: "worker_test".to_string(),
+            working_directory: std::env::temp_dir().to_string_lossy().to_string(),
+            environment: std::env::vars().collect(),
+            allocated_resources: ResourceAllocation::default(),
+            checkpoint_id: None,
+            progress: None,
+        };
+        
+        // Aloca ~100MB e mantém por tempo suficiente para algumas amostras
+        let result = executor.execute_command(
+            &TaskId::new_v4(),
+            "python3 -c 'import time; b = bytearray(100 * 1024 * 1024); time.sleep(0.5)'",
+            &context,
+            tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        
+        assert_eq!(result.exit_code, 0);
+        let memory_mb = result.metrics.memory_usage / (1024 * 1024);
+        assert!(memory_mb >= 90 && memory_mb < 1024, "memory_usage: {} MB", memory_mb);
+        assert!(result.metrics.cpu_usage >= 0.0);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_command_runs_with_nice_and_affinity() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = TaskExecutor::new(1, state_store, error_handler).await.unwrap();
+        
+        let mut task = Task::new("pinned".to_string(), TaskDefinition::command("true"), vec![]);
+        task.metadata.insert(process_controls::NICE_KEY.to_string(), "10".to_string());
+        task.metadata.insert(process_controls::CPU_AFFINITY_KEY.to_string(), "0".to_string());
+        let mut context = test_context();
+        process_controls::apply(&task, &mut context.allocated_resources).unwrap();
+        
+        let result = executor.execute_command(
+            &TaskId::new_v4(),
+            "grep Cpus_allowed_list /proc/self/status; ps -o nice= -p $$",
+            &context,
+            tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        
+        assert_eq!(result.exit_code, 0, "{}", result.stderr);
+        let lines: Vec<&str> = result.stdout.lines().map(str::trim).collect();
+        assert_eq!(lines, vec!["Cpus_allowed_list:\t0", "10"]);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_python_script_with_process_controls_bypasses_the_pool() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            python_pool: Some(PythonPoolConfig { size: 1, ..PythonPoolConfig::default() }),
+            ..ExecutorConfig::default()
+        };
+        let executor = TaskExecutor::with_config(config, state_store, error_handler).await.unwrap();
+        let pool = executor.python_pool().cloned().unwrap();
+        
+        let mut context = test_context();
+        context.allocated_resources.nice = Some(10);
+        let result = executor.execute_python_script(
+            &TaskId::new_v4(),
+            "import os; print(os.nice(0))",
+            &[],
+            &HashMap::new(),
+            &context,
+            tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        assert_eq!(result.stdout.trim(), "10", "{}", result.stderr);
+        assert_eq!(pool.stats().spawned, 0);
+    }
+    
+    #[cfg(all(feature = "cgroups", target_os = "linux"))]
+    #[tokio::test]
+    async fn test_memory_hog_exceeds_cgroup_limit() {
+        // SAFETY: geteuid não tem pré-condições
+        if unsafe { libc::geteuid() } != 0 {
+            eprintln!("teste ignorado: cgroups exigem root");
+            return;
+        }
+        let parent = std::path::Path::new("/sys/fs/cgroup").join(format!("taskmesh-test-{}", TaskId::new_v4()));
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            cgroups: Some(CgroupConfig::new(&parent)),
+            ..ExecutorConfig::default()
+        };
+        let executor = TaskExecutor::with_config(config, state_store, error_handler).await.unwrap();
+        let Some(cgroups) = executor.cgroups().cloned() else {
+            eprintln!("teste ignorado: cgroup v2 indisponível");
+            return;
+        };
+        
+        let mut context = test_context();
+        context.allocated_resources.memory_bytes = 64 * 1024 * 1024;
+        let result = executor.execute_command(
+            &TaskId::new_v4(),
+            "python3 -c 'b = b\"x\" * (512 * 1024 * 1024)'",
+            &context,
+            tokio_util::sync::CancellationToken::new(),
+        ).await;
+        
+        match result {
+            Err(TaskMeshError::ResourceLimitExceeded(message)) => assert!(message.contains("oom_kill="), "{}", message),
+            other => panic!("esperava ResourceLimitExceeded, obtido {:?}", other),
+        }
+        cgroups.sweep().unwrap();
+        assert_eq!(std::fs::read_dir(&parent).unwrap().flatten().filter(|entry| entry.path().is_dir()).count(), 0);
+        std::fs::remove_dir(&parent).ok();
+    }
+    
+    #[tokio::test]
+    async fn test_large_output_offloaded_to_log_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            log_dir: Some(dir.path().to_path_buf()),
+            ..ExecutorConfig::default()
+        };
+        let executor = TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap();
+        
+        let task_id = TaskId::new_v4();
+        let line = "0123456789".repeat(10);
+        let mut result = TaskResult {
+            exit_code: 0,
+            stdout: format!("{}\n", line).repeat(100_000), // ~10MB
+            stderr: String::new(),
+            output_data: None,
+            metrics: ExecutionMetrics::default(),
+            log_ref: None,
+        };
+        
+        executor.offload_output(&task_id, &mut result).await.unwrap();
+        
+        let log_ref = result.log_ref.clone().unwrap();
+        assert!(log_ref.bytes > 10_000_000);
+        assert!(result.stdout.len() <= INLINE_TAIL_BYTES);
+        
+        // A linha de status persistida continua pequena
+        let status = TaskStatus::Completed {
+            started_at: SystemTime::now(),
+            completed_at: SystemTime::now(),
+            result,
+        };
+        assert!(serde_json::to_string(&status).unwrap().len() < 8 * 1024);
+        
+        // Leitura por intervalo retorna exatamente os bytes da primeira linha
+        let log_store = executor.log_store().unwrap();
+        let first_line = log_store.read(&task_id, 0..4096).await.unwrap();
+        let first_line = String::from_utf8(first_line).unwrap();
+        let first_line = first_line.lines().next().unwrap();
+        assert!(first_line.ends_with(&format!(" stdout: {}", line)));
+        let len = first_line.len() as u64 + 1;
+        assert_eq!(log_store.read(&task_id, len..len * 2).await.unwrap().len() as u64, len);
+    }
+    
+    #[test]
+    fn test_target_platform_shell_and_process_commands() {
+        let (program, args) = TargetPlatform::Unix.shell_command("echo hi");
+        assert_eq!((program, args.as_slice()), ("sh", ["-c".to_string(), "echo hi".to_string()].as_slice()));
+        let (program, args) = TargetPlatform::Windows.shell_command("echo hi");
+        assert_eq!((program, args[0].as_str()), ("cmd", "/C"));
+        
+        assert_eq!(TargetPlatform::Windows.python_interpreter(), "py");
+        assert_eq!(TargetPlatform::Windows.kill_tree_command(42).0, "taskkill");
+        assert_eq!(TargetPlatform::Unix.kill_tree_command(42).1.last().unwrap(), "-42");
+    }
+    
+    #[test]
+    fn test_target_platform_env_merge() {
+        let base: HashMap<String, String> = [("PATH".to_string(), "a".to_string())].into();
+        let overrides: HashMap<String, String> = [("Path".to_string(), "b".to_string())].into();
+        
+        let windows = TargetPlatform::Windows.merge_env(&base, &overrides);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows["Path"], "b");
+        
+        let unix = TargetPlatform::Unix.merge_env(&base, &overrides);
+        assert_eq!(unix.len(), 2);
+    }
+    
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn test_windows_python_script_in_path_with_spaces() {
+        let dir = tempfile::Builder::new().prefix("task mesh ").tempdir().unwrap();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            default_working_dir: dir.path().to_string_lossy().to_string(),
+            ..ExecutorConfig::default()
+        };
+        let executor = TaskExecutor::with_config(config, state_store, error_handler).await.unwrap();
+        
+        let context = ExecutionContext {
+            worker_id: "worker_test".to_string(),
+            working_directory: dir.path().to_string_lossy().to_string(),
+            environment: std::env::vars().collect(),
+            allocated_resources: ResourceAllocation::default(),
+            checkpoint_id: None,
+            progress: None,
+        };
+        let result = executor.execute_python_script(
+            &TaskId::new_v4(),
+            "import sys; print(sys.argv[1])",
+            &["com espaço".to_string()],
+            &HashMap::new(),
+            &context,
+            tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        assert_eq!(result.stdout.trim(), "com espaço");
+    }
+    
+    fn test_context() -> ExecutionContext {
+        ExecutionContext {
+            worker_id: "worker_test".to_string(),
+            working_directory: std::env::temp_dir().to_string_lossy().to_string(),
+            environment: std::env::vars().collect(),
+            allocated_resources: ResourceAllocation::default(),
+            checkpoint_id: None,
+            progress: None,
+        }
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_exec_passes_arguments_literally() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let dir = tempfile::tempdir().unwrap();
+        let canary = dir.path().join("canary");
+        std::fs::write(&canary, "").unwrap();
+        
+        // Helper que imprime cada argumento recebido em uma linha
+        let echo_args = dir.path().join("echo-args");
+        std::fs::write(&echo_args, "#!/bin/sh\nfor arg in \"$@\"; do printf '%s\\n' \"$arg\"; done\n").unwrap();
+        std::fs::set_permissions(&echo_args, std::fs::Permissions::from_mode(0o755)).unwrap();
+        
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = TaskExecutor::new(1, state_store, error_handler).await.unwrap();
+        
+        let hostile = format!("x; rm -rf {}", canary.display());
+        let task = Task::new(
+            "exec_literal".to_string(),
+            TaskDefinition::exec(echo_args.to_string_lossy(), [hostile.as_str(), "$HOME", "a b"]),
+            vec![],
+        );
+        
+        let result = executor.execute_task_on_worker(
+            "worker_test", Arc::new(task), test_context(), tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        assert_eq!(result.exit_code, 0);
+        let lines: Vec<&str> = result.stdout.lines().collect();
+        assert_eq!(lines, vec![hostile.as_str(), "$HOME", "a b"]);
+        assert!(canary.exists());
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_with_custom_shell_pipefail() {
+        if resolve_program("bash").is_none() {
+            return;
+        }
+        
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = TaskExecutor::new(1, state_store, error_handler).await.unwrap();
+        
+        let pipefail = Task::new(
+            "pipefail".to_string(),
+            TaskDefinition::shell_command("bash", "set -o pipefail; false | true"),
+            vec![],
+        );
+        let result = executor.execute_task_on_worker(
+            "worker_test", Arc::new(pipefail), test_context(), tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        assert_ne!(result.exit_code, 0);
+        
+        let default_semantics = Task::new(
+            "no_pipefail".to_string(),
+            TaskDefinition::shell_command("bash", "false | true"),
+            vec![],
+        );
+        let result = executor.execute_task_on_worker(
+            "worker_test", Arc::new(default_semantics), test_context(), tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        assert_eq!(result.exit_code, 0);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_generator_emits_specs() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = TaskExecutor::new(1, state_store, error_handler).await.unwrap();
+        
+        let source = TaskDefinition::command(
+            r#"printf '[{"name":"e1","definition":{"Command":{"command":"echo 1"}}},{"name":"e2","definition":{"Command":{"command":"echo 2"}}}]'"#,
+        );
+        let generator = Task::new("gen".to_string(), TaskDefinition::generator(source.clone(), None), vec![]);
+        let result = executor.execute_task_on_worker(
+            "worker_test", Arc::new(generator), test_context(), tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        
+        let specs = crate::generator::parse_specs(&result).unwrap();
+        assert_eq!(specs.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["e1", "e2"]);
+        
+        let limited = Task::new("gen".to_string(), TaskDefinition::generator(source, Some(1)), vec![]);
+        let result = executor.execute_task_on_worker(
+            "worker_test", Arc::new(limited), test_context(), tokio_util::sync::CancellationToken::new(),
+        ).await;
+        assert!(matches!(result, Err(TaskMeshError::FanOutExceeded { generated: 2, limit: 1 })));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_live_concurrency_change() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = Arc::new(TaskExecutor::new(4, state_store, error_handler).await.unwrap());
+        
+        // Amostra continuamente o número de tarefas em execução
+        let peak = Arc::new(AtomicUsize::new(0));
+        let sampler = {
+            let (executor, peak) = (executor.clone(), peak.clone());
+            tokio::spawn(async move {
+                loop {
+                    let running = executor.running_tasks.len();
+                    peak.fetch_max(running, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            })
+        };
+        let run_batch = |count: usize| {
+            let executor = executor.clone();
+            (0..count)
+                .map(|i| {
+                    let executor = executor.clone();
+                    let task = Task::new(format!("sleep_{}", i), TaskDefinition::command("sleep 0.3"), vec![]);
+                    tokio::spawn(async move { executor.handle_execute_task(task.id, Arc::new(task)).await })
+                })
+                .collect::<Vec<_>>()
+        };
+        
+        // 4 tarefas em paralelo; a redução não interrompe nenhuma delas
+        let in_flight = run_batch(4);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(executor.running_tasks.len(), 4);
+        assert_eq!(executor.set_max_concurrency(1).await.unwrap(), 4);
+        let queued = run_batch(3);
+        for handle in in_flight {
+            handle.await.unwrap().unwrap();
+        }
+        
+        // Após as tarefas em voo, apenas uma por vez
+        peak.store(0, Ordering::SeqCst);
+        for handle in queued {
+            handle.await.unwrap().unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+        
+        // Aumentar de volta restaura o paralelismo
+        executor.set_max_concurrency(4).await.unwrap();
+        peak.store(0, Ordering::SeqCst);
+        for handle in run_batch(4) {
+            handle.await.unwrap().unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+        assert_eq!(executor.get_worker_info().await.len(), 4);
+        
+        sampler.abort();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_abandoned_dispatch_releases_the_group_slot() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = Arc::new(TaskExecutor::new(1, state_store, error_handler).await.unwrap());
+        
+        // A única permissão fica com uma tarefa sem grupo
+        let busy = Task::new("ocupada".to_string(), TaskDefinition::command("sleep 0.3"), vec![]);
+        let running = {
+            let executor = executor.clone();
+            tokio::spawn(async move { executor.handle_execute_task(busy.id, Arc::new(busy)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        
+        // A tarefa do grupo obtém a vaga e espera a permissão; o dispatch é abandonado
+        let grouped = Task::new("migracao".to_string(), TaskDefinition::command("true"), vec![]).with_concurrency_group("db");
+        let waiting = {
+            let executor = executor.clone();
+            tokio::spawn(async move { executor.handle_execute_task(grouped.id, Arc::new(grouped)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(executor.concurrency_groups.blocked("db", &TaskId::new_v4()), Some((1, 1)));
+        waiting.abort();
+        let _ = waiting.await;
+        
+        assert_eq!(executor.concurrency_groups.blocked("db", &TaskId::new_v4()), None);
+        running.await.unwrap().unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_concurrency_group_serializes_running_windows() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = Arc::new(TaskExecutor::new(3, state_store.clone(), error_handler).await.unwrap());
+        
+        let tasks: Vec<Task> = (0..3)
+            .map(|i| {
+                Task::new(format!("migracao_{}", i), TaskDefinition::command("sleep 0.2"), vec![])
+                    .with_concurrency_group("db")
+            })
+            .collect();
+        let handles: Vec<_> = tasks.iter()
+            .cloned()
+            .map(|task| {
+                let executor = executor.clone();
+                tokio::spawn(async move { executor.handle_execute_task(task.id, Arc::new(task)).await })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+        
+        // Cada tentativa começa depois que a anterior terminou
+        let mut windows = Vec::new();
+        for task in &tasks {
+            let attempts = state_store.list_attempts(&task.id).await.unwrap();
+            let [attempt] = attempts.as_slice() else { panic!("tentativas: {:?}", attempts) };
+            let TaskStatus::Completed { completed_at, .. } = attempt.status else { panic!("{:?}", attempt.status) };
+            windows.push((attempt.started_at, completed_at));
+        }
+        windows.sort();
+        for pair in windows.windows(2) {
+            assert!(pair[0].1 <= pair[1].0, "janelas sobrepostas: {:?}", pair);
+        }
+    }
+    
+    #[test]
+    fn test_shell_args() {
+        assert_eq!(shell_args("/usr/bin/bash"), &["-c"]);
+        assert_eq!(shell_args("pwsh"), &["-NoProfile", "-NonInteractive", "-Command"]);
+        assert_eq!(shell_args("C:\\Windows\\System32\\cmd.exe"), &["/C"]);
+    }
+    
+    /// Aguarda um status final da tarefa no StateStore
+    async fn wait_finished(state_store: &MemoryStateStore, task_id: &TaskId, within: Duration) -> TaskStatus {
+        let deadline = Instant::now() + within;
+        loop {
+            let status = state_store.get_task_status(task_id).await.unwrap();
+            if matches!(status, TaskStatus::Completed { .. } | TaskStatus::Failed { .. }) || Instant::now() > deadline {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_scratch_limit_fails_task_and_removes_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            write_behind: false,
+            scratch: Some(ScratchConfig {
+                limit_bytes: Some(1024 * 1024),
+                check_interval: Duration::from_millis(20),
+                ..ScratchConfig::new(dir.path())
+            }),
+            ..ExecutorConfig::default()
+        };
+        let executor = Arc::new(TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap());
+        executor.start().await.unwrap();
+        
+        // 10MB sob um limite de 1MB; o sleep só termina se o limite não agir
+        let task = Task::new(
+            "fill_scratch".to_string(),
+            TaskDefinition::command("head -c 10485760 /dev/zero > \"$TASKMESH_SCRATCH/big\" && sleep 5"),
+            vec![],
+        );
+        let task_id = executor.execute_task(task).await.unwrap();
+        
+        match wait_finished(&state_store, &task_id, Duration::from_secs(4)).await {
+            TaskStatus::Failed { error, .. } => assert!(error.contains("Limite de recurso excedido"), "{}", error),
+            other => panic!("status inesperado: {:?}", other),
+        }
+        assert!(!executor.scratch().unwrap().path(&task_id).exists());
+        executor.shutdown().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_failed_task_scratch_retained_until_sweep() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            write_behind: false,
+            scratch: Some(ScratchConfig {
+                retain_on_failure: true,
+                retention: Duration::from_millis(300),
+                ..ScratchConfig::new(dir.path())
+            }),
+            ..ExecutorConfig::default()
+        };
+        let executor = Arc::new(TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap());
+        executor.start().await.unwrap();
+        
+        let task = Task::new("debug_me".to_string(), TaskDefinition::command("echo estado > state.txt; exit 3"), vec![]);
+        let task_id = executor.execute_task(task).await.unwrap();
+        assert!(matches!(
+            wait_finished(&state_store, &task_id, Duration::from_secs(5)).await,
+            TaskStatus::Completed { result: TaskResult { exit_code: 3, .. }, .. }
+        ));
+        
+        let scratch = executor.scratch().unwrap();
+        let retained = scratch.path(&task_id).join("state.txt");
+        assert!(retained.exists());
+        assert_eq!(scratch.sweep().await.unwrap(), 0);
+        
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(scratch.sweep().await.unwrap(), 1);
+        assert!(!scratch.path(&task_id).exists());
+        executor.shutdown().await.unwrap();
+    }
+
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_retry_backoff_frees_worker_for_queued_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let scheduler = Arc::new(Scheduler::new(crate::scheduler::SchedulingHeuristic::FIFO));
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff_strategy: BackoffStrategy::Fixed { delay: Duration::from_millis(300) },
+            retry_conditions: vec![RetryCondition::ExitCode(vec![1])],
+        };
+        let config = ExecutorConfig { max_workers: 1, write_behind: false, ..ExecutorConfig::default() };
+        let executor = Arc::new(
+            TaskExecutor::with_config(config, state_store.clone(), error_handler)
+                .await
+                .unwrap()
+                .with_retries(scheduler.clone(), policy),
+        );
+        executor.start().await.unwrap();
+        
+        // Falha na primeira tentativa e conclui na segunda
+        let marker = dir.path().join("first-attempt");
+        let command = format!("test -f {0} || {{ touch {0}; exit 1; }}", marker.display());
+        let flaky = Arc::new(Task::new("flaky".to_string(), TaskDefinition::command(command), vec![]));
+        executor.execute_task(flaky.clone()).await.unwrap();
+        let other_id = executor
+            .execute_task(Task::new("other".to_string(), TaskDefinition::command("true"), vec![]))
+            .await
+            .unwrap();
+        
+        // O único worker executa a outra tarefa durante o backoff
+        let TaskStatus::Completed { completed_at, .. } = wait_finished(&state_store, &other_id, Duration::from_secs(5)).await else {
+            panic!("a outra tarefa não concluiu");
+        };
+        let TaskStatus::AwaitingRetry { attempt: 1, next_attempt_at } = state_store.get_task_status(&flaky.id).await.unwrap() else {
+            panic!("tarefa não aguarda retry");
+        };
+        assert!(completed_at < next_attempt_at);
+        let resources = ResourceAllocation::default();
+        assert_eq!(scheduler.get_next_task(&resources).await, None);
+        assert_eq!(scheduler.queue_depth(), 1);
+        
+        tokio::time::sleep(next_attempt_at.duration_since(SystemTime::now()).unwrap_or_default()).await;
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(flaky.id));
+        executor.execute_task(flaky.clone()).await.unwrap();
+        assert!(matches!(
+            wait_finished(&state_store, &flaky.id, Duration::from_secs(5)).await,
+            TaskStatus::Completed { result: TaskResult { exit_code: 0, .. }, .. }
+        ));
+        
+        // Quantas vezes `running` aparece (pendente e no worker) varia entre execuções
+        let transitions = state_store.get_status_history(&flaky.id).await.unwrap();
+        let mut history: Vec<&str> = transitions
+            .iter()
+            .map(|transition| crate::report::status_label(&transition.status))
+            .collect();
+        history.dedup();
+        assert_eq!(history, ["running", "failed", "awaiting_retry", "running", "completed"]);
+        
+        // `Failed` leva o início da tentativa que falhou, não o momento do reagendamento
+        let failed = transitions.iter().position(|transition| matches!(transition.status, TaskStatus::Failed { .. })).unwrap();
+        let TaskStatus::Running { started_at: first_started_at, .. } = transitions[failed - 1].status else { unreachable!() };
+        let TaskStatus::Failed { started_at, failed_at, .. } = transitions[failed].status else { unreachable!() };
+        assert_eq!(started_at, first_started_at);
+        assert!(started_at < failed_at);
+        assert!(executor.retry_attempts.is_empty());
+        executor.shutdown().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_each_attempt_keeps_its_own_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let scheduler = Arc::new(Scheduler::new(crate::scheduler::SchedulingHeuristic::FIFO));
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff_strategy: BackoffStrategy::Fixed { delay: Duration::from_millis(50) },
+            retry_conditions: vec![RetryCondition::ExitCode(vec![1])],
+        };
+        let config = ExecutorConfig { max_workers: 1, write_behind: false, ..ExecutorConfig::default() };
+        let executor = Arc::new(
+            TaskExecutor::with_config(config, state_store.clone(), error_handler)
+                .await
+                .unwrap()
+                .with_retries(scheduler.clone(), policy),
+        );
+        executor.start().await.unwrap();
+        
+        // Tentativa n dorme 0.n s e só a terceira conclui
+        let counter = dir.path().join("attempts");
+        let command = format!(
+            "n=$(cat {0} 2>/dev/null || echo 0); n=$((n+1)); echo $n > {0}; sleep 0.$n; [ $n -ge 3 ]",
+            counter.display()
+        );
+        let task = Arc::new(Task::new("instavel".to_string(), TaskDefinition::command(command), vec![]));
+        executor.execute_task(task.clone()).await.unwrap();
+        for _ in 0..2 {
+            let next_attempt_at = tokio::time::timeout(Duration::from_secs(5), async {
+                loop {
+                    if let TaskStatus::AwaitingRetry { next_attempt_at, .. } = state_store.get_task_status(&task.id).await.unwrap() {
+                        break next_attempt_at;
+                    }
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            }).await.unwrap();
+            tokio::time::sleep(next_attempt_at.duration_since(SystemTime::now()).unwrap_or_default()).await;
+            assert_eq!(scheduler.get_next_task(&ResourceAllocation::default()).await, Some(task.id));
+            executor.execute_task(task.clone()).await.unwrap();
+        }
+        assert!(matches!(wait_finished(&state_store, &task.id, Duration::from_secs(5)).await, TaskStatus::Completed { .. }));
+        
+        let attempts = state_store.list_attempts(&task.id).await.unwrap();
+        let summary: Vec<(u32, &str, i32)> = attempts
+            .iter()
+            .map(|attempt| (
+                attempt.attempt,
+                crate::report::status_label(&attempt.status),
+                attempt.result.as_ref().unwrap().exit_code,
+            ))
+            .collect();
+        assert_eq!(summary, [(1, "failed", 1), (2, "failed", 1), (3, "completed", 0)]);
+        let durations: Vec<Duration> = attempts
+            .iter()
+            .map(|attempt| attempt.metrics.as_ref().unwrap().execution_time)
+            .collect();
+        assert!(durations[0] < durations[1] && durations[1] < durations[2], "{:?}", durations);
+        assert_eq!(state_store.list_manifests(&task.id).await.unwrap().len(), 3);
+        assert!(matches!(state_store.get_task_status(&task.id).await.unwrap(), TaskStatus::Completed { .. }));
+        executor.shutdown().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_script_progress_updates_running_status_and_events() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            write_behind: false,
+            // Só a primeira atualização passa pelo limite; a última é gravada ao final
+            progress_interval: Duration::from_secs(60),
+            ..ExecutorConfig::default()
+        };
+        let executor = Arc::new(TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap());
+        executor.start().await.unwrap();
+        
+        let script = (1..=3)
+            .map(|step| format!(
+                r#"echo '{{"taskmesh_progress": {{"percent": {}, "message": "etapa {}/3"}}}}' >> "$TASKMESH_PROGRESS_FILE"; sleep 0.3"#,
+                step * 30,
+                step
+            ))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let task = Task::new("progresso".to_string(), TaskDefinition::command(&script), vec![]);
+        let task_id = executor.execute_task(task).await.unwrap();
+        assert!(matches!(wait_finished(&state_store, &task_id, Duration::from_secs(5)).await, TaskStatus::Completed { .. }));
+        
+        let reported: Vec<TaskProgress> = state_store
+            .get_status_history(&task_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|transition| match transition.status {
+                TaskStatus::Running { progress, .. } => progress,
+                _ => None,
+            })
+            .collect();
+        let percents: Vec<_> = reported.iter().map(|progress| progress.percent).collect();
+        assert_eq!(percents, vec![Some(30), Some(90)]);
+        assert_eq!(reported[1].message.as_deref(), Some("etapa 3/3"));
+        
+        let events: Vec<_> = state_store
+            .get_events(None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|event| event.event_type == EventType::TaskProgress)
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].data["progress"]["percent"], 90);
+        executor.shutdown().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_python_task_manifest_records_script_and_interpreter() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig { max_workers: 1, write_behind: false, ..ExecutorConfig::default() };
+        let executor = Arc::new(TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap());
+        executor.start().await.unwrap();
+        
+        let script = "import os\nprint(len(os.environ['API_TOKEN']))";
+        let mut task_ids = Vec::new();
+        for name in ["relatorio", "relatorio-2"] {
+            let definition = TaskDefinition::PythonScript {
+                script: Arc::from(script),
+                args: vec![],
+                env: HashMap::from([("API_TOKEN".to_string(), "s3cr3t-valor".to_string())]),
+            };
+            let task_id = executor.execute_task(Task::new(name.to_string(), definition, vec![])).await.unwrap();
+            assert!(matches!(wait_finished(&state_store, &task_id, Duration::from_secs(10)).await, TaskStatus::Completed { .. }));
+            task_ids.push(task_id);
+        }
+        
+        let manifest = state_store.get_manifest(&task_ids[0], 1).await.unwrap().unwrap();
+        assert_eq!(manifest.script_sha256, Some(manifest::sha256_hex(script.as_bytes())));
+        assert!(manifest.tool_versions["python3"].starts_with("Python 3"));
+        assert!(manifest.env_vars.contains(&"API_TOKEN".to_string()));
+        assert!(!serde_json::to_string(&manifest).unwrap().contains("s3cr3t-valor"));
+        assert_eq!(manifest.orchestrator_version, manifest::ORCHESTRATOR_VERSION);
+        // Versão do interpretador sondada uma única vez
+        assert_eq!(executor.tool_versions.len(), 1);
+        assert!(state_store.get_manifest(&task_ids[1], 1).await.unwrap().is_some());
+        executor.shutdown().await.unwrap();
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sidecar_serves_main_command_and_is_stopped() {
+        if resolve_program("python3").is_none() || resolve_program("curl").is_none() {
+            return;
+        }
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig { max_workers: 1, log_dir: Some(dir.path().to_path_buf()), ..ExecutorConfig::default() };
+        let executor = TaskExecutor::with_config(config, state_store, error_handler).await.unwrap();
+        
+        let web = crate::sidecar::SidecarSpec::new("web", format!("exec python3 -m http.server {} --bind 127.0.0.1", port))
+            .with_readiness(crate::sidecar::ReadinessProbe::tcp(port));
+        let task = Task::new(
+            "com-sidecar".to_string(),
+            TaskDefinition::command("curl -sf -o /dev/null http://127.0.0.1:$TASKMESH_SIDECAR_WEB_PORT/"),
+            vec![],
+        ).with_sidecar(web);
+        let task_id = task.id;
+        
+        let result = executor.execute_task_on_worker(
+            "worker_test", Arc::new(task), test_context(), tokio_util::sync::CancellationToken::new(),
+        ).await.unwrap();
+        assert_eq!(result.exit_code, 0, "{}", result.stderr);
+        
+        // Nenhum processo do sidecar continua escutando
+        assert!(tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_err());
+        let log = executor.log_store().unwrap().read(&task_id, 0..1 << 20).await.unwrap();
+        assert!(String::from_utf8_lossy(&log).contains("[sidecar:web] stderr: "));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sidecar_not_ready_fails_task() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let executor = TaskExecutor::new(1, state_store, error_handler).await.unwrap();
+        
+        let mut never_ready = crate::sidecar::SidecarSpec::new("lento", "sleep 30")
+            .with_readiness(crate::sidecar::ReadinessProbe::tcp(port));
+        never_ready.ready_timeout_ms = 300;
+        never_ready.stop_grace_ms = 100;
+        let task = Task::new("sem-sidecar".to_string(), TaskDefinition::command("echo nunca"), vec![])
+            .with_sidecar(never_ready);
+        
+        let started = Instant::now();
+        let result = executor.execute_task_on_worker(
+            "worker_test", Arc::new(task), test_context(), tokio_util::sync::CancellationToken::new(),
+        ).await;
+        assert!(matches!(result, Err(TaskMeshError::SidecarNotReady { ref name, .. }) if name == "lento"), "{:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+    
+    /// Executor sem write-behind com os hooks globais dados
+    async fn hooks_executor(global: Vec<HookSpec>) -> (TaskExecutor, Arc<MemoryStateStore>) {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig { max_workers: 1, write_behind: false, hooks: global, ..ExecutorConfig::default() };
+        let executor = TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap();
+        (executor, state_store)
+    }
+    
+    /// Hook que acrescenta `line` ao arquivo `log`
+    fn append_hook(on: HookPhase, log: &std::path::Path, line: &str) -> HookSpec {
+        HookSpec::new(on, TaskDefinition::command(format!("echo {} >> {}", line, log.display())))
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hooks_run_in_phase_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("ordem");
+        let (executor, state_store) = hooks_executor(vec![append_hook(HookPhase::Before, &log, "global")]).await;
+        
+        let run = |command: &str| {
+            Task::new("com-hooks".to_string(), TaskDefinition::command(format!("echo main >> {}; {}", log.display(), command)), vec![])
+                .with_hook(append_hook(HookPhase::Finally, &log, "finally"))
+                .with_hook(append_hook(HookPhase::AfterFailure, &log, "after_failure"))
+                .with_hook(append_hook(HookPhase::AfterSuccess, &log, "after_success"))
+                .with_hook(append_hook(HookPhase::Before, &log, "before"))
+        };
+        for (command, after) in [("true", "after_success"), ("exit 2", "after_failure")] {
+            std::fs::remove_file(&log).ok();
+            let task = run(command);
+            executor.handle_execute_task(task.id, Arc::new(task.clone())).await.unwrap();
+            let lines = std::fs::read_to_string(&log).unwrap();
+            assert_eq!(lines.lines().collect::<Vec<_>>(), ["global", "before", "main", after, "finally"]);
+            
+            let attempts = state_store.list_attempts(&task.id).await.unwrap();
+            let labels: Vec<&str> = attempts[0].hooks.iter().map(|run| run.label.as_str()).collect();
+            let after_label = format!("{}[0]", after);
+            assert_eq!(labels, ["before[0]", "before[1]", after_label.as_str(), "finally[0]"]);
+            assert!(attempts[0].hooks.iter().all(HookRun::succeeded));
+        }
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hook_failure_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("main");
+        let (executor, state_store) = hooks_executor(Vec::new()).await;
+        let main = TaskDefinition::command(format!("touch {}", marker.display()));
+        let failing = |on: HookPhase, policy: HookFailurePolicy| {
+            HookSpec::new(on, TaskDefinition::command("exit 1")).with_failure_policy(policy)
+        };
+        let cases = [
+            (failing(HookPhase::Before, HookFailurePolicy::WarnOnly), true, true),
+            (failing(HookPhase::Before, HookFailurePolicy::FailTask), false, false),
+            (failing(HookPhase::AfterSuccess, HookFailurePolicy::FailTask), true, false),
+            (failing(HookPhase::Finally, HookFailurePolicy::FailTask), true, true),
+        ];
+        for (hook, main_runs, completes) in cases {
+            std::fs::remove_file(&marker).ok();
+            let task = Task::new("politica".to_string(), main.clone(), vec![]).with_hook(hook.clone());
+            executor.handle_execute_task(task.id, Arc::new(task.clone())).await.unwrap();
+            let status = state_store.get_task_status(&task.id).await.unwrap();
+            assert_eq!(matches!(status, TaskStatus::Completed { .. }), completes, "{:?}", hook);
+            assert_eq!(marker.exists(), main_runs, "{:?}", hook);
+        }
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hook_timeout_is_independent_of_the_task() {
+        let (executor, state_store) = hooks_executor(Vec::new()).await;
+        let task = Task::new("curta".to_string(), TaskDefinition::command("true"), vec![])
+            .with_timeout(Duration::from_millis(200))
+            .with_hook(HookSpec::new(HookPhase::Finally, TaskDefinition::command("sleep 0.5")))
+            .with_hook(HookSpec::new(HookPhase::Finally, TaskDefinition::command("sleep 5")).with_timeout(Duration::from_millis(100)));
+        executor.handle_execute_task(task.id, Arc::new(task.clone())).await.unwrap();
+        
+        // Sem limite próprio o hook não herda os 200ms da tarefa; com limite, respeita o dele
+        let attempts = state_store.list_attempts(&task.id).await.unwrap();
+        assert!(attempts[0].hooks[0].succeeded(), "{:?}", attempts[0].hooks[0].error);
+        assert!(!attempts[0].hooks[1].succeeded());
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_after_failure_hook_receives_exit_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let summary = dir.path().join("resumo");
+        let (executor, state_store) = hooks_executor(Vec::new()).await;
+        let hook = HookSpec::new(
+            HookPhase::AfterFailure,
+            TaskDefinition::command(format!(
+                "echo $TASKMESH_HOOK_PHASE:$TASKMESH_HOOK_STATUS:$TASKMESH_HOOK_EXIT_CODE > {}",
+                summary.display()
+            )),
+        );
+        let task = Task::new("falha".to_string(), TaskDefinition::command("exit 3"), vec![]).with_hook(hook);
+        executor.handle_execute_task(task.id, Arc::new(task.clone())).await.unwrap();
+        
+        assert_eq!(std::fs::read_to_string(&summary).unwrap().trim(), "after_failure:failure:3");
+        let attempts = state_store.list_attempts(&task.id).await.unwrap();
+        assert_eq!(attempts[0].hooks[0].result.as_ref().map(|result| result.exit_code), Some(0));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_forced_outcomes_follow_the_real_failure_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let summary = dir.path().join("resumo");
+        let marker = dir.path().join("executou");
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig { max_workers: 1, write_behind: false, allow_forced_outcomes: true, ..ExecutorConfig::default() };
+        let executor = TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap();
+        let hook = HookSpec::new(
+            HookPhase::AfterFailure,
+            TaskDefinition::command(format!("echo \"$TASKMESH_HOOK_ERROR\" > {}", summary.display())),
+        );
+        let task = |outcome: ForcedOutcome| {
+            Task::new("forcada".to_string(), TaskDefinition::command(format!("touch {}", marker.display())), vec![])
+                .with_hook(hook.clone())
+                .with_forced_outcome(outcome)
+        };
+        
+        // Timeout: mesmo erro, status, evento e hook de um processo que estoura o limite
+        let timed_out = task(ForcedOutcome::new(ForcedStatus::Timeout).with_after(Duration::from_millis(30)));
+        executor.handle_execute_task(timed_out.id, Arc::new(timed_out.clone())).await.unwrap();
+        let expected = TaskMeshError::ExecutionTimeout(timed_out.id).to_string();
+        let history = state_store.get_status_history(&timed_out.id).await.unwrap();
+        assert!(matches!(history[0].status, TaskStatus::Running { .. }), "{:?}", history);
+        match &history.last().unwrap().status {
+            TaskStatus::Failed { error, .. } => assert_eq!(error, &expected),
+            other => panic!("status inesperado: {:?}", other),
+        }
+        let events = state_store
+            .query_events(&EventQuery { task_id: Some(timed_out.id), ..EventQuery::default() })
+            .await
+            .unwrap()
+            .events;
+        assert_eq!(events.iter().map(|event| event.event_type.clone()).collect::<Vec<_>>(), vec![EventType::TaskFailed]);
+        assert_eq!(std::fs::read_to_string(&summary).unwrap().trim(), expected);
+        let attempts = state_store.list_attempts(&timed_out.id).await.unwrap();
+        assert!(matches!(attempts[0].status, TaskStatus::Failed { .. }), "{:?}", attempts[0].status);
+        assert!(!marker.exists());
+        
+        // Fail termina como um comando com código de saída diferente de zero
+        let failed = task(ForcedOutcome::new(ForcedStatus::Fail).with_exit_code(7).with_stderr("disco cheio"));
+        executor.handle_execute_task(failed.id, Arc::new(failed.clone())).await.unwrap();
+        match state_store.get_task_status(&failed.id).await.unwrap() {
+            TaskStatus::Completed { result, .. } => assert_eq!((result.exit_code, result.stderr.as_str()), (7, "disco cheio")),
+            other => panic!("status inesperado: {:?}", other),
+        }
+        assert!(!marker.exists());
+        
+        let disabled = TaskExecutor::with_config(
+            ExecutorConfig { max_workers: 1, write_behind: false, ..ExecutorConfig::default() },
+            state_store.clone(),
+            Arc::new(ErrorHandler::new(RetryPolicy::default())),
+        ).await.unwrap();
+        let refused = task(ForcedOutcome::new(ForcedStatus::Panic));
+        disabled.handle_execute_task(refused.id, Arc::new(refused.clone())).await.unwrap();
+        match state_store.get_task_status(&refused.id).await.unwrap() {
+            TaskStatus::Failed { error, .. } => assert!(error.contains("allow_forced_outcomes"), "{}", error),
+            other => panic!("status inesperado: {:?}", other),
+        }
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_rule_sends_matching_failures_to_dead_letters() {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let scheduler = Arc::new(Scheduler::new(crate::scheduler::SchedulingHeuristic::FIFO));
+        let default_policy = RetryPolicy {
+            max_attempts: 3,
+            backoff_strategy: BackoffStrategy::Fixed { delay: Duration::from_secs(60) },
+            retry_conditions: vec![RetryCondition::StderrContains(vec!["pânico".to_string()])],
+        };
+        let rules = RetryRules::new(
+            vec![crate::retry_rules::RetryRule {
+                name: Some("fragil-sem-retry".to_string()),
+                matcher: crate::retry_rules::ErrorMatcher::Category(crate::retry_rules::ErrorCategory::Execution),
+                tags: vec!["fragil".to_string()],
+                action: crate::retry_rules::RetryDirective::NoRetry,
+            }],
+            default_policy,
+        ).unwrap();
+        let config = ExecutorConfig { max_workers: 1, write_behind: false, allow_forced_outcomes: true, ..ExecutorConfig::default() };
+        let executor = TaskExecutor::with_config(config, state_store.clone(), error_handler)
+            .await
+            .unwrap()
+            .with_retries(scheduler.clone(), rules);
+        let task = |tags: Vec<String>| {
+            Task::new("panico".to_string(), TaskDefinition::command("true"), vec![])
+                .with_tags(tags)
+                .with_forced_outcome(ForcedOutcome::new(ForcedStatus::Panic))
+        };
+        let failed_event = |task_id: TaskId| {
+            let state_store = state_store.clone();
+            async move {
+                let events = state_store
+                    .query_events(&EventQuery { task_id: Some(task_id), ..EventQuery::default() })
+                    .await
+                    .unwrap()
+                    .events;
+                events.into_iter().find(|event| event.event_type == EventType::TaskFailed).unwrap().data
+            }
+        };
+
+        // A regra casa: falha definitiva na primeira tentativa
+        let fragile = task(vec!["fragil".to_string()]);
+        let explained = executor.explain_failure(&fragile, &Err(TaskMeshError::ExecutionError("x".to_string()))).unwrap();
+        assert_eq!(explained.rule.as_deref(), Some("fragil-sem-retry"));
+        executor.handle_execute_task(fragile.id, Arc::new(fragile.clone())).await.unwrap();
+        assert!(matches!(state_store.get_task_status(&fragile.id).await.unwrap(), TaskStatus::Failed { retry_count: 0, .. }));
+        assert_eq!(scheduler.queue_depth(), 0);
+        let decision = failed_event(fragile.id).await["retry_decision"].clone();
+        assert_eq!(decision["rule"], "fragil-sem-retry");
+        assert_eq!((decision["category"].as_str(), decision["directive"].as_str()), (Some("execution"), Some("no_retry")));
+        assert_eq!(failed_event(fragile.id).await["dead_letter"], true);
+        let dead_letters = executor.dead_letters().list();
+        assert_eq!(dead_letters.iter().map(|letter| letter.task_id).collect::<Vec<_>>(), vec![fragile.id]);
+        assert_eq!(dead_letters[0].decision.rule.as_deref(), Some("fragil-sem-retry"));
+
+        // Sem a tag vale a política padrão, que repete
+        let plain = task(Vec::new());
+        executor.handle_execute_task(plain.id, Arc::new(plain.clone())).await.unwrap();
+        assert!(matches!(state_store.get_task_status(&plain.id).await.unwrap(), TaskStatus::AwaitingRetry { attempt: 1, .. }));
+        assert_eq!(scheduler.queue_depth(), 1);
+        assert_eq!(executor.dead_letters().len(), 1);
+        let decision = failed_event(plain.id).await["retry_decision"].clone();
+        assert!(decision["rule"].is_null() && decision["retryable"] == true, "{}", decision);
+    }
 
+    /// Executor iniciado com `until_cancelled` (coopera) e `stubborn` (ignora o cancelamento)
+    async fn function_executor(grace_period: Duration) -> (Arc<TaskExecutor>, Arc<MemoryStateStore>) {
+        let state_store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let error_handler = Arc::new(ErrorHandler::new(RetryPolicy::default()));
+        let config = ExecutorConfig {
+            max_workers: 1,
+            write_behind: false,
+            cancel_grace_period: grace_period,
+            ..ExecutorConfig::default()
+        };
+        let executor = Arc::new(TaskExecutor::with_config(config, state_store.clone(), error_handler).await.unwrap());
+        executor.functions().register("until_cancelled", |ctx: FnContext, _args| async move {
+            while !ctx.is_cancelled() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Ok(serde_json::Value::Null)
+        });
+        executor.functions().register("stubborn", |_ctx: FnContext, _args| async move {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            Ok(serde_json::Value::Null)
+        });
+        executor.start().await.unwrap();
+        (executor, state_store)
+    }
+    
+    /// Submete a função e a cancela depois que começa a rodar
+    async fn run_and_cancel(executor: &TaskExecutor, state_store: &MemoryStateStore, function_name: &str) -> (TaskId, Instant) {
+        let definition = TaskDefinition::RustFunction {
+            function_name: function_name.to_string(),
+            args: serde_json::Value::Null,
+        };
+        let task_id = executor.execute_task(Task::new(function_name.to_string(), definition, vec![])).await.unwrap();
+        while !state_store.get_task_status(&task_id).await.unwrap().is_active() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let cancelled_at = Instant::now();
+        executor.cancel_task(&task_id, CancelReason::UserRequested).await.unwrap();
+        (task_id, cancelled_at)
+    }
+    
+    #[tokio::test]
+    async fn test_cancelled_function_returns_promptly() {
+        let (executor, state_store) = function_executor(Duration::from_secs(5)).await;
+        let (task_id, cancelled_at) = run_and_cancel(&executor, &state_store, "until_cancelled").await;
+        
+        // O único worker só fica livre quando a função termina
+        let next = Task::new("next".to_string(), TaskDefinition::command("true"), vec![]);
+        let next_id = executor.execute_task(next).await.unwrap();
+        assert!(matches!(wait_finished(&state_store, &next_id, Duration::from_secs(2)).await, TaskStatus::Completed { .. }));
+        assert!(cancelled_at.elapsed() < Duration::from_secs(2));
+        assert!(matches!(
+            state_store.get_task_status(&task_id).await.unwrap(),
+            TaskStatus::Cancelled { reason: CancelReason::UserRequested, note: None, .. }
+        ));
+        assert_eq!(cancelled_transitions(&state_store, &task_id).await, 1);
+        executor.shutdown().await.unwrap();
+    }
+    
+    /// Quantas vezes `Cancelled` foi gravado para a tarefa
+    async fn cancelled_transitions(state_store: &MemoryStateStore, task_id: &TaskId) -> usize {
+        state_store.get_status_history(task_id).await.unwrap()
+            .iter()
+            .filter(|transition| matches!(transition.status, TaskStatus::Cancelled { .. }))
+            .count()
+    }
+    
+    #[tokio::test]
+    async fn test_stubborn_function_aborted_after_grace_period() {
+        let grace_period = Duration::from_millis(200);
+        let (executor, state_store) = function_executor(grace_period).await;
+        let (task_id, cancelled_at) = run_and_cancel(&executor, &state_store, "stubborn").await;
+        
+        loop {
+            match state_store.get_task_status(&task_id).await.unwrap() {
+                TaskStatus::Cancelled { note: Some(note), .. } => {
+                    assert_eq!(note, CancelNote::CancelledUncooperatively);
+                    break;
+                }
+                _ => assert!(cancelled_at.elapsed() < Duration::from_secs(2), "função não foi abortada"),
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(cancelled_at.elapsed() >= grace_period);
+        assert_eq!(cancelled_transitions(&state_store, &task_id).await, 1);
+        executor.shutdown().await.unwrap();
+    }
+    
+    fn function_task(function_name: &str, hint: ExecutionHint) -> Task {
+        let definition = TaskDefinition::RustFunction {
+            function_name: function_name.to_string(),
+            args: serde_json::Value::Null,
+        };
+        Task::new(function_name.to_string(), definition, vec![]).with_execution_hint(hint)
+    }
+    
+    /// Sequência de status, eventos e tentativas da tarefa, sem horários
+    async fn observed(state_store: &MemoryStateStore, task_id: &TaskId) -> (Vec<std::mem::Discriminant<TaskStatus>>, Vec<EventType>, usize) {
+        let statuses = state_store.get_status_history(task_id).await.unwrap()
+            .iter()
+            .map(|transition| std::mem::discriminant(&transition.status))
+            .collect();
+        let events = state_store.get_events(None, None).await.unwrap()
+            .into_iter()
+            .filter(|event| event.task_id == Some(*task_id))
+            .map(|event| event.event_type)
+            .collect();
+        (statuses, events, state_store.list_attempts(task_id).await.unwrap().len())
+    }
+    
+    #[tokio::test]
+    async fn test_inline_failure_recorded_like_queued() {
+        let (executor, state_store) = function_executor(Duration::from_secs(1)).await;
+        executor.functions().register("fails", |_ctx: FnContext, _args| async move {
+            Err(anyhow::anyhow!("quebrou"))
+        });
+        
+        let queued = executor.execute_task(function_task("fails", ExecutionHint::Default)).await.unwrap();
+        assert!(matches!(wait_finished(&state_store, &queued, Duration::from_secs(2)).await, TaskStatus::Failed { .. }));
+        
+        // O retorno já encontra a tarefa terminada
+        let inline = executor.execute_task(function_task("fails", ExecutionHint::Inline)).await.unwrap();
+        let status = state_store.get_task_status(&inline).await.unwrap();
+        assert!(matches!(&status, TaskStatus::Failed { error, .. } if error.contains("quebrou")), "{:?}", status);
+        assert_eq!(observed(&state_store, &inline).await, observed(&state_store, &queued).await);
+        assert_eq!(executor.queued_count(), 0);
+        executor.shutdown().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_inline_task_cancelled_while_holding_the_only_permit() {
+        let (executor, state_store) = function_executor(Duration::from_secs(5)).await;
+        executor.functions().register("noop", |_ctx: FnContext, _args| async move { Ok(serde_json::json!("ok")) });
+        
+        let task = function_task("until_cancelled", ExecutionHint::Inline);
+        let task_id = task.id;
+        let running = tokio::spawn({
+            let executor = executor.clone();
+            async move { executor.execute_task(task).await }
+        });
+        while !executor.is_running(&task_id) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        
+        // Com um único worker, a segunda tarefa inline espera pela permissão
+        let waiting = tokio::spawn({
+            let executor = executor.clone();
+            async move { executor.execute_task(function_task("noop", ExecutionHint::Inline)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!waiting.is_finished());
+        
+        executor.cancel_task(&task_id, CancelReason::UserRequested).await.unwrap();
+        assert_eq!(running.await.unwrap().unwrap(), task_id);
+        assert!(matches!(
+            state_store.get_task_status(&task_id).await.unwrap(),
+            TaskStatus::Cancelled { reason: CancelReason::UserRequested, note: None, .. }
+        ));
+        let next_id = tokio::time::timeout(Duration::from_secs(2), waiting).await.unwrap().unwrap().unwrap();
+        assert!(matches!(state_store.get_task_status(&next_id).await.unwrap(), TaskStatus::Completed { .. }));
+        assert!(state_store.get_metrics(&next_id).await.unwrap().is_some());
+        executor.shutdown().await.unwrap();
+    }
+}
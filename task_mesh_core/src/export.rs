@@ -0,0 +1,476 @@
+//! Exportação do histórico de execução para análise offline
+//!
+//! Cada linha junta a tarefa, o resumo do seu histórico de status e as
+//! métricas de execução. As tarefas são lidas do StateStore em páginas de
+//! `batch_size` e cada página vira um arquivo `part-NNNNN.{csv,parquet}` no
+//! diretório de destino, sem materializar o histórico inteiro. Depois de cada
+//! arquivo, `_progress.json` registra o cursor: uma exportação interrompida
+//! retoma do último lote concluído sem duplicar linhas.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray, UInt32Array, UInt64Array, UInt8Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::alias;
+use crate::features::DefinitionKind;
+use crate::report::status_label;
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Arquivo de progresso no diretório de destino
+pub const PROGRESS_FILE: &str = "_progress.json";
+
+/// Tarefas lidas por lote quando não especificado
+pub const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// Formato dos arquivos exportados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Extensão dos arquivos gerados
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = TaskMeshError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            other => Err(TaskMeshError::Configuration(format!(
+                "Formato de exportação desconhecido: {} (use csv ou parquet)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Destino da exportação
+///
+/// Por enquanto apenas diretórios locais; o layout em arquivos `part-*`
+/// independentes é o mesmo que um destino em object store usaria.
+#[derive(Debug, Clone)]
+pub enum ExportDestination {
+    Path(PathBuf),
+}
+
+/// Pedido de exportação
+#[derive(Debug, Clone)]
+pub struct ExportRequest {
+    /// Intervalo de criação das tarefas (início, fim)
+    pub range: (Option<SystemTime>, Option<SystemTime>),
+    /// Namespaces incluídos (vazio = todos)
+    pub namespaces: Vec<String>,
+    /// Formato dos arquivos
+    pub format: ExportFormat,
+    /// Destino dos arquivos
+    pub destination: ExportDestination,
+    /// Tarefas lidas por lote
+    pub batch_size: usize,
+    /// Máximo de lotes nesta execução (`None` = até o fim)
+    pub max_batches: Option<usize>,
+}
+
+impl ExportRequest {
+    /// Exportação completa para um diretório
+    pub fn new(format: ExportFormat, destination: impl Into<PathBuf>) -> Self {
+        Self {
+            range: (None, None),
+            namespaces: Vec::new(),
+            format,
+            destination: ExportDestination::Path(destination.into()),
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_batches: None,
+        }
+    }
+
+    /// Identifica a exportação no arquivo de progresso
+    fn fingerprint(&self) -> String {
+        let secs = |t: Option<SystemTime>| t.map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs());
+        format!(
+            "{:?}|{:?}|{:?}|{}|{}",
+            secs(self.range.0),
+            secs(self.range.1),
+            self.namespaces,
+            self.format.extension(),
+            self.batch_size
+        )
+    }
+
+    /// Verifica se a tarefa entra na exportação
+    fn matches(&self, task: &Task) -> bool {
+        let (start, end) = self.range;
+        start.map_or(true, |start| task.created_at >= start)
+            && end.map_or(true, |end| task.created_at < end)
+            && (self.namespaces.is_empty()
+                || self.namespaces.iter().any(|ns| ns == alias::namespace_of(task)))
+    }
+}
+
+/// Estado persistido entre execuções
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ExportProgress {
+    fingerprint: String,
+    cursor: Option<TaskId>,
+    batches: usize,
+    parts: usize,
+    rows: u64,
+    finished: bool,
+}
+
+/// Resultado de uma exportação
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportSummary {
+    /// Lotes concluídos, incluindo execuções anteriores
+    pub batches: usize,
+    /// Arquivos `part-*` gravados
+    pub files: usize,
+    /// Linhas exportadas
+    pub rows: u64,
+    /// Lotes já concluídos quando esta execução começou
+    pub resumed_from: usize,
+    /// Se todas as tarefas foram percorridas
+    pub finished: bool,
+}
+
+/// Esquema das linhas exportadas
+pub fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("task_id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("alias", DataType::Utf8, true),
+        Field::new("namespace", DataType::Utf8, false),
+        Field::new("definition_kind", DataType::Utf8, false),
+        Field::new("priority", DataType::UInt8, false),
+        Field::new("created_at_ms", DataType::Int64, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("transitions", DataType::UInt32, false),
+        Field::new("first_transition_ms", DataType::Int64, true),
+        Field::new("last_transition_ms", DataType::Int64, true),
+        Field::new("execution_time_ms", DataType::Float64, true),
+        Field::new("cpu_usage", DataType::Float64, true),
+        Field::new("memory_bytes", DataType::UInt64, true),
+        Field::new("disk_read_bytes", DataType::UInt64, true),
+        Field::new("disk_write_bytes", DataType::UInt64, true),
+        Field::new("network_read_bytes", DataType::UInt64, true),
+        Field::new("network_write_bytes", DataType::UInt64, true),
+    ]))
+}
+
+/// Tarefa com seu histórico e métricas
+struct ExportRow {
+    task: Task,
+    status: TaskStatus,
+    history: Vec<StatusTransition>,
+    metrics: Option<ExecutionMetrics>,
+}
+
+/// Exporta o histórico do `store` conforme o pedido
+///
+/// Retoma automaticamente uma exportação anterior para o mesmo destino; um
+/// destino com o progresso de outro pedido é recusado.
+pub async fn export_history(store: &dyn StateStore, request: &ExportRequest) -> TaskMeshResult<ExportSummary> {
+    let ExportDestination::Path(dir) = &request.destination;
+    if request.batch_size == 0 {
+        return Err(TaskMeshError::Configuration("batch_size deve ser maior que zero".to_string()));
+    }
+    tokio::fs::create_dir_all(dir).await?;
+
+    let fingerprint = request.fingerprint();
+    let mut progress = match read_progress(dir).await? {
+        Some(progress) if progress.fingerprint != fingerprint => {
+            return Err(TaskMeshError::Configuration(format!(
+                "{} contém o progresso de outra exportação",
+                dir.display()
+            )));
+        }
+        Some(progress) => progress,
+        None => ExportProgress { fingerprint, ..ExportProgress::default() },
+    };
+    let resumed_from = progress.batches;
+    if resumed_from > 0 && !progress.finished {
+        info!("Retomando exportação em {} a partir do lote {}", dir.display(), resumed_from);
+    }
+
+    let mut batches_this_run = 0;
+    while !progress.finished && request.max_batches.map_or(true, |max| batches_this_run < max) {
+        let page = store.list_tasks_page(progress.cursor.as_ref(), request.batch_size).await?;
+        let page_len = page.len();
+        let Some(last) = page.last().map(|task| task.id) else {
+            progress.finished = true;
+            write_progress(dir, &progress).await?;
+            break;
+        };
+
+        let rows = load_rows(store, page.into_iter().filter(|task| request.matches(task))).await?;
+
+        if !rows.is_empty() {
+            let path = dir.join(format!("part-{:05}.{}", progress.parts, request.format.extension()));
+            write_rows(&rows, path, request.format).await?;
+            progress.parts += 1;
+            progress.rows += rows.len() as u64;
+        }
+
+        progress.cursor = Some(last);
+        progress.batches += 1;
+        progress.finished = page_len < request.batch_size;
+        write_progress(dir, &progress).await?;
+        batches_this_run += 1;
+        debug!("Lote {} exportado: {} linhas", progress.batches, rows.len());
+    }
+
+    info!(
+        "Exportação em {}: {} linhas em {} arquivos{}",
+        dir.display(),
+        progress.rows,
+        progress.parts,
+        if progress.finished { "" } else { " (parcial)" }
+    );
+    Ok(ExportSummary {
+        batches: progress.batches,
+        files: progress.parts,
+        rows: progress.rows,
+        resumed_from,
+        finished: progress.finished,
+    })
+}
+
+/// Grava `tasks` em `{dir}/{stem}.{ext}` no esquema da exportação
+///
+/// Usado pela coleta de tarefas terminais ([`crate::gc`]) para arquivar um
+/// lote antes de removê-lo do StateStore. Retorna o caminho gravado.
+pub async fn write_archive(
+    store: &dyn StateStore,
+    tasks: Vec<Task>,
+    dir: &Path,
+    stem: &str,
+    format: ExportFormat,
+) -> TaskMeshResult<PathBuf> {
+    tokio::fs::create_dir_all(dir).await?;
+    let rows = load_rows(store, tasks).await?;
+    let path = dir.join(format!("{}.{}", stem, format.extension()));
+    write_rows(&rows, path.clone(), format).await?;
+    Ok(path)
+}
+
+/// Junta a cada tarefa seu status, histórico e métricas
+async fn load_rows(store: &dyn StateStore, tasks: impl IntoIterator<Item = Task>) -> TaskMeshResult<Vec<ExportRow>> {
+    let mut rows = Vec::new();
+    for task in tasks {
+        rows.push(ExportRow {
+            status: store.get_task_status(&task.id).await?,
+            history: store.get_status_history(&task.id).await?,
+            metrics: store.get_metrics(&task.id).await?,
+            task,
+        });
+    }
+    Ok(rows)
+}
+
+async fn write_rows(rows: &[ExportRow], path: PathBuf, format: ExportFormat) -> TaskMeshResult<()> {
+    let batch = to_record_batch(rows)?;
+    tokio::task::spawn_blocking(move || write_file(&path, format, &batch))
+        .await
+        .map_err(|e| TaskMeshError::Internal(format!("Escrita da exportação abortada: {}", e)))?
+}
+
+fn millis(at: SystemTime) -> i64 {
+    at.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as i64
+}
+
+fn to_record_batch(rows: &[ExportRow]) -> TaskMeshResult<RecordBatch> {
+    let metric = |f: fn(&ExecutionMetrics) -> u64| -> ArrayRef {
+        Arc::new(UInt64Array::from(rows.iter().map(|r| r.metrics.as_ref().map(f)).collect::<Vec<_>>()))
+    };
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.task.id.to_string()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.task.name.as_str()))),
+        Arc::new(StringArray::from(rows.iter().map(|r| r.task.alias.as_deref()).collect::<Vec<_>>())),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| alias::namespace_of(&r.task)))),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|r| DefinitionKind::of(&r.task.definition).as_str()),
+        )),
+        Arc::new(UInt8Array::from_iter_values(rows.iter().map(|r| r.task.priority.value()))),
+        Arc::new(Int64Array::from_iter_values(rows.iter().map(|r| millis(r.task.created_at)))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| status_label(&r.status)))),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.history.len() as u32))),
+        Arc::new(Int64Array::from(
+            rows.iter().map(|r| r.history.first().map(|t| millis(t.changed_at))).collect::<Vec<_>>(),
+        )),
+        Arc::new(Int64Array::from(
+            rows.iter().map(|r| r.history.last().map(|t| millis(t.changed_at))).collect::<Vec<_>>(),
+        )),
+        Arc::new(Float64Array::from(
+            rows.iter()
+                .map(|r| r.metrics.as_ref().map(|m| m.execution_time.as_secs_f64() * 1000.0))
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(Float64Array::from(
+            rows.iter().map(|r| r.metrics.as_ref().map(|m| m.cpu_usage)).collect::<Vec<_>>(),
+        )),
+        metric(|m| m.memory_usage),
+        metric(|m| m.disk_io.0),
+        metric(|m| m.disk_io.1),
+        metric(|m| m.network_io.0),
+        metric(|m| m.network_io.1),
+    ];
+    RecordBatch::try_new(schema(), columns).map_err(export_error)
+}
+
+/// Grava um lote em `path`, via arquivo temporário e rename
+fn write_file(path: &Path, format: ExportFormat, batch: &RecordBatch) -> TaskMeshResult<()> {
+    let tmp = path.with_extension("tmp");
+    let file = std::fs::File::create(&tmp)?;
+
+    let file = match format {
+        ExportFormat::Csv => {
+            let mut writer = arrow::csv::Writer::new(file);
+            writer.write(batch).map_err(export_error)?;
+            writer.into_inner()
+        }
+        ExportFormat::Parquet => {
+            let mut writer = ArrowWriter::try_new(file.try_clone()?, batch.schema(), None).map_err(export_error)?;
+            writer.write(batch).map_err(export_error)?;
+            writer.close().map_err(export_error)?;
+            file
+        }
+    };
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+async fn read_progress(dir: &Path) -> TaskMeshResult<Option<ExportProgress>> {
+    match tokio::fs::read(dir.join(PROGRESS_FILE)).await {
+        Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn write_progress(dir: &Path, progress: &ExportProgress) -> TaskMeshResult<()> {
+    let path = dir.join(PROGRESS_FILE);
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, serde_json::to_vec_pretty(progress)?).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(())
+}
+
+fn export_error(error: impl std::fmt::Display) -> TaskMeshError {
+    TaskMeshError::Internal(format!("Erro de exportação: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::time::Duration;
+    use crate::state_store::SqliteStateStore;
+
+    const TASKS: usize = 25;
+
+    async fn seeded_store() -> SqliteStateStore {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        for i in 0..TASKS {
+            let task = Task::new(format!("task_{}", i), TaskDefinition::command("true"), vec![]);
+            store.store_task(&task).await.unwrap();
+            store.update_task_status(&task.id, TaskStatus::Scheduled).await.unwrap();
+            if i % 2 == 0 {
+                store.store_metrics(&task.id, &ExecutionMetrics {
+                    execution_time: Duration::from_millis(100 + i as u64),
+                    ..ExecutionMetrics::default()
+                }).await.unwrap();
+            }
+        }
+        store
+    }
+
+    fn parts(dir: &Path, extension: &str) -> Vec<PathBuf> {
+        let mut parts: Vec<PathBuf> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+            .collect();
+        parts.sort();
+        parts
+    }
+
+    #[tokio::test]
+    async fn test_csv_export_resumes_without_duplicates() {
+        let store = seeded_store().await;
+        let dir = tempfile::tempdir().unwrap();
+        let mut request = ExportRequest::new(ExportFormat::Csv, dir.path());
+        request.batch_size = 10;
+        request.max_batches = Some(1);
+
+        // Primeira execução interrompida após um lote
+        let partial = export_history(&store, &request).await.unwrap();
+        assert_eq!((partial.rows, partial.finished), (10, false));
+
+        request.max_batches = None;
+        let summary = export_history(&store, &request).await.unwrap();
+        assert_eq!(summary.resumed_from, 1);
+        assert!(summary.finished);
+        assert_eq!(summary.rows, TASKS as u64);
+
+        let header: Vec<String> = schema().fields().iter().map(|f| f.name().clone()).collect();
+        let mut ids = HashSet::new();
+        for part in parts(dir.path(), "csv") {
+            let content = std::fs::read_to_string(part).unwrap();
+            let mut lines = content.lines();
+            assert_eq!(lines.next().unwrap(), header.join(","));
+            for line in lines {
+                assert!(ids.insert(line.split(',').next().unwrap().to_string()), "linha duplicada: {}", line);
+            }
+        }
+        assert_eq!(ids.len(), TASKS);
+    }
+
+    #[tokio::test]
+    async fn test_parquet_export_schema_and_row_count() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let store = seeded_store().await;
+        let dir = tempfile::tempdir().unwrap();
+        let mut request = ExportRequest::new(ExportFormat::Parquet, dir.path());
+        request.batch_size = 10;
+        export_history(&store, &request).await.unwrap();
+
+        let mut rows = 0;
+        for part in parts(dir.path(), "parquet") {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(part).unwrap())
+                .unwrap()
+                .build()
+                .unwrap();
+            for batch in reader {
+                let batch = batch.unwrap();
+                assert_eq!(batch.schema().fields(), schema().fields());
+                rows += batch.num_rows();
+            }
+        }
+        assert_eq!(rows, TASKS);
+
+        // Reexecutar uma exportação concluída não grava nada novo
+        let again = export_history(&store, &request).await.unwrap();
+        assert_eq!((again.rows, again.files), (TASKS as u64, 3));
+    }
+}
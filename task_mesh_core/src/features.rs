@@ -0,0 +1,380 @@
+//! Extração de features de tarefas para os modelos de aprendizado
+//!
+//! [`TaskFeatures`] é a representação comum entre tarefas e modelos: o
+//! scheduler a usa para estimar durações e os modelos do
+//! [`crate::learning::ModelRegistry`] a recebem já extraída, em vez de cada
+//! um interpretar `Task` por conta própria. A extração é determinística —
+//! a mesma tarefa com o mesmo histórico produz sempre as mesmas features.
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+
+/// Número de posições do one-hot de tags (tags são mapeadas por hash)
+pub const TAG_BUCKETS: usize = 16;
+
+/// Nomes das colunas de [`TaskFeatures::to_vector`], na ordem
+pub fn feature_names() -> Vec<String> {
+    let mut names: Vec<String> = DefinitionKind::ALL
+        .iter()
+        .map(|kind| format!("kind_{}", kind.as_str()))
+        .collect();
+    names.extend(
+        ["payload_bytes", "dependency_count", "hour_of_day", "priority", "historical_mean_ms", "historical_p95_ms"]
+            .iter()
+            .map(|name| name.to_string()),
+    );
+    names.extend((0..TAG_BUCKETS).map(|bucket| format!("tag_{}", bucket)));
+    names
+}
+
+/// Variante de [`TaskDefinition`], sem os dados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionKind {
+    Command,
+    Exec,
+    PythonScript,
+    RustFunction,
+    HttpRequest,
+    Workflow,
+    Generator,
+}
+
+impl DefinitionKind {
+    pub const ALL: [DefinitionKind; 7] = [
+        DefinitionKind::Command,
+        DefinitionKind::Exec,
+        DefinitionKind::PythonScript,
+        DefinitionKind::RustFunction,
+        DefinitionKind::HttpRequest,
+        DefinitionKind::Workflow,
+        DefinitionKind::Generator,
+    ];
+
+    pub fn of(definition: &TaskDefinition) -> Self {
+        match definition {
+            TaskDefinition::Command { .. } => DefinitionKind::Command,
+            TaskDefinition::Exec { .. } => DefinitionKind::Exec,
+            TaskDefinition::PythonScript { .. } => DefinitionKind::PythonScript,
+            TaskDefinition::RustFunction { .. } => DefinitionKind::RustFunction,
+            TaskDefinition::HttpRequest { .. } => DefinitionKind::HttpRequest,
+            TaskDefinition::Workflow { .. } => DefinitionKind::Workflow,
+            TaskDefinition::Generator { .. } => DefinitionKind::Generator,
+        }
+    }
+
+    /// Chave estável, usada também para agrupar o histórico
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DefinitionKind::Command => "command",
+            DefinitionKind::Exec => "exec",
+            DefinitionKind::PythonScript => "python",
+            DefinitionKind::RustFunction => "rust",
+            DefinitionKind::HttpRequest => "http",
+            DefinitionKind::Workflow => "workflow",
+            DefinitionKind::Generator => "generator",
+        }
+    }
+}
+
+/// Durações observadas de execuções anteriores
+pub trait HistoryProvider {
+    /// Durações de tarefas do mesmo tipo, em qualquer ordem
+    fn durations(&self, kind: DefinitionKind) -> Vec<Duration>;
+}
+
+/// Histórico do scheduler, agrupado por [`DefinitionKind::as_str`]
+impl HistoryProvider for HashMap<String, Vec<ExecutionMetrics>> {
+    fn durations(&self, kind: DefinitionKind) -> Vec<Duration> {
+        self.get(kind.as_str())
+            .map(|metrics| metrics.iter().map(|m| m.execution_time).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Sem histórico: os campos históricos ficam `None`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoHistory;
+
+impl HistoryProvider for NoHistory {
+    fn durations(&self, _kind: DefinitionKind) -> Vec<Duration> {
+        Vec::new()
+    }
+}
+
+/// Features de uma tarefa
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskFeatures {
+    pub definition_kind: DefinitionKind,
+    /// Tamanho do conteúdo da definição (comando, script, corpo etc.)
+    pub payload_bytes: u64,
+    pub dependency_count: u32,
+    /// One-hot das tags por hash, com [`TAG_BUCKETS`] posições
+    pub tag_onehot: Vec<u8>,
+    /// Hora (UTC) de criação da tarefa
+    pub hour_of_day: u8,
+    pub priority: Priority,
+    /// Duração média das execuções do mesmo tipo
+    pub historical_mean: Option<Duration>,
+    /// Percentil 95 das durações do mesmo tipo
+    pub historical_p95: Option<Duration>,
+    /// Execuções no histórico
+    pub historical_samples: usize,
+}
+
+/// Extrai as features de uma tarefa
+pub fn extract(task: &Task, history: &dyn HistoryProvider) -> TaskFeatures {
+    let definition_kind = DefinitionKind::of(&task.definition);
+    let mut durations = history.durations(definition_kind);
+    durations.sort_unstable();
+
+    let mut tag_onehot = vec![0u8; TAG_BUCKETS];
+    for tag in &task.tags {
+        tag_onehot[tag_bucket(tag)] = 1;
+    }
+
+    let hour_of_day = task
+        .created_at
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|since_epoch| (since_epoch.as_secs() / 3600 % 24) as u8)
+        .unwrap_or(0);
+
+    TaskFeatures {
+        definition_kind,
+        payload_bytes: payload_bytes(&task.definition),
+        dependency_count: task.dependencies.len() as u32,
+        tag_onehot,
+        hour_of_day,
+        priority: task.priority,
+        historical_mean: mean(&durations),
+        historical_p95: percentile(&durations, 0.95),
+        historical_samples: durations.len(),
+    }
+}
+
+impl TaskFeatures {
+    /// Vetor numérico na ordem de [`feature_names`]
+    ///
+    /// Campos históricos ausentes viram 0.
+    pub fn to_vector(&self) -> Vec<f64> {
+        let mut vector: Vec<f64> = DefinitionKind::ALL
+            .iter()
+            .map(|kind| if *kind == self.definition_kind { 1.0 } else { 0.0 })
+            .collect();
+        vector.extend([
+            self.payload_bytes as f64,
+            self.dependency_count as f64,
+            self.hour_of_day as f64,
+            f64::from(self.priority.value()),
+            self.historical_mean.map(|d| d.as_secs_f64() * 1000.0).unwrap_or(0.0),
+            self.historical_p95.map(|d| d.as_secs_f64() * 1000.0).unwrap_or(0.0),
+        ]);
+        vector.extend(self.tag_onehot.iter().map(|&bit| bit as f64));
+        vector
+    }
+
+    /// Serialização estável para persistência (formato de [`crate::learning::encode_model`])
+    pub fn to_bytes(&self) -> TaskMeshResult<Vec<u8>> {
+        crate::learning::encode_model(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> TaskMeshResult<Self> {
+        crate::learning::decode_model(bytes)
+    }
+}
+
+/// Normalização por coluna (z-score) ajustada sobre um conjunto de vetores
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Normalizer {
+    pub means: Vec<f64>,
+    pub std_devs: Vec<f64>,
+}
+
+impl Normalizer {
+    /// Ajusta médias e desvios; colunas constantes ficam com desvio 1
+    pub fn fit(rows: &[Vec<f64>]) -> Self {
+        let width = rows.first().map(Vec::len).unwrap_or(0);
+        let count = rows.len().max(1) as f64;
+        let mut means = vec![0.0; width];
+        for row in rows {
+            for (mean, value) in means.iter_mut().zip(row) {
+                *mean += value / count;
+            }
+        }
+        let mut std_devs = vec![0.0; width];
+        for row in rows {
+            for ((std_dev, mean), value) in std_devs.iter_mut().zip(&means).zip(row) {
+                *std_dev += (value - mean).powi(2) / count;
+            }
+        }
+        for std_dev in &mut std_devs {
+            *std_dev = if *std_dev > f64::EPSILON { std_dev.sqrt() } else { 1.0 };
+        }
+        Self { means, std_devs }
+    }
+
+    pub fn transform(&self, row: &[f64]) -> Vec<f64> {
+        row.iter()
+            .zip(self.means.iter().zip(&self.std_devs))
+            .map(|(value, (mean, std_dev))| (value - mean) / std_dev)
+            .collect()
+    }
+
+    pub fn inverse(&self, row: &[f64]) -> Vec<f64> {
+        row.iter()
+            .zip(self.means.iter().zip(&self.std_devs))
+            .map(|(value, (mean, std_dev))| value * std_dev + mean)
+            .collect()
+    }
+}
+
+/// Escala valores com cauda longa (bytes, milissegundos) para `ln(1 + x)`
+pub fn log_scale(value: f64) -> f64 {
+    value.max(0.0).ln_1p()
+}
+
+/// Posição da tag no one-hot (FNV-1a, estável entre versões e plataformas)
+fn tag_bucket(tag: &str) -> usize {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in tag.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    (hash % TAG_BUCKETS as u64) as usize
+}
+
+fn payload_bytes(definition: &TaskDefinition) -> u64 {
+    let strings = |items: &[String]| items.iter().map(|s| s.len() as u64).sum::<u64>();
+    let pairs = |map: &HashMap<String, String>| map.iter().map(|(k, v)| (k.len() + v.len()) as u64).sum::<u64>();
+    match definition {
+        TaskDefinition::Command { command, shell } => {
+            command.len() as u64 + shell.as_ref().map_or(0, |shell| shell.len() as u64)
+        }
+        TaskDefinition::Exec { program, args } => program.len() as u64 + strings(args),
+        TaskDefinition::PythonScript { script, args, env } => script.len() as u64 + strings(args) + pairs(env),
+        TaskDefinition::RustFunction { function_name, args } => {
+            function_name.len() as u64 + args.to_string().len() as u64
+        }
+        TaskDefinition::HttpRequest { method, url, headers, body } => {
+            (method.len() + url.len()) as u64 + pairs(headers) + body.as_ref().map_or(0, |body| body.len() as u64)
+        }
+        TaskDefinition::Workflow { tasks, .. } => tasks.iter().map(|task| payload_bytes(&task.definition)).sum(),
+        TaskDefinition::Generator { source, .. } => payload_bytes(source),
+    }
+}
+
+fn mean(sorted: &[Duration]) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    Some(sorted.iter().sum::<Duration>() / sorted.len() as u32)
+}
+
+/// Percentil por posição mais próxima
+pub(crate) fn percentile(sorted: &[Duration], quantile: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn with_definition(definition: TaskDefinition) -> Task {
+        let mut task = Task::new("t".to_string(), definition, vec![TaskId::from_u128(1)])
+            .with_priority(Priority::HIGH)
+            .with_tags(vec!["etl".to_string(), "nightly".to_string()]);
+        task.created_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        task
+    }
+
+    #[test]
+    fn test_extraction_per_definition_variant() {
+        let leaf = with_definition(TaskDefinition::command("echo hi"));
+        let cases = vec![
+            (TaskDefinition::command("echo hi"), DefinitionKind::Command, 7),
+            (TaskDefinition::exec("ls", ["-la"]), DefinitionKind::Exec, 5),
+            (
+                TaskDefinition::PythonScript {
+                    script: Arc::from("print(1)"),
+                    args: vec!["x".to_string()],
+                    env: HashMap::from([("K".to_string(), "vv".to_string())]),
+                },
+                DefinitionKind::PythonScript,
+                12,
+            ),
+            (
+                TaskDefinition::RustFunction { function_name: "f".to_string(), args: serde_json::json!([1]) },
+                DefinitionKind::RustFunction,
+                4,
+            ),
+            (
+                TaskDefinition::HttpRequest {
+                    method: "POST".to_string(),
+                    url: "http://a".to_string(),
+                    headers: HashMap::new(),
+                    body: Some("{}".to_string()),
+                },
+                DefinitionKind::HttpRequest,
+                14,
+            ),
+            (
+                TaskDefinition::Workflow { tasks: vec![leaf.clone(), leaf], execution_strategy: WorkflowStrategy::Sequential },
+                DefinitionKind::Workflow,
+                14,
+            ),
+            (TaskDefinition::generator(TaskDefinition::command("gen"), None), DefinitionKind::Generator, 3),
+        ];
+
+        let history = HashMap::from([(
+            "command".to_string(),
+            (1..=20)
+                .map(|secs| ExecutionMetrics { execution_time: Duration::from_secs(secs), ..Default::default() })
+                .collect::<Vec<_>>(),
+        )]);
+
+        for (definition, kind, payload) in cases {
+            let features = extract(&with_definition(definition), &history);
+            assert_eq!(features.definition_kind, kind);
+            assert_eq!(features.payload_bytes, payload, "{:?}", kind);
+            assert_eq!(features.dependency_count, 1);
+            assert_eq!(features.priority, Priority::HIGH);
+            assert_eq!(features.hour_of_day, 22);
+            assert_eq!(features.tag_onehot.iter().filter(|&&bit| bit == 1).count(), 2);
+            assert_eq!(features.to_vector().len(), feature_names().len());
+
+            if kind == DefinitionKind::Command {
+                assert_eq!(features.historical_mean, Some(Duration::from_millis(10_500)));
+                assert_eq!(features.historical_p95, Some(Duration::from_secs(19)));
+                assert_eq!(features.historical_samples, 20);
+            } else {
+                assert_eq!(features.historical_mean, None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extraction_is_deterministic() {
+        let task = with_definition(TaskDefinition::exec("cargo", ["build", "--release"]));
+        let first = extract(&task, &NoHistory);
+        let second = extract(&task.clone(), &NoHistory);
+        assert_eq!(first, second);
+        assert_eq!(first.to_vector(), second.to_vector());
+        assert_eq!(first.to_bytes().unwrap(), second.to_bytes().unwrap());
+        assert_eq!(TaskFeatures::from_bytes(&first.to_bytes().unwrap()).unwrap(), first);
+
+        let rows = vec![first.to_vector(), extract(&with_definition(TaskDefinition::command("x")), &NoHistory).to_vector()];
+        let normalizer = Normalizer::fit(&rows);
+        let normalized = normalizer.transform(&rows[0]);
+        assert!(normalized.iter().all(|value| value.is_finite()));
+        let restored = normalizer.inverse(&normalized);
+        assert!(restored.iter().zip(&rows[0]).all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+}
@@ -0,0 +1,158 @@
+//! Resultados forçados para testar caminhos de falha
+//!
+//! Para exercitar alertas e hooks de falha sem escrever comandos quebrados,
+//! uma tarefa pode declarar em `Task::forced_outcome` (ver
+//! [`Task::with_forced_outcome`]) o resultado que o executor deve produzir
+//! no lugar da definição. A definição e os sidecars
+//! não rodam; todo o resto (hooks, retries, status, eventos, tentativas)
+//! segue o mesmo caminho de uma execução real:
+//!
+//! - [`ForcedStatus::Fail`]: termina com `exit_code` (padrão 1) e `stderr`;
+//! - [`ForcedStatus::Timeout`]: estoura o limite de tempo, como um processo
+//!   que não termina;
+//! - [`ForcedStatus::Panic`]: o worker cai durante a execução.
+//!
+//! Só é aceito com `TaskMeshConfig::allow_forced_outcomes`; com a opção
+//! desligada a submissão é recusada e o executor não honra o campo.
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+use crate::units::DurationSpec;
+use crate::validation::Violation;
+
+/// Desfecho simulado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForcedStatus {
+    Fail,
+    Timeout,
+    Panic,
+}
+
+/// Resultado produzido no lugar da definição da tarefa
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForcedOutcome {
+    pub status: ForcedStatus,
+    /// Duração simulada; ausente é imediato (`Timeout`: o limite de tempo da tarefa)
+    #[serde(default)]
+    pub after: Option<DurationSpec>,
+    /// Código de saída de `Fail` (padrão 1)
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub stderr: String,
+}
+
+impl ForcedOutcome {
+    pub fn new(status: ForcedStatus) -> Self {
+        Self { status, after: None, exit_code: None, stderr: String::new() }
+    }
+
+    pub fn with_after(mut self, after: Duration) -> Self {
+        self.after = Some(after.into());
+        self
+    }
+
+    pub fn with_exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = Some(exit_code);
+        self
+    }
+
+    pub fn with_stderr(mut self, stderr: impl Into<String>) -> Self {
+        self.stderr = stderr.into();
+        self
+    }
+
+    /// Espera a duração simulada e produz o resultado
+    ///
+    /// `time_limit` é o limite que um processo da tarefa teria. Cancelar
+    /// durante a espera resulta no mesmo erro de um processo cancelado.
+    pub async fn produce(
+        &self,
+        task_id: &TaskId,
+        time_limit: Duration,
+        cancel_token: tokio_util::sync::CancellationToken,
+    ) -> TaskMeshResult<TaskResult> {
+        let wait = match (self.status, self.after) {
+            (_, Some(after)) => after.as_duration(),
+            (ForcedStatus::Timeout, None) => time_limit,
+            (_, None) => Duration::ZERO,
+        };
+        tokio::select! {
+            _ = cancel_token.cancelled() => {
+                return Err(TaskMeshError::ExecutionError("Tarefa cancelada".to_string()));
+            }
+            _ = tokio::time::sleep(wait) => {}
+        }
+        match self.status {
+            ForcedStatus::Fail => Ok(TaskResult {
+                exit_code: self.exit_code.unwrap_or(1),
+                stdout: String::new(),
+                stderr: self.stderr.clone(),
+                output_data: None,
+                metrics: ExecutionMetrics::default(),
+                log_ref: None,
+            }),
+            ForcedStatus::Timeout => Err(TaskMeshError::ExecutionTimeout(*task_id)),
+            ForcedStatus::Panic => {
+                let message = if self.stderr.is_empty() { "falha forçada" } else { self.stderr.as_str() };
+                Err(TaskMeshError::ExecutionError(format!("Worker entrou em pânico: {}", message)))
+            }
+        }
+    }
+}
+
+impl Task {
+    /// Faz o executor produzir `outcome` em vez de executar a definição
+    pub fn with_forced_outcome(mut self, outcome: ForcedOutcome) -> Self {
+        self.forced_outcome = Some(outcome);
+        self
+    }
+}
+
+/// Verifica o resultado forçado da tarefa
+///
+/// Os campos das violações recebem `prefix` (ex.: `tasks[2].`).
+pub fn validate(task: &Task, prefix: &str, allowed: bool) -> Vec<Violation> {
+    if task.forced_outcome.is_none() || allowed {
+        return Vec::new();
+    }
+    vec![Violation {
+        field: format!("{}forced_outcome", prefix),
+        message: "resultados forçados estão desativados (habilite allow_forced_outcomes)".to_string(),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_requires_opt_in() {
+        let plain = Task::new("normal".to_string(), TaskDefinition::command("true"), vec![]);
+        assert!(validate(&plain, "", false).is_empty());
+
+        let outcome = ForcedOutcome::new(ForcedStatus::Fail).with_exit_code(7).with_after(Duration::from_millis(20));
+        let forced = plain.clone().with_forced_outcome(outcome.clone());
+        assert_eq!(forced.forced_outcome, Some(outcome));
+        assert!(forced.metadata.is_empty());
+        assert!(validate(&forced, "", true).is_empty());
+        let refused = validate(&forced, "tasks[0].", false);
+        assert_eq!(refused[0].field, "tasks[0].forced_outcome");
+        assert!(refused[0].message.contains("allow_forced_outcomes"), "{}", refused[0].message);
+
+        // O metadado de mesmo nome não força nada
+        let forged = plain.with_metadata("forced_outcome".to_string(), r#"{"status":"panic"}"#.to_string());
+        assert!(validate(&forged, "", false).is_empty());
+    }
+
+    #[test]
+    fn test_outcome_survives_bincode() {
+        let task = Task::new("normal".to_string(), TaskDefinition::command("true"), vec![])
+            .with_forced_outcome(ForcedOutcome::new(ForcedStatus::Timeout).with_after(Duration::from_millis(1500)));
+        let decoded: Task = bincode::deserialize(&bincode::serialize(&task).unwrap()).unwrap();
+        assert_eq!(decoded.forced_outcome, task.forced_outcome);
+    }
+}
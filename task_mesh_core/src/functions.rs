@@ -0,0 +1,158 @@
+//! Registro de funções Rust executadas por `TaskDefinition::RustFunction`
+//!
+//! Cada função tem a forma `async fn(ctx: FnContext, args: Value) -> Result<Value>`.
+//! O [`FnContext`] dá acesso ao token de cancelamento da tarefa, ao
+//! [`ProgressReporter`], ao diretório de trabalho (scratch, quando configurado)
+//! e às saídas (`output_data`) das dependências já concluídas.
+//!
+//! O cancelamento é cooperativo: a função deve consultar
+//! [`FnContext::is_cancelled`] ou aguardar [`FnContext::cancelled`]. Se não
+//! terminar dentro do período de tolerância após o cancelamento, a execução é
+//! abortada no próximo ponto de espera e a tarefa termina `Cancelled` com a
+//! nota [`CancelNote::CancelledUncooperatively`]. Módulos WASM registrados com
+//! `register_wasm` (feature `wasm`) seguem as mesmas regras, ver
+//! [`crate::wasm`].
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+
+use crate::progress::{ProgressReporter, ProgressUpdate};
+use crate::types::*;
+
+/// Período de tolerância padrão entre o cancelamento e o aborto forçado
+pub const DEFAULT_CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Resultado de uma função registrada
+pub type FnResult = anyhow::Result<Value>;
+
+/// Função registrada, já com o futuro encapsulado
+pub type RegisteredFn = Arc<dyn Fn(FnContext, Value) -> BoxFuture<'static, FnResult> + Send + Sync>;
+
+/// Contexto entregue a cada execução de uma função registrada
+#[derive(Debug, Clone)]
+pub struct FnContext {
+    /// Tarefa em execução
+    pub task_id: TaskId,
+    /// Token cancelado quando a tarefa é cancelada ou estoura o tempo
+    pub cancel: CancellationToken,
+    /// Canal de progresso da tarefa
+    pub progress: ProgressReporter,
+    /// Diretório de trabalho da tarefa
+    pub scratch_dir: PathBuf,
+    /// `output_data` das dependências concluídas, por tarefa
+    pub inputs: HashMap<TaskId, Value>,
+}
+
+impl FnContext {
+    /// A tarefa já foi cancelada
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Aguarda o cancelamento da tarefa
+    pub async fn cancelled(&self) {
+        self.cancel.cancelled().await
+    }
+
+    /// Reporta o progresso atual
+    pub fn report(&self, update: ProgressUpdate) {
+        self.progress.report(update);
+    }
+}
+
+/// Registro de funções por nome
+#[derive(Default)]
+pub struct FunctionRegistry {
+    functions: DashMap<String, RegisteredFn>,
+}
+
+impl std::fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FunctionRegistry").field("functions", &self.names()).finish()
+    }
+}
+
+impl FunctionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra `function` sob `name`, substituindo a anterior de mesmo nome
+    pub fn register<F, Fut>(&self, name: impl Into<String>, function: F)
+    where
+        F: Fn(FnContext, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = FnResult> + Send + 'static,
+    {
+        let function: RegisteredFn = Arc::new(move |ctx, args| Box::pin(function(ctx, args)));
+        self.functions.insert(name.into(), function);
+    }
+
+    /// Remove a função; retorna se ela existia
+    pub fn unregister(&self, name: &str) -> bool {
+        self.functions.remove(name).is_some()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    /// Nomes registrados, em ordem alfabética
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.functions.iter().map(|entry| entry.key().clone()).collect();
+        names.sort();
+        names
+    }
+
+    /// Executa a função `name` com o contexto da tarefa
+    ///
+    /// A função roda em uma tarefa Tokio própria. Ao cancelamento de
+    /// `ctx.cancel`, ela tem `grace_period` para terminar; depois disso é
+    /// abortada com [`TaskMeshError::CancelledUncooperatively`]. Estourar
+    /// `time_limit` cancela o contexto e aborta na hora.
+    pub async fn call(
+        &self,
+        name: &str,
+        ctx: FnContext,
+        args: Value,
+        time_limit: Duration,
+        grace_period: Duration,
+    ) -> TaskMeshResult<Value> {
+        let function = self.functions
+            .get(name)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| TaskMeshError::ExecutionError(format!("Função não registrada: {}", name)))?;
+        let (task_id, cancel) = (ctx.task_id, ctx.cancel.clone());
+        let mut handle = tokio::spawn(function(ctx, args));
+
+        let joined = tokio::select! {
+            joined = &mut handle => joined,
+            _ = cancel.cancelled() => {
+                return match tokio::time::timeout(grace_period, &mut handle).await {
+                    Ok(_) => Err(TaskMeshError::ExecutionError("Tarefa cancelada".to_string())),
+                    Err(_) => {
+                        handle.abort();
+                        Err(TaskMeshError::CancelledUncooperatively(grace_period))
+                    }
+                };
+            }
+            _ = tokio::time::sleep(time_limit) => {
+                cancel.cancel();
+                handle.abort();
+                return Err(TaskMeshError::ExecutionTimeout(task_id));
+            }
+        };
+
+        match joined {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(error)) => Err(TaskMeshError::ExecutionError(format!("Função {} falhou: {:#}", name, error))),
+            Err(error) => Err(TaskMeshError::ExecutionError(format!("Função {} interrompida: {}", name, error))),
+        }
+    }
+}
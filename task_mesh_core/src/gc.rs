@@ -0,0 +1,414 @@
+//! Coleta de tarefas terminais
+//!
+//! Tarefas concluídas, falhas ou canceladas há mais que
+//! [`RetentionPolicy::keep_terminal_tasks_for`] (por namespace) saem do
+//! StateStore em lotes de [`GcConfig::batch_size`]. Com
+//! `archive_before_delete`, cada lote é antes gravado em
+//! `{archive_dir}/gc-{início}-{lote}.jsonl`, uma linha por tarefa com status,
+//! histórico, métricas, tentativas e manifestos; o log da tarefa é movido para
+//! `{archive_dir}/logs`. Com [`GcConfig::columnar`] o lote também vai para um
+//! arquivo Parquet no esquema da exportação ([`crate::export`]).
+//!
+//! Tarefas das quais alguma tarefa não terminal depende nunca são coletadas,
+//! qualquer que seja a idade: o dependente ainda precisa do seu resultado.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::alias;
+use crate::event_bus::EventBus;
+use crate::log_store::LogStore;
+use crate::manifest::ExecutionManifest;
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Tarefas removidas por lote quando não especificado
+pub const DEFAULT_GC_BATCH_SIZE: usize = 500;
+
+/// Retenção das tarefas terminais de um namespace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Tempo desde o fim da tarefa até ela poder ser coletada
+    pub keep_terminal_tasks_for: Duration,
+    /// Grava a tarefa no arquivo antes de removê-la
+    #[serde(default = "default_archive_before_delete")]
+    pub archive_before_delete: bool,
+}
+
+fn default_archive_before_delete() -> bool {
+    true
+}
+
+/// Configuração da coleta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GcConfig {
+    /// Políticas por namespace
+    #[serde(default)]
+    pub namespaces: HashMap<String, RetentionPolicy>,
+    /// Política dos namespaces sem entrada própria (`None`: nunca coletados)
+    #[serde(default)]
+    pub default_policy: Option<RetentionPolicy>,
+    /// Tarefas removidas por lote
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// Diretório do arquivo (padrão: `{data_dir}/archive`)
+    #[serde(default)]
+    pub archive_dir: Option<String>,
+    /// Também grava cada lote arquivado em Parquet (requer a feature `export`)
+    #[serde(default)]
+    pub columnar: bool,
+    /// Intervalo da coleta periódica (ms); `None` desativa o loop
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+}
+
+fn default_batch_size() -> usize {
+    DEFAULT_GC_BATCH_SIZE
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        Self {
+            namespaces: HashMap::new(),
+            default_policy: None,
+            batch_size: default_batch_size(),
+            archive_dir: None,
+            columnar: false,
+            interval_ms: None,
+        }
+    }
+}
+
+impl GcConfig {
+    /// Política aplicada às tarefas do namespace
+    pub fn policy_for(&self, namespace: &str) -> Option<&RetentionPolicy> {
+        self.namespaces.get(namespace).or(self.default_policy.as_ref())
+    }
+
+    /// Se alguma política está configurada
+    pub fn is_active(&self) -> bool {
+        self.default_policy.is_some() || !self.namespaces.is_empty()
+    }
+}
+
+/// Tarefa arquivada: uma linha do arquivo JSONL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedTask {
+    pub task: Task,
+    pub status: TaskStatus,
+    pub history: Vec<StatusTransition>,
+    pub metrics: Option<ExecutionMetrics>,
+    pub attempts: Vec<AttemptRecord>,
+    pub manifests: Vec<ExecutionManifest>,
+    /// Log movido para o arquivo, relativo ao diretório do arquivo
+    pub log_file: Option<String>,
+}
+
+/// Resultado de uma coleta
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GcReport {
+    /// Nada foi removido: `collected` lista o que seria
+    pub dry_run: bool,
+    /// Tarefas removidas (ou que seriam, em `dry_run`)
+    pub collected: Vec<TaskId>,
+    /// Tarefas expiradas mantidas por serem dependência de tarefas não terminais
+    pub preserved: Vec<TaskId>,
+    /// Tarefas gravadas no arquivo antes da remoção
+    pub archived: usize,
+    /// Lotes processados
+    pub batches: usize,
+    /// Arquivos gravados
+    pub archive_files: Vec<PathBuf>,
+}
+
+/// Candidata à coleta
+struct Candidate {
+    task_id: TaskId,
+    archive: bool,
+}
+
+/// Momento em que a tarefa chegou ao estado terminal
+fn finished_at(status: &TaskStatus) -> Option<SystemTime> {
+    match status {
+        TaskStatus::Completed { completed_at, .. } => Some(*completed_at),
+        TaskStatus::Failed { failed_at, .. } => Some(*failed_at),
+        TaskStatus::Cancelled { cancelled_at, .. } => Some(*cancelled_at),
+        _ => None,
+    }
+}
+
+/// Coleta as tarefas terminais expiradas em `now`
+///
+/// As tarefas são percorridas em páginas: primeiro para achar as expiradas e
+/// as dependências de tarefas não terminais, depois removidas lote a lote,
+/// com um evento `TasksCollected` por lote. Retorna as tarefas removidas; o
+/// chamador tira-as do registro em memória.
+pub async fn collect(
+    config: &GcConfig,
+    store: &dyn StateStore,
+    log_store: Option<&LogStore>,
+    event_bus: &EventBus,
+    now: SystemTime,
+    dry_run: bool,
+) -> TaskMeshResult<GcReport> {
+    if config.batch_size == 0 {
+        return Err(TaskMeshError::Configuration("gc.batch_size deve ser maior que zero".to_string()));
+    }
+    let mut report = GcReport { dry_run, ..GcReport::default() };
+    if !config.is_active() {
+        return Ok(report);
+    }
+
+    let mut candidates = Vec::new();
+    let mut needed = HashSet::new();
+    let mut cursor = None;
+    loop {
+        let page = store.list_tasks_page(cursor.as_ref(), config.batch_size).await?;
+        let Some(last) = page.last().map(|task| task.id) else { break };
+        let page_len = page.len();
+        for task in page {
+            let status = store.get_task_status(&task.id).await?;
+            let Some(finished) = finished_at(&status) else {
+                needed.extend(task.dependencies.iter().copied());
+                continue;
+            };
+            let Some(policy) = config.policy_for(alias::namespace_of(&task)) else { continue };
+            if finished + policy.keep_terminal_tasks_for <= now {
+                candidates.push(Candidate { task_id: task.id, archive: policy.archive_before_delete });
+            }
+        }
+        if page_len < config.batch_size {
+            break;
+        }
+        cursor = Some(last);
+    }
+
+    let (preserved, candidates): (Vec<Candidate>, Vec<Candidate>) =
+        candidates.into_iter().partition(|candidate| needed.contains(&candidate.task_id));
+    report.preserved = preserved.iter().map(|candidate| candidate.task_id).collect();
+    if dry_run {
+        report.collected = candidates.iter().map(|candidate| candidate.task_id).collect();
+        return Ok(report);
+    }
+
+    let archive_dir = config.archive_dir.as_ref().map(PathBuf::from);
+    let run_id = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let total = candidates.len();
+    for chunk in candidates.chunks(config.batch_size) {
+        let to_archive: Vec<TaskId> = chunk.iter().filter(|c| c.archive).map(|c| c.task_id).collect();
+        if !to_archive.is_empty() {
+            let dir = archive_dir.as_deref().ok_or_else(|| {
+                TaskMeshError::Configuration("gc: arquivamento exige archive_dir ou data_dir".to_string())
+            })?;
+            let stem = format!("gc-{}-{:05}", run_id, report.batches);
+            report.archive_files.extend(archive_batch(config, store, log_store, &to_archive, dir, &stem).await?);
+            report.archived += to_archive.len();
+        }
+
+        for candidate in chunk {
+            if let Some(log_store) = log_store {
+                log_store.remove(&candidate.task_id).await?;
+            }
+            store.remove_task(&candidate.task_id).await?;
+            report.collected.push(candidate.task_id);
+        }
+        report.batches += 1;
+
+        event_bus.publish(SystemEvent::new(
+            EventType::TasksCollected,
+            None,
+            serde_json::json!({
+                "batch": report.batches,
+                "deleted": chunk.len(),
+                "archived": to_archive.len(),
+                "remaining": total - report.collected.len(),
+            }),
+        )).await?;
+        debug!("Coleta: lote {} com {} tarefas", report.batches, chunk.len());
+    }
+
+    info!(
+        "Coleta de tarefas terminais: {} removidas ({} arquivadas), {} mantidas como dependência",
+        report.collected.len(),
+        report.archived,
+        report.preserved.len()
+    );
+    Ok(report)
+}
+
+/// Grava um lote no arquivo e move os logs das tarefas
+async fn archive_batch(
+    config: &GcConfig,
+    store: &dyn StateStore,
+    log_store: Option<&LogStore>,
+    task_ids: &[TaskId],
+    dir: &Path,
+    stem: &str,
+) -> TaskMeshResult<Vec<PathBuf>> {
+    tokio::fs::create_dir_all(dir).await?;
+    let mut lines = Vec::new();
+    let mut tasks = Vec::new();
+    for task_id in task_ids {
+        let Some(task) = store.get_task(task_id).await? else { continue };
+        let log_file = match log_store {
+            Some(log_store) => archive_log(log_store, task_id, dir).await?,
+            None => None,
+        };
+        let archived = ArchivedTask {
+            status: store.get_task_status(task_id).await?,
+            history: store.get_status_history(task_id).await?,
+            metrics: store.get_metrics(task_id).await?,
+            attempts: store.list_attempts(task_id).await?,
+            manifests: store.list_manifests(task_id).await?,
+            log_file,
+            task: task.clone(),
+        };
+        lines.extend(serde_json::to_vec(&archived)?);
+        lines.push(b'\n');
+        tasks.push(task);
+    }
+
+    let path = dir.join(format!("{}.jsonl", stem));
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, &lines).await?;
+    tokio::fs::File::open(&tmp).await?.sync_all().await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    let mut files = vec![path];
+
+    if config.columnar {
+        files.push(write_columnar(store, tasks, dir, stem).await?);
+    }
+    Ok(files)
+}
+
+#[cfg(feature = "export")]
+async fn write_columnar(store: &dyn StateStore, tasks: Vec<Task>, dir: &Path, stem: &str) -> TaskMeshResult<PathBuf> {
+    crate::export::write_archive(store, tasks, dir, stem, crate::export::ExportFormat::Parquet).await
+}
+
+#[cfg(not(feature = "export"))]
+async fn write_columnar(_store: &dyn StateStore, _tasks: Vec<Task>, _dir: &Path, _stem: &str) -> TaskMeshResult<PathBuf> {
+    Err(TaskMeshError::Configuration(
+        "gc.columnar exige a feature `export`".to_string(),
+    ))
+}
+
+/// Copia o log da tarefa para `{dir}/logs`; `None` se ela não tem log
+async fn archive_log(log_store: &LogStore, task_id: &TaskId, dir: &Path) -> TaskMeshResult<Option<String>> {
+    let source = log_store.path(task_id);
+    if !tokio::fs::try_exists(&source).await? {
+        return Ok(None);
+    }
+    let relative = format!("logs/{}.log", task_id);
+    tokio::fs::create_dir_all(dir.join("logs")).await?;
+    tokio::fs::copy(&source, dir.join(&relative)).await?;
+    Ok(Some(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::state_store::SqliteStateStore;
+
+    fn completed(at: SystemTime) -> TaskStatus {
+        TaskStatus::Completed {
+            started_at: at,
+            completed_at: at,
+            result: TaskResult {
+                exit_code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+                output_data: None,
+                metrics: ExecutionMetrics::default(),
+                log_ref: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gc_archives_expired_tasks_and_preserves_needed_dependency() {
+        let store = Arc::new(SqliteStateStore::new("sqlite::memory:").await.unwrap());
+        let event_bus = EventBus::new(store.clone());
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let old = now - Duration::from_secs(10 * 24 * 3600);
+
+        let mut expired = Vec::new();
+        for i in 0..5 {
+            let task = Task::new(format!("old_{}", i), TaskDefinition::command("true"), vec![]);
+            store.store_task(&task).await.unwrap();
+            store.update_task_status(&task.id, completed(old)).await.unwrap();
+            store.store_metrics(&task.id, &ExecutionMetrics::default()).await.unwrap();
+            expired.push(task.id);
+        }
+        // Antiga, mas uma tarefa pendente ainda depende dela
+        let needed = Task::new("needed".to_string(), TaskDefinition::command("true"), vec![]);
+        store.store_task(&needed).await.unwrap();
+        store.update_task_status(&needed.id, completed(old)).await.unwrap();
+        let pending = Task::new("pending".to_string(), TaskDefinition::command("true"), vec![needed.id]);
+        store.store_task(&pending).await.unwrap();
+        // Recente: dentro da retenção
+        let recent = Task::new("recent".to_string(), TaskDefinition::command("true"), vec![]);
+        store.store_task(&recent).await.unwrap();
+        store.update_task_status(&recent.id, completed(now)).await.unwrap();
+
+        let config = GcConfig {
+            default_policy: Some(RetentionPolicy {
+                keep_terminal_tasks_for: Duration::from_secs(7 * 24 * 3600),
+                archive_before_delete: true,
+            }),
+            batch_size: 2,
+            archive_dir: Some(dir.path().display().to_string()),
+            ..GcConfig::default()
+        };
+
+        let preview = collect(&config, store.as_ref(), None, &event_bus, now, true).await.unwrap();
+        let mut previewed = preview.collected.clone();
+        previewed.sort();
+        let mut expected = expired.clone();
+        expected.sort();
+        assert_eq!(previewed, expected);
+        assert_eq!(preview.preserved, vec![needed.id]);
+        assert_eq!(store.list_tasks().await.unwrap().len(), 8);
+
+        let report = collect(&config, store.as_ref(), None, &event_bus, now, false).await.unwrap();
+        assert_eq!((report.collected.len(), report.archived, report.batches), (5, 5, 3));
+        assert_eq!(report.preserved, vec![needed.id]);
+
+        let mut remaining: Vec<TaskId> = store.list_tasks().await.unwrap().iter().map(|task| task.id).collect();
+        remaining.sort();
+        let mut kept = vec![needed.id, pending.id, recent.id];
+        kept.sort();
+        assert_eq!(remaining, kept);
+        for task_id in &expired {
+            assert!(store.get_metrics(task_id).await.unwrap().is_none());
+            assert!(store.get_status_history(task_id).await.unwrap().is_empty());
+        }
+
+        let mut archived = Vec::new();
+        for file in &report.archive_files {
+            for line in std::fs::read_to_string(file).unwrap().lines() {
+                let record: ArchivedTask = serde_json::from_str(line).unwrap();
+                assert!(matches!(record.status, TaskStatus::Completed { .. }));
+                assert!(record.metrics.is_some());
+                archived.push(record.task.id);
+            }
+        }
+        archived.sort();
+        assert_eq!(archived, expected);
+
+        // get_events traz os mais recentes primeiro
+        let progress = store.get_events(None, None).await.unwrap();
+        let batches: Vec<u64> = progress
+            .iter()
+            .rev()
+            .filter(|event| event.event_type == EventType::TasksCollected)
+            .map(|event| event.data["remaining"].as_u64().unwrap())
+            .collect();
+        assert_eq!(batches, vec![3, 1, 0]);
+    }
+}
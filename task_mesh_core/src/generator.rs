@@ -0,0 +1,162 @@
+//! Expansão de tarefas geradoras
+//!
+//! Uma tarefa `TaskDefinition::Generator` executa sua fonte (comando, função,
+//! ...) e a saída JSON é interpretada como uma lista de especificações de
+//! tarefas. Na expansão, as tarefas geradas tornam-se filhas do gerador e
+//! passam a ser dependências de tudo que dependia dele.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+
+/// Chave de metadados com o ID do gerador que criou a tarefa
+pub const GENERATED_BY_KEY: &str = "generated_by";
+
+/// Chave de metadados herdada pelas tarefas geradas
+pub const NAMESPACE_KEY: &str = "namespace";
+
+/// Limite padrão de tarefas por gerador
+pub const DEFAULT_MAX_FAN_OUT: usize = 1000;
+
+/// Especificação de uma tarefa emitida por um gerador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedTaskSpec {
+    pub name: String,
+    pub definition: TaskDefinition,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Interpreta a saída de um gerador
+///
+/// Usa `output_data` quando presente; caso contrário, o stdout precisa ser
+/// um array JSON de especificações.
+pub fn parse_specs(result: &TaskResult) -> TaskMeshResult<Vec<GeneratedTaskSpec>> {
+    let specs = match &result.output_data {
+        Some(serde_json::Value::Object(data)) if data.contains_key("generated") => {
+            serde_json::from_value(data["generated"].clone())?
+        }
+        _ => serde_json::from_str(result.stdout.trim())?,
+    };
+    Ok(specs)
+}
+
+/// Verifica o limite de fan-out de um gerador
+pub fn check_fan_out(generated: usize, limit: usize) -> TaskMeshResult<()> {
+    if generated > limit {
+        return Err(TaskMeshError::FanOutExceeded { generated, limit });
+    }
+    Ok(())
+}
+
+/// Cria as tarefas filhas de um gerador
+///
+/// Cada tarefa depende do gerador, herda suas tags, namespace e padrões
+/// propagáveis ([`crate::defaults::inherit`]) e recebe `generated_by` nos
+/// metadados.
+pub fn expand(generator: &Task, specs: Vec<GeneratedTaskSpec>) -> TaskMeshResult<Vec<Task>> {
+    let limit = match &generator.definition {
+        TaskDefinition::Generator { max_fan_out, .. } => max_fan_out.unwrap_or(DEFAULT_MAX_FAN_OUT),
+        _ => return Err(TaskMeshError::Configuration(format!(
+            "Tarefa {} não é um gerador", generator.id
+        ))),
+    };
+    check_fan_out(specs.len(), limit)?;
+
+    let tasks = specs
+        .into_iter()
+        .map(|spec| {
+            let mut task = Task::new(spec.name, spec.definition, vec![generator.id])
+                .with_priority(spec.priority.unwrap_or(generator.priority))
+                .with_max_retries(generator.max_retries)
+                .with_tags(generator.tags.clone());
+            for tag in spec.tags {
+                if !task.tags.contains(&tag) {
+                    task.tags.push(tag);
+                }
+            }
+            if let Some(timeout_ms) = spec.timeout_ms {
+                task = task.with_timeout(std::time::Duration::from_millis(timeout_ms));
+            }
+            task.metadata = spec.metadata;
+            if let Some(namespace) = generator.metadata.get(NAMESPACE_KEY) {
+                task.metadata.insert(NAMESPACE_KEY.to_string(), namespace.clone());
+            }
+            task.metadata.insert(GENERATED_BY_KEY.to_string(), generator.id.to_string());
+            crate::defaults::inherit(generator, &mut task);
+            task
+        })
+        .collect();
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator(max_fan_out: Option<usize>) -> Task {
+        Task::new(
+            "list_files".to_string(),
+            TaskDefinition::generator(TaskDefinition::command("ls"), max_fan_out),
+            vec![],
+        )
+        .with_tags(vec!["ingest".to_string()])
+        .with_metadata(NAMESPACE_KEY.to_string(), "team-a".to_string())
+    }
+
+    fn result_with_stdout(stdout: &str) -> TaskResult {
+        TaskResult {
+            exit_code: 0,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            output_data: None,
+            metrics: ExecutionMetrics::default(),
+            log_ref: None,
+        }
+    }
+
+    #[test]
+    fn test_expand_inherits_from_generator() {
+        let generator = generator(None);
+        let result = result_with_stdout(
+            r#"[{"name": "a", "definition": {"Command": {"command": "echo a"}}, "tags": ["file"]},
+                {"name": "b", "definition": {"Exec": {"program": "echo", "args": ["b"]}}, "priority": 90}]"#,
+        );
+
+        let tasks = expand(&generator, parse_specs(&result).unwrap()).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].dependencies, vec![generator.id]);
+        assert_eq!(tasks[0].tags, vec!["ingest".to_string(), "file".to_string()]);
+        assert_eq!(tasks[0].metadata[NAMESPACE_KEY], "team-a");
+        assert_eq!(tasks[1].metadata[GENERATED_BY_KEY], generator.id.to_string());
+        assert_eq!(tasks[1].priority, Priority::custom(90).unwrap());
+    }
+
+    #[test]
+    fn test_fan_out_guard() {
+        let generator = generator(Some(2));
+        let specs: Vec<GeneratedTaskSpec> = (0..3)
+            .map(|i| GeneratedTaskSpec {
+                name: format!("t{}", i),
+                definition: TaskDefinition::command("true"),
+                priority: None,
+                tags: vec![],
+                metadata: HashMap::new(),
+                timeout_ms: None,
+            })
+            .collect();
+
+        assert!(matches!(
+            expand(&generator, specs),
+            Err(TaskMeshError::FanOutExceeded { generated: 3, limit: 2 })
+        ));
+    }
+}
@@ -0,0 +1,372 @@
+//! Verificação de saúde agregada
+//!
+//! Cada componente (armazenamento de estado e sua ocupação, checkpoints,
+//! pool de workers, buffer de eventos, despacho, reservas, modo de operação e ociosidade) é verificado isoladamente e gera um `ComponentHealth`.
+//! O estado geral é o pior entre os componentes: qualquer componente
+//! degradado torna o sistema `Degraded`, qualquer falha o torna `Unhealthy`.
+//!
+//! Liveness indica apenas que o processo responde; readiness indica que ele
+//! pode aceitar tarefas (nenhum componente `Unhealthy`).
+
+use std::time::{Duration, Instant, SystemTime};
+use serde::{Deserialize, Serialize};
+
+use crate::executor::TaskExecutor;
+use crate::idle::IdleStatus;
+use crate::maintenance::DispatchGate;
+use crate::reservation::ReservationBook;
+use crate::state_store::{StateStore, StorageStats};
+use crate::Mode;
+
+/// Nomes dos componentes verificados
+pub mod components {
+    pub const STATE_STORE: &str = "state_store";
+    pub const STORAGE: &str = "storage";
+    pub const CHECKPOINT: &str = "checkpoint";
+    pub const WORKER_POOL: &str = "worker_pool";
+    pub const EVENT_BUFFER: &str = "event_buffer";
+    pub const DISPATCH: &str = "dispatch";
+    pub const RESERVATIONS: &str = "reservations";
+    pub const MODE: &str = "mode";
+    pub const IDLE: &str = "idle";
+}
+
+/// Estado de saúde (ordenado do melhor para o pior)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Saúde de um componente
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    /// Nome do componente (ver [`components`])
+    pub name: String,
+    /// Estado do componente
+    pub status: HealthStatus,
+    /// Descrição legível do estado
+    pub detail: String,
+    /// Tempo gasto na verificação
+    pub latency_ms: u64,
+}
+
+impl ComponentHealth {
+    fn new(name: &str, status: HealthStatus, detail: impl Into<String>, started: Instant) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            latency_ms: started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Relatório de saúde agregado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Pior estado entre os componentes
+    pub overall: HealthStatus,
+    /// Estado de cada componente
+    pub components: Vec<ComponentHealth>,
+    /// Momento da verificação
+    pub checked_at: SystemTime,
+}
+
+impl HealthReport {
+    /// Monta o relatório a partir das verificações dos componentes
+    pub fn from_components(components: Vec<ComponentHealth>) -> Self {
+        let overall = components
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(HealthStatus::Healthy);
+        Self {
+            overall,
+            components,
+            checked_at: SystemTime::now(),
+        }
+    }
+
+    /// Processo responde (sempre verdadeiro se o relatório foi gerado)
+    pub fn is_live(&self) -> bool {
+        true
+    }
+
+    /// Sistema pode aceitar tarefas
+    pub fn is_ready(&self) -> bool {
+        self.overall != HealthStatus::Unhealthy
+    }
+
+    /// Saúde de um componente pelo nome
+    pub fn component(&self, name: &str) -> Option<&ComponentHealth> {
+        self.components.iter().find(|c| c.name == name)
+    }
+}
+
+/// Verifica se o armazenamento de estado responde
+pub async fn check_state_store(store: &dyn StateStore) -> ComponentHealth {
+    let started = Instant::now();
+    match store.ping().await {
+        Ok(()) => ComponentHealth::new(components::STATE_STORE, HealthStatus::Healthy, "ok", started),
+        Err(e) => ComponentHealth::new(
+            components::STATE_STORE,
+            HealthStatus::Unhealthy,
+            format!("Armazenamento inacessível: {}", e),
+            started,
+        ),
+    }
+}
+
+/// Janela após o último descarte de evento em que o armazenamento segue degradado
+pub const DROPPED_EVENTS_WINDOW: Duration = Duration::from_secs(300);
+
+/// Informa a ocupação do armazenamento
+///
+/// Eventos descartados pela retenção deixam o componente degradado por
+/// [`DROPPED_EVENTS_WINDOW`]: o histórico consultável já não está completo,
+/// mas sem novos descartes o componente volta a ficar saudável.
+pub async fn check_storage(store: &dyn StateStore) -> ComponentHealth {
+    let started = Instant::now();
+    match store.stats().await {
+        Ok(stats) => {
+            let status = storage_status(&stats, SystemTime::now());
+            ComponentHealth::new(components::STORAGE, status, stats.describe(), started)
+        }
+        Err(e) => ComponentHealth::new(
+            components::STORAGE,
+            HealthStatus::Degraded,
+            format!("Falha ao consultar ocupação: {}", e),
+            started,
+        ),
+    }
+}
+
+/// Degradado apenas se houve descarte de evento dentro da janela
+fn storage_status(stats: &StorageStats, now: SystemTime) -> HealthStatus {
+    let recent = stats.last_dropped_at
+        .is_some_and(|at| now.duration_since(at).unwrap_or_default() < DROPPED_EVENTS_WINDOW);
+    if recent { HealthStatus::Degraded } else { HealthStatus::Healthy }
+}
+
+/// Verifica a idade do último checkpoint contra 2× o intervalo configurado
+///
+/// Sem checkpoint algum, o sistema só é considerado degradado depois de ter
+/// rodado por mais de 2× o intervalo. Estratégias sem intervalo não têm
+/// limite de idade.
+pub async fn check_checkpoint(
+    store: &dyn StateStore,
+    interval: Option<Duration>,
+    running_since: SystemTime,
+) -> ComponentHealth {
+    let started = Instant::now();
+    let age_of = |time: SystemTime| SystemTime::now().duration_since(time).unwrap_or_default();
+    // Sem intervalo (contagem ou apenas shutdown) a idade não é limitada
+    let stale = |time: SystemTime| interval.map(|interval| interval * 2).filter(|max_age| age_of(time) > *max_age);
+
+    let (status, detail) = match store.last_checkpoint_at().await {
+        Err(e) => (HealthStatus::Unhealthy, format!("Falha ao consultar checkpoints: {}", e)),
+        Ok(Some(at)) => match stale(at) {
+            Some(max_age) => (
+                HealthStatus::Degraded,
+                format!("Último checkpoint há {}s (limite {}s)", age_of(at).as_secs(), max_age.as_secs()),
+            ),
+            None => (HealthStatus::Healthy, format!("Último checkpoint há {}s", age_of(at).as_secs())),
+        },
+        Ok(None) if stale(running_since).is_some() => (
+            HealthStatus::Degraded,
+            format!("Nenhum checkpoint em {}s de execução", age_of(running_since).as_secs()),
+        ),
+        Ok(None) => (HealthStatus::Healthy, "Nenhum checkpoint ainda".to_string()),
+    };
+    ComponentHealth::new(components::CHECKPOINT, status, detail, started)
+}
+
+/// Verifica a ocupação do pool de workers
+///
+/// Pool totalmente ocupado com tarefas aguardando na fila é degradado.
+pub async fn check_worker_pool(executor: &TaskExecutor, queued: usize) -> ComponentHealth {
+    let started = Instant::now();
+    let running = executor.running_count().await;
+    let capacity = executor.max_concurrency();
+    let status = if running >= capacity && queued > 0 {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    };
+    ComponentHealth::new(
+        components::WORKER_POOL,
+        status,
+        format!("{}/{} workers ocupados, {} tarefas na fila", running, capacity, queued),
+        started,
+    )
+}
+
+/// Verifica o acúmulo de escritas no buffer write-behind
+///
+/// Um buffer que atingiu o tamanho de lote indica que os flushes não estão
+/// acompanhando o volume de eventos.
+pub async fn check_event_buffer(executor: &TaskExecutor) -> ComponentHealth {
+    let started = Instant::now();
+    let backlog = executor.pending_write_count().await;
+    let limit = executor.config().write_behind_batch_size;
+    let status = if backlog >= limit {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    };
+    ComponentHealth::new(
+        components::EVENT_BUFFER,
+        status,
+        format!("{} escritas pendentes (lote {})", backlog, limit),
+        started,
+    )
+}
+
+/// Verifica se o despacho está pausado (manualmente ou por janela)
+pub fn check_dispatch(gate: &DispatchGate) -> ComponentHealth {
+    let started = Instant::now();
+    match gate.describe(SystemTime::now()) {
+        Some(reason) => ComponentHealth::new(
+            components::DISPATCH,
+            HealthStatus::Degraded,
+            format!("Despacho pausado: {}", reason),
+            started,
+        ),
+        None => ComponentHealth::new(components::DISPATCH, HealthStatus::Healthy, "ok", started),
+    }
+}
+
+/// Informa a capacidade bloqueada por reservas ativas
+///
+/// Reservas são planejadas, não uma falha: o componente é sempre saudável.
+pub fn check_reservations(reservations: &ReservationBook) -> ComponentHealth {
+    let started = Instant::now();
+    let active = reservations.active(SystemTime::now());
+    let cpu_cores: f64 = active.iter().map(|reservation| reservation.resources.cpu_cores).sum();
+    let memory_bytes = active.iter().fold(0u64, |total, reservation| total.saturating_add(reservation.resources.memory_bytes));
+    ComponentHealth::new(
+        components::RESERVATIONS,
+        HealthStatus::Healthy,
+        format!("{} reservas ativas ({} CPUs, {} bytes)", active.len(), cpu_cores, memory_bytes),
+        started,
+    )
+}
+
+/// Informa o modo de operação do core
+///
+/// Somente leitura é um estado válido (réplica em espera), não uma falha.
+pub fn check_mode(mode: Mode) -> ComponentHealth {
+    let detail = match mode {
+        Mode::Active => "ativo",
+        Mode::ReadOnly => "somente leitura",
+    };
+    ComponentHealth::new(components::MODE, HealthStatus::Healthy, detail, Instant::now())
+}
+
+/// Informa se os recursos estão reduzidos por ociosidade
+///
+/// Ociosidade é economia de recursos, não uma falha: o componente é sempre
+/// saudável.
+pub fn check_idle(status: &IdleStatus) -> ComponentHealth {
+    let detail = match (status.idle_since, status.last_warmup) {
+        (Some(since), _) => format!(
+            "ocioso há {:?} ({} workers ativos)",
+            SystemTime::now().duration_since(since).unwrap_or_default(),
+            status.active_workers
+        ),
+        (None, Some(warmup)) => format!("ativo (último aquecimento em {:?})", warmup),
+        (None, None) => "ativo".to_string(),
+    };
+    ComponentHealth::new(components::IDLE, HealthStatus::Healthy, detail, Instant::now())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use async_trait::async_trait;
+    use crate::manifest::ExecutionManifest;
+    use crate::types::*;
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    /// Armazenamento cujo backend caiu: toda operação falha
+    pub(crate) struct UnreachableStore;
+
+    fn down<T>() -> TaskMeshResult<T> {
+        Err(TaskMeshError::Internal("Connection refused".to_string()))
+    }
+
+    #[async_trait]
+    impl StateStore for UnreachableStore {
+        async fn store_task(&self, _: &Task) -> TaskMeshResult<()> { down() }
+        async fn get_task(&self, _: &TaskId) -> TaskMeshResult<Option<Task>> { down() }
+        async fn remove_task(&self, _: &TaskId) -> TaskMeshResult<()> { down() }
+        async fn update_task_status(&self, _: &TaskId, _: TaskStatus) -> TaskMeshResult<()> { down() }
+        async fn get_task_status(&self, _: &TaskId) -> TaskMeshResult<TaskStatus> { down() }
+        async fn get_status_history(&self, _: &TaskId) -> TaskMeshResult<Vec<StatusTransition>> { down() }
+        async fn list_tasks(&self) -> TaskMeshResult<Vec<Task>> { down() }
+        async fn list_tasks_by_status(&self, _: &[TaskStatus]) -> TaskMeshResult<Vec<Task>> { down() }
+        async fn store_event(&self, _: &SystemEvent) -> TaskMeshResult<()> { down() }
+        async fn query_events(&self, _: &EventQuery) -> TaskMeshResult<EventPage> { down() }
+        async fn store_metrics(&self, _: &TaskId, _: &ExecutionMetrics) -> TaskMeshResult<()> { down() }
+        async fn get_metrics(&self, _: &TaskId) -> TaskMeshResult<Option<ExecutionMetrics>> { down() }
+        async fn store_model(&self, _: &ModelRecord) -> TaskMeshResult<()> { down() }
+        async fn list_model_versions(&self, _: &str) -> TaskMeshResult<Vec<ModelRecord>> { down() }
+        async fn store_attempt(&self, _: &AttemptRecord) -> TaskMeshResult<()> { down() }
+        async fn list_attempts(&self, _: &TaskId) -> TaskMeshResult<Vec<AttemptRecord>> { down() }
+        async fn store_manifest(&self, _: &ExecutionManifest) -> TaskMeshResult<()> { down() }
+        async fn list_manifests(&self, _: &TaskId) -> TaskMeshResult<Vec<ExecutionManifest>> { down() }
+        async fn register_alias(&self, _: &str, _: &str, _: &TaskId) -> TaskMeshResult<()> { down() }
+        async fn resolve_alias(&self, _: &str, _: &str) -> TaskMeshResult<Option<TaskId>> { down() }
+        async fn put_setting(&self, _: &str, _: Option<&str>) -> TaskMeshResult<()> { down() }
+        async fn get_setting(&self, _: &str) -> TaskMeshResult<Option<String>> { down() }
+        async fn create_checkpoint(&self, _: &str) -> TaskMeshResult<()> { down() }
+        async fn restore_checkpoint(&self, _: &str) -> TaskMeshResult<()> { down() }
+        async fn list_checkpoints(&self) -> TaskMeshResult<Vec<String>> { down() }
+        async fn cleanup_old_data(&self, _: u32) -> TaskMeshResult<()> { down() }
+    }
+
+    #[test]
+    fn test_overall_is_worst_component() {
+        let started = Instant::now();
+        let report = HealthReport::from_components(vec![
+            ComponentHealth::new("a", HealthStatus::Healthy, "", started),
+            ComponentHealth::new("b", HealthStatus::Degraded, "", started),
+        ]);
+        assert_eq!(report.overall, HealthStatus::Degraded);
+        assert!(report.is_ready());
+        assert_eq!(HealthReport::from_components(vec![]).overall, HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_dropped_events_degrade_storage_only_within_window() {
+        let now = SystemTime::now();
+        let mut stats = StorageStats { dropped_events: 10, ..StorageStats::default() };
+        assert_eq!(storage_status(&stats, now), HealthStatus::Healthy);
+
+        stats.last_dropped_at = Some(now - Duration::from_secs(1));
+        assert_eq!(storage_status(&stats, now), HealthStatus::Degraded);
+
+        stats.last_dropped_at = Some(now - DROPPED_EVENTS_WINDOW - Duration::from_secs(1));
+        assert_eq!(storage_status(&stats, now), HealthStatus::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_dead_state_store_is_unhealthy() {
+        let mut core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        assert_eq!(core.health().await.overall, HealthStatus::Healthy);
+
+        core.state_store = Arc::new(UnreachableStore);
+        let report = core.health().await;
+        assert_eq!(report.overall, HealthStatus::Unhealthy);
+        assert!(!report.is_ready());
+        assert!(report.is_live());
+
+        let store = report.component(components::STATE_STORE).unwrap();
+        assert_eq!(store.status, HealthStatus::Unhealthy);
+        assert!(store.detail.contains("Connection refused"));
+        assert_eq!(report.component(components::WORKER_POOL).unwrap().status, HealthStatus::Healthy);
+    }
+}
@@ -0,0 +1,210 @@
+//! Hooks executados antes e depois de cada tarefa
+//!
+//! Avisos de início, envio de artefatos e limpeza do scratch rodam em volta
+//! da tarefa sem fazer parte do comando. Os hooks vêm de duas fontes, nesta
+//! ordem: `TaskMeshConfig::hooks` (todas as tarefas) e `Task::hooks` (ver
+//! [`Task::with_hook`]).
+//!
+//! Cada fase roda seus hooks em sequência, no mesmo contexto da tarefa
+//! (diretório de trabalho, scratch e ambiente), acrescido das variáveis
+//! `TASKMESH_HOOK_*`:
+//!
+//! - [`HookPhase::Before`]: antes do comando principal;
+//! - [`HookPhase::AfterSuccess`] / [`HookPhase::AfterFailure`]: conforme o
+//!   resultado, que chega em `TASKMESH_HOOK_EXIT_CODE`, `_ERROR` e
+//!   `_DURATION_MS`;
+//! - [`HookPhase::Finally`]: sempre, por último.
+//!
+//! Um hook `Before` ou `AfterSuccess` que falha com
+//! [`HookFailurePolicy::FailTask`] falha a tarefa (e um `Before` impede o
+//! comando principal); nas demais fases a falha só gera aviso. Os hooks são
+//! executados diretamente pelo worker, nunca como tarefas: hooks de hooks não
+//! existem. Cada execução fica registrada na tentativa como um [`HookRun`].
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+use crate::validation::Violation;
+
+/// Fase em execução (`before`, `after_success`, `after_failure`, `finally`)
+pub const HOOK_PHASE_ENV: &str = "TASKMESH_HOOK_PHASE";
+/// Rótulo do hook (ex.: `before[0]`)
+pub const HOOK_LABEL_ENV: &str = "TASKMESH_HOOK_LABEL";
+/// ID da tarefa principal
+pub const HOOK_TASK_ID_ENV: &str = "TASKMESH_HOOK_TASK_ID";
+/// Nome da tarefa principal
+pub const HOOK_TASK_NAME_ENV: &str = "TASKMESH_HOOK_TASK_NAME";
+/// `success` ou `failure` (hooks posteriores)
+pub const HOOK_STATUS_ENV: &str = "TASKMESH_HOOK_STATUS";
+/// Código de saída do comando principal, quando houve um
+pub const HOOK_EXIT_CODE_ENV: &str = "TASKMESH_HOOK_EXIT_CODE";
+/// Erro da tarefa principal, quando não houve código de saída
+pub const HOOK_ERROR_ENV: &str = "TASKMESH_HOOK_ERROR";
+/// Duração do comando principal (ms)
+pub const HOOK_DURATION_MS_ENV: &str = "TASKMESH_HOOK_DURATION_MS";
+
+/// Momento em que o hook roda
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookPhase {
+    Before,
+    AfterSuccess,
+    AfterFailure,
+    Finally,
+}
+
+impl HookPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookPhase::Before => "before",
+            HookPhase::AfterSuccess => "after_success",
+            HookPhase::AfterFailure => "after_failure",
+            HookPhase::Finally => "finally",
+        }
+    }
+}
+
+/// O que a falha de um hook causa à tarefa
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookFailurePolicy {
+    /// Falha a tarefa (só em `Before` e `AfterSuccess`)
+    #[default]
+    FailTask,
+    /// Registra um aviso e segue
+    WarnOnly,
+}
+
+/// Hook de uma tarefa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookSpec {
+    pub on: HookPhase,
+    /// O que executar (qualquer definição, exceto `Workflow` e `Generator`)
+    pub run: TaskDefinition,
+    /// Limite de duração (ms); ausente usa o timeout padrão do executor
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub failure_policy: HookFailurePolicy,
+}
+
+impl HookSpec {
+    pub fn new(on: HookPhase, run: TaskDefinition) -> Self {
+        Self { on, run, timeout_ms: None, failure_policy: HookFailurePolicy::default() }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    pub fn with_failure_policy(mut self, policy: HookFailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// A falha do hook falha a tarefa
+    pub fn fails_task(&self) -> bool {
+        self.failure_policy == HookFailurePolicy::FailTask
+            && matches!(self.on, HookPhase::Before | HookPhase::AfterSuccess)
+    }
+}
+
+/// Execução de um hook, registrada na tentativa da tarefa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRun {
+    /// Fase e posição na fase (ex.: `after_success[1]`)
+    pub label: String,
+    pub phase: HookPhase,
+    pub started_at: SystemTime,
+    pub finished_at: SystemTime,
+    /// Resultado do processo, quando houve um
+    pub result: Option<TaskResult>,
+    /// Erro ou código de saída diferente de zero
+    pub error: Option<String>,
+}
+
+impl HookRun {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl Task {
+    /// Acrescenta um hook à tarefa
+    pub fn with_hook(mut self, spec: HookSpec) -> Self {
+        self.hooks.push(spec);
+        self
+    }
+}
+
+/// Hooks de uma fase, com seus rótulos, na ordem de execução
+pub fn in_phase(hooks: &[HookSpec], phase: HookPhase) -> Vec<(String, &HookSpec)> {
+    hooks
+        .iter()
+        .filter(|hook| hook.on == phase)
+        .enumerate()
+        .map(|(index, hook)| (format!("{}[{}]", phase.as_str(), index), hook))
+        .collect()
+}
+
+/// Variáveis com o resumo do resultado principal, para os hooks posteriores
+pub fn result_env(result: &TaskMeshResult<TaskResult>, duration: Duration) -> HashMap<String, String> {
+    let succeeded = matches!(result, Ok(task_result) if task_result.exit_code == 0);
+    let mut env = HashMap::from([
+        (HOOK_STATUS_ENV.to_string(), if succeeded { "success" } else { "failure" }.to_string()),
+        (HOOK_DURATION_MS_ENV.to_string(), duration.as_millis().to_string()),
+    ]);
+    match result {
+        Ok(task_result) => env.insert(HOOK_EXIT_CODE_ENV.to_string(), task_result.exit_code.to_string()),
+        Err(error) => env.insert(HOOK_ERROR_ENV.to_string(), error.to_string()),
+    };
+    env
+}
+
+/// Verifica os hooks da tarefa
+///
+/// Os campos das violações recebem `prefix` (ex.: `tasks[2].`).
+pub fn validate(task: &Task, prefix: &str) -> Vec<Violation> {
+    validate_specs(&task.hooks, &format!("{}hooks", prefix))
+}
+
+/// Verifica uma lista de hooks (da tarefa ou `TaskMeshConfig::hooks`)
+pub fn validate_specs(hooks: &[HookSpec], field: &str) -> Vec<Violation> {
+    hooks
+        .iter()
+        .enumerate()
+        .filter(|(_, hook)| matches!(hook.run, TaskDefinition::Workflow { .. } | TaskDefinition::Generator { .. }))
+        .map(|(index, _)| Violation {
+            field: format!("{}[{}].run", field, index),
+            message: "hooks não podem ser workflows nem geradores".to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_follow_phase_position_and_workflows_are_rejected() {
+        let task = Task::new("t".to_string(), TaskDefinition::command("true"), vec![])
+            .with_hook(HookSpec::new(HookPhase::Finally, TaskDefinition::command("true")))
+            .with_hook(HookSpec::new(HookPhase::Before, TaskDefinition::command("true")))
+            .with_hook(HookSpec::new(HookPhase::Before, TaskDefinition::Workflow {
+                tasks: vec![],
+                execution_strategy: WorkflowStrategy::Sequential,
+            }));
+        let hooks = &task.hooks;
+        let labels: Vec<String> = in_phase(hooks, HookPhase::Before).into_iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["before[0]", "before[1]"]);
+        assert_eq!(in_phase(hooks, HookPhase::Finally)[0].0, "finally[0]");
+        assert!(task.metadata.is_empty());
+
+        let violations = validate(&task, "tasks[0].");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "tasks[0].hooks[2].run");
+    }
+}
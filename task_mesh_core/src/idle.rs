@@ -0,0 +1,292 @@
+//! Redução de recursos enquanto ocioso
+//!
+//! Embutido em aplicações de desktop, o core passa a maior parte do tempo
+//! sem tarefas. Depois de [`IdleConfig::idle_after`] sem tarefas na fila ou
+//! em execução, o [`IdleManager`] reduz os workers a
+//! [`IdleConfig::worker_floor`], fecha as conexões ociosas do StateStore até
+//! restarem [`IdleConfig::keep_connections`] e multiplica o intervalo de
+//! checkpoint por [`IdleConfig::timer_stretch`].
+//!
+//! A primeira submissão depois disso restaura tudo antes de registrar a
+//! tarefa; tarefas que chegam por outros caminhos (retries, execução direta
+//! no executor) são percebidas na verificação seguinte e usam os workers
+//! parados enquanto isso. Entradas e saídas da ociosidade publicam
+//! `ConfigurationChanged` com `"idle"`; a duração do aquecimento fica em
+//! [`IdleStatus::last_warmup`] e no histograma
+//! `taskmesh_idle_warmup_seconds` (feature `metrics`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::checkpoint::CheckpointEngine;
+use crate::event_bus::EventBus;
+use crate::executor::TaskExecutor;
+use crate::scheduler::Scheduler;
+use crate::state_store::StateStore;
+use crate::types::*;
+use crate::units::DurationSpec;
+
+/// Configuração da redução por ociosidade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleConfig {
+    /// Tempo sem tarefas até reduzir os recursos (ex.: `"5m"`); `None` desativa
+    #[serde(default)]
+    pub idle_after: Option<DurationSpec>,
+    /// Workers mantidos enquanto ocioso
+    #[serde(default)]
+    pub worker_floor: usize,
+    /// Conexões do StateStore mantidas enquanto ocioso (no mínimo 1)
+    #[serde(default = "default_keep_connections")]
+    pub keep_connections: u32,
+    /// Multiplicador do intervalo de checkpoint enquanto ocioso
+    #[serde(default = "default_timer_stretch")]
+    pub timer_stretch: u32,
+    /// Intervalo entre verificações da fila
+    #[serde(default = "default_check_interval")]
+    pub check_interval: DurationSpec,
+}
+
+fn default_keep_connections() -> u32 {
+    1
+}
+
+fn default_timer_stretch() -> u32 {
+    10
+}
+
+fn default_check_interval() -> DurationSpec {
+    DurationSpec::from_secs(1)
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            idle_after: None,
+            worker_floor: 0,
+            keep_connections: default_keep_connections(),
+            timer_stretch: default_timer_stretch(),
+            check_interval: default_check_interval(),
+        }
+    }
+}
+
+/// Estado da ociosidade
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IdleStatus {
+    /// Início da ociosidade atual (`None` quando ativo)
+    pub idle_since: Option<SystemTime>,
+    /// Workers não parados
+    pub active_workers: usize,
+    /// Conexões abertas com o StateStore, quando o backend informa
+    pub open_connections: Option<u32>,
+    /// Vezes que o core entrou em ociosidade
+    pub idle_entries: u64,
+    /// Aquecimentos concluídos
+    pub warmups: u64,
+    /// Duração do último aquecimento
+    pub last_warmup: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct IdleState {
+    /// Início do período atual sem tarefas
+    quiet_since: Option<Instant>,
+    idle_since: Option<SystemTime>,
+    idle_entries: u64,
+    warmups: u64,
+    last_warmup: Option<Duration>,
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct IdleMetrics {
+    idle: prometheus::IntGauge,
+    warmup_seconds: prometheus::Histogram,
+}
+
+/// Reduz e restaura os recursos do core conforme a fila
+pub struct IdleManager {
+    config: IdleConfig,
+    executor: Arc<TaskExecutor>,
+    scheduler: Arc<Scheduler>,
+    state_store: Arc<dyn StateStore>,
+    checkpoint_engine: Arc<CheckpointEngine>,
+    event_bus: Arc<EventBus>,
+    /// Leitura sem lock no caminho de submissão
+    idle: AtomicBool,
+    state: Mutex<IdleState>,
+    #[cfg(feature = "metrics")]
+    metrics: IdleMetrics,
+}
+
+impl IdleManager {
+    pub fn new(
+        config: IdleConfig,
+        executor: Arc<TaskExecutor>,
+        scheduler: Arc<Scheduler>,
+        state_store: Arc<dyn StateStore>,
+        checkpoint_engine: Arc<CheckpointEngine>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
+        Self {
+            config,
+            executor,
+            scheduler,
+            state_store,
+            checkpoint_engine,
+            event_bus,
+            idle: AtomicBool::new(false),
+            state: Mutex::new(IdleState::default()),
+            #[cfg(feature = "metrics")]
+            metrics: IdleMetrics {
+                idle: prometheus::IntGauge::new(
+                    "taskmesh_idle",
+                    "1 enquanto os recursos estão reduzidos por ociosidade",
+                ).expect("nome de métrica válido"),
+                warmup_seconds: prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+                    "taskmesh_idle_warmup_seconds",
+                    "Duração da restauração dos recursos ao sair da ociosidade",
+                )).expect("nome de métrica válido"),
+            },
+        }
+    }
+
+    /// Registra as métricas de ociosidade em um registry Prometheus
+    #[cfg(feature = "metrics")]
+    pub fn register_metrics(&self, registry: &prometheus::Registry) -> TaskMeshResult<()> {
+        registry
+            .register(Box::new(self.metrics.idle.clone()))
+            .and_then(|_| registry.register(Box::new(self.metrics.warmup_seconds.clone())))
+            .map_err(|e| TaskMeshError::Internal(format!("Erro ao registrar métricas de ociosidade: {}", e)))
+    }
+
+    pub fn config(&self) -> &IdleConfig {
+        &self.config
+    }
+
+    /// Indica se os recursos estão reduzidos
+    pub fn is_idle(&self) -> bool {
+        self.idle.load(Ordering::SeqCst)
+    }
+
+    /// Estado atual, com os contadores de recursos
+    pub async fn status(&self) -> IdleStatus {
+        let state = self.state.lock().await;
+        IdleStatus {
+            idle_since: state.idle_since,
+            active_workers: self.executor.active_worker_count().await,
+            open_connections: self.state_store.open_connections(),
+            idle_entries: state.idle_entries,
+            warmups: state.warmups,
+            last_warmup: state.last_warmup,
+        }
+    }
+
+    /// Nenhuma tarefa na fila, aguardando o executor ou em execução
+    async fn is_quiet(&self) -> bool {
+        self.scheduler.queue_depth() == 0
+            && self.executor.queued_count() == 0
+            && self.executor.running_count().await == 0
+    }
+
+    /// Verifica a fila e entra ou sai da ociosidade
+    ///
+    /// Retorna `true` se houve transição.
+    pub async fn check(&self) -> TaskMeshResult<bool> {
+        let Some(idle_after) = self.config.idle_after else { return Ok(false) };
+        let quiet = self.is_quiet().await;
+        if self.is_idle() {
+            // Tarefas que não passaram pela submissão (ex.: retries)
+            return Ok(!quiet && self.warm_up().await?.is_some());
+        }
+
+        let mut state = self.state.lock().await;
+        if !quiet {
+            state.quiet_since = None;
+            return Ok(false);
+        }
+        let quiet_since = *state.quiet_since.get_or_insert_with(Instant::now);
+        if quiet_since.elapsed() < idle_after.as_duration() {
+            return Ok(false);
+        }
+
+        self.executor.scale_workers(self.config.worker_floor).await?;
+        self.state_store.shrink_pool(self.config.keep_connections).await?;
+        self.checkpoint_engine.set_interval_stretch(self.config.timer_stretch);
+        let now = SystemTime::now();
+        state.idle_since = Some(now);
+        state.idle_entries += 1;
+        self.idle.store(true, Ordering::SeqCst);
+        #[cfg(feature = "metrics")]
+        self.metrics.idle.set(1);
+        drop(state);
+
+        let connections = self.state_store.open_connections();
+        info!(
+            "Ocioso há {}: recursos reduzidos ({} workers, {:?} conexões)",
+            idle_after, self.config.worker_floor, connections
+        );
+        self.event_bus.publish(SystemEvent::new(
+            EventType::ConfigurationChanged,
+            None,
+            serde_json::json!({
+                "idle": true,
+                "workers": self.config.worker_floor,
+                "connections": connections,
+                "checkpoint_interval_stretch": self.config.timer_stretch,
+            }),
+        ).at(now)).await?;
+        Ok(true)
+    }
+
+    /// Restaura os recursos reduzidos, retornando a duração do aquecimento
+    ///
+    /// Retorna `None` se o core não estava ocioso.
+    pub async fn warm_up(&self) -> TaskMeshResult<Option<Duration>> {
+        if !self.is_idle() {
+            return Ok(None);
+        }
+        let mut state = self.state.lock().await;
+        // Outra submissão pode ter aquecido enquanto esperávamos o lock
+        if !self.is_idle() {
+            return Ok(None);
+        }
+
+        let started = Instant::now();
+        self.executor.scale_workers(self.executor.max_concurrency()).await?;
+        self.checkpoint_engine.set_interval_stretch(1);
+        // Reabre uma conexão antes da gravação da tarefa
+        self.state_store.ping().await?;
+        let warmup = started.elapsed();
+
+        let idle_for = state.idle_since.take()
+            .and_then(|since| SystemTime::now().duration_since(since).ok())
+            .unwrap_or_default();
+        state.quiet_since = None;
+        state.warmups += 1;
+        state.last_warmup = Some(warmup);
+        self.idle.store(false, Ordering::SeqCst);
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics.idle.set(0);
+            self.metrics.warmup_seconds.observe(warmup.as_secs_f64());
+        }
+        drop(state);
+
+        info!("Saindo da ociosidade após {:?}: recursos restaurados em {:?}", idle_for, warmup);
+        self.event_bus.publish(SystemEvent::new(
+            EventType::ConfigurationChanged,
+            None,
+            serde_json::json!({
+                "idle": false,
+                "idle_ms": idle_for.as_millis() as u64,
+                "warmup_ms": warmup.as_millis() as u64,
+            }),
+        )).await?;
+        Ok(Some(warmup))
+    }
+}
@@ -0,0 +1,322 @@
+//! Importação de tarefas criadas fora do TaskMesh
+//!
+//! Registros históricos (tarefa, status final e resultado) são gravados no
+//! StateStore e adotados pelo registro sem serem executados
+//! ([`crate::TaskMeshCore::import_tasks`]). O importador de referência lê
+//! JSON-lines, um registro por linha, com a serialização JSON do TaskMesh:
+//!
+//! ```text
+//! {"task": {...}, "status": {"Completed": {...}}, "result": null}
+//! ```
+//!
+//! `result`, quando presente, substitui o resultado de um status `Completed`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::alias;
+use crate::generator::NAMESPACE_KEY;
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Registro importado: tarefa, status e resultado opcional
+pub type ImportRecord = (Task, TaskStatus, Option<TaskResult>);
+
+/// Linha do formato JSON-lines de referência
+#[derive(Debug, Deserialize)]
+struct JsonLineRecord {
+    task: Task,
+    status: TaskStatus,
+    #[serde(default)]
+    result: Option<TaskResult>,
+}
+
+/// Origem dos registros a importar
+pub struct ImportSource {
+    records: Box<dyn Iterator<Item = TaskMeshResult<ImportRecord>> + Send>,
+}
+
+impl ImportSource {
+    /// Importa registros já em memória
+    pub fn from_records<I>(records: I) -> Self
+    where
+        I: IntoIterator<Item = ImportRecord>,
+        I::IntoIter: Send + 'static,
+    {
+        Self { records: Box::new(records.into_iter().map(Ok)) }
+    }
+
+    /// Lê registros JSON-lines; linhas em branco são ignoradas
+    pub fn json_lines(reader: impl BufRead + Send + 'static) -> Self {
+        let records = reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .map(|(index, line)| {
+                let line = line?;
+                let record: JsonLineRecord = serde_json::from_str(&line).map_err(|e| {
+                    TaskMeshError::Configuration(format!("Linha {} inválida: {}", index + 1, e))
+                })?;
+                Ok((record.task, record.status, record.result))
+            });
+        Self { records: Box::new(records) }
+    }
+
+    /// Lê registros JSON-lines de um arquivo
+    pub fn json_lines_file(path: impl AsRef<Path>) -> TaskMeshResult<Self> {
+        Ok(Self::json_lines(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl Iterator for ImportSource {
+    type Item = TaskMeshResult<ImportRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records.next()
+    }
+}
+
+/// O que fazer quando um ID importado já existe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionPolicy {
+    /// Ignora o registro
+    Skip,
+    /// Importa com um novo ID
+    Rename,
+}
+
+/// Tratamento dos IDs importados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdPolicy {
+    /// Mantém o ID de origem, aplicando a política em colisões
+    Preserve(CollisionPolicy),
+    /// Gera um novo ID para todos os registros
+    Regenerate,
+}
+
+/// Opções de importação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportOptions {
+    /// Namespace de origem → namespace de destino (`default` cobre tarefas
+    /// sem namespace)
+    pub namespace_map: HashMap<String, String>,
+    pub id_policy: IdPolicy,
+    /// Tarefas importadas como `Completed` liberam seus dependentes
+    pub satisfies_dependencies: bool,
+    /// Registros por transação
+    pub batch_size: usize,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            namespace_map: HashMap::new(),
+            id_policy: IdPolicy::Preserve(CollisionPolicy::Skip),
+            satisfies_dependencies: true,
+            batch_size: 500,
+        }
+    }
+}
+
+/// Resultado de uma importação
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    /// Colisões de ID ou de alias encontradas
+    pub conflicts: usize,
+    /// IDs trocados na importação (origem → destino)
+    pub remapped: HashMap<TaskId, TaskId>,
+    pub batches: usize,
+}
+
+/// Estado de uma importação entre lotes
+///
+/// IDs renomeados ou regenerados são reescritos nas dependências dos
+/// registros seguintes; dependências de registros posteriores mantêm o ID
+/// de origem.
+pub(crate) struct ImportPlan {
+    options: ImportOptions,
+    seen: HashSet<TaskId>,
+    summary: ImportSummary,
+}
+
+impl ImportPlan {
+    pub(crate) fn new(mut options: ImportOptions) -> Self {
+        options.batch_size = options.batch_size.max(1);
+        Self { options, seen: HashSet::new(), summary: ImportSummary::default() }
+    }
+
+    pub(crate) fn satisfies_dependencies(&self) -> bool {
+        self.options.satisfies_dependencies
+    }
+
+    /// Lê e prepara o próximo lote; vazio quando a origem termina
+    pub(crate) async fn next_batch(
+        &mut self,
+        store: &dyn StateStore,
+        source: &mut ImportSource,
+    ) -> TaskMeshResult<Vec<(Task, TaskStatus)>> {
+        let mut batch = Vec::with_capacity(self.options.batch_size);
+        while batch.len() < self.options.batch_size {
+            let Some(record) = source.next() else { break };
+            if let Some(prepared) = self.prepare(store, record?).await? {
+                batch.push(prepared);
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Contabiliza um lote gravado
+    pub(crate) fn record_batch(&mut self, batch: &[(Task, TaskStatus)]) {
+        self.summary.imported += batch.len();
+        self.summary.batches += 1;
+    }
+
+    pub(crate) fn finish(self) -> ImportSummary {
+        self.summary
+    }
+
+    async fn prepare(
+        &mut self,
+        store: &dyn StateStore,
+        (mut task, mut status, result): ImportRecord,
+    ) -> TaskMeshResult<Option<(Task, TaskStatus)>> {
+        let original = task.id;
+        let collides = self.seen.contains(&original) || store.get_task(&original).await?.is_some();
+
+        match self.options.id_policy {
+            IdPolicy::Regenerate => task.id = TaskId::new_v4(),
+            IdPolicy::Preserve(_) if !collides => {}
+            IdPolicy::Preserve(CollisionPolicy::Skip) => {
+                self.summary.conflicts += 1;
+                self.summary.skipped += 1;
+                return Ok(None);
+            }
+            IdPolicy::Preserve(CollisionPolicy::Rename) => {
+                self.summary.conflicts += 1;
+                task.id = TaskId::new_v4();
+            }
+        }
+        if task.id != original {
+            self.summary.remapped.insert(original, task.id);
+        }
+        self.seen.insert(original);
+        self.seen.insert(task.id);
+
+        for dependency in &mut task.dependencies {
+            if let Some(new_id) = self.summary.remapped.get(dependency) {
+                *dependency = *new_id;
+            }
+        }
+
+        let namespace = alias::namespace_of(&task).to_string();
+        if let Some(target) = self.options.namespace_map.get(&namespace) {
+            task.metadata.insert(NAMESPACE_KEY.to_string(), target.clone());
+        }
+
+        if let Some(name) = task.alias.take() {
+            let namespace = alias::namespace_of(&task).to_string();
+            match alias::validate_alias(&name) {
+                Err(e) => warn!("Alias descartado na importação de {}: {}", original, e),
+                Ok(()) if store.resolve_alias(&namespace, &name).await?.is_some() => {
+                    warn!("Alias '{}/{}' já em uso, importando {} sem alias", namespace, name, original);
+                    self.summary.conflicts += 1;
+                }
+                Ok(()) => task.alias = Some(name),
+            }
+        }
+
+        if let (Some(result), TaskStatus::Completed { result: existing, .. }) = (result, &mut status) {
+            *existing = result;
+        }
+
+        Ok(Some((task, status)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskMeshConfig, TaskMeshCore};
+    use std::io::Cursor;
+    use std::time::SystemTime;
+
+    fn historical_task(name: &str) -> Task {
+        Task::new(name.to_string(), TaskDefinition::command("true"), vec![])
+    }
+
+    fn completed() -> TaskStatus {
+        TaskStatus::Completed {
+            started_at: SystemTime::UNIX_EPOCH,
+            completed_at: SystemTime::UNIX_EPOCH,
+            result: TaskResult {
+                exit_code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+                output_data: None,
+                metrics: ExecutionMetrics::default(),
+                log_ref: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_json_lines_skip_and_rename_collisions() {
+        let tasks: Vec<Task> = (0..1000).map(|i| historical_task(&format!("hist-{}", i))).collect();
+        let lines: String = tasks
+            .iter()
+            .map(|task| format!("{}\n", serde_json::json!({ "task": task, "status": completed() })))
+            .collect();
+
+        for (policy, imported, skipped) in [(CollisionPolicy::Skip, 990, 10), (CollisionPolicy::Rename, 1000, 0)] {
+            let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+            for task in tasks.iter().step_by(100) {
+                core.state_store.store_task(task).await.unwrap();
+            }
+
+            let options = ImportOptions {
+                id_policy: IdPolicy::Preserve(policy),
+                batch_size: 128,
+                ..ImportOptions::default()
+            };
+            let source = ImportSource::json_lines(Cursor::new(lines.clone().into_bytes()));
+            let summary = core.import_tasks(source, options).await.unwrap();
+
+            assert_eq!(summary.imported, imported, "{:?}", policy);
+            assert_eq!(summary.skipped, skipped, "{:?}", policy);
+            assert_eq!(summary.conflicts, 10, "{:?}", policy);
+            assert_eq!(summary.batches, imported.div_ceil(128));
+            assert!(matches!(core.get_task_status(tasks[1].id).await.unwrap(), TaskStatus::Completed { .. }));
+
+            if policy == CollisionPolicy::Rename {
+                assert_eq!(summary.remapped.len(), 10);
+                let renamed = summary.remapped[&tasks[100].id];
+                assert!(matches!(core.get_task_status(renamed).await.unwrap(), TaskStatus::Completed { .. }));
+            } else {
+                assert!(summary.remapped.is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_imported_completed_tasks_satisfy_dependencies() {
+        for satisfies in [true, false] {
+            let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+            let upstream = historical_task("upstream");
+            let options = ImportOptions { satisfies_dependencies: satisfies, ..ImportOptions::default() };
+            let source = ImportSource::from_records(vec![(upstream.clone(), completed(), None)]);
+            core.import_tasks(source, options).await.unwrap();
+
+            let downstream = Task::new("downstream".to_string(), TaskDefinition::command("true"), vec![upstream.id]);
+            let downstream_id = core.submit_task(downstream).await.unwrap();
+
+            let next = core.scheduler.get_next_task(&ResourceAllocation::default()).await;
+            assert_eq!(next == Some(downstream_id), satisfies);
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Registro versionado de modelos de aprendizado
+//!
+//! Modelos são serializados em um blob binário com cabeçalho de formato
+//! ([`encode_model`]) e persistidos pelo [`StateStore`], de modo que um
+//! reinício carrega o último modelo promovido em vez de retreinar do zero.
+//!
+//! Cada nome tem no máximo uma versão promovida. As predições dela são
+//! comparadas com as execuções reais via [`ModelRegistry::record_outcome`];
+//! se o erro relativo ficar acima do limite em `rollback_window` tarefas
+//! seguidas, o registro volta para a versão promovida anteriormente.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Assinatura do blob de modelo
+pub const MODEL_BLOB_MAGIC: [u8; 4] = *b"TMML";
+
+/// Versão do formato do blob; incrementada a cada mudança incompatível
+pub const MODEL_BLOB_FORMAT: u16 = 1;
+
+const MODEL_BLOB_HEADER_LEN: usize = MODEL_BLOB_MAGIC.len() + 2;
+
+/// Serializa um modelo no formato versionado
+pub fn encode_model<T: Serialize>(model: &T) -> TaskMeshResult<Vec<u8>> {
+    let payload = bincode::serialize(model)
+        .map_err(|e| TaskMeshError::Internal(format!("Erro de serialização: {}", e)))?;
+    let mut blob = Vec::with_capacity(MODEL_BLOB_HEADER_LEN + payload.len());
+    blob.extend_from_slice(&MODEL_BLOB_MAGIC);
+    blob.extend_from_slice(&MODEL_BLOB_FORMAT.to_le_bytes());
+    blob.extend_from_slice(&payload);
+    Ok(blob)
+}
+
+/// Desserializa um blob produzido por [`encode_model`]
+pub fn decode_model<T: DeserializeOwned>(blob: &[u8]) -> TaskMeshResult<T> {
+    check_header(blob)?;
+    bincode::deserialize(&blob[MODEL_BLOB_HEADER_LEN..])
+        .map_err(|e| TaskMeshError::Internal(format!("Blob de modelo inválido: {}", e)))
+}
+
+fn check_header(blob: &[u8]) -> TaskMeshResult<()> {
+    if blob.len() < MODEL_BLOB_HEADER_LEN || blob[..MODEL_BLOB_MAGIC.len()] != MODEL_BLOB_MAGIC {
+        return Err(TaskMeshError::Internal("Blob de modelo sem cabeçalho".to_string()));
+    }
+    let format = u16::from_le_bytes([blob[4], blob[5]]);
+    if format != MODEL_BLOB_FORMAT {
+        return Err(TaskMeshError::Internal(format!(
+            "Formato de modelo {} não suportado (esperado {})",
+            format, MODEL_BLOB_FORMAT
+        )));
+    }
+    Ok(())
+}
+
+/// Configuração do registro de modelos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelRegistryConfig {
+    /// Erro relativo (|previsto - real| / real) considerado uma falha
+    pub max_relative_error: f64,
+    /// Falhas consecutivas que disparam o rollback
+    pub rollback_window: usize,
+}
+
+impl Default for ModelRegistryConfig {
+    fn default() -> Self {
+        Self {
+            max_relative_error: 0.5,
+            rollback_window: 5,
+        }
+    }
+}
+
+/// Métricas do pipeline de aprendizado
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LearningMetrics {
+    /// Versões de modelo salvas
+    pub models_trained: u64,
+    /// Predições comparadas com execuções reais
+    pub predictions_made: u64,
+    /// Média de `1 - erro relativo` (limitada a [0, 1]) das predições
+    pub prediction_accuracy: f64,
+    /// Rollbacks automáticos realizados
+    pub rollbacks: u64,
+}
+
+/// Acompanhamento da versão promovida de um modelo
+#[derive(Debug, Clone)]
+struct LiveModel {
+    version: u32,
+    /// Versão promovida antes desta, alvo do rollback
+    previous: Option<u32>,
+    consecutive_misses: usize,
+}
+
+/// Registro versionado de modelos
+pub struct ModelRegistry {
+    store: Arc<dyn StateStore>,
+    config: ModelRegistryConfig,
+    live: RwLock<HashMap<String, LiveModel>>,
+    metrics: RwLock<LearningMetrics>,
+}
+
+impl ModelRegistry {
+    pub fn new(store: Arc<dyn StateStore>, config: ModelRegistryConfig) -> Self {
+        Self {
+            store,
+            config,
+            live: RwLock::new(HashMap::new()),
+            metrics: RwLock::new(LearningMetrics::default()),
+        }
+    }
+
+    /// Salva uma nova versão; a versão promovida não muda
+    pub async fn save_model(
+        &self,
+        name: &str,
+        version: u32,
+        blob: Vec<u8>,
+        metrics: HashMap<String, f64>,
+    ) -> TaskMeshResult<ModelRecord> {
+        check_header(&blob)?;
+        let versions = self.store.list_model_versions(name).await?;
+        if versions.iter().any(|model| model.version == version) {
+            return Err(TaskMeshError::Configuration(format!(
+                "Modelo {} v{} já existe",
+                name, version
+            )));
+        }
+
+        let record = ModelRecord {
+            name: name.to_string(),
+            version,
+            blob,
+            metrics,
+            promoted: false,
+            created_at: SystemTime::now(),
+        };
+        self.store.store_model(&record).await?;
+        self.metrics.write().await.models_trained += 1;
+
+        info!("Modelo {} v{} salvo", name, version);
+        Ok(record)
+    }
+
+    /// Versão em uso: a promovida ou, sem promoção, a mais recente
+    pub async fn load_latest(&self, name: &str) -> TaskMeshResult<Option<ModelRecord>> {
+        let mut versions = self.store.list_model_versions(name).await?;
+        match versions.iter().rposition(|model| model.promoted) {
+            Some(index) => Ok(Some(versions.swap_remove(index))),
+            None => Ok(versions.pop()),
+        }
+    }
+
+    /// Promove uma versão, despromovendo as demais
+    pub async fn promote(&self, name: &str, version: u32) -> TaskMeshResult<()> {
+        let previous = self.set_promoted(name, version).await?;
+        self.live.write().await.insert(
+            name.to_string(),
+            LiveModel {
+                version,
+                previous: previous.filter(|&previous| previous != version),
+                consecutive_misses: 0,
+            },
+        );
+        info!("Modelo {} v{} promovido", name, version);
+        Ok(())
+    }
+
+    /// Grava a flag de promoção e retorna a versão promovida anterior
+    async fn set_promoted(&self, name: &str, version: u32) -> TaskMeshResult<Option<u32>> {
+        let versions = self.store.list_model_versions(name).await?;
+        if !versions.iter().any(|model| model.version == version) {
+            return Err(TaskMeshError::Configuration(format!(
+                "Modelo {} v{} não existe",
+                name, version
+            )));
+        }
+
+        let previous = versions.iter().rev().find(|model| model.promoted).map(|model| model.version);
+        for mut model in versions {
+            let promoted = model.version == version;
+            if model.promoted != promoted {
+                model.promoted = promoted;
+                self.store.store_model(&model).await?;
+            }
+        }
+        Ok(previous)
+    }
+
+    /// Compara uma predição da versão em uso com o valor real observado
+    ///
+    /// Retorna a versão restaurada quando o erro dispara um rollback.
+    pub async fn record_outcome(&self, name: &str, predicted: f64, actual: f64) -> TaskMeshResult<Option<u32>> {
+        let relative_error = (predicted - actual).abs() / actual.abs().max(f64::EPSILON);
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.predictions_made += 1;
+            let accuracy = (1.0 - relative_error).clamp(0.0, 1.0);
+            metrics.prediction_accuracy +=
+                (accuracy - metrics.prediction_accuracy) / metrics.predictions_made as f64;
+        }
+
+        if !self.live.read().await.contains_key(name) {
+            // Após um reinício o acompanhamento parte da versão persistida
+            let Some(current) = self.load_latest(name).await? else {
+                return Ok(None);
+            };
+            self.live.write().await.entry(name.to_string()).or_insert(LiveModel {
+                version: current.version,
+                previous: None,
+                consecutive_misses: 0,
+            });
+        }
+
+        let (current, target) = {
+            let mut live = self.live.write().await;
+            let Some(model) = live.get_mut(name) else {
+                return Ok(None);
+            };
+            if relative_error <= self.config.max_relative_error {
+                model.consecutive_misses = 0;
+                return Ok(None);
+            }
+            model.consecutive_misses += 1;
+            if model.consecutive_misses < self.config.rollback_window.max(1) {
+                return Ok(None);
+            }
+            (model.version, model.previous)
+        };
+
+        let target = match target {
+            Some(version) => Some(version),
+            None => self
+                .store
+                .list_model_versions(name)
+                .await?
+                .iter()
+                .map(|model| model.version)
+                .filter(|&version| version < current)
+                .max(),
+        };
+        let Some(target) = target else {
+            warn!("Modelo {} v{} degradado, mas não há versão anterior", name, current);
+            if let Some(model) = self.live.write().await.get_mut(name) {
+                model.consecutive_misses = 0;
+            }
+            return Ok(None);
+        };
+
+        warn!(
+            "Modelo {} v{} excedeu erro {:.2} em {} tarefas seguidas; revertendo para v{}",
+            name, current, self.config.max_relative_error, self.config.rollback_window, target
+        );
+        self.set_promoted(name, target).await?;
+        // Sem alvo de rollback: a versão revertida não volta automaticamente
+        self.live.write().await.insert(
+            name.to_string(),
+            LiveModel { version: target, previous: None, consecutive_misses: 0 },
+        );
+        self.metrics.write().await.rollbacks += 1;
+        Ok(Some(target))
+    }
+
+    /// Métricas atuais do pipeline
+    pub async fn metrics(&self) -> LearningMetrics {
+        self.metrics.read().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_store::SqliteStateStore;
+
+    #[tokio::test]
+    async fn test_degraded_promoted_model_rolls_back() {
+        let store: Arc<dyn StateStore> = Arc::new(SqliteStateStore::new("sqlite::memory:").await.unwrap());
+        let registry = ModelRegistry::new(store.clone(), ModelRegistryConfig::default());
+
+        let v1 = encode_model(&vec![1.0_f64, 0.5]).unwrap();
+        let v2 = encode_model(&vec![2.0_f64, 0.1]).unwrap();
+        registry.save_model("duration", 1, v1, HashMap::from([("mae".to_string(), 0.2)])).await.unwrap();
+        registry.promote("duration", 1).await.unwrap();
+        registry.save_model("duration", 2, v2, HashMap::new()).await.unwrap();
+        assert!(registry.save_model("duration", 2, encode_model(&0u8).unwrap(), HashMap::new()).await.is_err());
+        registry.promote("duration", 2).await.unwrap();
+
+        let latest = registry.load_latest("duration").await.unwrap().unwrap();
+        assert_eq!(latest.version, 2);
+        assert_eq!(decode_model::<Vec<f64>>(&latest.blob).unwrap(), vec![2.0, 0.1]);
+
+        // Predições boas não disparam nada
+        assert_eq!(registry.record_outcome("duration", 100.0, 110.0).await.unwrap(), None);
+
+        // v2 degrada: erro de 300% por 5 tarefas seguidas
+        let window = ModelRegistryConfig::default().rollback_window;
+        for _ in 0..window - 1 {
+            assert_eq!(registry.record_outcome("duration", 400.0, 100.0).await.unwrap(), None);
+        }
+        assert_eq!(registry.record_outcome("duration", 400.0, 100.0).await.unwrap(), Some(1));
+
+        let latest = registry.load_latest("duration").await.unwrap().unwrap();
+        assert_eq!(latest.version, 1);
+        assert_eq!(latest.metrics["mae"], 0.2);
+
+        let metrics = registry.metrics().await;
+        assert_eq!(metrics.models_trained, 2);
+        assert_eq!(metrics.predictions_made, window as u64 + 1);
+        assert_eq!(metrics.rollbacks, 1);
+        assert!(metrics.prediction_accuracy > 0.0 && metrics.prediction_accuracy < 0.5);
+
+        // Um novo registro sobre o mesmo armazenamento vê a versão revertida
+        let restarted = ModelRegistry::new(store, ModelRegistryConfig::default());
+        assert_eq!(restarted.load_latest("duration").await.unwrap().unwrap().version, 1);
+    }
+
+    #[test]
+    fn test_model_blob_rejects_unknown_format() {
+        let mut blob = encode_model(&42u32).unwrap();
+        assert_eq!(decode_model::<u32>(&blob).unwrap(), 42);
+
+        blob[4] = 0xff;
+        assert!(decode_model::<u32>(&blob).is_err());
+        assert!(decode_model::<u32>(b"xx").is_err());
+    }
+}
@@ -13,19 +13,83 @@
 //! - **ErrorHandler**: Tratamento robusto de erros com retry patterns
 //! - **FFI**: Interface Python via maturin/PyO3
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::{info, warn, error};
+use tracing::{info, warn, error, Instrument};
 
 // Módulos públicos
 pub mod task_registry;
 pub mod scheduler;
+pub mod plan_optimizer;
 pub mod executor;
 pub mod state_store;
 pub mod checkpoint;
 pub mod error_handler;
 pub mod types;
 pub mod metrics;
+pub mod log_store;
+pub mod scratch;
+pub mod sla;
+pub mod maintenance;
+pub mod reservation;
+pub mod watchdog;
+pub mod logging;
+pub mod event_bus;
+pub mod migrations;
+pub mod process_metrics;
+pub mod progress;
+pub mod manifest;
+pub mod report;
+pub mod generator;
+pub mod health;
+pub mod preflight;
+pub use system_events::background;
+pub mod learning;
+pub mod features;
+pub mod alias;
+pub mod workflow_file;
+pub mod validation;
+pub mod import;
+pub mod compat;
+pub mod process_controls;
+pub mod sidecar;
+pub mod hooks;
+pub mod gc;
+pub mod functions;
+pub mod python_pool;
+pub mod codec;
+pub mod concurrency_group;
+pub mod compaction;
+pub mod units;
+pub mod problem;
+pub mod idle;
+pub mod encryption;
+pub mod blocking;
+pub mod forced_outcome;
+pub mod dispatch_latency;
+pub mod retry_rules;
+pub mod dead_letter;
+pub mod execution_hint;
+pub mod defaults;
+pub mod status_cache;
+pub mod lifecycle;
+
+// Exportação de histórico em Parquet/CSV (opcional)
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+// Limites rígidos de memória/CPU via cgroup v2 (opcional)
+#[cfg(feature = "cgroups")]
+pub mod cgroups;
+// Funções WASM com cancelamento por interrupção de época (opcional)
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(test)]
+mod state_store_conformance;
 
 // FFI Python (opcional)
 #[cfg(feature = "python")]
@@ -35,11 +99,75 @@ pub mod python_bindings;
 pub use task_registry::TaskRegistry;
 pub use scheduler::{Scheduler, SchedulingHeuristic};
 pub use executor::{TaskExecutor, ExecutionContext};
-pub use state_store::{StateStore, StorageBackend};
-pub use checkpoint::{CheckpointEngine, CheckpointStrategy};
+pub use state_store::{SqliteConfig, StateStore, StorageBackend, StorageStats};
+pub use checkpoint::{CheckpointEngine, CheckpointInfo, CheckpointStrategy, CheckpointTrigger};
 pub use error_handler::{ErrorHandler, RetryPolicy};
+pub use report::{ReportFormat, TimelineReport};
+pub use health::{ComponentHealth, HealthReport, HealthStatus};
+pub use preflight::{CheckId, CheckStatus, PreflightReport};
+pub use background::BackgroundTasks;
+pub use learning::{LearningMetrics, ModelRegistry};
+pub use alias::TaskRef;
+pub use progress::{ProgressReporter, ProgressUpdate};
+pub use functions::{FnContext, FunctionRegistry};
+pub use manifest::ExecutionManifest;
+pub use sidecar::{ReadinessProbe, SidecarSpec};
+pub use logging::{init_logging, LogConfig, LogFormat, LogRotation};
+pub use event_bus::{BusEvent, EventBus, EventBusStats, Subscription};
+pub use workflow_file::WorkflowFile;
+pub use import::{ImportOptions, ImportSource, ImportSummary};
+pub use retry_rules::{PolicyDecision, RetryRule, RetryRules};
+pub use dead_letter::DeadLetter;
+pub use lifecycle::{LifecycleContext, LifecyclePhase, LifecycleStats};
 pub use types::*;
 
+/// Tempo máximo de espera pelos loops de background no shutdown
+const BACKGROUND_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Intervalo em que `wait_any`/`wait_all` verificam eventos descartados
+const WAIT_LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Limite padrão de tarefas por lote ou workflow (`max_workflow_tasks`)
+pub const DEFAULT_MAX_WORKFLOW_TASKS: usize = 100_000;
+
+/// Tarefas registradas por vez em lotes grandes (`submit_chunk_size`)
+pub const DEFAULT_SUBMIT_CHUNK_SIZE: usize = 1_000;
+
+fn default_max_workflow_tasks() -> usize {
+    DEFAULT_MAX_WORKFLOW_TASKS
+}
+
+fn default_submit_chunk_size() -> usize {
+    DEFAULT_SUBMIT_CHUNK_SIZE
+}
+
+/// Modo de operação do core
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Despacha e executa tarefas
+    #[default]
+    Active,
+    /// Réplica em espera: atende consultas, nunca despacha nem grava
+    ReadOnly,
+}
+
+/// Configuração persistente com os ajustes de execução (JSON de [`RuntimeTuning`])
+pub const RUNTIME_TUNING_SETTING: &str = "runtime_tuning";
+
+/// Heurística e concorrência alteradas sem reiniciar
+///
+/// Gravadas por [`TaskMeshCore::set_scheduling_heuristic`] e
+/// [`TaskMeshCore::set_max_concurrency`]; prevalecem sobre a configuração
+/// nos próximos inícios e são reaplicadas por [`TaskMeshCore::load_queue`].
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct RuntimeTuning {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduling_heuristic: Option<SchedulingHeuristic>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+}
+
 /// Configuração principal do TaskMesh Core
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TaskMeshConfig {
@@ -49,12 +177,107 @@ pub struct TaskMeshConfig {
     pub redis_url: Option<String>,
     /// Número máximo de workers
     pub max_workers: usize,
-    /// Intervalo de checkpoint em segundos
-    pub checkpoint_interval: u64,
+    /// Intervalo de checkpoint (ex.: `"30s"`; números são segundos)
+    pub checkpoint_interval: units::DurationSpec,
+    /// Estratégia de checkpoint (padrão: a cada `checkpoint_interval`)
+    #[serde(default)]
+    pub checkpoint_strategy: Option<CheckpointStrategy>,
+    /// Codec e compressão dos checkpoints (SQLite; o Redis grava JSON)
+    #[serde(default)]
+    pub checkpoint_codec: codec::CodecConfig,
+    /// Cifragem das tarefas por namespace (apenas SQLite)
+    #[serde(default)]
+    pub encryption: Option<encryption::EncryptionConfig>,
     /// Estratégia de retry padrão
     pub retry_policy: RetryPolicy,
+    /// Regras de retry por categoria de erro e tag, avaliadas em ordem antes de `retry_policy`
+    #[serde(default)]
+    pub retry_rules: Vec<retry_rules::RetryRule>,
     /// Habilitar métricas
     pub enable_metrics: bool,
+    /// Configuração do backend SQLite
+    #[serde(default)]
+    pub sqlite: SqliteConfig,
+    /// Durabilidade estrita: desabilita o buffer write-behind do executor
+    #[serde(default)]
+    pub strict_durability: bool,
+    /// Diretório de dados (logs de tarefas em `{data_dir}/logs`)
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    /// Máximo de tarefas pendentes aguardando despacho (`None` = ilimitado)
+    #[serde(default)]
+    pub max_pending_tasks: Option<usize>,
+    /// Máximo de tarefas em um lote ou workflow; lotes maiores são recusados
+    #[serde(default = "default_max_workflow_tasks")]
+    pub max_workflow_tasks: usize,
+    /// Tarefas registradas por vez em um lote, cedendo o runtime entre blocos
+    #[serde(default = "default_submit_chunk_size")]
+    pub submit_chunk_size: usize,
+    /// Limite de espaço do scratch de cada tarefa (`{data_dir}/scratch`; ex.: `"512MiB"`)
+    #[serde(default)]
+    pub scratch_limit_bytes: Option<units::ByteSize>,
+    /// Mantém o scratch de tarefas que falharam para depuração
+    #[serde(default)]
+    pub retain_scratch_on_failure: bool,
+    /// Políticas de SLA e monitor de prazos
+    #[serde(default)]
+    pub sla: sla::SlaConfig,
+    /// Watchdog de tarefas presas em execução
+    #[serde(default)]
+    pub stuck: watchdog::StuckConfig,
+    /// Janelas em que o despacho fica suspenso
+    #[serde(default)]
+    pub maintenance_windows: Vec<maintenance::MaintenanceWindow>,
+    /// Recusa submissões de tarefas cujo despacho está pausado
+    #[serde(default)]
+    pub reject_submissions_while_paused: bool,
+    /// Modo de operação (ver [`TaskMeshCore::promote`])
+    #[serde(default)]
+    pub mode: Mode,
+    /// Limites de tags e metadados aplicados na submissão
+    #[serde(default)]
+    pub metadata_limits: validation::MetadataLimits,
+    /// Cache das leituras de status (ver [`status_cache`])
+    #[serde(default)]
+    pub status_cache: status_cache::StatusCacheConfig,
+    /// Tags e metadados padrão das tarefas de cada namespace (ver [`defaults`])
+    #[serde(default)]
+    pub namespace_defaults: HashMap<String, defaults::TaskDefaults>,
+    /// Verificação de configuração e conectividade no `start()`
+    #[serde(default)]
+    pub preflight: preflight::PreflightConfig,
+    /// Retenção e arquivamento de tarefas terminais
+    #[serde(default)]
+    pub gc: gc::GcConfig,
+    /// Compactação periódica do armazenamento de estado
+    #[serde(default)]
+    pub storage_maintenance: compaction::StorageMaintenanceConfig,
+    /// Redução de workers, conexões e timers enquanto não há tarefas
+    #[serde(default)]
+    pub idle: idle::IdleConfig,
+    /// Runners Python pré-iniciados para `PythonScript` (`None` = um interpretador por tarefa)
+    #[serde(default)]
+    pub python_pool: Option<python_pool::PythonPoolConfig>,
+    /// Tarefas simultâneas por grupo de concorrência (grupos ausentes: 1)
+    #[serde(default)]
+    pub concurrency_group_limits: HashMap<String, usize>,
+    /// Hooks de todas as tarefas, antes dos hooks próprios de cada uma
+    #[serde(default)]
+    pub hooks: Vec<hooks::HookSpec>,
+    /// Aceita tarefas com resultado forçado (ver [`forced_outcome`]); só para testes
+    #[serde(default)]
+    pub allow_forced_outcomes: bool,
+    /// Tempo máximo de cada callback de ciclo de vida (ver [`lifecycle`])
+    #[serde(default = "lifecycle::default_callback_timeout")]
+    pub lifecycle_callback_timeout: units::DurationSpec,
+    /// Falhas injetadas para testes de resiliência (`TASKMESH_CHAOS_*` quando ausente)
+    #[cfg(feature = "chaos")]
+    #[serde(default)]
+    pub chaos: Option<chaos::ChaosConfig>,
+    /// cgroup pai dos limites rígidos por tarefa (ex.: `/sys/fs/cgroup/taskmesh.slice`)
+    #[cfg(feature = "cgroups")]
+    #[serde(default)]
+    pub cgroup_parent: Option<String>,
 }
 
 impl Default for TaskMeshConfig {
@@ -63,13 +286,55 @@ impl Default for TaskMeshConfig {
             database_url: "sqlite::memory:".to_string(),
             redis_url: None,
             max_workers: num_cpus::get(),
-            checkpoint_interval: 30,
+            checkpoint_interval: units::DurationSpec::from_secs(30),
+            checkpoint_strategy: None,
+            checkpoint_codec: codec::CodecConfig::default(),
+            encryption: None,
             retry_policy: RetryPolicy::default(),
+            retry_rules: Vec::new(),
             enable_metrics: false,
+            sqlite: SqliteConfig::default(),
+            strict_durability: false,
+            data_dir: None,
+            max_pending_tasks: None,
+            max_workflow_tasks: DEFAULT_MAX_WORKFLOW_TASKS,
+            submit_chunk_size: DEFAULT_SUBMIT_CHUNK_SIZE,
+            scratch_limit_bytes: None,
+            retain_scratch_on_failure: false,
+            sla: sla::SlaConfig::default(),
+            stuck: watchdog::StuckConfig::default(),
+            maintenance_windows: Vec::new(),
+            reject_submissions_while_paused: false,
+            mode: Mode::Active,
+            metadata_limits: validation::MetadataLimits::default(),
+            status_cache: status_cache::StatusCacheConfig::default(),
+            namespace_defaults: HashMap::new(),
+            preflight: preflight::PreflightConfig::default(),
+            gc: gc::GcConfig::default(),
+            storage_maintenance: compaction::StorageMaintenanceConfig::default(),
+            idle: idle::IdleConfig::default(),
+            python_pool: None,
+            concurrency_group_limits: HashMap::new(),
+            hooks: Vec::new(),
+            allow_forced_outcomes: false,
+            lifecycle_callback_timeout: lifecycle::default_callback_timeout(),
+            #[cfg(feature = "chaos")]
+            chaos: None,
+            #[cfg(feature = "cgroups")]
+            cgroup_parent: None,
         }
     }
 }
 
+impl TaskMeshConfig {
+    /// Estratégia de checkpoint efetiva
+    pub fn effective_checkpoint_strategy(&self) -> CheckpointStrategy {
+        self.checkpoint_strategy
+            .clone()
+            .unwrap_or_else(|| CheckpointStrategy::Interval(self.checkpoint_interval.into()))
+    }
+}
+
 /// Core principal do TaskMesh
 ///
 /// Integra todos os componentes em uma interface unificada
@@ -82,42 +347,168 @@ pub struct TaskMeshCore {
     pub executor: Arc<TaskExecutor>,
     /// Armazenamento de estado
     pub state_store: Arc<dyn StateStore>,
+    /// Barramento de eventos (grava no `state_store` e entrega aos assinantes)
+    pub event_bus: Arc<EventBus>,
     /// Engine de checkpoint
     pub checkpoint_engine: Arc<CheckpointEngine>,
     /// Handler de erros
     pub error_handler: Arc<ErrorHandler>,
+    /// Monitor de SLA
+    pub sla_monitor: Arc<sla::SlaMonitor>,
+    /// Watchdog de tarefas presas
+    pub stuck_watchdog: Arc<watchdog::StuckWatchdog>,
+    /// Compactação e estatísticas do armazenamento
+    pub storage_maintenance: Arc<compaction::StorageMaintenance>,
+    /// Redução de recursos enquanto ocioso
+    pub idle: Arc<idle::IdleManager>,
+    /// Atrasos entre a tarefa ficar pronta e começar a executar
+    pub dispatch_latency: Arc<dispatch_latency::DispatchLatency>,
+    /// Cache de leitura dos status
+    status_cache: Arc<status_cache::StatusCache>,
+    /// Callbacks de ciclo de vida da aplicação
+    lifecycle: lifecycle::LifecycleHooks,
     /// Configuração
     config: TaskMeshConfig,
+    /// Momento da criação (referência para a ausência de checkpoints)
+    started_at: std::time::SystemTime,
+    /// Loops de background de todos os componentes
+    background: BackgroundTasks,
+    /// Modo atual (muda de somente leitura para ativo em `promote`)
+    mode: std::sync::RwLock<Mode>,
+    /// `start` já foi chamado (loops pendentes em modo somente leitura)
+    started: std::sync::atomic::AtomicBool,
+    /// Serializa promoções concorrentes
+    promotion: tokio::sync::Mutex<()>,
+    /// Injetor de falhas, quando configurado
+    #[cfg(feature = "chaos")]
+    pub fault_injector: Option<Arc<chaos::FaultInjector>>,
 }
 
 impl TaskMeshCore {
     /// Cria uma nova instância do TaskMesh Core
     pub async fn new(config: TaskMeshConfig) -> Result<Self, TaskMeshError> {
         info!("Inicializando TaskMesh Core");
+        let violations = hooks::validate_specs(&config.hooks, "hooks");
+        if !violations.is_empty() {
+            return Err(validation::ValidationError { violations }.into());
+        }
 
         // Inicializar componentes
         let registry = Arc::new(RwLock::new(TaskRegistry::new()));
         let state_store = Self::create_state_store(&config).await?;
+        #[cfg(feature = "chaos")]
+        let (state_store, fault_injector) = Self::inject_faults(&config, state_store)?;
         let error_handler = Arc::new(ErrorHandler::new(config.retry_policy.clone()));
-        let checkpoint_engine = Arc::new(CheckpointEngine::new(
-            state_store.clone(),
-            config.checkpoint_interval,
-        ));
-        let scheduler = Arc::new(Scheduler::new(SchedulingHeuristic::default()));
-        let executor = Arc::new(TaskExecutor::new(
-            config.max_workers,
+        let retry_rules = RetryRules::new(config.retry_rules.clone(), config.retry_policy.clone())?;
+        let event_bus = Arc::new(EventBus::new(state_store.clone()));
+        let background = BackgroundTasks::new();
+        let checkpoint_strategy = config.effective_checkpoint_strategy();
+        checkpoint_strategy.validate()?;
+        let checkpoint_engine = Arc::new(
+            CheckpointEngine::with_config(state_store.clone(), checkpoint::CheckpointConfig::with_strategy(checkpoint_strategy))
+                .with_background(background.clone())
+                .with_event_bus(event_bus.clone()),
+        );
+        let scheduler_config = scheduler::SchedulerConfig {
+            max_queue_depth: config.max_pending_tasks,
+            ..scheduler::SchedulerConfig::default()
+        };
+        let concurrency_groups = Arc::new(concurrency_group::ConcurrencyGroups::new(config.concurrency_group_limits.clone()));
+        let dispatch_latency = Arc::new(dispatch_latency::DispatchLatency::new());
+        let status_cache = Arc::new(status_cache::StatusCache::new(config.status_cache.clone()));
+        let tuning = Self::load_tuning(state_store.as_ref()).await?;
+        if tuning.scheduling_heuristic.is_some() || tuning.max_concurrency.is_some() {
+            info!("Ajustes de execução restaurados: {:?}", tuning);
+        }
+        let scheduler = Arc::new(
+            Scheduler::with_config(tuning.scheduling_heuristic.unwrap_or_default(), scheduler_config)
+                .with_concurrency_groups(concurrency_groups.clone())
+                .with_dispatch_latency(dispatch_latency.clone()),
+        );
+        scheduler.dispatch_gate().set_windows(config.maintenance_windows.clone())?;
+        if let Some(pause) = state_store.get_setting(maintenance::DISPATCH_PAUSE_SETTING).await? {
+            let pause: maintenance::DispatchPause = serde_json::from_str(&pause)?;
+            warn!("Despacho pausado desde a execução anterior: {}", pause.reason);
+            scheduler.dispatch_gate().pause(pause);
+        }
+        if let Some(reservations) = state_store.get_setting(reservation::RESERVATIONS_SETTING).await? {
+            scheduler.reservations().restore(serde_json::from_str(&reservations)?);
+            let expired = scheduler.reservations().expire(std::time::SystemTime::now());
+            info!(
+                "{} reservas de capacidade restauradas ({} expiradas)",
+                scheduler.reservations().list().len(),
+                expired.len()
+            );
+        }
+        let executor_config = executor::ExecutorConfig {
+            max_workers: tuning.max_concurrency.unwrap_or(config.max_workers),
+            write_behind: !config.strict_durability,
+            python_pool: config.python_pool.clone(),
+            hooks: config.hooks.clone(),
+            allow_forced_outcomes: config.allow_forced_outcomes,
+            log_dir: config.data_dir.as_ref().map(|dir| std::path::Path::new(dir).join("logs")),
+            scratch: config.data_dir.as_ref().map(|dir| scratch::ScratchConfig {
+                limit_bytes: config.scratch_limit_bytes.map(u64::from),
+                retain_on_failure: config.retain_scratch_on_failure,
+                ..scratch::ScratchConfig::new(std::path::Path::new(dir).join("scratch"))
+            }),
+            #[cfg(feature = "cgroups")]
+            cgroups: config.cgroup_parent.as_ref().map(cgroups::CgroupConfig::new),
+            ..executor::ExecutorConfig::default()
+        };
+        let executor = TaskExecutor::with_config(
+            executor_config,
             state_store.clone(),
             error_handler.clone(),
-        ).await?);
+        ).await?
+            .with_background(background.clone())
+            .with_event_bus(event_bus.clone())
+            .with_retries(scheduler.clone(), retry_rules)
+            .with_concurrency_groups(concurrency_groups)
+            .with_dispatch_latency(dispatch_latency.clone())
+            .with_status_cache(status_cache.clone());
+        #[cfg(feature = "chaos")]
+        let executor = match &fault_injector {
+            Some(injector) => executor.with_fault_injector(injector.clone()),
+            None => executor,
+        };
+        let executor = Arc::new(executor);
+        let sla_monitor = Arc::new(sla::SlaMonitor::new(config.sla.policies.clone())?);
+        let stuck_watchdog = Arc::new(watchdog::StuckWatchdog::new(config.stuck.clone()));
+        let storage_maintenance = Arc::new(compaction::StorageMaintenance::new(state_store.clone()));
+        checkpoint_engine.set_load_signal(executor.clone());
+        let idle = Arc::new(idle::IdleManager::new(
+            config.idle.clone(),
+            executor.clone(),
+            scheduler.clone(),
+            state_store.clone(),
+            checkpoint_engine.clone(),
+            event_bus.clone(),
+        ));
 
         let core = Self {
             registry,
             scheduler,
             executor,
             state_store,
+            event_bus,
             checkpoint_engine,
             error_handler,
+            sla_monitor,
+            stuck_watchdog,
+            storage_maintenance,
+            idle,
+            dispatch_latency,
+            status_cache,
+            lifecycle: lifecycle::LifecycleHooks::new(config.lifecycle_callback_timeout.as_duration()),
+            mode: std::sync::RwLock::new(config.mode),
             config,
+            started_at: std::time::SystemTime::now(),
+            background,
+            started: std::sync::atomic::AtomicBool::new(false),
+            promotion: tokio::sync::Mutex::new(()),
+            #[cfg(feature = "chaos")]
+            fault_injector,
         };
 
         // Inicializar métricas se habilitado
@@ -137,8 +528,17 @@ impl TaskMeshCore {
         use state_store::*;
 
         if config.database_url.starts_with("sqlite") {
-            let store = SqliteStateStore::new(&config.database_url).await?;
+            let mut store = SqliteStateStore::with_config(&config.database_url, &config.sqlite)
+                .await?
+                .with_checkpoint_codec(config.checkpoint_codec);
+            if let Some(encryption) = &config.encryption {
+                store = store.with_payload_encryption(Arc::new(encryption::PayloadCipher::from_config(encryption)?));
+            }
             Ok(Arc::new(store))
+        } else if config.encryption.is_some() {
+            Err(TaskMeshError::Configuration(
+                "Cifragem de tarefas disponível apenas com SQLite".to_string(),
+            ))
         } else if config.database_url.starts_with("postgres") {
             let store = PostgresStateStore::new(&config.database_url).await?;
             Ok(Arc::new(store))
@@ -152,122 +552,2662 @@ impl TaskMeshCore {
         }
     }
 
+    /// Envolve o StateStore com o injetor de falhas configurado
+    #[cfg(feature = "chaos")]
+    #[allow(clippy::type_complexity)]
+    fn inject_faults(
+        config: &TaskMeshConfig,
+        state_store: Arc<dyn StateStore>,
+    ) -> Result<(Arc<dyn StateStore>, Option<Arc<chaos::FaultInjector>>), TaskMeshError> {
+        let chaos_config = match config.chaos.clone() {
+            Some(chaos_config) => chaos_config,
+            None => match chaos::ChaosConfig::from_env() {
+                Some(chaos_config) => chaos_config?,
+                None => return Ok((state_store, None)),
+            },
+        };
+        warn!("Injeção de falhas habilitada: {:?}", chaos_config);
+        let injector = Arc::new(chaos::FaultInjector::new(chaos_config)?);
+        let store: Arc<dyn StateStore> = Arc::new(chaos::ChaosStateStore::new(state_store, injector.clone()));
+        Ok((store, Some(injector)))
+    }
+
     /// Inicia o TaskMesh Core
+    ///
+    /// Em modo somente leitura os loops de despacho, checkpoint e SLA só
+    /// iniciam na promoção. Com `preflight.on_start`, falhas nas verificações
+    /// de `preflight.fail_fast_on` impedem o início. Os callbacks de
+    /// `Started` só rodam se o início for concluído.
     pub async fn start(&self) -> Result<(), TaskMeshError> {
         info!("Iniciando TaskMesh Core");
+        self.run_lifecycle(LifecyclePhase::Starting, "start").await;
+        match self.start_components().await {
+            Ok(reason) => {
+                self.run_lifecycle(LifecyclePhase::Started, reason).await;
+                Ok(())
+            }
+            Err(e) => {
+                // Quem se preparou em `Starting` é avisado de que o start falhou
+                self.started.store(false, std::sync::atomic::Ordering::SeqCst);
+                self.background.shutdown(BACKGROUND_SHUTDOWN_TIMEOUT).await;
+                error!("Falha ao iniciar o TaskMesh Core: {}", e);
+                self.run_lifecycle(LifecyclePhase::Stopped, &e.to_string()).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Verificação prévia e loops de `start`; retorna o motivo entregue a `Started`
+    async fn start_components(&self) -> Result<&'static str, TaskMeshError> {
+        if self.config.preflight.on_start {
+            let report = self.preflight().await;
+            let failures = report.failures(&self.config.preflight.fail_fast_on);
+            if !failures.is_empty() {
+                let failures: Vec<String> = failures
+                    .iter()
+                    .map(|check| format!("{}: {}", check.id.as_str(), check.detail))
+                    .collect();
+                return Err(TaskMeshError::Configuration(format!(
+                    "verificação prévia falhou ({})",
+                    failures.join("; ")
+                )));
+            }
+        }
+        self.background.reset();
+        self.started.store(true, std::sync::atomic::Ordering::SeqCst);
+        if self.mode() == Mode::ReadOnly {
+            info!("TaskMesh Core iniciado em modo somente leitura");
+            return Ok("start em modo somente leitura");
+        }
+        self.start_loops().await?;
+        info!("TaskMesh Core iniciado");
+        Ok("start")
+    }
+
+    /// Registra um callback para uma fase do ciclo de vida (ver [`lifecycle`])
+    ///
+    /// Os callbacks de cada fase rodam em sequência, na ordem de registro,
+    /// com até `lifecycle_callback_timeout` cada; falhas só são registradas
+    /// no log.
+    pub fn on_lifecycle<F, Fut>(&self, phase: LifecyclePhase, callback: F)
+    where
+        F: Fn(LifecycleContext) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = lifecycle::LifecycleResult> + Send + 'static,
+    {
+        self.lifecycle.register(phase, callback);
+    }
 
+    /// Estado atual entregue aos callbacks de ciclo de vida
+    pub async fn lifecycle_stats(&self) -> LifecycleStats {
+        LifecycleStats {
+            uptime: self.started_at.elapsed().unwrap_or_default(),
+            pending_tasks: self.scheduler.queue_depth(),
+            queued_tasks: self.executor.queued_count(),
+            running_tasks: self.executor.running_count().await,
+            background_loops: self.background_task_count(),
+        }
+    }
+
+    async fn run_lifecycle(&self, phase: LifecyclePhase, reason: &str) {
+        let ctx = LifecycleContext { phase, reason: reason.to_string(), stats: self.lifecycle_stats().await };
+        let failures = self.lifecycle.run(ctx).await;
+        if failures > 0 {
+            warn!("{} callbacks da fase {} falharam", failures, phase.as_str());
+        }
+    }
+
+    /// Inicia checkpoint engine, executor e monitor de SLA
+    ///
+    /// Tarefas que o `StateStore` ainda registra em execução são reanexadas
+    /// ao processo sobrevivente ou remediadas pelo watchdog.
+    async fn start_loops(&self) -> Result<(), TaskMeshError> {
         // Iniciar checkpoint engine
         self.checkpoint_engine.start().await?;
 
         // Iniciar executor
         self.executor.start().await?;
 
-        info!("TaskMesh Core iniciado");
+        // Reanexar ou remediar as tarefas em execução no processo anterior
+        self.stuck_watchdog
+            .reconcile(self.state_store.as_ref(), &self.status_cache, &self.executor, &self.scheduler, &self.registry, &self.event_bus)
+            .await?;
+
+        // Iniciar monitor de SLA
+        if self.config.sla.is_active() {
+            self.start_sla_monitor();
+        }
+
+        // Iniciar watchdog de tarefas presas
+        if self.config.stuck.enabled {
+            self.start_stuck_watchdog();
+        }
+
+        // Iniciar coleta periódica de tarefas terminais
+        if let Some(interval_ms) = self.config.gc.interval_ms.filter(|_| self.config.gc.is_active()) {
+            self.start_gc(interval_ms);
+        }
+
+        // Iniciar compactação periódica do armazenamento
+        if let Some(interval_ms) = self.config.storage_maintenance.interval_ms {
+            self.start_storage_maintenance(interval_ms);
+        }
+
+        // Iniciar verificação de ociosidade
+        if self.config.idle.idle_after.is_some() {
+            self.start_idle_monitor();
+        }
+
+        // Persistir reservas consumidas no despacho
+        self.start_reservation_sync();
+        Ok(())
+    }
+
+    /// Modo de operação atual
+    pub fn mode(&self) -> Mode {
+        *self.mode.read().unwrap()
+    }
+
+    /// Promove um core somente leitura a ativo
+    ///
+    /// Recupera do `StateStore` as tarefas ainda não despachadas e, se o core
+    /// já foi iniciado, inicia os loops. Retorna o número de tarefas
+    /// reagendadas; em um core ativo não faz nada.
+    pub async fn promote(&self) -> Result<usize, TaskMeshError> {
+        let _promotion = self.promotion.lock().await;
+        if self.mode() == Mode::Active {
+            return Ok(0);
+        }
+        let recovered = self.recover_tasks().await?;
+        *self.mode.write().unwrap() = Mode::Active;
+        if self.started.load(std::sync::atomic::Ordering::SeqCst) {
+            self.start_loops().await?;
+        }
+        warn!("TaskMesh Core promovido a ativo ({} tarefas reagendadas)", recovered);
+        self.event_bus.publish(SystemEvent::new(
+            EventType::ConfigurationChanged,
+            None,
+            serde_json::json!({ "mode": Mode::Active, "recovered_tasks": recovered }),
+        )).await?;
+        Ok(recovered)
+    }
+
+    /// Reconstrói a fila em memória a partir do `StateStore`
+    ///
+    /// Para inspecionar a fila de outro processo (ex.: `taskmesh queue`) sem
+    /// promover o core; nada é despachado. Retorna o número de tarefas
+    /// enfileiradas.
+    pub async fn load_queue(&self) -> Result<usize, TaskMeshError> {
+        self.apply_tuning().await?;
+        self.recover_tasks().await
+    }
+
+    /// Ajustes de execução persistidos (ver [`RuntimeTuning`])
+    async fn load_tuning(state_store: &dyn StateStore) -> Result<RuntimeTuning, TaskMeshError> {
+        match state_store.get_setting(RUNTIME_TUNING_SETTING).await? {
+            Some(tuning) => Ok(serde_json::from_str(&tuning)?),
+            None => Ok(RuntimeTuning::default()),
+        }
+    }
+
+    /// Aplica os ajustes gravados por outro processo (ex.: `taskmesh tune`)
+    async fn apply_tuning(&self) -> Result<(), TaskMeshError> {
+        if self.mode() != Mode::Active {
+            return Ok(());
+        }
+        let tuning = Self::load_tuning(self.state_store.as_ref()).await?;
+        if let Some(heuristic) = tuning.scheduling_heuristic {
+            let current = serde_json::to_value(self.scheduler.heuristic().await)?;
+            if serde_json::to_value(&heuristic)? != current {
+                self.scheduler.update_heuristic(heuristic).await;
+            }
+        }
+        match tuning.max_concurrency {
+            Some(limit) if limit != self.executor.max_concurrency() => {
+                self.executor.set_max_concurrency(limit).await?;
+            }
+            _ => {}
+        }
         Ok(())
     }
 
+    /// Grava um ajuste de execução, preservando os demais
+    async fn save_tuning(&self, update: impl FnOnce(&mut RuntimeTuning)) -> Result<(), TaskMeshError> {
+        let mut tuning = Self::load_tuning(self.state_store.as_ref()).await?;
+        update(&mut tuning);
+        self.state_store
+            .put_setting(RUNTIME_TUNING_SETTING, Some(&serde_json::to_string(&tuning)?))
+            .await
+    }
+
+    /// Carrega no registro as tarefas do `StateStore` e agenda as pendentes
+    ///
+    /// Tarefas concluídas liberam seus dependentes; as aguardando retry
+    /// voltam à fila com o horário persistido; as em execução ou terminadas
+    /// não são reagendadas.
+    async fn recover_tasks(&self) -> Result<usize, TaskMeshError> {
+        let mut recovered = Vec::new();
+        for task in self.state_store.list_tasks().await? {
+            if self.registry.read().await.get_task(&task.id).is_some() {
+                continue;
+            }
+            let status = self.state_store.get_task_status(&task.id).await?;
+            recovered.push((Arc::new(task), status));
+        }
+        recovered.sort_by_key(|(task, _)| task.created_at);
+
+        {
+            let mut registry = self.registry.write().await;
+            for (task, _) in &recovered {
+                registry.adopt_task(task.clone());
+            }
+        }
+        let completed: Vec<TaskId> = recovered
+            .iter()
+            .filter(|(_, status)| matches!(status, TaskStatus::Completed { .. }))
+            .map(|(task, _)| task.id)
+            .collect();
+        self.scheduler.mark_completed(&completed).await;
+
+        let mut scheduled = 0;
+        for (task, status) in recovered {
+            match status {
+                TaskStatus::Pending | TaskStatus::Scheduled => self.scheduler.schedule_task(task).await?,
+                TaskStatus::AwaitingRetry { next_attempt_at, .. } => {
+                    self.scheduler.schedule_retry(task, next_attempt_at).await?
+                }
+                _ => continue,
+            }
+            scheduled += 1;
+        }
+        Ok(scheduled)
+    }
+
+    /// Recusa operações que despacham ou gravam em modo somente leitura
+    fn ensure_active(&self, operation: &str) -> Result<(), TaskMeshError> {
+        match self.mode() {
+            Mode::Active => Ok(()),
+            Mode::ReadOnly => Err(TaskMeshError::UnsupportedOperation(format!(
+                "{} indisponível em modo somente leitura",
+                operation
+            ))),
+        }
+    }
+
+    /// Avalia periodicamente os SLAs das tarefas registradas
+    fn start_sla_monitor(&self) {
+        let monitor = self.sla_monitor.clone();
+        let registry = self.registry.clone();
+        let state_store = self.state_store.clone();
+        let token = self.background.token();
+        let mut ticker = tokio::time::interval(Duration::from_millis(self.config.sla.check_interval_ms.max(1)));
+        self.background.spawn("sla.monitor", async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if let Err(e) = monitor.evaluate(&registry, state_store.as_ref()).await {
+                    error!("Erro na avaliação de SLAs: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Procura periodicamente tarefas presas em execução
+    fn start_stuck_watchdog(&self) {
+        let watchdog = self.stuck_watchdog.clone();
+        let state_store = self.state_store.clone();
+        let status_cache = self.status_cache.clone();
+        let executor = self.executor.clone();
+        let scheduler = self.scheduler.clone();
+        let registry = self.registry.clone();
+        let event_bus = self.event_bus.clone();
+        let token = self.background.token();
+        let mut ticker = tokio::time::interval(Duration::from_millis(self.config.stuck.scan_interval_ms.max(1)));
+        self.background.spawn("stuck.watchdog", async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if let Err(e) = watchdog.scan(state_store.as_ref(), &status_cache, &executor, &scheduler, &registry, &event_bus).await {
+                    error!("Erro na varredura de tarefas presas: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Coleta periodicamente as tarefas terminais expiradas
+    fn start_gc(&self, interval_ms: u64) {
+        let config = self.gc_config();
+        let state_store = self.state_store.clone();
+        let executor = self.executor.clone();
+        let registry = self.registry.clone();
+        let event_bus = self.event_bus.clone();
+        let token = self.background.token();
+        let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms.max(1)));
+        self.background.spawn("gc", async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                let collected = gc::collect(
+                    &config,
+                    state_store.as_ref(),
+                    executor.log_store().map(Arc::as_ref),
+                    &event_bus,
+                    std::time::SystemTime::now(),
+                    false,
+                ).await;
+                match collected {
+                    Ok(report) => Self::forget_collected(&registry, &report.collected).await,
+                    Err(e) => error!("Erro na coleta de tarefas terminais: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Compacta periodicamente o armazenamento (modo incremental)
+    fn start_storage_maintenance(&self, interval_ms: u64) {
+        let maintenance = self.storage_maintenance.clone();
+        let token = self.background.token();
+        let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms.max(1)));
+        self.background.spawn("storage.maintenance", async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if let Err(e) = maintenance.compact(state_store::CompactionMode::Incremental).await {
+                    error!("Erro na compactação do armazenamento: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Reduz ou restaura os recursos conforme a fila (ver [`idle`])
+    fn start_idle_monitor(&self) {
+        let idle = self.idle.clone();
+        let token = self.background.token();
+        let mut ticker = tokio::time::interval(self.config.idle.check_interval.as_duration().max(Duration::from_millis(1)));
+        self.background.spawn("idle.monitor", async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if let Err(e) = idle.check().await {
+                    error!("Erro na verificação de ociosidade: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Regrava as reservas sempre que o despacho consome alguma
+    fn start_reservation_sync(&self) {
+        let scheduler = self.scheduler.clone();
+        let state_store = self.state_store.clone();
+        let token = self.background.token();
+        self.background.spawn("reservation.sync", async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = scheduler.reservations().consumed() => {}
+                }
+                if let Err(e) = Self::save_reservations(&scheduler, state_store.as_ref()).await {
+                    error!("Erro ao persistir reservas consumidas: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Configuração da coleta com o diretório de arquivo padrão resolvido
+    fn gc_config(&self) -> gc::GcConfig {
+        let mut config = self.config.gc.clone();
+        if config.archive_dir.is_none() {
+            config.archive_dir = self.config.data_dir.as_ref()
+                .map(|dir| std::path::Path::new(dir).join("archive").display().to_string());
+        }
+        config
+    }
+
+    /// Remove do registro em memória as tarefas coletadas
+    async fn forget_collected(registry: &RwLock<TaskRegistry>, collected: &[TaskId]) {
+        let mut registry = registry.write().await;
+        for task_id in collected {
+            // Tarefas recuperadas de outro processo podem não estar no registro
+            let _ = registry.unregister_task(task_id);
+        }
+    }
+
+    /// Coleta agora as tarefas terminais expiradas (ver [`gc`])
+    ///
+    /// Com `dry_run` nada é removido e o relatório lista o que seria.
+    pub async fn run_gc(&self, dry_run: bool) -> Result<gc::GcReport, TaskMeshError> {
+        if !dry_run {
+            self.ensure_active("coleta de tarefas")?;
+        }
+        let report = gc::collect(
+            &self.gc_config(),
+            self.state_store.as_ref(),
+            self.executor.log_store().map(Arc::as_ref),
+            &self.event_bus,
+            std::time::SystemTime::now(),
+            dry_run,
+        ).await?;
+        if !dry_run {
+            Self::forget_collected(&self.registry, &report.collected).await;
+        }
+        Ok(report)
+    }
+
+    /// Compacta agora o armazenamento com `VACUUM` (ver [`compaction`])
+    ///
+    /// A reescrita segura a trava de escrita do banco até terminar; por isso
+    /// é recusada enquanto o despacho estiver ativo (ver
+    /// [`Self::pause_dispatch`]), salvo com `force`.
+    pub async fn compact_storage(&self, force: bool) -> Result<compaction::CompactionReport, TaskMeshError> {
+        self.ensure_active("compactação do armazenamento")?;
+        let paused = self.scheduler.dispatch_gate().held(&[], std::time::SystemTime::now()).is_some();
+        if !paused && !force {
+            return Err(TaskMeshError::UnsupportedOperation(
+                "compactação completa com o despacho ativo: pause o despacho ou use force".to_string(),
+            ));
+        }
+        self.storage_maintenance.compact(state_store::CompactionMode::Full).await
+    }
+
+    /// Recifra as tarefas de um namespace com outra chave (ver [`encryption`])
+    ///
+    /// Retorna o número de tarefas regravadas.
+    pub async fn rewrap_namespace(&self, namespace: &str, key_id: &str) -> Result<u64, TaskMeshError> {
+        self.ensure_active("recifragem de tarefas")?;
+        self.state_store.rewrap_namespace(namespace, key_id).await
+    }
+
     /// Para o TaskMesh Core graciosamente
     pub async fn shutdown(&self) -> Result<(), TaskMeshError> {
-        info!("Parando TaskMesh Core");
+        self.shutdown_with_reason("shutdown").await
+    }
+
+    /// Para o TaskMesh Core informando o motivo aos callbacks de ciclo de vida
+    ///
+    /// Os callbacks de `Draining` rodam antes de parar o executor; os de
+    /// `Stopped`, depois que os loops de background terminam. Só rodam se o
+    /// core foi iniciado, e uma vez por `start`.
+    pub async fn shutdown_with_reason(&self, reason: &str) -> Result<(), TaskMeshError> {
+        info!("Parando TaskMesh Core ({})", reason);
+        let was_started = self.started.swap(false, std::sync::atomic::Ordering::SeqCst);
+        if was_started {
+            self.run_lifecycle(LifecyclePhase::Draining, reason).await;
+        }
+
+        if self.mode() == Mode::Active {
+            // Parar executor
+            self.executor.shutdown().await?;
 
-        // Parar executor
-        self.executor.shutdown().await?;
+            // Parar checkpoint engine
+            self.checkpoint_engine.stop().await?;
 
-        // Parar checkpoint engine
-        self.checkpoint_engine.stop().await?;
+            // Criar checkpoint final
+            self.checkpoint_engine.checkpoint_on_shutdown().await?;
+        }
 
-        // Criar checkpoint final
-        self.checkpoint_engine.create_checkpoint().await?;
+        // Aguardar o término de todos os loops de background
+        let lingering = self.background.shutdown(BACKGROUND_SHUTDOWN_TIMEOUT).await;
+        if !lingering.is_empty() {
+            warn!("{} loops de background não pararam: {:?}", lingering.len(), lingering);
+        }
 
         info!("TaskMesh Core parado");
+        if was_started {
+            self.run_lifecycle(LifecyclePhase::Stopped, reason).await;
+        }
         Ok(())
     }
 
+    /// Número de loops de background em execução
+    pub fn background_task_count(&self) -> usize {
+        self.background.count()
+    }
+
     /// Submete uma nova tarefa
-    pub async fn submit_task(&self, task: Task) -> Result<TaskId, TaskMeshError> {
+    ///
+    /// Retorna `QueueFull` se `max_pending_tasks` tarefas já aguardam despacho
+    /// e `Validation` se tags ou metadados excedem `metadata_limits`.
+    pub async fn submit_task(&self, mut task: Task) -> Result<TaskId, TaskMeshError> {
+        self.ensure_active("submissão de tarefas")?;
+        self.validate_tasks(std::slice::from_mut(&mut task), false)?;
+        let reservation = self.scheduler.try_reserve()?;
+        self.submit_reserved(task, reservation).await
+    }
+
+    /// Submete uma tarefa aguardando até `timeout` por espaço na fila
+    pub async fn submit_task_blocking(
+        &self,
+        mut task: Task,
+        timeout: std::time::Duration,
+    ) -> Result<TaskId, TaskMeshError> {
+        self.ensure_active("submissão de tarefas")?;
+        self.validate_tasks(std::slice::from_mut(&mut task), false)?;
+        let reservation = self.scheduler.reserve(timeout).await?;
+        self.submit_reserved(task, reservation).await
+    }
+
+    /// Submete um lote de tarefas (todas ou nenhuma, quanto à capacidade da fila)
+    ///
+    /// Tarefas do lote podem depender de tarefas anteriores do mesmo lote.
+    /// Tarefas sem grupo formam um novo grupo (ver [`Self::group_status`]).
+    /// Lotes com mais de `max_workflow_tasks` tarefas são recusados com
+    /// `WorkflowTooLarge`; os demais são registrados em blocos de
+    /// `submit_chunk_size`, cedendo o runtime entre eles para que consultas
+    /// concorrentes não esperem o lote inteiro.
+    pub async fn submit_batch(&self, mut tasks: Vec<Task>) -> Result<Vec<TaskId>, TaskMeshError> {
+        self.ensure_active("submissão de tarefas")?;
+        if tasks.len() > self.config.max_workflow_tasks {
+            return Err(TaskMeshError::WorkflowTooLarge { tasks: tasks.len(), limit: self.config.max_workflow_tasks });
+        }
+        self.validate_tasks(&mut tasks, true)?;
+        self.check_aliases(&tasks).await?;
+        let group_id = uuid::Uuid::new_v4();
+        for task in tasks.iter_mut().filter(|task| task.group_id.is_none()) {
+            task.group_id = Some(group_id);
+        }
+        let reservations = tasks
+            .iter()
+            .map(|_| self.scheduler.try_reserve())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let chunk_size = self.config.submit_chunk_size.max(1);
+        let mut task_ids = Vec::with_capacity(tasks.len());
+        let mut tasks = tasks.into_iter().peekable();
+        let mut reservations = reservations.into_iter();
+        while tasks.peek().is_some() {
+            let chunk: Vec<Task> = tasks.by_ref().take(chunk_size).collect();
+            let chunk_reservations = reservations.by_ref().take(chunk.len()).collect();
+            task_ids.extend(self.register_chunk(chunk, chunk_reservations).await?);
+            tokio::task::yield_now().await;
+        }
+        Ok(task_ids)
+    }
+
+    /// Submete as tarefas de um arquivo de workflow
+    ///
+    /// Dependências por alias são resolvidas primeiro no próprio arquivo e
+    /// depois entre as tarefas já submetidas. As tarefas formam um grupo com
+    /// o nome do workflow.
+    pub async fn submit_workflow_file(&self, workflow: WorkflowFile) -> Result<Vec<TaskId>, TaskMeshError> {
+        let tasks = workflow.into_tasks(self.state_store.as_ref()).await?;
+        self.submit_batch(tasks).await
+    }
+
+    /// Importa tarefas históricas sem executá-las
+    ///
+    /// Cada lote é gravado em uma transação e então adotado pelo registro;
+    /// um erro interrompe a importação mantendo os lotes já gravados.
+    pub async fn import_tasks(
+        &self,
+        mut source: ImportSource,
+        options: ImportOptions,
+    ) -> Result<ImportSummary, TaskMeshError> {
+        self.ensure_active("importação de tarefas")?;
+        let mut plan = import::ImportPlan::new(options);
+        loop {
+            let batch = plan.next_batch(self.state_store.as_ref(), &mut source).await?;
+            if batch.is_empty() {
+                break;
+            }
+            self.state_store.import_tasks(&batch).await?;
+
+            for (task, _) in &batch {
+                if let Some(name) = &task.alias {
+                    self.state_store.register_alias(alias::namespace_of(task), name, &task.id).await?;
+                }
+            }
+            {
+                let mut registry = self.registry.write().await;
+                for (task, _) in &batch {
+                    registry.adopt_task(task.clone());
+                }
+            }
+            if plan.satisfies_dependencies() {
+                let completed: Vec<TaskId> = batch
+                    .iter()
+                    .filter(|(_, status)| matches!(status, TaskStatus::Completed { .. }))
+                    .map(|(task, _)| task.id)
+                    .collect();
+                self.scheduler.mark_completed(&completed).await;
+            }
+            plan.record_batch(&batch);
+        }
+
+        let summary = plan.finish();
+        info!(
+            "Importação concluída: {} importadas, {} ignoradas, {} conflitos",
+            summary.imported, summary.skipped, summary.conflicts
+        );
+        Ok(summary)
+    }
+
+    /// Valida e normaliza as tarefas de uma submissão
+    ///
+    /// Mescla os padrões do namespace ([`defaults`]), normaliza as tags,
+    /// aplica `metadata_limits` ao resultado e verifica os controles de
+    /// processo ([`process_controls`]), reunindo as violações de todas as
+    /// tarefas (em lotes, prefixadas por `tasks[i].`)
+    fn validate_tasks(&self, tasks: &mut [Task], batch: bool) -> Result<(), TaskMeshError> {
+        let violations: Vec<validation::Violation> = tasks
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(index, task)| {
+                let prefix = if batch { format!("tasks[{}].", index) } else { String::new() };
+                if let Some(defaults) = self.config.namespace_defaults.get(alias::namespace_of(task)) {
+                    defaults.apply(task, defaults::DefaultSource::Namespace);
+                }
+                let mut violations = self.config.metadata_limits.validate(task, &prefix);
+                violations.extend(process_controls::validate(task, &prefix));
+                violations.extend(scheduler::validate_requested_resources(task, &prefix));
+                violations.extend(sidecar::validate(task, &prefix));
+                violations.extend(hooks::validate(task, &prefix));
+                violations.extend(forced_outcome::validate(task, &prefix, self.config.allow_forced_outcomes));
+                violations.extend(execution_hint::validate(task, &prefix));
+                violations
+            })
+            .collect();
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(validation::ValidationError { violations }.into())
+    }
+
+    /// Recusa um lote com aliases inválidos, repetidos ou já em uso
+    ///
+    /// Evita que um conflito no meio do lote deixe parte dele submetida.
+    async fn check_aliases(&self, tasks: &[Task]) -> Result<(), TaskMeshError> {
+        let mut seen = std::collections::HashSet::new();
+        for task in tasks {
+            let Some(name) = &task.alias else { continue };
+            alias::validate_alias(name)?;
+            let namespace = alias::namespace_of(task);
+            if !seen.insert((namespace, name.as_str())) {
+                return Err(TaskMeshError::Configuration(format!("Alias '{}' repetido no lote", name)));
+            }
+            match self.state_store.resolve_alias(namespace, name).await? {
+                Some(existing) if existing != task.id => {
+                    return Err(TaskMeshError::AliasConflict {
+                        namespace: namespace.to_string(),
+                        alias: name.clone(),
+                        existing,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Registra e agenda uma tarefa em uma vaga já reservada
+    async fn submit_reserved(
+        &self,
+        task: Task,
+        reservation: scheduler::QueueReservation,
+    ) -> Result<TaskId, TaskMeshError> {
+        let span = logging::task_span(&task);
+        self.register_and_schedule(task, reservation).instrument(span).await
+    }
+
+    async fn register_and_schedule(
+        &self,
+        task: Task,
+        reservation: scheduler::QueueReservation,
+    ) -> Result<TaskId, TaskMeshError> {
         let task_id = task.id;
+        if self.config.reject_submissions_while_paused {
+            if let Some(reason) = self.scheduler.dispatch_gate().held(&task.tags, std::time::SystemTime::now()) {
+                return Err(TaskMeshError::ResourceUnavailable(format!("Despacho pausado: {}", reason)));
+            }
+        }
+        // Restaura workers, conexões e timers reduzidos por ociosidade
+        self.idle.warm_up().await?;
+        if let Some(name) = &task.alias {
+            alias::validate_alias(name)?;
+        }
+        // Envolvida uma única vez; registro e scheduler compartilham a tarefa
+        let task: SharedTask = Arc::new(task);
 
-        // Registrar tarefa
+        // Registrar tarefa; uma submissão recusada não chega ao StateStore
         self.registry.write().await.register_task(task.clone())?;
 
+        // Persistida para réplicas somente leitura e recuperação na promoção;
+        // o índice do StateStore garante a unicidade do alias
+        if let Err(e) = self.persist_chunk(std::slice::from_ref(&*task)).await {
+            let _ = self.registry.write().await.unregister_task(&task_id);
+            self.discard_chunk(std::iter::once(&task_id)).await;
+            return Err(e);
+        }
+
         // Agendar execução
-        self.scheduler.schedule_task(task).await?;
+        self.scheduler.schedule_reserved(task, reservation).await?;
 
         info!("Tarefa {} submetida", task_id);
         Ok(task_id)
     }
 
-    /// Obtém o status de uma tarefa
-    pub async fn get_task_status(&self, task_id: &TaskId) -> Result<TaskStatus, TaskMeshError> {
-        self.state_store.get_task_status(task_id).await
+    /// Registra e agenda um bloco de um lote, travando registro e grafo uma vez
+    async fn register_chunk(
+        &self,
+        tasks: Vec<Task>,
+        reservations: Vec<scheduler::QueueReservation>,
+    ) -> Result<Vec<TaskId>, TaskMeshError> {
+        if self.config.reject_submissions_while_paused {
+            let now = std::time::SystemTime::now();
+            if let Some(reason) = tasks.iter().find_map(|task| self.scheduler.dispatch_gate().held(&task.tags, now)) {
+                return Err(TaskMeshError::ResourceUnavailable(format!("Despacho pausado: {}", reason)));
+            }
+        }
+        // O bloco inteiro é validado antes de qualquer escrita
+        self.registry.read().await.validate_batch(&tasks)?;
+        self.idle.warm_up().await?;
+        if let Err(e) = self.persist_chunk(&tasks).await {
+            self.discard_chunk(tasks.iter().map(|task| &task.id)).await;
+            return Err(e);
+        }
+        let tasks: Vec<SharedTask> = tasks.into_iter().map(Arc::new).collect();
+        // O registro pode ter mudado desde a validação; desfaz a persistência
+        if let Err(e) = self.registry.write().await.register_tasks(&tasks) {
+            self.discard_chunk(tasks.iter().map(|task| &task.id)).await;
+            return Err(e);
+        }
+        self.scheduler.schedule_reserved_batch(&tasks, reservations).await?;
+
+        info!("{} tarefas submetidas", tasks.len());
+        Ok(tasks.iter().map(|task| task.id).collect())
     }
 
-    /// Lista todas as tarefas
-    pub async fn list_tasks(&self) -> Result<Vec<Task>, TaskMeshError> {
-        self.registry.read().await.list_tasks()
+    /// Persiste aliases e tarefas de um bloco já validado
+    async fn persist_chunk(&self, tasks: &[Task]) -> Result<(), TaskMeshError> {
+        for task in tasks {
+            if let Some(name) = &task.alias {
+                self.state_store.register_alias(alias::namespace_of(task), name, &task.id).await?;
+            }
+        }
+        self.state_store.store_tasks(tasks).await
     }
 
-    /// Cancela uma tarefa
-    pub async fn cancel_task(&self, task_id: &TaskId) -> Result<(), TaskMeshError> {
-        self.executor.cancel_task(task_id).await
+    /// Remove do StateStore as tarefas (e aliases) de um bloco recusado
+    async fn discard_chunk(&self, task_ids: impl Iterator<Item = &TaskId>) {
+        for task_id in task_ids {
+            if let Err(e) = self.state_store.remove_task(task_id).await {
+                warn!("Falha ao desfazer a persistência da tarefa {}: {}", task_id, e);
+            }
+        }
     }
 
-    /// Obtém métricas do sistema
-    #[cfg(feature = "metrics")]
-    pub async fn get_metrics(&self) -> Result<metrics::SystemMetrics, TaskMeshError> {
-        metrics::collect_metrics().await
+    /// Insere no grafo as tarefas emitidas por um gerador
+    ///
+    /// As tarefas geradas dependem do gerador, e quem dependia do gerador
+    /// passa a depender também de todas elas. Os dependentes são religados
+    /// antes da submissão das geradas; chame antes de reportar a conclusão
+    /// do gerador para que nenhum dependente seja despachado no intervalo.
+    pub async fn expand_generator(
+        &self,
+        generator_id: &TaskId,
+        result: &TaskResult,
+    ) -> Result<Vec<TaskId>, TaskMeshError> {
+        self.ensure_active("expansão de gerador")?;
+        let (generator, dependents) = {
+            let registry = self.registry.read().await;
+            let generator = registry.get_task(generator_id)
+                .cloned()
+                .ok_or(TaskMeshError::TaskNotFound(*generator_id))?;
+            let dependents: Vec<TaskId> = registry.get_dependents(generator_id)
+                .map(|ids| ids.iter().copied().collect())
+                .unwrap_or_default();
+            (generator, dependents)
+        };
+
+        let children = generator::expand(&generator, generator::parse_specs(result)?)?;
+        let child_ids: Vec<TaskId> = children.iter().map(|child| child.id).collect();
+
+        // Religar dependentes às geradas antes de submetê-las: até lá os nós
+        // ainda não concluídos já seguram os dependentes no scheduler
+        for dependent in &dependents {
+            self.scheduler.add_dependencies(dependent, &child_ids).await;
+        }
+        if let Err(e) = self.submit_batch(children).await {
+            for dependent in &dependents {
+                self.scheduler.remove_dependencies(dependent, &child_ids).await;
+            }
+            return Err(e);
+        }
+        // No registro, as arestas só podem apontar para tarefas já registradas;
+        // se o religamento falhar, as geradas são retiradas para não ficarem órfãs
+        let rewired = self.registry.write().await.add_dependencies(&dependents, &child_ids);
+        if let Err(e) = rewired {
+            self.discard_expansion(&dependents, &child_ids).await;
+            return Err(e);
+        }
+
+        info!("Gerador {} expandido em {} tarefas", generator_id, child_ids.len());
+        Ok(child_ids)
     }
 
-    /// Força criação de checkpoint
-    pub async fn create_checkpoint(&self) -> Result<(), TaskMeshError> {
-        self.checkpoint_engine.create_checkpoint().await
+    /// Desfaz a submissão das tarefas geradas por uma expansão que falhou
+    async fn discard_expansion(&self, dependents: &[TaskId], child_ids: &[TaskId]) {
+        for dependent in dependents {
+            self.scheduler.remove_dependencies(dependent, child_ids).await;
+        }
+        {
+            let mut registry = self.registry.write().await;
+            for child_id in child_ids {
+                let _ = registry.unregister_task(child_id);
+            }
+        }
+        for child_id in child_ids {
+            self.scheduler.remove_task(child_id).await;
+        }
+        self.discard_chunk(child_ids.iter()).await;
     }
 
-    /// Restaura estado a partir de checkpoint
-    pub async fn restore_from_checkpoint(
+    /// Suspende o despacho de novas tarefas
+    ///
+    /// Submissões continuam aceitas (salvo `reject_submissions_while_paused`)
+    /// e tarefas em execução terminam. A pausa é persistida e sobrevive a
+    /// reinícios até [`Self::resume_dispatch`].
+    pub async fn pause_dispatch(&self, reason: impl Into<String>) -> Result<(), TaskMeshError> {
+        self.ensure_active("pausa de despacho")?;
+        let pause = maintenance::DispatchPause {
+            reason: reason.into(),
+            paused_at: std::time::SystemTime::now(),
+            scope: maintenance::DispatchScope::All,
+        };
+        self.state_store
+            .put_setting(maintenance::DISPATCH_PAUSE_SETTING, Some(&serde_json::to_string(&pause)?))
+            .await?;
+        warn!("Despacho pausado: {}", pause.reason);
+        self.scheduler.dispatch_gate().pause(pause.clone());
+        self.event_bus.publish(SystemEvent::new(
+            EventType::ConfigurationChanged,
+            None,
+            serde_json::json!({ "dispatch_paused": pause.reason }),
+        ).at(pause.paused_at)).await.map(|_| ())
+    }
+
+    /// Retoma o despacho suspenso por [`Self::pause_dispatch`]
+    ///
+    /// Janelas de manutenção ativas continuam valendo.
+    pub async fn resume_dispatch(&self) -> Result<(), TaskMeshError> {
+        self.ensure_active("retomada de despacho")?;
+        self.state_store.put_setting(maintenance::DISPATCH_PAUSE_SETTING, None).await?;
+        if self.scheduler.dispatch_gate().resume().is_some() {
+            info!("Despacho retomado");
+            self.event_bus.publish(SystemEvent::new(
+                EventType::ConfigurationChanged,
+                None,
+                serde_json::json!({ "dispatch_paused": null }),
+            )).await?;
+        }
+        Ok(())
+    }
+
+    /// Reserva capacidade para tarefas futuras (ver [`reservation`])
+    ///
+    /// A reserva é persistida e sobrevive a reinícios até expirar ou ser
+    /// cancelada com [`Self::cancel_reservation`].
+    pub async fn reserve_resources(
         &self,
-        checkpoint_id: &str,
-    ) -> Result<(), TaskMeshError> {
-        self.checkpoint_engine.restore_checkpoint(checkpoint_id).await
+        request: reservation::ReservationRequest,
+    ) -> Result<reservation::Reservation, TaskMeshError> {
+        self.ensure_active("reserva de capacidade")?;
+        let reservation = self.scheduler.reserve_resources(request)?;
+        if let Err(e) = self.persist_reservations().await {
+            self.scheduler.cancel_reservation(&reservation.id);
+            return Err(e);
+        }
+        Ok(reservation)
     }
-}
 
-// Helper para inicializar logging
-pub fn init_logging() {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-}
+    /// Cancela uma reserva; `false` se ela não existe (ou já expirou)
+    pub async fn cancel_reservation(&self, id: &uuid::Uuid) -> Result<bool, TaskMeshError> {
+        self.ensure_active("cancelamento de reserva")?;
+        let cancelled = self.scheduler.cancel_reservation(id).is_some();
+        if cancelled {
+            info!("Reserva {} cancelada", id);
+            self.persist_reservations().await?;
+        }
+        Ok(cancelled)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tokio_test;
+    /// Reservas ativas ou futuras
+    pub fn list_reservations(&self) -> Vec<reservation::Reservation> {
+        self.scheduler.reservations().list()
+    }
 
-    #[tokio::test]
-    async fn test_task_mesh_core_creation() {
-        let config = TaskMeshConfig::default();
-        let core = TaskMeshCore::new(config).await;
-        assert!(core.is_ok());
+    async fn persist_reservations(&self) -> Result<(), TaskMeshError> {
+        Self::save_reservations(&self.scheduler, self.state_store.as_ref()).await
     }
 
-    #[tokio::test]
-    async fn test_submit_and_get_task() {
-        let config = TaskMeshConfig::default();
-        let core = TaskMeshCore::new(config).await.unwrap();
-        
-        let task = Task::new(
-            "test_task".to_string(),
-            TaskDefinition::Command("echo hello".to_string()),
-            vec![],
+    async fn save_reservations(scheduler: &Scheduler, state_store: &dyn StateStore) -> Result<(), TaskMeshError> {
+        scheduler.reservations().expire(std::time::SystemTime::now());
+        let reservations = scheduler.reservations().list();
+        let value = if reservations.is_empty() { None } else { Some(serde_json::to_string(&reservations)?) };
+        state_store.put_setting(reservation::RESERVATIONS_SETTING, value.as_deref()).await
+    }
+
+    /// Verifica a saúde de todos os componentes
+    pub async fn health(&self) -> HealthReport {
+        let checkpoint_interval = self.config.effective_checkpoint_strategy().interval();
+        let (state_store, storage, checkpoint, worker_pool, event_buffer) = tokio::join!(
+            health::check_state_store(self.state_store.as_ref()),
+            health::check_storage(self.state_store.as_ref()),
+            health::check_checkpoint(self.state_store.as_ref(), checkpoint_interval, self.started_at),
+            health::check_worker_pool(&self.executor, self.scheduler.queue_depth()),
+            health::check_event_buffer(&self.executor),
         );
-        let task_id = task.id;
-        
-        let result = core.submit_task(task).await;
-        assert!(result.is_ok());
-        
-        let status = core.get_task_status(&task_id).await;
-        assert!(status.is_ok());
+        let dispatch = health::check_dispatch(self.scheduler.dispatch_gate());
+        let reservations = health::check_reservations(self.scheduler.reservations());
+        let mode = health::check_mode(self.mode());
+        let idle = health::check_idle(&self.idle.status().await);
+
+        let report = HealthReport::from_components(vec![
+            state_store,
+            storage,
+            checkpoint,
+            worker_pool,
+            event_buffer,
+            dispatch,
+            reservations,
+            mode,
+            idle,
+        ]);
+        if report.overall != HealthStatus::Healthy {
+            warn!("Saúde do TaskMesh: {:?}", report.overall);
+        }
+        report
     }
-}
 
+    /// Verifica configuração e conectividade (ver [`preflight`])
+    pub async fn preflight(&self) -> PreflightReport {
+        let store = self.state_store.as_ref();
+        let data_dir = self.config.data_dir.as_deref().map(std::path::Path::new);
+        let log_dir = self.executor.config().log_dir.clone();
+        let scratch_dir = self.executor.scratch().map(|scratch| scratch.config().dir.clone());
+        let (database, schema, redis, data, logs, scratch, clock, worker_pool) = tokio::join!(
+            preflight::check_database(store),
+            preflight::check_schema(store),
+            preflight::check_redis(self.config.redis_url.as_deref()),
+            preflight::check_writable_dir(CheckId::DataDir, data_dir),
+            preflight::check_writable_dir(CheckId::LogDir, log_dir.as_deref()),
+            preflight::check_writable_dir(CheckId::ScratchDir, scratch_dir.as_deref()),
+            preflight::check_clock(store, std::time::SystemTime::now()),
+            preflight::check_worker_pool(&self.executor),
+        );
+
+        let report = PreflightReport::new(vec![database, schema, redis, data, logs, scratch, clock, worker_pool]);
+        for check in report.checks.iter().filter(|check| check.status != CheckStatus::Pass) {
+            warn!(
+                "Verificação prévia {} ({:?}): {} — {}",
+                check.id.as_str(),
+                check.status,
+                check.detail,
+                check.hint.as_deref().unwrap_or("")
+            );
+        }
+        report
+    }
+
+    /// Liveness: o processo responde
+    pub fn is_live(&self) -> bool {
+        true
+    }
+
+    /// Readiness: nenhum componente em falha
+    pub async fn is_ready(&self) -> bool {
+        self.health().await.is_ready()
+    }
+
+    /// Troca a heurística de agendamento sem reiniciar
+    ///
+    /// A troca é persistida e vale também nos próximos inícios.
+    pub async fn set_scheduling_heuristic(&self, heuristic: SchedulingHeuristic) -> Result<(), TaskMeshError> {
+        self.ensure_active("troca de heurística")?;
+        self.save_tuning(|tuning| tuning.scheduling_heuristic = Some(heuristic.clone())).await?;
+        self.scheduler.update_heuristic(heuristic.clone()).await;
+        self.event_bus.publish(SystemEvent::new(
+            EventType::ConfigurationChanged,
+            None,
+            serde_json::json!({ "scheduling_heuristic": heuristic }),
+        )).await.map(|_| ())
+    }
+
+    /// Altera o número máximo de tarefas executando em paralelo
+    ///
+    /// Tarefas em execução não são interrompidas; reduções valem à medida
+    /// que elas terminam. O limite é persistido e vale também nos próximos
+    /// inícios, no lugar de `max_workers`. Retorna o limite anterior.
+    pub async fn set_max_concurrency(&self, limit: usize) -> Result<usize, TaskMeshError> {
+        self.ensure_active("ajuste de concorrência")?;
+        let previous = self.executor.set_max_concurrency(limit).await?;
+        self.save_tuning(|tuning| tuning.max_concurrency = Some(limit)).await?;
+        Ok(previous)
+    }
+
+    /// Número de tarefas aguardando despacho
+    pub fn queue_depth(&self) -> usize {
+        self.scheduler.queue_depth()
+    }
+
+    /// Tarefas aguardando despacho, com o que retém cada uma
+    pub async fn queue_snapshot(&self) -> Vec<scheduler::QueuedTaskView> {
+        self.scheduler.queue_snapshot().await
+    }
+
+    /// Por que uma tarefa enfileirada (por ID ou alias) ainda não executa
+    ///
+    /// Retorna `None` se a tarefa não está na fila.
+    pub async fn why_not_running(
+        &self,
+        task: impl Into<TaskRef>,
+    ) -> Result<Option<scheduler::NotRunningReason>, TaskMeshError> {
+        let task_id = self.resolve_task(task).await?;
+        Ok(self.scheduler.why_not_running(&task_id).await)
+    }
+
+    /// Altera a prioridade de uma tarefa ainda não despachada (por ID ou alias)
+    ///
+    /// Só tarefas `Pending`, `Scheduled` ou `AwaitingRetry` mudam de
+    /// prioridade; as demais resultam em [`TaskMeshError::InvalidState`]. A
+    /// tarefa persistida e o seu score na fila são atualizados, e um evento
+    /// `TaskReprioritized` registra a mudança na linha do tempo. Ancestrais
+    /// ainda na fila com prioridade menor herdam a nova prioridade, para que
+    /// a tarefa não espere atrás deles. Retorna as tarefas alteradas, a
+    /// indicada primeiro.
+    pub async fn reprioritize(
+        &self,
+        task: impl Into<TaskRef>,
+        priority: Priority,
+    ) -> Result<Vec<TaskId>, TaskMeshError> {
+        self.ensure_active("troca de prioridade")?;
+        let task_id = self.resolve_task(task).await?;
+        let status = self.state_store.get_task_status(&task_id).await?;
+        if !status.is_queued() {
+            return Err(TaskMeshError::InvalidState(format!(
+                "tarefa {} não está na fila ({})", task_id, status
+            )));
+        }
+        self.apply_priority(&task_id, priority, None).await?;
+        let mut changed = vec![task_id];
+
+        // Ancestrais em largura, como em `cascade_cancel`
+        let ancestors = {
+            let registry = self.registry.read().await;
+            let mut seen = std::collections::HashSet::new();
+            let mut order = Vec::new();
+            let mut frontier = std::collections::VecDeque::from([task_id]);
+            while let Some(current) = frontier.pop_front() {
+                for dependency in registry.get_dependencies(&current).into_iter().flatten() {
+                    let lower = registry.get_task(dependency).map_or(false, |task| task.priority < priority);
+                    if seen.insert(*dependency) {
+                        frontier.push_back(*dependency);
+                        if lower {
+                            order.push(*dependency);
+                        }
+                    }
+                }
+            }
+            order
+        };
+        for ancestor in ancestors {
+            if self.state_store.get_task_status(&ancestor).await?.is_queued() {
+                self.apply_priority(&ancestor, priority, Some(task_id)).await?;
+                changed.push(ancestor);
+            }
+        }
+        info!("Prioridade da tarefa {} alterada para {} ({} tarefas)", task_id, priority, changed.len());
+        Ok(changed)
+    }
+
+    /// Persiste a nova prioridade, reordena a fila e publica `TaskReprioritized`
+    async fn apply_priority(
+        &self,
+        task_id: &TaskId,
+        priority: Priority,
+        inherited_from: Option<TaskId>,
+    ) -> Result<(), TaskMeshError> {
+        let (task, previous) = {
+            let mut registry = self.registry.write().await;
+            let previous = registry.get_task(task_id).ok_or(TaskMeshError::TaskNotFound(*task_id))?.priority;
+            (registry.set_priority(task_id, priority)?, previous)
+        };
+        self.state_store.store_task(&task).await?;
+        self.scheduler.reprioritize(&task).await;
+        self.event_bus.publish(SystemEvent::new(
+            EventType::TaskReprioritized,
+            Some(*task_id),
+            serde_json::json!({ "from": previous, "to": priority, "inherited_from": inherited_from }),
+        )).await?;
+        Ok(())
+    }
+
+    /// Resolve uma referência (ID ou alias) para o ID da tarefa
+    ///
+    /// Aliases sem namespace são procurados em [`alias::DEFAULT_NAMESPACE`].
+    pub async fn resolve_task(&self, task: impl Into<TaskRef>) -> Result<TaskId, TaskMeshError> {
+        match task.into() {
+            TaskRef::Id(task_id) => Ok(task_id),
+            TaskRef::Alias(qualified) => {
+                let (namespace, name) = alias::split_alias(&qualified, alias::DEFAULT_NAMESPACE);
+                self.state_store
+                    .resolve_alias(namespace, name)
+                    .await?
+                    .ok_or(TaskMeshError::AliasNotFound(qualified))
+            }
+        }
+    }
+
+    /// Obtém o status de uma tarefa (por ID ou alias)
+    ///
+    /// Pode vir do cache de status, com até `status_cache.ttl` de idade para
+    /// transições gravadas fora deste processo (ver [`status_cache`]).
+    pub async fn get_task_status(&self, task: impl Into<TaskRef>) -> Result<TaskStatus, TaskMeshError> {
+        self.get_task_status_with(task, status_cache::Consistency::Cached).await
+    }
+
+    /// Obtém o status de uma tarefa com a consistência pedida
+    ///
+    /// [`Consistency::Strong`](status_cache::Consistency::Strong) ignora o
+    /// cache e lê o StateStore, que pode ainda não ter as transições no
+    /// buffer write-behind do executor.
+    pub async fn get_task_status_with(
+        &self,
+        task: impl Into<TaskRef>,
+        consistency: status_cache::Consistency,
+    ) -> Result<TaskStatus, TaskMeshError> {
+        let task_id = self.resolve_task(task).await?;
+        match consistency {
+            status_cache::Consistency::Cached => {
+                self.status_cache.get_or_load(&task_id, self.state_store.get_task_status(&task_id)).await
+            }
+            status_cache::Consistency::Strong => self.state_store.get_task_status(&task_id).await,
+        }
+    }
+
+    /// Acertos e faltas do cache de status
+    pub fn status_cache_stats(&self) -> status_cache::StatusCacheStats {
+        self.status_cache.stats()
+    }
+
+    /// Grava um status decidido pelo core, mantendo o cache em dia
+    async fn write_status(&self, task_id: &TaskId, status: TaskStatus) -> Result<(), TaskMeshError> {
+        if status.is_final() {
+            self.executor.forget_retries(task_id);
+        }
+        self.status_cache.record(task_id, &status);
+        self.state_store.update_task_status(task_id, status).await
+    }
+
+    /// Espera a primeira das tarefas chegar a um status final
+    ///
+    /// Tarefas já finalizadas antes da chamada contam (a mais à frente em
+    /// `task_ids` vence). Falhas com retry agendado não são finais. Se
+    /// `timeout` vencer, retorna [`TaskMeshError::WaitTimeout`] com todas as
+    /// tarefas em `pending`. Descartar o future não tem efeito colateral.
+    pub async fn wait_any(&self, task_ids: &[TaskId], timeout: Duration) -> Result<(TaskId, TaskStatus), TaskMeshError> {
+        if task_ids.is_empty() {
+            return Err(TaskMeshError::Configuration("wait_any precisa de ao menos uma tarefa".to_string()));
+        }
+        let (mut finished, pending) = self.wait_until_final(task_ids, timeout, 1).await?;
+        if finished.is_empty() {
+            return Err(TaskMeshError::WaitTimeout { completed: Vec::new(), pending });
+        }
+        Ok(finished.swap_remove(0))
+    }
+
+    /// Espera todas as tarefas chegarem a um status final
+    ///
+    /// Retorna os status na ordem de `task_ids` (sem repetições). Se
+    /// `timeout` vencer, retorna [`TaskMeshError::WaitTimeout`] com as
+    /// tarefas já finalizadas e as pendentes. Descartar o future não tem
+    /// efeito colateral.
+    pub async fn wait_all(&self, task_ids: &[TaskId], timeout: Duration) -> Result<Vec<(TaskId, TaskStatus)>, TaskMeshError> {
+        let (mut finished, pending) = self.wait_until_final(task_ids, timeout, usize::MAX).await?;
+        finished.sort_by_key(|(task_id, _)| task_ids.iter().position(|id| id == task_id));
+        if !pending.is_empty() {
+            return Err(TaskMeshError::WaitTimeout { completed: finished, pending });
+        }
+        Ok(finished)
+    }
+
+    /// Espera até `wanted` tarefas finalizarem ou `timeout` vencer
+    ///
+    /// Retorna as finalizadas, em ordem de chegada, e as pendentes.
+    async fn wait_until_final(
+        &self,
+        task_ids: &[TaskId],
+        timeout: Duration,
+        wanted: usize,
+    ) -> Result<(Vec<(TaskId, TaskStatus)>, Vec<TaskId>), TaskMeshError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        // Assina antes de ler o store: o que finalizar durante a leitura
+        // chega como evento e é reconciliado abaixo
+        let mut subscription = self.event_bus.subscribe(EventQuery {
+            event_types: vec![EventType::TaskCompleted, EventType::TaskFailed, EventType::TaskCancelled],
+            ..EventQuery::default()
+        });
+        let mut pending: Vec<TaskId> = Vec::with_capacity(task_ids.len());
+        for task_id in task_ids {
+            if !pending.contains(task_id) {
+                pending.push(*task_id);
+            }
+        }
+        let mut finished = Vec::new();
+        let wanted = wanted.min(pending.len());
+
+        // O executor grava o status antes de publicar o evento, mas a gravação
+        // pode estar no buffer write-behind
+        self.executor.flush_pending_writes().await?;
+        self.collect_final(&mut pending, &mut finished, None).await?;
+
+        let mut lag_seen = subscription.lagged();
+        let mut lag_check = tokio::time::interval_at(tokio::time::Instant::now() + WAIT_LAG_CHECK_INTERVAL, WAIT_LAG_CHECK_INTERVAL);
+        while finished.len() < wanted {
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => break,
+                event = subscription.recv() => {
+                    let Some(BusEvent { event, .. }) = event? else {
+                        return Err(TaskMeshError::Internal("Barramento de eventos encerrado durante a espera".to_string()));
+                    };
+                    if let Some(task_id) = event.task_id.filter(|task_id| pending.contains(task_id)) {
+                        self.executor.flush_pending_writes().await?;
+                        self.collect_final(&mut pending, &mut finished, Some(task_id)).await?;
+                    }
+                },
+                _ = lag_check.tick() => {
+                    // Eventos descartados por fila cheia: relê todas as pendentes
+                    let lagged = subscription.lagged();
+                    if lagged > lag_seen {
+                        lag_seen = lagged;
+                        self.executor.flush_pending_writes().await?;
+                        self.collect_final(&mut pending, &mut finished, None).await?;
+                    }
+                },
+            }
+        }
+        Ok((finished, pending))
+    }
+
+    /// Move de `pending` para `finished` as tarefas (ou só `only`) com status final
+    async fn collect_final(
+        &self,
+        pending: &mut Vec<TaskId>,
+        finished: &mut Vec<(TaskId, TaskStatus)>,
+        only: Option<TaskId>,
+    ) -> Result<(), TaskMeshError> {
+        let candidates: Vec<TaskId> = match only {
+            Some(task_id) => vec![task_id],
+            None => pending.clone(),
+        };
+        for task_id in candidates {
+            let status = self.state_store.get_task_status(&task_id).await?;
+            if status.is_final() {
+                pending.retain(|id| *id != task_id);
+                finished.push((task_id, status));
+            }
+        }
+        Ok(())
+    }
+
+    /// Tentativas de execução de uma tarefa, em ordem
+    ///
+    /// O status da tarefa continua sendo o da tentativa mais recente.
+    pub async fn list_attempts(&self, task: impl Into<TaskRef>) -> Result<Vec<AttemptRecord>, TaskMeshError> {
+        let task_id = self.resolve_task(task).await?;
+        self.state_store.list_attempts(&task_id).await
+    }
+
+    /// Manifesto de reprodutibilidade de uma tentativa (a partir de 1)
+    pub async fn get_manifest(
+        &self,
+        task: impl Into<TaskRef>,
+        attempt: u32,
+    ) -> Result<Option<ExecutionManifest>, TaskMeshError> {
+        let task_id = self.resolve_task(task).await?;
+        self.state_store.get_manifest(&task_id, attempt).await
+    }
+
+    /// Linha do tempo de uma tarefa (histórico de status, eventos, manifestos e marcos de despacho)
+    pub async fn get_task_timeline(&self, task_id: &TaskId) -> Result<Vec<TimelineItem>, TaskMeshError> {
+        let mut timeline: Vec<TimelineItem> = self.state_store
+            .get_status_history(task_id)
+            .await?
+            .into_iter()
+            .map(TimelineItem::Status)
+            .collect();
+        timeline.extend(
+            self.state_store
+                .list_manifests(task_id)
+                .await?
+                .into_iter()
+                .map(|manifest| TimelineItem::Manifest(Box::new(manifest))),
+        );
+        timeline.extend(self.state_store.list_attempts(task_id).await?.into_iter().filter_map(|attempt| {
+            attempt.dispatch.map(|times| TimelineItem::Dispatch { attempt: attempt.attempt, times })
+        }));
+
+        let mut query = EventQuery {
+            task_id: Some(*task_id),
+            limit: 1000,
+            ..EventQuery::default()
+        };
+        loop {
+            let page = self.state_store.query_events(&query).await?;
+            timeline.extend(page.events.into_iter().map(TimelineItem::Event));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        // Ordenação estável: status antes de eventos no mesmo instante
+        timeline.sort_by_key(|item| item.timestamp());
+        Ok(timeline)
+    }
+
+    /// Lista todas as tarefas
+    ///
+    /// Em modo somente leitura as tarefas vêm do `StateStore`.
+    pub async fn list_tasks(&self) -> Result<Vec<Task>, TaskMeshError> {
+        match self.mode() {
+            Mode::Active => self.registry.read().await.list_tasks(),
+            Mode::ReadOnly => self.state_store.list_tasks().await,
+        }
+    }
+
+    /// Tarefas com a tag (comparada na forma normalizada)
+    pub async fn list_tasks_with_tag(&self, tag: &str) -> Result<Vec<Task>, TaskMeshError> {
+        let tag = validation::normalize_tag(tag);
+        let mut tasks = self.list_tasks().await?;
+        tasks.retain(|task| task.tags.contains(&tag));
+        Ok(tasks)
+    }
+
+    /// Busca textual no nome e nos metadados das tarefas persistidas
+    ///
+    /// Correspondências exatas do nome vêm primeiro. Consultas sem palavras
+    /// resultam em erro de configuração; o backend Redis não suporta busca.
+    pub async fn search_tasks(&self, query: &str, limit: usize) -> Result<Vec<Task>, TaskMeshError> {
+        self.state_store.search_tasks(query, limit).await
+    }
+
+    /// Cancela uma tarefa (por ID ou alias) e seus dependentes
+    pub async fn cancel_task(&self, task: impl Into<TaskRef>) -> Result<(), TaskMeshError> {
+        self.cancel_task_with(task, CascadePolicy::default()).await.map(|_| ())
+    }
+
+    /// Cancela uma tarefa aplicando `policy` aos dependentes
+    ///
+    /// Retorna as tarefas canceladas ou marcadas como falhas, a raiz primeiro;
+    /// vazio se a tarefa já estava finalizada.
+    pub async fn cancel_task_with(
+        &self,
+        task: impl Into<TaskRef>,
+        policy: CascadePolicy,
+    ) -> Result<Vec<TaskId>, TaskMeshError> {
+        self.ensure_active("cancelamento de tarefas")?;
+        let task_id = self.resolve_task(task).await?;
+        if !self.cancel_one(&task_id, CancelReason::UserRequested, &task_id).await? {
+            return Ok(Vec::new());
+        }
+        let mut affected = vec![task_id];
+        affected.extend(self.cascade_cancel(&task_id, policy).await?);
+        Ok(affected)
+    }
+
+    /// Cancela uma tarefa não finalizada e publica `TaskCancelled` com a causa raiz
+    async fn cancel_one(&self, task_id: &TaskId, reason: CancelReason, root: &TaskId) -> Result<bool, TaskMeshError> {
+        if self.state_store.get_task_status(task_id).await?.is_final() {
+            return Ok(false);
+        }
+        let now = std::time::SystemTime::now();
+        if self.executor.is_running(task_id) {
+            self.executor.cancel_task(task_id, reason.clone()).await?;
+        } else {
+            self.scheduler.remove_task(task_id).await;
+            self.write_status(task_id, TaskStatus::Cancelled { cancelled_at: now, reason: reason.clone(), note: None })
+                .await?;
+        }
+        self.event_bus.publish(SystemEvent::new(
+            EventType::TaskCancelled,
+            Some(*task_id),
+            serde_json::json!({ "reason": reason, "root_cause": root }),
+        ).at(now)).await?;
+        Ok(true)
+    }
+
+    /// Aplica `policy` aos dependentes transitivos de `root`, em largura
+    async fn cascade_cancel(&self, root: &TaskId, policy: CascadePolicy) -> Result<Vec<TaskId>, TaskMeshError> {
+        let dependents = {
+            let registry = self.registry.read().await;
+            let mut seen = std::collections::HashSet::new();
+            let mut order = Vec::new();
+            let mut frontier = std::collections::VecDeque::from([*root]);
+            while let Some(task_id) = frontier.pop_front() {
+                for dependent in registry.get_dependents(&task_id).into_iter().flatten() {
+                    if seen.insert(*dependent) {
+                        order.push(*dependent);
+                        frontier.push_back(*dependent);
+                    }
+                }
+            }
+            order
+        };
+
+        let mut affected = Vec::new();
+        match policy {
+            CascadePolicy::CancelDependents => {
+                for task_id in dependents {
+                    if self.cancel_one(&task_id, CancelReason::DependencyFailed { root: *root }, root).await? {
+                        affected.push(task_id);
+                    }
+                }
+            }
+            CascadePolicy::FailDependents => {
+                let error = format!("Dependência cancelada (causa raiz {})", root);
+                for task_id in dependents {
+                    if self.state_store.get_task_status(&task_id).await?.is_final() {
+                        continue;
+                    }
+                    let now = std::time::SystemTime::now();
+                    self.scheduler.remove_task(&task_id).await;
+                    self.write_status(&task_id, TaskStatus::Failed {
+                        started_at: now,
+                        failed_at: now,
+                        error: error.clone(),
+                        retry_count: 0,
+                    }).await?;
+                    self.event_bus.publish(SystemEvent::new(
+                        EventType::TaskFailed,
+                        Some(task_id),
+                        serde_json::json!({ "error": error, "root_cause": root }),
+                    ).at(now)).await?;
+                    affected.push(task_id);
+                }
+            }
+            CascadePolicy::OrphanDependents => {
+                // A raiz nunca conclui: os dependentes ficam retidos na fila
+                if !dependents.is_empty() {
+                    warn!("{} dependentes da tarefa {} ficam bloqueados", dependents.len(), root);
+                }
+            }
+        }
+        Ok(affected)
+    }
+
+    /// Status agregado de um grupo de tarefas
+    pub async fn group_status(&self, group_id: &uuid::Uuid) -> Result<Option<GroupStatus>, TaskMeshError> {
+        self.state_store.group_status(group_id).await
+    }
+
+    /// Lista os grupos de tarefas com seu status agregado
+    pub async fn list_groups(&self) -> Result<Vec<GroupStatus>, TaskMeshError> {
+        self.state_store.list_groups().await
+    }
+
+    /// Cancela as tarefas não finalizadas de um grupo e seus dependentes
+    ///
+    /// Tarefas ainda na fila saem dela e são marcadas como canceladas; as em
+    /// execução são canceladas pelo executor. Retorna quantas tarefas foram
+    /// afetadas (zero para um grupo desconhecido).
+    pub async fn cancel_group(&self, group_id: &uuid::Uuid) -> Result<usize, TaskMeshError> {
+        self.cancel_group_with(group_id, CascadePolicy::default()).await
+    }
+
+    /// Cancela um grupo aplicando `policy` aos dependentes fora dele
+    pub async fn cancel_group_with(&self, group_id: &uuid::Uuid, policy: CascadePolicy) -> Result<usize, TaskMeshError> {
+        self.ensure_active("cancelamento de grupos")?;
+        let mut cancelled = Vec::new();
+        for task in self.state_store.list_group_tasks(group_id).await? {
+            if self.cancel_one(&task.id, CancelReason::GroupCancelled, &task.id).await? {
+                cancelled.push(task.id);
+            }
+        }
+        // Membros primeiro: todos recebem `GroupCancelled`, não a causa em cascata
+        let mut affected = cancelled.len();
+        for task_id in &cancelled {
+            affected += self.cascade_cancel(task_id, policy).await?.len();
+        }
+        info!("Grupo {}: {} tarefas canceladas", group_id, affected);
+        Ok(affected)
+    }
+
+    /// Obtém métricas do sistema
+    #[cfg(feature = "metrics")]
+    pub async fn get_metrics(&self) -> Result<metrics::SystemMetrics, TaskMeshError> {
+        metrics::collect_metrics().await
+    }
+
+    /// Avalia os SLAs agora, retornando os alertas e violações gerados
+    pub async fn check_slas(&self) -> Result<Vec<SystemEvent>, TaskMeshError> {
+        self.ensure_active("avaliação de SLAs")?;
+        self.sla_monitor.evaluate(&self.registry, self.state_store.as_ref()).await
+    }
+
+    /// Procura tarefas presas agora, aplicando a política configurada
+    ///
+    /// Retorna os eventos `TaskStuck` gerados.
+    pub async fn check_stuck_tasks(&self) -> Result<Vec<SystemEvent>, TaskMeshError> {
+        self.ensure_active("varredura de tarefas presas")?;
+        self.stuck_watchdog
+            .scan(self.state_store.as_ref(), &self.status_cache, &self.executor, &self.scheduler, &self.registry, &self.event_bus)
+            .await
+    }
+
+    /// Alertas e violações de SLA registrados desde a criação
+    pub fn sla_stats(&self) -> sla::SlaStats {
+        self.sla_monitor.stats()
+    }
+
+    /// Percentis dos atrasos do scheduler e de despacho nas últimas tentativas
+    pub fn dispatch_latency_stats(&self) -> dispatch_latency::LatencyStats {
+        self.dispatch_latency.stats()
+    }
+
+    /// Falhas enviadas à fila de mensagens mortas por regras `NoRetry`
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.executor.dead_letters().list()
+    }
+
+    /// Gera relatório de timeline para um conjunto de tarefas
+    pub async fn generate_report(
+        &self,
+        task_ids: &[TaskId],
+        format: ReportFormat,
+    ) -> Result<String, TaskMeshError> {
+        let report = TimelineReport::build(self.state_store.as_ref(), task_ids).await?;
+        report.render(format)
+    }
+
+    /// Emite um evento customizado da aplicação
+    pub async fn emit_custom_event(
+        &self,
+        name: impl Into<String>,
+        task_id: Option<TaskId>,
+        data: serde_json::Value,
+    ) -> Result<(), TaskMeshError> {
+        self.ensure_active("emissão de eventos")?;
+        let event = SystemEvent::new(EventType::Custom(name.into()), task_id, data);
+        self.event_bus.publish(event).await.map(|_| ())
+    }
+
+    /// Lê um intervalo de bytes do log de uma tarefa
+    pub async fn read_log(
+        &self,
+        task_id: &TaskId,
+        range: std::ops::Range<u64>,
+    ) -> Result<Vec<u8>, TaskMeshError> {
+        match self.executor.log_store() {
+            Some(log_store) => log_store.read(task_id, range).await,
+            None => Err(TaskMeshError::Configuration(
+                "Logs em arquivo desabilitados (data_dir não configurado)".to_string(),
+            )),
+        }
+    }
+
+    /// Exporta o histórico de execução para análise offline (ver [`export`])
+    #[cfg(feature = "export")]
+    pub async fn export_history(
+        &self,
+        request: export::ExportRequest,
+    ) -> Result<export::ExportSummary, TaskMeshError> {
+        export::export_history(self.state_store.as_ref(), &request).await
+    }
+
+    /// Remove dados antigos do StateStore, arquivos de log expirados,
+    /// diretórios de scratch e cgroups órfãos
+    pub async fn cleanup_old_data(&self, retention_days: u32) -> Result<(), TaskMeshError> {
+        self.ensure_active("limpeza de dados")?;
+        self.state_store.cleanup_old_data(retention_days).await?;
+        if let Some(log_store) = self.executor.log_store() {
+            log_store.cleanup(retention_days).await?;
+        }
+        if let Some(scratch) = self.executor.scratch() {
+            scratch.sweep().await?;
+        }
+        #[cfg(feature = "cgroups")]
+        if let Some(cgroups) = self.executor.cgroups() {
+            cgroups.sweep()?;
+        }
+        Ok(())
+    }
+
+    /// Força criação de checkpoint
+    pub async fn create_checkpoint(&self) -> Result<(), TaskMeshError> {
+        self.ensure_active("criação de checkpoint")?;
+        self.executor.flush_pending_writes().await?;
+        self.checkpoint_engine.create_checkpoint().await
+    }
+
+    /// Restaura estado a partir de checkpoint
+    pub async fn restore_from_checkpoint(
+        &self,
+        checkpoint_id: &str,
+    ) -> Result<(), TaskMeshError> {
+        self.ensure_active("restauração de checkpoint")?;
+        let restored = self.checkpoint_engine.restore_checkpoint(checkpoint_id, false).await;
+        self.status_cache.clear();
+        restored.map(|_| ())
+    }
+
+    /// Restaura um checkpoint, recorrendo ao anterior íntegro se ele estiver corrompido
+    ///
+    /// Retorna o ID do checkpoint efetivamente restaurado.
+    pub async fn restore_from_checkpoint_with_fallback(
+        &self,
+        checkpoint_id: &str,
+    ) -> Result<String, TaskMeshError> {
+        self.ensure_active("restauração de checkpoint")?;
+        let restored = self.checkpoint_engine.restore_checkpoint(checkpoint_id, true).await;
+        self.status_cache.clear();
+        restored
+    }
+
+    /// Audita os checkpoints armazenados e retorna os IDs corrompidos
+    pub async fn verify_checkpoints(&self) -> Result<Vec<String>, TaskMeshError> {
+        self.state_store.verify_checkpoints().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_test;
+
+    #[tokio::test]
+    async fn test_task_mesh_core_creation() {
+        let config = TaskMeshConfig::default();
+        let core = TaskMeshCore::new(config).await;
+        assert!(core.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_start_stop_leaves_no_background_tasks() {
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        assert_eq!(core.background_task_count(), 0);
+
+        for _ in 0..50 {
+            core.start().await.unwrap();
+            // Loop de comandos, flush write-behind, checkpoints periódicos e reservas consumidas
+            assert_eq!(core.background_task_count(), 4);
+            core.shutdown().await.unwrap();
+            assert_eq!(core.background_task_count(), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_callbacks_run_in_order_and_hanging_ones_time_out() {
+        let config = TaskMeshConfig {
+            lifecycle_callback_timeout: Duration::from_millis(200).into(),
+            ..TaskMeshConfig::default()
+        };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let record = |label: &'static str| {
+            let seen = seen.clone();
+            move |ctx: LifecycleContext| {
+                seen.lock().unwrap().push((label, ctx));
+                async { Ok(()) }
+            }
+        };
+        for phase in [LifecyclePhase::Starting, LifecyclePhase::Started, LifecyclePhase::Draining] {
+            core.on_lifecycle(phase, record("early"));
+        }
+        core.on_lifecycle(LifecyclePhase::Draining, |_ctx| std::future::pending());
+
+        core.start().await.unwrap();
+        core.on_lifecycle(LifecyclePhase::Stopped, record("late"));
+        let started = std::time::Instant::now();
+        core.shutdown_with_reason("deploy").await.unwrap();
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(200) && elapsed < Duration::from_secs(2), "{:?}", elapsed);
+
+        let seen = seen.lock().unwrap();
+        let order: Vec<(&str, LifecyclePhase, &str)> =
+            seen.iter().map(|(label, ctx)| (*label, ctx.phase, ctx.reason.as_str())).collect();
+        assert_eq!(
+            order,
+            vec![
+                ("early", LifecyclePhase::Starting, "start"),
+                ("early", LifecyclePhase::Started, "start"),
+                ("early", LifecyclePhase::Draining, "deploy"),
+                ("late", LifecyclePhase::Stopped, "deploy"),
+            ]
+        );
+        // Loop de comandos, flush write-behind, checkpoints periódicos e reservas consumidas
+        assert_eq!(seen[0].1.stats.background_loops, 0);
+        assert_eq!(seen[1].1.stats.background_loops, 4);
+        assert_eq!(seen[3].1.stats.background_loops, 0);
+        assert!(seen[3].1.stats.uptime >= seen[1].1.stats.uptime);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_phases_run_once_per_start() {
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for phase in [LifecyclePhase::Draining, LifecyclePhase::Stopped] {
+            let seen = seen.clone();
+            core.on_lifecycle(phase, move |ctx: LifecycleContext| {
+                seen.lock().unwrap().push(ctx.phase);
+                async { Ok(()) }
+            });
+        }
+
+        // Sem start, nada a drenar
+        core.shutdown().await.unwrap();
+        assert!(seen.lock().unwrap().is_empty());
+
+        core.start().await.unwrap();
+        core.shutdown().await.unwrap();
+        core.shutdown().await.unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![LifecyclePhase::Draining, LifecyclePhase::Stopped]);
+    }
+
+    #[tokio::test]
+    async fn test_submit_and_get_task() {
+        let config = TaskMeshConfig::default();
+        let core = TaskMeshCore::new(config).await.unwrap();
+        
+        let task = Task::new(
+            "test_task".to_string(),
+            TaskDefinition::command("echo hello"),
+            vec![],
+        );
+        let task_id = task.id;
+        
+        let result = core.submit_task(task).await;
+        assert!(result.is_ok());
+        
+        let status = core.get_task_status(&task_id).await;
+        assert!(status.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejected_when_queue_full() {
+        let config = TaskMeshConfig {
+            max_pending_tasks: Some(5),
+            ..TaskMeshConfig::default()
+        };
+        // Executor não iniciado: nenhuma tarefa é despachada
+        let core = TaskMeshCore::new(config).await.unwrap();
+
+        let mut rejected = 0;
+        for i in 0..10 {
+            let task = Task::new(format!("task_{}", i), TaskDefinition::command("true"), vec![]);
+            match core.submit_task(task).await {
+                Ok(_) => {}
+                Err(TaskMeshError::QueueFull { pending, limit }) => {
+                    assert_eq!((pending, limit), (5, 5));
+                    rejected += 1;
+                }
+                Err(e) => panic!("erro inesperado: {}", e),
+            }
+        }
+        assert_eq!(rejected, 5);
+        assert_eq!(core.queue_depth(), 5);
+        assert_eq!(TaskMeshError::QueueFull { pending: 5, limit: 5 }.http_status(), 429);
+
+        // Lote não cabe: nada é submetido
+        let batch = vec![Task::new("x".to_string(), TaskDefinition::command("true"), vec![])];
+        assert!(matches!(core.submit_batch(batch).await, Err(TaskMeshError::QueueFull { .. })));
+        assert_eq!(core.queue_depth(), 5);
+
+        let blocked = Task::new("blocked".to_string(), TaskDefinition::command("true"), vec![]);
+        let result = core.submit_task_blocking(blocked, std::time::Duration::from_millis(50)).await;
+        assert!(matches!(result, Err(TaskMeshError::QueueFull { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_generator_expansion_rewires_sink() {
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+
+        let generator = Task::new(
+            "find_files".to_string(),
+            TaskDefinition::generator(TaskDefinition::command("ls"), Some(10)),
+            vec![],
+        ).with_tags(vec!["batch".to_string()]);
+        let generator_id = generator.id;
+        let sink = Task::new("sink".to_string(), TaskDefinition::command("echo done"), vec![generator_id])
+            .with_priority(Priority::CRITICAL);
+        let sink_id = sink.id;
+        core.submit_batch(vec![generator, sink]).await.unwrap();
+
+        let specs: Vec<serde_json::Value> = (1..=3)
+            .map(|i| serde_json::json!({
+                "name": format!("echo_{}", i),
+                "definition": TaskDefinition::command(format!("echo {}", i)),
+            }))
+            .collect();
+        let result = TaskResult {
+            exit_code: 0,
+            stdout: String::new(),
+            stderr: String::new(),
+            output_data: Some(serde_json::json!({ "generated": specs })),
+            metrics: ExecutionMetrics::default(),
+            log_ref: None,
+        };
+
+        let resources = ResourceAllocation::default();
+        assert_eq!(core.scheduler.get_next_task(&resources).await, Some(generator_id));
+        let children = core.expand_generator(&generator_id, &result).await.unwrap();
+        assert_eq!(children.len(), 3);
+        core.scheduler.report_task_completion(generator_id, ExecutionMetrics::default()).await;
+
+        // Tarefas geradas aparecem na listagem com a origem
+        let listed = core.list_tasks().await.unwrap();
+        let generated: Vec<&Task> = listed.iter()
+            .filter(|t| t.metadata.get(generator::GENERATED_BY_KEY) == Some(&generator_id.to_string()))
+            .collect();
+        assert_eq!(generated.len(), 3);
+        assert!(generated.iter().all(|t| t.tags.contains(&"batch".to_string())));
+
+        // As 3 tarefas geradas são despachadas; o sink espera por todas
+        let mut dispatched = Vec::new();
+        while let Some(next) = core.scheduler.get_next_task(&resources).await {
+            dispatched.push(next);
+        }
+        let mut expected = children.clone();
+        expected.sort();
+        dispatched.sort();
+        assert_eq!(dispatched, expected);
+
+        for child in &children {
+            assert_eq!(core.scheduler.get_next_task(&resources).await, None);
+            core.scheduler.report_task_completion(*child, ExecutionMetrics::default()).await;
+        }
+        assert_eq!(core.scheduler.get_next_task(&resources).await, Some(sink_id));
+    }
+
+    #[tokio::test]
+    async fn test_task_alias_lookup_and_conflict() {
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let nightly: TaskRef = "nightly-etl".parse().unwrap();
+
+        let etl = Task::new("etl".to_string(), TaskDefinition::command("true"), vec![]).with_alias("nightly-etl");
+        let etl_id = core.submit_task(etl).await.unwrap();
+        assert_eq!(core.resolve_task(nightly.clone()).await.unwrap(), etl_id);
+        assert!(core.get_task_status(nightly).await.is_ok());
+
+        let clash = Task::new("clash".to_string(), TaskDefinition::command("true"), vec![]).with_alias("nightly-etl");
+        match core.submit_task(clash).await {
+            Err(error @ TaskMeshError::AliasConflict { existing, .. }) => {
+                assert_eq!(existing, etl_id);
+                assert_eq!(error.http_status(), 409);
+            }
+            other => panic!("esperado AliasConflict, obtido {:?}", other),
+        }
+
+        // O mesmo alias é livre em outro namespace
+        let scoped = Task::new("scoped".to_string(), TaskDefinition::command("true"), vec![])
+            .with_alias("nightly-etl")
+            .with_metadata(generator::NAMESPACE_KEY.to_string(), "data".to_string());
+        let scoped_id = core.submit_task(scoped).await.unwrap();
+        assert_eq!(core.resolve_task("data/nightly-etl".parse::<TaskRef>().unwrap()).await.unwrap(), scoped_id);
+        assert!(matches!(
+            core.resolve_task("missing".parse::<TaskRef>().unwrap()).await,
+            Err(TaskMeshError::AliasNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_file_mixes_alias_and_id_dependencies() {
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let upstream = Task::new("upstream".to_string(), TaskDefinition::command("true"), vec![]).with_alias("upstream");
+        let plain = Task::new("plain".to_string(), TaskDefinition::command("true"), vec![]);
+        let (upstream_id, plain_id) = (upstream.id, plain.id);
+        core.submit_batch(vec![upstream, plain]).await.unwrap();
+
+        let workflow = WorkflowFile::from_json(&serde_json::json!({ "tasks": [
+            {
+                "name": "extract",
+                "alias": "extract",
+                "definition": TaskDefinition::command("echo extract"),
+                "depends_on": ["upstream", plain_id.to_string()],
+            },
+            {
+                "name": "load",
+                "definition": TaskDefinition::command("echo load"),
+                "depends_on": ["extract"],
+            },
+        ]}).to_string()).unwrap();
+        let ids = core.submit_workflow_file(workflow).await.unwrap();
+
+        {
+            let registry = core.registry.read().await;
+            assert_eq!(registry.get_task(&ids[0]).unwrap().dependencies, vec![upstream_id, plain_id]);
+            assert_eq!(registry.get_task(&ids[1]).unwrap().dependencies, vec![ids[0]]);
+        }
+        assert_eq!(core.resolve_task("extract".parse::<TaskRef>().unwrap()).await.unwrap(), ids[0]);
+    }
+
+    #[tokio::test]
+    async fn test_status_cache_absorbs_polling_and_sees_local_writes() {
+        let config = TaskMeshConfig {
+            status_cache: status_cache::StatusCacheConfig { ttl: Duration::from_secs(3600).into(), ..Default::default() },
+            ..TaskMeshConfig::default()
+        };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        let task = Task::new("poll".to_string(), TaskDefinition::command("sleep 5"), vec![]);
+        let task_id = core.submit_task(task.clone()).await.unwrap();
+
+        for _ in 0..1000 {
+            core.get_task_status(task_id).await.unwrap();
+        }
+        let stats = core.status_cache_stats();
+        assert_eq!(stats.hits + stats.misses, 1000);
+        assert!(stats.misses <= 3, "{:?}", stats);
+
+        // A transição do executor ainda está no buffer write-behind, mas já é lida apesar do TTL
+        core.executor.execute_task(task).await.unwrap();
+        assert!(matches!(core.get_task_status(task_id).await.unwrap(), TaskStatus::Running { .. }));
+        let strong = core.get_task_status_with(task_id, status_cache::Consistency::Strong).await.unwrap();
+        assert!(matches!(strong, TaskStatus::Pending | TaskStatus::Scheduled), "{:?}", strong);
+
+        core.create_checkpoint().await.unwrap();
+        let checkpoint_id = core.state_store.list_checkpoints().await.unwrap().pop().unwrap();
+        assert_eq!(core.status_cache_stats().entries, 1);
+        core.restore_from_checkpoint(&checkpoint_id).await.unwrap();
+        assert_eq!(core.status_cache_stats().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_defaults_merge_into_workflow_and_generated_tasks() {
+        use defaults::{DefaultSource, TaskDefaults};
+
+        let payments = TaskDefaults {
+            tags: vec!["Team:Payments".to_string()],
+            metadata: HashMap::from([
+                ("cost-center".to_string(), "123".to_string()),
+                ("owner".to_string(), "ns".to_string()),
+            ]),
+            propagate_to_generated: true,
+        };
+        let config = TaskMeshConfig {
+            metadata_limits: validation::MetadataLimits { max_metadata_bytes: 256, ..Default::default() },
+            namespace_defaults: HashMap::from([("payments".to_string(), payments)]),
+            ..TaskMeshConfig::default()
+        };
+        let core = TaskMeshCore::new(config).await.unwrap();
+
+        let workflow = WorkflowFile::from_json(&serde_json::json!({
+            "name": "nightly",
+            "defaults": { "tags": ["tier:batch"], "metadata": { "namespace": "payments", "owner": "wf" } },
+            "tasks": [
+                { "name": "own", "definition": TaskDefinition::command("true"), "metadata": { "owner": "ana" } },
+                { "name": "inherits", "definition": TaskDefinition::command("true") },
+                { "name": "gen", "definition": TaskDefinition::generator(TaskDefinition::command("ls"), None) },
+            ],
+        }).to_string()).unwrap();
+        let ids = core.submit_workflow_file(workflow).await.unwrap();
+        let task = |registry: &TaskRegistry, id: &TaskId| registry.get_task(id).unwrap().clone();
+
+        // Tarefa > workflow > namespace
+        let (own, inherits) = {
+            let registry = core.registry.read().await;
+            (task(&registry, &ids[0]), task(&registry, &ids[1]))
+        };
+        assert_eq!(own.metadata["owner"], "ana");
+        assert_eq!(inherits.metadata["owner"], "wf");
+        assert_eq!(inherits.metadata["cost-center"], "123");
+        assert_eq!(inherits.tags, vec!["tier:batch", "team:payments"]);
+        let sources = defaults::provenance(&inherits).unwrap();
+        assert_eq!(sources.metadata["owner"], DefaultSource::Workflow);
+        assert_eq!(sources.metadata["cost-center"], DefaultSource::Namespace);
+        assert!(!defaults::provenance(&own).unwrap().metadata.contains_key("owner"));
+
+        // Geradas herdam só o namespace, que propaga; o `owner` do workflow não passa
+        let result = TaskResult {
+            exit_code: 0,
+            stdout: serde_json::json!([{ "name": "child", "definition": TaskDefinition::command("true") }]).to_string(),
+            stderr: String::new(),
+            output_data: None,
+            metrics: ExecutionMetrics::default(),
+            log_ref: None,
+        };
+        let children = core.expand_generator(&ids[2], &result).await.unwrap();
+        let child = task(&*core.registry.read().await, &children[0]);
+        assert_eq!(child.metadata["cost-center"], "123");
+        assert_eq!(child.metadata["owner"], "ns");
+        assert!(child.tags.contains(&"team:payments".to_string()));
+        assert_eq!(defaults::provenance(&child).unwrap().tags["team:payments"], DefaultSource::Namespace);
+
+        // O limite vale para o resultado da mesclagem
+        let blob = |namespace: &str| {
+            Task::new("blob".to_string(), TaskDefinition::command("true"), vec![])
+                .with_metadata(generator::NAMESPACE_KEY.to_string(), namespace.to_string())
+                .with_metadata("blob".to_string(), "x".repeat(200))
+        };
+        core.submit_task(blob("other")).await.unwrap();
+        let TaskMeshError::Validation(validation) = core.submit_task(blob("payments")).await.unwrap_err() else {
+            panic!("erro inesperado")
+        };
+        assert_eq!(validation.violations[0].field, "metadata");
+    }
+
+    #[tokio::test]
+    async fn test_read_only_standby_sees_active_tasks_and_promotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig {
+            database_url: format!("sqlite://{}", dir.path().join("state.db").display()),
+            ..TaskMeshConfig::default()
+        };
+        let task = |name: &str| Task::new(name.to_string(), TaskDefinition::command("true"), vec![]);
+
+        let active = TaskMeshCore::new(config.clone()).await.unwrap();
+        let standby = TaskMeshCore::new(TaskMeshConfig { mode: Mode::ReadOnly, ..config }).await.unwrap();
+        standby.start().await.unwrap();
+        assert_eq!(standby.background_task_count(), 0);
+
+        let submitted = active.submit_task(task("primary")).await.unwrap();
+        let listed: Vec<TaskId> = standby.list_tasks().await.unwrap().iter().map(|task| task.id).collect();
+        assert_eq!(listed, vec![submitted]);
+        assert!(matches!(standby.get_task_status(&submitted).await.unwrap(), TaskStatus::Pending));
+        let mode = standby.health().await.component(health::components::MODE).cloned().unwrap();
+        assert_eq!((mode.status, mode.detail.as_str()), (HealthStatus::Healthy, "somente leitura"));
+
+        assert!(matches!(standby.submit_task(task("rejected")).await, Err(TaskMeshError::UnsupportedOperation(_))));
+        assert!(matches!(standby.cancel_task(&submitted).await, Err(TaskMeshError::UnsupportedOperation(_))));
+        assert!(matches!(standby.create_checkpoint().await, Err(TaskMeshError::UnsupportedOperation(_))));
+        assert_eq!(standby.state_store.list_tasks().await.unwrap().len(), 1);
+        assert!(standby.state_store.list_checkpoints().await.unwrap().is_empty());
+
+        drop(active);
+        assert_eq!(standby.promote().await.unwrap(), 1);
+        assert_eq!(standby.mode(), Mode::Active);
+        assert_eq!(standby.background_task_count(), 4);
+        let promoted = standby.submit_task(task("after-promotion")).await.unwrap();
+
+        let resources = ResourceAllocation::default();
+        let mut dispatched = vec![
+            standby.scheduler.get_next_task(&resources).await.unwrap(),
+            standby.scheduler.get_next_task(&resources).await.unwrap(),
+        ];
+        dispatched.sort();
+        let mut expected = vec![submitted, promoted];
+        expected.sort();
+        assert_eq!(dispatched, expected);
+        standby.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_workflow_group_status_and_cancel_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig {
+            database_url: format!("sqlite://{}", dir.path().join("state.db").display()),
+            ..TaskMeshConfig::default()
+        };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        let spec = |name: &str, depends_on: &[&str]| serde_json::json!({
+            "name": name,
+            "alias": name,
+            "definition": TaskDefinition::command("true"),
+            "depends_on": depends_on,
+        });
+        // extract -> (clean -> train, enrich -> score) -> publish
+        let workflow = WorkflowFile::from_json(&serde_json::json!({
+            "name": "nightly",
+            "tasks": [
+                spec("extract", &[]),
+                spec("clean", &["extract"]),
+                spec("enrich", &["extract"]),
+                spec("train", &["clean"]),
+                spec("score", &["enrich"]),
+                spec("publish", &["train", "score"]),
+            ],
+        }).to_string()).unwrap();
+        let ids = core.submit_workflow_file(workflow).await.unwrap();
+        let group_id = core.state_store.get_task(&ids[0]).await.unwrap().unwrap().group_id.unwrap();
+
+        let resources = ResourceAllocation::default();
+        let now = std::time::SystemTime::now();
+        let completed = TaskStatus::Completed {
+            started_at: now,
+            completed_at: now,
+            result: TaskResult {
+                exit_code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+                output_data: None,
+                metrics: ExecutionMetrics::default(),
+                log_ref: None,
+            },
+        };
+        assert_eq!(core.scheduler.get_next_task(&resources).await, Some(ids[0]));
+        core.state_store.update_task_status(&ids[0], completed.clone()).await.unwrap();
+        core.scheduler.mark_completed(&[ids[0]]).await;
+        // O ramo `enrich` falha; `clean` conclui
+        for _ in 0..2 {
+            core.scheduler.get_next_task(&resources).await.unwrap();
+        }
+        core.state_store.update_task_status(&ids[1], completed).await.unwrap();
+        core.scheduler.mark_completed(&[ids[1]]).await;
+        core.state_store.update_task_status(&ids[2], TaskStatus::Failed {
+            started_at: now,
+            failed_at: now,
+            error: "exit 1".to_string(),
+            retry_count: 0,
+        }).await.unwrap();
+        core.scheduler.report_task_failure(ids[2], "exit 1".to_string()).await;
+
+        let status = core.group_status(&group_id).await.unwrap().unwrap();
+        assert_eq!(status.name.as_deref(), Some("nightly"));
+        assert_eq!((status.counts.completed, status.counts.failed, status.counts.pending), (2, 1, 3));
+        assert_eq!(status.state, GroupState::Running);
+        assert_eq!(core.list_groups().await.unwrap(), vec![status]);
+
+        assert_eq!(core.cancel_group(&group_id).await.unwrap(), 3);
+        for id in &ids[3..] {
+            assert!(matches!(core.get_task_status(id).await.unwrap(), TaskStatus::Cancelled { .. }));
+        }
+        assert_eq!(core.scheduler.get_next_task(&resources).await, None);
+        assert_eq!(core.queue_depth(), 0);
+        let status = core.group_status(&group_id).await.unwrap().unwrap();
+        assert_eq!((status.counts.cancelled, status.state), (3, GroupState::Failed));
+        assert_eq!(core.cancel_group(&group_id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_chain_root_under_each_cascade_policy() {
+        async fn chain(core: &TaskMeshCore) -> Vec<TaskId> {
+            let mut tasks: Vec<Task> = Vec::new();
+            for name in ["a", "b", "c", "d"] {
+                let dependencies = tasks.last().map(|task| vec![task.id]).unwrap_or_default();
+                tasks.push(Task::new(name.to_string(), TaskDefinition::command("true"), dependencies));
+            }
+            core.submit_batch(tasks).await.unwrap()
+        }
+
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let ids = chain(&core).await;
+        assert_eq!(core.cancel_task_with(&ids[0], CascadePolicy::CancelDependents).await.unwrap(), ids);
+        assert!(matches!(
+            core.get_task_status(&ids[0]).await.unwrap(),
+            TaskStatus::Cancelled { reason: CancelReason::UserRequested, .. }
+        ));
+        for id in &ids[1..] {
+            match core.get_task_status(id).await.unwrap() {
+                TaskStatus::Cancelled { reason: CancelReason::DependencyFailed { root }, .. } => assert_eq!(root, ids[0]),
+                other => panic!("status inesperado: {:?}", other),
+            }
+        }
+        let events = core.state_store.get_events(None, None).await.unwrap();
+        let cascaded: Vec<&SystemEvent> = events
+            .iter()
+            .filter(|event| event.event_type == EventType::TaskCancelled && event.task_id != Some(ids[0]))
+            .collect();
+        assert_eq!(cascaded.len(), 3);
+        assert!(cascaded.iter().all(|event| event.data["root_cause"] == ids[0].to_string()));
+        assert_eq!(core.queue_depth(), 0);
+
+        let ids = chain(&core).await;
+        assert_eq!(core.cancel_task_with(&ids[0], CascadePolicy::OrphanDependents).await.unwrap(), vec![ids[0]]);
+        for id in &ids[1..] {
+            assert!(matches!(core.get_task_status(id).await.unwrap(), TaskStatus::Pending));
+        }
+        // Bloqueados: permanecem na fila sem serem despachados
+        assert_eq!(core.queue_depth(), 3);
+        assert_eq!(core.scheduler.get_next_task(&ResourceAllocation::default()).await, None);
+
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let ids = chain(&core).await;
+        assert_eq!(core.cancel_task_with(&ids[0], CascadePolicy::FailDependents).await.unwrap(), ids);
+        for id in &ids[1..] {
+            match core.get_task_status(id).await.unwrap() {
+                TaskStatus::Failed { error, .. } => assert!(error.contains(&ids[0].to_string()), "{}", error),
+                other => panic!("status inesperado: {:?}", other),
+            }
+        }
+        assert!(core.cancel_task_with(&ids[0], CascadePolicy::default()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_submission_validates_metadata_and_normalizes_tags() {
+        let limits = validation::MetadataLimits { max_tags: 3, max_value_len: 16, ..Default::default() };
+        let core = TaskMeshCore::new(TaskMeshConfig { metadata_limits: limits, ..TaskMeshConfig::default() })
+            .await
+            .unwrap();
+        let task = |name: &str, tags: &[&str]| {
+            Task::new(name.to_string(), TaskDefinition::command("true"), vec![])
+                .with_tags(tags.iter().map(|tag| tag.to_string()).collect())
+        };
+
+        let id = core.submit_task(task("normalizada", &["Tag:Foo", " tag:foo ", "ETL"])).await.unwrap();
+        assert_eq!(core.list_tasks_with_tag("tag:foo").await.unwrap()[0].id, id);
+        assert_eq!(core.list_tasks_with_tag("TAG:FOO").await.unwrap()[0].tags, vec!["tag:foo", "etl"]);
+
+        // Lote: todas as violações de todas as tarefas, nenhuma submetida
+        let mut oversized = task("grande", &["a", "b", "c", "d"]);
+        oversized.metadata.insert("blob".to_string(), "x".repeat(17));
+        let error = core.submit_batch(vec![task("ok", &["a"]), oversized]).await.unwrap_err();
+        assert_eq!(error.http_status(), 422);
+        let TaskMeshError::Validation(validation) = error else { panic!("erro inesperado") };
+        let fields: Vec<&str> = validation.violations.iter().map(|violation| violation.field.as_str()).collect();
+        assert_eq!(fields, vec!["tasks[1].tags", "tasks[1].metadata.blob"]);
+        assert_eq!(core.list_tasks().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_tuning_survives_restart_and_reaches_running_core() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let config = TaskMeshConfig { database_url, max_workers: 4, ..TaskMeshConfig::default() };
+        let running = TaskMeshCore::new(config.clone()).await.unwrap();
+
+        // Outro processo (como `taskmesh tune`) grava os ajustes
+        let cli = TaskMeshCore::new(config.clone()).await.unwrap();
+        cli.set_scheduling_heuristic(SchedulingHeuristic::FIFO).await.unwrap();
+        assert_eq!(cli.set_max_concurrency(2).await.unwrap(), 4);
+
+        running.load_queue().await.unwrap();
+        assert_eq!(running.executor.max_concurrency(), 2);
+        assert!(matches!(running.scheduler.heuristic().await, SchedulingHeuristic::FIFO));
+
+        let restarted = TaskMeshCore::new(config).await.unwrap();
+        assert_eq!(restarted.executor.max_concurrency(), 2);
+        assert!(matches!(restarted.scheduler.heuristic().await, SchedulingHeuristic::FIFO));
+    }
+
+    #[tokio::test]
+    async fn test_full_compaction_requires_paused_dispatch_or_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let core = TaskMeshCore::new(TaskMeshConfig { database_url, ..TaskMeshConfig::default() }).await.unwrap();
+
+        let refused = core.compact_storage(false).await.unwrap_err();
+        assert!(matches!(refused, TaskMeshError::UnsupportedOperation(_)), "{}", refused);
+        assert!(core.compact_storage(true).await.unwrap().after.file_bytes.is_some());
+
+        core.pause_dispatch("compactação").await.unwrap();
+        let report = core.compact_storage(false).await.unwrap();
+        assert_eq!(report.mode, state_store::CompactionMode::Full);
+        assert_eq!(report.after.freelist_pages, Some(0));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_idle_scale_down_and_warm_up_on_submit() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig {
+            database_url: format!("sqlite://{}", dir.path().join("state.db").display()),
+            max_workers: 2,
+            strict_durability: true,
+            idle: idle::IdleConfig {
+                idle_after: Some(units::DurationSpec::from_millis(50)),
+                check_interval: units::DurationSpec::from_millis(10),
+                ..idle::IdleConfig::default()
+            },
+            ..TaskMeshConfig::default()
+        };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        core.start().await.unwrap();
+        // Consultas simultâneas abrem mais conexões no pool
+        futures::future::join_all((0..4).map(|_| core.state_store.list_tasks())).await;
+        let busy = core.idle.status().await;
+        assert_eq!((busy.idle_since, busy.active_workers), (None, 2));
+
+        let mut events = core.event_bus.subscribe(EventQuery {
+            event_types: vec![EventType::ConfigurationChanged],
+            ..EventQuery::default()
+        });
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !core.idle.is_idle() {
+            assert!(std::time::Instant::now() < deadline, "core não ficou ocioso");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let idle = core.idle.status().await;
+        assert!(idle.idle_since.is_some());
+        assert_eq!((idle.active_workers, idle.open_connections, idle.idle_entries), (0, Some(1), 1));
+        assert_eq!(core.checkpoint_engine.interval_stretch(), 10);
+        assert_eq!(events.recv().await.unwrap().unwrap().event.data["idle"], true);
+        let health = core.health().await;
+        let component = health.components.iter().find(|c| c.name == health::components::IDLE).unwrap();
+        assert!(component.detail.starts_with("ocioso"), "{}", component.detail);
+
+        let task = Task::new("after_idle".to_string(), TaskDefinition::command("echo ok"), vec![]);
+        let task_id = core.submit_task(task.clone()).await.unwrap();
+        let warm = core.idle.status().await;
+        assert_eq!((warm.idle_since, warm.active_workers, warm.warmups), (None, 2, 1));
+        assert!(warm.last_warmup.is_some());
+        assert_eq!(core.checkpoint_engine.interval_stretch(), 1);
+        let woke = events.recv().await.unwrap().unwrap().event;
+        assert_eq!(woke.data["idle"], false);
+        assert!(woke.data["warmup_ms"].is_u64());
+
+        assert_eq!(core.scheduler.get_next_task(&ResourceAllocation::default()).await, Some(task_id));
+        core.executor.execute_task(task).await.unwrap();
+        loop {
+            match core.state_store.get_task_status(&task_id).await.unwrap() {
+                TaskStatus::Completed { .. } => break,
+                TaskStatus::Running { .. } => {}
+                other => panic!("status inesperado: {:?}", other),
+            }
+            assert!(std::time::Instant::now() < deadline + Duration::from_secs(5), "tarefa não terminou");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        core.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_sees_tasks_finished_before_the_call() {
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let tasks: Vec<Task> = (0..2)
+            .map(|i| Task::new(format!("pronta-{}", i), TaskDefinition::command("true"), vec![]))
+            .collect();
+        let ids = core.submit_batch(tasks).await.unwrap();
+        // Finalizadas sem evento: só a leitura inicial do store as encontra
+        let now = std::time::SystemTime::now();
+        for id in &ids {
+            core.state_store
+                .update_task_status(id, TaskStatus::Cancelled { cancelled_at: now, reason: CancelReason::UserRequested, note: None })
+                .await
+                .unwrap();
+        }
+
+        let (first, status) = core.wait_any(&[ids[1], ids[0]], Duration::from_millis(10)).await.unwrap();
+        assert_eq!(first, ids[1]);
+        assert!(status.is_final());
+        let all = core.wait_all(&[ids[1], ids[0], ids[1]], Duration::from_millis(10)).await.unwrap();
+        assert_eq!(all.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![ids[1], ids[0]]);
+        assert!(core.wait_all(&[], Duration::ZERO).await.unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_wait_all_mixed_terminal_states() {
+        let core = Arc::new(TaskMeshCore::new(TaskMeshConfig { max_workers: 2, ..TaskMeshConfig::default() }).await.unwrap());
+        core.start().await.unwrap();
+        let task = |name: &str| Task::new(name.to_string(), TaskDefinition::command("true"), vec![]);
+        let completes = task("conclui");
+        let ids = core.submit_batch(vec![completes.clone(), task("falha"), task("cancela")]).await.unwrap();
+
+        let waiter = {
+            let core = core.clone();
+            let ids = ids.clone();
+            tokio::spawn(async move { core.wait_all(&ids, Duration::from_secs(10)).await })
+        };
+        let first = {
+            let core = core.clone();
+            let ids = ids.clone();
+            tokio::spawn(async move { core.wait_any(&ids[1..], Duration::from_secs(10)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Falha com retry agendado não é final
+        let now = std::time::SystemTime::now();
+        let failed = |retry: bool| SystemEvent::new(
+            EventType::TaskFailed,
+            Some(ids[1]),
+            serde_json::json!({ "retry": retry }),
+        ).at(now);
+        core.state_store
+            .update_task_status(&ids[1], TaskStatus::AwaitingRetry { attempt: 1, next_attempt_at: now })
+            .await
+            .unwrap();
+        core.event_bus.publish(failed(true)).await.unwrap();
+        core.cancel_task(&ids[2]).await.unwrap();
+        let (winner, status) = first.await.unwrap().unwrap();
+        assert_eq!(winner, ids[2]);
+        assert!(matches!(status, TaskStatus::Cancelled { .. }), "{:?}", status);
+
+        core.state_store
+            .update_task_status(&ids[1], TaskStatus::Failed { started_at: now, failed_at: now, error: "exit 1".to_string(), retry_count: 1 })
+            .await
+            .unwrap();
+        core.event_bus.publish(failed(false)).await.unwrap();
+        core.scheduler.remove_task(&ids[1]).await;
+        assert_eq!(core.scheduler.get_next_task(&ResourceAllocation::default()).await, Some(ids[0]));
+        core.executor.execute_task(completes).await.unwrap();
+
+        let statuses = waiter.await.unwrap().unwrap();
+        assert_eq!(statuses.iter().map(|(id, _)| *id).collect::<Vec<_>>(), ids);
+        assert!(matches!(statuses[0].1, TaskStatus::Completed { .. }), "{:?}", statuses[0].1);
+        assert!(matches!(statuses[1].1, TaskStatus::Failed { .. }), "{:?}", statuses[1].1);
+        assert!(matches!(statuses[2].1, TaskStatus::Cancelled { .. }), "{:?}", statuses[2].1);
+        core.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_timeout_returns_partial_results() {
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let tasks: Vec<Task> = (0..3)
+            .map(|i| Task::new(format!("parcial-{}", i), TaskDefinition::command("true"), vec![]))
+            .collect();
+        let ids = core.submit_batch(tasks).await.unwrap();
+        core.cancel_task(&ids[1]).await.unwrap();
+
+        let error = core.wait_all(&ids, Duration::from_millis(50)).await.unwrap_err();
+        assert_eq!(error.http_status(), 504);
+        let TaskMeshError::WaitTimeout { completed, pending } = error else { panic!("erro inesperado: {}", error) };
+        assert_eq!(completed.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![ids[1]]);
+        assert_eq!(pending, vec![ids[0], ids[2]]);
+
+        let error = core.wait_any(&[ids[0], ids[2]], Duration::from_millis(20)).await.unwrap_err();
+        assert!(matches!(error, TaskMeshError::WaitTimeout { ref completed, ref pending } if completed.is_empty() && pending.len() == 2), "{}", error);
+
+        // Cancelar o future no meio da espera não deixa assinaturas para trás
+        let subscribers = core.event_bus.stats().subscribers;
+        assert!(tokio::time::timeout(Duration::from_millis(20), core.wait_any(&ids[..1], Duration::from_secs(10))).await.is_err());
+        assert_eq!(core.event_bus.stats().subscribers, subscribers);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_global_hooks_are_rejected_at_construction() {
+        let config = TaskMeshConfig {
+            hooks: vec![hooks::HookSpec::new(hooks::HookPhase::Before, TaskDefinition::Workflow {
+                tasks: vec![],
+                execution_strategy: WorkflowStrategy::Sequential,
+            })],
+            ..TaskMeshConfig::default()
+        };
+        let Err(TaskMeshError::Validation(validation)) = TaskMeshCore::new(config).await else {
+            panic!("hooks globais inválidos foram aceitos")
+        };
+        assert_eq!(validation.violations[0].field, "hooks[0].run");
+    }
+
+    #[tokio::test]
+    async fn test_forced_outcomes_require_opt_in() {
+        let forced = || {
+            Task::new("alerta".to_string(), TaskDefinition::command("true"), vec![])
+                .with_forced_outcome(forced_outcome::ForcedOutcome::new(forced_outcome::ForcedStatus::Timeout))
+        };
+        let core = TaskMeshCore::new(TaskMeshConfig::default()).await.unwrap();
+        let refused = core.submit_task(forced()).await.unwrap_err();
+        assert_eq!(refused.http_status(), 422);
+        assert!(refused.to_string().contains("allow_forced_outcomes"), "{}", refused);
+        assert!(core.list_tasks().await.unwrap().is_empty());
+
+        let core = TaskMeshCore::new(TaskMeshConfig { allow_forced_outcomes: true, ..TaskMeshConfig::default() }).await.unwrap();
+        core.submit_task(forced()).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dispatch_lag_is_measured_per_attempt() {
+        // Um único worker: a segunda tarefa espera a primeira terminar
+        let core = TaskMeshCore::new(TaskMeshConfig { max_workers: 1, strict_durability: true, ..TaskMeshConfig::default() })
+            .await
+            .unwrap();
+        core.start().await.unwrap();
+        let tasks: Vec<Task> = (0..2)
+            .map(|i| Task::new(format!("lenta-{}", i), TaskDefinition::command("sleep 0.3"), vec![]))
+            .collect();
+        let ids = core.submit_batch(tasks.clone()).await.unwrap();
+        for _ in 0..2 {
+            let task_id = core.scheduler.get_next_task(&ResourceAllocation::default()).await.unwrap();
+            let task = tasks.iter().find(|task| task.id == task_id).unwrap().clone();
+            core.executor.execute_task(task).await.unwrap();
+        }
+        core.wait_all(&ids, Duration::from_secs(10)).await.unwrap();
+
+        let dispatch = |attempts: Vec<AttemptRecord>| attempts[0].dispatch.expect("tentativa sem marcos de despacho");
+        let first = dispatch(core.list_attempts(ids[0]).await.unwrap());
+        let second = dispatch(core.list_attempts(ids[1]).await.unwrap());
+        assert!(first.dispatch_lag() < Duration::from_millis(250), "{:?}", first);
+        assert!(second.dispatch_lag() >= Duration::from_millis(250), "{:?}", second);
+        assert!(second.dispatch_lag() < Duration::from_secs(5), "{:?}", second);
+        for times in [first, second] {
+            assert!(times.submitted_at <= times.ready_at && times.ready_at <= times.scheduled_at, "{:?}", times);
+            assert!(times.scheduled_at <= times.started_at, "{:?}", times);
+            assert!(times.scheduler_lag() < Duration::from_millis(250), "{:?}", times);
+        }
+
+        let timeline = core.get_task_timeline(&ids[1]).await.unwrap();
+        let marks: Vec<&DispatchTimes> = timeline
+            .iter()
+            .filter_map(|item| match item {
+                TimelineItem::Dispatch { attempt: 1, times } => Some(times),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(marks, vec![&second]);
+
+        let stats = core.dispatch_latency_stats();
+        assert_eq!(stats.samples, 2);
+        assert!(stats.dispatch_lag_p95.unwrap() >= Duration::from_millis(250), "{:?}", stats);
+        #[cfg(feature = "metrics")]
+        {
+            let registry = prometheus::Registry::new();
+            core.dispatch_latency.register_metrics(&registry).unwrap();
+            let mut output = Vec::new();
+            prometheus::Encoder::encode(&prometheus::TextEncoder::new(), &registry.gather(), &mut output).unwrap();
+            let output = String::from_utf8(output).unwrap();
+            assert!(output.contains("orchestrator_scheduler_lag_seconds_count 2"), "{}", output);
+            assert!(output.contains("orchestrator_dispatch_lag_seconds_count 2"), "{}", output);
+        }
+        core.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reprioritize_changes_dispatch_order_and_lifts_ancestors() {
+        let core = TaskMeshCore::new(TaskMeshConfig { max_workers: 1, strict_durability: true, ..TaskMeshConfig::default() })
+            .await
+            .unwrap();
+        core.start().await.unwrap();
+        core.set_scheduling_heuristic(SchedulingHeuristic::Priority).await.unwrap();
+        let task = |name: &str, priority: Priority, dependencies: Vec<TaskId>| {
+            Task::new(name.to_string(), TaskDefinition::command("true"), dependencies).with_priority(priority)
+        };
+        let fetch = task("fetch", Priority::LOW, vec![]);
+        let report = task("relatorio", Priority::LOW, vec![fetch.id]).with_alias("relatorio");
+        let urgent = task("urgente", Priority::HIGH, vec![]);
+        let ids = core.submit_batch(vec![fetch.clone(), report.clone(), urgent.clone()]).await.unwrap();
+
+        let changed = core.reprioritize(TaskRef::Alias("relatorio".to_string()), Priority::CRITICAL).await.unwrap();
+        assert_eq!(changed, vec![report.id, fetch.id]);
+        assert_eq!(core.state_store.get_task(&fetch.id).await.unwrap().unwrap().priority, Priority::CRITICAL);
+        let bumps: Vec<serde_json::Value> = core.get_task_timeline(&fetch.id).await.unwrap()
+            .into_iter()
+            .filter_map(|item| match item {
+                TimelineItem::Event(event) if event.event_type == EventType::TaskReprioritized => Some(event.data),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(bumps, vec![serde_json::json!({ "from": 25, "to": 100, "inherited_from": report.id })]);
+
+        // O ancestral herdado passa à frente da tarefa urgente
+        let resources = ResourceAllocation::default();
+        let first = core.scheduler.get_next_task(&resources).await.unwrap();
+        assert_eq!(first, fetch.id);
+        core.executor.execute_task(fetch.clone().with_priority(Priority::CRITICAL)).await.unwrap();
+        core.wait_all(&ids[..1], Duration::from_secs(10)).await.unwrap();
+        core.scheduler.report_task_completion(fetch.id, ExecutionMetrics::default()).await;
+        assert_eq!(core.scheduler.get_next_task(&resources).await, Some(report.id));
+        assert_eq!(core.scheduler.get_next_task(&resources).await, Some(urgent.id));
+        assert_eq!(core.scheduler.get_next_task(&resources).await, None);
+
+        let finished = core.reprioritize(fetch.id, Priority::LOW).await.unwrap_err();
+        assert!(matches!(finished, TaskMeshError::InvalidState(_)), "{}", finished);
+        assert_eq!(finished.http_status(), 409);
+        core.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_large_batches_are_chunked_and_bounded() {
+        let config = TaskMeshConfig { max_workflow_tasks: 5, submit_chunk_size: 2, ..TaskMeshConfig::default() };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        let task = |i: usize| Task::new(format!("t{}", i), TaskDefinition::command("true"), vec![]);
+
+        let oversized: Vec<Task> = (0..6).map(task).collect();
+        let error = core.submit_batch(oversized).await.unwrap_err();
+        assert!(matches!(error, TaskMeshError::WorkflowTooLarge { tasks: 6, limit: 5 }), "{}", error);
+        assert_eq!(error.http_status(), 413);
+        assert_eq!(core.queue_depth(), 0);
+        assert!(core.list_tasks().await.unwrap().is_empty());
+
+        // A última tarefa depende da primeira, registrada dois blocos antes
+        let mut tasks: Vec<Task> = (0..5).map(task).collect();
+        tasks[4].dependencies = vec![tasks[0].id];
+        let ids = core.submit_batch(tasks).await.unwrap();
+        assert_eq!(core.queue_depth(), 5);
+        for task_id in &ids {
+            assert_eq!(core.get_task_status(*task_id).await.unwrap(), TaskStatus::Pending);
+        }
+        assert!(core.registry.read().await.get_dependents(&ids[0]).unwrap().contains(&ids[4]));
+
+        let mut dispatched = Vec::new();
+        while let Some(task_id) = core.scheduler.get_next_task(&ResourceAllocation::default()).await {
+            dispatched.push(task_id);
+        }
+        assert_eq!(dispatched.len(), 4);
+        assert!(!dispatched.contains(&ids[4]));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[ignore = "teste de escala (100 mil tarefas): cargo test -- --ignored"]
+    async fn test_hundred_thousand_task_batch_keeps_queries_responsive() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig {
+            database_url: format!("sqlite://{}", dir.path().join("state.db").display()),
+            ..TaskMeshConfig::default()
+        };
+        let core = Arc::new(TaskMeshCore::new(config).await.unwrap());
+        let probe = core
+            .submit_task(Task::new("sonda".to_string(), TaskDefinition::command("true"), vec![]))
+            .await
+            .unwrap();
+        let tasks: Vec<Task> = (0..DEFAULT_MAX_WORKFLOW_TASKS)
+            .map(|i| Task::new(format!("noop-{}", i), TaskDefinition::command("true"), vec![]))
+            .collect();
+
+        // Consultas de status concorrentes durante todo o registro
+        let registering = Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let poller = tokio::spawn({
+            let core = core.clone();
+            let registering = registering.clone();
+            async move {
+                let mut slowest = Duration::ZERO;
+                let mut calls = 0;
+                while registering.load(std::sync::atomic::Ordering::Acquire) {
+                    let started = std::time::Instant::now();
+                    core.get_task_status(probe).await.unwrap();
+                    slowest = slowest.max(started.elapsed());
+                    calls += 1;
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+                (slowest, calls)
+            }
+        });
+
+        let started = std::time::Instant::now();
+        let ids = core.submit_batch(tasks).await.unwrap();
+        let elapsed = started.elapsed();
+        registering.store(false, std::sync::atomic::Ordering::Release);
+        let (slowest, calls) = poller.await.unwrap();
+
+        assert_eq!(ids.len(), DEFAULT_MAX_WORKFLOW_TASKS);
+        assert_eq!(core.queue_depth(), DEFAULT_MAX_WORKFLOW_TASKS + 1);
+        assert!(elapsed < Duration::from_secs(60), "registro levou {:?}", elapsed);
+        assert!(calls > 0);
+        assert!(slowest < Duration::from_millis(50), "consulta mais lenta: {:?} ({} consultas)", slowest, calls);
+    }
+}
@@ -0,0 +1,177 @@
+//! Callbacks do ciclo de vida do core
+//!
+//! Aplicações que embutem o [`TaskMeshCore`](crate::TaskMeshCore) registram
+//! callbacks com [`TaskMeshCore::on_lifecycle`](crate::TaskMeshCore::on_lifecycle)
+//! para agir nas transições do core (ex.: sair do service discovery no
+//! `Draining`) em vez de depender de `Drop`. As fases, em ordem:
+//!
+//! - `Starting`: início de `start()`, antes da verificação prévia;
+//! - `Started`: `start()` concluído (também em modo somente leitura);
+//! - `Draining`: início de `shutdown()`, com o executor ainda ativo;
+//! - `Stopped`: executor, checkpoints e loops de background parados.
+//!
+//! Os callbacks de uma fase rodam em sequência, na ordem de registro, cada
+//! um com até `lifecycle_callback_timeout`. Erros, pânicos e estouros de
+//! tempo são registrados no log e não interrompem a transição. Um callback
+//! registrado depois de `Started` recebe as fases seguintes normalmente.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::units::DurationSpec;
+
+/// Fase do ciclo de vida do core
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecyclePhase {
+    Starting,
+    Started,
+    Draining,
+    Stopped,
+}
+
+impl LifecyclePhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecyclePhase::Starting => "starting",
+            LifecyclePhase::Started => "started",
+            LifecyclePhase::Draining => "draining",
+            LifecyclePhase::Stopped => "stopped",
+        }
+    }
+}
+
+/// Estado do core no momento da transição
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifecycleStats {
+    /// Tempo desde a criação do core
+    pub uptime: Duration,
+    /// Tarefas aguardando despacho no scheduler
+    pub pending_tasks: usize,
+    /// Tarefas enviadas ao executor ainda não concluídas
+    pub queued_tasks: usize,
+    /// Tarefas em execução nos workers
+    pub running_tasks: usize,
+    /// Loops de background vivos
+    pub background_loops: usize,
+}
+
+/// Contexto entregue a cada callback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifecycleContext {
+    pub phase: LifecyclePhase,
+    /// Motivo da transição (ex.: o informado em `shutdown_with_reason`)
+    pub reason: String,
+    pub stats: LifecycleStats,
+}
+
+/// Resultado de um callback
+pub type LifecycleResult = anyhow::Result<()>;
+
+/// Callback registrado, já com o futuro encapsulado
+pub type LifecycleCallback = Arc<dyn Fn(LifecycleContext) -> BoxFuture<'static, LifecycleResult> + Send + Sync>;
+
+/// Tempo padrão de cada callback (`lifecycle_callback_timeout`)
+pub fn default_callback_timeout() -> DurationSpec {
+    DurationSpec::from_secs(5)
+}
+
+/// Callbacks registrados, por fase
+pub struct LifecycleHooks {
+    callbacks: Mutex<Vec<(LifecyclePhase, LifecycleCallback)>>,
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for LifecycleHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let phases: Vec<LifecyclePhase> = self.callbacks.lock().unwrap().iter().map(|(phase, _)| *phase).collect();
+        f.debug_struct("LifecycleHooks").field("callbacks", &phases).field("timeout", &self.timeout).finish()
+    }
+}
+
+impl LifecycleHooks {
+    pub fn new(timeout: Duration) -> Self {
+        Self { callbacks: Mutex::new(Vec::new()), timeout }
+    }
+
+    /// Registra `callback` para `phase`, após os já registrados
+    pub fn register<F, Fut>(&self, phase: LifecyclePhase, callback: F)
+    where
+        F: Fn(LifecycleContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = LifecycleResult> + Send + 'static,
+    {
+        let callback: LifecycleCallback = Arc::new(move |ctx| Box::pin(callback(ctx)));
+        self.callbacks.lock().unwrap().push((phase, callback));
+    }
+
+    /// Executa os callbacks da fase do contexto, em sequência
+    ///
+    /// Retorna o número de callbacks que falharam ou estouraram o tempo.
+    pub async fn run(&self, ctx: LifecycleContext) -> usize {
+        let callbacks: Vec<LifecycleCallback> = self
+            .callbacks
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(phase, _)| *phase == ctx.phase)
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        let phase = ctx.phase.as_str();
+        let mut failures = 0;
+        for (index, callback) in callbacks.into_iter().enumerate() {
+            // Tarefa própria: um pânico no callback não derruba a transição
+            let mut handle = tokio::spawn(callback(ctx.clone()));
+            match tokio::time::timeout(self.timeout, &mut handle).await {
+                Ok(Ok(Ok(()))) => debug!("Callback {} da fase {} concluído", index, phase),
+                Ok(Ok(Err(e))) => {
+                    warn!("Callback {} da fase {} falhou: {:#}", index, phase, e);
+                    failures += 1;
+                }
+                Ok(Err(e)) => {
+                    warn!("Callback {} da fase {} terminou em pânico: {}", index, phase, e);
+                    failures += 1;
+                }
+                Err(_) => {
+                    handle.abort();
+                    warn!("Callback {} da fase {} excedeu {:?} e foi abortado", index, phase, self.timeout);
+                    failures += 1;
+                }
+            }
+        }
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(phase: LifecyclePhase) -> LifecycleContext {
+        LifecycleContext { phase, reason: "teste".to_string(), stats: LifecycleStats::default() }
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_counted_and_do_not_stop_later_callbacks() {
+        let hooks = LifecycleHooks::new(Duration::from_millis(50));
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        hooks.register(LifecyclePhase::Stopped, |_ctx| async { anyhow::bail!("sem conexão") });
+        hooks.register(LifecyclePhase::Stopped, |_ctx| async { panic!("callback com defeito") });
+        hooks.register(LifecyclePhase::Stopped, |_ctx| std::future::pending());
+        let log = calls.clone();
+        hooks.register(LifecyclePhase::Stopped, move |ctx| {
+            let log = log.clone();
+            async move {
+                log.lock().unwrap().push(ctx.reason);
+                Ok(())
+            }
+        });
+        hooks.register(LifecyclePhase::Starting, |_ctx| async { anyhow::bail!("fase errada") });
+
+        assert_eq!(hooks.run(context(LifecyclePhase::Stopped)).await, 3);
+        assert_eq!(*calls.lock().unwrap(), vec!["teste".to_string()]);
+    }
+}
@@ -0,0 +1,260 @@
+//! Armazenamento de logs de tarefas em arquivos
+//!
+//! Cada tarefa tem um arquivo `{dir}/{task_id}.log` com uma linha por linha
+//! de saída, prefixada com timestamp e stream (`stdout`/`stderr`); a saída de
+//! processos auxiliares (sidecars) leva também a seção (`[sidecar:proxy] stdout`). Quando o
+//! arquivo ultrapassa o tamanho máximo ele é rotacionado para
+//! `{task_id}.log.1`, `{task_id}.log.2`, ... mantendo até `max_rotations`.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tracing::{debug, info};
+
+use crate::types::*;
+
+/// Stream de origem de uma linha de log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl LogStream {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+        }
+    }
+}
+
+/// Configuração do armazenamento de logs
+#[derive(Debug, Clone)]
+pub struct LogStoreConfig {
+    /// Diretório dos arquivos de log
+    pub dir: PathBuf,
+    /// Tamanho máximo de um arquivo antes da rotação (bytes)
+    pub max_file_bytes: u64,
+    /// Número de arquivos rotacionados mantidos por tarefa
+    pub max_rotations: usize,
+}
+
+impl LogStoreConfig {
+    /// Configuração padrão para um diretório
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_file_bytes: 64 * 1024 * 1024, // 64MB
+            max_rotations: 3,
+        }
+    }
+}
+
+/// Armazenamento de logs por tarefa
+#[derive(Debug)]
+pub struct LogStore {
+    config: LogStoreConfig,
+}
+
+impl LogStore {
+    /// Cria o armazenamento, garantindo que o diretório exista
+    pub async fn new(config: LogStoreConfig) -> TaskMeshResult<Self> {
+        tokio::fs::create_dir_all(&config.dir).await?;
+        info!("LogStore em {}", config.dir.display());
+        Ok(Self { config })
+    }
+
+    /// Caminho do arquivo de log atual de uma tarefa
+    pub fn path(&self, task_id: &TaskId) -> PathBuf {
+        self.config.dir.join(format!("{}.log", task_id))
+    }
+
+    fn rotated_path(&self, task_id: &TaskId, index: usize) -> PathBuf {
+        self.config.dir.join(format!("{}.log.{}", task_id, index))
+    }
+
+    /// Anexa a saída de um stream ao log da tarefa
+    ///
+    /// Retorna o tamanho do arquivo atual após a escrita.
+    pub async fn append(&self, task_id: &TaskId, stream: LogStream, output: &str) -> TaskMeshResult<u64> {
+        self.append_labeled(task_id, stream.as_str(), output).await
+    }
+
+    /// Anexa a saída de um stream a uma seção do log da tarefa
+    pub async fn append_section(
+        &self,
+        task_id: &TaskId,
+        section: &str,
+        stream: LogStream,
+        output: &str,
+    ) -> TaskMeshResult<u64> {
+        self.append_labeled(task_id, &format!("[{}] {}", section, stream.as_str()), output).await
+    }
+
+    async fn append_labeled(&self, task_id: &TaskId, label: &str, output: &str) -> TaskMeshResult<u64> {
+        if output.is_empty() {
+            return self.len(task_id).await;
+        }
+
+        let path = self.path(task_id);
+        if self.len(task_id).await? >= self.config.max_file_bytes {
+            self.rotate(task_id).await?;
+        }
+
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let mut buffer = String::with_capacity(output.len() + output.len() / 8);
+        for line in output.lines() {
+            buffer.push_str(&timestamp);
+            buffer.push(' ');
+            buffer.push_str(label);
+            buffer.push_str(": ");
+            buffer.push_str(line);
+            buffer.push('\n');
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        file.write_all(buffer.as_bytes()).await?;
+        file.flush().await?;
+
+        Ok(file.metadata().await?.len())
+    }
+
+    /// Rotaciona o arquivo atual, descartando o mais antigo
+    async fn rotate(&self, task_id: &TaskId) -> TaskMeshResult<()> {
+        debug!("Rotacionando log da tarefa {}", task_id);
+
+        if self.config.max_rotations == 0 {
+            return remove_if_exists(&self.path(task_id)).await;
+        }
+
+        remove_if_exists(&self.rotated_path(task_id, self.config.max_rotations)).await?;
+        for index in (1..self.config.max_rotations).rev() {
+            let from = self.rotated_path(task_id, index);
+            if tokio::fs::try_exists(&from).await? {
+                tokio::fs::rename(&from, self.rotated_path(task_id, index + 1)).await?;
+            }
+        }
+        tokio::fs::rename(self.path(task_id), self.rotated_path(task_id, 1)).await?;
+        Ok(())
+    }
+
+    /// Tamanho do arquivo de log atual (0 se inexistente)
+    pub async fn len(&self, task_id: &TaskId) -> TaskMeshResult<u64> {
+        match tokio::fs::metadata(self.path(task_id)).await {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Lê um intervalo de bytes do log atual (limitado ao tamanho do arquivo)
+    pub async fn read(&self, task_id: &TaskId, range: Range<u64>) -> TaskMeshResult<Vec<u8>> {
+        let len = self.len(task_id).await?;
+        let start = range.start.min(len);
+        let end = range.end.min(len).max(start);
+
+        let mut buffer = vec![0u8; (end - start) as usize];
+        if buffer.is_empty() {
+            return Ok(buffer);
+        }
+
+        let mut file = tokio::fs::File::open(self.path(task_id)).await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        file.read_exact(&mut buffer).await?;
+        Ok(buffer)
+    }
+
+    /// Remove todos os arquivos de log de uma tarefa
+    pub async fn remove(&self, task_id: &TaskId) -> TaskMeshResult<()> {
+        remove_if_exists(&self.path(task_id)).await?;
+        for index in 1..=self.config.max_rotations {
+            remove_if_exists(&self.rotated_path(task_id, index)).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove arquivos de log não modificados dentro do período de retenção
+    pub async fn cleanup(&self, retention_days: u32) -> TaskMeshResult<usize> {
+        let cutoff = SystemTime::now() - Duration::from_secs(retention_days as u64 * 24 * 60 * 60);
+        let mut removed = 0;
+
+        let mut entries = tokio::fs::read_dir(&self.config.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let is_log = entry.file_name().to_string_lossy().contains(".log");
+            let modified = entry.metadata().await?.modified()?;
+            if is_log && modified < cutoff {
+                tokio::fs::remove_file(entry.path()).await?;
+                removed += 1;
+            }
+        }
+
+        info!("Limpeza de logs: {} arquivos removidos", removed);
+        Ok(removed)
+    }
+}
+
+async fn remove_if_exists(path: &Path) -> TaskMeshResult<()> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_append_and_ranged_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LogStore::new(LogStoreConfig::new(dir.path())).await.unwrap();
+        let task_id = TaskId::new_v4();
+
+        store.append(&task_id, LogStream::Stdout, "linha 1\nlinha 2\n").await.unwrap();
+        let len = store.append(&task_id, LogStream::Stderr, "erro").await.unwrap();
+
+        let content = String::from_utf8(store.read(&task_id, 0..len).await.unwrap()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with(" stdout: linha 1"));
+        assert!(lines[2].ends_with(" stderr: erro"));
+
+        // Intervalo parcial e intervalo além do fim
+        let tail = store.read(&task_id, len - 5..len + 100).await.unwrap();
+        assert_eq!(tail, b"erro\n");
+        assert!(store.read(&task_id, len + 10..len + 20).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = LogStoreConfig {
+            max_file_bytes: 100,
+            max_rotations: 2,
+            ..LogStoreConfig::new(dir.path())
+        };
+        let store = LogStore::new(config).await.unwrap();
+        let task_id = TaskId::new_v4();
+
+        for _ in 0..5 {
+            store.append(&task_id, LogStream::Stdout, &"x".repeat(120)).await.unwrap();
+        }
+
+        assert!(store.path(&task_id).exists());
+        assert!(store.rotated_path(&task_id, 1).exists());
+        assert!(store.rotated_path(&task_id, 2).exists());
+        assert!(!store.rotated_path(&task_id, 3).exists());
+
+        store.remove(&task_id).await.unwrap();
+        assert!(!store.path(&task_id).exists());
+        assert!(!store.rotated_path(&task_id, 1).exists());
+    }
+}
@@ -0,0 +1,245 @@
+//! Configuração de logging
+//!
+//! [`init_logging`] instala o subscriber global em formato texto ou JSON,
+//! escrevendo no stdout ou em arquivos rotacionados. Executor e scheduler
+//! abrem spans `task` com `task_id`, `namespace`, `trace_id` e `worker_id`
+//! (ver [`task_span`]), de modo que cada linha emitida durante o ciclo de
+//! vida de uma tarefa carrega seus campos de correlação.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+use crate::alias;
+use crate::types::*;
+
+/// Metadado com o trace de origem da tarefa (padrão: o próprio ID)
+pub const TRACE_ID_KEY: &str = "trace_id";
+
+/// Formato das linhas de log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// Um objeto JSON por linha
+    Json,
+}
+
+/// Rotação dos arquivos de log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+impl LogRotation {
+    fn appender(self) -> tracing_appender::rolling::Rotation {
+        use tracing_appender::rolling::Rotation;
+        match self {
+            LogRotation::Minutely => Rotation::MINUTELY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        }
+    }
+}
+
+/// Configuração de logging
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    /// Diretivas de filtro (sintaxe de `RUST_LOG`, ex.: `info,task_mesh_core=debug`)
+    pub level: String,
+    /// Inclui o span atual e a lista de spans em cada linha JSON
+    pub include_spans: bool,
+    /// Arquivo de log; `None` escreve no stdout
+    pub file: Option<PathBuf>,
+    /// Rotação do arquivo (ignorada sem `file`)
+    pub rotation: LogRotation,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Text,
+            level: std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            include_spans: true,
+            file: None,
+            rotation: LogRotation::Daily,
+        }
+    }
+}
+
+impl LogConfig {
+    fn filter(&self) -> TaskMeshResult<EnvFilter> {
+        EnvFilter::try_new(&self.level).map_err(|e| {
+            TaskMeshError::Configuration(format!("Nível de log inválido '{}': {}", self.level, e))
+        })
+    }
+}
+
+/// Filtro recarregável do subscriber global
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Inicializa o logging global
+///
+/// Idempotente: chamadas seguintes apenas trocam o filtro pelo `level`
+/// informado; formato e destino ficam os da primeira chamada.
+pub fn init_logging(config: LogConfig) -> TaskMeshResult<()> {
+    let filter = config.filter()?;
+    if let Some(handle) = FILTER_HANDLE.get() {
+        return handle
+            .reload(filter)
+            .map_err(|e| TaskMeshError::Configuration(format!("Erro ao recarregar filtro de log: {}", e)));
+    }
+
+    let (filter, handle) = reload::Layer::new(filter);
+    let output = match &config.file {
+        Some(path) => {
+            let directory = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+            let prefix = path.file_name().ok_or_else(|| {
+                TaskMeshError::Configuration(format!("Arquivo de log inválido: {}", path.display()))
+            })?;
+            let appender = tracing_appender::rolling::RollingFileAppender::new(config.rotation.appender(), directory, prefix);
+            format_layer(&config, appender)
+        }
+        None => format_layer(&config, std::io::stdout),
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(output)
+        .try_init()
+        .map_err(|e| TaskMeshError::Configuration(format!("Subscriber de log já instalado: {}", e)))?;
+    // Corrida entre duas primeiras chamadas é barrada pelo `try_init`
+    let _ = FILTER_HANDLE.set(handle);
+    Ok(())
+}
+
+/// Camada de formatação de `config` escrevendo em `writer`
+pub fn format_layer<S, W>(config: &LogConfig, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match config.format {
+        LogFormat::Text => fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .with_current_span(config.include_spans)
+            .with_span_list(config.include_spans)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+/// Trace de correlação da tarefa
+pub fn trace_id(task: &Task) -> String {
+    task.metadata.get(TRACE_ID_KEY).cloned().unwrap_or_else(|| task.id.to_string())
+}
+
+/// Span com os campos de correlação da tarefa
+///
+/// `worker_id` fica vazio até a tarefa ser atribuída a um worker.
+pub fn task_span(task: &Task) -> tracing::Span {
+    tracing::info_span!(
+        "task",
+        task_id = %task.id,
+        task_name = %task.name,
+        namespace = %alias::namespace_of(task),
+        trace_id = %trace_id(task),
+        worker_id = tracing::field::Empty,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    /// Writer que acumula as linhas em memória
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_carry_task_id_through_lifecycle() {
+        let captured = Captured::default();
+        let config = LogConfig { format: LogFormat::Json, level: "task_mesh_core=debug".to_string(), ..LogConfig::default() };
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry()
+            .with(config.filter().unwrap())
+            .with(format_layer(&config, move || writer.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let core = TaskMeshCore::new(TaskMeshConfig { max_workers: 1, ..TaskMeshConfig::default() }).await.unwrap();
+        core.start().await.unwrap();
+        let mut task = Task::new("logged".to_string(), TaskDefinition::command("true"), vec![]);
+        task.metadata.insert(TRACE_ID_KEY.to_string(), "trace-42".to_string());
+        let task_id = core.submit_task(task).await.unwrap();
+        assert_eq!(core.scheduler.get_next_task(&ResourceAllocation::default()).await, Some(task_id));
+        let task = core.registry.read().await.get_task(&task_id).cloned().unwrap();
+        core.executor.execute_task(task).await.unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !matches!(core.get_task_status(&task_id).await.unwrap(), TaskStatus::Completed { .. }) {
+            assert!(Instant::now() < deadline, "tarefa não concluiu");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        core.shutdown().await.unwrap();
+
+        let lines = captured.lines();
+        for stage in ["Agendando tarefa", "submetida", "Próxima tarefa selecionada", "Executando tarefa", "concluída com sucesso"] {
+            let line = lines
+                .iter()
+                .find(|line| line["fields"]["message"].as_str().is_some_and(|message| message.contains(stage)))
+                .unwrap_or_else(|| panic!("nenhuma linha para '{}'", stage));
+            let text = line.to_string();
+            assert!(text.contains(&format!("\"task_id\":\"{}\"", task_id)), "{}: {}", stage, text);
+        }
+        let completed = lines
+            .iter()
+            .find(|line| line["fields"]["message"].as_str().is_some_and(|message| message.contains("concluída com sucesso")))
+            .unwrap();
+        assert_eq!(completed["span"]["trace_id"], "trace-42");
+        assert!(completed["span"]["worker_id"].as_str().is_some_and(|worker| !worker.is_empty()));
+    }
+
+    #[test]
+    fn test_invalid_level_is_configuration_error() {
+        let config = LogConfig { level: "task_mesh_core=barulhento".to_string(), ..LogConfig::default() };
+        assert!(matches!(init_logging(config), Err(TaskMeshError::Configuration(_))));
+    }
+}
@@ -0,0 +1,206 @@
+//! Pausa de despacho e janelas de manutenção
+//!
+//! O [`DispatchGate`] decide se o scheduler pode despachar uma tarefa. O
+//! despacho fica suspenso enquanto houver uma pausa manual
+//! ([`crate::TaskMeshCore::pause_dispatch`]) ou uma [`MaintenanceWindow`]
+//! ativa cujo escopo cubra a tarefa. Submissões continuam aceitas e tarefas
+//! em execução terminam normalmente; apenas o despacho é retido.
+
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+use crate::validation;
+
+/// Configuração persistente com a pausa manual (JSON de [`DispatchPause`])
+pub const DISPATCH_PAUSE_SETTING: &str = "dispatch_pause";
+
+/// Tarefas afetadas por uma pausa
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DispatchScope {
+    All,
+    /// Tarefas com qualquer uma das tags
+    Tags(Vec<String>),
+}
+
+impl DispatchScope {
+    pub(crate) fn covers(&self, tags: &[String]) -> bool {
+        match self {
+            DispatchScope::All => true,
+            DispatchScope::Tags(held) => held.iter().any(|tag| tags.contains(&validation::normalize_tag(tag))),
+        }
+    }
+}
+
+/// Janela de manutenção recorrente
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// Início da janela: expressão cron com segundos, em UTC
+    pub cron_start: String,
+    /// Duração da janela (ms)
+    pub duration_ms: u64,
+    pub scope: DispatchScope,
+}
+
+impl MaintenanceWindow {
+    fn schedule(&self) -> TaskMeshResult<cron::Schedule> {
+        cron::Schedule::from_str(&self.cron_start).map_err(|e| {
+            TaskMeshError::Configuration(format!("Cron de manutenção inválido '{}': {}", self.cron_start, e))
+        })
+    }
+
+    pub fn validate(&self) -> TaskMeshResult<()> {
+        self.schedule().map(|_| ())
+    }
+
+    /// A janela está aberta em `now` (começou há menos de `duration_ms`)
+    pub fn is_active_at(&self, now: SystemTime) -> TaskMeshResult<bool> {
+        let opened_after = now.checked_sub(Duration::from_millis(self.duration_ms)).unwrap_or(SystemTime::UNIX_EPOCH);
+        Ok(self
+            .schedule()?
+            .after(&DateTime::<Utc>::from(opened_after))
+            .next()
+            .is_some_and(|start| SystemTime::from(start) <= now))
+    }
+}
+
+/// Pausa manual do despacho
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DispatchPause {
+    pub reason: String,
+    pub paused_at: SystemTime,
+    pub scope: DispatchScope,
+}
+
+/// Decide quais tarefas podem ser despachadas
+#[derive(Debug, Default)]
+pub struct DispatchGate {
+    pause: RwLock<Option<DispatchPause>>,
+    windows: RwLock<Vec<MaintenanceWindow>>,
+}
+
+impl DispatchGate {
+    /// Substitui as janelas de manutenção, validando as expressões cron
+    pub fn set_windows(&self, windows: Vec<MaintenanceWindow>) -> TaskMeshResult<()> {
+        windows.iter().try_for_each(MaintenanceWindow::validate)?;
+        *self.windows.write().unwrap() = windows;
+        Ok(())
+    }
+
+    /// Aplica uma pausa manual, substituindo a anterior
+    pub fn pause(&self, pause: DispatchPause) {
+        *self.pause.write().unwrap() = Some(pause);
+    }
+
+    /// Remove a pausa manual, retornando-a
+    pub fn resume(&self) -> Option<DispatchPause> {
+        self.pause.write().unwrap().take()
+    }
+
+    pub fn current_pause(&self) -> Option<DispatchPause> {
+        self.pause.read().unwrap().clone()
+    }
+
+    /// Motivo pelo qual uma tarefa com `tags` está retida em `now`
+    pub fn held(&self, tags: &[String], now: SystemTime) -> Option<String> {
+        if let Some(pause) = self.pause.read().unwrap().as_ref() {
+            if pause.scope.covers(tags) {
+                return Some(pause.reason.clone());
+            }
+        }
+        self.active_windows(now)
+            .into_iter()
+            .find(|window| window.scope.covers(tags))
+            .map(|window| format!("janela de manutenção '{}'", window.cron_start))
+    }
+
+    /// Janelas abertas em `now`
+    pub fn active_windows(&self, now: SystemTime) -> Vec<MaintenanceWindow> {
+        self.windows
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|window| window.is_active_at(now).unwrap_or(false))
+            .cloned()
+            .collect()
+    }
+
+    /// Descrição das pausas em vigor (`None` = despacho livre)
+    pub fn describe(&self, now: SystemTime) -> Option<String> {
+        let mut reasons: Vec<String> = self
+            .current_pause()
+            .map(|pause| format!("pausa manual ({:?}): {}", pause.scope, pause.reason))
+            .into_iter()
+            .collect();
+        reasons.extend(
+            self.active_windows(now)
+                .iter()
+                .map(|window| format!("janela de manutenção '{}' ({:?})", window.cron_start, window.scope)),
+        );
+        (!reasons.is_empty()).then(|| reasons.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::{components, HealthStatus};
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    fn tagged_task(name: &str, tags: &[&str]) -> Task {
+        let mut task = Task::new(name.to_string(), TaskDefinition::command("true"), vec![]);
+        task.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        task
+    }
+
+    #[tokio::test]
+    async fn test_pause_holds_dispatch_across_restart_until_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig {
+            database_url: format!("sqlite://{}", dir.path().join("state.db").display()),
+            ..TaskMeshConfig::default()
+        };
+        let resources = ResourceAllocation::default();
+
+        let core = TaskMeshCore::new(config.clone()).await.unwrap();
+        core.pause_dispatch("migração do banco").await.unwrap();
+        core.submit_task(tagged_task("held", &[])).await.unwrap();
+        assert_eq!(core.scheduler.get_next_task(&resources).await, None);
+        drop(core);
+
+        let core = TaskMeshCore::new(config).await.unwrap();
+        let task_id = core.submit_task(tagged_task("after-restart", &[])).await.unwrap();
+        assert_eq!(core.scheduler.get_next_task(&resources).await, None);
+        let dispatch = core.health().await.component(components::DISPATCH).cloned().unwrap();
+        assert_eq!(dispatch.status, HealthStatus::Degraded);
+        assert!(dispatch.detail.contains("migração do banco"));
+
+        core.resume_dispatch().await.unwrap();
+        assert_eq!(core.scheduler.get_next_task(&resources).await, Some(task_id));
+        assert_eq!(core.health().await.component(components::DISPATCH).unwrap().status, HealthStatus::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_window_holds_only_tagged_tasks() {
+        let window = MaintenanceWindow {
+            cron_start: "* * * * * *".to_string(),
+            duration_ms: 60_000,
+            scope: DispatchScope::Tags(vec!["db".to_string()]),
+        };
+        assert!(window.is_active_at(SystemTime::now()).unwrap());
+
+        let config = TaskMeshConfig { maintenance_windows: vec![window], ..TaskMeshConfig::default() };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        core.submit_task(tagged_task("db-backup", &["db"])).await.unwrap();
+        let free = core.submit_task(tagged_task("report", &["web"])).await.unwrap();
+
+        let resources = ResourceAllocation::default();
+        assert_eq!(core.scheduler.get_next_task(&resources).await, Some(free));
+        assert_eq!(core.scheduler.get_next_task(&resources).await, None);
+        assert_eq!(core.queue_depth(), 1);
+    }
+}
@@ -0,0 +1,315 @@
+//! Manifesto de reprodutibilidade de cada execução
+//!
+//! No início de cada tentativa o executor registra o que exatamente vai
+//! rodar: argv resolvido, diretório de trabalho, hash do corpo do script,
+//! nomes das variáveis de ambiente relevantes (valores nunca são gravados),
+//! versões de interpretadores, versão do orquestrador, dados do host e a
+//! origem das tags e metadados herdados de padrões ([`crate::defaults`]). As
+//! versões de ferramentas são consultadas uma vez por worker host e ficam em
+//! cache ([`ToolVersions`]).
+
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::defaults::{self, DefaultsProvenance};
+use crate::types::*;
+
+/// Versão do orquestrador registrada nos manifestos
+pub const ORCHESTRATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Tempo máximo de espera por `<ferramenta> --version`
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Prefixo das variáveis injetadas pelo próprio executor
+const TASKMESH_ENV_PREFIX: &str = "TASKMESH_";
+
+/// Host e worker que executaram a tentativa
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostInfo {
+    pub hostname: String,
+    pub os: String,
+    pub arch: String,
+    pub cpus: usize,
+    pub worker_id: String,
+}
+
+impl HostInfo {
+    /// Host atual, para o worker informado
+    pub fn current(worker_id: &str) -> Self {
+        Self {
+            hostname: hostname().to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: num_cpus::get(),
+            worker_id: worker_id.to_string(),
+        }
+    }
+}
+
+/// Manifesto de uma tentativa de execução
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionManifest {
+    pub task_id: TaskId,
+    /// Tentativa (a partir de 1)
+    pub attempt: u32,
+    pub captured_at: SystemTime,
+    /// Tipo da definição (`command`, `exec`, `python_script`, ...)
+    pub kind: String,
+    /// Programa e argumentos como passados ao processo (`<script>` marca o
+    /// arquivo temporário do script Python)
+    pub argv: Vec<String>,
+    pub working_directory: String,
+    /// SHA-256 (hex) do corpo do script, para scripts Python
+    pub script_sha256: Option<String>,
+    /// Nomes das variáveis definidas pela tarefa ou pelo executor
+    pub env_vars: Vec<String>,
+    /// Versão reportada por interpretadores e shells usados
+    pub tool_versions: BTreeMap<String, String>,
+    pub orchestrator_version: String,
+    pub host: HostInfo,
+    /// Nice aplicado ao processo
+    #[serde(default)]
+    pub nice: Option<i8>,
+    /// Classe de IO aplicada ao processo
+    #[serde(default)]
+    pub ionice_class: Option<IoClass>,
+    /// Núcleos aos quais o processo ficou restrito
+    #[serde(default)]
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Origem das tags e metadados herdados de padrões (ver [`crate::defaults`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defaults_sources: Option<DefaultsProvenance>,
+}
+
+impl ExecutionManifest {
+    /// Manifesto da definição, sem versões de ferramentas
+    ///
+    /// `python_interpreter` e `platform` são os que o executor usará. Do
+    /// ambiente do contexto só entram os nomes das variáveis do executor
+    /// (`TASKMESH_*`); as da definição vêm da própria tarefa.
+    pub fn capture(
+        task: &Task,
+        attempt: u32,
+        context: &ExecutionContext,
+        platform: crate::executor::TargetPlatform,
+        python_interpreter: &str,
+    ) -> Self {
+        let mut env_vars: Vec<String> = context
+            .environment
+            .keys()
+            .filter(|name| name.starts_with(TASKMESH_ENV_PREFIX))
+            .cloned()
+            .collect();
+        let mut script_sha256 = None;
+        let (kind, argv) = match &task.definition {
+            TaskDefinition::Command { command, shell: None } => {
+                let (program, args) = platform.shell_command(command);
+                ("command", std::iter::once(program.to_string()).chain(args).collect())
+            }
+            TaskDefinition::Command { command, shell: Some(shell) } => {
+                let mut argv = vec![shell.clone()];
+                argv.extend(crate::executor::shell_args(shell).iter().map(|arg| arg.to_string()));
+                argv.push(command.clone());
+                ("command", argv)
+            }
+            TaskDefinition::Exec { program, args } => {
+                let resolved = crate::executor::resolve_program(program)
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_else(|| program.clone());
+                ("exec", std::iter::once(resolved).chain(args.iter().cloned()).collect())
+            }
+            TaskDefinition::PythonScript { script, args, env } => {
+                script_sha256 = Some(sha256_hex(script.as_bytes()));
+                env_vars.extend(env.keys().cloned());
+                let argv = [python_interpreter.to_string(), "<script>".to_string()]
+                    .into_iter()
+                    .chain(args.iter().cloned())
+                    .collect();
+                ("python_script", argv)
+            }
+            TaskDefinition::RustFunction { function_name, .. } => ("rust_function", vec![function_name.clone()]),
+            TaskDefinition::HttpRequest { method, url, .. } => ("http_request", vec![method.clone(), url.clone()]),
+            TaskDefinition::Workflow { .. } => ("workflow", Vec::new()),
+            TaskDefinition::Generator { .. } => ("generator", Vec::new()),
+        };
+        env_vars.sort();
+        env_vars.dedup();
+        let (nice, ionice_class, cpu_affinity) = crate::process_controls::supported(&context.allocated_resources);
+
+        Self {
+            task_id: task.id,
+            attempt,
+            captured_at: SystemTime::now(),
+            kind: kind.to_string(),
+            argv,
+            working_directory: context.working_directory.clone(),
+            script_sha256,
+            env_vars,
+            tool_versions: BTreeMap::new(),
+            orchestrator_version: ORCHESTRATOR_VERSION.to_string(),
+            host: HostInfo::current(&context.worker_id),
+            nice,
+            ionice_class,
+            cpu_affinity,
+            defaults_sources: defaults::provenance(task),
+        }
+    }
+}
+
+/// Ferramenta cuja versão vale registrar: o interpretador Python ou o shell
+/// escolhido pela tarefa
+///
+/// Programas de `Exec` não são sondados, pois `--version` pode não ser
+/// inofensivo para eles.
+pub fn probed_tool<'a>(definition: &'a TaskDefinition, python_interpreter: &'a str) -> Option<&'a str> {
+    match definition {
+        TaskDefinition::PythonScript { .. } => Some(python_interpreter),
+        TaskDefinition::Command { shell: Some(shell), .. } => Some(shell),
+        _ => None,
+    }
+}
+
+/// SHA-256 (hex) de um conteúdo
+pub fn sha256_hex(data: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, data)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Nome do host, lido uma única vez
+fn hostname() -> &'static str {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+    HOSTNAME.get_or_init(|| {
+        std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .ok()
+            .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    })
+}
+
+/// Cache de versões de ferramentas do worker host
+///
+/// Cada ferramenta é sondada com `--version` no máximo uma vez (falhas também
+/// ficam em cache).
+#[derive(Debug, Default)]
+pub struct ToolVersions {
+    versions: DashMap<String, Option<String>>,
+}
+
+impl ToolVersions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Versão de `program` (primeira linha não vazia de `--version`)
+    pub async fn version(&self, program: &str) -> Option<String> {
+        if let Some(version) = self.versions.get(program) {
+            return version.clone();
+        }
+        let version = probe_version(program).await;
+        self.versions.insert(program.to_string(), version.clone());
+        version
+    }
+
+    /// Quantidade de ferramentas já sondadas
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+}
+
+/// Executa `program --version`; versões antigas do Python escrevem em stderr
+async fn probe_version(program: &str) -> Option<String> {
+    let output = Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(VERSION_PROBE_TIMEOUT, output).await.ok()?.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    [output.stdout, output.stderr]
+        .iter()
+        .flat_map(|stream| String::from_utf8_lossy(stream).lines().map(str::to_string).collect::<Vec<_>>())
+        .map(|line| line.trim().to_string())
+        .find(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use crate::executor::TargetPlatform;
+
+    fn context() -> ExecutionContext {
+        ExecutionContext {
+            worker_id: "worker-0".to_string(),
+            working_directory: "/srv/jobs".to_string(),
+            environment: HashMap::from([
+                ("PATH".to_string(), "/usr/bin".to_string()),
+                ("TASKMESH_CHECKPOINT_ID".to_string(), "task-1".to_string()),
+                ("API_TOKEN".to_string(), "s3cr3t".to_string()),
+            ]),
+            allocated_resources: ResourceAllocation::default(),
+            checkpoint_id: None,
+            progress: None,
+        }
+    }
+
+    #[test]
+    fn test_capture_keeps_env_names_without_values() {
+        let definition = TaskDefinition::PythonScript {
+            script: Arc::from("print('oi')"),
+            args: vec!["--dia".to_string()],
+            env: HashMap::from([("API_TOKEN".to_string(), "s3cr3t".to_string())]),
+        };
+        let task = Task::new("script".to_string(), definition, vec![]);
+        let manifest = ExecutionManifest::capture(&task, 2, &context(), TargetPlatform::Unix, "python3");
+
+        assert_eq!(manifest.argv, vec!["python3", "<script>", "--dia"]);
+        assert_eq!(manifest.script_sha256, Some(sha256_hex(b"print('oi')")));
+        assert_eq!(manifest.env_vars, vec!["API_TOKEN", "TASKMESH_CHECKPOINT_ID"]);
+        assert_eq!(probed_tool(&task.definition, "python3"), Some("python3"));
+        assert!(!serde_json::to_string(&manifest).unwrap().contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_capture_records_process_controls() {
+        let mut context = context();
+        context.allocated_resources.nice = Some(10);
+        context.allocated_resources.cpu_affinity = Some(vec![0]);
+        let task = Task::new("cmd".to_string(), TaskDefinition::command("echo oi"), vec![]);
+        let manifest = ExecutionManifest::capture(&task, 1, &context, TargetPlatform::Unix, "python3");
+
+        assert_eq!(manifest.nice, Some(10));
+        assert_eq!(manifest.cpu_affinity, cfg!(target_os = "linux").then(|| vec![0]));
+        assert_eq!(manifest.ionice_class, None);
+    }
+
+    #[test]
+    fn test_only_interpreters_and_chosen_shells_are_probed() {
+        let task = Task::new("cmd".to_string(), TaskDefinition::command("echo oi"), vec![]);
+        let manifest = ExecutionManifest::capture(&task, 1, &context(), TargetPlatform::Unix, "python3");
+        assert_eq!(manifest.argv, vec!["sh", "-c", "echo oi"]);
+        assert_eq!(probed_tool(&task.definition, "python3"), None);
+
+        let definition = TaskDefinition::Exec { program: "deploy".to_string(), args: vec![] };
+        assert_eq!(probed_tool(&definition, "python3"), None);
+        assert_eq!(probed_tool(&TaskDefinition::shell_command("bash", "echo oi"), "python3"), Some("bash"));
+    }
+}
@@ -0,0 +1,933 @@
+//! Migrações versionadas de schema dos StateStores
+//!
+//! Cada backend possui uma lista ordenada de migrações. A versão aplicada
+//! fica registrada na tabela `schema_version`; na inicialização todas as
+//! migrações pendentes são aplicadas em ordem, cada uma em sua transação.
+//! Bancos criados antes deste módulo (sem `schema_version`) são tratados
+//! como versão 0 — a migração 1 usa apenas `CREATE ... IF NOT EXISTS` e
+//! portanto preserva os dados existentes.
+
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row, SqlitePool};
+use tracing::{debug, info};
+
+use crate::types::*;
+
+/// Migração de schema
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Versão (sequencial, começando em 1)
+    pub version: u32,
+    /// Descrição curta
+    pub description: &'static str,
+    /// Comandos SQL executados em ordem
+    pub statements: &'static [&'static str],
+}
+
+/// Resultado de uma verificação ou execução de migrações
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Versão do banco antes da execução
+    pub current_version: u32,
+    /// Versão mais recente conhecida pelo código
+    pub target_version: u32,
+    /// Migrações pendentes (versão, descrição)
+    pub pending: Vec<(u32, String)>,
+    /// Se as migrações pendentes foram aplicadas
+    pub applied: bool,
+}
+
+impl MigrationReport {
+    /// Indica se o banco já está na versão mais recente
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Migrações do backend SQLite
+pub const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "schema inicial",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS tasks (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                definition TEXT NOT NULL,
+                dependencies TEXT NOT NULL,
+                priority INTEGER NOT NULL,
+                metadata TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                timeout_ms INTEGER,
+                max_retries INTEGER NOT NULL,
+                tags TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS task_status (
+                task_id TEXT PRIMARY KEY,
+                status_type TEXT NOT NULL,
+                status_data TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (task_id) REFERENCES tasks (id)
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                event_type TEXT NOT NULL,
+                task_id TEXT,
+                data TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS metrics (
+                task_id TEXT PRIMARY KEY,
+                execution_time_ms INTEGER NOT NULL,
+                cpu_usage REAL NOT NULL,
+                memory_usage INTEGER NOT NULL,
+                network_io_read INTEGER NOT NULL,
+                network_io_write INTEGER NOT NULL,
+                disk_io_read INTEGER NOT NULL,
+                disk_io_write INTEGER NOT NULL,
+                recorded_at INTEGER NOT NULL,
+                FOREIGN KEY (task_id) REFERENCES tasks (id)
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS checkpoints (
+                id TEXT PRIMARY KEY,
+                data BLOB NOT NULL,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_task_status_type ON task_status (status_type)",
+            "CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events (timestamp)",
+            "CREATE INDEX IF NOT EXISTS idx_events_task_id ON events (task_id)",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_created_at ON tasks (created_at)",
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "histórico de status",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS task_status_history (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                task_id TEXT NOT NULL,
+                status_type TEXT NOT NULL,
+                status_data TEXT NOT NULL,
+                changed_at INTEGER NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_status_history_task ON task_status_history (task_id, seq)",
+            "CREATE INDEX IF NOT EXISTS idx_status_history_changed_at ON task_status_history (changed_at)",
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "checksum de checkpoints",
+        statements: &[
+            "ALTER TABLE checkpoints ADD COLUMN checksum TEXT",
+            "ALTER TABLE checkpoints ADD COLUMN payload_len INTEGER",
+        ],
+    },
+    Migration {
+        version: 4,
+        description: "status e métricas sem chave estrangeira",
+        // O executor registra status e métricas de tarefas que não foram
+        // persistidas em `tasks`; SQLite só remove a FK recriando a tabela
+        statements: &[
+            r#"
+            CREATE TABLE task_status_v4 (
+                task_id TEXT PRIMARY KEY,
+                status_type TEXT NOT NULL,
+                status_data TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )
+            "#,
+            "INSERT INTO task_status_v4 SELECT task_id, status_type, status_data, updated_at FROM task_status",
+            "DROP TABLE task_status",
+            "ALTER TABLE task_status_v4 RENAME TO task_status",
+            "CREATE INDEX IF NOT EXISTS idx_task_status_type ON task_status (status_type)",
+            r#"
+            CREATE TABLE metrics_v4 (
+                task_id TEXT PRIMARY KEY,
+                execution_time_ms INTEGER NOT NULL,
+                cpu_usage REAL NOT NULL,
+                memory_usage INTEGER NOT NULL,
+                network_io_read INTEGER NOT NULL,
+                network_io_write INTEGER NOT NULL,
+                disk_io_read INTEGER NOT NULL,
+                disk_io_write INTEGER NOT NULL,
+                recorded_at INTEGER NOT NULL
+            )
+            "#,
+            "INSERT INTO metrics_v4 SELECT * FROM metrics",
+            "DROP TABLE metrics",
+            "ALTER TABLE metrics_v4 RENAME TO metrics",
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "registro de modelos",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS models (
+                name TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                blob BLOB NOT NULL,
+                metrics TEXT NOT NULL,
+                promoted INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (name, version)
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "aliases de tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN alias TEXT",
+            r#"
+            CREATE TABLE IF NOT EXISTS task_aliases (
+                namespace TEXT NOT NULL,
+                alias TEXT NOT NULL,
+                task_id TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (namespace, alias)
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_task_aliases_task ON task_aliases(task_id)",
+        ],
+    },
+    Migration {
+        version: 7,
+        description: "configurações persistentes",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 8,
+        description: "grupos de tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN group_id TEXT",
+            "ALTER TABLE tasks ADD COLUMN group_name TEXT",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_group ON tasks (group_id)",
+        ],
+    },
+    Migration {
+        version: 9,
+        description: "busca textual de tarefas",
+        statements: &[
+            // Rowid estável por tarefa (o de `tasks` muda a cada INSERT OR REPLACE)
+            r#"
+            CREATE TABLE IF NOT EXISTS task_search_rows (
+                id INTEGER PRIMARY KEY,
+                task_id TEXT NOT NULL UNIQUE
+            )
+            "#,
+            "CREATE VIRTUAL TABLE IF NOT EXISTS tasks_fts USING fts5 (name, metadata)",
+            "INSERT OR IGNORE INTO task_search_rows (task_id) SELECT id FROM tasks",
+            r#"
+            INSERT INTO tasks_fts (rowid, name, metadata)
+            SELECT search.id, tasks.name,
+                   (SELECT COALESCE(group_concat(value, ' '), '') FROM json_each(tasks.metadata))
+            FROM tasks JOIN task_search_rows search ON search.task_id = tasks.id
+            "#,
+        ],
+    },
+    Migration {
+        version: 10,
+        description: "manifestos de execução",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS execution_manifests (
+                task_id TEXT NOT NULL,
+                attempt INTEGER NOT NULL,
+                manifest TEXT NOT NULL,
+                captured_at INTEGER NOT NULL,
+                PRIMARY KEY (task_id, attempt)
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 11,
+        description: "tentativas de execução",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS task_attempts (
+                task_id TEXT NOT NULL,
+                attempt INTEGER NOT NULL,
+                worker_id TEXT,
+                started_at INTEGER NOT NULL,
+                status_type TEXT NOT NULL,
+                status_data TEXT NOT NULL,
+                result TEXT,
+                metrics TEXT,
+                PRIMARY KEY (task_id, attempt)
+            )
+            "#,
+            // Tarefas já iniciadas ganham a tentativa 1 a partir do status e das métricas atuais
+            r#"
+            INSERT OR IGNORE INTO task_attempts
+            (task_id, attempt, worker_id, started_at, status_type, status_data, result, metrics)
+            SELECT s.task_id, 1, NULL, s.updated_at * 1000, s.status_type, s.status_data,
+                   json_extract(s.status_data, '$.Completed.result'),
+                   CASE WHEN m.task_id IS NULL THEN NULL ELSE json_object(
+                       'execution_time', json_object(
+                           'secs', m.execution_time_ms / 1000,
+                           'nanos', (m.execution_time_ms % 1000) * 1000000
+                       ),
+                       'cpu_usage', m.cpu_usage,
+                       'memory_usage', m.memory_usage,
+                       'network_io', json_array(m.network_io_read, m.network_io_write),
+                       'disk_io', json_array(m.disk_io_read, m.disk_io_write)
+                   ) END
+            FROM task_status s LEFT JOIN metrics m ON m.task_id = s.task_id
+            WHERE s.status_type IN ('Running', 'Completed', 'Failed', 'Cancelled')
+            "#,
+        ],
+    },
+    Migration {
+        version: 12,
+        description: "hooks das tentativas",
+        statements: &[
+            "ALTER TABLE task_attempts ADD COLUMN hooks TEXT",
+        ],
+    },
+    Migration {
+        version: 13,
+        description: "cifragem de tarefas por namespace",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN namespace TEXT",
+            "ALTER TABLE tasks ADD COLUMN key_id TEXT",
+            "ALTER TABLE tasks ADD COLUMN sealed BLOB",
+            "UPDATE tasks SET namespace = COALESCE(json_extract(metadata, '$.namespace'), 'default')",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_namespace ON tasks (namespace, key_id)",
+        ],
+    },
+    Migration {
+        version: 14,
+        description: "marcos de despacho das tentativas",
+        statements: &[
+            "ALTER TABLE task_attempts ADD COLUMN dispatch TEXT",
+        ],
+    },
+    Migration {
+        version: 15,
+        description: "sequência, severidade e origem dos eventos",
+        statements: &[
+            "ALTER TABLE events ADD COLUMN sequence INTEGER",
+            "ALTER TABLE events ADD COLUMN severity TEXT",
+            "ALTER TABLE events ADD COLUMN source TEXT",
+        ],
+    },
+    Migration {
+        version: 16,
+        description: "sidecars das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN sidecars TEXT",
+        ],
+    },
+    Migration {
+        version: 17,
+        description: "grupo de concorrência das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN concurrency_group TEXT",
+            "UPDATE tasks SET concurrency_group = json_extract(metadata, '$.concurrency_group') WHERE key_id IS NULL",
+        ],
+    },
+    Migration {
+        version: 18,
+        description: "hooks das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN hooks TEXT",
+            "UPDATE tasks SET hooks = json_extract(metadata, '$.hooks') WHERE key_id IS NULL",
+        ],
+    },
+    Migration {
+        version: 19,
+        description: "tentativas e manifestos cifrados",
+        statements: &[
+            "ALTER TABLE task_attempts ADD COLUMN key_id TEXT",
+            "ALTER TABLE task_attempts ADD COLUMN sealed BLOB",
+            "ALTER TABLE execution_manifests ADD COLUMN key_id TEXT",
+            "ALTER TABLE execution_manifests ADD COLUMN sealed BLOB",
+        ],
+    },
+    Migration {
+        version: 20,
+        description: "SLA das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN sla TEXT",
+            "UPDATE tasks SET sla = json_extract(metadata, '$.sla') WHERE key_id IS NULL",
+        ],
+    },
+    Migration {
+        version: 21,
+        description: "resultado forçado das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN forced_outcome TEXT",
+            "UPDATE tasks SET forced_outcome = json_extract(metadata, '$.forced_outcome') WHERE key_id IS NULL",
+        ],
+    },
+];
+
+/// Migrações do backend PostgreSQL
+pub const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "schema inicial",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS tasks (
+                id UUID PRIMARY KEY,
+                name TEXT NOT NULL,
+                definition JSONB NOT NULL,
+                dependencies JSONB NOT NULL,
+                priority SMALLINT NOT NULL,
+                metadata JSONB NOT NULL,
+                created_at BIGINT NOT NULL,
+                timeout_ms BIGINT,
+                max_retries INTEGER NOT NULL,
+                tags JSONB NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS task_status (
+                task_id UUID PRIMARY KEY REFERENCES tasks (id),
+                status_type TEXT NOT NULL,
+                status_data JSONB NOT NULL,
+                updated_at BIGINT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS events (
+                id BIGSERIAL PRIMARY KEY,
+                timestamp BIGINT NOT NULL,
+                event_type TEXT NOT NULL,
+                task_id UUID,
+                data JSONB NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS metrics (
+                task_id UUID PRIMARY KEY REFERENCES tasks (id),
+                execution_time_ms BIGINT NOT NULL,
+                cpu_usage DOUBLE PRECISION NOT NULL,
+                memory_usage BIGINT NOT NULL,
+                network_io_read BIGINT NOT NULL,
+                network_io_write BIGINT NOT NULL,
+                disk_io_read BIGINT NOT NULL,
+                disk_io_write BIGINT NOT NULL,
+                recorded_at BIGINT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS checkpoints (
+                id TEXT PRIMARY KEY,
+                data BYTEA NOT NULL,
+                created_at BIGINT NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_task_status_type ON task_status (status_type)",
+            "CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events (timestamp)",
+            "CREATE INDEX IF NOT EXISTS idx_events_task_id ON events (task_id)",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_created_at ON tasks (created_at)",
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "histórico de status",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS task_status_history (
+                seq BIGSERIAL PRIMARY KEY,
+                task_id UUID NOT NULL,
+                status_type TEXT NOT NULL,
+                status_data JSONB NOT NULL,
+                changed_at BIGINT NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_status_history_task ON task_status_history (task_id, seq)",
+            "CREATE INDEX IF NOT EXISTS idx_status_history_changed_at ON task_status_history (changed_at)",
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "checksum de checkpoints",
+        statements: &[
+            "ALTER TABLE checkpoints ADD COLUMN IF NOT EXISTS checksum TEXT",
+            "ALTER TABLE checkpoints ADD COLUMN IF NOT EXISTS payload_len BIGINT",
+        ],
+    },
+    Migration {
+        version: 4,
+        description: "status e métricas sem chave estrangeira",
+        statements: &[
+            "ALTER TABLE task_status DROP CONSTRAINT IF EXISTS task_status_task_id_fkey",
+            "ALTER TABLE metrics DROP CONSTRAINT IF EXISTS metrics_task_id_fkey",
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "registro de modelos",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS models (
+                name TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                blob BYTEA NOT NULL,
+                metrics JSONB NOT NULL,
+                promoted BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at BIGINT NOT NULL,
+                PRIMARY KEY (name, version)
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "aliases de tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS alias TEXT",
+            r#"
+            CREATE TABLE IF NOT EXISTS task_aliases (
+                namespace TEXT NOT NULL,
+                alias TEXT NOT NULL,
+                task_id UUID NOT NULL,
+                created_at BIGINT NOT NULL,
+                PRIMARY KEY (namespace, alias)
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_task_aliases_task ON task_aliases(task_id)",
+        ],
+    },
+    Migration {
+        version: 7,
+        description: "configurações persistentes",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at BIGINT NOT NULL
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 8,
+        description: "grupos de tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS group_id UUID",
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS group_name TEXT",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_group ON tasks (group_id)",
+        ],
+    },
+    Migration {
+        version: 9,
+        description: "manifestos de execução",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS execution_manifests (
+                task_id UUID NOT NULL,
+                attempt INTEGER NOT NULL,
+                manifest JSONB NOT NULL,
+                captured_at BIGINT NOT NULL,
+                PRIMARY KEY (task_id, attempt)
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 10,
+        description: "tentativas de execução",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS task_attempts (
+                task_id UUID NOT NULL,
+                attempt INTEGER NOT NULL,
+                worker_id TEXT,
+                started_at BIGINT NOT NULL,
+                status_type TEXT NOT NULL,
+                status_data JSONB NOT NULL,
+                result JSONB,
+                metrics JSONB,
+                PRIMARY KEY (task_id, attempt)
+            )
+            "#,
+            r#"
+            INSERT INTO task_attempts
+            (task_id, attempt, worker_id, started_at, status_type, status_data, result, metrics)
+            SELECT s.task_id, 1, NULL, s.updated_at * 1000, s.status_type, s.status_data,
+                   s.status_data -> 'Completed' -> 'result',
+                   CASE WHEN m.task_id IS NULL THEN NULL ELSE jsonb_build_object(
+                       'execution_time', jsonb_build_object(
+                           'secs', m.execution_time_ms / 1000,
+                           'nanos', (m.execution_time_ms % 1000) * 1000000
+                       ),
+                       'cpu_usage', m.cpu_usage,
+                       'memory_usage', m.memory_usage,
+                       'network_io', jsonb_build_array(m.network_io_read, m.network_io_write),
+                       'disk_io', jsonb_build_array(m.disk_io_read, m.disk_io_write)
+                   ) END
+            FROM task_status s LEFT JOIN metrics m ON m.task_id = s.task_id
+            WHERE s.status_type IN ('Running', 'Completed', 'Failed', 'Cancelled')
+            ON CONFLICT DO NOTHING
+            "#,
+        ],
+    },
+    Migration {
+        version: 11,
+        description: "hooks das tentativas",
+        statements: &[
+            "ALTER TABLE task_attempts ADD COLUMN IF NOT EXISTS hooks JSONB",
+        ],
+    },
+    Migration {
+        version: 12,
+        description: "marcos de despacho das tentativas",
+        statements: &[
+            "ALTER TABLE task_attempts ADD COLUMN IF NOT EXISTS dispatch JSONB",
+        ],
+    },
+    Migration {
+        version: 13,
+        description: "sequência, severidade e origem dos eventos",
+        statements: &[
+            "ALTER TABLE events ADD COLUMN IF NOT EXISTS sequence BIGINT",
+            "ALTER TABLE events ADD COLUMN IF NOT EXISTS severity TEXT",
+            "ALTER TABLE events ADD COLUMN IF NOT EXISTS source TEXT",
+        ],
+    },
+    Migration {
+        version: 14,
+        description: "sidecars das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS sidecars JSONB",
+        ],
+    },
+    Migration {
+        version: 15,
+        description: "grupo de concorrência das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS concurrency_group TEXT",
+            "UPDATE tasks SET concurrency_group = metadata->>'concurrency_group' WHERE concurrency_group IS NULL",
+        ],
+    },
+    Migration {
+        version: 16,
+        description: "hooks das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS hooks JSONB",
+            "UPDATE tasks SET hooks = (metadata->>'hooks')::jsonb WHERE hooks IS NULL AND metadata->>'hooks' IS NOT NULL",
+        ],
+    },
+    Migration {
+        version: 17,
+        description: "SLA das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS sla JSONB",
+            "UPDATE tasks SET sla = (metadata->>'sla')::jsonb WHERE sla IS NULL AND metadata->>'sla' IS NOT NULL",
+        ],
+    },
+    Migration {
+        version: 18,
+        description: "resultado forçado das tarefas",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS forced_outcome JSONB",
+            "UPDATE tasks SET forced_outcome = (metadata->>'forced_outcome')::jsonb WHERE forced_outcome IS NULL AND metadata->>'forced_outcome' IS NOT NULL",
+        ],
+    },
+];
+
+/// Versão mais recente de uma lista de migrações
+pub fn latest_version(migrations: &[Migration]) -> u32 {
+    migrations.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Monta o relatório de migrações pendentes, recusando bancos mais novos que o código
+fn plan(migrations: &[Migration], current_version: u32) -> TaskMeshResult<MigrationReport> {
+    let target_version = latest_version(migrations);
+    if current_version > target_version {
+        return Err(TaskMeshError::Configuration(format!(
+            "Schema do banco (versão {}) é mais novo que o suportado por esta versão (versão {}); atualize o TaskMesh",
+            current_version, target_version
+        )));
+    }
+
+    Ok(MigrationReport {
+        current_version,
+        target_version,
+        pending: migrations
+            .iter()
+            .filter(|m| m.version > current_version)
+            .map(|m| (m.version, m.description.to_string()))
+            .collect(),
+        applied: false,
+    })
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Versão atual do schema SQLite (0 se nunca migrado)
+pub async fn sqlite_version(pool: &SqlitePool) -> TaskMeshResult<u32> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (version INTEGER PRIMARY KEY, description TEXT NOT NULL, applied_at INTEGER NOT NULL)"
+    )
+    .execute(pool)
+    .await?;
+
+    let row = sqlx::query("SELECT COALESCE(MAX(version), 0) AS version FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+    Ok(row.try_get::<i64, _>("version")? as u32)
+}
+
+/// Verifica ou aplica as migrações SQLite
+///
+/// Com `dry_run` apenas reporta as migrações pendentes, sem aplicá-las.
+pub async fn migrate_sqlite(pool: &SqlitePool, dry_run: bool) -> TaskMeshResult<MigrationReport> {
+    let current_version = sqlite_version(pool).await?;
+    let mut report = plan(SQLITE_MIGRATIONS, current_version)?;
+    if dry_run || report.is_up_to_date() {
+        return Ok(report);
+    }
+
+    for migration in SQLITE_MIGRATIONS.iter().filter(|m| m.version > current_version) {
+        debug!("Aplicando migração SQLite {}: {}", migration.version, migration.description);
+
+        let mut tx = pool.begin().await?;
+        for statement in migration.statements {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+        sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)")
+            .bind(migration.version as i64)
+            .bind(migration.description)
+            .bind(now_secs())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+
+    info!(
+        "Schema SQLite migrado da versão {} para {}",
+        report.current_version, report.target_version
+    );
+    report.applied = true;
+    Ok(report)
+}
+
+/// Versão atual do schema PostgreSQL (0 se nunca migrado)
+pub async fn postgres_version(pool: &PgPool) -> TaskMeshResult<u32> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (version INTEGER PRIMARY KEY, description TEXT NOT NULL, applied_at BIGINT NOT NULL)"
+    )
+    .execute(pool)
+    .await?;
+
+    let row = sqlx::query("SELECT COALESCE(MAX(version), 0) AS version FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+    Ok(row.try_get::<i32, _>("version")? as u32)
+}
+
+/// Verifica ou aplica as migrações PostgreSQL
+pub async fn migrate_postgres(pool: &PgPool, dry_run: bool) -> TaskMeshResult<MigrationReport> {
+    let current_version = postgres_version(pool).await?;
+    let mut report = plan(POSTGRES_MIGRATIONS, current_version)?;
+    if dry_run || report.is_up_to_date() {
+        return Ok(report);
+    }
+
+    for migration in POSTGRES_MIGRATIONS.iter().filter(|m| m.version > current_version) {
+        debug!("Aplicando migração PostgreSQL {}: {}", migration.version, migration.description);
+
+        let mut tx = pool.begin().await?;
+        for statement in migration.statements {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+        sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES ($1, $2, $3)")
+            .bind(migration.version as i32)
+            .bind(migration.description)
+            .bind(now_secs())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+
+    info!(
+        "Schema PostgreSQL migrado da versão {} para {}",
+        report.current_version, report.target_version
+    );
+    report.applied = true;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cria um banco no formato anterior ao versionamento (v1 sem `schema_version`)
+    async fn legacy_v1_fixture(pool: &SqlitePool) -> TaskId {
+        for statement in SQLITE_MIGRATIONS[0].statements.iter().take(5) {
+            sqlx::query(statement).execute(pool).await.unwrap();
+        }
+
+        let task_id = TaskId::new_v4();
+        sqlx::query(
+            "INSERT INTO tasks (id, name, definition, dependencies, priority, metadata, created_at, timeout_ms, max_retries, tags) VALUES (?, 'legacy', '{\"Command\":\"true\"}', '[]', 50, '{}', 0, NULL, 3, '[]')"
+        )
+        .bind(task_id.to_string())
+        .execute(pool)
+        .await
+        .unwrap();
+        task_id
+    }
+
+    #[tokio::test]
+    async fn test_migrate_legacy_database() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let task_id = legacy_v1_fixture(&pool).await;
+
+        let check = migrate_sqlite(&pool, true).await.unwrap();
+        assert_eq!(check.current_version, 0);
+        assert!(!check.applied);
+        assert_eq!(check.pending.len(), SQLITE_MIGRATIONS.len());
+        assert_eq!(sqlite_version(&pool).await.unwrap(), 0);
+
+        let report = migrate_sqlite(&pool, false).await.unwrap();
+        assert!(report.applied);
+        assert_eq!(sqlite_version(&pool).await.unwrap(), latest_version(SQLITE_MIGRATIONS));
+
+        // Schema esperado
+        let indices: Vec<String> = sqlx::query("SELECT name FROM sqlite_master WHERE type = 'index' AND name LIKE 'idx_%'")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("name"))
+            .collect();
+        assert!(indices.contains(&"idx_task_status_type".to_string()));
+
+        // Dados existentes preservados
+        let row = sqlx::query("SELECT name FROM tasks WHERE id = ?")
+            .bind(task_id.to_string())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("name"), "legacy");
+
+        // Segunda execução é idempotente
+        assert!(migrate_sqlite(&pool, false).await.unwrap().is_up_to_date());
+    }
+
+    #[tokio::test]
+    async fn test_status_and_metrics_drop_task_foreign_keys() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let task_id = legacy_v1_fixture(&pool).await;
+        migrate_sqlite(&pool, false).await.unwrap();
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&pool).await.unwrap();
+
+        for table in ["task_status", "metrics"] {
+            let keys = sqlx::query(&format!("SELECT * FROM pragma_foreign_key_list('{}')", table))
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+            assert!(keys.is_empty(), "{} ainda tem chave estrangeira", table);
+        }
+
+        // Status e métricas de tarefa que nunca foi persistida
+        let unstored = TaskId::new_v4();
+        sqlx::query("INSERT INTO task_status (task_id, status_type, status_data, updated_at) VALUES (?, 'Pending', '\"Pending\"', 0)")
+            .bind(unstored.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO metrics (task_id, execution_time_ms, cpu_usage, memory_usage, network_io_read, network_io_write, disk_io_read, disk_io_write, recorded_at) VALUES (?, 0, 0, 0, 0, 0, 0, 0, 0)")
+            .bind(unstored.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // A tabela recriada mantém a tarefa existente
+        let row = sqlx::query("SELECT name FROM tasks WHERE id = ?")
+            .bind(task_id.to_string())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("name"), "legacy");
+    }
+
+    #[tokio::test]
+    async fn test_refuse_newer_database() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_sqlite(&pool, false).await.unwrap();
+        sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES (?, 'futura', 0)")
+            .bind(latest_version(SQLITE_MIGRATIONS) as i64 + 1)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let result = migrate_sqlite(&pool, false).await;
+        assert!(matches!(result, Err(TaskMeshError::Configuration(_))));
+    }
+
+    #[tokio::test]
+    async fn test_existing_executions_become_first_attempt() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let task_id = legacy_v1_fixture(&pool).await;
+        let result = TaskResult {
+            exit_code: 0,
+            stdout: "ok".to_string(),
+            stderr: String::new(),
+            output_data: None,
+            metrics: ExecutionMetrics::default(),
+            log_ref: None,
+        };
+        let status = TaskStatus::Completed { started_at: SystemTime::UNIX_EPOCH, completed_at: SystemTime::UNIX_EPOCH, result };
+        sqlx::query("INSERT INTO task_status (task_id, status_type, status_data, updated_at) VALUES (?, 'Completed', ?, 1700000000)")
+            .bind(task_id.to_string())
+            .bind(serde_json::to_string(&status).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO metrics VALUES (?, 1500, 12.5, 2048, 1, 2, 3, 4, 0)")
+            .bind(task_id.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        migrate_sqlite(&pool, false).await.unwrap();
+        let row = sqlx::query("SELECT attempt, started_at, result, metrics FROM task_attempts WHERE task_id = ?")
+            .bind(task_id.to_string())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((row.get::<i64, _>("attempt"), row.get::<i64, _>("started_at")), (1, 1_700_000_000_000));
+        let result: TaskResult = serde_json::from_str(&row.get::<String, _>("result")).unwrap();
+        assert_eq!(result.stdout, "ok");
+        let metrics: ExecutionMetrics = serde_json::from_str(&row.get::<String, _>("metrics")).unwrap();
+        assert_eq!(metrics.execution_time, std::time::Duration::from_millis(1500));
+        assert_eq!((metrics.memory_usage, metrics.network_io, metrics.disk_io), (2048, (1, 2), (3, 4)));
+    }
+}
@@ -0,0 +1,352 @@
+//! Otimização de planos de execução por simulated annealing
+//!
+//! O plano é modelado como uma sequência de estágios: as tarefas de um
+//! estágio rodam em paralelo (até `max_parallel`) e o estágio dura tanto
+//! quanto sua tarefa mais longa. O otimizador move tarefas entre estágios
+//! minimizando um objetivo ponderado de makespan, excesso de CPU por estágio
+//! e prazos perdidos. Um movimento só é aceito se mantiver cada tarefa em um
+//! estágio posterior a todas as suas dependências e anterior a todos os
+//! seus dependentes.
+
+use std::time::{Duration, Instant};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::types::TaskId;
+
+/// Configuração do otimizador
+#[derive(Debug, Clone)]
+pub struct PlanOptimizerConfig {
+    /// Tempo máximo gasto na otimização
+    pub time_budget: Duration,
+    /// Número máximo de iterações
+    pub max_iterations: usize,
+    /// Semente do gerador aleatório (`None` = aleatória)
+    pub seed: Option<u64>,
+    /// Temperatura inicial, relativa ao objetivo do plano inicial
+    pub initial_temperature: f64,
+    /// Fator de resfriamento aplicado a cada iteração
+    pub cooling_rate: f64,
+    /// Peso do makespan (por segundo)
+    pub makespan_weight: f64,
+    /// Peso do excesso de CPU (por núcleo acima da capacidade, por estágio)
+    pub overcommit_weight: f64,
+    /// Peso de cada prazo perdido
+    pub deadline_weight: f64,
+    /// Núcleos disponíveis para um estágio
+    pub cpu_capacity: f64,
+}
+
+impl Default for PlanOptimizerConfig {
+    fn default() -> Self {
+        Self {
+            time_budget: Duration::from_millis(200),
+            max_iterations: 20_000,
+            seed: None,
+            initial_temperature: 0.1,
+            cooling_rate: 0.9995,
+            makespan_weight: 1.0,
+            overcommit_weight: 10.0,
+            deadline_weight: 60.0,
+            cpu_capacity: num_cpus::get() as f64,
+        }
+    }
+}
+
+/// Tarefa do problema de planejamento
+#[derive(Debug, Clone)]
+pub struct PlanTask {
+    pub id: TaskId,
+    /// Duração estimada
+    pub duration: Duration,
+    /// Núcleos de CPU necessários
+    pub cpu_cores: f64,
+    /// Prazo relativo ao início do plano
+    pub deadline: Option<Duration>,
+    /// Índices (em `PlanProblem::tasks`) das dependências
+    pub dependencies: Vec<usize>,
+}
+
+/// Problema de planejamento
+#[derive(Debug, Clone)]
+pub struct PlanProblem {
+    /// Tarefas em ordem topológica
+    pub tasks: Vec<PlanTask>,
+    /// Máximo de tarefas por estágio
+    pub max_parallel: usize,
+}
+
+impl PlanProblem {
+    /// Makespan de um agrupamento (soma das durações máximas dos estágios)
+    pub fn makespan(&self, groups: &[Vec<usize>]) -> Duration {
+        groups
+            .iter()
+            .map(|group| group.iter().map(|&i| self.tasks[i].duration).max().unwrap_or_default())
+            .sum()
+    }
+}
+
+/// Resultado da otimização
+#[derive(Debug, Clone)]
+pub struct OptimizedPlan {
+    /// Estágios (índices das tarefas), em ordem de execução
+    pub groups: Vec<Vec<usize>>,
+    /// Objetivo do plano recebido
+    pub initial_objective: f64,
+    /// Objetivo do plano otimizado
+    pub final_objective: f64,
+    /// Iterações executadas
+    pub iterations: usize,
+}
+
+impl OptimizedPlan {
+    /// Melhora relativa do objetivo (0.0 = nenhuma)
+    pub fn improvement(&self) -> f64 {
+        if self.initial_objective > 0.0 {
+            (self.initial_objective - self.final_objective) / self.initial_objective
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Otimizador de planos por simulated annealing
+#[derive(Debug, Clone, Default)]
+pub struct PlanOptimizer {
+    config: PlanOptimizerConfig,
+}
+
+impl PlanOptimizer {
+    pub fn new(config: PlanOptimizerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Objetivo ponderado de um agrupamento
+    pub fn objective(&self, problem: &PlanProblem, groups: &[Vec<usize>]) -> f64 {
+        let mut elapsed = Duration::ZERO;
+        let mut overcommit = 0.0;
+        let mut missed = 0usize;
+
+        for group in groups.iter().filter(|g| !g.is_empty()) {
+            let tasks = group.iter().map(|&i| &problem.tasks[i]);
+            elapsed += tasks.clone().map(|t| t.duration).max().unwrap_or_default();
+            overcommit += (tasks.clone().map(|t| t.cpu_cores).sum::<f64>() - self.config.cpu_capacity).max(0.0);
+            missed += tasks.filter(|t| t.deadline.is_some_and(|d| elapsed > d)).count();
+        }
+
+        self.config.makespan_weight * elapsed.as_secs_f64()
+            + self.config.overcommit_weight * overcommit
+            + self.config.deadline_weight * missed as f64
+    }
+
+    /// Otimiza um agrupamento inicial
+    ///
+    /// O agrupamento inicial pode violar dependências; ele é reparado
+    /// (tarefas empurradas para estágios posteriores) antes da busca, mas a
+    /// melhora é medida em relação ao agrupamento recebido.
+    pub fn optimize(&self, problem: &PlanProblem, initial: &[Vec<usize>]) -> OptimizedPlan {
+        let n = problem.tasks.len();
+        let initial_objective = self.objective(problem, initial);
+        let max_parallel = problem.max_parallel.max(1);
+
+        let successors = {
+            let mut successors = vec![Vec::new(); n];
+            for (i, task) in problem.tasks.iter().enumerate() {
+                for &dep in &task.dependencies {
+                    successors[dep].push(i);
+                }
+            }
+            successors
+        };
+
+        let mut state = StageAssignment::repaired(problem, initial, max_parallel);
+        let mut current = self.objective(problem, &state.groups());
+        let mut best = (state.stage.clone(), current);
+
+        let mut rng = match self.config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let mut temperature = self.config.initial_temperature * current.max(f64::EPSILON);
+        let started = Instant::now();
+        let mut iterations = 0;
+
+        while n > 1 && iterations < self.config.max_iterations && started.elapsed() < self.config.time_budget {
+            iterations += 1;
+            temperature *= self.config.cooling_rate;
+
+            let undo = if rng.gen_bool(0.5) {
+                state.random_move(problem, &successors, max_parallel, &mut rng)
+            } else {
+                state.random_swap(problem, &successors, &mut rng)
+            };
+            let Some(undo) = undo else { continue };
+
+            let candidate = self.objective(problem, &state.groups());
+            let delta = candidate - current;
+            if delta <= 0.0 || rng.gen::<f64>() < (-delta / temperature).exp() {
+                current = candidate;
+                if current < best.1 {
+                    best = (state.stage.clone(), current);
+                }
+            } else {
+                state.undo(undo);
+            }
+        }
+
+        state.stage = best.0;
+        OptimizedPlan {
+            groups: state.groups(),
+            initial_objective,
+            final_objective: best.1,
+            iterations,
+        }
+    }
+}
+
+/// Estágio de cada tarefa, com ocupação por estágio
+struct StageAssignment {
+    stage: Vec<usize>,
+    occupancy: Vec<usize>,
+}
+
+/// Movimentos aplicados, para desfazer: (tarefa, estágio anterior)
+type Undo = Vec<(usize, usize)>;
+
+impl StageAssignment {
+    /// Parte do agrupamento dado, empurrando tarefas para depois das dependências
+    fn repaired(problem: &PlanProblem, initial: &[Vec<usize>], max_parallel: usize) -> Self {
+        let n = problem.tasks.len();
+        let mut preferred = vec![0; n];
+        for (index, group) in initial.iter().enumerate() {
+            for &i in group {
+                preferred[i] = index;
+            }
+        }
+
+        let mut assignment = Self { stage: vec![0; n], occupancy: vec![0; n.max(1)] };
+        for (i, task) in problem.tasks.iter().enumerate() {
+            let earliest = task.dependencies.iter().map(|&d| assignment.stage[d] + 1).max().unwrap_or(0);
+            let mut stage = preferred[i].max(earliest);
+            while assignment.occupancy.get(stage).is_some_and(|&o| o >= max_parallel) {
+                stage += 1;
+            }
+            if stage >= assignment.occupancy.len() {
+                assignment.occupancy.resize(stage + 1, 0);
+            }
+            assignment.stage[i] = stage;
+            assignment.occupancy[stage] += 1;
+        }
+        // Espaço para abrir novos estágios durante a busca
+        let len = assignment.occupancy.len().max(n + 1);
+        assignment.occupancy.resize(len, 0);
+        assignment
+    }
+
+    /// Intervalo de estágios permitido para uma tarefa
+    fn feasible_range(&self, problem: &PlanProblem, successors: &[Vec<usize>], i: usize) -> (usize, usize) {
+        let lo = problem.tasks[i].dependencies.iter().map(|&d| self.stage[d] + 1).max().unwrap_or(0);
+        // Permite abrir no máximo um estágio novo
+        let last = self.stage.iter().copied().max().unwrap_or(0) + 1;
+        let hi = successors[i].iter().map(|&s| self.stage[s]).min().map_or(last, |s| s.saturating_sub(1));
+        (lo, hi.min(self.occupancy.len() - 1))
+    }
+
+    fn set(&mut self, i: usize, stage: usize) {
+        self.occupancy[self.stage[i]] -= 1;
+        self.occupancy[stage] += 1;
+        self.stage[i] = stage;
+    }
+
+    /// Move uma tarefa aleatória para outro estágio viável
+    fn random_move(
+        &mut self,
+        problem: &PlanProblem,
+        successors: &[Vec<usize>],
+        max_parallel: usize,
+        rng: &mut StdRng,
+    ) -> Option<Undo> {
+        let i = rng.gen_range(0..self.stage.len());
+        let (lo, hi) = self.feasible_range(problem, successors, i);
+        if lo > hi {
+            return None;
+        }
+        let target = rng.gen_range(lo..=hi);
+        if target == self.stage[i] || self.occupancy[target] >= max_parallel {
+            return None;
+        }
+        let undo = vec![(i, self.stage[i])];
+        self.set(i, target);
+        Some(undo)
+    }
+
+    /// Troca os estágios de duas tarefas, se ambas continuarem viáveis
+    fn random_swap(&mut self, problem: &PlanProblem, successors: &[Vec<usize>], rng: &mut StdRng) -> Option<Undo> {
+        let i = rng.gen_range(0..self.stage.len());
+        let j = rng.gen_range(0..self.stage.len());
+        let (si, sj) = (self.stage[i], self.stage[j]);
+        if si == sj {
+            return None;
+        }
+        let (lo_i, hi_i) = self.feasible_range(problem, successors, i);
+        let (lo_j, hi_j) = self.feasible_range(problem, successors, j);
+        if !(lo_i..=hi_i).contains(&sj) || !(lo_j..=hi_j).contains(&si) {
+            return None;
+        }
+        self.set(i, sj);
+        self.set(j, si);
+        Some(vec![(j, sj), (i, si)])
+    }
+
+    fn undo(&mut self, undo: Undo) {
+        for (i, stage) in undo {
+            self.set(i, stage);
+        }
+    }
+
+    /// Estágios não vazios, em ordem
+    fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups = vec![Vec::new(); self.occupancy.len()];
+        for (i, &stage) in self.stage.iter().enumerate() {
+            groups[stage].push(i);
+        }
+        groups.retain(|g| !g.is_empty());
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_optimization_is_deterministic_and_respects_dependencies() {
+        let tasks: Vec<PlanTask> = (0..12)
+            .map(|i| PlanTask {
+                id: TaskId::new_v4(),
+                duration: Duration::from_secs(if i % 2 == 0 { 8 } else { 1 }),
+                cpu_cores: 1.0,
+                deadline: None,
+                dependencies: if i >= 6 { vec![i - 6] } else { vec![] },
+            })
+            .collect();
+        let problem = PlanProblem { tasks, max_parallel: 3 };
+        let initial: Vec<Vec<usize>> = (0..12).collect::<Vec<_>>().chunks(3).map(|c| c.to_vec()).collect();
+        let optimizer = PlanOptimizer::new(PlanOptimizerConfig {
+            seed: Some(7),
+            time_budget: Duration::from_secs(10),
+            ..PlanOptimizerConfig::default()
+        });
+
+        let first = optimizer.optimize(&problem, &initial);
+        let second = optimizer.optimize(&problem, &initial);
+        assert_eq!(first.groups, second.groups);
+        assert!(first.final_objective <= first.initial_objective);
+
+        let stage_of = |i: usize| first.groups.iter().position(|g| g.contains(&i)).unwrap();
+        for i in 6..12 {
+            assert!(stage_of(i - 6) < stage_of(i));
+        }
+        assert!(first.groups.iter().all(|g| g.len() <= 3));
+    }
+}
@@ -0,0 +1,364 @@
+//! Verificação da configuração e da conectividade antes de iniciar
+//!
+//! Uma configuração errada (URL do banco, Redis fora do ar, diretório sem
+//! permissão de escrita) normalmente só aparece na primeira tarefa que a
+//! exercita. A verificação prévia exercita cada dependência uma vez e gera um
+//! [`PreflightReport`] em que cada verificação passa, avisa ou falha com uma
+//! dica de correção:
+//!
+//! - banco de dados: consulta mínima e versão do schema;
+//! - Redis (se configurado): `PING`;
+//! - diretórios de dados, logs e scratch: grava e remove um arquivo de teste;
+//! - relógio: não pode estar antes do último checkpoint gravado;
+//! - pool de workers: capacidade de execução disponível.
+//!
+//! O relatório é exposto por [`TaskMeshCore::preflight`](crate::TaskMeshCore::preflight)
+//! e pelo subcomando `taskmesh doctor`. Com [`PreflightConfig::on_start`], o
+//! `start()` roda a verificação e recusa iniciar se falhar alguma das
+//! verificações de [`PreflightConfig::fail_fast_on`].
+
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+use serde::{Deserialize, Serialize};
+
+use crate::executor::TaskExecutor;
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Tempo máximo de cada verificação de conectividade
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Relógios antes desta data (2020-01-01) estão certamente errados
+const EARLIEST_SANE_CLOCK: Duration = Duration::from_secs(1_577_836_800);
+
+/// Verificações realizadas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckId {
+    Database,
+    Schema,
+    Redis,
+    DataDir,
+    LogDir,
+    ScratchDir,
+    Clock,
+    WorkerPool,
+}
+
+impl CheckId {
+    /// Nome da verificação, como serializado
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckId::Database => "database",
+            CheckId::Schema => "schema",
+            CheckId::Redis => "redis",
+            CheckId::DataDir => "data_dir",
+            CheckId::LogDir => "log_dir",
+            CheckId::ScratchDir => "scratch_dir",
+            CheckId::Clock => "clock",
+            CheckId::WorkerPool => "worker_pool",
+        }
+    }
+}
+
+/// Resultado de uma verificação
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Resultado de uma verificação, com a dica de correção quando não passa
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub id: CheckId,
+    pub status: CheckStatus,
+    /// Descrição legível do resultado
+    pub detail: String,
+    /// Como corrigir o problema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// Tempo gasto na verificação
+    pub latency_ms: u64,
+}
+
+impl CheckResult {
+    fn new(id: CheckId, status: CheckStatus, detail: impl Into<String>, hint: Option<String>, started: Instant) -> Self {
+        Self { id, status, detail: detail.into(), hint, latency_ms: started.elapsed().as_millis() as u64 }
+    }
+
+    fn pass(id: CheckId, detail: impl Into<String>, started: Instant) -> Self {
+        Self::new(id, CheckStatus::Pass, detail, None, started)
+    }
+
+    fn fail(id: CheckId, detail: impl Into<String>, hint: impl Into<String>, started: Instant) -> Self {
+        Self::new(id, CheckStatus::Fail, detail, Some(hint.into()), started)
+    }
+}
+
+/// Relatório da verificação prévia
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub checks: Vec<CheckResult>,
+    /// Momento da verificação
+    pub checked_at: SystemTime,
+}
+
+impl PreflightReport {
+    pub fn new(checks: Vec<CheckResult>) -> Self {
+        Self { checks, checked_at: SystemTime::now() }
+    }
+
+    /// Relatório de quando nem a conexão com o banco pôde ser aberta
+    pub fn connection_failure(error: &TaskMeshError) -> Self {
+        Self::new(vec![CheckResult::fail(CheckId::Database, error.to_string(), DATABASE_HINT, Instant::now())])
+    }
+
+    /// Nenhuma verificação falhou (avisos são aceitos)
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Fail)
+    }
+
+    /// Resultado de uma verificação
+    pub fn check(&self, id: CheckId) -> Option<&CheckResult> {
+        self.checks.iter().find(|check| check.id == id)
+    }
+
+    /// Verificações de `ids` que falharam
+    pub fn failures(&self, ids: &[CheckId]) -> Vec<&CheckResult> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail && ids.contains(&check.id))
+            .collect()
+    }
+}
+
+/// Verificação prévia na inicialização
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreflightConfig {
+    /// Roda a verificação no `start()` e registra o resultado
+    #[serde(default)]
+    pub on_start: bool,
+    /// Verificações cuja falha impede o `start()`
+    #[serde(default)]
+    pub fail_fast_on: Vec<CheckId>,
+}
+
+const DATABASE_HINT: &str = "Verifique `database_url` e se o banco aceita conexões deste host";
+
+/// Roda uma verificação de conectividade com [`CHECK_TIMEOUT`]
+async fn with_timeout<T>(future: impl std::future::Future<Output = TaskMeshResult<T>>) -> TaskMeshResult<T> {
+    tokio::time::timeout(CHECK_TIMEOUT, future)
+        .await
+        .map_err(|_| TaskMeshError::ResourceUnavailable(format!("sem resposta em {:?}", CHECK_TIMEOUT)))?
+}
+
+/// Verifica se o banco responde
+pub async fn check_database(store: &dyn StateStore) -> CheckResult {
+    let started = Instant::now();
+    match with_timeout(store.ping()).await {
+        Ok(()) => CheckResult::pass(CheckId::Database, "banco acessível", started),
+        Err(e) => CheckResult::fail(CheckId::Database, format!("banco inacessível: {}", e), DATABASE_HINT, started),
+    }
+}
+
+/// Verifica se o schema do banco está na versão esperada pelo código
+pub async fn check_schema(store: &dyn StateStore) -> CheckResult {
+    let started = Instant::now();
+    match with_timeout(store.schema_version()).await {
+        Ok(Some((current, target))) if current == target => {
+            CheckResult::pass(CheckId::Schema, format!("schema na versão {}", current), started)
+        }
+        Ok(Some((current, target))) => CheckResult::fail(
+            CheckId::Schema,
+            format!("schema na versão {}, esperada {}", current, target),
+            if current < target {
+                "Execute `taskmesh migrate` para aplicar as migrações pendentes"
+            } else {
+                "O banco foi migrado por uma versão mais nova do TaskMesh; atualize este binário"
+            },
+            started,
+        ),
+        Ok(None) => CheckResult::pass(CheckId::Schema, "backend sem versionamento de schema", started),
+        Err(e) => CheckResult::fail(
+            CheckId::Schema,
+            format!("versão do schema ilegível: {}", e),
+            "Execute `taskmesh migrate --check` para inspecionar a tabela schema_version",
+            started,
+        ),
+    }
+}
+
+/// Envia `PING` ao Redis configurado
+pub async fn check_redis(redis_url: Option<&str>) -> CheckResult {
+    let started = Instant::now();
+    let Some(redis_url) = redis_url else {
+        return CheckResult::pass(CheckId::Redis, "Redis não configurado", started);
+    };
+    let ping = async {
+        let client = redis::Client::open(redis_url)?;
+        let mut connection = client.get_async_connection().await?;
+        redis::cmd("PING").query_async::<_, String>(&mut connection).await?;
+        Ok::<_, TaskMeshError>(())
+    };
+    match with_timeout(ping).await {
+        Ok(()) => CheckResult::pass(CheckId::Redis, "Redis respondeu ao PING", started),
+        Err(e) => CheckResult::fail(
+            CheckId::Redis,
+            format!("Redis inacessível: {}", e),
+            "Verifique `redis_url` (redis://host:porta) e se o Redis aceita conexões deste host",
+            started,
+        ),
+    }
+}
+
+/// Verifica que é possível criar, gravar e remover arquivos em `dir`
+pub async fn check_writable_dir(id: CheckId, dir: Option<&Path>) -> CheckResult {
+    let started = Instant::now();
+    let Some(dir) = dir else {
+        return CheckResult::pass(id, "diretório não configurado", started);
+    };
+    let probe = dir.join(format!(".taskmesh-preflight-{}", uuid::Uuid::new_v4()));
+    let outcome = async {
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(&probe, b"preflight").await?;
+        tokio::fs::remove_file(&probe).await
+    };
+    match outcome.await {
+        Ok(()) => CheckResult::pass(id, format!("{} gravável", dir.display()), started),
+        Err(e) => CheckResult::fail(
+            id,
+            format!("{} não é gravável: {}", dir.display(), e),
+            format!("Crie {} com permissão de escrita para este usuário ou ajuste `data_dir`", dir.display()),
+            started,
+        ),
+    }
+}
+
+/// Verifica se o relógio do sistema é plausível
+///
+/// Um relógio antes do último checkpoint gravado voltou no tempo (NTP
+/// ausente, VM restaurada) e quebraria prazos, retenção e ordenação de eventos.
+pub async fn check_clock(store: &dyn StateStore, now: SystemTime) -> CheckResult {
+    let started = Instant::now();
+    const HINT: &str = "Sincronize o relógio do sistema (NTP) antes de iniciar";
+    if now < SystemTime::UNIX_EPOCH + EARLIEST_SANE_CLOCK {
+        return CheckResult::fail(CheckId::Clock, format!("relógio em {:?}, antes de 2020", now), HINT, started);
+    }
+    match with_timeout(store.last_checkpoint_at()).await {
+        Ok(Some(last)) if last > now => {
+            let behind = last.duration_since(now).unwrap_or_default();
+            CheckResult::fail(
+                CheckId::Clock,
+                format!("relógio {:?} atrás do último checkpoint", behind),
+                HINT,
+                started,
+            )
+        }
+        Ok(_) => CheckResult::pass(CheckId::Clock, "relógio coerente com o último checkpoint", started),
+        Err(e) => CheckResult::new(
+            CheckId::Clock,
+            CheckStatus::Warn,
+            format!("último checkpoint indisponível para comparação: {}", e),
+            Some("Verifique o acesso ao banco; o relógio não pôde ser comparado".to_string()),
+            started,
+        ),
+    }
+}
+
+/// Verifica se o executor tem capacidade para rodar tarefas
+pub async fn check_worker_pool(executor: &TaskExecutor) -> CheckResult {
+    let started = Instant::now();
+    let capacity = executor.max_concurrency();
+    if capacity == 0 {
+        return CheckResult::fail(
+            CheckId::WorkerPool,
+            "nenhum worker disponível",
+            "Configure `max_workers` com pelo menos 1",
+            started,
+        );
+    }
+    let running = executor.running_count().await;
+    CheckResult::pass(CheckId::WorkerPool, format!("{}/{} workers ocupados", running, capacity), started)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::tests::UnreachableStore;
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    #[tokio::test]
+    async fn test_healthy_core_passes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig { data_dir: Some(dir.path().to_string_lossy().into_owned()), ..Default::default() };
+        let core = TaskMeshCore::new(config).await.unwrap();
+
+        let report = core.preflight().await;
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.check(CheckId::Schema).unwrap().status, CheckStatus::Pass);
+        assert!(report.check(CheckId::ScratchDir).unwrap().detail.contains("scratch"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["id"], "database");
+        assert_eq!(json["checks"][0]["status"], "pass");
+    }
+
+    #[tokio::test]
+    async fn test_failing_components_report_hints() {
+        let database = check_database(&UnreachableStore).await;
+        assert_eq!(database.status, CheckStatus::Fail);
+        assert!(database.hint.unwrap().contains("database_url"));
+
+        let redis = check_redis(Some("redis://127.0.0.1:1")).await;
+        assert_eq!(redis.status, CheckStatus::Fail);
+        assert!(redis.hint.unwrap().contains("redis_url"));
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let data_dir = check_writable_dir(CheckId::DataDir, Some(file.path())).await;
+        assert_eq!(data_dir.status, CheckStatus::Fail);
+        assert!(data_dir.hint.unwrap().contains("permissão de escrita"));
+
+        let store = crate::state_store::MemoryStateStore::new().await.unwrap();
+        store.create_checkpoint("futuro").await.unwrap();
+        let clock = check_clock(&store, SystemTime::now() - Duration::from_secs(3600)).await;
+        assert_eq!(clock.status, CheckStatus::Fail);
+        assert!(clock.hint.unwrap().contains("NTP"));
+
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_blocks_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().join("data");
+        let config = TaskMeshConfig {
+            data_dir: Some(data_dir.to_string_lossy().into_owned()),
+            preflight: PreflightConfig { on_start: true, fail_fast_on: vec![CheckId::DataDir] },
+            ..Default::default()
+        };
+        let core = TaskMeshCore::new(config).await.unwrap();
+        let stopped = std::sync::Arc::new(std::sync::Mutex::new(None));
+        core.on_lifecycle(crate::LifecyclePhase::Stopped, {
+            let stopped = stopped.clone();
+            move |ctx: crate::LifecycleContext| {
+                *stopped.lock().unwrap() = Some(ctx.reason);
+                async { Ok(()) }
+            }
+        });
+
+        // O diretório de dados é substituído por um arquivo depois da criação
+        std::fs::remove_dir_all(&data_dir).unwrap();
+        std::fs::write(&data_dir, b"").unwrap();
+
+        let report = core.preflight().await;
+        assert_eq!(report.failures(&[CheckId::DataDir, CheckId::Clock]).len(), 1);
+        match core.start().await {
+            Err(TaskMeshError::Configuration(message)) => assert!(message.contains("data_dir")),
+            other => panic!("esperava falha da verificação prévia, obtido {:?}", other),
+        }
+        // Callbacks de `Starting` são avisados da falha
+        assert!(stopped.lock().unwrap().as_deref().is_some_and(|reason| reason.contains("data_dir")));
+    }
+}
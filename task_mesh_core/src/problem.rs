@@ -0,0 +1,205 @@
+//! Respostas de erro no formato problem+json (RFC 7807)
+//!
+//! Cada [`TaskMeshError`] vira um [`ProblemDetails`] com `type` estável
+//! (derivado de [`TaskMeshError::error_code`]), título fixo e o status de
+//! [`TaskMeshError::http_status`]. Os três mapeamentos são `match`
+//! exaustivos: uma variante nova não compila sem as suas entradas. O corpo
+//! é o de [`system_events::problem`], comum ao `orchestrator_core`.
+//!
+//! Erros de validação listam as violações em `errors`. Mensagens de erros
+//! 5xx podem conter detalhes internos (caminhos, SQL) e só vão para o corpo
+//! com [`ApiError::with_debug`]. O crate não tem servidor HTTP: quem expõe a
+//! API responde com [`ApiError::status`], [`PROBLEM_JSON`] e
+//! [`ApiError::to_json`].
+
+pub use system_events::problem::{FieldError, ProblemDetails, PROBLEM_JSON};
+use system_events::problem::problem_type;
+
+use crate::types::*;
+
+/// Prefixo dos URIs de `type`
+pub const PROBLEM_TYPE_PREFIX: &str = "urn:taskmesh:problem:";
+
+/// Título de cada variante
+fn title(error: &TaskMeshError) -> &'static str {
+    match error {
+        TaskMeshError::Configuration(_) => "Configuração inválida",
+        TaskMeshError::TaskNotFound(_) => "Tarefa não encontrada",
+        TaskMeshError::CheckpointNotFound(_) => "Checkpoint não encontrado",
+        TaskMeshError::AliasNotFound(_) => "Alias não encontrado",
+        TaskMeshError::CircularDependency(_) => "Dependência circular",
+        TaskMeshError::FanOutExceeded { .. } => "Gerador excedeu o limite de tarefas",
+        TaskMeshError::WorkflowTooLarge { .. } => "Lote de tarefas grande demais",
+        TaskMeshError::AliasConflict { .. } => "Alias em uso",
+        TaskMeshError::UnsupportedOperation(_) => "Operação não suportada",
+        TaskMeshError::InvalidState(_) => "Estado da tarefa não permite a operação",
+        TaskMeshError::Validation(_) => "Tarefa inválida",
+        TaskMeshError::QueueFull { .. } => "Fila de tarefas cheia",
+        TaskMeshError::ResourceUnavailable(_) => "Recurso indisponível",
+        TaskMeshError::SidecarNotReady { .. } => "Sidecar não ficou pronto",
+        TaskMeshError::Network(_) => "Falha de rede",
+        TaskMeshError::ExecutionTimeout(_) => "Timeout na execução da tarefa",
+        TaskMeshError::WaitTimeout { .. } => "Tarefas não finalizaram a tempo",
+        TaskMeshError::Database(_)
+        | TaskMeshError::Redis(_)
+        | TaskMeshError::Io(_)
+        | TaskMeshError::Serialization(_)
+        | TaskMeshError::ResourceLimitExceeded(_)
+        | TaskMeshError::ExecutionError(_)
+        | TaskMeshError::CheckpointCorrupted(_)
+        | TaskMeshError::UnsupportedFormatVersion { .. }
+        | TaskMeshError::CancelledUncooperatively(_)
+        | TaskMeshError::BlockingInAsyncContext(_)
+        | TaskMeshError::Internal(_) => "Erro interno",
+    }
+}
+
+/// Erro pronto para virar uma resposta HTTP
+#[derive(Debug)]
+pub struct ApiError {
+    pub error: TaskMeshError,
+    trace_id: Option<String>,
+    debug: bool,
+}
+
+impl From<TaskMeshError> for ApiError {
+    fn from(error: TaskMeshError) -> Self {
+        Self { error, trace_id: None, debug: false }
+    }
+}
+
+impl ApiError {
+    /// `trace_id` da requisição, devolvido ao cliente para correlação com os logs
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = Some(trace_id.into());
+        self
+    }
+
+    /// Inclui a mensagem de erros 5xx no corpo (apenas para depuração)
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.error.http_status()
+    }
+
+    /// Corpo problem+json do erro
+    pub fn problem(&self) -> ProblemDetails {
+        let status = self.status();
+        let code = self.error.error_code();
+        let errors = match &self.error {
+            TaskMeshError::Validation(validation) => validation
+                .violations
+                .iter()
+                .map(|violation| FieldError { field: violation.field.clone(), message: violation.message.clone() })
+                .collect(),
+            _ => Vec::new(),
+        };
+        ProblemDetails {
+            type_uri: problem_type(PROBLEM_TYPE_PREFIX, code),
+            title: title(&self.error).to_string(),
+            status,
+            detail: (status < 500 || self.debug).then(|| self.error.to_string()),
+            code: code.to_string(),
+            trace_id: self.trace_id.clone(),
+            errors,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.problem()).expect("ProblemDetails serializável")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::{ValidationError, Violation};
+
+    #[test]
+    fn test_every_variant_has_a_distinct_stable_type() {
+        let task_id = TaskId::from_u128(0);
+        let errors = vec![
+            TaskMeshError::Configuration("c".to_string()),
+            TaskMeshError::Database(sqlx::Error::RowNotFound),
+            TaskMeshError::Redis(redis::RedisError::from((redis::ErrorKind::IoError, "down"))),
+            TaskMeshError::Io(std::io::Error::new(std::io::ErrorKind::Other, "disk")),
+            TaskMeshError::Serialization(serde_json::from_str::<u8>("x").unwrap_err()),
+            TaskMeshError::TaskNotFound(task_id),
+            TaskMeshError::CircularDependency(vec![task_id]),
+            TaskMeshError::ResourceUnavailable("gpu".to_string()),
+            TaskMeshError::ResourceLimitExceeded("memória".to_string()),
+            TaskMeshError::ExecutionTimeout(task_id),
+            TaskMeshError::ExecutionError("exit 1".to_string()),
+            TaskMeshError::Network("conexão recusada".to_string()),
+            TaskMeshError::CheckpointNotFound("c".to_string()),
+            TaskMeshError::CheckpointCorrupted("c".to_string()),
+            TaskMeshError::UnsupportedFormatVersion { found: 9, supported: 1 },
+            TaskMeshError::QueueFull { pending: 5, limit: 5 },
+            TaskMeshError::FanOutExceeded { generated: 9, limit: 1 },
+            TaskMeshError::WorkflowTooLarge { tasks: 9, limit: 1 },
+            TaskMeshError::AliasConflict { namespace: "n".to_string(), alias: "a".to_string(), existing: task_id },
+            TaskMeshError::AliasNotFound("a".to_string()),
+            TaskMeshError::SidecarNotReady { name: "db".to_string(), reason: "r".to_string() },
+            TaskMeshError::CancelledUncooperatively(std::time::Duration::from_secs(1)),
+            TaskMeshError::UnsupportedOperation("u".to_string()),
+            TaskMeshError::InvalidState("s".to_string()),
+            TaskMeshError::BlockingInAsyncContext("submit_task".to_string()),
+            TaskMeshError::WaitTimeout { completed: vec![(task_id, TaskStatus::Pending)], pending: vec![task_id] },
+            TaskMeshError::Validation(ValidationError { violations: Vec::new() }),
+            TaskMeshError::Internal("i".to_string()),
+        ];
+        let problems: Vec<ProblemDetails> = errors.into_iter().map(|error| ApiError::from(error).problem()).collect();
+        let types: std::collections::HashSet<&str> = problems.iter().map(|p| p.type_uri.as_str()).collect();
+        assert_eq!(types.len(), problems.len());
+        for problem in &problems {
+            assert_eq!(problem.detail.is_none(), problem.status >= 500, "{:?}", problem);
+        }
+    }
+
+    #[test]
+    fn test_representative_bodies() {
+        let not_found = ApiError::from(TaskMeshError::TaskNotFound(TaskId::from_u128(0))).with_trace_id("trace-1");
+        assert_eq!(not_found.to_json(), serde_json::json!({
+            "type": "urn:taskmesh:problem:task-not-found",
+            "title": "Tarefa não encontrada",
+            "status": 404,
+            "detail": "Tarefa não encontrada: 00000000-0000-0000-0000-000000000000",
+            "code": "TASK_NOT_FOUND",
+            "trace_id": "trace-1",
+        }));
+
+        let queue_full = ApiError::from(TaskMeshError::QueueFull { pending: 5, limit: 5 });
+        assert_eq!((queue_full.status(), queue_full.to_json()["code"].as_str()), (429, Some("QUEUE_FULL")));
+
+        let invalid = TaskMeshError::Validation(ValidationError {
+            violations: vec![
+                Violation { field: "tasks[1].tags".to_string(), message: "mais de 3 tags".to_string() },
+                Violation { field: "tasks[1].metadata.blob".to_string(), message: "valor longo demais".to_string() },
+            ],
+        });
+        assert_eq!(ApiError::from(invalid).to_json(), serde_json::json!({
+            "type": "urn:taskmesh:problem:validation-error",
+            "title": "Tarefa inválida",
+            "status": 422,
+            "detail": "Tarefa inválida: tasks[1].tags: mais de 3 tags; tasks[1].metadata.blob: valor longo demais",
+            "code": "VALIDATION_ERROR",
+            "errors": [
+                { "field": "tasks[1].tags", "message": "mais de 3 tags" },
+                { "field": "tasks[1].metadata.blob", "message": "valor longo demais" },
+            ],
+        }));
+
+        let internal = || TaskMeshError::Internal("/var/lib/taskmesh/state.db travado".to_string());
+        assert_eq!(ApiError::from(internal()).to_json(), serde_json::json!({
+            "type": "urn:taskmesh:problem:internal-error",
+            "title": "Erro interno",
+            "status": 500,
+            "code": "INTERNAL_ERROR",
+        }));
+        let debug = ApiError::from(internal()).with_debug(true).to_json();
+        assert_eq!(debug["detail"], "Erro interno: /var/lib/taskmesh/state.db travado");
+    }
+}
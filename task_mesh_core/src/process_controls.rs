@@ -0,0 +1,273 @@
+//! Prioridade de CPU/IO e afinidade dos processos de tarefas locais
+//!
+//! Tarefas pesadas competem pela CPU com o runtime do próprio orquestrador.
+//! Os metadados [`NICE_KEY`], [`IONICE_KEY`] e [`CPU_AFFINITY_KEY`] preenchem
+//! os campos correspondentes de [`ResourceAllocation`], aplicados ao processo
+//! filho entre o `fork` e o `exec`:
+//!
+//! - Linux: `setpriority` (nice), `ioprio_set` (ionice) e `sched_setaffinity`.
+//! - Outros Unix: apenas nice; ionice e afinidade são ignorados com aviso.
+//! - Windows: melhor esforço. O nice vira a classe de prioridade do processo
+//!   (`IDLE` a `HIGH`); ionice e afinidade são ignorados com aviso.
+//!
+//! Valores inválidos (nice fora de -20..=19, classe desconhecida, núcleos que
+//! o processo não pode usar) são recusados na submissão. Nice negativo e a
+//! classe `realtime` exigem privilégios: sem eles, o processo não é iniciado.
+
+use crate::types::{IoClass, ResourceAllocation, Task, TaskMeshError, TaskMeshResult};
+use crate::validation::Violation;
+
+/// Metadado com o nice do processo (-20..=19)
+pub const NICE_KEY: &str = "nice";
+
+/// Metadado com a classe de IO: `realtime`, `best_effort` ou `idle`
+pub const IONICE_KEY: &str = "ionice";
+
+/// Metadado com os núcleos permitidos, no formato do `taskset -c` (`0,2-3`)
+pub const CPU_AFFINITY_KEY: &str = "cpu_affinity";
+
+/// Verifica os metadados de prioridade e afinidade da tarefa
+///
+/// Os campos das violações recebem `prefix` (ex.: `tasks[2].`).
+pub fn validate(task: &Task, prefix: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut check = |key: &str, outcome: Result<(), String>| {
+        if let Err(message) = outcome {
+            violations.push(Violation { field: format!("{}metadata.{}", prefix, key), message });
+        }
+    };
+    if let Some(value) = task.metadata.get(NICE_KEY) {
+        check(NICE_KEY, parse_nice(value).map(drop));
+    }
+    if let Some(value) = task.metadata.get(IONICE_KEY) {
+        check(IONICE_KEY, parse_io_class(value).map(drop));
+    }
+    if let Some(value) = task.metadata.get(CPU_AFFINITY_KEY) {
+        check(CPU_AFFINITY_KEY, parse_cpu_affinity(value).map(drop));
+    }
+    violations
+}
+
+/// Copia para `resources` os controles definidos nos metadados da tarefa
+pub fn apply(task: &Task, resources: &mut ResourceAllocation) -> TaskMeshResult<()> {
+    if let Some(value) = task.metadata.get(NICE_KEY) {
+        resources.nice = Some(parse_nice(value).map_err(invalid(NICE_KEY))?);
+    }
+    if let Some(value) = task.metadata.get(IONICE_KEY) {
+        resources.ionice_class = Some(parse_io_class(value).map_err(invalid(IONICE_KEY))?);
+    }
+    if let Some(value) = task.metadata.get(CPU_AFFINITY_KEY) {
+        resources.cpu_affinity = Some(parse_cpu_affinity(value).map_err(invalid(CPU_AFFINITY_KEY))?);
+    }
+    Ok(())
+}
+
+fn invalid(key: &'static str) -> impl Fn(String) -> TaskMeshError {
+    move |message| TaskMeshError::Configuration(format!("{} inválido: {}", key, message))
+}
+
+/// Se algum controle de processo foi definido para a tarefa
+pub fn any(resources: &ResourceAllocation) -> bool {
+    resources.nice.is_some() || resources.ionice_class.is_some() || resources.cpu_affinity.is_some()
+}
+
+/// Controles que esta plataforma de fato aplica ao processo
+pub fn supported(resources: &ResourceAllocation) -> (Option<i8>, Option<IoClass>, Option<Vec<usize>>) {
+    if cfg!(target_os = "linux") {
+        (resources.nice, resources.ionice_class, resources.cpu_affinity.clone())
+    } else {
+        (resources.nice, None, None)
+    }
+}
+
+fn parse_nice(value: &str) -> Result<i8, String> {
+    value
+        .trim()
+        .parse::<i8>()
+        .ok()
+        .filter(|nice| (-20..=19).contains(nice))
+        .ok_or_else(|| format!("'{}' fora do intervalo -20..=19", value))
+}
+
+fn parse_io_class(value: &str) -> Result<IoClass, String> {
+    match value.trim() {
+        "realtime" => Ok(IoClass::Realtime),
+        "best_effort" => Ok(IoClass::BestEffort),
+        "idle" => Ok(IoClass::Idle),
+        other => Err(format!("classe '{}' desconhecida (realtime, best_effort ou idle)", other)),
+    }
+}
+
+/// Lê uma lista de núcleos (`0,2-3`), ordenada e sem repetições
+fn parse_cpu_affinity(value: &str) -> Result<Vec<usize>, String> {
+    let available = available_cpus();
+    let mut cores = Vec::new();
+    for part in value.split(',').map(str::trim) {
+        let parse = |core: &str| core.trim().parse::<usize>().map_err(|_| format!("núcleo '{}' inválido", core.trim()));
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            None => (parse(part)?, parse(part)?),
+        };
+        if first > last {
+            return Err(format!("intervalo '{}' invertido", part));
+        }
+        // Limita o intervalo antes de expandi-lo: `0-4000000000` não aloca nada
+        if available.last().map_or(true, |&max| last > max) {
+            return Err(format!("núcleo {} indisponível (disponíveis: {:?})", last, available));
+        }
+        cores.extend(first..=last);
+    }
+    cores.sort_unstable();
+    cores.dedup();
+    match cores.iter().find(|core| !available.contains(core)) {
+        Some(core) => Err(format!("núcleo {} indisponível (disponíveis: {:?})", core, available)),
+        None => Ok(cores),
+    }
+}
+
+/// Núcleos que os processos deste orquestrador podem usar
+#[cfg(target_os = "linux")]
+fn available_cpus() -> Vec<usize> {
+    // SAFETY: `set` é um cpu_set_t válido e o tamanho informado é o dele
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return (0..num_cpus::get()).collect();
+        }
+        (0..libc::CPU_SETSIZE as usize).filter(|&core| libc::CPU_ISSET(core, &set)).collect()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_cpus() -> Vec<usize> {
+    (0..num_cpus::get()).collect()
+}
+
+/// Argumento `ioprio` do `ioprio_set` (nível 4, o padrão do kernel)
+#[cfg(target_os = "linux")]
+fn ioprio(class: IoClass) -> libc::c_long {
+    const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+    let (class, level) = match class {
+        IoClass::Realtime => (1, 4),
+        IoClass::BestEffort => (2, 4),
+        IoClass::Idle => (3, 0),
+    };
+    (class << IOPRIO_CLASS_SHIFT) | level
+}
+
+/// Configura o processo para aplicar os controles de `resources` ao iniciar
+#[cfg(unix)]
+pub(crate) fn configure(cmd: &mut tokio::process::Command, resources: &ResourceAllocation) {
+    let nice = resources.nice;
+    #[cfg(target_os = "linux")]
+    let io_class = resources.ionice_class.map(ioprio);
+    #[cfg(target_os = "linux")]
+    let cpu_set = resources.cpu_affinity.as_ref().map(|cores| {
+        // SAFETY: cpu_set_t zerado é um conjunto vazio válido
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for &core in cores {
+            unsafe { libc::CPU_SET(core, &mut set) };
+        }
+        set
+    });
+    #[cfg(not(target_os = "linux"))]
+    if resources.ionice_class.is_some() || resources.cpu_affinity.is_some() {
+        tracing::warn!("ionice e afinidade de CPU não são suportados nesta plataforma; ignorados");
+    }
+    if supported(resources) == (None, None, None) {
+        return;
+    }
+
+    // SAFETY: entre o fork e o exec só há chamadas de sistema diretas, sem
+    // alocação nem locks
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(nice) = nice {
+                if libc::setpriority(libc::PRIO_PROCESS, 0, nice as libc::c_int) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            #[cfg(target_os = "linux")]
+            {
+                const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+                if let Some(ioprio) = io_class {
+                    if libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0 as libc::c_long, ioprio) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(set) = &cpu_set {
+                    if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Configura o processo para aplicar os controles de `resources` ao iniciar
+#[cfg(windows)]
+pub(crate) fn configure(cmd: &mut tokio::process::Command, resources: &ResourceAllocation) {
+    const IDLE_PRIORITY_CLASS: u32 = 0x0000_0040;
+    const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+    const NORMAL_PRIORITY_CLASS: u32 = 0x0000_0020;
+    const ABOVE_NORMAL_PRIORITY_CLASS: u32 = 0x0000_8000;
+    const HIGH_PRIORITY_CLASS: u32 = 0x0000_0080;
+
+    if let Some(nice) = resources.nice {
+        cmd.creation_flags(match nice {
+            15.. => IDLE_PRIORITY_CLASS,
+            5..=14 => BELOW_NORMAL_PRIORITY_CLASS,
+            -4..=4 => NORMAL_PRIORITY_CLASS,
+            -14..=-5 => ABOVE_NORMAL_PRIORITY_CLASS,
+            _ => HIGH_PRIORITY_CLASS,
+        });
+    }
+    if resources.ionice_class.is_some() || resources.cpu_affinity.is_some() {
+        tracing::warn!("ionice e afinidade de CPU não são suportados no Windows; ignorados");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaskDefinition;
+
+    fn task_with(metadata: &[(&str, &str)]) -> Task {
+        let mut task = Task::new("controlada".to_string(), TaskDefinition::command("true"), vec![]);
+        task.metadata = metadata.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        task
+    }
+
+    #[test]
+    fn test_invalid_controls_reported_at_submission() {
+        let task = task_with(&[(NICE_KEY, "25"), (IONICE_KEY, "urgent"), (CPU_AFFINITY_KEY, "0,100000")]);
+        let fields: Vec<String> = validate(&task, "tasks[0].").into_iter().map(|violation| violation.field).collect();
+        assert_eq!(fields, vec![
+            "tasks[0].metadata.nice",
+            "tasks[0].metadata.ionice",
+            "tasks[0].metadata.cpu_affinity",
+        ]);
+    }
+
+    #[test]
+    fn test_apply_parses_ranges_and_classes() {
+        let first = available_cpus()[0];
+        let task = task_with(&[(NICE_KEY, "10"), (IONICE_KEY, "idle"), (CPU_AFFINITY_KEY, &format!("{0}-{0},{0}", first))]);
+        assert!(validate(&task, "").is_empty());
+
+        let mut resources = ResourceAllocation::default();
+        apply(&task, &mut resources).unwrap();
+        assert_eq!(resources.nice, Some(10));
+        assert_eq!(resources.ionice_class, Some(IoClass::Idle));
+        assert_eq!(resources.cpu_affinity, Some(vec![first]));
+    }
+
+    #[test]
+    fn test_huge_range_rejected_without_expanding() {
+        let error = parse_cpu_affinity("0-4000000000").unwrap_err();
+        assert!(error.contains("núcleo 4000000000 indisponível"), "{}", error);
+        assert!(parse_cpu_affinity(&format!("0-{}", usize::MAX)).is_err());
+    }
+}
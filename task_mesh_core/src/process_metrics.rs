@@ -0,0 +1,279 @@
+//! Coleta de métricas de processos filhos
+//!
+//! Amostra periodicamente a árvore de processos de uma tarefa (o processo
+//! raiz e seus descendentes) e acumula pico de memória residente, tempo de
+//! CPU (utime + stime) e bytes lidos/escritos em disco. No Linux os dados
+//! vêm de `/proc`; nas demais plataformas a coleta é desabilitada e as
+//! métricas ficam zeradas.
+//!
+//! O mesmo `/proc/[pid]/stat` fornece a impressão digital de início usada
+//! para reconhecer, após um reinício, o processo de uma tarefa em execução.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::types::{ExecutionMetrics, ProcessHandle};
+
+/// Amostra instantânea de um processo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessSample {
+    /// Memória residente (bytes)
+    pub rss_bytes: u64,
+    /// Tempo de CPU acumulado (utime + stime)
+    pub cpu_time: Duration,
+    /// Bytes lidos do armazenamento
+    pub read_bytes: u64,
+    /// Bytes escritos no armazenamento
+    pub write_bytes: u64,
+}
+
+/// Acumulador das amostras de uma árvore de processos
+#[derive(Debug, Default)]
+struct SampleAccumulator {
+    /// Último valor visto por PID (contadores são monotônicos por processo)
+    last_seen: HashMap<u32, ProcessSample>,
+    /// Pico da soma de RSS entre todos os processos vivos
+    peak_rss: u64,
+}
+
+impl SampleAccumulator {
+    fn record(&mut self, samples: &[(u32, ProcessSample)]) {
+        let rss: u64 = samples.iter().map(|(_, s)| s.rss_bytes).sum();
+        self.peak_rss = self.peak_rss.max(rss);
+        for (pid, sample) in samples {
+            self.last_seen.insert(*pid, *sample);
+        }
+    }
+
+    fn into_metrics(self, elapsed: Duration) -> ExecutionMetrics {
+        let cpu_time: Duration = self.last_seen.values().map(|s| s.cpu_time).sum();
+        let read: u64 = self.last_seen.values().map(|s| s.read_bytes).sum();
+        let write: u64 = self.last_seen.values().map(|s| s.write_bytes).sum();
+
+        ExecutionMetrics {
+            execution_time: elapsed,
+            // Porcentagem de um núcleo (pode passar de 100 com vários núcleos)
+            cpu_usage: if elapsed.is_zero() {
+                0.0
+            } else {
+                cpu_time.as_secs_f64() / elapsed.as_secs_f64() * 100.0
+            },
+            memory_usage: self.peak_rss,
+            network_io: (0, 0),
+            disk_io: (read, write),
+        }
+    }
+}
+
+/// Amostrador em background de uma árvore de processos
+///
+/// Descartado sem [`ProcessSampler::finish`] (ex.: cancelamento ou timeout da
+/// tarefa), a amostragem é abortada.
+pub struct ProcessSampler {
+    stop: CancellationToken,
+    handle: JoinHandle<ExecutionMetrics>,
+}
+
+impl ProcessSampler {
+    /// Inicia a amostragem do processo `pid` e descendentes
+    pub fn spawn(pid: u32, interval: Duration) -> Self {
+        let stop = CancellationToken::new();
+        let token = stop.clone();
+        let started = Instant::now();
+
+        let handle = tokio::spawn(async move {
+            let mut accumulator = SampleAccumulator::default();
+            let mut ticker = tokio::time::interval(interval.max(Duration::from_millis(5)));
+            loop {
+                accumulator.record(&sample_tree(pid));
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+            }
+            accumulator.into_metrics(started.elapsed())
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Encerra a amostragem e retorna as métricas acumuladas
+    pub async fn finish(mut self) -> ExecutionMetrics {
+        self.stop.cancel();
+        (&mut self.handle).await.unwrap_or_default()
+    }
+}
+
+impl Drop for ProcessSampler {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Amostra o processo raiz e todos os seus descendentes
+#[cfg(target_os = "linux")]
+pub fn sample_tree(root: u32) -> Vec<(u32, ProcessSample)> {
+    let mut pids = vec![root];
+    let mut index = 0;
+    let children = children_map();
+    while index < pids.len() {
+        if let Some(kids) = children.get(&pids[index]) {
+            pids.extend(kids);
+        }
+        index += 1;
+    }
+
+    pids.into_iter()
+        .filter_map(|pid| sample_process(pid).map(|sample| (pid, sample)))
+        .collect()
+}
+
+/// Amostragem indisponível fora do Linux
+#[cfg(not(target_os = "linux"))]
+pub fn sample_tree(_root: u32) -> Vec<(u32, ProcessSample)> {
+    Vec::new()
+}
+
+/// Impressão digital do processo `pid`: instante de início em ticks desde o boot
+///
+/// `None` se o processo não existe ou já terminou (zumbi aguardando o pai).
+#[cfg(target_os = "linux")]
+pub fn start_fingerprint(pid: u32) -> Option<u64> {
+    let stat = parse_stat(&std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)?;
+    (!matches!(stat.state, 'Z' | 'X')).then_some(stat.start_ticks)
+}
+
+/// Impressão digital indisponível fora do Linux
+#[cfg(not(target_os = "linux"))]
+pub fn start_fingerprint(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// Identifica o processo do PID atual, se ainda está vivo
+pub fn process_handle(pid: u32) -> Option<ProcessHandle> {
+    start_fingerprint(pid).map(|start_fingerprint| ProcessHandle { pid, start_fingerprint })
+}
+
+/// O processo de `handle` ainda está vivo (e o PID não foi reutilizado)
+pub fn is_alive(handle: &ProcessHandle) -> bool {
+    start_fingerprint(handle.pid) == Some(handle.start_fingerprint)
+}
+
+/// Mapa PID pai -> filhos a partir de /proc/*/stat
+#[cfg(target_os = "linux")]
+fn children_map() -> HashMap<u32, Vec<u32>> {
+    let mut map: HashMap<u32, Vec<u32>> = HashMap::new();
+    let Ok(entries) = std::fs::read_dir("/proc") else { return map };
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else { continue };
+        let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) else { continue };
+        if let Some(ppid) = parse_stat(&stat).map(|fields| fields.ppid) {
+            map.entry(ppid).or_default().push(pid);
+        }
+    }
+    map
+}
+
+/// Campos relevantes de /proc/[pid]/stat
+#[derive(Debug, PartialEq, Eq)]
+struct StatFields {
+    state: char,
+    ppid: u32,
+    utime_ticks: u64,
+    stime_ticks: u64,
+    start_ticks: u64,
+}
+
+/// Interpreta /proc/[pid]/stat (o nome do comando pode conter espaços e parênteses)
+fn parse_stat(stat: &str) -> Option<StatFields> {
+    let after_comm = &stat[stat.rfind(')')? + 2..];
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    // Após o comm: state(0) ppid(1) ... utime(11) stime(12) ... starttime(19)
+    Some(StatFields {
+        state: fields.first()?.chars().next()?,
+        ppid: fields.get(1)?.parse().ok()?,
+        utime_ticks: fields.get(11)?.parse().ok()?,
+        stime_ticks: fields.get(12)?.parse().ok()?,
+        start_ticks: fields.get(19)?.parse().ok()?,
+    })
+}
+
+/// Extrai um campo numérico "Chave: valor" de /proc/[pid]/status ou /proc/[pid]/io
+fn parse_kv(content: &str, key: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .and_then(|value| value.split_whitespace().next()?.parse().ok())
+}
+
+#[cfg(target_os = "linux")]
+fn sample_process(pid: u32) -> Option<ProcessSample> {
+    /// Ticks por segundo de CLK_TCK (100 em praticamente todos os kernels Linux)
+    const CLOCK_TICKS: u64 = 100;
+
+    let stat = parse_stat(&std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)?;
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+    // /proc/[pid]/io pode não ser legível (outro usuário); nesse caso, I/O zerado
+    let io = std::fs::read_to_string(format!("/proc/{}/io", pid)).unwrap_or_default();
+
+    let ticks = stat.utime_ticks + stat.stime_ticks;
+    Some(ProcessSample {
+        rss_bytes: parse_kv(&status, "VmRSS").unwrap_or(0) * 1024,
+        cpu_time: Duration::from_millis(ticks * 1000 / CLOCK_TICKS),
+        read_bytes: parse_kv(&io, "read_bytes").unwrap_or(0),
+        write_bytes: parse_kv(&io, "write_bytes").unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_with_spaces_in_comm() {
+        let stat = "1234 (my (weird) cmd) S 42 1234 1234 0 -1 4194304 100 0 0 0 250 30 0 0 20 0 1 0 100 1000 50";
+        let fields = parse_stat(stat).unwrap();
+        assert_eq!(fields, StatFields { state: 'S', ppid: 42, utime_ticks: 250, stime_ticks: 30, start_ticks: 100 });
+    }
+
+    #[test]
+    fn test_parse_kv() {
+        let status = "Name:\tpython\nVmHWM:\t  204800 kB\nVmRSS:\t  102400 kB\n";
+        assert_eq!(parse_kv(status, "VmRSS"), Some(102400));
+        assert_eq!(parse_kv("read_bytes: 4096\nwrite_bytes: 8192\n", "write_bytes"), Some(8192));
+        assert_eq!(parse_kv(status, "VmSwap"), None);
+    }
+
+    #[test]
+    fn test_accumulator_peak_and_counters() {
+        let mut acc = SampleAccumulator::default();
+        let sample = |rss, cpu_ms, read| ProcessSample {
+            rss_bytes: rss,
+            cpu_time: Duration::from_millis(cpu_ms),
+            read_bytes: read,
+            write_bytes: 0,
+        };
+        acc.record(&[(1, sample(100, 10, 5)), (2, sample(200, 20, 0))]);
+        acc.record(&[(1, sample(150, 50, 10))]);
+
+        let metrics = acc.into_metrics(Duration::from_millis(100));
+        assert_eq!(metrics.memory_usage, 300);
+        assert_eq!(metrics.disk_io, (10, 0));
+        assert!((metrics.cpu_usage - 70.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_sampler_stops_sampling() {
+        let sampler = ProcessSampler::spawn(std::process::id(), Duration::from_millis(5));
+        let task = sampler.handle.abort_handle();
+        drop(sampler);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !task.is_finished() {
+            assert!(Instant::now() < deadline, "amostragem continuou após o drop");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+}
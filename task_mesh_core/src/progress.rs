@@ -0,0 +1,158 @@
+//! Progresso reportado por tarefas em execução
+//!
+//! Tarefas de comando e script escrevem linhas JSON como
+//! `{"taskmesh_progress": {"percent": 45, "message": "shard 9/20"}}` no
+//! arquivo indicado por [`PROGRESS_ENV`]; funções Rust recebem um
+//! [`ProgressReporter`] no contexto de execução. O executor acompanha as
+//! atualizações e grava a mais recente no status `Running`, com limite de
+//! frequência, publicando também eventos `TaskProgress`.
+
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::watch;
+
+use crate::types::*;
+
+/// Variável de ambiente com o arquivo de progresso da tarefa
+pub const PROGRESS_ENV: &str = "TASKMESH_PROGRESS_FILE";
+
+/// Linha do arquivo de progresso
+#[derive(Debug, Deserialize)]
+struct ProgressLine {
+    taskmesh_progress: ProgressUpdate,
+}
+
+/// Atualização de progresso enviada pela tarefa
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ProgressUpdate {
+    /// Porcentagem concluída (limitada a 0..=100)
+    #[serde(default)]
+    pub percent: Option<f64>,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Tempo restante estimado (segundos)
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
+}
+
+impl ProgressUpdate {
+    /// Progresso registrado no status, com o horário da atualização
+    pub fn into_progress(self, updated_at: SystemTime) -> TaskProgress {
+        TaskProgress {
+            percent: self.percent.map(|percent| percent.clamp(0.0, 100.0).round() as u8),
+            message: self.message,
+            eta_secs: self.eta_secs,
+            updated_at,
+        }
+    }
+}
+
+/// Interpreta uma linha do arquivo de progresso
+///
+/// Linhas que não são JSON com a chave `taskmesh_progress` são ignoradas.
+pub fn parse_line(line: &str) -> Option<ProgressUpdate> {
+    serde_json::from_str::<ProgressLine>(line.trim())
+        .ok()
+        .map(|line| line.taskmesh_progress)
+}
+
+/// Handle para uma tarefa reportar o próprio progresso
+///
+/// Só a atualização mais recente é mantida; o executor decide quando gravá-la.
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    tx: Arc<watch::Sender<Option<TaskProgress>>>,
+}
+
+impl ProgressReporter {
+    /// Cria o reporter e o receptor acompanhado pelo executor
+    pub fn channel() -> (Self, watch::Receiver<Option<TaskProgress>>) {
+        let (tx, rx) = watch::channel(None);
+        (Self { tx: Arc::new(tx) }, rx)
+    }
+
+    /// Reporta o progresso atual, substituindo o anterior
+    pub fn report(&self, update: ProgressUpdate) {
+        self.tx.send_replace(Some(update.into_progress(SystemTime::now())));
+    }
+}
+
+/// Arquivo de progresso de uma execução, removido ao ser descartado
+#[derive(Debug)]
+pub struct ProgressFile {
+    path: PathBuf,
+    /// Posição já lida
+    offset: u64,
+    /// Linha ainda incompleta no fim do arquivo
+    partial: String,
+}
+
+impl ProgressFile {
+    /// Cria um arquivo vazio para a tarefa no diretório temporário do sistema
+    pub async fn create(task_id: &TaskId) -> std::io::Result<Self> {
+        let path = std::env::temp_dir().join(format!("taskmesh-progress-{}", task_id));
+        tokio::fs::write(&path, b"").await?;
+        Ok(Self { path, offset: 0, partial: String::new() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Atualização mais recente entre as linhas completas escritas desde a
+    /// leitura anterior
+    pub async fn read_latest(&mut self) -> Option<ProgressUpdate> {
+        let mut file = tokio::fs::File::open(&self.path).await.ok()?;
+        file.seek(SeekFrom::Start(self.offset)).await.ok()?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).await.ok()?;
+        self.offset += bytes.len() as u64;
+        self.partial.push_str(&String::from_utf8_lossy(&bytes));
+
+        let complete = self.partial.rfind('\n')?;
+        let lines: String = self.partial.drain(..=complete).collect();
+        lines.lines().filter_map(parse_line).last()
+    }
+}
+
+impl Drop for ProgressFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_clamps_percent_and_ignores_other_output() {
+        let update = parse_line(r#"{"taskmesh_progress": {"percent": 145.2, "message": "quase"}}"#).unwrap();
+        let progress = update.into_progress(SystemTime::UNIX_EPOCH);
+        assert_eq!((progress.percent, progress.message.as_deref()), (Some(100), Some("quase")));
+        assert_eq!(parse_line("progresso: 45%"), None);
+        assert_eq!(parse_line(r#"{"percent": 45}"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_latest_waits_for_complete_lines() {
+        let mut file = ProgressFile::create(&TaskId::new_v4()).await.unwrap();
+        let line = |percent: u32| format!("{{\"taskmesh_progress\": {{\"percent\": {}}}}}\n", percent);
+        let partial = line(30);
+        tokio::fs::write(file.path(), format!("{}{}", line(10), &partial[..10])).await.unwrap();
+        assert_eq!(file.read_latest().await.unwrap().percent, Some(10.0));
+
+        let mut rest = std::fs::OpenOptions::new().append(true).open(file.path()).unwrap();
+        std::io::Write::write_all(&mut rest, partial[10..].as_bytes()).unwrap();
+        assert_eq!(file.read_latest().await.unwrap().percent, Some(30.0));
+        assert_eq!(file.read_latest().await, None);
+
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+    }
+}
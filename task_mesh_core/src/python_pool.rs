@@ -0,0 +1,496 @@
+//! Pool de interpretadores Python pré-iniciados para `PythonScript`
+//!
+//! Cada runner é um processo Python de longa duração executando [`DRIVER`]:
+//! recebe pedidos JSON, um por linha, pelo stdin e responde uma linha JSON
+//! pelo stdout. O script roda em um namespace de módulo novo (`__main__`),
+//! com `sys.argv`, ambiente e diretório de trabalho da execução aplicados
+//! apenas durante ela. O protocolo corre em cópias privadas dos fds 0 e 1:
+//! durante a execução os fds 1 e 2 apontam para arquivos temporários, de modo
+//! que a saída do script, de seus subprocessos e de extensões em C é
+//! capturada e devolvida na resposta sem se misturar ao protocolo. Módulos
+//! já importados ficam em cache entre execuções, e é daí que vem o ganho
+//! sobre iniciar um interpretador por tarefa.
+//!
+//! - Um runner é reciclado após `max_tasks_per_process` execuções.
+//! - Um runner que cai (o script chama `os._exit`, estoura a memória...)
+//!   falha apenas a tarefa em curso; um novo é iniciado no lugar.
+//! - Cancelamento e timeout encerram o runner, que também é substituído.
+//! - [`PythonPool::health_check`] envia `ping` aos runners ociosos e
+//!   substitui os que não respondem.
+//!
+//! Sem runner disponível (todos ocupados e o pool no tamanho máximo),
+//! [`PythonPool::run`] devolve `None` e o executor volta a iniciar um
+//! interpretador por tarefa.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::types::*;
+
+/// Script executado por cada runner
+pub const DRIVER: &str = r#"
+import builtins, json, os, sys, tempfile, traceback
+
+# O protocolo usa cópias privadas dos fds 0 e 1; os fds 0, 1 e 2 ficam para a
+# tarefa, de modo que subprocessos, os.write e extensões em C não os alcancem
+requests = os.fdopen(os.dup(0), "r")
+channel = os.fdopen(os.dup(1), "w")
+devnull = os.open(os.devnull, os.O_RDWR)
+for fd in (0, 1, 2):
+    os.dup2(devnull, fd)
+
+def flush():
+    for stream in (sys.stdout, sys.stderr):
+        try:
+            stream.flush()
+        except Exception:
+            pass
+
+def run(request):
+    stdout, stderr = tempfile.TemporaryFile(), tempfile.TemporaryFile()
+    saved_env, saved_argv, saved_cwd = dict(os.environ), sys.argv, os.getcwd()
+    saved_streams = sys.stdout, sys.stderr
+    exit_code = 0
+    try:
+        os.environ.clear()
+        os.environ.update(request["env"])
+        os.chdir(request["cwd"])
+        sys.argv = ["<taskmesh>"] + request["args"]
+        namespace = {"__name__": "__main__", "__builtins__": builtins}
+        os.dup2(stdout.fileno(), 1)
+        os.dup2(stderr.fileno(), 2)
+        try:
+            exec(compile(request["script"], "<taskmesh>", "exec"), namespace)
+        except SystemExit as exit:
+            if exit.code is None or isinstance(exit.code, int):
+                exit_code = exit.code or 0
+            else:
+                print(exit.code, file=sys.stderr)
+                exit_code = 1
+        except BaseException:
+            traceback.print_exc()
+            exit_code = 1
+    finally:
+        flush()
+        sys.stdout, sys.stderr = saved_streams
+        flush()
+        os.dup2(devnull, 1)
+        os.dup2(devnull, 2)
+        os.environ.clear()
+        os.environ.update(saved_env)
+        sys.argv = saved_argv
+        os.chdir(saved_cwd)
+    stdout.seek(0)
+    stderr.seek(0)
+    return {
+        "exit_code": exit_code,
+        "stdout": stdout.read().decode("utf-8", "replace"),
+        "stderr": stderr.read().decode("utf-8", "replace"),
+    }
+
+for line in requests:
+    request = json.loads(line)
+    response = {"pong": True} if request["op"] == "ping" else run(request)
+    channel.write(json.dumps(response) + "\n")
+    channel.flush()
+"#;
+
+/// Espera máxima pela resposta de um `ping`
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn default_size() -> usize {
+    2
+}
+
+fn default_max_tasks_per_process() -> u32 {
+    100
+}
+
+fn default_health_check_interval_ms() -> u64 {
+    30_000
+}
+
+/// Configuração do pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PythonPoolConfig {
+    /// Número máximo de runners
+    #[serde(default = "default_size")]
+    pub size: usize,
+    /// Execuções por runner antes de reciclá-lo
+    #[serde(default = "default_max_tasks_per_process")]
+    pub max_tasks_per_process: u32,
+    /// Intervalo entre verificações dos runners ociosos
+    #[serde(default = "default_health_check_interval_ms")]
+    pub health_check_interval_ms: u64,
+}
+
+impl Default for PythonPoolConfig {
+    fn default() -> Self {
+        Self {
+            size: default_size(),
+            max_tasks_per_process: default_max_tasks_per_process(),
+            health_check_interval_ms: default_health_check_interval_ms(),
+        }
+    }
+}
+
+impl PythonPoolConfig {
+    pub fn health_check_interval(&self) -> Duration {
+        Duration::from_millis(self.health_check_interval_ms)
+    }
+}
+
+/// Contadores do pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PythonPoolStats {
+    /// Runners existentes (ociosos e ocupados)
+    pub live: usize,
+    pub idle: usize,
+    /// Runners iniciados desde a criação do pool
+    pub spawned: u64,
+    /// Runners encerrados por atingir `max_tasks_per_process`
+    pub recycled: u64,
+    /// Runners descartados por queda, cancelamento, timeout ou health check
+    pub discarded: u64,
+}
+
+/// Pedido ao driver
+#[derive(Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Request<'a> {
+    Ping,
+    Run {
+        script: &'a str,
+        args: &'a [String],
+        env: &'a HashMap<String, String>,
+        cwd: &'a str,
+    },
+}
+
+#[derive(Deserialize)]
+struct Pong {
+    pong: bool,
+}
+
+#[derive(Deserialize)]
+struct RunResponse {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+/// Processo Python executando o driver
+struct PythonRunner {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    /// Execuções concluídas
+    tasks_run: u32,
+}
+
+impl PythonRunner {
+    fn spawn(interpreter: &str) -> std::io::Result<Self> {
+        let mut child = Command::new(interpreter)
+            .arg("-u")
+            .arg("-c")
+            .arg(DRIVER)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin do runner");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout do runner"));
+        Ok(Self { child, stdin, stdout, tasks_run: 0 })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Envia um pedido e aguarda a linha de resposta
+    async fn request<R: DeserializeOwned>(&mut self, request: &Request<'_>) -> std::io::Result<R> {
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        self.stdin.write_all(&line).await?;
+        self.stdin.flush().await?;
+
+        let mut response = String::new();
+        if self.stdout.read_line(&mut response).await? == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "runner encerrado"));
+        }
+        Ok(serde_json::from_str(&response)?)
+    }
+}
+
+/// Pool de runners Python
+pub struct PythonPool {
+    config: PythonPoolConfig,
+    interpreter: String,
+    idle: std::sync::Mutex<Vec<PythonRunner>>,
+    /// Runners existentes, ociosos ou em uso
+    live: AtomicUsize,
+    spawned: AtomicU64,
+    recycled: AtomicU64,
+    discarded: AtomicU64,
+}
+
+impl PythonPool {
+    pub fn new(config: PythonPoolConfig, interpreter: impl Into<String>) -> Self {
+        Self {
+            config,
+            interpreter: interpreter.into(),
+            idle: std::sync::Mutex::new(Vec::new()),
+            live: AtomicUsize::new(0),
+            spawned: AtomicU64::new(0),
+            recycled: AtomicU64::new(0),
+            discarded: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &PythonPoolConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> PythonPoolStats {
+        PythonPoolStats {
+            live: self.live.load(Ordering::SeqCst),
+            idle: self.idle.lock().unwrap().len(),
+            spawned: self.spawned.load(Ordering::SeqCst),
+            recycled: self.recycled.load(Ordering::SeqCst),
+            discarded: self.discarded.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Inicia runners ociosos até o tamanho do pool
+    pub fn warm(&self) {
+        while let Some(runner) = self.spawn_runner() {
+            self.idle.lock().unwrap().push(runner);
+        }
+    }
+
+    /// Executa o script em um runner do pool
+    ///
+    /// `env` é o ambiente completo da execução (substitui o do runner durante
+    /// ela). `None` quando não há runner disponível.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn run(
+        &self,
+        task_id: &TaskId,
+        script: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+        cwd: &str,
+        cancel_token: &CancellationToken,
+        time_limit: Duration,
+    ) -> Option<TaskMeshResult<TaskResult>> {
+        let mut runner = self.checkout()?;
+        let request = Request::Run { script, args, env, cwd };
+
+        let outcome = tokio::select! {
+            _ = cancel_token.cancelled() => {
+                Err(TaskMeshError::ExecutionError("Tarefa cancelada".to_string()))
+            }
+            result = timeout(time_limit, runner.request::<RunResponse>(&request)) => match result {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(e)) => Err(TaskMeshError::ExecutionError(format!("Runner Python caiu: {}", e))),
+                Err(_) => Err(TaskMeshError::ExecutionTimeout(*task_id)),
+            },
+        };
+
+        Some(match outcome {
+            Ok(response) => {
+                runner.tasks_run += 1;
+                self.checkin(runner);
+                Ok(TaskResult {
+                    exit_code: response.exit_code,
+                    stdout: response.stdout,
+                    stderr: response.stderr,
+                    output_data: None,
+                    metrics: ExecutionMetrics::default(),
+                    log_ref: None,
+                })
+            }
+            Err(error) => {
+                self.discard(runner);
+                self.warm();
+                Err(error)
+            }
+        })
+    }
+
+    /// Envia `ping` aos runners ociosos e substitui os que não respondem
+    ///
+    /// Retorna o número de runners substituídos.
+    pub async fn health_check(&self) -> usize {
+        let runners = std::mem::take(&mut *self.idle.lock().unwrap());
+        let mut failed = 0;
+        for mut runner in runners {
+            match timeout(PING_TIMEOUT, runner.request::<Pong>(&Request::Ping)).await {
+                Ok(Ok(Pong { pong: true })) => self.idle.lock().unwrap().push(runner),
+                _ => {
+                    failed += 1;
+                    self.discard(runner);
+                }
+            }
+        }
+        if failed > 0 {
+            warn!("{} runners Python sem resposta substituídos", failed);
+        }
+        self.warm();
+        failed
+    }
+
+    /// Encerra os runners ociosos
+    pub fn close(&self) {
+        let runners = std::mem::take(&mut *self.idle.lock().unwrap());
+        self.live.fetch_sub(runners.len(), Ordering::SeqCst);
+    }
+
+    /// Runner ocioso vivo ou um novo, se o pool ainda não está cheio
+    fn checkout(&self) -> Option<PythonRunner> {
+        loop {
+            let Some(mut runner) = self.idle.lock().unwrap().pop() else { break };
+            if runner.is_alive() {
+                return Some(runner);
+            }
+            self.discard(runner);
+        }
+        self.spawn_runner()
+    }
+
+    /// Inicia um runner se o pool ainda não está no tamanho máximo
+    fn spawn_runner(&self) -> Option<PythonRunner> {
+        let size = self.config.size;
+        self.live
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |live| (live < size).then_some(live + 1))
+            .ok()?;
+        match PythonRunner::spawn(&self.interpreter) {
+            Ok(runner) => {
+                self.spawned.fetch_add(1, Ordering::SeqCst);
+                debug!("Runner Python iniciado (pid {:?})", runner.child.id());
+                Some(runner)
+            }
+            Err(e) => {
+                self.live.fetch_sub(1, Ordering::SeqCst);
+                warn!("Falha ao iniciar runner Python com {}: {}", self.interpreter, e);
+                None
+            }
+        }
+    }
+
+    /// Devolve o runner ao pool ou o recicla após `max_tasks_per_process`
+    fn checkin(&self, runner: PythonRunner) {
+        if runner.tasks_run >= self.config.max_tasks_per_process {
+            info!("Runner Python reciclado após {} execuções", runner.tasks_run);
+            self.recycled.fetch_add(1, Ordering::SeqCst);
+            self.live.fetch_sub(1, Ordering::SeqCst);
+            drop(runner);
+            self.warm();
+        } else {
+            self.idle.lock().unwrap().push(runner);
+        }
+    }
+
+    /// Encerra o runner (o processo morre ao ser descartado)
+    fn discard(&self, runner: PythonRunner) {
+        self.discarded.fetch_add(1, Ordering::SeqCst);
+        self.live.fetch_sub(1, Ordering::SeqCst);
+        drop(runner);
+    }
+}
+
+impl std::fmt::Debug for PythonPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PythonPool")
+            .field("config", &self.config)
+            .field("interpreter", &self.interpreter)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    async fn run(pool: &PythonPool, script: &str, env: &HashMap<String, String>) -> TaskMeshResult<TaskResult> {
+        let cwd = std::env::temp_dir().to_string_lossy().to_string();
+        pool.run(&TaskId::new_v4(), script, &[], env, &cwd, &CancellationToken::new(), Duration::from_secs(10))
+            .await
+            .expect("runner disponível")
+    }
+
+    #[tokio::test]
+    async fn test_runner_recycled_after_max_tasks() {
+        let config = PythonPoolConfig { size: 1, max_tasks_per_process: 2, ..PythonPoolConfig::default() };
+        let pool = PythonPool::new(config, "python3");
+        let env = HashMap::new();
+
+        let mut pids = Vec::new();
+        for _ in 0..4 {
+            let result = run(&pool, "import os; print(os.getpid())", &env).await.unwrap();
+            assert_eq!(result.exit_code, 0, "{}", result.stderr);
+            pids.push(result.stdout.trim().to_string());
+        }
+        assert_eq!(pids[0], pids[1]);
+        assert_eq!(pids[2], pids[3]);
+        assert_ne!(pids[1], pids[2]);
+        let stats = pool.stats();
+        assert_eq!((stats.recycled, stats.spawned, stats.live), (2, 3, 1));
+    }
+
+    #[tokio::test]
+    async fn test_crashed_runner_fails_only_its_task_and_is_respawned() {
+        let config = PythonPoolConfig { size: 1, ..PythonPoolConfig::default() };
+        let pool = PythonPool::new(config, "python3");
+        pool.warm();
+
+        let crashed = run(&pool, "import os; os._exit(7)", &HashMap::new()).await;
+        assert!(matches!(crashed, Err(TaskMeshError::ExecutionError(ref e)) if e.contains("Runner Python caiu")), "{:?}", crashed);
+        assert_eq!((pool.stats().discarded, pool.stats().idle), (1, 1));
+
+        // O substituto executa normalmente, com o ambiente apenas desta execução
+        let env = HashMap::from([("GREETING".to_string(), "oi".to_string())]);
+        let result = run(&pool, "import os, sys; print(os.environ['GREETING']); sys.exit(3)", &env).await.unwrap();
+        assert_eq!((result.exit_code, result.stdout.as_str()), (3, "oi\n"));
+        let result = run(&pool, "import os; print(os.environ.get('GREETING'))", &HashMap::new()).await.unwrap();
+        assert_eq!(result.stdout, "None\n");
+        assert_eq!(pool.health_check().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fd_level_output_does_not_corrupt_the_protocol() {
+        let config = PythonPoolConfig { size: 1, ..PythonPoolConfig::default() };
+        let pool = PythonPool::new(config, "python3");
+        let script = "import os, subprocess, sys\n\
+                      print('py')\n\
+                      subprocess.run(['echo', 'hi'])\n\
+                      os.write(1, b'raw\\n')\n\
+                      os.write(2, b'err\\n')\n\
+                      subprocess.run(['cat'])\n";
+        let result = run(&pool, script, &HashMap::new()).await.unwrap();
+        assert_eq!((result.exit_code, result.stdout.as_str(), result.stderr.as_str()), (0, "py\nhi\nraw\n", "err\n"));
+
+        // O runner segue respondendo e não herda a saída da execução anterior
+        let result = run(&pool, "print('again')", &HashMap::new()).await.unwrap();
+        assert_eq!(result.stdout, "again\n");
+        assert_eq!(pool.stats().discarded, 0);
+
+        let task_id = TaskId::new_v4();
+        let cwd = std::env::temp_dir().to_string_lossy().to_string();
+        let timed_out = pool
+            .run(&task_id, "import time; time.sleep(5)", &[], &HashMap::new(), &cwd, &CancellationToken::new(), Duration::from_millis(200))
+            .await
+            .unwrap();
+        assert!(matches!(timed_out, Err(TaskMeshError::ExecutionTimeout(id)) if id == task_id), "{:?}", timed_out);
+    }
+}
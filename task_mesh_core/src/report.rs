@@ -0,0 +1,401 @@
+//! Relatórios de execução (timeline/Gantt) a partir do StateStore
+//!
+//! Dado um conjunto de tarefas já executadas, monta uma linha do tempo com
+//! início/fim/duração de cada tarefa, eficiência de paralelismo e as tarefas
+//! mais lentas. O relatório pode ser renderizado em JSON, Gantt ASCII ou HTML
+//! com SVG embutido.
+
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Quantidade de tarefas listadas no ranking de mais lentas
+const SLOWEST_TASKS_LIMIT: usize = 5;
+
+/// Largura (em caracteres) da área do gráfico Gantt ASCII
+const ASCII_GANTT_WIDTH: usize = 60;
+
+/// Formato de saída do relatório
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportFormat {
+    /// JSON estruturado
+    Json,
+    /// Gráfico Gantt em texto
+    AsciiGantt,
+    /// Página HTML com SVG embutido
+    Html,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = TaskMeshError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "ascii" | "gantt" => Ok(ReportFormat::AsciiGantt),
+            "html" => Ok(ReportFormat::Html),
+            other => Err(TaskMeshError::Configuration(format!(
+                "Formato de relatório desconhecido: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Entrada da linha do tempo de uma tarefa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTimelineEntry {
+    /// ID da tarefa
+    pub task_id: TaskId,
+    /// Nome da tarefa
+    pub name: String,
+    /// Status final (texto)
+    pub status: String,
+    /// Início em milissegundos relativos ao início do relatório
+    pub start_offset_ms: u64,
+    /// Fim em milissegundos relativos ao início do relatório
+    pub end_offset_ms: u64,
+    /// Duração em milissegundos
+    pub duration_ms: u64,
+    /// Worker que executou a tarefa (se conhecido)
+    pub worker: Option<String>,
+    /// Camada de execução (se conhecida)
+    pub layer: Option<String>,
+    /// Número de retries
+    pub retries: u32,
+    /// Número de execuções especulativas (hedge)
+    pub hedges: u32,
+}
+
+/// Relatório de timeline de uma execução
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineReport {
+    /// Início da primeira tarefa (ms desde UNIX_EPOCH)
+    pub started_at_ms: u64,
+    /// Tempo total de parede em milissegundos
+    pub wall_time_ms: u64,
+    /// Soma das durações das tarefas em milissegundos
+    pub total_task_time_ms: u64,
+    /// Eficiência de paralelismo (soma das durações / tempo de parede)
+    pub parallelism_efficiency: f64,
+    /// Tarefas na ordem de início
+    pub entries: Vec<TaskTimelineEntry>,
+    /// IDs das tarefas mais lentas (ordem decrescente de duração)
+    pub slowest_tasks: Vec<TaskId>,
+    /// Total de retries
+    pub total_retries: u32,
+    /// Total de hedges
+    pub total_hedges: u32,
+    /// Tarefas sem dados de execução (nunca iniciadas)
+    pub missing_tasks: Vec<TaskId>,
+}
+
+impl TimelineReport {
+    /// Monta o relatório a partir do StateStore
+    pub async fn build(state_store: &dyn StateStore, task_ids: &[TaskId]) -> TaskMeshResult<Self> {
+        let mut spans = Vec::new();
+        let mut missing_tasks = Vec::new();
+
+        for task_id in task_ids {
+            let task = state_store.get_task(task_id).await?;
+            let status = state_store.get_task_status(task_id).await?;
+            let metrics = state_store.get_metrics(task_id).await?;
+            let attempts = state_store.list_attempts(task_id).await?;
+
+            let (started_at, finished_at, retries, worker) = match &status {
+                TaskStatus::Completed { started_at, completed_at, .. } => {
+                    (*started_at, *completed_at, 0, None)
+                }
+                TaskStatus::Failed { started_at, failed_at, retry_count, .. } => {
+                    (*started_at, *failed_at, *retry_count, None)
+                }
+                TaskStatus::Running { started_at, worker_id, .. } => {
+                    (*started_at, SystemTime::now(), 0, Some(worker_id.clone()))
+                }
+                _ => {
+                    missing_tasks.push(*task_id);
+                    continue;
+                }
+            };
+            // Tentativas registradas valem mais que o contador do status final
+            let retries = retries.max(attempts.len().saturating_sub(1) as u32);
+            let worker = worker.or_else(|| attempts.last().and_then(|attempt| attempt.worker_id.clone()));
+
+            // Preferir a duração medida pelo executor quando disponível
+            let measured = finished_at.duration_since(started_at).unwrap_or_default();
+            let duration = metrics
+                .map(|m| m.execution_time)
+                .filter(|d| !d.is_zero())
+                .unwrap_or(measured);
+
+            let metadata = task.as_ref().map(|t| &t.metadata);
+            let meta = |key: &str| metadata.and_then(|m| m.get(key)).cloned();
+
+            spans.push((
+                started_at,
+                TaskTimelineEntry {
+                    task_id: *task_id,
+                    name: task.as_ref().map(|t| t.name.clone()).unwrap_or_else(|| task_id.to_string()),
+                    status: status_label(&status).to_string(),
+                    start_offset_ms: 0,
+                    end_offset_ms: 0,
+                    duration_ms: duration.as_millis() as u64,
+                    worker: worker.or_else(|| meta("worker_id")),
+                    layer: meta("layer"),
+                    retries: retries.max(meta("retry_count").and_then(|v| v.parse().ok()).unwrap_or(0)),
+                    hedges: meta("hedge_count").and_then(|v| v.parse().ok()).unwrap_or(0),
+                },
+            ));
+        }
+
+        spans.sort_by_key(|(started_at, _)| *started_at);
+
+        let origin = spans.first().map(|(s, _)| *s).unwrap_or(UNIX_EPOCH);
+        let mut wall_end = 0u64;
+        let mut entries = Vec::with_capacity(spans.len());
+        for (started_at, mut entry) in spans {
+            entry.start_offset_ms = started_at.duration_since(origin).unwrap_or_default().as_millis() as u64;
+            entry.end_offset_ms = entry.start_offset_ms + entry.duration_ms;
+            wall_end = wall_end.max(entry.end_offset_ms);
+            entries.push(entry);
+        }
+
+        let total_task_time_ms: u64 = entries.iter().map(|e| e.duration_ms).sum();
+        let parallelism_efficiency = if wall_end > 0 {
+            total_task_time_ms as f64 / wall_end as f64
+        } else {
+            0.0
+        };
+
+        let mut by_duration: Vec<&TaskTimelineEntry> = entries.iter().collect();
+        by_duration.sort_by(|a, b| b.duration_ms.cmp(&a.duration_ms));
+        let slowest_tasks = by_duration
+            .iter()
+            .take(SLOWEST_TASKS_LIMIT)
+            .map(|e| e.task_id)
+            .collect();
+
+        Ok(Self {
+            started_at_ms: origin.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            wall_time_ms: wall_end,
+            total_task_time_ms,
+            parallelism_efficiency,
+            total_retries: entries.iter().map(|e| e.retries).sum(),
+            total_hedges: entries.iter().map(|e| e.hedges).sum(),
+            entries,
+            slowest_tasks,
+            missing_tasks,
+        })
+    }
+
+    /// Tempo total de parede
+    pub fn wall_time(&self) -> Duration {
+        Duration::from_millis(self.wall_time_ms)
+    }
+
+    /// Renderiza o relatório no formato solicitado
+    pub fn render(&self, format: ReportFormat) -> TaskMeshResult<String> {
+        match format {
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ReportFormat::AsciiGantt => Ok(self.render_ascii()),
+            ReportFormat::Html => Ok(self.render_html()),
+        }
+    }
+
+    /// Renderiza um gráfico Gantt em texto
+    fn render_ascii(&self) -> String {
+        let mut out = String::new();
+        let name_width = self.entries.iter().map(|e| e.name.len()).max().unwrap_or(4).max(4);
+        let scale = self.wall_time_ms.max(1) as f64 / ASCII_GANTT_WIDTH as f64;
+
+        let _ = writeln!(
+            out,
+            "{:<name_width$} |{}| duração",
+            "task",
+            "-".repeat(ASCII_GANTT_WIDTH),
+        );
+        for entry in &self.entries {
+            let start = ((entry.start_offset_ms as f64 / scale) as usize).min(ASCII_GANTT_WIDTH);
+            let end = ((entry.end_offset_ms as f64 / scale).ceil() as usize)
+                .clamp(start + 1, ASCII_GANTT_WIDTH.max(start + 1));
+            let bar = format!(
+                "{}{}{}",
+                " ".repeat(start),
+                "#".repeat(end - start),
+                " ".repeat(ASCII_GANTT_WIDTH.saturating_sub(end)),
+            );
+            let _ = writeln!(out, "{:<name_width$} |{}| {}ms", entry.name, bar, entry.duration_ms);
+        }
+        let _ = writeln!(
+            out,
+            "wall: {}ms, soma: {}ms, eficiência: {:.2}, retries: {}, hedges: {}",
+            self.wall_time_ms,
+            self.total_task_time_ms,
+            self.parallelism_efficiency,
+            self.total_retries,
+            self.total_hedges,
+        );
+        out
+    }
+
+    /// Renderiza uma página HTML com o Gantt em SVG
+    fn render_html(&self) -> String {
+        const ROW_HEIGHT: usize = 24;
+        const LABEL_WIDTH: usize = 200;
+        const CHART_WIDTH: f64 = 800.0;
+
+        let scale = CHART_WIDTH / self.wall_time_ms.max(1) as f64;
+        let height = ROW_HEIGHT * (self.entries.len() + 1);
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}">"#,
+            LABEL_WIDTH + CHART_WIDTH as usize + 80,
+            height,
+        );
+        for (row, entry) in self.entries.iter().enumerate() {
+            let y = row * ROW_HEIGHT;
+            let x = LABEL_WIDTH as f64 + entry.start_offset_ms as f64 * scale;
+            let width = (entry.duration_ms as f64 * scale).max(1.0);
+            let color = if entry.status == "completed" { "#4caf50" } else { "#f44336" };
+            let _ = write!(
+                svg,
+                r#"<text x="0" y="{}" font-size="12">{}</text><rect x="{:.1}" y="{}" width="{:.1}" height="{}" fill="{}"><title>{} ({}ms)</title></rect>"#,
+                y + ROW_HEIGHT - 8,
+                html_escape(&entry.name),
+                x,
+                y + 4,
+                width,
+                ROW_HEIGHT - 8,
+                color,
+                html_escape(&entry.name),
+                entry.duration_ms,
+            );
+        }
+        svg.push_str("</svg>");
+
+        format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>TaskMesh timeline</title></head><body>\n\
+             <h1>TaskMesh timeline</h1>\n\
+             <p>wall: {}ms &middot; soma: {}ms &middot; eficiência: {:.2} &middot; retries: {} &middot; hedges: {}</p>\n\
+             {}\n</body></html>\n",
+            self.wall_time_ms,
+            self.total_task_time_ms,
+            self.parallelism_efficiency,
+            self.total_retries,
+            self.total_hedges,
+            svg,
+        )
+    }
+}
+
+/// Rótulo curto do status para exibição
+pub(crate) fn status_label(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::Scheduled => "scheduled",
+        TaskStatus::Running { .. } => "running",
+        TaskStatus::Completed { .. } => "completed",
+        TaskStatus::Failed { .. } => "failed",
+        TaskStatus::AwaitingRetry { .. } => "awaiting_retry",
+        TaskStatus::Cancelled { .. } => "cancelled",
+        TaskStatus::Paused { .. } => "paused",
+    }
+}
+
+/// Escapa caracteres especiais de HTML
+fn html_escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::execution_hint::ExecutionHint;
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    /// `sleep` executado inline, para que tarefas da mesma onda rodem em paralelo
+    fn sleeper(name: &str, seconds: &str, dependencies: Vec<TaskId>) -> Task {
+        Task::new(name.to_string(), TaskDefinition::exec("sleep", [seconds]), dependencies)
+            .with_execution_hint(ExecutionHint::Inline)
+    }
+
+    /// Executa o DAG a -> (b, c) -> d com sleeps de 100, 200, 300 e 100 ms
+    async fn diamond_run() -> (TaskMeshCore, Vec<TaskId>) {
+        let core = TaskMeshCore::new(TaskMeshConfig { max_workers: 4, strict_durability: true, ..TaskMeshConfig::default() })
+            .await
+            .unwrap();
+        core.start().await.unwrap();
+
+        let a = sleeper("a", "0.1", vec![]);
+        let b = sleeper("b", "0.2", vec![a.id]);
+        let c = sleeper("c", "0.3", vec![a.id]);
+        let d = sleeper("d", "0.1", vec![b.id, c.id]);
+        let ids = core.submit_batch(vec![a.clone(), b.clone(), c.clone(), d.clone()]).await.unwrap();
+
+        core.executor.execute_task(a).await.unwrap();
+        let (b, c) = tokio::join!(core.executor.execute_task(b), core.executor.execute_task(c));
+        b.unwrap();
+        c.unwrap();
+        core.executor.execute_task(d).await.unwrap();
+        (core, ids)
+    }
+
+    /// `value` está a no máximo `tolerance` de `expected`
+    fn near(value: u64, expected: u64, tolerance: u64) -> bool {
+        value.abs_diff(expected) <= tolerance
+    }
+
+    #[tokio::test]
+    async fn test_timeline_report_fields() {
+        let (core, ids) = diamond_run().await;
+        let json = core.generate_report(&ids, ReportFormat::Json).await.unwrap();
+        let report: TimelineReport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(report.entries.len(), 4);
+        assert!(report.missing_tasks.is_empty());
+        let names: Vec<&str> = report.entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!((names[0], names[3]), ("a", "d"));
+        let entry = |name: &str| report.entries.iter().find(|entry| entry.name == name).unwrap();
+        for (name, expected) in [("a", 100), ("b", 200), ("c", 300), ("d", 100)] {
+            assert!(near(entry(name).duration_ms, expected, 80), "{:?}", entry(name));
+            assert!(entry(name).worker.is_some(), "{:?}", entry(name));
+        }
+        // b e c começam juntas, depois de a; d começa depois de c
+        assert!(near(entry("b").start_offset_ms, entry("c").start_offset_ms, 50));
+        assert!(entry("b").start_offset_ms >= entry("a").end_offset_ms.saturating_sub(20));
+        assert!(entry("d").start_offset_ms >= entry("c").end_offset_ms.saturating_sub(20));
+
+        assert!(near(report.wall_time_ms, 500, 150), "{}", report.wall_time_ms);
+        assert!(near(report.total_task_time_ms, 700, 150), "{}", report.total_task_time_ms);
+        assert!((report.parallelism_efficiency - 1.4).abs() < 0.25, "{}", report.parallelism_efficiency);
+        assert_eq!(report.slowest_tasks[0], ids[2]);
+        assert_eq!((report.total_retries, report.total_hedges), (0, 0));
+        core.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_timeline_report_renderers() {
+        let (core, mut ids) = diamond_run().await;
+        ids.push(TaskId::new_v4());
+        let report = TimelineReport::build(core.state_store.as_ref(), &ids).await.unwrap();
+        assert_eq!(report.missing_tasks.len(), 1);
+
+        let ascii = report.render(ReportFormat::AsciiGantt).unwrap();
+        assert_eq!(ascii.lines().count(), 6);
+        assert!(ascii.contains('#'));
+
+        let html = report.render(ReportFormat::Html).unwrap();
+        assert!(html.contains("<svg"));
+        assert_eq!(html.matches("<rect").count(), 4);
+        core.shutdown().await.unwrap();
+    }
+}
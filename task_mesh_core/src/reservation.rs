@@ -0,0 +1,368 @@
+//! Reservas de capacidade para tarefas futuras
+//!
+//! Uma tarefa crítica que chega em hora marcada não deve encontrar todos os
+//! workers ocupados por tarefas longas de baixa prioridade. Uma [`Reservation`]
+//! bloqueia CPU e memória durante uma janela: enquanto ela estiver ativa,
+//! [`Scheduler::get_next_task`](crate::Scheduler::get_next_task) desconta a
+//! capacidade reservada dos recursos disponíveis para as tarefas que não são
+//! o alvo da reserva. Tarefas-alvo enxergam a capacidade inteira.
+//!
+//! Reservas cujas janelas se sobrepõem não podem somar mais que a capacidade
+//! do scheduler; a criação é recusada. Uma reserva para uma tarefa
+//! específica é consumida quando a tarefa é despachada; reservas por tag
+//! valem até o fim da janela. Reservas terminadas expiram sozinhas.
+//!
+//! As reservas são persistidas no `StateStore` (configuração
+//! [`RESERVATIONS_SETTING`]) e restauradas na inicialização. O consumo no
+//! despacho também: o core aguarda [`ReservationBook::consumed`] e regrava
+//! a configuração, para que um reinício não devolva uma reserva já usada.
+
+use std::sync::RwLock;
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::types::*;
+use crate::validation;
+
+/// Configuração persistente com as reservas (JSON de `Vec<Reservation>`)
+pub const RESERVATIONS_SETTING: &str = "resource_reservations";
+
+/// Intervalo `[start, end)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: SystemTime,
+    pub end: SystemTime,
+}
+
+impl TimeRange {
+    pub fn new(start: SystemTime, end: SystemTime) -> Self {
+        Self { start, end }
+    }
+
+    pub fn contains(&self, at: SystemTime) -> bool {
+        self.start <= at && at < self.end
+    }
+
+    pub fn overlaps(&self, other: &TimeRange) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// Tarefas que podem usar a capacidade reservada
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservationTarget {
+    /// Tarefas com a tag
+    Tag(String),
+    /// Uma tarefa específica
+    Task(TaskId),
+}
+
+impl ReservationTarget {
+    pub fn covers(&self, task_id: &TaskId, tags: &[String]) -> bool {
+        match self {
+            ReservationTarget::Tag(tag) => tags.contains(&validation::normalize_tag(tag)),
+            ReservationTarget::Task(id) => id == task_id,
+        }
+    }
+}
+
+/// Pedido de reserva
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReservationRequest {
+    /// Capacidade reservada (apenas `cpu_cores` e `memory_bytes` são considerados)
+    pub resources: ResourceAllocation,
+    pub window: TimeRange,
+    pub for_tag_or_task: ReservationTarget,
+}
+
+/// Reserva aceita
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reservation {
+    pub id: Uuid,
+    pub resources: ResourceAllocation,
+    pub window: TimeRange,
+    pub target: ReservationTarget,
+    pub created_at: SystemTime,
+}
+
+/// Reservas do scheduler e a capacidade total que elas dividem
+#[derive(Debug)]
+pub struct ReservationBook {
+    capacity: ResourceAllocation,
+    reservations: RwLock<Vec<Reservation>>,
+    /// Sinalizado quando `consume` remove alguma reserva
+    consumed: Notify,
+}
+
+impl ReservationBook {
+    pub fn new(capacity: ResourceAllocation) -> Self {
+        Self { capacity, reservations: RwLock::new(Vec::new()), consumed: Notify::new() }
+    }
+
+    /// Capacidade total dividida pelas reservas
+    pub fn capacity(&self) -> &ResourceAllocation {
+        &self.capacity
+    }
+
+    /// Cria uma reserva, recusando-a se exceder a capacidade em algum
+    /// momento da janela
+    pub fn reserve(&self, request: ReservationRequest, now: SystemTime) -> TaskMeshResult<Reservation> {
+        let ReservationRequest { resources, window, for_tag_or_task: target } = request;
+        if window.end <= window.start {
+            return Err(TaskMeshError::Configuration("Janela de reserva vazia ou invertida".to_string()));
+        }
+        if window.end <= now {
+            return Err(TaskMeshError::Configuration("Janela de reserva já terminou".to_string()));
+        }
+        if !(resources.cpu_cores >= 0.0 && resources.cpu_cores.is_finite()) {
+            return Err(TaskMeshError::Configuration(format!("cpu_cores inválido: {}", resources.cpu_cores)));
+        }
+
+        let mut reservations = self.reservations.write().unwrap();
+        let (cpu_cores, memory_bytes) = peak_usage(&reservations, &window);
+        if cpu_cores + resources.cpu_cores > self.capacity.cpu_cores
+            || memory_bytes.saturating_add(resources.memory_bytes) > self.capacity.memory_bytes
+        {
+            return Err(TaskMeshError::ResourceUnavailable(format!(
+                "reserva de {} CPUs e {} bytes excede a capacidade ({} CPUs e {} bytes, {} CPUs e {} bytes já reservados na janela)",
+                resources.cpu_cores,
+                resources.memory_bytes,
+                self.capacity.cpu_cores,
+                self.capacity.memory_bytes,
+                cpu_cores,
+                memory_bytes,
+            )));
+        }
+
+        let reservation = Reservation { id: Uuid::new_v4(), resources, window, target, created_at: now };
+        reservations.push(reservation.clone());
+        Ok(reservation)
+    }
+
+    /// Cancela uma reserva, retornando-a
+    pub fn cancel(&self, id: &Uuid) -> Option<Reservation> {
+        let mut reservations = self.reservations.write().unwrap();
+        let index = reservations.iter().position(|reservation| reservation.id == *id)?;
+        Some(reservations.remove(index))
+    }
+
+    /// Remove as reservas cuja janela terminou antes de `now`
+    pub fn expire(&self, now: SystemTime) -> Vec<Reservation> {
+        let mut reservations = self.reservations.write().unwrap();
+        let (expired, kept) = reservations.drain(..).partition(|reservation| reservation.window.end <= now);
+        *reservations = kept;
+        expired
+    }
+
+    /// Substitui as reservas (restauração do `StateStore`)
+    pub fn restore(&self, reservations: Vec<Reservation>) {
+        *self.reservations.write().unwrap() = reservations;
+    }
+
+    /// Todas as reservas, ativas ou futuras
+    pub fn list(&self) -> Vec<Reservation> {
+        self.reservations.read().unwrap().clone()
+    }
+
+    /// Reservas cuja janela contém `now`
+    pub fn active(&self, now: SystemTime) -> Vec<Reservation> {
+        self.reservations
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|reservation| reservation.window.contains(now))
+            .cloned()
+            .collect()
+    }
+
+    /// Recursos disponíveis para uma tarefa, descontadas as reservas ativas
+    /// de que ela não é alvo
+    pub fn available_for(
+        &self,
+        available: &ResourceAllocation,
+        task_id: &TaskId,
+        tags: &[String],
+        now: SystemTime,
+    ) -> ResourceAllocation {
+        let mut remaining = available.clone();
+        for reservation in self.reservations.read().unwrap().iter() {
+            if reservation.window.contains(now) && !reservation.target.covers(task_id, tags) {
+                remaining.cpu_cores = (remaining.cpu_cores - reservation.resources.cpu_cores).max(0.0);
+                remaining.memory_bytes = remaining.memory_bytes.saturating_sub(reservation.resources.memory_bytes);
+            }
+        }
+        remaining
+    }
+
+    /// Consome as reservas feitas para a tarefa despachada
+    pub fn consume(&self, task_id: &TaskId) -> bool {
+        let mut reservations = self.reservations.write().unwrap();
+        let before = reservations.len();
+        reservations.retain(|reservation| reservation.target != ReservationTarget::Task(*task_id));
+        let consumed = reservations.len() != before;
+        if consumed {
+            self.consumed.notify_one();
+        }
+        consumed
+    }
+
+    /// Espera até alguma reserva ser consumida desde a última espera
+    pub async fn consumed(&self) {
+        self.consumed.notified().await;
+    }
+}
+
+/// Maior soma de CPU e memória reservadas em algum instante de `window`
+///
+/// A soma só cresce no início de uma reserva, então basta avaliá-la no
+/// início da janela e no início de cada reserva que começa dentro dela.
+fn peak_usage(reservations: &[Reservation], window: &TimeRange) -> (f64, u64) {
+    let overlapping: Vec<&Reservation> =
+        reservations.iter().filter(|reservation| reservation.window.overlaps(window)).collect();
+    std::iter::once(window.start)
+        .chain(overlapping.iter().map(|reservation| reservation.window.start).filter(|start| window.contains(*start)))
+        .map(|at| {
+            overlapping
+                .iter()
+                .filter(|reservation| reservation.window.contains(at))
+                .fold((0.0, 0u64), |(cpu_cores, memory_bytes), reservation| {
+                    (
+                        cpu_cores + reservation.resources.cpu_cores,
+                        memory_bytes.saturating_add(reservation.resources.memory_bytes),
+                    )
+                })
+        })
+        .fold((0.0, 0), |(peak_cpu, peak_memory), (cpu_cores, memory_bytes)| {
+            (f64::max(peak_cpu, cpu_cores), peak_memory.max(memory_bytes))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn cores(cpu_cores: f64) -> ResourceAllocation {
+        ResourceAllocation { cpu_cores, memory_bytes: 0, ..ResourceAllocation::default() }
+    }
+
+    fn window(now: SystemTime, from_secs: u64, to_secs: u64) -> TimeRange {
+        TimeRange::new(now + Duration::from_secs(from_secs), now + Duration::from_secs(to_secs))
+    }
+
+    #[test]
+    fn test_overlapping_reservations_limited_by_capacity() {
+        let now = SystemTime::now();
+        let book = ReservationBook::new(cores(4.0));
+        let request = |resources, window| ReservationRequest {
+            resources,
+            window,
+            for_tag_or_task: ReservationTarget::Tag("critical".to_string()),
+        };
+
+        book.reserve(request(cores(3.0), window(now, 0, 10)), now).unwrap();
+        // Começa depois da primeira terminar: cabe
+        book.reserve(request(cores(3.0), window(now, 10, 20)), now).unwrap();
+        assert!(matches!(
+            book.reserve(request(cores(2.0), window(now, 5, 15)), now),
+            Err(TaskMeshError::ResourceUnavailable(_))
+        ));
+        book.reserve(request(cores(1.0), window(now, 5, 15)), now).unwrap();
+        assert!(book.reserve(request(cores(1.0), window(now, 20, 10)), now).is_err());
+
+        assert_eq!(book.expire(now + Duration::from_secs(12)).len(), 1);
+        assert_eq!(book.active(now + Duration::from_secs(12)).len(), 2);
+    }
+
+    #[test]
+    fn test_only_non_matching_tasks_lose_reserved_capacity() {
+        let now = SystemTime::now();
+        let book = ReservationBook::new(cores(4.0));
+        let critical = TaskId::new_v4();
+        let reservation = book
+            .reserve(
+                ReservationRequest {
+                    resources: cores(2.0),
+                    window: window(now, 0, 10),
+                    for_tag_or_task: ReservationTarget::Task(critical),
+                },
+                now,
+            )
+            .unwrap();
+
+        let other = book.available_for(&cores(3.0), &TaskId::new_v4(), &[], now);
+        assert_eq!(other.cpu_cores, 1.0);
+        assert_eq!(book.available_for(&cores(3.0), &critical, &[], now).cpu_cores, 3.0);
+
+        assert!(book.consume(&critical));
+        assert!(book.cancel(&reservation.id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reservations_survive_restart() {
+        use crate::health::components;
+        use crate::{TaskMeshConfig, TaskMeshCore};
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig {
+            database_url: format!("sqlite://{}", dir.path().join("state.db").display()),
+            ..TaskMeshConfig::default()
+        };
+        let now = SystemTime::now();
+        let request = ReservationRequest {
+            resources: cores(1.0),
+            window: window(now, 0, 3600),
+            for_tag_or_task: ReservationTarget::Tag("critical".to_string()),
+        };
+
+        let core = TaskMeshCore::new(config.clone()).await.unwrap();
+        let reservation = core.reserve_resources(request).await.unwrap();
+        drop(core);
+
+        let core = TaskMeshCore::new(config.clone()).await.unwrap();
+        assert_eq!(core.list_reservations()[0].id, reservation.id);
+        let health = core.health().await;
+        assert!(health.component(components::RESERVATIONS).unwrap().detail.starts_with("1 reservas ativas"));
+
+        assert!(core.cancel_reservation(&reservation.id).await.unwrap());
+        drop(core);
+        assert!(TaskMeshCore::new(config).await.unwrap().list_reservations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_consumed_reservation_not_restored() {
+        use crate::{TaskMeshConfig, TaskMeshCore};
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskMeshConfig {
+            database_url: format!("sqlite://{}", dir.path().join("state.db").display()),
+            ..TaskMeshConfig::default()
+        };
+        let core = TaskMeshCore::new(config.clone()).await.unwrap();
+        core.start().await.unwrap();
+        let task = Task::new("critical".to_string(), TaskDefinition::command("true"), vec![]);
+        let task_id = core.submit_task(task).await.unwrap();
+        core.reserve_resources(ReservationRequest {
+            resources: cores(1.0),
+            window: window(SystemTime::now(), 0, 3600),
+            for_tag_or_task: ReservationTarget::Task(task_id),
+        })
+        .await
+        .unwrap();
+
+        // O despacho consome a reserva e a configuração é regravada
+        assert_eq!(core.scheduler.get_next_task(&ResourceAllocation::default()).await, Some(task_id));
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while core.state_store.get_setting(RESERVATIONS_SETTING).await.unwrap().is_some() {
+            assert!(std::time::Instant::now() < deadline, "consumo não persistido");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        core.shutdown().await.unwrap();
+        drop(core);
+
+        assert!(TaskMeshCore::new(config).await.unwrap().list_reservations().is_empty());
+    }
+}
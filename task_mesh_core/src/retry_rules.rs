@@ -0,0 +1,338 @@
+//! Regras de retry por categoria de erro e tag
+//!
+//! Uma única [`RetryPolicy`] trata igual um timeout de rede e um erro de
+//! validação do script. As [`RetryRule`]s de `TaskMeshConfig::retry_rules`
+//! escolhem a política pela falha e pelas tags da tarefa: a primeira regra
+//! cujo [`ErrorMatcher`] e tags casam decide, e sem regra aplicável vale a
+//! `retry_policy` padrão.
+//!
+//! O código de uma falha é o [`TaskMeshError::error_code`] do erro ou, para
+//! execuções que terminaram com código de saída diferente de zero,
+//! `EXIT_CODE_<n>`. Padrões de código aceitam `*` como curinga
+//! (`EXIT_CODE_*`, `*_TIMEOUT`); padrões inválidos são recusados ao criar o
+//! core.
+//!
+//! [`RetryRules::explain`] mostra a decisão para uma falha; a decisão
+//! aplicada vai nos dados dos eventos `TaskFailed` e, por eles, para a linha
+//! do tempo da tarefa.
+//!
+//! Falhas decididas por `NoRetry` vão para a fila de mensagens mortas
+//! ([`crate::dead_letter`]).
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+use crate::validation;
+
+/// Categoria de uma falha
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Terminou com código de saída diferente de zero
+    ExitCode,
+    Timeout,
+    /// Recurso indisponível ou limite excedido
+    Resource,
+    /// Erro de rede/HTTP da definição
+    Network,
+    /// Sidecar não ficou pronto
+    Sidecar,
+    /// Demais erros de execução
+    Execution,
+    Internal,
+}
+
+impl ErrorCategory {
+    /// Categoria de uma execução que falhou
+    pub fn of(failure: &TaskMeshResult<TaskResult>) -> Self {
+        match failure {
+            Ok(_) => ErrorCategory::ExitCode,
+            Err(TaskMeshError::ExecutionTimeout(_)) => ErrorCategory::Timeout,
+            Err(TaskMeshError::ResourceUnavailable(_) | TaskMeshError::ResourceLimitExceeded(_)) => {
+                ErrorCategory::Resource
+            }
+            Err(TaskMeshError::Network(_)) => ErrorCategory::Network,
+            Err(TaskMeshError::SidecarNotReady { .. }) => ErrorCategory::Sidecar,
+            Err(TaskMeshError::ExecutionError(_)) => ErrorCategory::Execution,
+            Err(_) => ErrorCategory::Internal,
+        }
+    }
+}
+
+/// Código de uma execução que falhou (`EXIT_CODE_<n>` ou o código do erro)
+pub fn failure_code(failure: &TaskMeshResult<TaskResult>) -> String {
+    match failure {
+        Ok(result) => format!("EXIT_CODE_{}", result.exit_code),
+        Err(error) => error.error_code().to_string(),
+    }
+}
+
+/// Falhas cobertas por uma regra
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorMatcher {
+    /// Qualquer falha
+    Any,
+    Category(ErrorCategory),
+    /// Padrão do código da falha, com `*` como curinga
+    ErrorCode(String),
+}
+
+impl ErrorMatcher {
+    fn matches(&self, category: ErrorCategory, code: &str) -> bool {
+        match self {
+            ErrorMatcher::Any => true,
+            ErrorMatcher::Category(expected) => *expected == category,
+            ErrorMatcher::ErrorCode(pattern) => code_matches(pattern, code),
+        }
+    }
+}
+
+/// O que fazer com as falhas cobertas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryDirective {
+    /// Repete segundo a política (condições, tentativas e backoff)
+    Retry(RetryPolicy),
+    /// Falha definitiva na primeira ocorrência, enviada à fila de mensagens
+    /// mortas (ver [`crate::dead_letter`])
+    NoRetry,
+}
+
+/// Regra de `TaskMeshConfig::retry_rules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryRule {
+    /// Nome mostrado nas decisões (padrão: `retry_rules[i]`)
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename = "match")]
+    pub matcher: ErrorMatcher,
+    /// Tags exigidas, todas; vazio cobre qualquer tarefa
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub action: RetryDirective,
+}
+
+/// Decisão de retry para uma falha
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    /// Regra aplicada (`None`: política padrão)
+    pub rule: Option<String>,
+    pub category: ErrorCategory,
+    pub error_code: String,
+    pub directive: RetryDirective,
+    /// A falha satisfaz as condições da política (nunca com `NoRetry`)
+    pub retryable: bool,
+}
+
+impl PolicyDecision {
+    /// Política que rege as tentativas (`None` com `NoRetry`)
+    pub fn policy(&self) -> Option<&RetryPolicy> {
+        match &self.directive {
+            RetryDirective::Retry(policy) => Some(policy),
+            RetryDirective::NoRetry => None,
+        }
+    }
+}
+
+/// Regras avaliadas em ordem, com a política padrão como fallback
+#[derive(Debug, Clone)]
+pub struct RetryRules {
+    rules: Vec<(String, RetryRule)>,
+    default: RetryPolicy,
+}
+
+impl From<RetryPolicy> for RetryRules {
+    fn from(default: RetryPolicy) -> Self {
+        Self { rules: Vec::new(), default }
+    }
+}
+
+impl RetryRules {
+    /// Valida as regras; padrões de código inválidos são recusados
+    pub fn new(rules: Vec<RetryRule>, default: RetryPolicy) -> TaskMeshResult<Self> {
+        let rules = rules
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut rule)| {
+                let name = rule.name.clone().unwrap_or_else(|| format!("retry_rules[{}]", i));
+                if let ErrorMatcher::ErrorCode(pattern) = &rule.matcher {
+                    validate_pattern(pattern).map_err(|reason| {
+                        TaskMeshError::Configuration(format!(
+                            "Padrão de código inválido em {}: '{}' ({})", name, pattern, reason
+                        ))
+                    })?;
+                }
+                validation::normalize_tags(&mut rule.tags);
+                Ok((name, rule))
+            })
+            .collect::<TaskMeshResult<_>>()?;
+        Ok(Self { rules, default })
+    }
+
+    /// Política usada quando nenhuma regra casa
+    pub fn default_policy(&self) -> &RetryPolicy {
+        &self.default
+    }
+
+    /// Decisão para a falha `failure` da tarefa `task` (a primeira regra que casa)
+    pub fn explain(&self, failure: &TaskMeshResult<TaskResult>, task: &Task) -> PolicyDecision {
+        let category = ErrorCategory::of(failure);
+        let error_code = failure_code(failure);
+        let matched = self.rules.iter().find(|(_, rule)| {
+            rule.matcher.matches(category, &error_code)
+                && rule.tags.iter().all(|tag| task.tags.iter().any(|own| validation::normalize_tag(own) == *tag))
+        });
+        let (rule, directive) = match matched {
+            Some((name, rule)) => (Some(name.clone()), rule.action.clone()),
+            None => (None, RetryDirective::Retry(self.default.clone())),
+        };
+        let retryable = match (&directive, failure) {
+            (RetryDirective::NoRetry, _) => false,
+            (RetryDirective::Retry(policy), Ok(result)) => policy.retries_result(result),
+            (RetryDirective::Retry(policy), Err(error)) => policy.retries_error(error),
+        };
+        PolicyDecision { rule, category, error_code, directive, retryable }
+    }
+}
+
+/// Motivo da recusa de um padrão de código
+fn validate_pattern(pattern: &str) -> Result<(), &'static str> {
+    if pattern.is_empty() {
+        return Err("padrão vazio");
+    }
+    if !pattern.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '*') {
+        return Err("use apenas A-Z, 0-9, _ e *");
+    }
+    Ok(())
+}
+
+/// Compara um código com um padrão em que `*` casa qualquer sequência
+fn code_matches(pattern: &str, code: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = code.strip_prefix(parts.next().unwrap_or_default()) else { return false };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else { return rest.is_empty() };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn exit(code: i32) -> TaskMeshResult<TaskResult> {
+        Ok(TaskResult {
+            exit_code: code,
+            stdout: String::new(),
+            stderr: String::new(),
+            output_data: None,
+            metrics: ExecutionMetrics::default(),
+            log_ref: None,
+        })
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            backoff_strategy: BackoffStrategy::Fixed { delay: Duration::from_millis(10) },
+            retry_conditions: vec![RetryCondition::ExitCode(vec![1, 137]), RetryCondition::Timeout],
+        }
+    }
+
+    fn rule(name: Option<&str>, matcher: ErrorMatcher, tags: &[&str], action: RetryDirective) -> RetryRule {
+        RetryRule { name: name.map(str::to_string), matcher, tags: tags.iter().map(|t| t.to_string()).collect(), action }
+    }
+
+    #[test]
+    fn test_code_patterns() {
+        assert!(code_matches("EXIT_CODE_*", "EXIT_CODE_137"));
+        assert!(code_matches("*_TIMEOUT", "EXECUTION_TIMEOUT"));
+        assert!(code_matches("*CODE*7", "EXIT_CODE_137"));
+        assert!(code_matches("EXECUTION_ERROR", "EXECUTION_ERROR"));
+        assert!(!code_matches("EXECUTION_ERROR", "EXECUTION_ERRORS"));
+        assert!(!code_matches("A*A", "A"));
+        assert!(code_matches("*", ""));
+
+        for invalid in ["", "exit_code_*", "EXIT CODE", "EXIT_CODE_[0-9]"] {
+            let rules = vec![rule(None, ErrorMatcher::ErrorCode(invalid.to_string()), &[], RetryDirective::NoRetry)];
+            let error = RetryRules::new(rules, RetryPolicy::default()).unwrap_err();
+            assert!(matches!(&error, TaskMeshError::Configuration(message) if message.contains("retry_rules[0]")), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_network_category_comes_from_error_variant() {
+        let network = Err(TaskMeshError::Network("conexão recusada".to_string()));
+        assert_eq!(ErrorCategory::of(&network), ErrorCategory::Network);
+        let policy = RetryPolicy { retry_conditions: vec![RetryCondition::NetworkError], ..RetryPolicy::default() };
+        assert!(policy.retries_error(&TaskMeshError::Network("conexão recusada".to_string())));
+        let execution = Err(TaskMeshError::ExecutionError("script imprimiu HTTP 503 na requisição".to_string()));
+        assert_eq!(ErrorCategory::of(&execution), ErrorCategory::Execution);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = RetryRules::new(
+            vec![
+                rule(Some("oom-no-gpu"), ErrorMatcher::ErrorCode("EXIT_CODE_137".to_string()), &["GPU"], RetryDirective::NoRetry),
+                rule(None, ErrorMatcher::Category(ErrorCategory::ExitCode), &[], RetryDirective::Retry(policy(5))),
+                rule(Some("nunca"), ErrorMatcher::Any, &[], RetryDirective::NoRetry),
+            ],
+            policy(2),
+        )
+        .unwrap();
+        let plain = Task::new("t".to_string(), TaskDefinition::command("true"), vec![]);
+        let gpu = plain.clone().with_tags(vec!["gpu".to_string()]);
+
+        let decision = rules.explain(&exit(137), &gpu);
+        assert_eq!(decision.rule.as_deref(), Some("oom-no-gpu"));
+        assert_eq!((decision.category, decision.error_code.as_str()), (ErrorCategory::ExitCode, "EXIT_CODE_137"));
+        assert!(decision.policy().is_none() && !decision.retryable);
+
+        // Sem a tag, a regra seguinte decide
+        let decision = rules.explain(&exit(137), &plain);
+        assert_eq!(decision.rule.as_deref(), Some("retry_rules[1]"));
+        assert_eq!(decision.policy().map(|p| p.max_attempts), Some(5));
+        assert!(decision.retryable);
+        // A regra casa, mas as condições da política não
+        assert!(!rules.explain(&exit(3), &plain).retryable);
+
+        let timeout = Err(TaskMeshError::ExecutionTimeout(plain.id));
+        assert_eq!(rules.explain(&timeout, &plain).rule.as_deref(), Some("nunca"));
+
+        let fallback = RetryRules::from(policy(2)).explain(&timeout, &plain);
+        assert_eq!((fallback.rule, fallback.category, fallback.retryable), (None, ErrorCategory::Timeout, true));
+        assert_eq!(fallback.error_code, "EXECUTION_TIMEOUT");
+    }
+
+    #[test]
+    fn test_rules_from_config() {
+        let rules: Vec<RetryRule> = serde_json::from_value(serde_json::json!([
+            { "name": "rede", "match": { "category": "network" }, "tags": ["etl"], "action": "no_retry" },
+            { "match": { "error_code": "*_TIMEOUT" }, "action": { "retry": {
+                "max_attempts": 4,
+                "backoff_strategy": { "Fixed": { "delay": { "secs": 1, "nanos": 0 } } },
+                "retry_conditions": ["Timeout"],
+            } } },
+            { "match": "any", "action": "no_retry" },
+        ]))
+        .unwrap();
+        let rules = RetryRules::new(rules, RetryPolicy::default()).unwrap();
+        let etl = Task::new("t".to_string(), TaskDefinition::command("true"), vec![]).with_tags(vec!["etl".to_string()]);
+        let network = Err(TaskMeshError::Network("conexão recusada".to_string()));
+        assert_eq!(rules.explain(&network, &etl).rule.as_deref(), Some("rede"));
+
+        let decision = rules.explain(&Err(TaskMeshError::ExecutionTimeout(etl.id)), &etl);
+        assert_eq!(decision.rule.as_deref(), Some("retry_rules[1]"));
+        let data = serde_json::to_value(&decision).unwrap();
+        assert_eq!(data["category"], "timeout");
+        assert_eq!(data["directive"]["retry"]["max_attempts"], 4);
+    }
+}
@@ -1,16 +1,81 @@
 //! Scheduler inteligente com algoritmos topológicos e heurísticas avançadas
 
-use std::collections::{HashMap, HashSet, VecDeque, BinaryHeap};
+use std::collections::{BTreeMap, HashMap, BinaryHeap};
 use std::cmp::{Ordering, Reverse};
 use std::time::{Duration, SystemTime};
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+use tokio::sync::{Notify, RwLock};
 use tracing::{debug, error, info, warn};
 use petgraph::prelude::*;
 use petgraph::algo::toposort;
 
+use crate::concurrency_group::ConcurrencyGroups;
+use crate::dispatch_latency::DispatchLatency;
+use crate::features::{self, TaskFeatures};
+use crate::maintenance::DispatchGate;
+use crate::reservation::{Reservation, ReservationBook, ReservationRequest};
 use crate::types::*;
 use crate::TaskMeshResult;
+use crate::plan_optimizer::{PlanOptimizer, PlanOptimizerConfig, PlanProblem, PlanTask};
+use crate::units::ByteSize;
+use crate::validation::Violation;
+
+pub mod evaluation;
+
+use evaluation::{EvaluationReport, WorkloadTrace};
+
+/// Metadado com os núcleos de CPU que a tarefa ocupa (padrão: 1)
+pub const CPU_CORES_KEY: &str = "cpu_cores";
+
+/// Metadado com a memória que a tarefa ocupa (bytes ou `"512MiB"`; padrão: 1 GiB)
+pub const MEMORY_BYTES_KEY: &str = "memory_bytes";
+
+impl Task {
+    /// Define a memória que a tarefa ocupa
+    pub fn with_memory(mut self, memory: ByteSize) -> Self {
+        self.metadata.insert(MEMORY_BYTES_KEY.to_string(), memory.to_string());
+        self
+    }
+}
+
+/// Recursos que a tarefa pede nos metadados, sobre os valores padrão
+pub fn requested_resources(task: &Task) -> ResourceAllocation {
+    let mut resources = ResourceAllocation::default();
+    if let Some(Ok(cpu_cores)) = task.metadata.get(CPU_CORES_KEY).map(|value| parse_cpu_cores(value)) {
+        resources.cpu_cores = cpu_cores;
+    }
+    if let Some(Ok(memory)) = task.metadata.get(MEMORY_BYTES_KEY).map(|value| value.parse::<ByteSize>()) {
+        resources.memory_bytes = memory.into();
+    }
+    resources
+}
+
+/// Verifica os metadados de recursos da tarefa
+///
+/// Os campos das violações recebem `prefix` (ex.: `tasks[2].`).
+pub fn validate_requested_resources(task: &Task, prefix: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if let Some(Err(message)) = task.metadata.get(CPU_CORES_KEY).map(|value| parse_cpu_cores(value)) {
+        violations.push(Violation { field: format!("{}metadata.{}", prefix, CPU_CORES_KEY), message });
+    }
+    if let Some(Err(error)) = task.metadata.get(MEMORY_BYTES_KEY).map(|value| value.parse::<ByteSize>()) {
+        violations.push(Violation {
+            field: format!("{}metadata.{}", prefix, MEMORY_BYTES_KEY),
+            message: error.to_string(),
+        });
+    }
+    violations
+}
+
+fn parse_cpu_cores(value: &str) -> Result<f64, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|cores| cores.is_finite() && *cores >= 0.0)
+        .ok_or_else(|| format!("'{}' não é um número de núcleos", value))
+}
 
 /// Heurísticas de agendamento
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -51,6 +116,40 @@ impl Default for SchedulingHeuristic {
     }
 }
 
+impl SchedulingHeuristic {
+    /// Nome curto, aceito por [`FromStr`](std::str::FromStr)
+    pub fn name(&self) -> &'static str {
+        match self {
+            SchedulingHeuristic::FIFO => "fifo",
+            SchedulingHeuristic::LIFO => "lifo",
+            SchedulingHeuristic::Priority => "priority",
+            SchedulingHeuristic::ShortestJobFirst => "shortest_job_first",
+            SchedulingHeuristic::EarliestDeadlineFirst => "earliest_deadline_first",
+            SchedulingHeuristic::CriticalRatio => "critical_ratio",
+            SchedulingHeuristic::Genetic { .. } => "genetic",
+            SchedulingHeuristic::Hybrid { .. } => "hybrid",
+        }
+    }
+}
+
+impl std::str::FromStr for SchedulingHeuristic {
+    type Err = TaskMeshError;
+
+    /// `hybrid` é a heurística padrão; `genetic` não tem forma curta
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "fifo" => Ok(SchedulingHeuristic::FIFO),
+            "lifo" => Ok(SchedulingHeuristic::LIFO),
+            "priority" => Ok(SchedulingHeuristic::Priority),
+            "sjf" | "shortest_job_first" => Ok(SchedulingHeuristic::ShortestJobFirst),
+            "edf" | "earliest_deadline_first" => Ok(SchedulingHeuristic::EarliestDeadlineFirst),
+            "critical_ratio" => Ok(SchedulingHeuristic::CriticalRatio),
+            "hybrid" => Ok(SchedulingHeuristic::default()),
+            other => Err(TaskMeshError::Configuration(format!("heurística desconhecida: {}", other))),
+        }
+    }
+}
+
 /// Estimativa de custo de execução
 #[derive(Debug, Clone)]
 pub struct ExecutionEstimate {
@@ -62,6 +161,8 @@ pub struct ExecutionEstimate {
     pub confidence: f64,
     /// Histórico de execuções similares
     pub historical_data: Vec<ExecutionMetrics>,
+    /// Features da tarefa, com os campos históricos preenchidos
+    pub features: TaskFeatures,
 }
 
 /// Plano de execução
@@ -90,16 +191,98 @@ pub struct PlanMetrics {
     pub load_factor: f64,
     /// Caminho crítico
     pub critical_path_length: Duration,
+    /// Makespan estimado (soma da tarefa mais longa de cada grupo)
+    #[serde(default)]
+    pub estimated_makespan: Duration,
+    /// Melhora relativa do objetivo obtida pelo otimizador (`None` = não otimizado)
+    #[serde(default)]
+    pub objective_improvement: Option<f64>,
+}
+
+/// Dados de uma tarefa considerados pelas heurísticas
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScoreInput {
+    pub created_at: SystemTime,
+    pub priority: Priority,
+    pub deadline: Option<SystemTime>,
+    pub estimated_duration: Duration,
+    /// Confiança na estimativa, usada pela heurística híbrida
+    pub confidence: f64,
+}
+
+/// Score de prioridade (maior = despachado antes) no instante `now`
+///
+/// Função pura: o scheduler a chama com o relógio real e o simulador de
+/// [`evaluation`] com o relógio simulado.
+pub(crate) fn heuristic_score(heuristic: &SchedulingHeuristic, task: &ScoreInput, now: SystemTime) -> f64 {
+    let time_to_deadline = |deadline: SystemTime| {
+        deadline.duration_since(now).unwrap_or_default().as_secs_f64()
+    };
+    let since_epoch = task.created_at.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default().as_secs_f64();
+    
+    match heuristic {
+        // Timestamp mais antigo = prioridade mais alta
+        SchedulingHeuristic::FIFO => -since_epoch,
+        // Timestamp mais recente = prioridade mais alta
+        SchedulingHeuristic::LIFO => since_epoch,
+        SchedulingHeuristic::Priority => f64::from(task.priority.value()),
+        // Tarefas mais curtas têm prioridade mais alta
+        SchedulingHeuristic::ShortestJobFirst => 1.0 / (task.estimated_duration.as_secs_f64() + 1.0),
+        SchedulingHeuristic::EarliestDeadlineFirst => match task.deadline {
+            Some(deadline) => 1.0 / (time_to_deadline(deadline) + 1.0),
+            None => f64::from(task.priority.value()),
+        },
+        SchedulingHeuristic::CriticalRatio => match task.deadline {
+            Some(deadline) => {
+                // Razão menor = mais urgente, por isso o sinal negativo
+                let processing_time = task.estimated_duration.as_secs_f64();
+                if processing_time > 0.0 {
+                    -(time_to_deadline(deadline) / processing_time)
+                } else {
+                    f64::NEG_INFINITY
+                }
+            }
+            None => f64::from(task.priority.value()),
+        },
+        // TODO: Implementar algoritmo genético
+        SchedulingHeuristic::Genetic { .. } => f64::from(task.priority.value()),
+        SchedulingHeuristic::Hybrid { primary, secondary, threshold } => {
+            let primary_score = heuristic_score(primary, task, now);
+            // Usar heurística primária se confiança é alta
+            if task.confidence >= *threshold {
+                primary_score
+            } else {
+                // Combinar ambas
+                let secondary_score = heuristic_score(secondary, task, now);
+                primary_score * task.confidence + secondary_score * (1.0 - task.confidence)
+            }
+        }
+    }
 }
 
 /// Item da fila de agendamento
 #[derive(Debug, Clone)]
 struct ScheduleItem {
     task_id: TaskId,
+    name: String,
+    priority: Priority,
     priority_score: f64,
     estimated_duration: Duration,
     deadline: Option<SystemTime>,
     resource_requirements: ResourceAllocation,
+    /// Tags da tarefa, consultadas pelas pausas de despacho
+    tags: Vec<String>,
+    /// Grupo de concorrência da tarefa
+    concurrency_group: Option<String>,
+    /// Entrada na fila (ou na fila de retentativas)
+    enqueued_at: SystemTime,
+    /// Momento a partir do qual a tarefa pode estar pronta: entrada na fila
+    /// ou fim do backoff
+    not_before: SystemTime,
+    /// Geração do item; cópias no heap com geração diferente da do índice
+    /// foram substituídas por [`Scheduler::reprioritize`] e são descartadas
+    generation: u64,
 }
 
 impl PartialEq for ScheduleItem {
@@ -123,14 +306,154 @@ impl Ord for ScheduleItem {
     }
 }
 
+/// Se o item do heap é a versão atual da tarefa no índice
+///
+/// Itens sem entrada no índice pertencem a tarefas já despachadas ou
+/// retiradas da fila.
+fn is_current(index: &HashMap<TaskId, QueueIndexEntry>, item: &ScheduleItem) -> bool {
+    index.get(&item.task_id).map_or(false, |entry| entry.item.generation == item.generation)
+}
+
+/// Entrada do índice secundário da fila
+///
+/// Espelha o heap e a fila de retentativas para que a introspecção não
+/// precise esvaziá-los.
+#[derive(Debug, Clone)]
+struct QueueIndexEntry {
+    item: ScheduleItem,
+    /// Horário da próxima tentativa, enquanto a tarefa aguarda backoff
+    retry_at: Option<SystemTime>,
+}
+
+/// Tarefa enfileirada, como vista por [`Scheduler::queue_snapshot`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct QueuedTaskView {
+    pub task_id: TaskId,
+    pub name: String,
+    /// Score calculado pela heurística ativa
+    pub priority_score: f64,
+    /// Prioridade declarada da tarefa
+    pub effective_priority: Priority,
+    pub estimated_duration: Duration,
+    /// Dependências ainda não concluídas com sucesso
+    pub blocked_on: Vec<TaskId>,
+    /// Pausa, janela de manutenção, reserva ou grupo de concorrência que retém o despacho
+    pub throttled_by: Option<String>,
+    /// Tempo desde a entrada na fila
+    pub queued_for: Duration,
+}
+
+/// Motivo de uma tarefa enfileirada ainda não ter sido despachada
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotRunningReason {
+    /// Em backoff até a próxima tentativa
+    AwaitingRetry { until: SystemTime },
+    /// Dependências ainda não concluídas com sucesso
+    DependenciesUnmet { blocked_on: Vec<TaskId> },
+    /// Pausa manual de despacho
+    Paused { reason: String },
+    /// Janela de manutenção ou capacidade reservada para outras tarefas
+    Throttled { by: String },
+    /// Recursos livres insuficientes no último despacho
+    Resources {
+        required_cpu_cores: f64,
+        required_memory_bytes: u64,
+        available_cpu_cores: f64,
+        available_memory_bytes: u64,
+    },
+    /// Nada impede o despacho; aguarda a vez na fila
+    Ready,
+}
+
+impl std::fmt::Display for NotRunningReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotRunningReason::AwaitingRetry { until } => {
+                write!(f, "aguardando retentativa até {}", chrono::DateTime::<chrono::Utc>::from(*until).to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            }
+            NotRunningReason::DependenciesUnmet { blocked_on } => {
+                let ids: Vec<String> = blocked_on.iter().map(TaskId::short).collect();
+                write!(f, "dependências pendentes: {}", ids.join(", "))
+            }
+            NotRunningReason::Paused { reason } => write!(f, "despacho pausado: {}", reason),
+            NotRunningReason::Throttled { by } => write!(f, "retida por {}", by),
+            NotRunningReason::Resources {
+                required_cpu_cores,
+                required_memory_bytes,
+                available_cpu_cores,
+                available_memory_bytes,
+            } => write!(
+                f,
+                "recursos insuficientes: pede {} CPU / {} bytes, livres {} CPU / {} bytes",
+                required_cpu_cores, required_memory_bytes, available_cpu_cores, available_memory_bytes
+            ),
+            NotRunningReason::Ready => write!(f, "pronta, aguardando a vez na fila"),
+        }
+    }
+}
+
+/// Ocupação da fila de agendamento, compartilhada com as reservas
+#[derive(Debug, Default)]
+struct QueueCapacity {
+    /// Tarefas reservadas ou enfileiradas ainda não despachadas
+    pending: AtomicUsize,
+    /// Acordado a cada vaga liberada
+    released: Notify,
+}
+
+impl QueueCapacity {
+    fn release(&self) {
+        self.pending.fetch_sub(1, AtomicOrdering::AcqRel);
+        self.released.notify_one();
+    }
+}
+
+/// Vaga reservada na fila de agendamento
+///
+/// Consumida por [`Scheduler::schedule_reserved`]; se descartada sem uso, a
+/// vaga volta a ficar disponível.
+#[derive(Debug)]
+pub struct QueueReservation {
+    capacity: Arc<QueueCapacity>,
+    consumed: bool,
+}
+
+impl QueueReservation {
+    /// Marca a vaga como usada: a tarefa ocupa a fila no lugar da reserva
+    fn consume(mut self) {
+        self.consumed = true;
+    }
+}
+
+impl Drop for QueueReservation {
+    fn drop(&mut self) {
+        if !self.consumed {
+            self.capacity.release();
+        }
+    }
+}
+
 /// Scheduler principal
 pub struct Scheduler {
-    /// Heurística ativa
-    heuristic: SchedulingHeuristic,
+    /// Heurística ativa (pode ser trocada em execução)
+    heuristic: RwLock<SchedulingHeuristic>,
     
     /// Fila de agendamento
     schedule_queue: Arc<RwLock<BinaryHeap<ScheduleItem>>>,
     
+    /// Tarefas aguardando backoff, indexadas pelo horário da próxima tentativa
+    retry_queue: Arc<RwLock<BTreeMap<(SystemTime, TaskId), ScheduleItem>>>,
+    
+    /// Índice secundário das duas filas, para introspecção
+    queue_index: Arc<RwLock<HashMap<TaskId, QueueIndexEntry>>>,
+    
+    /// Próxima geração de item da fila
+    next_generation: AtomicU64,
+    
+    /// Recursos livres informados no último despacho
+    last_available: std::sync::Mutex<Option<ResourceAllocation>>,
+    
     /// Grafo de dependências
     dependency_graph: Arc<RwLock<DiGraph<TaskId, ()>>>,
     
@@ -143,25 +466,31 @@ pub struct Scheduler {
     /// Histórico de performance
     performance_history: Arc<RwLock<HashMap<String, Vec<ExecutionMetrics>>>>,
     
-    /// Canal de comunicação
-    command_tx: mpsc::UnboundedSender<SchedulerCommand>,
-    command_rx: Arc<RwLock<Option<mpsc::UnboundedReceiver<SchedulerCommand>>>>,
+    /// Ocupação da fila (limitada por `max_queue_depth`)
+    capacity: Arc<QueueCapacity>,
+    
+    /// Tarefas finalizadas (true = sucesso)
+    finished: Arc<RwLock<HashMap<TaskId, bool>>>,
+    
+    /// Conclusão com sucesso de cada tarefa, para o momento em que os dependentes ficam prontos
+    completed_at: Arc<RwLock<HashMap<TaskId, SystemTime>>>,
+    
+    /// Marcos de despacho compartilhados com o executor
+    dispatch_latency: Arc<DispatchLatency>,
+    
+    /// Pausas de despacho e janelas de manutenção
+    dispatch_gate: DispatchGate,
+    
+    /// Capacidade reservada para tarefas futuras
+    reservations: ReservationBook,
+    
+    /// Vagas dos grupos de concorrência
+    concurrency_groups: Arc<ConcurrencyGroups>,
     
     /// Configuração
     config: SchedulerConfig,
 }
 
-/// Comandos do scheduler
-#[derive(Debug)]
-enum SchedulerCommand {
-    ScheduleTask(Task),
-    UpdateHeuristic(SchedulingHeuristic),
-    RecalculatePlan,
-    UpdateEstimate(TaskId, ExecutionEstimate),
-    TaskCompleted(TaskId, ExecutionMetrics),
-    TaskFailed(TaskId, String),
-}
-
 /// Configuração do scheduler
 #[derive(Debug, Clone)]
 pub struct SchedulerConfig {
@@ -175,6 +504,14 @@ pub struct SchedulerConfig {
     pub max_parallel_tasks: usize,
     /// Habilitar aprendizado adaptativo
     pub enable_adaptive_learning: bool,
+    /// Máximo de tarefas pendentes na fila (`None` = ilimitado)
+    pub max_queue_depth: Option<usize>,
+    /// Otimizar os grupos paralelos do plano por simulated annealing
+    pub enable_plan_optimization: bool,
+    /// Configuração do otimizador de planos
+    pub plan_optimizer: PlanOptimizerConfig,
+    /// Capacidade total que as reservas podem dividir
+    pub reservation_capacity: ResourceAllocation,
 }
 
 impl Default for SchedulerConfig {
@@ -185,6 +522,14 @@ impl Default for SchedulerConfig {
             safety_factor: 1.2,
             max_parallel_tasks: num_cpus::get(),
             enable_adaptive_learning: true,
+            max_queue_depth: None,
+            enable_plan_optimization: false,
+            plan_optimizer: PlanOptimizerConfig::default(),
+            reservation_capacity: ResourceAllocation {
+                cpu_cores: num_cpus::get() as f64,
+                memory_bytes: u64::MAX,
+                ..ResourceAllocation::default()
+            },
         }
     }
 }
@@ -192,19 +537,26 @@ impl Default for SchedulerConfig {
 impl Scheduler {
     /// Cria um novo scheduler
     pub fn new(heuristic: SchedulingHeuristic) -> Self {
-        let (command_tx, command_rx) = mpsc::unbounded_channel();
-        
         info!("Inicializando Scheduler com heurística: {:?}", heuristic);
         
         Self {
-            heuristic,
+            heuristic: RwLock::new(heuristic),
             schedule_queue: Arc::new(RwLock::new(BinaryHeap::new())),
+            retry_queue: Arc::new(RwLock::new(BTreeMap::new())),
+            queue_index: Arc::new(RwLock::new(HashMap::new())),
+            next_generation: AtomicU64::new(0),
+            last_available: std::sync::Mutex::new(None),
             dependency_graph: Arc::new(RwLock::new(DiGraph::new())),
             node_map: Arc::new(RwLock::new(HashMap::new())),
             execution_estimates: Arc::new(RwLock::new(HashMap::new())),
             performance_history: Arc::new(RwLock::new(HashMap::new())),
-            command_tx,
-            command_rx: Arc::new(RwLock::new(Some(command_rx))),
+            capacity: Arc::new(QueueCapacity::default()),
+            finished: Arc::new(RwLock::new(HashMap::new())),
+            completed_at: Arc::new(RwLock::new(HashMap::new())),
+            dispatch_latency: Arc::new(DispatchLatency::new()),
+            dispatch_gate: DispatchGate::default(),
+            reservations: ReservationBook::new(SchedulerConfig::default().reservation_capacity),
+            concurrency_groups: Arc::new(ConcurrencyGroups::default()),
             config: SchedulerConfig::default(),
         }
     }
@@ -212,45 +564,260 @@ impl Scheduler {
     /// Cria scheduler com configuração personalizada
     pub fn with_config(heuristic: SchedulingHeuristic, config: SchedulerConfig) -> Self {
         let mut scheduler = Self::new(heuristic);
+        scheduler.reservations = ReservationBook::new(config.reservation_capacity.clone());
         scheduler.config = config;
         scheduler
     }
 
+    /// Compartilha as vagas dos grupos de concorrência (com o executor)
+    pub fn with_concurrency_groups(mut self, groups: Arc<ConcurrencyGroups>) -> Self {
+        self.concurrency_groups = groups;
+        self
+    }
+
+    /// Compartilha os marcos de despacho (com o executor)
+    pub fn with_dispatch_latency(mut self, latency: Arc<DispatchLatency>) -> Self {
+        self.dispatch_latency = latency;
+        self
+    }
+
+    /// Pausas de despacho aplicadas em [`Self::get_next_task`]
+    pub fn dispatch_gate(&self) -> &DispatchGate {
+        &self.dispatch_gate
+    }
+
+    /// Reservas de capacidade consultadas em [`Self::get_next_task`]
+    pub fn reservations(&self) -> &ReservationBook {
+        &self.reservations
+    }
+
+    /// Reserva capacidade para tarefas futuras (ver [`crate::reservation`])
+    ///
+    /// Recusada com `ResourceUnavailable` se, somada às reservas que se
+    /// sobrepõem à janela, exceder `reservation_capacity`.
+    pub fn reserve_resources(&self, request: ReservationRequest) -> TaskMeshResult<Reservation> {
+        let reservation = self.reservations.reserve(request, SystemTime::now())?;
+        info!(
+            "Reserva {} de {} CPUs para {:?}",
+            reservation.id, reservation.resources.cpu_cores, reservation.target
+        );
+        Ok(reservation)
+    }
+
+    /// Cancela uma reserva, retornando-a
+    pub fn cancel_reservation(&self, id: &uuid::Uuid) -> Option<Reservation> {
+        self.reservations.cancel(id)
+    }
+
+    /// Número de tarefas pendentes na fila (incluindo vagas reservadas)
+    pub fn queue_depth(&self) -> usize {
+        self.capacity.pending.load(AtomicOrdering::Acquire)
+    }
+
+    /// Reserva uma vaga na fila ou falha com `QueueFull`
+    pub fn try_reserve(&self) -> TaskMeshResult<QueueReservation> {
+        let limit = self.config.max_queue_depth.unwrap_or(usize::MAX);
+        self.capacity.pending
+            .fetch_update(AtomicOrdering::AcqRel, AtomicOrdering::Acquire, |pending| {
+                (pending < limit).then_some(pending + 1)
+            })
+            .map_err(|pending| TaskMeshError::QueueFull { pending, limit })?;
+
+        Ok(QueueReservation {
+            capacity: self.capacity.clone(),
+            consumed: false,
+        })
+    }
+
+    /// Reserva uma vaga, aguardando até `wait` por capacidade
+    pub async fn reserve(&self, wait: Duration) -> TaskMeshResult<QueueReservation> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            // Registrar interesse antes de verificar para não perder notificações
+            let released = self.capacity.released.notified();
+            match self.try_reserve() {
+                Err(TaskMeshError::QueueFull { .. }) => {}
+                result => return result,
+            }
+            if tokio::time::timeout_at(deadline, released).await.is_err() {
+                return self.try_reserve();
+            }
+        }
+    }
+
     /// Agenda uma nova tarefa
-    pub async fn schedule_task(&self, task: Task) -> TaskMeshResult<()> {
-        debug!("Agendando tarefa: {} ({})", task.name, task.id);
+    pub async fn schedule_task(&self, task: impl Into<SharedTask>) -> TaskMeshResult<()> {
+        let reservation = self.try_reserve()?;
+        self.schedule_reserved(task, reservation).await
+    }
+
+    /// Agenda uma tarefa em uma vaga já reservada
+    pub async fn schedule_reserved(
+        &self,
+        task: impl Into<SharedTask>,
+        mut reservation: QueueReservation,
+    ) -> TaskMeshResult<()> {
+        let task: SharedTask = task.into();
+        debug!(task = %task.id.short(), task_id = %task.id, "Agendando tarefa: {}", task.name);
         
         // Adicionar ao grafo de dependências
         self.add_to_dependency_graph(&task).await?;
         
+        let schedule_item = self.build_schedule_item(&task).await;
+        let priority_score = schedule_item.priority_score;
+        
+        // Adicionar à fila (a vaga passa a ser liberada no despacho)
+        self.queue_index.write().await.insert(task.id, QueueIndexEntry { item: schedule_item.clone(), retry_at: None });
+        self.schedule_queue.write().await.push(schedule_item);
+        reservation.consumed = true;
+        
+        info!(task = %task.id.short(), task_id = %task.id, "Tarefa agendada com prioridade {:.2}", priority_score);
+        Ok(())
+    }
+
+    /// Agenda um lote de tarefas nas vagas já reservadas (uma por tarefa)
+    ///
+    /// O grafo e as filas são travados uma vez por lote, e não por tarefa.
+    pub async fn schedule_reserved_batch(
+        &self,
+        tasks: &[SharedTask],
+        reservations: Vec<QueueReservation>,
+    ) -> TaskMeshResult<()> {
+        if tasks.len() != reservations.len() {
+            return Err(TaskMeshError::Internal(format!(
+                "{} tarefas para {} vagas reservadas", tasks.len(), reservations.len()
+            )));
+        }
+        debug!("Agendando lote de {} tarefas", tasks.len());
+        self.add_batch_to_dependency_graph(tasks).await;
+        
+        let mut items = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            items.push(self.build_schedule_item(task).await);
+        }
+        
+        // Adicionar à fila (as vagas passam a ser liberadas no despacho)
+        {
+            let mut index = self.queue_index.write().await;
+            index.reserve(items.len());
+            for item in &items {
+                index.insert(item.task_id, QueueIndexEntry { item: item.clone(), retry_at: None });
+            }
+        }
+        self.schedule_queue.write().await.extend(items);
+        reservations.into_iter().for_each(QueueReservation::consume);
+        
+        info!("{} tarefas agendadas", tasks.len());
+        Ok(())
+    }
+
+    /// Devolve à fila uma tarefa que falhou, liberada só em `next_attempt_at`
+    ///
+    /// Até lá a tarefa fica em uma fila secundária ordenada por horário, que
+    /// [`Self::get_next_task`] consulta sem varrer. A retentativa ocupa uma
+    /// vaga, mas não é recusada com a fila cheia: a tarefa já foi aceita.
+    pub async fn schedule_retry(&self, task: impl Into<SharedTask>, next_attempt_at: SystemTime) -> TaskMeshResult<()> {
+        let task: SharedTask = task.into();
+        let mut schedule_item = self.build_schedule_item(&task).await;
+        schedule_item.not_before = next_attempt_at;
+        self.capacity.pending.fetch_add(1, AtomicOrdering::AcqRel);
+        self.queue_index.write().await.insert(
+            task.id,
+            QueueIndexEntry { item: schedule_item.clone(), retry_at: Some(next_attempt_at) },
+        );
+        self.retry_queue.write().await.insert((next_attempt_at, task.id), schedule_item);
+        
+        debug!(task = %task.id.short(), task_id = %task.id, "Retentativa agendada para {:?}", next_attempt_at);
+        Ok(())
+    }
+
+    /// Estima a tarefa e monta seu item de agendamento
+    async fn build_schedule_item(&self, task: &Task) -> ScheduleItem {
         // Calcular estimativa de execução
-        let estimate = self.estimate_execution(&task).await;
+        let estimate = self.estimate_execution(task).await;
         self.execution_estimates.write().await.insert(task.id, estimate.clone());
         
         // Calcular score de prioridade
-        let priority_score = self.calculate_priority_score(&task, &estimate).await;
+        let priority_score = self.calculate_priority_score(task, &estimate).await;
+        let now = SystemTime::now();
         
-        // Criar item de agendamento
-        let schedule_item = ScheduleItem {
+        ScheduleItem {
             task_id: task.id,
+            name: task.name.clone(),
+            priority: task.priority,
             priority_score,
             estimated_duration: estimate.estimated_duration,
             deadline: task.timeout.map(|timeout| {
                 task.created_at + timeout
             }),
             resource_requirements: estimate.resource_requirements,
+            tags: task.tags.clone(),
+            concurrency_group: task.concurrency_group.clone(),
+            enqueued_at: now,
+            not_before: now,
+            generation: self.next_generation.fetch_add(1, AtomicOrdering::Relaxed),
+        }
+    }
+
+    /// Recalcula o score de uma tarefa enfileirada após a troca de prioridade
+    ///
+    /// O `BinaryHeap` não atualiza itens no lugar: o item com o novo score
+    /// entra no heap com uma geração nova e a cópia antiga vira obsoleta,
+    /// descartada quando chegar ao topo em [`Self::get_next_task`].
+    /// Retentativas em backoff são trocadas diretamente na fila secundária.
+    /// Entrada na fila e fim do backoff são preservados. Retorna `false` se
+    /// a tarefa não está na fila.
+    pub async fn reprioritize(&self, task: &Task) -> bool {
+        let mut item = self.build_schedule_item(task).await;
+        let mut queue = self.schedule_queue.write().await;
+        let mut retries = self.retry_queue.write().await;
+        let mut index = self.queue_index.write().await;
+        let Some(entry) = index.get_mut(&task.id) else {
+            return false;
         };
-        
-        // Adicionar à fila
-        self.schedule_queue.write().await.push(schedule_item);
-        
-        info!("Tarefa {} agendada com prioridade {:.2}", task.id, priority_score);
-        Ok(())
+        item.enqueued_at = entry.item.enqueued_at;
+        item.not_before = entry.item.not_before;
+        entry.item = item.clone();
+        match entry.retry_at {
+            Some(retry_at) => {
+                retries.insert((retry_at, task.id), item);
+            }
+            None => queue.push(item),
+        }
+        debug!(task = %task.id.short(), task_id = %task.id, "Prioridade alterada para {}", task.priority);
+        true
+    }
+
+    /// Move para a fila principal as retentativas cujo backoff terminou
+    async fn promote_due_retries(&self, queue: &mut BinaryHeap<ScheduleItem>, now: SystemTime) {
+        let mut retries = self.retry_queue.write().await;
+        let mut index = self.queue_index.write().await;
+        while let Some(entry) = retries.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            let item = entry.remove();
+            if let Some(indexed) = index.get_mut(&item.task_id) {
+                indexed.retry_at = None;
+            }
+            queue.push(item);
+        }
     }
 
     /// Obtém a próxima tarefa para execução
+    ///
+    /// Tarefas retidas por uma pausa de despacho permanecem na fila. A
+    /// capacidade de reservas ativas é descontada de `available_resources`
+    /// para as tarefas que não são alvo delas. A tarefa selecionada ocupa a
+    /// vaga do seu grupo de concorrência; membros de um grupo cheio esperam.
     pub async fn get_next_task(&self, available_resources: &ResourceAllocation) -> Option<TaskId> {
         let mut queue = self.schedule_queue.write().await;
+        let now = SystemTime::now();
+        *self.last_available.lock().unwrap() = Some(available_resources.clone());
+        self.promote_due_retries(&mut queue, now).await;
+        for expired in self.reservations.expire(now) {
+            debug!("Reserva {} expirada", expired.id);
+        }
         
         // Verificar se há tarefas na fila
         if queue.is_empty() {
@@ -260,13 +827,24 @@ impl Scheduler {
         // Encontrar tarefa que pode ser executada com recursos disponíveis
         let mut temp_queue = BinaryHeap::new();
         let mut selected_task = None;
+        let index = self.queue_index.read().await;
         
         while let Some(item) = queue.pop() {
-            if self.can_execute_with_resources(&item, available_resources).await {
-                if self.dependencies_satisfied(&item.task_id).await {
-                    selected_task = Some(item.task_id);
-                    break;
-                }
+            if !is_current(&index, &item) {
+                continue;
+            }
+            if let Some(reason) = self.dispatch_gate.held(&item.tags, now) {
+                debug!(task = %item.task_id.short(), "Despacho retido: {}", reason);
+                temp_queue.push(item);
+                continue;
+            }
+            let available = self.reservations.available_for(available_resources, &item.task_id, &item.tags, now);
+            if self.can_execute_with_resources(&item, &available).await
+                && self.dependencies_satisfied(&item.task_id).await
+                && self.claim_group(&item)
+            {
+                selected_task = Some((item.task_id, item.not_before));
+                break;
             }
             temp_queue.push(item);
         }
@@ -275,12 +853,17 @@ impl Scheduler {
         while let Some(item) = temp_queue.pop() {
             queue.push(item);
         }
+        drop(index);
         
-        if let Some(task_id) = selected_task {
-            debug!("Próxima tarefa selecionada: {}", task_id);
-        }
+        let (task_id, not_before) = selected_task?;
+        debug!(task = %task_id.short(), task_id = %task_id, "Próxima tarefa selecionada");
+        self.queue_index.write().await.remove(&task_id);
+        self.capacity.release();
+        self.reservations.consume(&task_id);
+        let ready_at = self.dependencies_completed_at(&task_id).await.map_or(not_before, |at| at.max(not_before));
+        self.dispatch_latency.mark_scheduled(task_id, ready_at, now);
         
-        selected_task
+        Some(task_id)
     }
 
     /// Gera plano de execução otimizado
@@ -310,7 +893,33 @@ impl Scheduler {
         }
         
         // Identificar grupos paralelos
-        let parallel_groups = self.identify_parallel_groups(&execution_order).await;
+        let mut parallel_groups = self.identify_parallel_groups(&execution_order).await;
+        let problem = self.plan_problem(&graph, &node_map, &execution_order, &estimates).await;
+        let position: HashMap<TaskId, usize> = problem.tasks.iter()
+            .enumerate()
+            .map(|(i, task)| (task.id, i))
+            .collect();
+        let to_indices = |groups: &[Vec<TaskId>]| -> Vec<Vec<usize>> {
+            groups.iter().map(|g| g.iter().map(|id| position[id]).collect()).collect()
+        };
+        
+        let mut objective_improvement = None;
+        if self.config.enable_plan_optimization && !execution_order.is_empty() {
+            let optimizer = PlanOptimizer::new(self.config.plan_optimizer.clone());
+            let optimized = optimizer.optimize(&problem, &to_indices(&parallel_groups));
+            debug!(
+                "Otimização do plano: objetivo {:.2} -> {:.2} em {} iterações",
+                optimized.initial_objective, optimized.final_objective, optimized.iterations
+            );
+            
+            // Estágios em ordem respeitam as dependências: a concatenação é topológica
+            parallel_groups = optimized.groups.iter()
+                .map(|g| g.iter().map(|&i| problem.tasks[i].id).collect())
+                .collect();
+            execution_order = parallel_groups.iter().flatten().copied().collect();
+            objective_improvement = Some(optimized.improvement());
+        }
+        let estimated_makespan = problem.makespan(&to_indices(&parallel_groups));
         
         // Calcular estimativas
         let total_estimated_time = self.calculate_total_time(&execution_order, &estimates);
@@ -322,6 +931,8 @@ impl Scheduler {
             resource_efficiency: self.calculate_resource_efficiency(&execution_order, &estimates).await,
             load_factor: self.calculate_load_factor(&parallel_groups),
             critical_path_length,
+            estimated_makespan,
+            objective_improvement,
         };
         
         let plan = ExecutionPlan {
@@ -338,10 +949,48 @@ impl Scheduler {
         Ok(plan)
     }
 
+    /// Monta o problema de otimização a partir do grafo e das estimativas
+    async fn plan_problem(
+        &self,
+        graph: &DiGraph<TaskId, ()>,
+        node_map: &HashMap<TaskId, NodeIndex>,
+        execution_order: &[TaskId],
+        estimates: &HashMap<TaskId, ExecutionEstimate>,
+    ) -> PlanProblem {
+        let position: HashMap<TaskId, usize> =
+            execution_order.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let deadlines: HashMap<TaskId, SystemTime> = self.schedule_queue.read().await
+            .iter()
+            .filter_map(|item| item.deadline.map(|deadline| (item.task_id, deadline)))
+            .collect();
+        let now = SystemTime::now();
+        
+        let tasks = execution_order.iter()
+            .map(|task_id| {
+                let estimate = estimates.get(task_id);
+                PlanTask {
+                    id: *task_id,
+                    duration: estimate.map(|e| e.estimated_duration).unwrap_or_default(),
+                    cpu_cores: estimate.map(|e| e.resource_requirements.cpu_cores).unwrap_or(1.0),
+                    deadline: deadlines.get(task_id)
+                        .map(|deadline| deadline.duration_since(now).unwrap_or_default()),
+                    dependencies: node_map.get(task_id)
+                        .map(|&node| graph
+                            .neighbors_directed(node, petgraph::Direction::Incoming)
+                            .filter_map(|dep| position.get(&graph[dep]).copied())
+                            .collect())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        
+        PlanProblem { tasks, max_parallel: self.config.max_parallel_tasks }
+    }
+
     /// Atualiza heurística de agendamento
-    pub async fn update_heuristic(&mut self, heuristic: SchedulingHeuristic) {
+    pub async fn update_heuristic(&self, heuristic: SchedulingHeuristic) {
         info!("Atualizando heurística: {:?}", heuristic);
-        self.heuristic = heuristic;
+        *self.heuristic.write().await = heuristic;
         
         // Recalcular prioridades
         self.recalculate_priorities().await;
@@ -349,7 +998,9 @@ impl Scheduler {
 
     /// Relata conclusão de tarefa para aprendizado
     pub async fn report_task_completion(&self, task_id: TaskId, metrics: ExecutionMetrics) {
-        debug!("Relatando conclusão da tarefa: {}", task_id);
+        debug!(task = %task_id.short(), "Relatando conclusão da tarefa");
+        self.finished.write().await.insert(task_id, true);
+        self.completed_at.write().await.insert(task_id, SystemTime::now());
         
         if self.config.enable_adaptive_learning {
             self.update_performance_history(task_id, metrics).await;
@@ -359,11 +1010,197 @@ impl Scheduler {
 
     /// Relata falha de tarefa
     pub async fn report_task_failure(&self, task_id: TaskId, error: String) {
-        warn!("Relatando falha da tarefa {}: {}", task_id, error);
+        warn!(task = %task_id.short(), "Relatando falha da tarefa: {}", error);
+        self.finished.write().await.insert(task_id, false);
         
         // TODO: Implementar ajuste de estimativas baseado em falhas
     }
 
+    /// Retira da fila uma tarefa ainda não despachada, liberando sua vaga
+    ///
+    /// A tarefa passa a contar como falha para os dependentes. Retorna
+    /// `false` se ela não estava na fila.
+    pub async fn remove_task(&self, task_id: &TaskId) -> bool {
+        let mut queue = self.schedule_queue.write().await;
+        let mut retries = self.retry_queue.write().await;
+        let before = queue.len() + retries.len();
+        queue.retain(|item| item.task_id != *task_id);
+        retries.retain(|(_, id), _| id != task_id);
+        if queue.len() + retries.len() == before {
+            return false;
+        }
+        drop(retries);
+        drop(queue);
+        self.queue_index.write().await.remove(task_id);
+        self.capacity.release();
+        self.concurrency_groups.release(task_id);
+        self.finished.write().await.insert(*task_id, false);
+        debug!(task = %task_id.short(), "Tarefa retirada da fila");
+        true
+    }
+
+    /// Tarefas enfileiradas, do maior para o menor score
+    ///
+    /// Lê o índice secundário; o heap e a fila de retentativas não são
+    /// tocados.
+    pub async fn queue_snapshot(&self) -> Vec<QueuedTaskView> {
+        let entries: Vec<QueueIndexEntry> = self.queue_index.read().await.values().cloned().collect();
+        let now = SystemTime::now();
+        let mut views = Vec::with_capacity(entries.len());
+        for QueueIndexEntry { item, .. } in entries {
+            views.push(QueuedTaskView {
+                blocked_on: self.unmet_dependencies(&item.task_id).await,
+                throttled_by: self.throttled_by(&item, now),
+                queued_for: now.duration_since(item.enqueued_at).unwrap_or_default(),
+                task_id: item.task_id,
+                name: item.name,
+                priority_score: item.priority_score,
+                effective_priority: item.priority,
+                estimated_duration: item.estimated_duration,
+            });
+        }
+        views.sort_by(|a, b| b.priority_score.partial_cmp(&a.priority_score).unwrap_or(Ordering::Equal));
+        views
+    }
+
+    /// Por que a tarefa enfileirada ainda não foi despachada
+    ///
+    /// Os motivos seguem a ordem de [`Self::get_next_task`]: backoff,
+    /// dependências, pausas e janelas, reservas e recursos. Recursos só são
+    /// avaliados depois do primeiro despacho, que informa a capacidade livre.
+    /// Retorna `None` se a tarefa não está na fila.
+    pub async fn why_not_running(&self, task_id: &TaskId) -> Option<NotRunningReason> {
+        let QueueIndexEntry { item, retry_at } = self.queue_index.read().await.get(task_id).cloned()?;
+        let now = SystemTime::now();
+        if let Some(until) = retry_at.filter(|until| *until > now) {
+            return Some(NotRunningReason::AwaitingRetry { until });
+        }
+        let blocked_on = self.unmet_dependencies(task_id).await;
+        if !blocked_on.is_empty() {
+            return Some(NotRunningReason::DependenciesUnmet { blocked_on });
+        }
+        if let Some(pause) = self.dispatch_gate.current_pause().filter(|pause| pause.scope.covers(&item.tags)) {
+            return Some(NotRunningReason::Paused { reason: pause.reason });
+        }
+        if let Some(by) = self.throttled_by(&item, now) {
+            return Some(NotRunningReason::Throttled { by });
+        }
+        let last_available = self.last_available.lock().unwrap().clone();
+        if let Some(available) = last_available {
+            if !self.can_execute_with_resources(&item, &available).await {
+                return Some(NotRunningReason::Resources {
+                    required_cpu_cores: item.resource_requirements.cpu_cores,
+                    required_memory_bytes: item.resource_requirements.memory_bytes,
+                    available_cpu_cores: available.cpu_cores,
+                    available_memory_bytes: available.memory_bytes,
+                });
+            }
+        }
+        Some(NotRunningReason::Ready)
+    }
+
+    /// Ocupa a vaga do grupo de concorrência do item (se tiver grupo)
+    fn claim_group(&self, item: &ScheduleItem) -> bool {
+        item.concurrency_group
+            .as_deref()
+            .map_or(true, |group| self.concurrency_groups.try_acquire(group, &item.task_id))
+    }
+
+    /// Pausa, janela, grupo de concorrência ou reserva que retém o item agora
+    ///
+    /// A reserva só conta se a capacidade livre do último despacho bastaria
+    /// sem ela.
+    fn throttled_by(&self, item: &ScheduleItem, now: SystemTime) -> Option<String> {
+        if let Some(reason) = self.dispatch_gate.held(&item.tags, now) {
+            return Some(reason);
+        }
+        if let Some(group) = &item.concurrency_group {
+            if let Some((held, limit)) = self.concurrency_groups.blocked(group, &item.task_id) {
+                return Some(format!("grupo de concorrência '{}' ocupado ({}/{})", group, held, limit));
+            }
+        }
+        let available = self.last_available.lock().unwrap().clone()?;
+        let fits = |available: &ResourceAllocation| {
+            available.cpu_cores >= item.resource_requirements.cpu_cores
+                && available.memory_bytes >= item.resource_requirements.memory_bytes
+        };
+        let unreserved = self.reservations.available_for(&available, &item.task_id, &item.tags, now);
+        (fits(&available) && !fits(&unreserved)).then(|| "capacidade reservada para outras tarefas".to_string())
+    }
+
+    /// Dependências da tarefa ainda não concluídas com sucesso
+    async fn unmet_dependencies(&self, task_id: &TaskId) -> Vec<TaskId> {
+        let graph = self.dependency_graph.read().await;
+        let node_map = self.node_map.read().await;
+        let Some(&node_idx) = node_map.get(task_id) else { return Vec::new() };
+        
+        let finished = self.finished.read().await;
+        graph
+            .neighbors_directed(node_idx, petgraph::Direction::Incoming)
+            .map(|dep_idx| graph[dep_idx])
+            .filter(|dep_id| finished.get(dep_id) != Some(&true))
+            .collect()
+    }
+
+    /// Marca tarefas concluídas fora do scheduler (ex.: importadas)
+    ///
+    /// Dependentes dessas tarefas passam a ser liberadas normalmente.
+    pub async fn mark_completed(&self, task_ids: &[TaskId]) {
+        let mut finished = self.finished.write().await;
+        let mut completed_at = self.completed_at.write().await;
+        let now = SystemTime::now();
+        for task_id in task_ids {
+            finished.insert(*task_id, true);
+            completed_at.entry(*task_id).or_insert(now);
+        }
+    }
+
+    /// Acrescenta dependências a uma tarefa já agendada
+    pub async fn add_dependencies(&self, task_id: &TaskId, dependencies: &[TaskId]) {
+        let mut graph = self.dependency_graph.write().await;
+        let mut node_map = self.node_map.write().await;
+        
+        let task_node = *node_map.entry(*task_id).or_insert_with(|| graph.add_node(*task_id));
+        for dep_id in dependencies {
+            let dep_node = *node_map.entry(*dep_id).or_insert_with(|| graph.add_node(*dep_id));
+            if graph.find_edge(dep_node, task_node).is_none() {
+                graph.add_edge(dep_node, task_node, ());
+            }
+        }
+    }
+
+    /// Desfaz [`Self::add_dependencies`] para as dependências informadas
+    pub async fn remove_dependencies(&self, task_id: &TaskId, dependencies: &[TaskId]) {
+        let mut graph = self.dependency_graph.write().await;
+        let node_map = self.node_map.read().await;
+        
+        let Some(&task_node) = node_map.get(task_id) else { return };
+        for dep_id in dependencies {
+            let edge = node_map.get(dep_id).and_then(|&dep_node| graph.find_edge(dep_node, task_node));
+            if let Some(edge) = edge {
+                graph.remove_edge(edge);
+            }
+        }
+    }
+
+    /// Adiciona um lote de tarefas ao grafo com capacidade pré-alocada
+    async fn add_batch_to_dependency_graph(&self, tasks: &[SharedTask]) {
+        let edge_count = tasks.iter().map(|task| task.dependencies.len()).sum();
+        let mut graph = self.dependency_graph.write().await;
+        let mut node_map = self.node_map.write().await;
+        graph.reserve_nodes(tasks.len());
+        graph.reserve_edges(edge_count);
+        node_map.reserve(tasks.len());
+        
+        for task in tasks {
+            let task_node = *node_map.entry(task.id).or_insert_with(|| graph.add_node(task.id));
+            for dep_id in &task.dependencies {
+                let dep_node = *node_map.entry(*dep_id).or_insert_with(|| graph.add_node(*dep_id));
+                graph.add_edge(dep_node, task_node, ());
+            }
+        }
+    }
+
     /// Adiciona tarefa ao grafo de dependências
     async fn add_to_dependency_graph(&self, task: &Task) -> TaskMeshResult<()> {
         let mut graph = self.dependency_graph.write().await;
@@ -398,22 +1235,15 @@ impl Scheduler {
     async fn estimate_execution(&self, task: &Task) -> ExecutionEstimate {
         // Buscar histórico similar
         let history = self.performance_history.read().await;
-        let task_type = self.classify_task(task);
+        let features = features::extract(task, &*history);
         
-        let historical_data = history.get(&task_type)
+        let historical_data = history.get(features.definition_kind.as_str())
             .cloned()
             .unwrap_or_default();
         
-        let estimated_duration = if historical_data.is_empty() {
-            // Estimativa padrão baseada no tipo de tarefa
-            self.default_estimate_for_task(task)
-        } else {
-            // Média ponderada do histórico
-            let total_time: Duration = historical_data.iter()
-                .map(|m| m.execution_time)
-                .sum();
-            total_time / historical_data.len() as u32
-        };
+        // Média do histórico ou, sem histórico, estimativa padrão do tipo
+        let estimated_duration = features.historical_mean
+            .unwrap_or_else(|| self.default_estimate_for_task(task));
         
         // Aplicar fator de segurança
         let adjusted_duration = Duration::from_millis(
@@ -428,108 +1258,36 @@ impl Scheduler {
         
         ExecutionEstimate {
             estimated_duration: adjusted_duration,
-            resource_requirements: ResourceAllocation::default(),
+            resource_requirements: requested_resources(task),
             confidence,
             historical_data,
+            features,
         }
     }
 
-    /// Calcula score de prioridade baseado na heurística
-    async fn calculate_priority_score(&self, task: &Task, estimate: &ExecutionEstimate) -> f64 {
-        match &self.heuristic {
-            SchedulingHeuristic::FIFO => {
-                // Timestamp mais antigo = prioridade mais alta
-                -(task.created_at.duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default().as_secs() as f64)
-            },
-            SchedulingHeuristic::LIFO => {
-                // Timestamp mais recente = prioridade mais alta
-                task.created_at.duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default().as_secs() as f64
-            },
-            SchedulingHeuristic::Priority => {
-                task.priority as f64
-            },
-            SchedulingHeuristic::ShortestJobFirst => {
-                // Tarefas mais curtas têm prioridade mais alta
-                1.0 / (estimate.estimated_duration.as_secs_f64() + 1.0)
-            },
-            SchedulingHeuristic::EarliestDeadlineFirst => {
-                if let Some(timeout) = task.timeout {
-                    let deadline = task.created_at + timeout;
-                    let time_to_deadline = deadline.duration_since(SystemTime::now())
-                        .unwrap_or_default().as_secs_f64();
-                    1.0 / (time_to_deadline + 1.0)
-                } else {
-                    task.priority as f64
-                }
-            },
-            SchedulingHeuristic::CriticalRatio => {
-                if let Some(timeout) = task.timeout {
-                    let deadline = task.created_at + timeout;
-                    let time_to_deadline = deadline.duration_since(SystemTime::now())
-                        .unwrap_or_default().as_secs_f64();
-                    let processing_time = estimate.estimated_duration.as_secs_f64();
-                    
-                    if processing_time > 0.0 {
-                        time_to_deadline / processing_time
-                    } else {
-                        f64::INFINITY
-                    }
-                } else {
-                    task.priority as f64
-                }
-            },
-            SchedulingHeuristic::Genetic { .. } => {
-                // TODO: Implementar algoritmo genético
-                task.priority as f64
-            },
-            SchedulingHeuristic::Hybrid { primary, secondary, threshold } => {
-                let primary_score = self.calculate_priority_score_for_heuristic(task, estimate, primary).await;
-                let secondary_score = self.calculate_priority_score_for_heuristic(task, estimate, secondary).await;
-                
-                // Usar heurística primária se confiança é alta
-                if estimate.confidence >= *threshold {
-                    primary_score
-                } else {
-                    // Combinar ambas
-                    primary_score * estimate.confidence + secondary_score * (1.0 - estimate.confidence)
-                }
-            },
-        }
+    /// Simula um trace sob cada heurística, sem executar tarefas
+    ///
+    /// Ver [`evaluation`].
+    pub fn evaluate(trace: &WorkloadTrace, heuristics: &[SchedulingHeuristic]) -> TaskMeshResult<EvaluationReport> {
+        evaluation::evaluate(trace, heuristics)
     }
 
-    /// Cálculo recursivo para heurísticas híbridas
-    async fn calculate_priority_score_for_heuristic(
-        &self, 
-        task: &Task, 
-        estimate: &ExecutionEstimate, 
-        heuristic: &SchedulingHeuristic
-    ) -> f64 {
-        // Implementação similar ao método principal, mas sem recursão infinita
-        match heuristic {
-            SchedulingHeuristic::Priority => task.priority as f64,
-            SchedulingHeuristic::ShortestJobFirst => {
-                1.0 / (estimate.estimated_duration.as_secs_f64() + 1.0)
-            },
-            SchedulingHeuristic::CriticalRatio => {
-                if let Some(timeout) = task.timeout {
-                    let deadline = task.created_at + timeout;
-                    let time_to_deadline = deadline.duration_since(SystemTime::now())
-                        .unwrap_or_default().as_secs_f64();
-                    let processing_time = estimate.estimated_duration.as_secs_f64();
-                    
-                    if processing_time > 0.0 {
-                        time_to_deadline / processing_time
-                    } else {
-                        f64::INFINITY
-                    }
-                } else {
-                    task.priority as f64
-                }
-            },
-            _ => task.priority as f64, // Fallback
-        }
+    /// Heurística ativa
+    pub async fn heuristic(&self) -> SchedulingHeuristic {
+        self.heuristic.read().await.clone()
+    }
+
+    /// Calcula score de prioridade baseado na heurística
+    async fn calculate_priority_score(&self, task: &Task, estimate: &ExecutionEstimate) -> f64 {
+        let heuristic = self.heuristic.read().await.clone();
+        let input = ScoreInput {
+            created_at: task.created_at,
+            priority: task.priority,
+            deadline: task.timeout.map(|timeout| task.created_at + timeout),
+            estimated_duration: estimate.estimated_duration,
+            confidence: estimate.confidence,
+        };
+        heuristic_score(&heuristic, &input, SystemTime::now())
     }
 
     /// Verifica se uma tarefa pode ser executada com recursos disponíveis
@@ -545,9 +1303,31 @@ impl Scheduler {
     }
 
     /// Verifica se dependências estão satisfeitas
-    async fn dependencies_satisfied(&self, _task_id: &TaskId) -> bool {
-        // TODO: Verificar estado das dependências
-        true
+    ///
+    /// Dependências de `Task` são hard: cada uma precisa ter sido relatada
+    /// como concluída com sucesso.
+    async fn dependencies_satisfied(&self, task_id: &TaskId) -> bool {
+        let graph = self.dependency_graph.read().await;
+        let node_map = self.node_map.read().await;
+        let Some(&node_idx) = node_map.get(task_id) else { return true };
+        
+        let finished = self.finished.read().await;
+        graph
+            .neighbors_directed(node_idx, petgraph::Direction::Incoming)
+            .all(|dep_idx| finished.get(&graph[dep_idx]) == Some(&true))
+    }
+
+    /// Conclusão da última dependência da tarefa (`None` sem dependências)
+    async fn dependencies_completed_at(&self, task_id: &TaskId) -> Option<SystemTime> {
+        let graph = self.dependency_graph.read().await;
+        let node_map = self.node_map.read().await;
+        let &node_idx = node_map.get(task_id)?;
+        
+        let completed_at = self.completed_at.read().await;
+        graph
+            .neighbors_directed(node_idx, petgraph::Direction::Incoming)
+            .filter_map(|dep_idx| completed_at.get(&graph[dep_idx]).copied())
+            .max()
     }
 
     /// Identifica grupos de tarefas que podem executar em paralelo
@@ -637,24 +1417,38 @@ impl Scheduler {
         let estimates = self.execution_estimates.read().await;
         
         let items: Vec<_> = queue.drain().collect();
+        let index = self.queue_index.read().await;
+        let items: Vec<_> = items.into_iter().filter(|item| is_current(&index, item)).collect();
+        drop(index);
         
         for mut item in items {
             if let Some(estimate) = estimates.get(&item.task_id) {
-                // Criar tarefa temporária para cálculo
+                // Criar tarefa temporária para cálculo, com a prioridade atual do item
                 let temp_task = Task {
                     id: item.task_id,
                     name: "temp".to_string(),
-                    definition: TaskDefinition::Command("temp".to_string()),
+                    alias: None,
+                    definition: TaskDefinition::command("temp"),
                     dependencies: vec![],
-                    priority: 50,
+                    priority: item.priority,
                     metadata: HashMap::new(),
                     created_at: SystemTime::now(),
                     timeout: None,
                     max_retries: 0,
                     tags: vec![],
+                    group_id: None,
+                    group_name: None,
+                    sidecars: vec![],
+                    concurrency_group: None,
+                    hooks: vec![],
+                    sla: None,
+                    forced_outcome: None,
                 };
                 
                 item.priority_score = self.calculate_priority_score(&temp_task, estimate).await;
+                if let Some(indexed) = self.queue_index.write().await.get_mut(&item.task_id) {
+                    indexed.item.priority_score = item.priority_score;
+                }
             }
             queue.push(item);
         }
@@ -662,20 +1456,22 @@ impl Scheduler {
 
     /// Atualiza histórico de performance
     async fn update_performance_history(&self, task_id: TaskId, metrics: ExecutionMetrics) {
-        let mut history = self.performance_history.write().await;
-        
-        // Classificar tipo de tarefa (simplificado)
-        let task_type = format!("task_{}", task_id); // TODO: Melhorar classificação
+        // Histórico agrupado pelo tipo da definição, o mesmo usado na estimativa
+        let kind = self.execution_estimates.read().await
+            .get(&task_id)
+            .map(|estimate| estimate.features.definition_kind);
+        let Some(kind) = kind else {
+            debug!(task = %task_id.short(), "Tarefa sem estimativa; métricas fora do histórico");
+            return;
+        };
         
-        history.entry(task_type)
-            .or_insert_with(Vec::new)
-            .push(metrics);
+        let mut history = self.performance_history.write().await;
+        let task_history = history.entry(kind.as_str().to_string()).or_default();
+        task_history.push(metrics);
         
         // Limitar histórico
-        if let Some(task_history) = history.get_mut(&format!("task_{}", task_id)) {
-            if task_history.len() > 100 {
-                task_history.drain(0..50); // Manter apenas os 50 mais recentes
-            }
+        if task_history.len() > 100 {
+            task_history.drain(0..50); // Manter apenas os 50 mais recentes
         }
     }
 
@@ -684,25 +1480,15 @@ impl Scheduler {
         // TODO: Implementar ajuste inteligente de estimativas
     }
 
-    /// Classifica tipo de tarefa para histórico
-    fn classify_task(&self, task: &Task) -> String {
-        match &task.definition {
-            TaskDefinition::Command(_) => "command".to_string(),
-            TaskDefinition::PythonScript { .. } => "python".to_string(),
-            TaskDefinition::RustFunction { .. } => "rust".to_string(),
-            TaskDefinition::HttpRequest { .. } => "http".to_string(),
-            TaskDefinition::Workflow { .. } => "workflow".to_string(),
-        }
-    }
-
     /// Estimativa padrão para tipos de tarefa
     fn default_estimate_for_task(&self, task: &Task) -> Duration {
         match &task.definition {
-            TaskDefinition::Command(_) => Duration::from_secs(30),
+            TaskDefinition::Command { .. } | TaskDefinition::Exec { .. } => Duration::from_secs(30),
             TaskDefinition::PythonScript { .. } => Duration::from_secs(60),
             TaskDefinition::RustFunction { .. } => Duration::from_secs(10),
             TaskDefinition::HttpRequest { .. } => Duration::from_secs(5),
             TaskDefinition::Workflow { .. } => Duration::from_secs(300),
+            TaskDefinition::Generator { .. } => Duration::from_secs(30),
         }
     }
 }
@@ -715,15 +1501,30 @@ mod tests {
     fn create_test_task(name: &str, priority: Priority) -> Task {
         Task::new(
             name.to_string(),
-            TaskDefinition::Command("echo test".to_string()),
+            TaskDefinition::command("echo test"),
             vec![],
         ).with_priority(priority)
     }
 
+    #[test]
+    fn test_memory_request_accepts_sizes() {
+        let task = create_test_task("memória", Priority::NORMAL).with_memory(ByteSize::mib(512));
+        assert_eq!(task.metadata[MEMORY_BYTES_KEY], "512MiB");
+        assert_eq!(requested_resources(&task).memory_bytes, 512 * 1024 * 1024);
+
+        let mut legacy = create_test_task("bytes", Priority::NORMAL);
+        legacy.metadata.insert(MEMORY_BYTES_KEY.to_string(), "1048576".to_string());
+        assert_eq!(requested_resources(&legacy).memory_bytes, 1024 * 1024);
+
+        legacy.metadata.insert(MEMORY_BYTES_KEY.to_string(), "muita".to_string());
+        let violations = validate_requested_resources(&legacy, "");
+        assert!(violations[0].message.contains("'muita'"), "{:?}", violations);
+    }
+
     #[tokio::test]
     async fn test_schedule_task() {
         let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
-        let task = create_test_task("test", 80);
+        let task = create_test_task("test", Priority::HIGH);
         
         let result = scheduler.schedule_task(task).await;
         assert!(result.is_ok());
@@ -733,8 +1534,8 @@ mod tests {
     async fn test_priority_scheduling() {
         let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
         
-        let task1 = create_test_task("low", 20);
-        let task2 = create_test_task("high", 80);
+        let task1 = create_test_task("low", Priority::LOW);
+        let task2 = create_test_task("high", Priority::HIGH);
         
         scheduler.schedule_task(task1).await.unwrap();
         scheduler.schedule_task(task2).await.unwrap();
@@ -750,8 +1551,8 @@ mod tests {
     async fn test_execution_plan_generation() {
         let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
         
-        let task1 = create_test_task("task1", 50);
-        let task2 = create_test_task("task2", 60);
+        let task1 = create_test_task("task1", Priority::NORMAL);
+        let task2 = create_test_task("task2", Priority::HIGH);
         
         scheduler.schedule_task(task1).await.unwrap();
         scheduler.schedule_task(task2).await.unwrap();
@@ -762,5 +1563,298 @@ mod tests {
         let plan = plan.unwrap();
         assert_eq!(plan.execution_order.len(), 2);
     }
-}
 
+    #[tokio::test]
+    async fn test_plan_optimization_improves_makespan() {
+        // 30 tarefas, uma longa a cada três; algumas cadeias de dependência
+        let mut tasks: Vec<Task> = Vec::new();
+        for i in 0..30 {
+            let dependencies = if i >= 24 { vec![tasks[i - 12].id] } else { vec![] };
+            tasks.push(Task::new(format!("t{}", i), TaskDefinition::command("true"), dependencies));
+        }
+        let durations: HashMap<TaskId, Duration> = tasks.iter().enumerate()
+            .map(|(i, t)| (t.id, Duration::from_secs(if i % 3 == 0 { 9 } else { 1 })))
+            .collect();
+        
+        let plan_with = |enable_plan_optimization: bool| {
+            let tasks = tasks.clone();
+            let durations = durations.clone();
+            async move {
+                let config = SchedulerConfig {
+                    max_parallel_tasks: 4,
+                    enable_plan_optimization,
+                    plan_optimizer: PlanOptimizerConfig {
+                        seed: Some(42),
+                        time_budget: Duration::from_secs(30),
+                        cpu_capacity: 4.0,
+                        ..PlanOptimizerConfig::default()
+                    },
+                    ..SchedulerConfig::default()
+                };
+                let scheduler = Scheduler::with_config(SchedulingHeuristic::FIFO, config);
+                for task in tasks {
+                    scheduler.schedule_task(task).await.unwrap();
+                }
+                for (task_id, estimate) in scheduler.execution_estimates.write().await.iter_mut() {
+                    estimate.estimated_duration = durations[task_id];
+                }
+                scheduler.generate_execution_plan().await.unwrap()
+            }
+        };
+        
+        let greedy = plan_with(false).await;
+        let optimized = plan_with(true).await;
+        assert!(greedy.plan_metrics.objective_improvement.is_none());
+        assert!(optimized.plan_metrics.objective_improvement.unwrap() > 0.0);
+        
+        let greedy_makespan = greedy.plan_metrics.estimated_makespan.as_secs_f64();
+        let optimized_makespan = optimized.plan_metrics.estimated_makespan.as_secs_f64();
+        assert!(
+            optimized_makespan <= greedy_makespan * 0.9,
+            "makespan {} -> {}", greedy_makespan, optimized_makespan
+        );
+        
+        // Mesma estrutura, com dependências respeitadas entre grupos
+        assert_eq!(optimized.execution_order.len(), 30);
+        assert!(optimized.parallel_groups.iter().all(|g| g.len() <= 4));
+        let group_of = |id: &TaskId| optimized.parallel_groups.iter().position(|g| g.contains(id)).unwrap();
+        for task in &tasks {
+            for dep in &task.dependencies {
+                assert!(group_of(dep) < group_of(&task.id));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_depth_limit_and_release() {
+        let config = SchedulerConfig {
+            max_queue_depth: Some(2),
+            ..SchedulerConfig::default()
+        };
+        let scheduler = Scheduler::with_config(SchedulingHeuristic::Priority, config);
+
+        scheduler.schedule_task(create_test_task("a", Priority::NORMAL)).await.unwrap();
+        let reservation = scheduler.try_reserve().unwrap();
+        assert_eq!(scheduler.queue_depth(), 2);
+        assert!(matches!(
+            scheduler.schedule_task(create_test_task("b", Priority::NORMAL)).await,
+            Err(TaskMeshError::QueueFull { pending: 2, limit: 2 })
+        ));
+
+        // Reserva descartada devolve a vaga
+        drop(reservation);
+        assert_eq!(scheduler.queue_depth(), 1);
+
+        // Despacho libera vaga para quem aguarda
+        scheduler.schedule_task(create_test_task("c", Priority::NORMAL)).await.unwrap();
+        let waiter = scheduler.reserve(Duration::from_secs(5));
+        let dispatch = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            scheduler.get_next_task(&ResourceAllocation::default()).await
+        };
+        let (reservation, dispatched) = tokio::join!(waiter, dispatch);
+        assert!(dispatched.is_some());
+        assert!(reservation.is_ok());
+        assert_eq!(scheduler.queue_depth(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_gate_dispatch() {
+        let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
+        let parent = create_test_task("parent", Priority::LOW);
+        let child = Task::new("child".to_string(), TaskDefinition::command("echo child"), vec![parent.id])
+            .with_priority(Priority::CRITICAL);
+        let (parent_id, child_id) = (parent.id, child.id);
+        scheduler.schedule_task(child).await.unwrap();
+        scheduler.schedule_task(parent).await.unwrap();
+        
+        let resources = ResourceAllocation::default();
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(parent_id));
+        assert_eq!(scheduler.get_next_task(&resources).await, None);
+        
+        scheduler.report_task_failure(parent_id, "boom".to_string()).await;
+        assert_eq!(scheduler.get_next_task(&resources).await, None);
+        
+        scheduler.report_task_completion(parent_id, ExecutionMetrics::default()).await;
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(child_id));
+    }
+
+    #[tokio::test]
+    async fn test_completed_tasks_feed_estimates_of_same_kind() {
+        let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
+        let first = create_test_task("first", Priority::NORMAL);
+        let first_id = first.id;
+        scheduler.schedule_task(first).await.unwrap();
+        assert_eq!(scheduler.execution_estimates.read().await[&first_id].features.historical_samples, 0);
+        
+        let metrics = ExecutionMetrics { execution_time: Duration::from_secs(2), ..Default::default() };
+        scheduler.report_task_completion(first_id, metrics).await;
+        
+        let second = create_test_task("second", Priority::NORMAL);
+        let second_id = second.id;
+        scheduler.schedule_task(second).await.unwrap();
+        let estimate = scheduler.execution_estimates.read().await[&second_id].clone();
+        assert_eq!(estimate.features.historical_mean, Some(Duration::from_secs(2)));
+        assert_eq!(estimate.historical_data.len(), 1);
+        assert_eq!(
+            estimate.estimated_duration,
+            Duration::from_millis((2000.0 * SchedulerConfig::default().safety_factor) as u64)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_priority_newtype_orders_dispatch() {
+        let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
+        let low = create_test_task("low", Priority::LOW);
+        let critical = create_test_task("critical", Priority::CRITICAL);
+        let normal = create_test_task("normal", Priority::NORMAL);
+        let ids = [critical.id, normal.id, low.id];
+        for task in [low, critical, normal] {
+            scheduler.schedule_task(task).await.unwrap();
+        }
+
+        let resources = ResourceAllocation::default();
+        for expected in ids {
+            assert_eq!(scheduler.get_next_task(&resources).await, Some(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reprioritize_reorders_queue_without_duplicates() {
+        let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
+        let low = create_test_task("low", Priority::LOW);
+        let high = create_test_task("high", Priority::HIGH);
+        let normal = create_test_task("normal", Priority::NORMAL);
+        for task in [low.clone(), high.clone(), normal.clone()] {
+            scheduler.schedule_task(task).await.unwrap();
+        }
+
+        let bumped = low.clone().with_priority(Priority::CRITICAL);
+        assert!(scheduler.reprioritize(&bumped).await);
+        assert!(!scheduler.reprioritize(&create_test_task("fora", Priority::HIGH)).await);
+        let snapshot = scheduler.queue_snapshot().await;
+        assert_eq!((snapshot.len(), snapshot[0].task_id, snapshot[0].effective_priority), (3, low.id, Priority::CRITICAL));
+
+        // A cópia antiga de `low` continua no heap, mas é descartada
+        let resources = ResourceAllocation::default();
+        for expected in [low.id, high.id, normal.id] {
+            assert_eq!(scheduler.get_next_task(&resources).await, Some(expected));
+        }
+        assert_eq!(scheduler.get_next_task(&resources).await, None);
+        assert_eq!(scheduler.queue_depth(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_change_keeps_bumped_priority() {
+        let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
+        let low = create_test_task("low", Priority::LOW);
+        let normal = create_test_task("normal", Priority::NORMAL);
+        for task in [low.clone(), normal.clone()] {
+            scheduler.schedule_task(task).await.unwrap();
+        }
+        assert!(scheduler.reprioritize(&low.clone().with_priority(Priority::CRITICAL)).await);
+
+        // Recalcular os scores não desfaz a prioridade alterada
+        scheduler.update_heuristic(SchedulingHeuristic::Priority).await;
+        let resources = ResourceAllocation::default();
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(low.id));
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(normal.id));
+    }
+
+    #[tokio::test]
+    async fn test_reservation_holds_capacity_for_matching_task() {
+        use crate::reservation::{ReservationTarget, TimeRange};
+
+        let two_cores = ResourceAllocation { cpu_cores: 2.0, ..ResourceAllocation::default() };
+        let config = SchedulerConfig { reservation_capacity: two_cores.clone(), ..SchedulerConfig::default() };
+        let scheduler = Scheduler::with_config(SchedulingHeuristic::Priority, config);
+        let now = SystemTime::now();
+        scheduler
+            .reserve_resources(ReservationRequest {
+                resources: ResourceAllocation { memory_bytes: 0, ..two_cores.clone() },
+                window: TimeRange::new(now, now + Duration::from_secs(1)),
+                for_tag_or_task: ReservationTarget::Tag("critical".to_string()),
+            })
+            .unwrap();
+
+        let mut background = create_test_task("background", Priority::HIGH);
+        background.metadata.insert(CPU_CORES_KEY.to_string(), "2".to_string());
+        scheduler.schedule_task(background.clone()).await.unwrap();
+        assert_eq!(scheduler.get_next_task(&two_cores).await, None);
+
+        let mut critical = create_test_task("critical", Priority::LOW);
+        critical.metadata.insert(CPU_CORES_KEY.to_string(), "2".to_string());
+        critical.tags = vec!["critical".to_string()];
+        scheduler.schedule_task(critical.clone()).await.unwrap();
+        assert_eq!(scheduler.get_next_task(&two_cores).await, Some(critical.id));
+        assert_eq!(scheduler.get_next_task(&two_cores).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_group_holds_members_until_release() {
+        let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
+        let first = create_test_task("migracao_1", Priority::HIGH).with_concurrency_group("db");
+        let second = create_test_task("migracao_2", Priority::NORMAL).with_concurrency_group("db");
+        let other = create_test_task("relatorio", Priority::LOW);
+        let (first_id, second_id, other_id) = (first.id, second.id, other.id);
+        for task in [first, second, other] {
+            scheduler.schedule_task(task).await.unwrap();
+        }
+
+        let resources = ResourceAllocation::default();
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(first_id));
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(other_id));
+        assert_eq!(scheduler.get_next_task(&resources).await, None);
+        let snapshot = scheduler.queue_snapshot().await;
+        assert_eq!(snapshot[0].throttled_by.as_deref(), Some("grupo de concorrência 'db' ocupado (1/1)"));
+
+        scheduler.concurrency_groups.release(&first_id);
+        assert_eq!(scheduler.get_next_task(&resources).await, Some(second_id));
+    }
+
+    #[tokio::test]
+    async fn test_queue_snapshot_explains_waiting_tasks() {
+        use crate::maintenance::{DispatchPause, DispatchScope};
+
+        let scheduler = Scheduler::new(SchedulingHeuristic::Priority);
+        let ready = create_test_task("ready", Priority::LOW);
+        let blocked = Task::new("blocked".to_string(), TaskDefinition::command("echo blocked"), vec![ready.id])
+            .with_priority(Priority::CRITICAL);
+        let mut throttled = create_test_task("throttled", Priority::HIGH);
+        throttled.tags = vec!["etl".to_string()];
+        let (ready_id, blocked_id, throttled_id) = (ready.id, blocked.id, throttled.id);
+        for task in [ready, blocked, throttled] {
+            scheduler.schedule_task(task).await.unwrap();
+        }
+        scheduler.dispatch_gate().pause(DispatchPause {
+            reason: "carga do ETL".to_string(),
+            paused_at: SystemTime::now(),
+            scope: DispatchScope::Tags(vec!["etl".to_string()]),
+        });
+
+        let snapshot = scheduler.queue_snapshot().await;
+        let order: Vec<TaskId> = snapshot.iter().map(|view| view.task_id).collect();
+        assert_eq!(order, vec![blocked_id, throttled_id, ready_id]);
+        assert_eq!(snapshot[0].blocked_on, vec![ready_id]);
+        assert_eq!(snapshot[0].throttled_by, None);
+        assert_eq!(snapshot[1].blocked_on, Vec::<TaskId>::new());
+        assert_eq!(snapshot[1].throttled_by.as_deref(), Some("carga do ETL"));
+        assert_eq!(snapshot[2].effective_priority, Priority::LOW);
+        assert!(snapshot[2].blocked_on.is_empty() && snapshot[2].throttled_by.is_none());
+
+        assert_eq!(
+            scheduler.why_not_running(&blocked_id).await,
+            Some(NotRunningReason::DependenciesUnmet { blocked_on: vec![ready_id] })
+        );
+        assert_eq!(
+            scheduler.why_not_running(&throttled_id).await,
+            Some(NotRunningReason::Paused { reason: "carga do ETL".to_string() })
+        );
+        assert_eq!(scheduler.why_not_running(&ready_id).await, Some(NotRunningReason::Ready));
+
+        // O despacho retira a tarefa do índice sem mexer nas demais
+        assert_eq!(scheduler.get_next_task(&ResourceAllocation::default()).await, Some(ready_id));
+        assert_eq!(scheduler.why_not_running(&ready_id).await, None);
+        assert_eq!(scheduler.queue_snapshot().await.len(), 2);
+    }
+}
@@ -0,0 +1,177 @@
+//! Diretórios de trabalho temporários por tarefa
+//!
+//! Cada tarefa recebe `{dir}/{task_id}`, exportado como `TASKMESH_SCRATCH`
+//! e usado como diretório de trabalho. O diretório é removido quando a
+//! tarefa termina, é cancelada ou expira; falhas podem mantê-lo para
+//! depuração (`retain_on_failure`) até que a varredura o remova após
+//! `retention`. A varredura também remove diretórios órfãos deixados por
+//! execuções interrompidas.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+
+use crate::types::*;
+use crate::units::ByteSize;
+
+/// Variável de ambiente com o diretório temporário da tarefa
+pub const SCRATCH_ENV: &str = "TASKMESH_SCRATCH";
+
+/// Metadado com o limite de espaço da tarefa (bytes)
+pub const SCRATCH_LIMIT_KEY: &str = "scratch_limit_bytes";
+
+/// Metadado que substitui o diretório de trabalho padrão
+pub const WORKING_DIR_KEY: &str = "working_dir";
+
+/// Configuração dos diretórios temporários
+#[derive(Debug, Clone)]
+pub struct ScratchConfig {
+    /// Diretório raiz (`{data_dir}/scratch`)
+    pub dir: PathBuf,
+    /// Limite padrão por tarefa (bytes); `None` = ilimitado
+    pub limit_bytes: Option<u64>,
+    /// Intervalo entre medições do espaço usado
+    pub check_interval: Duration,
+    /// Mantém o diretório de tarefas que falharam
+    pub retain_on_failure: bool,
+    /// Idade a partir da qual diretórios inativos são removidos
+    pub retention: Duration,
+    /// Intervalo da varredura periódica
+    pub sweep_interval: Duration,
+}
+
+impl ScratchConfig {
+    /// Configuração padrão para um diretório
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            limit_bytes: None,
+            check_interval: Duration::from_millis(250),
+            retain_on_failure: false,
+            retention: Duration::from_secs(24 * 60 * 60),
+            sweep_interval: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// Diretórios temporários das tarefas
+#[derive(Debug)]
+pub struct ScratchSpace {
+    config: ScratchConfig,
+    /// Tarefas cujo diretório está em uso
+    active: Mutex<HashSet<TaskId>>,
+}
+
+impl ScratchSpace {
+    /// Cria o gerenciador, garantindo que o diretório raiz exista
+    pub async fn new(config: ScratchConfig) -> TaskMeshResult<Self> {
+        tokio::fs::create_dir_all(&config.dir).await?;
+        info!("Scratch em {}", config.dir.display());
+        Ok(Self { config, active: Mutex::new(HashSet::new()) })
+    }
+
+    pub fn config(&self) -> &ScratchConfig {
+        &self.config
+    }
+
+    /// Diretório de uma tarefa
+    pub fn path(&self, task_id: &TaskId) -> PathBuf {
+        self.config.dir.join(task_id.to_string())
+    }
+
+    /// Limite de espaço de uma tarefa (metadado ou padrão da configuração)
+    pub fn limit_for(&self, task: &Task) -> TaskMeshResult<Option<u64>> {
+        match task.metadata.get(SCRATCH_LIMIT_KEY) {
+            Some(value) => value.parse::<ByteSize>().map(|limit| Some(limit.into())).map_err(|e| {
+                TaskMeshError::Configuration(format!("{} inválido: {}", SCRATCH_LIMIT_KEY, e))
+            }),
+            None => Ok(self.config.limit_bytes),
+        }
+    }
+
+    /// Cria um diretório vazio para a tarefa
+    pub async fn create(&self, task_id: &TaskId) -> TaskMeshResult<PathBuf> {
+        let path = self.path(task_id);
+        remove_dir_if_exists(&path).await?;
+        tokio::fs::create_dir_all(&path).await?;
+        self.active.lock().unwrap().insert(*task_id);
+        Ok(path)
+    }
+
+    /// Encerra o uso do diretório, removendo-o ou mantendo-o para depuração
+    pub async fn release(&self, task_id: &TaskId, retain: bool) -> TaskMeshResult<()> {
+        self.active.lock().unwrap().remove(task_id);
+        if retain {
+            info!("Scratch da tarefa {} mantido em {}", task_id, self.path(task_id).display());
+            return Ok(());
+        }
+        remove_dir_if_exists(&self.path(task_id)).await
+    }
+
+    /// Aguarda até o diretório ultrapassar `limit` bytes e retorna o uso medido
+    pub async fn exceeded(&self, task_id: &TaskId, limit: u64) -> u64 {
+        let path = self.path(task_id);
+        let mut ticker = tokio::time::interval(self.config.check_interval);
+        loop {
+            ticker.tick().await;
+            let measured = path.clone();
+            let used = tokio::task::spawn_blocking(move || dir_size(&measured))
+                .await
+                .unwrap_or(0);
+            if used > limit {
+                return used;
+            }
+        }
+    }
+
+    /// Remove diretórios inativos mais antigos que `retention`
+    ///
+    /// Retorna o número de diretórios removidos.
+    pub async fn sweep(&self) -> TaskMeshResult<usize> {
+        let cutoff = SystemTime::now() - self.config.retention;
+        let mut removed = 0;
+
+        let mut entries = tokio::fs::read_dir(&self.config.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let active = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<TaskId>().ok())
+                .is_some_and(|task_id| self.active.lock().unwrap().contains(&task_id));
+            let modified = entry.metadata().await?.modified()?;
+            if !active && modified < cutoff {
+                debug!("Removendo scratch órfão {}", entry.path().display());
+                match tokio::fs::remove_dir_all(entry.path()).await {
+                    Ok(()) => removed += 1,
+                    Err(e) => warn!("Falha ao remover {}: {}", entry.path().display(), e),
+                }
+            }
+        }
+
+        info!("Varredura de scratch: {} diretórios removidos", removed);
+        Ok(removed)
+    }
+}
+
+/// Tamanho total dos arquivos sob `path` (sem seguir links)
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+async fn remove_dir_if_exists(path: &Path) -> TaskMeshResult<()> {
+    match tokio::fs::remove_dir_all(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
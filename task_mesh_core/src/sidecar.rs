@@ -0,0 +1,406 @@
+//! Processos auxiliares (sidecars) com a duração da tarefa
+//!
+//! Algumas tarefas precisam de um processo auxiliar vivo enquanto rodam (um
+//! proxy local, um agente de métricas). Os sidecars ficam em
+//! `Task::sidecars` (ver [`Task::with_sidecar`]). O executor os inicia antes
+//! do comando principal, na ordem declarada, e aguarda cada um ficar pronto:
+//!
+//! - sem sonda, o sidecar é considerado pronto ao iniciar;
+//! - [`ReadinessProbe::Tcp`]: uma conexão TCP é aceita;
+//! - [`ReadinessProbe::Command`]: um comando termina com código 0.
+//!
+//! Um sidecar que sai ou não fica pronto em `ready_timeout_ms` falha a tarefa
+//! com [`TaskMeshError::SidecarNotReady`]. O comando principal recebe
+//! `TASKMESH_SIDECAR_{NOME}_PID` e, com sonda TCP, `_HOST`, `_PORT` e
+//! `_ADDR`. Ao fim do comando principal (ou no cancelamento) os sidecars
+//! recebem SIGTERM e, após `stop_grace_ms`, SIGKILL no grupo de processos
+//! inteiro; a saída de cada um vai para a seção `sidecar:{nome}` do log da
+//! tarefa.
+
+use std::collections::HashMap;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, Command};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+#[cfg(not(unix))]
+use crate::executor::kill_process_tree;
+use crate::executor::TargetPlatform;
+use crate::types::*;
+use crate::validation::Violation;
+
+/// Intervalo entre tentativas da sonda de prontidão
+const PROBE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Espera pela saída dos sidecars depois de encerrados
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+fn default_ready_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_stop_grace_ms() -> u64 {
+    5_000
+}
+
+fn default_probe_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+/// Processo auxiliar de uma tarefa
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SidecarSpec {
+    /// Nome, único na tarefa (vira `TASKMESH_SIDECAR_{NOME}_*`)
+    pub name: String,
+    /// Comando, executado no shell da plataforma
+    pub command: String,
+    #[serde(default)]
+    pub readiness: Option<ReadinessProbe>,
+    /// Prazo para o sidecar ficar pronto (ms)
+    #[serde(default = "default_ready_timeout_ms")]
+    pub ready_timeout_ms: u64,
+    /// Espera entre o SIGTERM e o SIGKILL no encerramento (ms)
+    #[serde(default = "default_stop_grace_ms")]
+    pub stop_grace_ms: u64,
+}
+
+impl SidecarSpec {
+    pub fn new(name: impl Into<String>, command: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            command: command.into(),
+            readiness: None,
+            ready_timeout_ms: default_ready_timeout_ms(),
+            stop_grace_ms: default_stop_grace_ms(),
+        }
+    }
+
+    pub fn with_readiness(mut self, readiness: ReadinessProbe) -> Self {
+        self.readiness = Some(readiness);
+        self
+    }
+
+    /// Prefixo das variáveis de ambiente do sidecar
+    fn env_prefix(&self) -> String {
+        let name: String = self
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        format!("TASKMESH_SIDECAR_{}_", name)
+    }
+}
+
+/// Como saber que o sidecar está pronto
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessProbe {
+    /// Conexão TCP aceita em `host:port`
+    Tcp {
+        #[serde(default = "default_probe_host")]
+        host: String,
+        port: u16,
+    },
+    /// Comando que termina com código 0
+    Command { command: String },
+}
+
+impl ReadinessProbe {
+    pub fn tcp(port: u16) -> Self {
+        ReadinessProbe::Tcp { host: default_probe_host(), port }
+    }
+}
+
+impl Task {
+    /// Acrescenta um sidecar à tarefa
+    pub fn with_sidecar(mut self, spec: SidecarSpec) -> Self {
+        self.sidecars.push(spec);
+        self
+    }
+}
+
+/// Verifica os sidecars da tarefa
+///
+/// Os campos das violações recebem `prefix` (ex.: `tasks[2].`).
+pub fn validate(task: &Task, prefix: &str) -> Vec<Violation> {
+    let field = format!("{}sidecars", prefix);
+    let mut violations = Vec::new();
+    let mut prefixes = HashMap::new();
+    for (index, sidecar) in task.sidecars.iter().enumerate() {
+        if sidecar.name.trim().is_empty() || sidecar.command.trim().is_empty() {
+            violations.push(Violation {
+                field: format!("{}[{}]", field, index),
+                message: "nome e comando são obrigatórios".to_string(),
+            });
+        }
+        if let Some(previous) = prefixes.insert(sidecar.env_prefix(), &sidecar.name) {
+            violations.push(Violation {
+                field: format!("{}[{}].name", field, index),
+                message: format!("'{}' colide com '{}'", sidecar.name, previous),
+            });
+        }
+    }
+    violations
+}
+
+/// Saída de um sidecar encerrado
+#[derive(Debug, Clone)]
+pub struct SidecarOutput {
+    pub name: String,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Sidecar em execução
+struct RunningSidecar {
+    spec: SidecarSpec,
+    child: Child,
+    pid: Option<u32>,
+    stdout: JoinHandle<Vec<u8>>,
+    stderr: JoinHandle<Vec<u8>>,
+}
+
+/// Sidecars de uma tarefa, encerrados por [`Self::stop`] ou ao serem descartados
+pub struct Sidecars {
+    running: Vec<RunningSidecar>,
+    platform: TargetPlatform,
+}
+
+impl Sidecars {
+    /// Inicia os sidecars em ordem, aguardando cada um ficar pronto, e
+    /// acrescenta ao `context` as variáveis de conexão
+    ///
+    /// Em caso de falha os sidecars já iniciados são encerrados.
+    pub async fn start(
+        specs: &[SidecarSpec],
+        context: &mut ExecutionContext,
+        platform: TargetPlatform,
+        cancel_token: &CancellationToken,
+    ) -> TaskMeshResult<Self> {
+        let mut sidecars = Self { running: Vec::with_capacity(specs.len()), platform };
+        for spec in specs {
+            let started = match sidecars.spawn(spec, context) {
+                Ok(sidecar) => sidecar,
+                Err(e) => {
+                    sidecars.stop().await;
+                    return Err(e);
+                }
+            };
+            sidecars.running.push(started);
+            let index = sidecars.running.len() - 1;
+            let ready = tokio::select! {
+                _ = cancel_token.cancelled() => Err(TaskMeshError::ExecutionError("Tarefa cancelada".to_string())),
+                ready = sidecars.wait_ready(index, context) => ready,
+            };
+            if let Err(e) = ready {
+                sidecars.stop().await;
+                return Err(e);
+            }
+            info!("Sidecar {} pronto", spec.name);
+            sidecars.export_env(index, context);
+        }
+        Ok(sidecars)
+    }
+
+    fn spawn(&self, spec: &SidecarSpec, context: &ExecutionContext) -> TaskMeshResult<RunningSidecar> {
+        let (program, args) = self.platform.shell_command(&spec.command);
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .current_dir(&context.working_directory)
+            .envs(&context.environment)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Grupo próprio para que o encerramento alcance os filhos do sidecar
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = cmd.spawn().map_err(|e| TaskMeshError::SidecarNotReady {
+            name: spec.name.clone(),
+            reason: format!("falha ao iniciar: {}", e),
+        })?;
+        debug!("Sidecar {} iniciado (pid {:?})", spec.name, child.id());
+        Ok(RunningSidecar {
+            spec: spec.clone(),
+            pid: child.id(),
+            stdout: drain(child.stdout.take()),
+            stderr: drain(child.stderr.take()),
+            child,
+        })
+    }
+
+    /// Aguarda a sonda do sidecar passar dentro do prazo
+    async fn wait_ready(&mut self, index: usize, context: &ExecutionContext) -> TaskMeshResult<()> {
+        let platform = self.platform;
+        let sidecar = &mut self.running[index];
+        let name = sidecar.spec.name.clone();
+        let not_ready = |reason: String| TaskMeshError::SidecarNotReady { name: name.clone(), reason };
+        let Some(probe) = sidecar.spec.readiness.clone() else { return Ok(()) };
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(sidecar.spec.ready_timeout_ms);
+
+        loop {
+            if let Some(status) = sidecar.child.try_wait()? {
+                return Err(not_ready(format!("saiu antes de ficar pronto ({})", describe_exit(status))));
+            }
+            if probe_once(&probe, context, platform).await {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(not_ready(format!(
+                    "sonda {:?} sem sucesso em {} ms",
+                    probe, sidecar.spec.ready_timeout_ms
+                )));
+            }
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+    }
+
+    /// Expõe ao comando principal como alcançar o sidecar
+    fn export_env(&self, index: usize, context: &mut ExecutionContext) {
+        let sidecar = &self.running[index];
+        let prefix = sidecar.spec.env_prefix();
+        if let Some(pid) = sidecar.pid {
+            context.environment.insert(format!("{}PID", prefix), pid.to_string());
+        }
+        if let Some(ReadinessProbe::Tcp { host, port }) = &sidecar.spec.readiness {
+            context.environment.insert(format!("{}HOST", prefix), host.clone());
+            context.environment.insert(format!("{}PORT", prefix), port.to_string());
+            context.environment.insert(format!("{}ADDR", prefix), format!("{}:{}", host, port));
+        }
+    }
+
+    /// Encerra os sidecars (SIGTERM, espera, SIGKILL) em ordem inversa e
+    /// retorna a saída de cada um
+    pub async fn stop(mut self) -> Vec<SidecarOutput> {
+        let mut outputs = Vec::with_capacity(self.running.len());
+        while let Some(mut sidecar) = self.running.pop() {
+            terminate(&mut sidecar, self.platform).await;
+            let stdout = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, &mut sidecar.stdout).await;
+            let stderr = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, &mut sidecar.stderr).await;
+            outputs.push(SidecarOutput {
+                name: sidecar.spec.name.clone(),
+                stdout: String::from_utf8_lossy(&stdout.ok().and_then(Result::ok).unwrap_or_default()).to_string(),
+                stderr: String::from_utf8_lossy(&stderr.ok().and_then(Result::ok).unwrap_or_default()).to_string(),
+            });
+        }
+        outputs.reverse();
+        outputs
+    }
+}
+
+impl Drop for Sidecars {
+    fn drop(&mut self) {
+        // Descartado sem `stop` (tarefa abortada): mata o grupo sem espera
+        for sidecar in &self.running {
+            #[cfg(unix)]
+            if let Some(pid) = sidecar.pid {
+                signal_group(pid, libc::SIGKILL);
+            }
+            #[cfg(not(unix))]
+            let _ = sidecar;
+        }
+    }
+}
+
+/// Lê um pipe até o fim em segundo plano
+fn drain(pipe: Option<impl tokio::io::AsyncRead + Unpin + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    tokio::spawn(async move {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer).await;
+        }
+        buffer
+    })
+}
+
+async fn probe_once(probe: &ReadinessProbe, context: &ExecutionContext, platform: TargetPlatform) -> bool {
+    match probe {
+        ReadinessProbe::Tcp { host, port } => {
+            let connect = tokio::net::TcpStream::connect((host.as_str(), *port));
+            matches!(tokio::time::timeout(PROBE_INTERVAL * 10, connect).await, Ok(Ok(_)))
+        }
+        ReadinessProbe::Command { command } => {
+            let (program, args) = platform.shell_command(command);
+            Command::new(program)
+                .args(args)
+                .current_dir(&context.working_directory)
+                .envs(&context.environment)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .status()
+                .await
+                .is_ok_and(|status| status.success())
+        }
+    }
+}
+
+/// Encerra o grupo do sidecar: SIGTERM, `stop_grace_ms` de espera, SIGKILL
+async fn terminate(sidecar: &mut RunningSidecar, platform: TargetPlatform) {
+    let Some(pid) = sidecar.pid else { return };
+    let grace = Duration::from_millis(sidecar.spec.stop_grace_ms);
+
+    #[cfg(unix)]
+    {
+        signal_group(pid, libc::SIGTERM);
+        match tokio::time::timeout(grace, sidecar.child.wait()).await {
+            Ok(Ok(status)) => debug!("Sidecar {} encerrado ({})", sidecar.spec.name, describe_exit(status)),
+            _ => warn!("Sidecar {} não encerrou em {:?}; forçando", sidecar.spec.name, grace),
+        }
+        // Filhos que ignoraram o SIGTERM ou sobreviveram ao líder do grupo
+        signal_group(pid, libc::SIGKILL);
+        let _ = sidecar.child.wait().await;
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = grace;
+        kill_process_tree(platform, pid).await;
+        let _ = sidecar.child.wait().await;
+    }
+    #[cfg(unix)]
+    let _ = platform;
+}
+
+/// Envia `signal` ao grupo de processos liderado por `pid`
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: libc::c_int) {
+    // SAFETY: kill não acessa memória; ESRCH (grupo já encerrado) é ignorado
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), signal);
+    }
+}
+
+fn describe_exit(status: ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("código {}", code),
+        None => "encerrado por sinal".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sidecar_env_prefixes_must_be_unique() {
+        let task = Task::new("com-proxy".to_string(), TaskDefinition::command("true"), vec![])
+            .with_sidecar(SidecarSpec::new("web-proxy", "proxy").with_readiness(ReadinessProbe::tcp(8080)))
+            .with_sidecar(SidecarSpec::new("web_proxy", "outro"));
+
+        assert_eq!(task.sidecars.len(), 2);
+        assert_eq!(task.sidecars[0].env_prefix(), "TASKMESH_SIDECAR_WEB_PROXY_");
+        assert_eq!(task.sidecars[0].stop_grace_ms, 5_000);
+        assert!(task.metadata.is_empty());
+
+        let violations = validate(&task, "");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "sidecars[1].name");
+    }
+}
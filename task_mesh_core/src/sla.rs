@@ -0,0 +1,408 @@
+//! SLAs de tarefas com alerta e detecção de violação
+//!
+//! Uma [`SlaPolicy`] associa um prazo a tarefas (por tag ou alias) ou vem em
+//! `Task::sla` (ver [`Task::with_sla`]). O [`SlaMonitor`] compara as
+//! tarefas registradas com seus prazos e grava eventos `SlaWarning` quando
+//! `warn_at`% da janela passou e `SlaBreach` quando o prazo vence sem
+//! conclusão. Tarefas concluídas após o prazo registram a violação
+//! retroativamente. Tarefas violadas recebem o metadado
+//! [`SLA_BREACHED_KEY`].
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::alias;
+use crate::validation;
+use crate::state_store::StateStore;
+use crate::task_registry::TaskRegistry;
+use crate::types::*;
+
+/// Metadado marcado em tarefas que violaram o SLA
+pub const SLA_BREACHED_KEY: &str = "sla_breached";
+
+/// Tarefas cobertas por uma política
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaTarget {
+    Tag(String),
+    /// Alias, opcionalmente qualificado (`namespace/alias`)
+    Alias(String),
+}
+
+impl SlaTarget {
+    fn matches(&self, task: &Task) -> bool {
+        match self {
+            SlaTarget::Tag(tag) => task.tags.contains(&validation::normalize_tag(tag)),
+            SlaTarget::Alias(qualified) => {
+                let (namespace, name) = alias::split_alias(qualified, alias::DEFAULT_NAMESPACE);
+                task.alias.as_deref() == Some(name) && alias::namespace_of(task) == namespace
+            }
+        }
+    }
+}
+
+/// Prazo de conclusão
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaDeadline {
+    /// Prazo relativo à criação da tarefa (ms)
+    WithinMs(u64),
+    /// Expressão cron com segundos, em UTC (`0 0 6 * * *` = 06:00); o prazo
+    /// é a primeira ocorrência após a criação da tarefa
+    Cron(String),
+}
+
+impl SlaDeadline {
+    /// Prazo de uma tarefa criada em `created_at`
+    pub fn deadline_for(&self, created_at: SystemTime) -> TaskMeshResult<SystemTime> {
+        match self {
+            SlaDeadline::WithinMs(ms) => Ok(created_at + Duration::from_millis(*ms)),
+            SlaDeadline::Cron(expression) => {
+                let schedule = cron::Schedule::from_str(expression).map_err(|e| {
+                    TaskMeshError::Configuration(format!("Cron de SLA inválido '{}': {}", expression, e))
+                })?;
+                schedule
+                    .after(&DateTime::<Utc>::from(created_at))
+                    .next()
+                    .map(SystemTime::from)
+                    .ok_or_else(|| {
+                        TaskMeshError::Configuration(format!("Cron de SLA sem próxima ocorrência: '{}'", expression))
+                    })
+            }
+        }
+    }
+}
+
+/// Prazo e limiar de alerta de um SLA
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlaSpec {
+    pub deadline: SlaDeadline,
+    /// Percentual da janela (criação → prazo) que dispara o alerta
+    #[serde(default = "default_warn_at")]
+    pub warn_at: u8,
+}
+
+fn default_warn_at() -> u8 {
+    80
+}
+
+impl SlaSpec {
+    pub fn validate(&self) -> TaskMeshResult<()> {
+        if self.warn_at > 100 {
+            return Err(TaskMeshError::Configuration(format!(
+                "warn_at deve estar entre 0 e 100 (recebido {})", self.warn_at
+            )));
+        }
+        self.deadline.deadline_for(SystemTime::now()).map(|_| ())
+    }
+}
+
+/// Política de SLA configurada no TaskMeshConfig
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlaPolicy {
+    pub applies_to: SlaTarget,
+    #[serde(flatten)]
+    pub spec: SlaSpec,
+}
+
+/// Configuração do monitor de SLA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaConfig {
+    #[serde(default)]
+    pub policies: Vec<SlaPolicy>,
+    /// Intervalo entre avaliações (ms)
+    #[serde(default = "default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Roda o monitor mesmo sem políticas (apenas SLAs por tarefa)
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+fn default_check_interval_ms() -> u64 {
+    1000
+}
+
+impl Default for SlaConfig {
+    fn default() -> Self {
+        Self {
+            policies: Vec::new(),
+            check_interval_ms: default_check_interval_ms(),
+            enabled: false,
+        }
+    }
+}
+
+impl SlaConfig {
+    /// Indica se o monitor periódico deve rodar
+    pub fn is_active(&self) -> bool {
+        self.enabled || !self.policies.is_empty()
+    }
+}
+
+impl Task {
+    /// Define o SLA próprio da tarefa
+    pub fn with_sla(mut self, deadline: SlaDeadline, warn_at: u8) -> Self {
+        self.sla = Some(SlaSpec { deadline, warn_at });
+        self
+    }
+}
+
+/// Contagem de alertas e violações
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SlaStats {
+    pub warnings: u64,
+    pub breaches: u64,
+}
+
+/// Acompanhamento de uma tarefa
+#[derive(Debug, Default)]
+struct SlaProgress {
+    warned: bool,
+    /// Violação registrada ou tarefa encerrada
+    settled: bool,
+}
+
+/// Avalia tarefas contra seus SLAs
+pub struct SlaMonitor {
+    policies: Vec<SlaPolicy>,
+    progress: Mutex<HashMap<TaskId, SlaProgress>>,
+    warnings: AtomicU64,
+    breaches: AtomicU64,
+}
+
+impl SlaMonitor {
+    pub fn new(policies: Vec<SlaPolicy>) -> TaskMeshResult<Self> {
+        for policy in &policies {
+            policy.spec.validate()?;
+        }
+        Ok(Self {
+            policies,
+            progress: Mutex::new(HashMap::new()),
+            warnings: AtomicU64::new(0),
+            breaches: AtomicU64::new(0),
+        })
+    }
+
+    pub fn stats(&self) -> SlaStats {
+        SlaStats {
+            warnings: self.warnings.load(Ordering::Relaxed),
+            breaches: self.breaches.load(Ordering::Relaxed),
+        }
+    }
+
+    /// SLA de uma tarefa: o da própria tarefa ou a primeira política aplicável
+    fn spec_for(&self, task: &Task) -> Option<SlaSpec> {
+        if let Some(spec) = &task.sla {
+            return Some(spec.clone());
+        }
+        self.policies
+            .iter()
+            .find(|policy| policy.applies_to.matches(task))
+            .map(|policy| policy.spec.clone())
+    }
+
+    /// Avalia as tarefas registradas e grava os eventos gerados
+    pub async fn evaluate(
+        &self,
+        registry: &RwLock<TaskRegistry>,
+        store: &dyn StateStore,
+    ) -> TaskMeshResult<Vec<SystemEvent>> {
+        let candidates: Vec<(TaskId, SlaSpec, SystemTime)> = {
+            let registry = registry.read().await;
+            let progress = self.progress.lock().unwrap();
+            registry
+                .list_tasks()?
+                .into_iter()
+                .filter(|task| !progress.get(&task.id).is_some_and(|p| p.settled))
+                .filter_map(|task| self.spec_for(&task).map(|spec| (task.id, spec, task.created_at)))
+                .collect()
+        };
+
+        let now = SystemTime::now();
+        let mut events = Vec::new();
+        for (task_id, spec, created_at) in candidates {
+            let deadline = match spec.deadline.deadline_for(created_at) {
+                Ok(deadline) => deadline,
+                Err(e) => {
+                    warn!("SLA da tarefa {} ignorado: {}", task_id, e);
+                    continue;
+                }
+            };
+            let status = store.get_task_status(&task_id).await?;
+            let event = self.check(task_id, &spec, created_at, deadline, &status, now);
+            if let Some(event) = event {
+                if event.event_type == EventType::SlaBreach {
+                    if let Some(task) = registry.write().await.get_task_mut(&task_id) {
+                        task.metadata.insert(SLA_BREACHED_KEY.to_string(), "true".to_string());
+                    }
+                }
+                store.store_event(&event).await?;
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    fn check(
+        &self,
+        task_id: TaskId,
+        spec: &SlaSpec,
+        created_at: SystemTime,
+        deadline: SystemTime,
+        status: &TaskStatus,
+        now: SystemTime,
+    ) -> Option<SystemEvent> {
+        let mut guard = self.progress.lock().unwrap();
+        let progress = guard.entry(task_id).or_default();
+        let event = |event_type: EventType, data: serde_json::Value| SystemEvent::new(
+            event_type,
+            Some(task_id),
+            data,
+        ).at(now);
+        let deadline_ms = deadline
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        match status {
+            TaskStatus::Completed { completed_at, .. } => {
+                progress.settled = true;
+                (*completed_at > deadline).then(|| {
+                    self.breaches.fetch_add(1, Ordering::Relaxed);
+                    event(EventType::SlaBreach, serde_json::json!({
+                        "deadline_ms": deadline_ms,
+                        "retroactive": true,
+                    }))
+                })
+            }
+            status if status.is_final() => {
+                progress.settled = true;
+                None
+            }
+            _ if now >= deadline => {
+                progress.settled = true;
+                self.breaches.fetch_add(1, Ordering::Relaxed);
+                Some(event(EventType::SlaBreach, serde_json::json!({
+                    "deadline_ms": deadline_ms,
+                    "retroactive": false,
+                })))
+            }
+            _ => {
+                let window = deadline.duration_since(created_at).unwrap_or_default();
+                let elapsed = now.duration_since(created_at).unwrap_or_default();
+                let percent = if window.is_zero() { 100 } else { elapsed.as_millis() * 100 / window.as_millis() };
+                if progress.warned || percent < u128::from(spec.warn_at) {
+                    return None;
+                }
+                progress.warned = true;
+                self.warnings.fetch_add(1, Ordering::Relaxed);
+                Some(event(EventType::SlaWarning, serde_json::json!({
+                    "deadline_ms": deadline_ms,
+                    "elapsed_percent": percent as u64,
+                })))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    async fn sla_core() -> TaskMeshCore {
+        let config = TaskMeshConfig {
+            strict_durability: true,
+            sla: SlaConfig {
+                policies: vec![SlaPolicy {
+                    applies_to: SlaTarget::Tag("report".to_string()),
+                    spec: SlaSpec { deadline: SlaDeadline::WithinMs(200), warn_at: 50 },
+                }],
+                ..SlaConfig::default()
+            },
+            ..TaskMeshConfig::default()
+        };
+        TaskMeshCore::new(config).await.unwrap()
+    }
+
+    fn report_task() -> Task {
+        Task::new("nightly-report".to_string(), TaskDefinition::command("sleep 0.5"), vec![])
+            .with_tags(vec!["report".to_string()])
+    }
+
+    #[test]
+    fn test_own_sla_comes_from_typed_field_only() {
+        let monitor = SlaMonitor::new(vec![]).unwrap();
+        let forged = Task::new("forjada".to_string(), TaskDefinition::command("true"), vec![])
+            .with_metadata("sla".to_string(), r#"{"deadline":{"within_ms":1},"warn_at":0}"#.to_string());
+        assert_eq!(monitor.spec_for(&forged), None);
+
+        let own = forged.with_sla(SlaDeadline::WithinMs(100), 50);
+        assert_eq!(monitor.spec_for(&own), Some(SlaSpec { deadline: SlaDeadline::WithinMs(100), warn_at: 50 }));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sla_warning_then_breach_while_running() {
+        let core = sla_core().await;
+        core.start().await.unwrap();
+        let task = report_task();
+        let task_id = core.submit_task(task.clone()).await.unwrap();
+        core.executor.execute_task(task).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        let events = core.check_slas().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::SlaWarning);
+        assert!(events[0].data["elapsed_percent"].as_u64().unwrap() >= 50);
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        let events = core.check_slas().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::SlaBreach);
+        assert_eq!(events[0].task_id, Some(task_id));
+        assert_eq!(events[0].data["retroactive"], false);
+
+        let registry = core.registry.read().await;
+        assert_eq!(registry.get_task(&task_id).unwrap().metadata[SLA_BREACHED_KEY], "true");
+        drop(registry);
+        assert_eq!(core.sla_stats(), SlaStats { warnings: 1, breaches: 1 });
+
+        let stored = core.state_store.get_events(None, None).await.unwrap();
+        assert!(stored.iter().any(|e| e.event_type == EventType::SlaBreach && e.task_id == Some(task_id)));
+        core.shutdown().await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sla_breach_registered_for_late_completion() {
+        let core = sla_core().await;
+        core.start().await.unwrap();
+        let task = report_task();
+        let task_id = core.submit_task(task.clone()).await.unwrap();
+        core.executor.execute_task(task).await.unwrap();
+
+        // Primeira avaliação só depois da conclusão
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !core.get_task_status(task_id).await.unwrap().is_final() {
+            assert!(std::time::Instant::now() < deadline);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let events = core.check_slas().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::SlaBreach);
+        assert_eq!(events[0].task_id, Some(task_id));
+        assert_eq!(events[0].data["retroactive"], true);
+
+        // Tarefas já resolvidas não geram novos eventos
+        assert!(core.check_slas().await.unwrap().is_empty());
+        core.shutdown().await.unwrap();
+    }
+}
@@ -1,16 +1,24 @@
 //! Armazenamento de estado com suporte a SQLite e Redis
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::SystemTime;
 use async_trait::async_trait;
+use dashmap::DashMap;
 use serde_json;
-use sqlx::{Database, Pool, Row, SqlitePool, PgPool};
+use sqlx::{Connection, Row, SqlitePool, PgPool};
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous,
+};
 use redis::{AsyncCommands, Client as RedisClient, aio::Connection as RedisConnection};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn, instrument};
 
 use crate::types::*;
+use crate::codec::{Codec, CodecConfig};
+use crate::compat::{check_format_version, FORMAT_VERSION};
+use crate::encryption::PayloadCipher;
+use crate::manifest::ExecutionManifest;
 use crate::TaskMeshResult;
 
 /// Trait para armazenamento de estado
@@ -22,7 +30,7 @@ pub trait StateStore: Send + Sync {
     /// Recupera uma tarefa por ID
     async fn get_task(&self, task_id: &TaskId) -> TaskMeshResult<Option<Task>>;
     
-    /// Remove uma tarefa
+    /// Remove uma tarefa com status, histórico, métricas, tentativas e manifestos
     async fn remove_task(&self, task_id: &TaskId) -> TaskMeshResult<()>;
     
     /// Atualiza status de uma tarefa
@@ -31,21 +39,108 @@ pub trait StateStore: Send + Sync {
     /// Recupera status de uma tarefa
     async fn get_task_status(&self, task_id: &TaskId) -> TaskMeshResult<TaskStatus>;
     
+    /// Recupera o histórico de transições de status, em ordem
+    async fn get_status_history(&self, task_id: &TaskId) -> TaskMeshResult<Vec<StatusTransition>>;
+    
     /// Lista todas as tarefas
     async fn list_tasks(&self) -> TaskMeshResult<Vec<Task>>;
     
+    /// Lista uma página de tarefas em ordem de ID, após `after` (exclusivo)
+    ///
+    /// Backends sem consulta paginada carregam todas as tarefas e recortam a página.
+    async fn list_tasks_page(&self, after: Option<&TaskId>, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        let mut tasks = self.list_tasks().await?;
+        tasks.retain(|task| after.map_or(true, |after| task.id > *after));
+        tasks.sort_by_key(|task| task.id);
+        tasks.truncate(limit);
+        Ok(tasks)
+    }
+    
     /// Lista tarefas com status específico
     async fn list_tasks_by_status(&self, status_filter: &[TaskStatus]) -> TaskMeshResult<Vec<Task>>;
     
+    /// Lista as tarefas de um grupo
+    async fn list_group_tasks(&self, group_id: &uuid::Uuid) -> TaskMeshResult<Vec<Task>> {
+        let mut tasks = self.list_tasks().await?;
+        tasks.retain(|task| task.group_id.as_ref() == Some(group_id));
+        Ok(tasks)
+    }
+    
+    /// Busca textual no nome e nos valores de metadados, até `limit` tarefas
+    ///
+    /// Correspondências exatas do nome vêm primeiro, depois as demais no nome
+    /// e por fim as só nos metadados. Backends sem índice textual comparam
+    /// substrings (sem diferenciar maiúsculas) em todas as tarefas.
+    async fn search_tasks(&self, query: &str, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        let terms = search_terms(query)?;
+        let exact = query.trim().to_lowercase();
+        let mut ranked: Vec<(u8, Task)> = self
+            .list_tasks()
+            .await?
+            .into_iter()
+            .filter_map(|task| {
+                let name = task.name.to_lowercase();
+                let metadata = task.metadata.values().map(|value| value.to_lowercase()).collect::<Vec<_>>().join(" ");
+                let rank = if name == exact {
+                    0
+                } else if terms.iter().all(|term| name.contains(term)) {
+                    1
+                } else if terms.iter().all(|term| name.contains(term) || metadata.contains(term)) {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, task))
+            })
+            .collect();
+        ranked.sort_by_key(|(rank, task)| (*rank, task.created_at));
+        Ok(ranked.into_iter().take(limit).map(|(_, task)| task).collect())
+    }
+    
+    /// Status agregado de um grupo; `None` se o grupo não tem tarefas
+    async fn group_status(&self, group_id: &uuid::Uuid) -> TaskMeshResult<Option<GroupStatus>> {
+        let tasks = self.list_group_tasks(group_id).await?;
+        let Some(name) = tasks.first().map(|task| task.group_name.clone()) else {
+            return Ok(None);
+        };
+        let mut counts = GroupCounts::default();
+        for task in &tasks {
+            counts.add(&self.get_task_status(&task.id).await?);
+        }
+        Ok(Some(GroupStatus { group_id: *group_id, name, state: GroupState::from_counts(&counts), counts }))
+    }
+    
+    /// Lista os grupos conhecidos com seu status agregado
+    async fn list_groups(&self) -> TaskMeshResult<Vec<GroupStatus>> {
+        let mut group_ids: Vec<uuid::Uuid> = self.list_tasks().await?.iter().filter_map(|task| task.group_id).collect();
+        group_ids.sort();
+        group_ids.dedup();
+        let mut groups = Vec::with_capacity(group_ids.len());
+        for group_id in group_ids {
+            groups.extend(self.group_status(&group_id).await?);
+        }
+        Ok(groups)
+    }
+    
     /// Armazena evento do sistema
     async fn store_event(&self, event: &SystemEvent) -> TaskMeshResult<()>;
     
+    /// Consulta eventos com filtros e paginação por cursor
+    async fn query_events(&self, query: &EventQuery) -> TaskMeshResult<EventPage>;
+    
     /// Recupera eventos por período
     async fn get_events(
         &self, 
         start_time: Option<SystemTime>, 
         end_time: Option<SystemTime>
-    ) -> TaskMeshResult<Vec<SystemEvent>>;
+    ) -> TaskMeshResult<Vec<SystemEvent>> {
+        let query = EventQuery {
+            time_range: (start_time, end_time),
+            limit: usize::MAX,
+            ..EventQuery::default()
+        };
+        Ok(self.query_events(&query).await?.events)
+    }
     
     /// Armazena métricas de execução
     async fn store_metrics(&self, task_id: &TaskId, metrics: &ExecutionMetrics) -> TaskMeshResult<()>;
@@ -53,19 +148,296 @@ pub trait StateStore: Send + Sync {
     /// Recupera métricas de uma tarefa
     async fn get_metrics(&self, task_id: &TaskId) -> TaskMeshResult<Option<ExecutionMetrics>>;
     
+    /// Armazena uma versão de modelo, substituindo a mesma (nome, versão)
+    async fn store_model(&self, model: &ModelRecord) -> TaskMeshResult<()>;
+    
+    /// Lista as versões de um modelo em ordem crescente
+    async fn list_model_versions(&self, name: &str) -> TaskMeshResult<Vec<ModelRecord>>;
+    
+    /// Armazena o registro de uma tentativa, substituindo o da mesma tentativa
+    async fn store_attempt(&self, attempt: &AttemptRecord) -> TaskMeshResult<()>;
+    
+    /// Tentativas de uma tarefa em ordem
+    async fn list_attempts(&self, task_id: &TaskId) -> TaskMeshResult<Vec<AttemptRecord>>;
+    
+    /// Armazena o manifesto de uma tentativa, substituindo o da mesma tentativa
+    async fn store_manifest(&self, manifest: &ExecutionManifest) -> TaskMeshResult<()>;
+    
+    /// Manifestos de uma tarefa em ordem de tentativa
+    async fn list_manifests(&self, task_id: &TaskId) -> TaskMeshResult<Vec<ExecutionManifest>>;
+    
+    /// Manifesto de uma tentativa (a partir de 1)
+    async fn get_manifest(&self, task_id: &TaskId, attempt: u32) -> TaskMeshResult<Option<ExecutionManifest>> {
+        Ok(self
+            .list_manifests(task_id)
+            .await?
+            .into_iter()
+            .find(|manifest| manifest.attempt == attempt))
+    }
+    
+    /// Associa um alias a uma tarefa no namespace
+    ///
+    /// Retorna `AliasConflict` se o alias já pertence a outra tarefa; registrar
+    /// de novo o mesmo par alias/tarefa não é erro.
+    async fn register_alias(&self, namespace: &str, alias: &str, task_id: &TaskId) -> TaskMeshResult<()>;
+    
+    /// Resolve um alias no namespace
+    async fn resolve_alias(&self, namespace: &str, alias: &str) -> TaskMeshResult<Option<TaskId>>;
+    
+    /// Grava uma configuração de execução persistente (`None` remove)
+    async fn put_setting(&self, key: &str, value: Option<&str>) -> TaskMeshResult<()>;
+    
+    /// Lê uma configuração de execução persistente
+    async fn get_setting(&self, key: &str) -> TaskMeshResult<Option<String>>;
+    
     /// Cria checkpoint do estado
     async fn create_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()>;
     
+    /// Cria checkpoint incremental (apenas o que mudou desde `since`)
+    ///
+    /// Backends sem suporte a checkpoints incrementais gravam um checkpoint completo.
+    async fn create_incremental_checkpoint(&self, checkpoint_id: &str, since: SystemTime) -> TaskMeshResult<()> {
+        let _ = since;
+        self.create_checkpoint(checkpoint_id).await
+    }
+    
     /// Restaura estado a partir de checkpoint
+    ///
+    /// Retorna `CheckpointCorrupted` se o conteúdo gravado não for íntegro.
     async fn restore_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()>;
     
-    /// Lista checkpoints disponíveis
+    /// Restaura um checkpoint, recorrendo aos anteriores se ele estiver corrompido
+    ///
+    /// Com `fallback`, checkpoints corrompidos são pulados em ordem do mais
+    /// novo para o mais antigo (ordem de `list_checkpoints`). Retorna o ID
+    /// efetivamente restaurado.
+    async fn restore_checkpoint_with_fallback(&self, checkpoint_id: &str, fallback: bool) -> TaskMeshResult<String> {
+        match self.restore_checkpoint(checkpoint_id).await {
+            Ok(()) => Ok(checkpoint_id.to_string()),
+            Err(TaskMeshError::CheckpointCorrupted(id)) if fallback => {
+                let checkpoints = self.list_checkpoints().await?;
+                let older = checkpoints.iter().skip_while(|c| c.as_str() != checkpoint_id).skip(1);
+                for candidate in older {
+                    match self.restore_checkpoint(candidate).await {
+                        Ok(()) => {
+                            warn!("Checkpoint {} corrompido; restaurado {} no lugar", id, candidate);
+                            return Ok(candidate.clone());
+                        }
+                        Err(TaskMeshError::CheckpointCorrupted(corrupted)) => {
+                            warn!("Checkpoint {} também corrompido", corrupted);
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Err(TaskMeshError::CheckpointCorrupted(id))
+            }
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Lista checkpoints disponíveis (do mais novo para o mais antigo)
     async fn list_checkpoints(&self) -> TaskMeshResult<Vec<String>>;
     
+    /// Audita os checkpoints armazenados e retorna os IDs corrompidos
+    async fn verify_checkpoints(&self) -> TaskMeshResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+    
+    /// Momento do checkpoint mais recente, se o backend registra essa informação
+    async fn last_checkpoint_at(&self) -> TaskMeshResult<Option<SystemTime>> {
+        Ok(None)
+    }
+    
+    /// Verifica se o backend responde (consulta mínima)
+    async fn ping(&self) -> TaskMeshResult<()> {
+        self.list_checkpoints().await.map(|_| ())
+    }
+    
+    /// Versão do schema aplicada e a esperada por este código
+    ///
+    /// Backends sem migrações versionadas retornam `None`.
+    async fn schema_version(&self) -> TaskMeshResult<Option<(u32, u32)>> {
+        Ok(None)
+    }
+    
+    /// Ocupação do armazenamento (contagens e tamanho aproximado)
+    ///
+    /// Backends sem estatísticas retornam campos vazios.
+    async fn stats(&self) -> TaskMeshResult<StorageStats> {
+        Ok(StorageStats::default())
+    }
+    
+    /// Devolve ao sistema o espaço de linhas removidas e atualiza estatísticas
+    ///
+    /// [`CompactionMode::Full`] reescreve o banco inteiro e bloqueia escritas
+    /// enquanto roda. Backends sem compactação não fazem nada.
+    async fn compact(&self, _mode: CompactionMode) -> TaskMeshResult<()> {
+        Ok(())
+    }
+    
+    /// Fecha conexões ociosas do pool até restarem `keep` (no mínimo 1)
+    ///
+    /// Conexões em uso não são interrompidas; o pool volta a abrir conexões
+    /// sob demanda. Backends sem pool não fazem nada.
+    async fn shrink_pool(&self, _keep: u32) -> TaskMeshResult<()> {
+        Ok(())
+    }
+    
+    /// Conexões abertas com o backend (`None` para backends sem conexão)
+    fn open_connections(&self) -> Option<u32> {
+        None
+    }
+    
+    /// Recifra as tarefas de `namespace` com a chave `key_id`, em lotes
+    ///
+    /// Gravações seguintes do namespace já usam a chave nova; tarefas do
+    /// namespace ainda em claro também são cifradas, e tentativas e manifestos
+    /// já cifrados passam para a chave nova. Retorna o número de tarefas
+    /// regravadas. Backends sem cifragem (ver [`crate::encryption`]) recusam.
+    async fn rewrap_namespace(&self, _namespace: &str, _key_id: &str) -> TaskMeshResult<u64> {
+        Err(TaskMeshError::UnsupportedOperation(
+            "cifragem de tarefas não suportada por este backend".to_string(),
+        ))
+    }
+    
+    /// Armazena um evento, retornando sua sequência no backend
+    ///
+    /// Sequências são crescentes e começam em 1; backends que não numeram
+    /// eventos retornam `None`.
+    async fn append_event(&self, event: &SystemEvent) -> TaskMeshResult<Option<u64>> {
+        self.store_event(event).await.map(|_| None)
+    }
+    
+    /// Eventos com sequência maior que `after_seq`, em ordem, até `limit`
+    async fn events_after(&self, after_seq: u64, limit: usize) -> TaskMeshResult<Vec<(u64, SystemEvent)>> {
+        let _ = (after_seq, limit);
+        Err(TaskMeshError::Configuration("Backend sem sequência de eventos".to_string()))
+    }
+    
     /// Limpa dados antigos
     async fn cleanup_old_data(&self, retention_days: u32) -> TaskMeshResult<()>;
+    
+    /// Armazena eventos em lote
+    async fn store_events(&self, events: &[SystemEvent]) -> TaskMeshResult<()> {
+        for event in events {
+            self.store_event(event).await?;
+        }
+        Ok(())
+    }
+    
+    /// Atualiza status de várias tarefas em lote
+    async fn update_task_statuses(&self, updates: &[(TaskId, TaskStatus)]) -> TaskMeshResult<()> {
+        for (task_id, status) in updates {
+            self.update_task_status(task_id, status.clone()).await?;
+        }
+        Ok(())
+    }
+    
+    /// Armazena várias tarefas em lote
+    ///
+    /// No SQLite cada chamada é uma transação; os demais backends gravam
+    /// tarefa a tarefa.
+    async fn store_tasks(&self, tasks: &[Task]) -> TaskMeshResult<()> {
+        for task in tasks {
+            self.store_task(task).await?;
+        }
+        Ok(())
+    }
+    
+    /// Grava tarefas importadas com seus status
+    ///
+    /// No SQLite cada chamada é uma transação; os demais backends gravam
+    /// registro a registro.
+    async fn import_tasks(&self, records: &[(Task, TaskStatus)]) -> TaskMeshResult<()> {
+        for (task, status) in records {
+            self.store_task(task).await?;
+            self.update_task_status(&task.id, status.clone()).await?;
+        }
+        Ok(())
+    }
+    
+    /// Armazena métricas de várias tarefas em lote
+    async fn store_metrics_batch(&self, metrics: &[(TaskId, ExecutionMetrics)]) -> TaskMeshResult<()> {
+        for (task_id, task_metrics) in metrics {
+            self.store_metrics(task_id, task_metrics).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Termos de uma consulta de busca, em minúsculas
+///
+/// Só letras, dígitos e `_` formam termos: pontuação e operadores do usuário
+/// nunca chegam à sintaxe do FTS. Consultas sem termos são recusadas.
+fn search_terms(query: &str) -> TaskMeshResult<Vec<String>> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if terms.is_empty() {
+        return Err(TaskMeshError::Configuration(format!(
+            "Consulta de busca inválida '{}': informe ao menos uma palavra",
+            query
+        )));
+    }
+    Ok(terms)
+}
+
+/// Consulta FTS5 equivalente: cada termo entre aspas, todos obrigatórios
+fn fts_query(terms: &[String]) -> String {
+    terms.iter().map(|term| format!("\"{}\"", term)).collect::<Vec<_>>().join(" ")
+}
+
+/// Texto indexado dos metadados (apenas os valores)
+fn search_metadata(task: &Task) -> String {
+    let mut keys: Vec<&String> = task.metadata.keys().collect();
+    keys.sort();
+    keys.into_iter().map(|key| task.metadata[key].as_str()).collect::<Vec<_>>().join(" ")
+}
+
+/// Marca no lugar dos dados que só existem cifrados
+const REDACTED: &str = "[cifrado]";
+
+/// Status e resultado cifrados de uma tentativa
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SealedAttempt {
+    status: TaskStatus,
+    result: Option<TaskResult>,
+}
+
+/// Rótulo do registro cifrado de uma tentativa
+fn attempt_label(attempt: u32) -> String {
+    format!("attempt:{}", attempt)
+}
+
+/// Rótulo do registro cifrado do manifesto de uma tentativa
+fn manifest_label(attempt: u32) -> String {
+    format!("manifest:{}", attempt)
+}
+
+/// Status sem a saída do processo nem a mensagem de erro, para a coluna em claro
+fn redact_status(status: &TaskStatus) -> TaskStatus {
+    let mut status = status.clone();
+    match &mut status {
+        TaskStatus::Completed { result, .. } => {
+            result.stdout = REDACTED.to_string();
+            result.stderr = REDACTED.to_string();
+            result.output_data = None;
+        }
+        TaskStatus::Failed { error, .. } => *error = REDACTED.to_string(),
+        _ => {}
+    }
+    status
 }
 
+/// Número máximo de linhas por INSERT multi-linha no SQLite
+/// (mantém o total de parâmetros abaixo do limite de 999 das versões antigas)
+const SQLITE_BATCH_ROWS: usize = 100;
+
+/// Tarefas regravadas por transação em `rewrap_namespace`
+const REWRAP_BATCH_ROWS: usize = 500;
+
 /// Backend de armazenamento
 #[derive(Debug, Clone)]
 pub enum StorageBackend {
@@ -75,9 +447,104 @@ pub enum StorageBackend {
     Memory,
 }
 
+/// Configuração do backend SQLite
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SqliteConfig {
+    /// Número máximo de conexões no pool
+    pub max_connections: u32,
+    /// Tempo de espera por locks antes de falhar (ms)
+    pub busy_timeout_ms: u64,
+    /// Habilitar journal em modo WAL
+    pub wal: bool,
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 8,
+            busy_timeout_ms: 5_000,
+            wal: true,
+        }
+    }
+}
+
+/// Capacidade padrão do buffer de eventos do `MemoryStateStore`
+pub const DEFAULT_MEMORY_EVENT_CAPACITY: usize = 10_000;
+
+/// Ocupação do armazenamento de estado
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StorageStats {
+    /// Tarefas armazenadas
+    pub tasks: Option<u64>,
+    /// Eventos retidos
+    pub events: Option<u64>,
+    /// Eventos descartados por falta de espaço
+    pub dropped_events: u64,
+    /// Momento do descarte mais recente, quando houve algum
+    #[serde(default)]
+    pub last_dropped_at: Option<SystemTime>,
+    /// Limite de eventos retidos, quando existe
+    pub event_capacity: Option<u64>,
+    /// Tamanho aproximado em bytes
+    pub approx_bytes: Option<u64>,
+    /// Tamanho do arquivo principal do banco em disco
+    #[serde(default)]
+    pub file_bytes: Option<u64>,
+    /// Páginas do banco
+    #[serde(default)]
+    pub page_count: Option<u64>,
+    /// Páginas livres, recuperáveis por compactação
+    #[serde(default)]
+    pub freelist_pages: Option<u64>,
+}
+
+/// Alcance de [`StateStore::compact`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionMode {
+    /// Libera as páginas livres sem reescrever o banco (`incremental_vacuum`)
+    Incremental,
+    /// Reescreve o banco inteiro (`VACUUM`)
+    Full,
+}
+
+impl StorageStats {
+    /// Resumo legível para o relatório de saúde
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(tasks) = self.tasks {
+            parts.push(format!("{} tarefas", tasks));
+        }
+        match (self.events, self.event_capacity) {
+            (Some(events), Some(capacity)) => parts.push(format!("{}/{} eventos", events, capacity)),
+            (Some(events), None) => parts.push(format!("{} eventos", events)),
+            _ => {}
+        }
+        if self.dropped_events > 0 {
+            parts.push(format!("{} eventos descartados", self.dropped_events));
+        }
+        if let Some(bytes) = self.approx_bytes {
+            parts.push(format!("~{} KiB", bytes / 1024));
+        }
+        if let Some(free) = self.freelist_pages.filter(|free| *free > 0) {
+            parts.push(format!("{} páginas livres", free));
+        }
+        if parts.is_empty() {
+            "sem estatísticas".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
 /// Implementação com SQLite
 pub struct SqliteStateStore {
     pool: SqlitePool,
+    checkpoint_codec: CodecConfig,
+    /// Arquivo do banco (`None` em memória)
+    path: Option<std::path::PathBuf>,
+    /// Cifragem das tarefas dos namespaces configurados
+    payload_cipher: Option<Arc<PayloadCipher>>,
 }
 
 /// Implementação com PostgreSQL
@@ -85,6 +552,12 @@ pub struct PostgresStateStore {
     pool: PgPool,
 }
 
+/// Índice ordenado (score 0, ordem lexicográfica) dos IDs de tarefas no Redis
+const TASKS_BY_ID_KEY: &str = "tasks:by_id";
+
+/// Eventos mais recentes lidos no Redis para semear a sequência de eventos
+const SEQUENCE_SEED_WINDOW: isize = 1_000;
+
 /// Implementação com Redis
 pub struct RedisStateStore {
     client: RedisClient,
@@ -92,103 +565,139 @@ pub struct RedisStateStore {
 }
 
 /// Implementação em memória (para testes)
+///
+/// Os mapas são fragmentados (`DashMap`): leituras de status não competem com
+/// escritas em outras tarefas. Nenhum guard de fragmento atravessa um `await`.
 pub struct MemoryStateStore {
-    tasks: Arc<RwLock<HashMap<TaskId, Task>>>,
-    task_status: Arc<RwLock<HashMap<TaskId, TaskStatus>>>,
-    events: Arc<RwLock<Vec<SystemEvent>>>,
-    metrics: Arc<RwLock<HashMap<TaskId, ExecutionMetrics>>>,
-    checkpoints: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+    tasks: DashMap<TaskId, Task>,
+    task_status: DashMap<TaskId, TaskStatus>,
+    status_history: DashMap<TaskId, Vec<StatusTransition>>,
+    status_seq: std::sync::atomic::AtomicU64,
+    events: Arc<RwLock<EventRing>>,
+    metrics: DashMap<TaskId, ExecutionMetrics>,
+    checkpoints: DashMap<String, Vec<u8>>,
+    checkpoint_codec: CodecConfig,
+    models: DashMap<String, std::collections::BTreeMap<u32, ModelRecord>>,
+    aliases: DashMap<(String, String), TaskId>,
+    settings: DashMap<String, String>,
+    attempts: DashMap<TaskId, std::collections::BTreeMap<u32, AttemptRecord>>,
+    manifests: DashMap<TaskId, std::collections::BTreeMap<u32, ExecutionManifest>>,
+}
+
+/// Buffer circular de eventos do `MemoryStateStore`
+///
+/// Posições são absolutas (a do evento mais antigo retido é o número de
+/// descartados), de modo que cursores continuam válidos após a volta.
+struct EventRing {
+    events: VecDeque<SystemEvent>,
+    capacity: usize,
+    dropped: u64,
+    last_drop: Option<SystemTime>,
+}
+
+impl EventRing {
+    fn new(capacity: usize) -> Self {
+        Self { events: VecDeque::with_capacity(capacity.min(DEFAULT_MEMORY_EVENT_CAPACITY)), capacity, dropped: 0, last_drop: None }
+    }
+    
+    /// Adiciona um evento, descartando o mais antigo se o buffer estiver cheio
+    fn push(&mut self, event: SystemEvent) {
+        if self.capacity == 0 {
+            self.dropped += 1;
+            self.last_drop = Some(SystemTime::now());
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+            self.last_drop = Some(SystemTime::now());
+        }
+        self.events.push_back(event);
+    }
 }
 
 impl SqliteStateStore {
-    /// Cria uma nova instância SQLite
+    /// Cria uma nova instância SQLite com configuração padrão
     pub async fn new(database_url: &str) -> TaskMeshResult<Self> {
+        Self::with_config(database_url, &SqliteConfig::default()).await
+    }
+    
+    /// Cria uma nova instância SQLite
+    ///
+    /// Os PRAGMAs (`journal_mode`, `synchronous`, `busy_timeout`) são
+    /// aplicados em cada conexão do pool, não apenas na primeira. Bancos
+    /// novos usam `auto_vacuum = INCREMENTAL`; bancos criados antes só passam
+    /// a usá-lo depois de uma compactação completa.
+    pub async fn with_config(database_url: &str, config: &SqliteConfig) -> TaskMeshResult<Self> {
         info!("Conectando ao SQLite: {}", database_url);
         
-        let pool = SqlitePool::connect(database_url).await?;
+        let mut options = database_url.parse::<SqliteConnectOptions>()?
+            .create_if_missing(true)
+            .busy_timeout(std::time::Duration::from_millis(config.busy_timeout_ms));
+        if config.wal {
+            options = options
+                .journal_mode(SqliteJournalMode::Wal)
+                .synchronous(SqliteSynchronous::Normal);
+        }
         
-        let store = Self { pool };
+        let filename = options.clone().get_filename().into_owned();
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections.max(1))
+            .connect_with(options)
+            .await?;
+        
+        let path = Some(filename).filter(|path| path.is_file());
+        let store = Self { pool, checkpoint_codec: CodecConfig::default(), path, payload_cipher: None };
+        store.enable_incremental_vacuum().await?;
         store.initialize_schema().await?;
         
         Ok(store)
     }
     
-    /// Inicializa schema do banco
+    /// Codec e compressão dos checkpoints gravados daqui em diante
+    ///
+    /// A restauração detecta o codec de cada checkpoint pelo cabeçalho.
+    pub fn with_checkpoint_codec(mut self, codec: CodecConfig) -> Self {
+        self.checkpoint_codec = codec;
+        self
+    }
+    
+    /// Cifra definição e metadados das tarefas gravadas daqui em diante
+    ///
+    /// Só os namespaces com chave no `cipher` são cifrados (ver
+    /// [`crate::encryption`]); tarefas já gravadas em claro continuam legíveis.
+    pub fn with_payload_encryption(mut self, cipher: Arc<PayloadCipher>) -> Self {
+        self.payload_cipher = Some(cipher);
+        self
+    }
+    
+    /// Liga `auto_vacuum = INCREMENTAL` em bancos ainda sem tabelas
+    ///
+    /// O modo só muda com um `VACUUM` depois do PRAGMA: com WAL, o cabeçalho
+    /// do arquivo já foi gravado quando a conexão abre, e o PRAGMA sozinho
+    /// não tem efeito. Em banco vazio o `VACUUM` é imediato.
+    async fn enable_incremental_vacuum(&self) -> TaskMeshResult<()> {
+        let mut conn = self.pool.acquire().await?;
+        let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master")
+            .fetch_one(&mut *conn)
+            .await?;
+        if tables == 0 {
+            set_incremental_vacuum(&mut conn).await?;
+        }
+        Ok(())
+    }
+    
+    /// Inicializa schema do banco aplicando migrações pendentes
     async fn initialize_schema(&self) -> TaskMeshResult<()> {
         debug!("Inicializando schema SQLite");
         
-        // Tabela de tarefas
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS tasks (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                definition TEXT NOT NULL,
-                dependencies TEXT NOT NULL,
-                priority INTEGER NOT NULL,
-                metadata TEXT NOT NULL,
-                created_at INTEGER NOT NULL,
-                timeout_ms INTEGER,
-                max_retries INTEGER NOT NULL,
-                tags TEXT NOT NULL
-            )
-            "#
-        ).execute(&self.pool).await?;
-        
-        // Tabela de status
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS task_status (
-                task_id TEXT PRIMARY KEY,
-                status_type TEXT NOT NULL,
-                status_data TEXT NOT NULL,
-                updated_at INTEGER NOT NULL,
-                FOREIGN KEY (task_id) REFERENCES tasks (id)
-            )
-            "#
-        ).execute(&self.pool).await?;
-        
-        // Tabela de eventos
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS events (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp INTEGER NOT NULL,
-                event_type TEXT NOT NULL,
-                task_id TEXT,
-                data TEXT NOT NULL
-            )
-            "#
-        ).execute(&self.pool).await?;
-        
-        // Tabela de métricas
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS metrics (
-                task_id TEXT PRIMARY KEY,
-                execution_time_ms INTEGER NOT NULL,
-                cpu_usage REAL NOT NULL,
-                memory_usage INTEGER NOT NULL,
-                network_io_read INTEGER NOT NULL,
-                network_io_write INTEGER NOT NULL,
-                disk_io_read INTEGER NOT NULL,
-                disk_io_write INTEGER NOT NULL,
-                recorded_at INTEGER NOT NULL,
-                FOREIGN KEY (task_id) REFERENCES tasks (id)
-            )
-            "#
-        ).execute(&self.pool).await?;
+        crate::migrations::migrate_sqlite(&self.pool, false).await?;
         
-        // Tabela de checkpoints
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS checkpoints (
-                id TEXT PRIMARY KEY,
-                data BLOB NOT NULL,
-                created_at INTEGER NOT NULL
-            )
-            "#
-        ).execute(&self.pool).await?;
+        // Eventos gravados por processos anteriores: a sequência continua de onde parou
+        let max_sequence: Option<i64> = sqlx::query_scalar("SELECT MAX(sequence) FROM events")
+            .fetch_one(&self.pool)
+            .await?;
+        system_events::seed_sequence(max_sequence.unwrap_or_default() as u64);
         
         info!("Schema SQLite inicializado");
         Ok(())
@@ -201,35 +710,8 @@ impl StateStore for SqliteStateStore {
     async fn store_task(&self, task: &Task) -> TaskMeshResult<()> {
         debug!("Armazenando tarefa: {}", task.id);
         
-        let definition = serde_json::to_string(&task.definition)?;
-        let dependencies = serde_json::to_string(&task.dependencies)?;
-        let metadata = serde_json::to_string(&task.metadata)?;
-        let tags = serde_json::to_string(&task.tags)?;
-        let created_at = task.created_at.duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or_default().as_secs() as i64;
-        let timeout_ms = task.timeout.map(|t| t.as_millis() as i64);
-        
-        sqlx::query(
-            r#"
-            INSERT OR REPLACE INTO tasks 
-            (id, name, definition, dependencies, priority, metadata, created_at, timeout_ms, max_retries, tags)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#
-        )
-        .bind(task.id.to_string())
-        .bind(&task.name)
-        .bind(definition)
-        .bind(dependencies)
-        .bind(task.priority as i32)
-        .bind(metadata)
-        .bind(created_at)
-        .bind(timeout_ms)
-        .bind(task.max_retries as i32)
-        .bind(tags)
-        .execute(&self.pool)
-        .await?;
-        
-        Ok(())
+        let mut conn = self.pool.acquire().await?;
+        self.insert_task(&mut conn, task).await
     }
     
     async fn get_task(&self, task_id: &TaskId) -> TaskMeshResult<Option<Task>> {
@@ -253,16 +735,46 @@ impl StateStore for SqliteStateStore {
     async fn remove_task(&self, task_id: &TaskId) -> TaskMeshResult<()> {
         debug!("Removendo tarefa: {}", task_id);
         
-        sqlx::query("DELETE FROM tasks WHERE id = ?")
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query("DELETE FROM task_status_history WHERE task_id = ?")
             .bind(task_id.to_string())
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
         
         sqlx::query("DELETE FROM task_status WHERE task_id = ?")
             .bind(task_id.to_string())
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .await?;
+        
+        sqlx::query("DELETE FROM task_aliases WHERE task_id = ?")
+            .bind(task_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        
+        for table in ["metrics", "task_attempts", "execution_manifests"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE task_id = ?", table))
+                .bind(task_id.to_string())
+                .execute(&mut *tx)
+                .await?;
+        }
+        
+        sqlx::query("DELETE FROM tasks_fts WHERE rowid IN (SELECT id FROM task_search_rows WHERE task_id = ?)")
+            .bind(task_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        
+        sqlx::query("DELETE FROM task_search_rows WHERE task_id = ?")
+            .bind(task_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        
+        sqlx::query("DELETE FROM tasks WHERE id = ?")
+            .bind(task_id.to_string())
+            .execute(&mut *tx)
             .await?;
         
+        tx.commit().await?;
         Ok(())
     }
     
@@ -271,8 +783,10 @@ impl StateStore for SqliteStateStore {
         
         let status_type = self.status_to_type(&status);
         let status_data = serde_json::to_string(&status)?;
-        let updated_at = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or_default().as_secs() as i64;
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        
+        let mut tx = self.pool.begin().await?;
         
         sqlx::query(
             r#"
@@ -282,12 +796,23 @@ impl StateStore for SqliteStateStore {
             "#
         )
         .bind(task_id.to_string())
+        .bind(&status_type)
+        .bind(&status_data)
+        .bind(now.as_secs() as i64)
+        .execute(&mut *tx)
+        .await?;
+        
+        sqlx::query(
+            "INSERT INTO task_status_history (task_id, status_type, status_data, changed_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(task_id.to_string())
         .bind(status_type)
         .bind(status_data)
-        .bind(updated_at)
-        .execute(&self.pool)
+        .bind(now.as_millis() as i64)
+        .execute(&mut *tx)
         .await?;
         
+        tx.commit().await?;
         Ok(())
     }
     
@@ -310,6 +835,31 @@ impl StateStore for SqliteStateStore {
         }
     }
     
+    async fn get_status_history(&self, task_id: &TaskId) -> TaskMeshResult<Vec<StatusTransition>> {
+        debug!("Recuperando histórico de status da tarefa: {}", task_id);
+        
+        let rows = sqlx::query(
+            "SELECT seq, status_data, changed_at FROM task_status_history WHERE task_id = ? ORDER BY seq"
+        )
+        .bind(task_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut history = Vec::with_capacity(rows.len());
+        for row in rows {
+            let seq: i64 = row.try_get("seq")?;
+            let status_data: String = row.try_get("status_data")?;
+            let changed_at_ms: i64 = row.try_get("changed_at")?;
+            history.push(StatusTransition {
+                seq: seq as u64,
+                status: serde_json::from_str(&status_data)?,
+                changed_at: SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(changed_at_ms as u64),
+            });
+        }
+        
+        Ok(history)
+    }
+    
     async fn list_tasks(&self) -> TaskMeshResult<Vec<Task>> {
         debug!("Listando todas as tarefas");
         
@@ -325,6 +875,17 @@ impl StateStore for SqliteStateStore {
         Ok(tasks)
     }
     
+    async fn list_tasks_page(&self, after: Option<&TaskId>, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        // UUIDs em texto minúsculo ordenam como os bytes, a mesma ordem de `TaskId`
+        let rows = sqlx::query("SELECT * FROM tasks WHERE id > ? ORDER BY id LIMIT ?")
+            .bind(after.map(|id| id.to_string()).unwrap_or_default())
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.into_iter().map(|row| self.row_to_task(row)).collect()
+    }
+    
     async fn list_tasks_by_status(&self, status_filter: &[TaskStatus]) -> TaskMeshResult<Vec<Task>> {
         debug!("Listando tarefas por status: {:?}", status_filter);
         
@@ -357,26 +918,80 @@ impl StateStore for SqliteStateStore {
         Ok(tasks)
     }
     
-    async fn store_event(&self, event: &SystemEvent) -> TaskMeshResult<()> {
-        debug!("Armazenando evento: {:?}", event.event_type);
+    async fn list_group_tasks(&self, group_id: &uuid::Uuid) -> TaskMeshResult<Vec<Task>> {
+        let rows = sqlx::query("SELECT * FROM tasks WHERE group_id = ? ORDER BY created_at")
+            .bind(group_id.to_string())
+            .fetch_all(&self.pool)
+            .await?;
         
-        let timestamp = event.timestamp.duration_since(SystemTime::UNIX_EPOCH)
+        rows.into_iter().map(|row| self.row_to_task(row)).collect()
+    }
+    
+    async fn search_tasks(&self, query: &str, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        let terms = search_terms(query)?;
+        // Nome exato primeiro; depois bm25 com o nome valendo 10x os metadados
+        let rows = sqlx::query(
+            r#"
+            SELECT tasks.* FROM tasks_fts
+            JOIN task_search_rows search ON search.id = tasks_fts.rowid
+            JOIN tasks ON tasks.id = search.task_id
+            WHERE tasks_fts MATCH ?
+            ORDER BY lower(tasks.name) = ? DESC, bm25(tasks_fts, 10.0, 1.0)
+            LIMIT ?
+            "#
+        )
+        .bind(fts_query(&terms))
+        .bind(query.trim().to_lowercase())
+        .bind(limit.min(i64::MAX as usize) as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        rows.into_iter().map(|row| self.row_to_task(row)).collect()
+    }
+    
+    async fn list_groups(&self) -> TaskMeshResult<Vec<GroupStatus>> {
+        let rows = sqlx::query("SELECT DISTINCT group_id FROM tasks WHERE group_id IS NOT NULL ORDER BY group_id")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        let mut groups = Vec::with_capacity(rows.len());
+        for row in rows {
+            let group_id: String = row.try_get("group_id")?;
+            let group_id: uuid::Uuid = group_id.parse()
+                .map_err(|e| TaskMeshError::Internal(format!("{}", e)))?;
+            groups.extend(self.group_status(&group_id).await?);
+        }
+        Ok(groups)
+    }
+    
+    async fn store_event(&self, event: &SystemEvent) -> TaskMeshResult<()> {
+        self.append_event(event).await.map(|_| ())
+    }
+    
+    async fn append_event(&self, event: &SystemEvent) -> TaskMeshResult<Option<u64>> {
+        debug!("Armazenando evento: {:?}", event.event_type);
+        
+        let timestamp = event.timestamp.duration_since(SystemTime::UNIX_EPOCH)
             .unwrap_or_default().as_secs() as i64;
-        let event_type = format!("{:?}", event.event_type);
+        let event_type = serde_json::to_string(&event.event_type)?;
         let task_id = event.task_id.map(|id| id.to_string());
         let data = serde_json::to_string(&event.data)?;
         
-        sqlx::query(
-            "INSERT INTO events (timestamp, event_type, task_id, data) VALUES (?, ?, ?, ?)"
+        let result = sqlx::query(
+            "INSERT INTO events (timestamp, event_type, task_id, data, sequence, severity, source) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(timestamp)
         .bind(event_type)
         .bind(task_id)
         .bind(data)
+        .bind(event.sequence as i64)
+        .bind(serde_json::to_string(&event.severity)?)
+        .bind(&event.source)
         .execute(&self.pool)
         .await?;
         
-        Ok(())
+        Ok(Some(result.last_insert_rowid() as u64))
     }
     
     async fn get_events(
@@ -394,20 +1009,86 @@ impl StateStore for SqliteStateStore {
             t.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs() as i64
         ).unwrap_or(i64::MAX);
         
+        // Ordem anterior a `query_events`: mais recentes primeiro
         let rows = sqlx::query(
-            "SELECT * FROM events WHERE timestamp >= ? AND timestamp <= ? ORDER BY timestamp DESC"
+            "SELECT * FROM events WHERE timestamp >= ? AND timestamp <= ? ORDER BY timestamp DESC, id DESC"
         )
         .bind(start_ts)
         .bind(end_ts)
         .fetch_all(&self.pool)
         .await?;
         
-        let mut events = Vec::new();
+        rows.into_iter().map(|row| self.row_to_event(row)).collect()
+    }
+    
+    async fn events_after(&self, after_seq: u64, limit: usize) -> TaskMeshResult<Vec<(u64, SystemEvent)>> {
+        let rows = sqlx::query("SELECT * FROM events WHERE id > ? ORDER BY id LIMIT ?")
+            .bind(after_seq as i64)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.into_iter()
+            .map(|row| {
+                let id: i64 = row.try_get("id")?;
+                Ok((id as u64, self.row_to_event(row)?))
+            })
+            .collect()
+    }
+    
+    async fn query_events(&self, query: &EventQuery) -> TaskMeshResult<EventPage> {
+        debug!("Consultando eventos: {:?}", query);
+        
+        let after_id: i64 = match &query.cursor {
+            Some(cursor) => cursor.parse()
+                .map_err(|_| TaskMeshError::Configuration(format!("Cursor inválido: {}", cursor)))?,
+            None => 0,
+        };
+        
+        let mut builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new("SELECT * FROM events WHERE id > ");
+        builder.push_bind(after_id);
+        
+        if let Some(task_id) = query.task_id {
+            builder.push(" AND task_id = ").push_bind(task_id.to_string());
+        }
+        if !query.event_types.is_empty() {
+            // Inclui o formato legado (Debug) para linhas antigas
+            builder.push(" AND event_type IN (");
+            let mut separated = builder.separated(", ");
+            for event_type in &query.event_types {
+                separated.push_bind(serde_json::to_string(event_type)?);
+                separated.push_bind(format!("{:?}", event_type));
+            }
+            separated.push_unseparated(")");
+        }
+        if let Some(start) = query.time_range.0 {
+            builder.push(" AND timestamp >= ")
+                .push_bind(start.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs() as i64);
+        }
+        if let Some(end) = query.time_range.1 {
+            builder.push(" AND timestamp <= ")
+                .push_bind(end.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs() as i64);
+        }
+        
+        // Busca um item a mais para saber se existe próxima página
+        let fetch_limit = query.limit.saturating_add(1).min(i64::MAX as usize) as i64;
+        builder.push(" ORDER BY id ASC LIMIT ").push_bind(fetch_limit);
+        
+        let mut rows = builder.build().fetch_all(&self.pool).await?;
+        let has_more = rows.len() > query.limit;
+        rows.truncate(query.limit);
+        
+        let next_cursor = match rows.last() {
+            Some(row) if has_more => Some(row.try_get::<i64, _>("id")?.to_string()),
+            _ => None,
+        };
+        
+        let mut events = Vec::with_capacity(rows.len());
         for row in rows {
             events.push(self.row_to_event(row)?);
         }
         
-        Ok(events)
+        Ok(EventPage { events, next_cursor })
     }
     
     async fn store_metrics(&self, task_id: &TaskId, metrics: &ExecutionMetrics) -> TaskMeshResult<()> {
@@ -455,31 +1136,302 @@ impl StateStore for SqliteStateStore {
         }
     }
     
-    async fn create_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
-        debug!("Criando checkpoint: {}", checkpoint_id);
+    async fn store_model(&self, model: &ModelRecord) -> TaskMeshResult<()> {
+        debug!("Armazenando modelo {} v{}", model.name, model.version);
         
-        // Serializar estado completo
-        let tasks = self.list_tasks().await?;
-        let checkpoint_data = CheckpointData {
-            tasks,
-            created_at: SystemTime::now(),
+        let created_at = model.created_at.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default().as_secs() as i64;
+        
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO models (name, version, blob, metrics, promoted, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&model.name)
+        .bind(model.version as i64)
+        .bind(&model.blob)
+        .bind(serde_json::to_string(&model.metrics)?)
+        .bind(model.promoted)
+        .bind(created_at)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    async fn list_model_versions(&self, name: &str) -> TaskMeshResult<Vec<ModelRecord>> {
+        let rows = sqlx::query("SELECT * FROM models WHERE name = ? ORDER BY version")
+            .bind(name)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        let mut models = Vec::with_capacity(rows.len());
+        for row in rows {
+            let metrics: String = row.try_get("metrics")?;
+            models.push(ModelRecord {
+                name: row.try_get("name")?,
+                version: row.try_get::<i64, _>("version")? as u32,
+                blob: row.try_get("blob")?,
+                metrics: serde_json::from_str(&metrics)?,
+                promoted: row.try_get("promoted")?,
+                created_at: SystemTime::UNIX_EPOCH
+                    + std::time::Duration::from_secs(row.try_get::<i64, _>("created_at")? as u64),
+            });
+        }
+        Ok(models)
+    }
+    
+    async fn store_attempt(&self, attempt: &AttemptRecord) -> TaskMeshResult<()> {
+        debug!("Armazenando tentativa {} da tarefa {}", attempt.attempt, attempt.task_id);
+        
+        let started_at = attempt.started_at.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default().as_millis() as i64;
+        // Tarefa cifrada: status e resultado só em `sealed`; em claro, sem a saída
+        let (status, result, key_id, sealed) = match self.sealing_key(&attempt.task_id).await? {
+            Some((cipher, key_id)) => {
+                let sealed = cipher.seal_record(
+                    &key_id,
+                    &attempt.task_id,
+                    &attempt_label(attempt.attempt),
+                    &SealedAttempt { status: attempt.status.clone(), result: attempt.result.clone() },
+                )?;
+                (redact_status(&attempt.status), None, Some(key_id), Some(sealed))
+            }
+            None => (
+                attempt.status.clone(),
+                attempt.result.as_ref().map(serde_json::to_string).transpose()?,
+                None,
+                None,
+            ),
         };
+        let metrics = attempt.metrics.as_ref().map(serde_json::to_string).transpose()?;
+        let hooks = (!attempt.hooks.is_empty()).then(|| serde_json::to_string(&attempt.hooks)).transpose()?;
+        let dispatch = attempt.dispatch.as_ref().map(serde_json::to_string).transpose()?;
         
-        let data = bincode::serialize(&checkpoint_data)
-            .map_err(|e| TaskMeshError::Internal(format!("Erro de serialização: {}", e)))?;
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO task_attempts
+            (task_id, attempt, worker_id, started_at, status_type, status_data, result, metrics, hooks, dispatch, key_id, sealed)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(attempt.task_id.to_string())
+        .bind(attempt.attempt as i64)
+        .bind(&attempt.worker_id)
+        .bind(started_at)
+        .bind(self.status_to_type(&attempt.status))
+        .bind(serde_json::to_string(&status)?)
+        .bind(result)
+        .bind(metrics)
+        .bind(hooks)
+        .bind(dispatch)
+        .bind(key_id)
+        .bind(sealed)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    async fn list_attempts(&self, task_id: &TaskId) -> TaskMeshResult<Vec<AttemptRecord>> {
+        let rows = sqlx::query("SELECT * FROM task_attempts WHERE task_id = ? ORDER BY attempt")
+            .bind(task_id.to_string())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        let mut attempts = Vec::with_capacity(rows.len());
+        for row in rows {
+            let attempt = row.try_get::<i64, _>("attempt")? as u32;
+            let status: String = row.try_get("status_data")?;
+            let result: Option<String> = row.try_get("result")?;
+            let metrics: Option<String> = row.try_get("metrics")?;
+            let hooks: Option<String> = row.try_get("hooks")?;
+            let dispatch: Option<String> = row.try_get("dispatch")?;
+            let key_id: Option<String> = row.try_get("key_id")?;
+            let (status, result) = match key_id {
+                Some(key_id) => {
+                    let sealed: Vec<u8> = row.try_get("sealed")?;
+                    let fields: SealedAttempt = self.open_record(&key_id, task_id, &attempt_label(attempt), &sealed)?;
+                    (fields.status, fields.result)
+                }
+                None => (
+                    serde_json::from_str(&status)?,
+                    result.as_deref().map(serde_json::from_str).transpose()?,
+                ),
+            };
+            attempts.push(AttemptRecord {
+                task_id: *task_id,
+                attempt,
+                worker_id: row.try_get("worker_id")?,
+                started_at: SystemTime::UNIX_EPOCH
+                    + std::time::Duration::from_millis(row.try_get::<i64, _>("started_at")? as u64),
+                status,
+                result,
+                metrics: metrics.as_deref().map(serde_json::from_str).transpose()?,
+                hooks: hooks.as_deref().map(serde_json::from_str).transpose()?.unwrap_or_default(),
+                dispatch: dispatch.as_deref().map(serde_json::from_str).transpose()?,
+            });
+        }
+        Ok(attempts)
+    }
+    
+    async fn store_manifest(&self, manifest: &ExecutionManifest) -> TaskMeshResult<()> {
+        debug!("Armazenando manifesto da tarefa {} (tentativa {})", manifest.task_id, manifest.attempt);
+        
+        let captured_at = manifest.captured_at.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default().as_secs() as i64;
+        // Tarefa cifrada: o manifesto completo só em `sealed`; em claro, sem o argv
+        let (plain, key_id, sealed) = match self.sealing_key(&manifest.task_id).await? {
+            Some((cipher, key_id)) => {
+                let sealed = cipher.seal_record(&key_id, &manifest.task_id, &manifest_label(manifest.attempt), manifest)?;
+                let redacted = ExecutionManifest { argv: vec![REDACTED.to_string()], ..manifest.clone() };
+                (serde_json::to_string(&redacted)?, Some(key_id), Some(sealed))
+            }
+            None => (serde_json::to_string(manifest)?, None, None),
+        };
+        
+        sqlx::query(
+            "INSERT OR REPLACE INTO execution_manifests (task_id, attempt, manifest, captured_at, key_id, sealed) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(manifest.task_id.to_string())
+        .bind(manifest.attempt as i64)
+        .bind(plain)
+        .bind(captured_at)
+        .bind(key_id)
+        .bind(sealed)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    async fn list_manifests(&self, task_id: &TaskId) -> TaskMeshResult<Vec<ExecutionManifest>> {
+        let rows: Vec<(i64, String, Option<String>, Option<Vec<u8>>)> = sqlx::query_as(
+            "SELECT attempt, manifest, key_id, sealed FROM execution_manifests WHERE task_id = ? ORDER BY attempt"
+        )
+        .bind(task_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        
+        rows.into_iter()
+            .map(|(attempt, manifest, key_id, sealed)| match (key_id, sealed) {
+                (Some(key_id), Some(sealed)) => {
+                    self.open_record(&key_id, task_id, &manifest_label(attempt as u32), &sealed)
+                }
+                _ => serde_json::from_str(&manifest).map_err(TaskMeshError::from),
+            })
+            .collect()
+    }
+    
+    async fn register_alias(&self, namespace: &str, alias: &str, task_id: &TaskId) -> TaskMeshResult<()> {
+        debug!("Registrando alias {}/{} para a tarefa {}", namespace, alias, task_id);
         
         let created_at = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
             .unwrap_or_default().as_secs() as i64;
         
         sqlx::query(
-            "INSERT OR REPLACE INTO checkpoints (id, data, created_at) VALUES (?, ?, ?)"
+            "INSERT OR IGNORE INTO task_aliases (namespace, alias, task_id, created_at) VALUES (?, ?, ?, ?)"
         )
-        .bind(checkpoint_id)
-        .bind(data)
+        .bind(namespace)
+        .bind(alias)
+        .bind(task_id.to_string())
         .bind(created_at)
         .execute(&self.pool)
         .await?;
         
+        match self.resolve_alias(namespace, alias).await? {
+            Some(existing) if existing != *task_id => Err(TaskMeshError::AliasConflict {
+                namespace: namespace.to_string(),
+                alias: alias.to_string(),
+                existing,
+            }),
+            _ => Ok(()),
+        }
+    }
+    
+    async fn resolve_alias(&self, namespace: &str, alias: &str) -> TaskMeshResult<Option<TaskId>> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT task_id FROM task_aliases WHERE namespace = ? AND alias = ?"
+        )
+        .bind(namespace)
+        .bind(alias)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        row.map(|(id,)| id.parse::<TaskId>().map_err(|e| TaskMeshError::Internal(e.to_string())))
+            .transpose()
+    }
+    
+    async fn put_setting(&self, key: &str, value: Option<&str>) -> TaskMeshResult<()> {
+        debug!("Gravando configuração {}", key);
+        
+        match value {
+            Some(value) => {
+                let updated_at = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default().as_secs() as i64;
+                sqlx::query("INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, ?)")
+                    .bind(key)
+                    .bind(value)
+                    .bind(updated_at)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM settings WHERE key = ?")
+                    .bind(key)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+    
+    async fn get_setting(&self, key: &str) -> TaskMeshResult<Option<String>> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT value FROM settings WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|(value,)| value))
+    }
+    
+    async fn create_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
+        debug!("Criando checkpoint: {}", checkpoint_id);
+        
+        // Serializar estado completo
+        let tasks = self.list_tasks().await?;
+        let data = CheckpointData::new(tasks).encode_with(&self.checkpoint_codec)?;
+        let checksum = checkpoint_checksum(&data);
+        
+        let created_at = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default().as_secs() as i64;
+        
+        // Checkpoints exigem fsync no commit mesmo com `synchronous = NORMAL` (WAL)
+        let mut conn = self.pool.acquire().await?;
+        let synchronous: i64 = sqlx::query("PRAGMA synchronous")
+            .fetch_one(&mut *conn)
+            .await?
+            .try_get(0)?;
+        sqlx::query("PRAGMA synchronous = FULL").execute(&mut *conn).await?;
+        
+        let written = async {
+            let mut tx = conn.begin().await?;
+            sqlx::query(
+                "INSERT OR REPLACE INTO checkpoints (id, data, created_at, checksum, payload_len) VALUES (?, ?, ?, ?, ?)"
+            )
+            .bind(checkpoint_id)
+            .bind(&data)
+            .bind(created_at)
+            .bind(&checksum)
+            .bind(data.len() as i64)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await
+        }.await;
+        
+        sqlx::query(&format!("PRAGMA synchronous = {}", synchronous)).execute(&mut *conn).await?;
+        written?;
+        
         info!("Checkpoint {} criado", checkpoint_id);
         Ok(())
     }
@@ -487,25 +1439,28 @@ impl StateStore for SqliteStateStore {
     async fn restore_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
         debug!("Restaurando checkpoint: {}", checkpoint_id);
         
-        let row = sqlx::query("SELECT data FROM checkpoints WHERE id = ?")
+        let row = sqlx::query("SELECT data, checksum, payload_len FROM checkpoints WHERE id = ?")
             .bind(checkpoint_id)
             .fetch_optional(&self.pool)
             .await?;
         
         if let Some(row) = row {
-            let data: Vec<u8> = row.try_get("data")?;
-            let checkpoint_data: CheckpointData = bincode::deserialize(&data)
-                .map_err(|e| TaskMeshError::Internal(format!("Erro de desserialização: {}", e)))?;
+            let checkpoint_data = Self::verified_checkpoint(checkpoint_id, &row)?;
+            
+            let mut tx = self.pool.begin().await?;
             
             // Limpar estado atual
-            sqlx::query("DELETE FROM tasks").execute(&self.pool).await?;
-            sqlx::query("DELETE FROM task_status").execute(&self.pool).await?;
+            sqlx::query("DELETE FROM task_status").execute(&mut *tx).await?;
+            sqlx::query("DELETE FROM tasks").execute(&mut *tx).await?;
+            sqlx::query("DELETE FROM tasks_fts").execute(&mut *tx).await?;
+            sqlx::query("DELETE FROM task_search_rows").execute(&mut *tx).await?;
             
             // Restaurar tarefas
-            for task in checkpoint_data.tasks {
-                self.store_task(&task).await?;
+            for task in &checkpoint_data.tasks {
+                self.insert_task(&mut tx, task).await?;
             }
             
+            tx.commit().await?;
             info!("Checkpoint {} restaurado", checkpoint_id);
             Ok(())
         } else {
@@ -516,7 +1471,7 @@ impl StateStore for SqliteStateStore {
     async fn list_checkpoints(&self) -> TaskMeshResult<Vec<String>> {
         debug!("Listando checkpoints");
         
-        let rows = sqlx::query("SELECT id FROM checkpoints ORDER BY created_at DESC")
+        let rows = sqlx::query("SELECT id FROM checkpoints ORDER BY created_at DESC, rowid DESC")
             .fetch_all(&self.pool)
             .await?;
         
@@ -529,6 +1484,127 @@ impl StateStore for SqliteStateStore {
         Ok(checkpoints)
     }
     
+    async fn verify_checkpoints(&self) -> TaskMeshResult<Vec<String>> {
+        let rows = sqlx::query("SELECT id, data, checksum, payload_len FROM checkpoints ORDER BY created_at DESC, rowid DESC")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        let mut corrupted = Vec::new();
+        for row in rows {
+            let id: String = row.try_get("id")?;
+            if let Err(TaskMeshError::CheckpointCorrupted(_)) = Self::verified_checkpoint(&id, &row) {
+                corrupted.push(id);
+            }
+        }
+        
+        if !corrupted.is_empty() {
+            warn!("Checkpoints corrompidos: {:?}", corrupted);
+        }
+        Ok(corrupted)
+    }
+    
+    async fn last_checkpoint_at(&self) -> TaskMeshResult<Option<SystemTime>> {
+        let row = sqlx::query("SELECT MAX(created_at) AS created_at FROM checkpoints")
+            .fetch_one(&self.pool)
+            .await?;
+        let created_at: Option<i64> = row.try_get("created_at")?;
+        Ok(created_at.map(|secs| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs as u64)))
+    }
+    
+    async fn ping(&self) -> TaskMeshResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+    
+    async fn schema_version(&self) -> TaskMeshResult<Option<(u32, u32)>> {
+        let current = crate::migrations::sqlite_version(&self.pool).await?;
+        Ok(Some((current, crate::migrations::latest_version(crate::migrations::SQLITE_MIGRATIONS))))
+    }
+    
+    async fn stats(&self) -> TaskMeshResult<StorageStats> {
+        let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks").fetch_one(&self.pool).await?;
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events").fetch_one(&self.pool).await?;
+        let page_count: i64 = sqlx::query_scalar("PRAGMA page_count").fetch_one(&self.pool).await?;
+        let page_size: i64 = sqlx::query_scalar("PRAGMA page_size").fetch_one(&self.pool).await?;
+        let freelist: i64 = sqlx::query_scalar("PRAGMA freelist_count").fetch_one(&self.pool).await?;
+        Ok(StorageStats {
+            tasks: Some(tasks as u64),
+            events: Some(events as u64),
+            approx_bytes: Some((page_count * page_size) as u64),
+            file_bytes: self.path.as_ref().and_then(|path| std::fs::metadata(path).ok()).map(|meta| meta.len()),
+            page_count: Some(page_count as u64),
+            freelist_pages: Some(freelist as u64),
+            ..StorageStats::default()
+        })
+    }
+    
+    async fn compact(&self, mode: CompactionMode) -> TaskMeshResult<()> {
+        debug!("Compactando SQLite ({:?})", mode);
+        match mode {
+            // Sem `auto_vacuum = INCREMENTAL` (bancos antigos) não faz nada
+            CompactionMode::Incremental => {
+                sqlx::query("PRAGMA incremental_vacuum").execute(&self.pool).await?;
+            }
+            // Converte também os bancos antigos para `auto_vacuum = INCREMENTAL`
+            CompactionMode::Full => set_incremental_vacuum(&mut *self.pool.acquire().await?).await?,
+        };
+        sqlx::query("ANALYZE").execute(&self.pool).await?;
+        // Com WAL, o arquivo principal só encolhe no checkpoint
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&self.pool).await?;
+        Ok(())
+    }
+    
+    async fn shrink_pool(&self, keep: u32) -> TaskMeshResult<()> {
+        // A última conexão mantém vivo um banco em memória
+        while self.pool.size() > keep.max(1) {
+            let Some(conn) = self.pool.try_acquire() else { break };
+            conn.close().await?;
+        }
+        Ok(())
+    }
+    
+    fn open_connections(&self) -> Option<u32> {
+        Some(self.pool.size())
+    }
+    
+    async fn rewrap_namespace(&self, namespace: &str, key_id: &str) -> TaskMeshResult<u64> {
+        let cipher = self.payload_cipher.as_ref().ok_or_else(|| {
+            TaskMeshError::UnsupportedOperation("cifragem de tarefas não configurada".to_string())
+        })?;
+        // Gravações concorrentes já saem com a chave nova e ficam fora dos lotes
+        cipher.set_namespace_key(namespace, key_id)?;
+        
+        let mut rewrapped = 0;
+        loop {
+            let rows = sqlx::query(
+                "SELECT * FROM tasks WHERE namespace = ? AND (key_id IS NULL OR key_id <> ?) LIMIT ?"
+            )
+            .bind(namespace)
+            .bind(key_id)
+            .bind(REWRAP_BATCH_ROWS as i64)
+            .fetch_all(&self.pool)
+            .await?;
+            if rows.is_empty() {
+                break;
+            }
+            
+            let mut tx = self.pool.begin().await?;
+            for row in rows {
+                let task = self.row_to_task(row)?;
+                self.insert_task(&mut tx, &task).await?;
+                rewrapped += 1;
+            }
+            tx.commit().await?;
+        }
+        
+        let records = self.rewrap_records(cipher, namespace, key_id).await?;
+        info!(
+            "{} tarefas e {} tentativas/manifestos do namespace '{}' recifrados com a chave '{}'",
+            rewrapped, records, namespace, key_id
+        );
+        Ok(rewrapped)
+    }
+    
     async fn cleanup_old_data(&self, retention_days: u32) -> TaskMeshResult<()> {
         debug!("Limpando dados antigos (retenção: {} dias)", retention_days);
         
@@ -544,6 +1620,12 @@ impl StateStore for SqliteStateStore {
             .await?
             .rows_affected();
         
+        // Limpar histórico de status antigo (changed_at em milissegundos)
+        sqlx::query("DELETE FROM task_status_history WHERE changed_at < ?")
+            .bind(cutoff_timestamp * 1000)
+            .execute(&self.pool)
+            .await?;
+        
         // Limpar checkpoints antigos (manter apenas os 10 mais recentes)
         sqlx::query(
             r#"
@@ -561,76 +1643,413 @@ impl StateStore for SqliteStateStore {
         info!("Limpeza concluída: {} eventos removidos", deleted_events);
         Ok(())
     }
-}
-
-impl SqliteStateStore {
-    /// Converte linha SQL para Task
-    fn row_to_task(&self, row: sqlx::sqlite::SqliteRow) -> TaskMeshResult<Task> {
-        use sqlx::Row;
-        
-        let id: String = row.try_get("id")?;
-        let name: String = row.try_get("name")?;
-        let definition_str: String = row.try_get("definition")?;
-        let dependencies_str: String = row.try_get("dependencies")?;
-        let priority: i32 = row.try_get("priority")?;
-        let metadata_str: String = row.try_get("metadata")?;
-        let created_at_secs: i64 = row.try_get("created_at")?;
-        let timeout_ms: Option<i64> = row.try_get("timeout_ms")?;
-        let max_retries: i32 = row.try_get("max_retries")?;
-        let tags_str: String = row.try_get("tags")?;
+    
+    async fn store_events(&self, events: &[SystemEvent]) -> TaskMeshResult<()> {
+        debug!("Armazenando {} eventos em lote", events.len());
+        
+        let mut tx = self.pool.begin().await?;
+        for chunk in events.chunks(SQLITE_BATCH_ROWS) {
+            let query = format!(
+                "INSERT INTO events (timestamp, event_type, task_id, data, sequence, severity, source) VALUES {}",
+                vec!["(?, ?, ?, ?, ?, ?, ?)"; chunk.len()].join(", ")
+            );
+            let mut query_builder = sqlx::query(&query);
+            for event in chunk {
+                let timestamp = event.timestamp.duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default().as_secs() as i64;
+                query_builder = query_builder
+                    .bind(timestamp)
+                    .bind(serde_json::to_string(&event.event_type)?)
+                    .bind(event.task_id.map(|id| id.to_string()))
+                    .bind(serde_json::to_string(&event.data)?)
+                    .bind(event.sequence as i64)
+                    .bind(serde_json::to_string(&event.severity)?)
+                    .bind(&event.source);
+            }
+            query_builder.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
         
-        let task_id = uuid::Uuid::parse_str(&id)
-            .map_err(|e| TaskMeshError::Internal(format!("UUID inválido: {}", e)))?;
+        Ok(())
+    }
+    
+    async fn update_task_statuses(&self, updates: &[(TaskId, TaskStatus)]) -> TaskMeshResult<()> {
+        debug!("Atualizando {} status em lote", updates.len());
         
-        let definition: TaskDefinition = serde_json::from_str(&definition_str)?;
-        let dependencies: Vec<TaskId> = serde_json::from_str(&dependencies_str)?;
-        let metadata: HashMap<String, String> = serde_json::from_str(&metadata_str)?;
-        let tags: Vec<String> = serde_json::from_str(&tags_str)?;
+        let mut tx = self.pool.begin().await?;
+        self.insert_statuses(&mut tx, updates).await?;
+        tx.commit().await?;
         
-        let created_at = SystemTime::UNIX_EPOCH + 
-            std::time::Duration::from_secs(created_at_secs as u64);
+        Ok(())
+    }
+    
+    async fn store_tasks(&self, tasks: &[Task]) -> TaskMeshResult<()> {
+        debug!("Armazenando {} tarefas em lote", tasks.len());
         
-        let timeout = timeout_ms.map(|ms| std::time::Duration::from_millis(ms as u64));
+        let mut tx = self.pool.begin().await?;
+        for task in tasks {
+            self.insert_task(&mut tx, task).await?;
+        }
+        tx.commit().await?;
         
-        Ok(Task {
-            id: task_id,
-            name,
-            definition,
-            dependencies,
-            priority: priority as u8,
-            metadata,
-            created_at,
-            timeout,
-            max_retries: max_retries as u32,
-            tags,
-        })
+        Ok(())
     }
     
-    /// Converte linha SQL para SystemEvent
-    fn row_to_event(&self, row: sqlx::sqlite::SqliteRow) -> TaskMeshResult<SystemEvent> {
-        use sqlx::Row;
+    async fn import_tasks(&self, records: &[(Task, TaskStatus)]) -> TaskMeshResult<()> {
+        debug!("Importando {} tarefas em lote", records.len());
         
-        let timestamp_secs: i64 = row.try_get("timestamp")?;
-        let event_type_str: String = row.try_get("event_type")?;
-        let task_id_str: Option<String> = row.try_get("task_id")?;
-        let data_str: String = row.try_get("data")?;
+        let mut tx = self.pool.begin().await?;
+        for (task, _) in records {
+            self.insert_task(&mut tx, task).await?;
+        }
+        let statuses: Vec<(TaskId, TaskStatus)> = records
+            .iter()
+            .map(|(task, status)| (task.id, status.clone()))
+            .collect();
+        self.insert_statuses(&mut tx, &statuses).await?;
+        tx.commit().await?;
         
-        let timestamp = SystemTime::UNIX_EPOCH + 
-            std::time::Duration::from_secs(timestamp_secs as u64);
+        Ok(())
+    }
+    
+    async fn store_metrics_batch(&self, metrics: &[(TaskId, ExecutionMetrics)]) -> TaskMeshResult<()> {
+        debug!("Armazenando métricas de {} tarefas em lote", metrics.len());
+        
+        let recorded_at = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default().as_secs() as i64;
+        
+        let mut tx = self.pool.begin().await?;
+        for chunk in metrics.chunks(SQLITE_BATCH_ROWS) {
+            let query = format!(
+                r#"
+                INSERT OR REPLACE INTO metrics 
+                (task_id, execution_time_ms, cpu_usage, memory_usage, 
+                 network_io_read, network_io_write, disk_io_read, disk_io_write, recorded_at)
+                VALUES {}
+                "#,
+                vec!["(?, ?, ?, ?, ?, ?, ?, ?, ?)"; chunk.len()].join(", ")
+            );
+            let mut query_builder = sqlx::query(&query);
+            for (task_id, task_metrics) in chunk {
+                query_builder = query_builder
+                    .bind(task_id.to_string())
+                    .bind(task_metrics.execution_time.as_millis() as i64)
+                    .bind(task_metrics.cpu_usage)
+                    .bind(task_metrics.memory_usage as i64)
+                    .bind(task_metrics.network_io.0 as i64)
+                    .bind(task_metrics.network_io.1 as i64)
+                    .bind(task_metrics.disk_io.0 as i64)
+                    .bind(task_metrics.disk_io.1 as i64)
+                    .bind(recorded_at);
+            }
+            query_builder.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        
+        Ok(())
+    }
+}
+
+impl SqliteStateStore {
+    /// Grava status e histórico em INSERTs multi-linha na conexão informada
+    async fn insert_statuses(&self, conn: &mut SqliteConnection, updates: &[(TaskId, TaskStatus)]) -> TaskMeshResult<()> {
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let updated_at = now.as_secs() as i64;
+        let changed_at_ms = now.as_millis() as i64;
+        
+        for chunk in updates.chunks(SQLITE_BATCH_ROWS) {
+            let query = format!(
+                "INSERT OR REPLACE INTO task_status (task_id, status_type, status_data, updated_at) VALUES {}",
+                vec!["(?, ?, ?, ?)"; chunk.len()].join(", ")
+            );
+            let mut query_builder = sqlx::query(&query);
+            for (task_id, status) in chunk {
+                query_builder = query_builder
+                    .bind(task_id.to_string())
+                    .bind(self.status_to_type(status))
+                    .bind(serde_json::to_string(status)?)
+                    .bind(updated_at);
+            }
+            query_builder.execute(&mut *conn).await?;
+            
+            let query = format!(
+                "INSERT INTO task_status_history (task_id, status_type, status_data, changed_at) VALUES {}",
+                vec!["(?, ?, ?, ?)"; chunk.len()].join(", ")
+            );
+            let mut query_builder = sqlx::query(&query);
+            for (task_id, status) in chunk {
+                query_builder = query_builder
+                    .bind(task_id.to_string())
+                    .bind(self.status_to_type(status))
+                    .bind(serde_json::to_string(status)?)
+                    .bind(changed_at_ms);
+            }
+            query_builder.execute(&mut *conn).await?;
+        }
+        Ok(())
+    }
+
+    /// Cifra e chave com que os registros da tarefa devem ser gravados
+    ///
+    /// Segue a chave com que a própria tarefa está gravada; `None` se a
+    /// cifragem não está configurada ou a tarefa não é cifrada.
+    async fn sealing_key(&self, task_id: &TaskId) -> TaskMeshResult<Option<(&PayloadCipher, String)>> {
+        let Some(cipher) = &self.payload_cipher else { return Ok(None) };
+        let key_id: Option<Option<String>> = sqlx::query_scalar("SELECT key_id FROM tasks WHERE id = ?")
+            .bind(task_id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(key_id.flatten().map(|key_id| (cipher.as_ref(), key_id)))
+    }
+    
+    /// Decifra um registro gravado com [`PayloadCipher::seal_record`]
+    fn open_record<T: serde::de::DeserializeOwned>(&self, key_id: &str, task_id: &TaskId, label: &str, sealed: &[u8]) -> TaskMeshResult<T> {
+        let cipher = self.payload_cipher.as_ref().ok_or_else(|| TaskMeshError::Configuration(format!(
+            "Registro {} da tarefa {} cifrado com a chave '{}', mas a cifragem não está configurada",
+            label, task_id, key_id
+        )))?;
+        cipher.open_record(key_id, task_id, label, sealed)
+    }
+    
+    /// Recifra com `key_id` as tentativas e manifestos cifrados do namespace
+    async fn rewrap_records(&self, cipher: &PayloadCipher, namespace: &str, key_id: &str) -> TaskMeshResult<u64> {
+        let mut rewrapped = 0;
+        for (table, label) in [("task_attempts", attempt_label as fn(u32) -> String), ("execution_manifests", manifest_label)] {
+            loop {
+                let rows: Vec<(String, i64, String, Vec<u8>)> = sqlx::query_as(&format!(
+                    "SELECT r.task_id, r.attempt, r.key_id, r.sealed FROM {} r JOIN tasks ON tasks.id = r.task_id \
+                     WHERE tasks.namespace = ? AND r.key_id IS NOT NULL AND r.key_id <> ? LIMIT ?",
+                    table
+                ))
+                .bind(namespace)
+                .bind(key_id)
+                .bind(REWRAP_BATCH_ROWS as i64)
+                .fetch_all(&self.pool)
+                .await?;
+                if rows.is_empty() {
+                    break;
+                }
+                
+                let mut tx = self.pool.begin().await?;
+                for (task_id, attempt, old_key_id, sealed) in rows {
+                    let id: TaskId = task_id.parse().map_err(|e| TaskMeshError::Internal(format!("{}", e)))?;
+                    let label = label(attempt as u32);
+                    let record: serde_json::Value = cipher.open_record(&old_key_id, &id, &label, &sealed)?;
+                    let sealed = cipher.seal_record(key_id, &id, &label, &record)?;
+                    sqlx::query(&format!("UPDATE {} SET key_id = ?, sealed = ? WHERE task_id = ? AND attempt = ?", table))
+                        .bind(key_id)
+                        .bind(sealed)
+                        .bind(&task_id)
+                        .bind(attempt)
+                        .execute(&mut *tx)
+                        .await?;
+                    rewrapped += 1;
+                }
+                tx.commit().await?;
+            }
+        }
+        Ok(rewrapped)
+    }
+    
+    /// Insere ou substitui uma tarefa usando a conexão (ou transação) informada
+    async fn insert_task(&self, conn: &mut SqliteConnection, task: &Task) -> TaskMeshResult<()> {
+        let namespace = crate::alias::namespace_of(task);
+        let key_id = self.payload_cipher.as_ref().and_then(|cipher| cipher.key_id_for(namespace));
+        // Cifradas, definição, metadados, sidecars e hooks ficam só em `sealed`
+        let (definition, metadata, sidecars, hooks, sealed, search_metadata) = match (&self.payload_cipher, &key_id) {
+            (Some(cipher), Some(key_id)) => {
+                let sealed = cipher.seal(key_id, task)?;
+                ("null".to_string(), "{}".to_string(), None, None, Some(sealed), String::new())
+            }
+            _ => (
+                serde_json::to_string(&task.definition)?,
+                serde_json::to_string(&task.metadata)?,
+                Some(serde_json::to_string(&task.sidecars)?),
+                Some(serde_json::to_string(&task.hooks)?),
+                None,
+                search_metadata(task),
+            ),
+        };
+        let dependencies = serde_json::to_string(&task.dependencies)?;
+        let tags = serde_json::to_string(&task.tags)?;
+        let created_at = task.created_at.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default().as_secs() as i64;
+        let timeout_ms = task.timeout.map(|t| t.as_millis() as i64);
+        
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO tasks 
+            (id, name, definition, dependencies, priority, metadata, created_at, timeout_ms, max_retries, tags, alias,
+             group_id, group_name, namespace, key_id, sealed, sidecars, concurrency_group, hooks, sla, forced_outcome)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(task.id.to_string())
+        .bind(&task.name)
+        .bind(definition)
+        .bind(dependencies)
+        .bind(i32::from(task.priority.value()))
+        .bind(metadata)
+        .bind(created_at)
+        .bind(timeout_ms)
+        .bind(task.max_retries as i32)
+        .bind(tags)
+        .bind(&task.alias)
+        .bind(task.group_id.map(|id| id.to_string()))
+        .bind(&task.group_name)
+        .bind(namespace)
+        .bind(&key_id)
+        .bind(sealed)
+        .bind(sidecars)
+        .bind(&task.concurrency_group)
+        .bind(hooks)
+        .bind(task.sla.as_ref().map(serde_json::to_string).transpose()?)
+        .bind(task.forced_outcome.as_ref().map(serde_json::to_string).transpose()?)
+        .execute(&mut *conn)
+        .await?;
+        
+        // Índice de busca: substitui a linha da tarefa no FTS
+        sqlx::query("INSERT OR IGNORE INTO task_search_rows (task_id) VALUES (?)")
+            .bind(task.id.to_string())
+            .execute(&mut *conn)
+            .await?;
+        let search_id: i64 = sqlx::query_scalar("SELECT id FROM task_search_rows WHERE task_id = ?")
+            .bind(task.id.to_string())
+            .fetch_one(&mut *conn)
+            .await?;
+        sqlx::query("DELETE FROM tasks_fts WHERE rowid = ?")
+            .bind(search_id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("INSERT INTO tasks_fts (rowid, name, metadata) VALUES (?, ?, ?)")
+            .bind(search_id)
+            .bind(&task.name)
+            .bind(search_metadata)
+            .execute(&mut *conn)
+            .await?;
         
-        let event_type = match event_type_str.as_str() {
-            "TaskSubmitted" => EventType::TaskSubmitted,
-            "TaskScheduled" => EventType::TaskScheduled,
-            "TaskStarted" => EventType::TaskStarted,
-            "TaskCompleted" => EventType::TaskCompleted,
-            "TaskFailed" => EventType::TaskFailed,
-            "TaskCancelled" => EventType::TaskCancelled,
-            _ => EventType::SystemStarted, // Fallback
+        Ok(())
+    }
+    
+    /// Converte linha SQL para Task
+    /// Verifica tamanho e checksum de um checkpoint antes de desserializá-lo
+    ///
+    /// Checkpoints gravados antes do checksum são aceitos se desserializarem.
+    fn verified_checkpoint(checkpoint_id: &str, row: &sqlx::sqlite::SqliteRow) -> TaskMeshResult<CheckpointData> {
+        let data: Vec<u8> = row.try_get("data")?;
+        let checksum: Option<String> = row.try_get("checksum")?;
+        let payload_len: Option<i64> = row.try_get("payload_len")?;
+        
+        let corrupted = || TaskMeshError::CheckpointCorrupted(checkpoint_id.to_string());
+        if payload_len.is_some_and(|len| len != data.len() as i64) {
+            return Err(corrupted());
+        }
+        if checksum.is_some_and(|checksum| checksum != checkpoint_checksum(&data)) {
+            return Err(corrupted());
+        }
+        CheckpointData::decode(checkpoint_id, &data)
+    }
+    
+    fn row_to_task(&self, row: sqlx::sqlite::SqliteRow) -> TaskMeshResult<Task> {
+        use sqlx::Row;
+        
+        let id: String = row.try_get("id")?;
+        let name: String = row.try_get("name")?;
+        let definition_str: String = row.try_get("definition")?;
+        let dependencies_str: String = row.try_get("dependencies")?;
+        let priority: i32 = row.try_get("priority")?;
+        let metadata_str: String = row.try_get("metadata")?;
+        let created_at_secs: i64 = row.try_get("created_at")?;
+        let timeout_ms: Option<i64> = row.try_get("timeout_ms")?;
+        let max_retries: i32 = row.try_get("max_retries")?;
+        let tags_str: String = row.try_get("tags")?;
+        let alias: Option<String> = row.try_get("alias")?;
+        let group_id: Option<String> = row.try_get("group_id")?;
+        let group_name: Option<String> = row.try_get("group_name")?;
+        let key_id: Option<String> = row.try_get("key_id")?;
+        let sidecars_str: Option<String> = row.try_get("sidecars")?;
+        let concurrency_group: Option<String> = row.try_get("concurrency_group")?;
+        let hooks_str: Option<String> = row.try_get("hooks")?;
+        let sla_str: Option<String> = row.try_get("sla")?;
+        let forced_outcome_str: Option<String> = row.try_get("forced_outcome")?;
+        
+        let task_id: TaskId = id.parse()
+            .map_err(|e| TaskMeshError::Internal(format!("{}", e)))?;
+        
+        let (definition, metadata, sidecars, hooks) = match key_id {
+            Some(key_id) => {
+                let cipher = self.payload_cipher.as_ref().ok_or_else(|| TaskMeshError::Configuration(format!(
+                    "Tarefa {} cifrada com a chave '{}', mas a cifragem não está configurada",
+                    task_id, key_id
+                )))?;
+                let sealed: Vec<u8> = row.try_get("sealed")?;
+                let fields = cipher.open(&key_id, &task_id, &sealed)?;
+                (fields.definition, fields.metadata, fields.sidecars, fields.hooks)
+            }
+            None => (
+                serde_json::from_str::<TaskDefinition>(&definition_str)?,
+                serde_json::from_str::<HashMap<String, String>>(&metadata_str)?,
+                sidecars_str.as_deref().map(serde_json::from_str).transpose()?.unwrap_or_default(),
+                hooks_str.as_deref().map(serde_json::from_str).transpose()?.unwrap_or_default(),
+            ),
         };
+        let dependencies: Vec<TaskId> = serde_json::from_str(&dependencies_str)?;
+        let tags: Vec<String> = serde_json::from_str(&tags_str)?;
+        let group_id = group_id
+            .map(|id| id.parse::<uuid::Uuid>())
+            .transpose()
+            .map_err(|e| TaskMeshError::Internal(format!("{}", e)))?;
+        
+        let created_at = SystemTime::UNIX_EPOCH + 
+            std::time::Duration::from_secs(created_at_secs as u64);
+        
+        let timeout = timeout_ms.map(|ms| std::time::Duration::from_millis(ms as u64));
+        
+        Ok(Task {
+            id: task_id,
+            name,
+            alias,
+            definition,
+            dependencies,
+            priority: Priority::from(priority.clamp(0, u8::MAX as i32) as u8),
+            metadata,
+            created_at,
+            timeout,
+            max_retries: max_retries as u32,
+            tags,
+            group_id,
+            group_name,
+            sidecars,
+            concurrency_group,
+            hooks,
+            sla: sla_str.as_deref().map(serde_json::from_str).transpose()?,
+            forced_outcome: forced_outcome_str.as_deref().map(serde_json::from_str).transpose()?,
+        })
+    }
+    
+    /// Converte linha SQL para SystemEvent
+    fn row_to_event(&self, row: sqlx::sqlite::SqliteRow) -> TaskMeshResult<SystemEvent> {
+        use sqlx::Row;
+        
+        let timestamp_secs: i64 = row.try_get("timestamp")?;
+        let event_type_str: String = row.try_get("event_type")?;
+        let task_id_str: Option<String> = row.try_get("task_id")?;
+        let data_str: String = row.try_get("data")?;
+        // Linhas anteriores à migração 15 não têm sequência, severidade nem origem
+        let sequence: Option<i64> = row.try_get("sequence")?;
+        let severity: Option<String> = row.try_get("severity")?;
+        let source: Option<String> = row.try_get("source")?;
+        
+        let timestamp = SystemTime::UNIX_EPOCH + 
+            std::time::Duration::from_secs(timestamp_secs as u64);
+        
+        // Linhas antigas usam o formato Debug; tipos desconhecidos são preservados
+        let event_type = EventType::from_stored(&event_type_str);
         
         let task_id = if let Some(id_str) = task_id_str {
-            Some(uuid::Uuid::parse_str(&id_str)
-                .map_err(|e| TaskMeshError::Internal(format!("UUID inválido: {}", e)))?)
+            Some(id_str.parse::<TaskId>()
+                .map_err(|e| TaskMeshError::Internal(format!("{}", e)))?)
         } else {
             None
         };
@@ -638,9 +2057,12 @@ impl SqliteStateStore {
         let data: serde_json::Value = serde_json::from_str(&data_str)?;
         
         Ok(SystemEvent {
+            sequence: sequence.unwrap_or_default() as u64,
             timestamp,
             event_type,
             task_id,
+            severity: severity.and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default(),
+            source: source.unwrap_or_default(),
             data,
         })
     }
@@ -674,6 +2096,7 @@ impl SqliteStateStore {
             TaskStatus::Running { .. } => "Running".to_string(),
             TaskStatus::Completed { .. } => "Completed".to_string(),
             TaskStatus::Failed { .. } => "Failed".to_string(),
+            TaskStatus::AwaitingRetry { .. } => "AwaitingRetry".to_string(),
             TaskStatus::Cancelled { .. } => "Cancelled".to_string(),
             TaskStatus::Paused { .. } => "Paused".to_string(),
         }
@@ -696,8 +2119,12 @@ impl PostgresStateStore {
     async fn initialize_schema(&self) -> TaskMeshResult<()> {
         debug!("Inicializando schema PostgreSQL");
         
-        // Implementação similar ao SQLite, mas com sintaxe PostgreSQL
-        // TODO: Implementar schema PostgreSQL completo
+        crate::migrations::migrate_postgres(&self.pool, false).await?;
+        
+        let max_sequence: Option<i64> = sqlx::query_scalar("SELECT MAX(sequence) FROM events")
+            .fetch_one(&self.pool)
+            .await?;
+        system_events::seed_sequence(max_sequence.unwrap_or_default() as u64);
         
         Ok(())
     }
@@ -714,14 +2141,52 @@ impl RedisStateStore {
         let client = RedisClient::open(redis_url)
             .map_err(|e| TaskMeshError::Redis(e))?;
         
-        let connection = client.get_async_connection().await
+        let mut connection = client.get_async_connection().await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        // Bancos gravados antes do índice ordenado: preenchê-lo a partir de `tasks:all`
+        let indexed: u64 = connection.zcard(TASKS_BY_ID_KEY).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        let stored: u64 = connection.scard("tasks:all").await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        if indexed < stored {
+            let task_ids: Vec<String> = connection.smembers("tasks:all").await
+                .map_err(|e| TaskMeshError::Redis(e))?;
+            let members: Vec<(u8, String)> = task_ids.into_iter().map(|id| (0, id)).collect();
+            connection.zadd_multiple(TASKS_BY_ID_KEY, &members).await
+                .map_err(|e| TaskMeshError::Redis(e))?;
+        }
+        
+        // A sequência cresce com o tempo, então a maior está entre os eventos mais recentes
+        let recent: Vec<String> = connection.zrevrange("events", 0, SEQUENCE_SEED_WINDOW - 1).await
             .map_err(|e| TaskMeshError::Redis(e))?;
+        let max_sequence = recent.iter()
+            .filter_map(|json| serde_json::from_str::<SystemEvent>(json).ok())
+            .map(|event| event.sequence)
+            .max();
+        system_events::seed_sequence(max_sequence.unwrap_or_default());
         
         Ok(Self {
             client,
             connection: Arc::new(RwLock::new(connection)),
         })
     }
+    
+    /// Lê as tarefas com um único MGET, ignorando as removidas no meio tempo
+    async fn fetch_tasks(conn: &mut RedisConnection, task_ids: &[String]) -> TaskMeshResult<Vec<Task>> {
+        if task_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let keys: Vec<String> = task_ids.iter().map(|id| format!("task:{}", id)).collect();
+        let jsons: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(conn).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        let mut tasks = Vec::with_capacity(jsons.len());
+        for json in jsons.into_iter().flatten() {
+            tasks.push(serde_json::from_str(&json)?);
+        }
+        Ok(tasks)
+    }
 }
 
 #[async_trait]
@@ -736,9 +2201,11 @@ impl StateStore for RedisStateStore {
         conn.set(&key, task_json).await
             .map_err(|e| TaskMeshError::Redis(e))?;
         
-        // Adicionar ao índice de tarefas
+        // Adicionar aos índices de tarefas
         conn.sadd("tasks:all", task.id.to_string()).await
             .map_err(|e| TaskMeshError::Redis(e))?;
+        conn.zadd(TASKS_BY_ID_KEY, task.id.to_string(), 0).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
         
         Ok(())
     }
@@ -773,8 +2240,28 @@ impl StateStore for RedisStateStore {
         conn.del(&status_key).await
             .map_err(|e| TaskMeshError::Redis(e))?;
         
+        conn.del(format!("status_history:{}", task_id)).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        for prefix in ["metrics", "attempts", "manifests"] {
+            conn.del(format!("{}:{}", prefix, task_id)).await
+                .map_err(|e| TaskMeshError::Redis(e))?;
+        }
+        
         conn.srem("tasks:all", task_id.to_string()).await
             .map_err(|e| TaskMeshError::Redis(e))?;
+        conn.zrem(TASKS_BY_ID_KEY, task_id.to_string()).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        let alias_of = format!("alias_of:{}", task_id);
+        let qualified: Option<String> = conn.get(&alias_of).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        if let Some((namespace, alias)) = qualified.as_deref().and_then(|q| q.rsplit_once('/')) {
+            conn.hdel(format!("aliases:{}", namespace), alias).await
+                .map_err(|e| TaskMeshError::Redis(e))?;
+            conn.del(&alias_of).await
+                .map_err(|e| TaskMeshError::Redis(e))?;
+        }
         
         Ok(())
     }
@@ -786,7 +2273,15 @@ impl StateStore for RedisStateStore {
         let key = format!("status:{}", task_id);
         let status_json = serde_json::to_string(&status)?;
         
-        conn.set(&key, status_json).await
+        let seq: u64 = conn.incr("status_history:seq", 1).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        let transition = StatusTransition { seq, status, changed_at: SystemTime::now() };
+        
+        redis::pipe()
+            .atomic()
+            .set(&key, status_json).ignore()
+            .rpush(format!("status_history:{}", task_id), serde_json::to_string(&transition)?).ignore()
+            .query_async::<_, ()>(&mut *conn).await
             .map_err(|e| TaskMeshError::Redis(e))?;
         
         Ok(())
@@ -809,6 +2304,21 @@ impl StateStore for RedisStateStore {
         }
     }
     
+    async fn get_status_history(&self, task_id: &TaskId) -> TaskMeshResult<Vec<StatusTransition>> {
+        debug!("Recuperando histórico de status do Redis: {}", task_id);
+        
+        let mut conn = self.connection.write().await;
+        let entries: Vec<String> = conn.lrange(format!("status_history:{}", task_id), 0, -1).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        let mut history = Vec::with_capacity(entries.len());
+        for json in entries {
+            history.push(serde_json::from_str::<StatusTransition>(&json)?);
+        }
+        
+        Ok(history)
+    }
+    
     async fn list_tasks(&self) -> TaskMeshResult<Vec<Task>> {
         debug!("Listando tarefas do Redis");
         
@@ -816,16 +2326,25 @@ impl StateStore for RedisStateStore {
         let task_ids: Vec<String> = conn.smembers("tasks:all").await
             .map_err(|e| TaskMeshError::Redis(e))?;
         
-        let mut tasks = Vec::new();
-        for task_id_str in task_ids {
-            if let Ok(task_id) = uuid::Uuid::parse_str(&task_id_str) {
-                if let Ok(Some(task)) = self.get_task(&task_id).await {
-                    tasks.push(task);
-                }
-            }
+        Self::fetch_tasks(&mut conn, &task_ids).await
+    }
+    
+    async fn list_tasks_page(&self, after: Option<&TaskId>, limit: usize) -> TaskMeshResult<Vec<Task>> {
+        debug!("Listando página de tarefas do Redis após {:?}", after);
+        
+        if limit == 0 {
+            return Ok(Vec::new());
         }
+        // Membros com o mesmo score ficam em ordem lexicográfica, que para o
+        // UUID hifenizado em minúsculas coincide com a ordem de `TaskId`
+        let min = after.map_or_else(|| "-".to_string(), |after| format!("({}", after));
+        let mut conn = self.connection.write().await;
+        let task_ids: Vec<String> = conn
+            .zrangebylex_limit(TASKS_BY_ID_KEY, min, "+", 0, limit as isize)
+            .await
+            .map_err(|e| TaskMeshError::Redis(e))?;
         
-        Ok(tasks)
+        Self::fetch_tasks(&mut conn, &task_ids).await
     }
     
     async fn list_tasks_by_status(&self, _status_filter: &[TaskStatus]) -> TaskMeshResult<Vec<Task>> {
@@ -833,6 +2352,10 @@ impl StateStore for RedisStateStore {
         self.list_tasks().await
     }
     
+    async fn search_tasks(&self, _query: &str, _limit: usize) -> TaskMeshResult<Vec<Task>> {
+        Err(TaskMeshError::UnsupportedOperation("busca textual no backend Redis".to_string()))
+    }
+    
     async fn store_event(&self, event: &SystemEvent) -> TaskMeshResult<()> {
         debug!("Armazenando evento no Redis: {:?}", event.event_type);
         
@@ -847,34 +2370,52 @@ impl StateStore for RedisStateStore {
         Ok(())
     }
     
-    async fn get_events(
-        &self, 
-        start_time: Option<SystemTime>, 
-        end_time: Option<SystemTime>
-    ) -> TaskMeshResult<Vec<SystemEvent>> {
-        debug!("Recuperando eventos do Redis");
+    async fn query_events(&self, query: &EventQuery) -> TaskMeshResult<EventPage> {
+        debug!("Consultando eventos do Redis: {:?}", query);
         
         let mut conn = self.connection.write().await;
         
-        let start_ts = start_time.map(|t| 
+        let start_ts = query.time_range.0.map(|t| 
             t.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as f64
         ).unwrap_or(0.0);
         
-        let end_ts = end_time.map(|t| 
+        let end_ts = query.time_range.1.map(|t| 
             t.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as f64
         ).unwrap_or(f64::MAX);
         
+        // O cursor é a chave (timestamp em ms, sequência) do último evento
+        // entregue: eventos gravados depois não deslocam as próximas páginas
+        let after: Option<(u64, u64)> = match &query.cursor {
+            Some(cursor) => Some(
+                cursor.split_once(':')
+                    .and_then(|(ts, seq)| Some((ts.parse().ok()?, seq.parse().ok()?)))
+                    .ok_or_else(|| TaskMeshError::Configuration(format!("Cursor inválido: {}", cursor)))?,
+            ),
+            None => None,
+        };
+        let start_ts = after.map_or(start_ts, |(ts, _)| start_ts.max(ts as f64));
+        
         let event_jsons: Vec<String> = conn.zrangebyscore("events", start_ts, end_ts).await
             .map_err(|e| TaskMeshError::Redis(e))?;
         
-        let mut events = Vec::new();
-        for json in event_jsons {
-            if let Ok(event) = serde_json::from_str::<SystemEvent>(&json) {
-                events.push(event);
-            }
-        }
+        // O sorted set desempata pelo JSON; a página segue (timestamp, sequência)
+        let key = |event: &SystemEvent| {
+            let ts = event.timestamp.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+            (ts, event.sequence)
+        };
+        let mut events: Vec<SystemEvent> = event_jsons.iter()
+            .filter_map(|json| serde_json::from_str::<SystemEvent>(json).ok())
+            .filter(|event| after.map_or(true, |after| key(event) > after) && query.matches(event))
+            .collect();
+        events.sort_by_key(|event| key(event));
         
-        Ok(events)
+        let next_cursor = query.limit.checked_sub(1)
+            .filter(|_| events.len() > query.limit)
+            .map(|last| key(&events[last]))
+            .map(|(ts, seq)| format!("{}:{}", ts, seq));
+        events.truncate(query.limit);
+        
+        Ok(EventPage { events, next_cursor })
     }
     
     async fn store_metrics(&self, task_id: &TaskId, metrics: &ExecutionMetrics) -> TaskMeshResult<()> {
@@ -907,14 +2448,129 @@ impl StateStore for RedisStateStore {
         }
     }
     
+    async fn store_model(&self, model: &ModelRecord) -> TaskMeshResult<()> {
+        debug!("Armazenando modelo no Redis: {} v{}", model.name, model.version);
+        
+        let data = bincode::serialize(model)
+            .map_err(|e| TaskMeshError::Internal(format!("Erro de serialização: {}", e)))?;
+        
+        let mut conn = self.connection.write().await;
+        conn.hset(format!("models:{}", model.name), model.version, data).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        Ok(())
+    }
+    
+    async fn list_model_versions(&self, name: &str) -> TaskMeshResult<Vec<ModelRecord>> {
+        let mut conn = self.connection.write().await;
+        let entries: HashMap<u32, Vec<u8>> = conn.hgetall(format!("models:{}", name)).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        drop(conn);
+        
+        let mut models = entries
+            .into_values()
+            .map(|data| bincode::deserialize::<ModelRecord>(&data)
+                .map_err(|e| TaskMeshError::Internal(format!("Modelo {} corrompido: {}", name, e))))
+            .collect::<TaskMeshResult<Vec<_>>>()?;
+        models.sort_by_key(|model| model.version);
+        Ok(models)
+    }
+    
+    async fn store_attempt(&self, attempt: &AttemptRecord) -> TaskMeshResult<()> {
+        let mut conn = self.connection.write().await;
+        conn.hset(format!("attempts:{}", attempt.task_id), attempt.attempt, serde_json::to_string(attempt)?).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        Ok(())
+    }
+    
+    async fn list_attempts(&self, task_id: &TaskId) -> TaskMeshResult<Vec<AttemptRecord>> {
+        let mut conn = self.connection.write().await;
+        let entries: HashMap<u32, String> = conn.hgetall(format!("attempts:{}", task_id)).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        drop(conn);
+        
+        let mut attempts = entries
+            .into_values()
+            .map(|json| serde_json::from_str::<AttemptRecord>(&json).map_err(TaskMeshError::from))
+            .collect::<TaskMeshResult<Vec<_>>>()?;
+        attempts.sort_by_key(|attempt| attempt.attempt);
+        Ok(attempts)
+    }
+    
+    async fn store_manifest(&self, manifest: &ExecutionManifest) -> TaskMeshResult<()> {
+        let mut conn = self.connection.write().await;
+        conn.hset(format!("manifests:{}", manifest.task_id), manifest.attempt, serde_json::to_string(manifest)?).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        Ok(())
+    }
+    
+    async fn list_manifests(&self, task_id: &TaskId) -> TaskMeshResult<Vec<ExecutionManifest>> {
+        let mut conn = self.connection.write().await;
+        let entries: HashMap<u32, String> = conn.hgetall(format!("manifests:{}", task_id)).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        drop(conn);
+        
+        let mut manifests = entries
+            .into_values()
+            .map(|json| serde_json::from_str::<ExecutionManifest>(&json).map_err(TaskMeshError::from))
+            .collect::<TaskMeshResult<Vec<_>>>()?;
+        manifests.sort_by_key(|manifest| manifest.attempt);
+        Ok(manifests)
+    }
+    
+    async fn register_alias(&self, namespace: &str, alias: &str, task_id: &TaskId) -> TaskMeshResult<()> {
+        debug!("Registrando alias no Redis: {}/{} -> {}", namespace, alias, task_id);
+        
+        // HSETNX garante a unicidade mesmo com submissões concorrentes
+        let mut conn = self.connection.write().await;
+        let key = format!("aliases:{}", namespace);
+        let _: bool = conn.hset_nx(&key, alias, task_id.to_string()).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        let owner: String = conn.hget(&key, alias).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        let existing = owner.parse::<TaskId>().map_err(|e| TaskMeshError::Internal(e.to_string()))?;
+        if existing != *task_id {
+            return Err(TaskMeshError::AliasConflict {
+                namespace: namespace.to_string(),
+                alias: alias.to_string(),
+                existing,
+            });
+        }
+        conn.set(format!("alias_of:{}", task_id), format!("{}/{}", namespace, alias)).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        Ok(())
+    }
+    
+    async fn resolve_alias(&self, namespace: &str, alias: &str) -> TaskMeshResult<Option<TaskId>> {
+        let mut conn = self.connection.write().await;
+        let owner: Option<String> = conn.hget(format!("aliases:{}", namespace), alias).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        owner.map(|id| id.parse::<TaskId>().map_err(|e| TaskMeshError::Internal(e.to_string())))
+            .transpose()
+    }
+    
+    async fn put_setting(&self, key: &str, value: Option<&str>) -> TaskMeshResult<()> {
+        let mut conn = self.connection.write().await;
+        let _: () = match value {
+            Some(value) => conn.hset("settings", key, value).await,
+            None => conn.hdel("settings", key).await,
+        }
+        .map_err(|e| TaskMeshError::Redis(e))?;
+        Ok(())
+    }
+    
+    async fn get_setting(&self, key: &str) -> TaskMeshResult<Option<String>> {
+        let mut conn = self.connection.write().await;
+        conn.hget("settings", key).await
+            .map_err(|e| TaskMeshError::Redis(e))
+    }
+    
     async fn create_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
         debug!("Criando checkpoint no Redis: {}", checkpoint_id);
         
         let tasks = self.list_tasks().await?;
-        let checkpoint_data = CheckpointData {
-            tasks,
-            created_at: SystemTime::now(),
-        };
+        let checkpoint_data = CheckpointData::new(tasks);
         
         let mut conn = self.connection.write().await;
         let key = format!("checkpoint:{}", checkpoint_id);
@@ -939,14 +2595,16 @@ impl StateStore for RedisStateStore {
             .map_err(|e| TaskMeshError::Redis(e))?;
         
         if let Some(json) = data_json {
-            let checkpoint_data: CheckpointData = serde_json::from_str(&json)?;
+            let checkpoint_data = CheckpointData::from_json(checkpoint_id, &json)?;
             
             // Limpar estado atual
             let task_ids: Vec<String> = conn.smembers("tasks:all").await
                 .map_err(|e| TaskMeshError::Redis(e))?;
+            // remove_task/store_task adquirem a conexão novamente
+            drop(conn);
             
             for task_id_str in task_ids {
-                if let Ok(task_id) = uuid::Uuid::parse_str(&task_id_str) {
+                if let Ok(task_id) = task_id_str.parse::<TaskId>() {
                     self.remove_task(&task_id).await?;
                 }
             }
@@ -972,66 +2630,186 @@ impl StateStore for RedisStateStore {
         Ok(checkpoints)
     }
     
+    async fn ping(&self) -> TaskMeshResult<()> {
+        let mut conn = self.connection.write().await;
+        redis::cmd("PING").query_async::<_, String>(&mut *conn).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        Ok(())
+    }
+    
+    fn open_connections(&self) -> Option<u32> {
+        // Uma única conexão multiplexada; não há pool a reduzir
+        Some(1)
+    }
+    
+    async fn stats(&self) -> TaskMeshResult<StorageStats> {
+        let mut conn = self.connection.write().await;
+        let tasks: u64 = conn.scard("tasks:all").await.map_err(TaskMeshError::Redis)?;
+        let events: u64 = conn.zcard("events").await.map_err(TaskMeshError::Redis)?;
+        // MEMORY USAGE pode estar desabilitado; o tamanho é opcional
+        let approx_bytes = redis::cmd("MEMORY").arg("USAGE").arg("events")
+            .query_async::<_, Option<u64>>(&mut *conn).await
+            .ok()
+            .flatten();
+        Ok(StorageStats {
+            tasks: Some(tasks),
+            events: Some(events),
+            approx_bytes,
+            ..StorageStats::default()
+        })
+    }
+    
     async fn cleanup_old_data(&self, _retention_days: u32) -> TaskMeshResult<()> {
         debug!("Limpeza de dados do Redis não implementada");
         // TODO: Implementar limpeza de dados antigos no Redis
         Ok(())
     }
-}
-
-/// Implementação em memória
-impl MemoryStateStore {
-    pub async fn new() -> TaskMeshResult<Self> {
-        Ok(Self {
-            tasks: Arc::new(RwLock::new(HashMap::new())),
-            task_status: Arc::new(RwLock::new(HashMap::new())),
-            events: Arc::new(RwLock::new(Vec::new())),
-            metrics: Arc::new(RwLock::new(HashMap::new())),
-            checkpoints: Arc::new(RwLock::new(HashMap::new())),
+    
+    async fn store_events(&self, events: &[SystemEvent]) -> TaskMeshResult<()> {
+        debug!("Armazenando {} eventos no Redis em lote", events.len());
+        
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for event in events {
+            let timestamp = event.timestamp.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default().as_millis();
+            pipe.zadd("events", serde_json::to_string(event)?, timestamp as f64).ignore();
+        }
+        
+        let mut conn = self.connection.write().await;
+        pipe.query_async::<_, ()>(&mut *conn).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        Ok(())
+    }
+    
+    async fn update_task_statuses(&self, updates: &[(TaskId, TaskStatus)]) -> TaskMeshResult<()> {
+        debug!("Atualizando {} status no Redis em lote", updates.len());
+        
+        let mut conn = self.connection.write().await;
+        let last_seq: u64 = conn.incr("status_history:seq", updates.len() as u64).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        let first_seq = last_seq + 1 - updates.len() as u64;
+        let changed_at = SystemTime::now();
+        
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for (offset, (task_id, status)) in updates.iter().enumerate() {
+            let transition = StatusTransition {
+                seq: first_seq + offset as u64,
+                status: status.clone(),
+                changed_at,
+            };
+            pipe.set(format!("status:{}", task_id), serde_json::to_string(status)?).ignore();
+            pipe.rpush(format!("status_history:{}", task_id), serde_json::to_string(&transition)?).ignore();
+        }
+        
+        pipe.query_async::<_, ()>(&mut *conn).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        Ok(())
+    }
+    
+    async fn store_metrics_batch(&self, metrics: &[(TaskId, ExecutionMetrics)]) -> TaskMeshResult<()> {
+        debug!("Armazenando métricas de {} tarefas no Redis em lote", metrics.len());
+        
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for (task_id, task_metrics) in metrics {
+            pipe.set(format!("metrics:{}", task_id), serde_json::to_string(task_metrics)?).ignore();
+        }
+        
+        let mut conn = self.connection.write().await;
+        pipe.query_async::<_, ()>(&mut *conn).await
+            .map_err(|e| TaskMeshError::Redis(e))?;
+        
+        Ok(())
+    }
+}
+
+/// Implementação em memória
+impl MemoryStateStore {
+    pub async fn new() -> TaskMeshResult<Self> {
+        Self::with_event_capacity(DEFAULT_MEMORY_EVENT_CAPACITY).await
+    }
+    
+    /// Cria a instância retendo no máximo `capacity` eventos
+    pub async fn with_event_capacity(capacity: usize) -> TaskMeshResult<Self> {
+        Ok(Self {
+            tasks: DashMap::new(),
+            task_status: DashMap::new(),
+            status_history: DashMap::new(),
+            status_seq: std::sync::atomic::AtomicU64::new(0),
+            events: Arc::new(RwLock::new(EventRing::new(capacity))),
+            metrics: DashMap::new(),
+            checkpoints: DashMap::new(),
+            checkpoint_codec: CodecConfig::default(),
+            models: DashMap::new(),
+            aliases: DashMap::new(),
+            settings: DashMap::new(),
+            attempts: DashMap::new(),
+            manifests: DashMap::new(),
         })
     }
+    
+    /// Codec e compressão dos checkpoints gravados daqui em diante
+    pub fn with_checkpoint_codec(mut self, codec: CodecConfig) -> Self {
+        self.checkpoint_codec = codec;
+        self
+    }
 }
 
 #[async_trait]
 impl StateStore for MemoryStateStore {
     async fn store_task(&self, task: &Task) -> TaskMeshResult<()> {
-        self.tasks.write().await.insert(task.id, task.clone());
+        self.tasks.insert(task.id, task.clone());
         Ok(())
     }
     
     async fn get_task(&self, task_id: &TaskId) -> TaskMeshResult<Option<Task>> {
-        Ok(self.tasks.read().await.get(task_id).cloned())
+        Ok(self.tasks.get(task_id).map(|task| task.value().clone()))
     }
     
     async fn remove_task(&self, task_id: &TaskId) -> TaskMeshResult<()> {
-        self.tasks.write().await.remove(task_id);
-        self.task_status.write().await.remove(task_id);
+        self.tasks.remove(task_id);
+        self.task_status.remove(task_id);
+        self.status_history.remove(task_id);
+        self.metrics.remove(task_id);
+        self.attempts.remove(task_id);
+        self.manifests.remove(task_id);
+        self.aliases.retain(|_, owner| owner != task_id);
         Ok(())
     }
     
     async fn update_task_status(&self, task_id: &TaskId, status: TaskStatus) -> TaskMeshResult<()> {
-        self.task_status.write().await.insert(*task_id, status);
+        // O fragmento do histórico serializa atualizações da mesma tarefa:
+        // sequência, histórico e status atual ficam consistentes entre si
+        let mut history = self.status_history.entry(*task_id).or_default();
+        let seq = self.status_seq.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        history.push(StatusTransition { seq, status: status.clone(), changed_at: SystemTime::now() });
+        self.task_status.insert(*task_id, status);
         Ok(())
     }
     
     async fn get_task_status(&self, task_id: &TaskId) -> TaskMeshResult<TaskStatus> {
-        Ok(self.task_status.read().await.get(task_id).cloned().unwrap_or(TaskStatus::Pending))
+        Ok(self.task_status.get(task_id).map(|status| status.value().clone()).unwrap_or(TaskStatus::Pending))
+    }
+    
+    async fn get_status_history(&self, task_id: &TaskId) -> TaskMeshResult<Vec<StatusTransition>> {
+        Ok(self.status_history.get(task_id).map(|history| history.value().clone()).unwrap_or_default())
     }
     
     async fn list_tasks(&self) -> TaskMeshResult<Vec<Task>> {
-        Ok(self.tasks.read().await.values().cloned().collect())
+        Ok(self.tasks.iter().map(|task| task.value().clone()).collect())
     }
     
     async fn list_tasks_by_status(&self, status_filter: &[TaskStatus]) -> TaskMeshResult<Vec<Task>> {
-        let tasks = self.tasks.read().await;
-        let status_map = self.task_status.read().await;
-        
-        let filtered_tasks: Vec<Task> = tasks.values()
-            .filter(|task| {
-                let status = status_map.get(&task.id).unwrap_or(&TaskStatus::Pending);
-                status_filter.contains(status)
+        let filtered_tasks: Vec<Task> = self.tasks.iter()
+            .filter(|task| match self.task_status.get(&task.id) {
+                Some(status) => status_filter.contains(&status),
+                None => status_filter.contains(&TaskStatus::Pending),
             })
-            .cloned()
+            .map(|task| task.value().clone())
             .collect();
         
         Ok(filtered_tasks)
@@ -1042,93 +2820,582 @@ impl StateStore for MemoryStateStore {
         Ok(())
     }
     
-    async fn get_events(
-        &self, 
-        start_time: Option<SystemTime>, 
-        end_time: Option<SystemTime>
-    ) -> TaskMeshResult<Vec<SystemEvent>> {
-        let events = self.events.read().await;
+    async fn append_event(&self, event: &SystemEvent) -> TaskMeshResult<Option<u64>> {
+        let mut ring = self.events.write().await;
+        ring.push(event.clone());
+        // Sequência = posição absoluta + 1
+        Ok(Some(ring.dropped + ring.events.len() as u64))
+    }
+    
+    async fn events_after(&self, after_seq: u64, limit: usize) -> TaskMeshResult<Vec<(u64, SystemEvent)>> {
+        let ring = self.events.read().await;
+        let skip = after_seq.saturating_sub(ring.dropped) as usize;
+        Ok(ring.events
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(limit)
+            .map(|(index, event)| (ring.dropped + index as u64 + 1, event.clone()))
+            .collect())
+    }
+    
+    async fn query_events(&self, query: &EventQuery) -> TaskMeshResult<EventPage> {
+        let ring = self.events.read().await;
+        
+        // O cursor é a posição absoluta do evento; posições já descartadas
+        // continuam do evento mais antigo retido
+        let offset: u64 = match &query.cursor {
+            Some(cursor) => cursor.parse()
+                .map_err(|_| TaskMeshError::Configuration(format!("Cursor inválido: {}", cursor)))?,
+            None => 0,
+        };
+        let skip = offset.saturating_sub(ring.dropped) as usize;
         
-        let filtered_events: Vec<SystemEvent> = events.iter()
-            .filter(|event| {
-                if let Some(start) = start_time {
-                    if event.timestamp < start {
-                        return false;
-                    }
-                }
-                if let Some(end) = end_time {
-                    if event.timestamp > end {
-                        return false;
-                    }
-                }
-                true
-            })
-            .cloned()
-            .collect();
+        let mut page = Vec::new();
+        let mut next_cursor = None;
+        for (index, event) in ring.events.iter().enumerate().skip(skip) {
+            if !query.matches(event) {
+                continue;
+            }
+            if page.len() == query.limit {
+                next_cursor = Some((ring.dropped + index as u64).to_string());
+                break;
+            }
+            page.push(event.clone());
+        }
         
-        Ok(filtered_events)
+        Ok(EventPage { events: page, next_cursor })
     }
     
     async fn store_metrics(&self, task_id: &TaskId, metrics: &ExecutionMetrics) -> TaskMeshResult<()> {
-        self.metrics.write().await.insert(*task_id, metrics.clone());
+        self.metrics.insert(*task_id, metrics.clone());
         Ok(())
     }
     
     async fn get_metrics(&self, task_id: &TaskId) -> TaskMeshResult<Option<ExecutionMetrics>> {
-        Ok(self.metrics.read().await.get(task_id).cloned())
+        Ok(self.metrics.get(task_id).map(|metrics| metrics.value().clone()))
+    }
+    
+    async fn store_model(&self, model: &ModelRecord) -> TaskMeshResult<()> {
+        self.models
+            .entry(model.name.clone())
+            .or_default()
+            .insert(model.version, model.clone());
+        Ok(())
+    }
+    
+    async fn list_model_versions(&self, name: &str) -> TaskMeshResult<Vec<ModelRecord>> {
+        Ok(self.models
+            .get(name)
+            .map(|versions| versions.values().cloned().collect())
+            .unwrap_or_default())
+    }
+    
+    async fn store_attempt(&self, attempt: &AttemptRecord) -> TaskMeshResult<()> {
+        self.attempts
+            .entry(attempt.task_id)
+            .or_default()
+            .insert(attempt.attempt, attempt.clone());
+        Ok(())
+    }
+    
+    async fn list_attempts(&self, task_id: &TaskId) -> TaskMeshResult<Vec<AttemptRecord>> {
+        Ok(self.attempts
+            .get(task_id)
+            .map(|attempts| attempts.values().cloned().collect())
+            .unwrap_or_default())
+    }
+    
+    async fn store_manifest(&self, manifest: &ExecutionManifest) -> TaskMeshResult<()> {
+        self.manifests
+            .entry(manifest.task_id)
+            .or_default()
+            .insert(manifest.attempt, manifest.clone());
+        Ok(())
+    }
+    
+    async fn list_manifests(&self, task_id: &TaskId) -> TaskMeshResult<Vec<ExecutionManifest>> {
+        Ok(self.manifests
+            .get(task_id)
+            .map(|attempts| attempts.values().cloned().collect())
+            .unwrap_or_default())
+    }
+    
+    async fn register_alias(&self, namespace: &str, alias: &str, task_id: &TaskId) -> TaskMeshResult<()> {
+        match self.aliases.entry((namespace.to_string(), alias.to_string())) {
+            dashmap::mapref::entry::Entry::Occupied(entry) if entry.get() != task_id => {
+                Err(TaskMeshError::AliasConflict {
+                    namespace: namespace.to_string(),
+                    alias: alias.to_string(),
+                    existing: *entry.get(),
+                })
+            }
+            dashmap::mapref::entry::Entry::Occupied(_) => Ok(()),
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(*task_id);
+                Ok(())
+            }
+        }
+    }
+    
+    async fn resolve_alias(&self, namespace: &str, alias: &str) -> TaskMeshResult<Option<TaskId>> {
+        Ok(self.aliases.get(&(namespace.to_string(), alias.to_string())).map(|owner| *owner))
+    }
+    
+    async fn put_setting(&self, key: &str, value: Option<&str>) -> TaskMeshResult<()> {
+        match value {
+            Some(value) => self.settings.insert(key.to_string(), value.to_string()),
+            None => self.settings.remove(key).map(|(_, value)| value),
+        };
+        Ok(())
+    }
+    
+    async fn get_setting(&self, key: &str) -> TaskMeshResult<Option<String>> {
+        Ok(self.settings.get(key).map(|value| value.clone()))
     }
     
     async fn create_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
         let tasks = self.list_tasks().await?;
-        let checkpoint_data = CheckpointData {
-            tasks,
-            created_at: SystemTime::now(),
-        };
+        let data = CheckpointData::new(tasks).encode_with(&self.checkpoint_codec)?;
         
-        let data = bincode::serialize(&checkpoint_data)
-            .map_err(|e| TaskMeshError::Internal(format!("Erro de serialização: {}", e)))?;
-        
-        self.checkpoints.write().await.insert(checkpoint_id.to_string(), data);
+        self.checkpoints.insert(checkpoint_id.to_string(), data);
         Ok(())
     }
     
     async fn restore_checkpoint(&self, checkpoint_id: &str) -> TaskMeshResult<()> {
-        let checkpoints = self.checkpoints.read().await;
+        let checkpoint_data = match self.checkpoints.get(checkpoint_id) {
+            Some(data) => CheckpointData::decode(checkpoint_id, data.value())?,
+            None => return Err(TaskMeshError::CheckpointNotFound(checkpoint_id.to_string())),
+        };
         
-        if let Some(data) = checkpoints.get(checkpoint_id) {
-            let checkpoint_data: CheckpointData = bincode::deserialize(data)
-                .map_err(|e| TaskMeshError::Internal(format!("Erro de desserialização: {}", e)))?;
-            
-            // Limpar estado atual
-            self.tasks.write().await.clear();
-            self.task_status.write().await.clear();
-            
-            // Restaurar tarefas
-            for task in checkpoint_data.tasks {
-                self.store_task(&task).await?;
-            }
-            
-            Ok(())
-        } else {
-            Err(TaskMeshError::CheckpointNotFound(checkpoint_id.to_string()))
+        // Limpar estado atual
+        self.tasks.clear();
+        self.task_status.clear();
+        
+        // Restaurar tarefas
+        for task in checkpoint_data.tasks {
+            self.tasks.insert(task.id, task);
         }
+        
+        Ok(())
     }
     
     async fn list_checkpoints(&self) -> TaskMeshResult<Vec<String>> {
-        Ok(self.checkpoints.read().await.keys().cloned().collect())
+        Ok(self.checkpoints.iter().map(|entry| entry.key().clone()).collect())
     }
     
-    async fn cleanup_old_data(&self, _retention_days: u32) -> TaskMeshResult<()> {
-        // Para implementação em memória, não há necessidade de limpeza
+    async fn verify_checkpoints(&self) -> TaskMeshResult<Vec<String>> {
+        Ok(self.checkpoints
+            .iter()
+            .filter(|entry| CheckpointData::decode(entry.key(), entry.value()).is_err())
+            .map(|entry| entry.key().clone())
+            .collect())
+    }
+    
+    async fn last_checkpoint_at(&self) -> TaskMeshResult<Option<SystemTime>> {
+        Ok(self.checkpoints
+            .iter()
+            .filter_map(|entry| CheckpointData::decode(entry.key(), entry.value()).ok())
+            .map(|checkpoint| checkpoint.created_at)
+            .max())
+    }
+    
+    async fn ping(&self) -> TaskMeshResult<()> {
         Ok(())
     }
+    
+    async fn stats(&self) -> TaskMeshResult<StorageStats> {
+        let ring = self.events.read().await;
+        Ok(StorageStats {
+            tasks: Some(self.tasks.len() as u64),
+            events: Some(ring.events.len() as u64),
+            dropped_events: ring.dropped,
+            last_dropped_at: ring.last_drop,
+            event_capacity: Some(ring.capacity as u64),
+            ..StorageStats::default()
+        })
+    }
+    
+    async fn cleanup_old_data(&self, retention_days: u32) -> TaskMeshResult<()> {
+        let cutoff = SystemTime::now() - 
+            std::time::Duration::from_secs(retention_days as u64 * 24 * 60 * 60);
+        
+        self.status_history.retain(|_, transitions| {
+            transitions.retain(|t| t.changed_at >= cutoff);
+            !transitions.is_empty()
+        });
+        Ok(())
+    }
+}
+
+/// SHA-256 (hex) do conteúdo serializado de um checkpoint
+fn checkpoint_checksum(data: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, data)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Liga `auto_vacuum = INCREMENTAL` e reescreve o banco com `VACUUM`
+///
+/// O PRAGMA e o `VACUUM` precisam da mesma conexão: o modo pedido vale só
+/// para ela até o `VACUUM` gravá-lo no arquivo.
+async fn set_incremental_vacuum(conn: &mut SqliteConnection) -> TaskMeshResult<()> {
+    sqlx::query("PRAGMA auto_vacuum = INCREMENTAL").execute(&mut *conn).await?;
+    sqlx::query("VACUUM").execute(&mut *conn).await?;
+    Ok(())
 }
 
+/// Assinatura dos checkpoints em bincode gravados com versão de formato
+const CHECKPOINT_MAGIC: [u8; 4] = *b"TMCK";
+
+const CHECKPOINT_HEADER_LEN: usize = CHECKPOINT_MAGIC.len() + 4;
+
 /// Dados de checkpoint
+///
+/// Em bincode, o conteúdo vem precedido de [`CHECKPOINT_MAGIC`] e da versão
+/// do formato (u32 little-endian); em JSON e CBOR a versão é o campo
+/// `format_version`. Com outro codec ou compressão que não bincode puro, o
+/// blob vai no envelope de [`crate::codec`]. Ver [`crate::compat`].
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-struct CheckpointData {
-    tasks: Vec<Task>,
+pub(crate) struct CheckpointData {
+    pub(crate) tasks: Vec<Task>,
+    pub(crate) created_at: SystemTime,
+    /// Versão do formato em que o checkpoint foi gravado (0: sem versão)
+    #[serde(default)]
+    pub(crate) format_version: u32,
+}
+
+impl CheckpointData {
+    fn new(tasks: Vec<Task>) -> Self {
+        Self { tasks, created_at: SystemTime::now(), format_version: FORMAT_VERSION }
+    }
+
+    /// Serializa em bincode com o cabeçalho de versão
+    pub(crate) fn encode(&self) -> TaskMeshResult<Vec<u8>> {
+        let payload = bincode::serialize(self)
+            .map_err(|e| TaskMeshError::Internal(format!("Erro de serialização: {}", e)))?;
+        let mut data = Vec::with_capacity(CHECKPOINT_HEADER_LEN + payload.len());
+        data.extend_from_slice(&CHECKPOINT_MAGIC);
+        data.extend_from_slice(&self.format_version.to_le_bytes());
+        data.extend_from_slice(&payload);
+        Ok(data)
+    }
+
+    /// Serializa no codec configurado
+    ///
+    /// Bincode sem compressão grava o mesmo blob de [`Self::encode`]; em
+    /// bincode com compressão, é esse blob que vai no envelope.
+    pub(crate) fn encode_with(&self, config: &CodecConfig) -> TaskMeshResult<Vec<u8>> {
+        if *config == CodecConfig::default() {
+            return self.encode();
+        }
+        let payload = match config.codec {
+            Codec::Bincode => self.encode()?,
+            codec => codec.serialize(self)?,
+        };
+        config.wrap(&payload)
+    }
+
+    /// Desserializa um checkpoint, recusando versões de formato mais novas
+    /// que a suportada
+    ///
+    /// O codec vem do cabeçalho do blob; sem ele, o blob é bincode.
+    pub(crate) fn decode(checkpoint_id: &str, data: &[u8]) -> TaskMeshResult<Self> {
+        let corrupted = || TaskMeshError::CheckpointCorrupted(checkpoint_id.to_string());
+        match CodecConfig::unwrap(data).map_err(|_| corrupted())? {
+            None => Self::decode_bincode(checkpoint_id, data),
+            Some((config, payload)) => match config.codec {
+                Codec::Bincode => Self::decode_bincode(checkpoint_id, &payload),
+                codec => {
+                    let checkpoint: Self = codec.deserialize(&payload).map_err(|_| corrupted())?;
+                    check_format_version(checkpoint.format_version)?;
+                    Ok(checkpoint)
+                }
+            },
+        }
+    }
+
+    /// Desserializa um checkpoint em bincode (com ou sem cabeçalho de versão)
+    fn decode_bincode(checkpoint_id: &str, data: &[u8]) -> TaskMeshResult<Self> {
+        let corrupted = |_| TaskMeshError::CheckpointCorrupted(checkpoint_id.to_string());
+        let Some(header) = data.strip_prefix(&CHECKPOINT_MAGIC) else {
+            return Self::decode_unversioned(data).map_err(corrupted);
+        };
+        let version = header
+            .get(..4)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or_else(|| TaskMeshError::CheckpointCorrupted(checkpoint_id.to_string()))?;
+        check_format_version(version)?;
+        let payload = &data[CHECKPOINT_HEADER_LEN..];
+        match version {
+            1 => Self::decode_layout::<TaskV1>(payload).map_err(corrupted),
+            2 => Self::decode_layout::<TaskV2>(payload).map_err(corrupted),
+            3 => Self::decode_layout::<TaskV3>(payload).map_err(corrupted),
+            4 => Self::decode_layout::<TaskV4>(payload).map_err(corrupted),
+            5 => Self::decode_layout::<TaskV5>(payload).map_err(corrupted),
+            _ => bincode::deserialize(payload).map_err(corrupted),
+        }
+    }
+
+    /// Desserializa um checkpoint bincode com o layout de tarefa `T` de uma
+    /// versão anterior do formato
+    fn decode_layout<T: serde::de::DeserializeOwned + Into<Task>>(payload: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize::<PreviousCheckpointData<T>>(payload).map(|previous| CheckpointData {
+            tasks: previous.tasks.into_iter().map(Into::into).collect(),
+            created_at: previous.created_at,
+            format_version: previous.format_version,
+        })
+    }
+
+    /// Desserializa um checkpoint em JSON (Redis)
+    pub(crate) fn from_json(checkpoint_id: &str, json: &str) -> TaskMeshResult<Self> {
+        let checkpoint: Self = serde_json::from_str(json)
+            .map_err(|_| TaskMeshError::CheckpointCorrupted(checkpoint_id.to_string()))?;
+        check_format_version(checkpoint.format_version)?;
+        Ok(checkpoint)
+    }
+
+    /// Desserializa um checkpoint gravado antes do cabeçalho de versão,
+    /// aceitando também os formatos anteriores a `Task::group_id` e a
+    /// `Task::alias`
+    fn decode_unversioned(data: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize::<UnversionedCheckpointData>(data)
+            .map(|unversioned| CheckpointData {
+                tasks: unversioned.tasks.into_iter().map(Task::from).collect(),
+                created_at: unversioned.created_at,
+                format_version: 0,
+            })
+            .or_else(|error| {
+                bincode::deserialize::<UngroupedCheckpointData>(data)
+                    .map(|ungrouped| CheckpointData {
+                        tasks: ungrouped.tasks.into_iter().map(Task::from).collect(),
+                        created_at: ungrouped.created_at,
+                        format_version: 0,
+                    })
+                    .or_else(|_| {
+                        bincode::deserialize::<LegacyCheckpointData>(data).map(|legacy| CheckpointData {
+                            tasks: legacy.tasks.into_iter().map(Task::from).collect(),
+                            created_at: legacy.created_at,
+                            format_version: 0,
+                        })
+                    })
+                    .map_err(|_| error)
+            })
+    }
+}
+
+/// Checkpoint versionado com o layout de tarefa `T` de uma versão anterior
+#[derive(serde::Deserialize)]
+struct PreviousCheckpointData<T> {
+    tasks: Vec<T>,
+    created_at: SystemTime,
+    format_version: u32,
+}
+
+/// Tarefa como gravada em bincode na versão 5 do formato, antes de
+/// `Task::forced_outcome` (ver [`TaskV2`])
+#[derive(serde::Deserialize)]
+pub(crate) struct TaskV5 {
+    v4: TaskV4,
+    sla: Option<crate::sla::SlaSpec>,
+}
+
+impl From<TaskV5> for Task {
+    fn from(v5: TaskV5) -> Self {
+        Task { sla: v5.sla, ..Task::from(v5.v4) }
+    }
+}
+
+/// Tarefa como gravada em bincode na versão 4 do formato, antes de
+/// `Task::sla` (ver [`TaskV2`])
+#[derive(serde::Deserialize)]
+pub(crate) struct TaskV4 {
+    v3: TaskV3,
+    hooks: Vec<crate::hooks::HookSpec>,
+}
+
+impl From<TaskV4> for Task {
+    fn from(v4: TaskV4) -> Self {
+        Task { hooks: v4.hooks, ..Task::from(v4.v3) }
+    }
+}
+
+/// Tarefa como gravada em bincode na versão 3 do formato, antes de
+/// `Task::hooks` (ver [`TaskV2`])
+#[derive(serde::Deserialize)]
+pub(crate) struct TaskV3 {
+    v2: TaskV2,
+    concurrency_group: Option<String>,
+}
+
+impl From<TaskV3> for Task {
+    fn from(v3: TaskV3) -> Self {
+        Task { concurrency_group: v3.concurrency_group, ..Task::from(v3.v2) }
+    }
+}
+
+/// Tarefa como gravada em bincode na versão 2 do formato, antes de
+/// `Task::concurrency_group`
+///
+/// Em bincode os campos de uma struct aninhada ficam em sequência, sem
+/// delimitação, então o layout é o de [`TaskV1`] seguido dos sidecars.
+#[derive(serde::Deserialize)]
+pub(crate) struct TaskV2 {
+    v1: TaskV1,
+    sidecars: Vec<crate::sidecar::SidecarSpec>,
+}
+
+impl From<TaskV2> for Task {
+    fn from(v2: TaskV2) -> Self {
+        Task { sidecars: v2.sidecars, ..Task::from(v2.v1) }
+    }
+}
+
+/// Tarefa como gravada em bincode na versão 1 do formato, antes de
+/// `Task::sidecars`
+#[derive(serde::Deserialize)]
+pub(crate) struct TaskV1 {
+    id: TaskId,
+    name: String,
+    alias: Option<String>,
+    definition: TaskDefinition,
+    dependencies: Vec<TaskId>,
+    priority: Priority,
+    metadata: HashMap<String, String>,
     created_at: SystemTime,
+    timeout: Option<std::time::Duration>,
+    max_retries: u32,
+    tags: Vec<String>,
+    group_id: Option<uuid::Uuid>,
+    group_name: Option<String>,
+}
+
+impl From<TaskV1> for Task {
+    fn from(v1: TaskV1) -> Self {
+        Task {
+            id: v1.id,
+            name: v1.name,
+            alias: v1.alias,
+            definition: v1.definition,
+            dependencies: v1.dependencies,
+            priority: v1.priority,
+            metadata: v1.metadata,
+            created_at: v1.created_at,
+            timeout: v1.timeout,
+            max_retries: v1.max_retries,
+            tags: v1.tags,
+            group_id: v1.group_id,
+            group_name: v1.group_name,
+            sidecars: Vec::new(),
+            concurrency_group: None,
+            hooks: Vec::new(),
+            sla: None,
+            forced_outcome: None,
+        }
+    }
+}
+
+/// Checkpoint gravado antes do cabeçalho de versão
+#[derive(serde::Deserialize)]
+struct UnversionedCheckpointData {
+    tasks: Vec<TaskV1>,
+    created_at: SystemTime,
+}
+
+/// Checkpoint gravado antes de `Task::group_id`
+#[derive(serde::Deserialize)]
+struct UngroupedCheckpointData {
+    tasks: Vec<UngroupedTask>,
+    created_at: SystemTime,
+}
+
+#[derive(serde::Deserialize)]
+struct UngroupedTask {
+    id: TaskId,
+    name: String,
+    alias: Option<String>,
+    definition: TaskDefinition,
+    dependencies: Vec<TaskId>,
+    priority: Priority,
+    metadata: HashMap<String, String>,
+    created_at: SystemTime,
+    timeout: Option<std::time::Duration>,
+    max_retries: u32,
+    tags: Vec<String>,
+}
+
+impl From<UngroupedTask> for Task {
+    fn from(ungrouped: UngroupedTask) -> Self {
+        Task {
+            id: ungrouped.id,
+            name: ungrouped.name,
+            alias: ungrouped.alias,
+            definition: ungrouped.definition,
+            dependencies: ungrouped.dependencies,
+            priority: ungrouped.priority,
+            metadata: ungrouped.metadata,
+            created_at: ungrouped.created_at,
+            timeout: ungrouped.timeout,
+            max_retries: ungrouped.max_retries,
+            tags: ungrouped.tags,
+            group_id: None,
+            group_name: None,
+            sidecars: Vec::new(),
+            concurrency_group: None,
+            hooks: Vec::new(),
+            sla: None,
+            forced_outcome: None,
+        }
+    }
+}
+
+/// Checkpoint gravado antes de `Task::alias` (o bincode não tolera campos novos)
+#[derive(serde::Deserialize)]
+struct LegacyCheckpointData {
+    tasks: Vec<LegacyTask>,
+    created_at: SystemTime,
+}
+
+#[derive(serde::Deserialize)]
+struct LegacyTask {
+    id: TaskId,
+    name: String,
+    definition: TaskDefinition,
+    dependencies: Vec<TaskId>,
+    priority: Priority,
+    metadata: HashMap<String, String>,
+    created_at: SystemTime,
+    timeout: Option<std::time::Duration>,
+    max_retries: u32,
+    tags: Vec<String>,
+}
+
+impl From<LegacyTask> for Task {
+    fn from(legacy: LegacyTask) -> Self {
+        Task {
+            id: legacy.id,
+            name: legacy.name,
+            alias: None,
+            definition: legacy.definition,
+            dependencies: legacy.dependencies,
+            priority: legacy.priority,
+            metadata: legacy.metadata,
+            created_at: legacy.created_at,
+            timeout: legacy.timeout,
+            max_retries: legacy.max_retries,
+            tags: legacy.tags,
+            group_id: None,
+            group_name: None,
+            sidecars: Vec::new(),
+            concurrency_group: None,
+            hooks: Vec::new(),
+            sla: None,
+            forced_outcome: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1141,7 +3408,7 @@ mod tests {
         
         let task = Task::new(
             "test_task".to_string(),
-            TaskDefinition::Command("echo hello".to_string()),
+            TaskDefinition::command("echo hello"),
             vec![],
         );
         let task_id = task.id;
@@ -1157,6 +3424,8 @@ mod tests {
         store.update_task_status(&task_id, TaskStatus::Running {
             started_at: SystemTime::now(),
             worker_id: "worker_1".to_string(),
+            process: None,
+            progress: None,
         }).await.unwrap();
         
         // Verificar status
@@ -1176,7 +3445,7 @@ mod tests {
         
         let task = Task::new(
             "test_task".to_string(),
-            TaskDefinition::Command("echo hello".to_string()),
+            TaskDefinition::command("echo hello"),
             vec![],
         );
         
@@ -1199,5 +3468,708 @@ mod tests {
         let restored_task = store.get_task(&task.id).await.unwrap();
         assert!(restored_task.is_some());
     }
-}
 
+    #[tokio::test]
+    async fn test_checkpoints_restore_with_any_codec() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let task = Task::new("t".to_string(), TaskDefinition::command("true"), vec![]);
+
+        for (i, config) in CodecConfig::all().into_iter().enumerate() {
+            let id = format!("checkpoint_{}", i);
+            let writer = SqliteStateStore::new(&url).await.unwrap().with_checkpoint_codec(config);
+            writer.store_task(&task).await.unwrap();
+            writer.create_checkpoint(&id).await.unwrap();
+            writer.remove_task(&task.id).await.unwrap();
+            drop(writer);
+
+            // O leitor usa o codec padrão; o formato vem do cabeçalho
+            let reader = SqliteStateStore::new(&url).await.unwrap();
+            reader.restore_checkpoint(&id).await.unwrap();
+            assert!(reader.get_task(&task.id).await.unwrap().is_some(), "{:?}", config);
+
+            let blob = CheckpointData::new(vec![task.clone()]).encode_with(&config).unwrap();
+            let decoded = CheckpointData::decode(&id, &blob).unwrap();
+            assert_eq!((decoded.tasks[0].id, decoded.format_version), (task.id, FORMAT_VERSION));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_corrupted_checkpoint_and_fallback() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let task = Task::new("t".to_string(), TaskDefinition::command("true"), vec![]);
+        store.store_task(&task).await.unwrap();
+        store.create_checkpoint("older").await.unwrap();
+        store.create_checkpoint("newer").await.unwrap();
+        assert!(store.verify_checkpoints().await.unwrap().is_empty());
+        
+        // Inverte um byte do blob mais recente
+        let row = sqlx::query("SELECT data FROM checkpoints WHERE id = 'newer'")
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        let mut data: Vec<u8> = row.get("data");
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        sqlx::query("UPDATE checkpoints SET data = ? WHERE id = 'newer'")
+            .bind(data)
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        
+        assert_eq!(store.verify_checkpoints().await.unwrap(), vec!["newer".to_string()]);
+        match store.restore_checkpoint("newer").await {
+            Err(TaskMeshError::CheckpointCorrupted(id)) => assert_eq!(id, "newer"),
+            other => panic!("esperado CheckpointCorrupted, obtido {:?}", other),
+        }
+        assert!(matches!(
+            store.restore_checkpoint_with_fallback("newer", false).await,
+            Err(TaskMeshError::CheckpointCorrupted(_))
+        ));
+        
+        let restored = store.restore_checkpoint_with_fallback("newer", true).await.unwrap();
+        assert_eq!(restored, "older");
+        assert!(store.get_task(&task.id).await.unwrap().is_some());
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_concurrent_status_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let store = Arc::new(SqliteStateStore::new(&url).await.unwrap());
+        
+        let mut handles = Vec::new();
+        for i in 0..16 {
+            let store = store.clone();
+            handles.push(tokio::spawn(async move {
+                let task = Task::new(
+                    format!("task_{}", i),
+                    TaskDefinition::command("true"),
+                    vec![],
+                );
+                store.store_task(&task).await?;
+                for _ in 0..20 {
+                    store.update_task_status(&task.id, TaskStatus::Running {
+                        started_at: SystemTime::now(),
+                        worker_id: format!("worker_{}", i),
+                        process: None,
+                        progress: None,
+                    }).await?;
+                }
+                store.update_task_status(&task.id, TaskStatus::Scheduled).await
+            }));
+        }
+        
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+        
+        let scheduled = store.list_tasks_by_status(&[TaskStatus::Scheduled]).await.unwrap();
+        assert_eq!(scheduled.len(), 16);
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_status_index_used() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        
+        let rows = sqlx::query(
+            "EXPLAIN QUERY PLAN SELECT task_id FROM task_status WHERE status_type IN (?)"
+        )
+        .bind("Running")
+        .fetch_all(&store.pool)
+        .await
+        .unwrap();
+        
+        let plan: Vec<String> = rows.iter()
+            .map(|row| row.try_get::<String, _>("detail").unwrap())
+            .collect();
+        assert!(plan.iter().any(|detail| detail.contains("idx_task_status_type")), "{:?}", plan);
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_batched_status_updates_faster() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let mut updates: Vec<(TaskId, TaskStatus)> = Vec::with_capacity(5_000);
+        for i in 0..5_000 {
+            let task = Task::new(
+                format!("task_{}", i),
+                TaskDefinition::command("true"),
+                vec![],
+            );
+            store.store_task(&task).await.unwrap();
+            updates.push((task.id, TaskStatus::Scheduled));
+        }
+        
+        let start = std::time::Instant::now();
+        for (task_id, status) in &updates {
+            store.update_task_status(task_id, status.clone()).await.unwrap();
+        }
+        let unbatched = start.elapsed();
+        
+        let start = std::time::Instant::now();
+        store.update_task_statuses(&updates).await.unwrap();
+        let batched = start.elapsed();
+        
+        assert!(
+            batched * 5 <= unbatched,
+            "batched: {:?}, unbatched: {:?}", batched, unbatched
+        );
+        
+        let scheduled = sqlx::query("SELECT COUNT(*) AS total FROM task_status WHERE status_type = 'Scheduled'")
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        assert_eq!(scheduled.try_get::<i64, _>("total").unwrap(), 5_000);
+    }
+    
+    async fn assert_status_history(store: &dyn StateStore) {
+        let task = Task::new(
+            "history_task".to_string(),
+            TaskDefinition::command("true"),
+            vec![],
+        );
+        store.store_task(&task).await.unwrap();
+        
+        let now = SystemTime::now();
+        let transitions = vec![
+            TaskStatus::Pending,
+            TaskStatus::Running { started_at: now, worker_id: "worker_1".to_string(), process: None, progress: None },
+            TaskStatus::Failed { started_at: now, failed_at: now, error: "boom".to_string(), retry_count: 0 },
+            TaskStatus::Running { started_at: now, worker_id: "worker_2".to_string(), process: None, progress: None },
+            TaskStatus::Completed {
+                started_at: now,
+                completed_at: now,
+                result: TaskResult {
+                    exit_code: 0,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    output_data: None,
+                    metrics: ExecutionMetrics::default(),
+                    log_ref: None,
+                },
+            },
+        ];
+        for status in &transitions {
+            store.update_task_status(&task.id, status.clone()).await.unwrap();
+        }
+        
+        let history = store.get_status_history(&task.id).await.unwrap();
+        assert_eq!(history.len(), 5);
+        for (entry, expected) in history.iter().zip(&transitions) {
+            assert_eq!(&entry.status, expected);
+        }
+        for pair in history.windows(2) {
+            assert!(pair[0].seq < pair[1].seq);
+            assert!(pair[0].changed_at <= pair[1].changed_at);
+        }
+        
+        // O status atual continua sendo o último
+        assert_eq!(store.get_task_status(&task.id).await.unwrap(), transitions[4]);
+    }
+    
+    #[tokio::test]
+    async fn test_status_history_memory() {
+        let store = MemoryStateStore::new().await.unwrap();
+        assert_status_history(&store).await;
+    }
+    
+    #[tokio::test]
+    async fn test_status_history_sqlite() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        assert_status_history(&store).await;
+    }
+    
+    fn all_event_types() -> Vec<EventType> {
+        vec![
+            EventType::TaskSubmitted,
+            EventType::TaskScheduled,
+            EventType::TaskStarted,
+            EventType::TaskCompleted,
+            EventType::TaskFailed,
+            EventType::TaskCancelled,
+            EventType::CheckpointCreated,
+            EventType::CheckpointRestored,
+            EventType::WorkerStarted,
+            EventType::WorkerStopped,
+            EventType::SystemStarted,
+            EventType::SystemStopped,
+            EventType::Custom("deploy.finished".to_string()),
+            EventType::Unknown("FutureEvent".to_string()),
+        ]
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_event_type_round_trip() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        
+        let mut written = Vec::new();
+        for event_type in all_event_types() {
+            let event = SystemEvent::new(event_type, None, serde_json::json!({})).with_source("teste");
+            store.store_event(&event).await.unwrap();
+            written.push(event);
+        }
+        
+        let events = store.get_events(None, None).await.unwrap();
+        for event in &written {
+            let read = events.iter().find(|read| read.sequence == event.sequence).unwrap();
+            assert_eq!((&read.event_type, read.severity, read.source.as_str()), (&event.event_type, event.severity, "teste"));
+        }
+        let stored: Vec<EventType> = events.into_iter().map(|event| event.event_type).collect();
+        for event_type in all_event_types() {
+            assert!(stored.contains(&event_type), "{:?} não encontrado", event_type);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_seeds_event_sequence_from_persisted_max() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let store = SqliteStateStore::new(&url).await.unwrap();
+        // Gravado por um processo anterior que chegou mais longe na sequência
+        let mut previous = SystemEvent::new(EventType::TaskStarted, None, serde_json::json!({}));
+        previous.sequence += 1_000_000;
+        store.store_event(&previous).await.unwrap();
+        drop(store);
+        
+        SqliteStateStore::new(&url).await.unwrap();
+        assert!(SystemEvent::new(EventType::TaskStarted, None, serde_json::json!({})).sequence > previous.sequence);
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_get_events_keeps_newest_first_order() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let base = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        for offset in [0u64, 20, 10] {
+            let event = SystemEvent::new(EventType::TaskStarted, None, serde_json::json!({ "offset": offset }))
+                .at(base + std::time::Duration::from_secs(offset));
+            store.store_event(&event).await.unwrap();
+        }
+        
+        let offsets: Vec<u64> = store.get_events(None, None).await.unwrap()
+            .iter()
+            .map(|event| event.data["offset"].as_u64().unwrap())
+            .collect();
+        assert_eq!(offsets, vec![20, 10, 0]);
+        
+        // query_events continua paginando na ordem de gravação
+        let page = store.query_events(&EventQuery { limit: 10, ..EventQuery::default() }).await.unwrap();
+        let offsets: Vec<u64> = page.events.iter().map(|event| event.data["offset"].as_u64().unwrap()).collect();
+        assert_eq!(offsets, vec![0, 20, 10]);
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_legacy_event_rows() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        
+        for legacy in ["CheckpointCreated", "Custom(\"legado\")", "Removido"] {
+            sqlx::query("INSERT INTO events (timestamp, event_type, task_id, data) VALUES (0, ?, NULL, '{}')")
+                .bind(legacy)
+                .execute(&store.pool)
+                .await
+                .unwrap();
+        }
+        
+        let events = store.get_events(None, None).await.unwrap();
+        // Linhas sem as colunas da migração 15 recebem os valores padrão
+        assert!(events.iter().all(|event| event.sequence == 0 && event.severity == EventSeverity::Low && event.source.is_empty()));
+        let stored: Vec<EventType> = events.into_iter().map(|event| event.event_type).collect();
+        assert!(stored.contains(&EventType::CheckpointCreated));
+        assert!(stored.contains(&EventType::Custom("legado".to_string())));
+        assert!(stored.contains(&EventType::Unknown("Removido".to_string())));
+        assert!(!stored.contains(&EventType::SystemStarted));
+    }
+    
+    async fn assert_event_pagination(store: &dyn StateStore) {
+        let task_ids: Vec<TaskId> = (0..3).map(|_| TaskId::new_v4()).collect();
+        let base = SystemTime::now();
+        for i in 0..500 {
+            store.store_event(&SystemEvent::new(
+                if i % 2 == 0 { EventType::TaskStarted } else { EventType::TaskCompleted },
+                Some(task_ids[i % 3]),
+                serde_json::json!({ "seq": i }),
+            ).at(base)).await.unwrap();
+        }
+        
+        let mut query = EventQuery {
+            task_id: Some(task_ids[1]),
+            event_types: vec![EventType::TaskCompleted],
+            limit: 7,
+            ..EventQuery::default()
+        };
+        
+        let mut seen = Vec::new();
+        loop {
+            let page = store.query_events(&query).await.unwrap();
+            assert!(page.events.len() <= 7);
+            seen.extend(page.events.iter().map(|e| e.data["seq"].as_u64().unwrap()));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        
+        let expected: Vec<u64> = (0..500u64).filter(|i| i % 3 == 1 && i % 2 == 1).collect();
+        assert_eq!(seen, expected);
+    }
+    
+    #[tokio::test]
+    async fn test_event_pagination_memory() {
+        let store = MemoryStateStore::new().await.unwrap();
+        assert_event_pagination(&store).await;
+    }
+    
+    #[tokio::test]
+    async fn test_memory_event_ring_keeps_newest_window() {
+        let store = MemoryStateStore::new().await.unwrap();
+        let base = SystemTime::now();
+        for i in 0..25_000u64 {
+            let event = SystemEvent::new(EventType::TaskStarted, None, serde_json::json!({ "seq": i })).at(base);
+            store.store_event(&event).await.unwrap();
+        }
+        
+        let stats = store.stats().await.unwrap();
+        assert_eq!(stats.events, Some(10_000));
+        assert_eq!(stats.dropped_events, 15_000);
+        assert!(stats.last_dropped_at.is_some());
+        assert_eq!(stats.event_capacity, Some(DEFAULT_MEMORY_EVENT_CAPACITY as u64));
+        
+        let retained: Vec<u64> = store.get_events(None, None).await.unwrap()
+            .iter()
+            .map(|e| e.data["seq"].as_u64().unwrap())
+            .collect();
+        assert_eq!(retained, (15_000..25_000).collect::<Vec<_>>());
+        
+        // Paginação através do buffer e cursor anterior à janela retida
+        let mut query = EventQuery { limit: 4_000, ..EventQuery::default() };
+        let mut paged = Vec::new();
+        loop {
+            let page = store.query_events(&query).await.unwrap();
+            paged.extend(page.events.iter().map(|e| e.data["seq"].as_u64().unwrap()));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(paged, retained);
+        query.cursor = Some("10".to_string());
+        assert_eq!(store.query_events(&query).await.unwrap().events[0].data["seq"], 15_000);
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_stats_count_rows() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let task = Task::new("counted".to_string(), TaskDefinition::command("true"), vec![]);
+        store.store_task(&task).await.unwrap();
+        store.store_event(&SystemEvent::new(EventType::TaskStarted, Some(task.id), serde_json::json!({}))).await.unwrap();
+        
+        let stats = store.stats().await.unwrap();
+        assert_eq!((stats.tasks, stats.events, stats.dropped_events), (Some(1), Some(1), 0));
+        assert!(stats.approx_bytes.unwrap() > 0);
+    }
+    
+    #[tokio::test]
+    async fn test_sqlite_compaction_shrinks_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let store = Arc::new(SqliteStateStore::new(&url).await.unwrap());
+        let maintenance = crate::compaction::StorageMaintenance::new(store.clone());
+        // 2 = INCREMENTAL; sem ele a compactação incremental não libera nada
+        let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum").fetch_one(&store.pool).await.unwrap();
+        assert_eq!(auto_vacuum, 2);
+        
+        let fill = |rows: u32| {
+            sqlx::query(
+                "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < ?) \
+                 INSERT INTO events (timestamp, event_type, task_id, data) \
+                 SELECT i, 'TaskStarted', NULL, printf('{\"padding\":\"%0200d\"}', i) FROM n",
+            )
+            .bind(rows)
+            .execute(&store.pool)
+        };
+        for mode in [CompactionMode::Incremental, CompactionMode::Full] {
+            fill(50_000).await.unwrap();
+            sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&store.pool).await.unwrap();
+            let filled = store.stats().await.unwrap();
+            assert_eq!(filled.events, Some(50_000));
+            sqlx::query("DELETE FROM events").execute(&store.pool).await.unwrap();
+            
+            let report = maintenance.compact(mode).await.unwrap();
+            assert!(report.before.freelist_pages.unwrap() > 0, "{:?}", mode);
+            assert_eq!(report.after.freelist_pages, Some(0), "{:?}", mode);
+            assert!(report.after.page_count < filled.page_count, "{:?}", mode);
+            assert!(report.after.file_bytes.unwrap() < filled.file_bytes.unwrap() / 4, "{:?}", mode);
+            assert!(report.reclaimed_bytes().unwrap() > 0, "{:?}", mode);
+        }
+        
+        let in_memory = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        assert_eq!(in_memory.stats().await.unwrap().file_bytes, None);
+    }
+    
+    #[tokio::test]
+    async fn test_event_pagination_sqlite() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        assert_event_pagination(&store).await;
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn test_memory_store_concurrent_status_updates() {
+        let store = Arc::new(MemoryStateStore::new().await.unwrap());
+        let task_ids: Vec<TaskId> = (0..32).map(|_| TaskId::new_v4()).collect();
+        
+        // 32 tarefas concorrentes, 10k operações mistas de leitura e escrita
+        let workers: Vec<_> = (0..32)
+            .map(|worker| {
+                let (store, task_ids) = (store.clone(), task_ids.clone());
+                tokio::spawn(async move {
+                    for op in 0..313 {
+                        let task_id = &task_ids[(worker * 7 + op) % task_ids.len()];
+                        if op % 3 == 0 {
+                            let status = TaskStatus::Running {
+                                started_at: SystemTime::now(),
+                                worker_id: format!("worker_{}", worker),
+                                process: None,
+                                progress: None,
+                            };
+                            store.update_task_status(task_id, status).await.unwrap();
+                        } else {
+                            store.get_task_status(task_id).await.unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        futures::future::try_join_all(workers).await.unwrap();
+        
+        // Status atual é sempre a última transição do histórico
+        let mut total = 0;
+        for task_id in &task_ids {
+            let history = store.get_status_history(task_id).await.unwrap();
+            assert!(history.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+            if let Some(last) = history.last() {
+                assert_eq!(store.get_task_status(task_id).await.unwrap(), last.status);
+            }
+            total += history.len();
+        }
+        assert_eq!(total, 32 * 105);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_priority_round_trip() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let task = Task::new("p".to_string(), TaskDefinition::command("true"), vec![])
+            .with_priority(Priority::custom(87).unwrap());
+        store.store_task(&task).await.unwrap();
+
+        let row: (i32,) = sqlx::query_as("SELECT priority FROM tasks WHERE id = ?")
+            .bind(task.id.to_string())
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        assert_eq!(row.0, 87);
+        let loaded = store.get_task(&task.id).await.unwrap().unwrap();
+        assert_eq!(loaded.priority, Priority::custom(87).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_id_round_trip() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let parent = TaskId::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let task = Task::new("child".to_string(), TaskDefinition::command("true"), vec![parent]);
+        store.store_task(&task).await.unwrap();
+        store.store_event(&SystemEvent::new(EventType::TaskSubmitted, Some(task.id), serde_json::json!({}))).await.unwrap();
+
+        let loaded = store.get_task(&task.id).await.unwrap().unwrap();
+        assert_eq!(loaded.id, task.id);
+        assert_eq!(loaded.dependencies, vec![parent]);
+        let events = store.get_events(None, None).await.unwrap();
+        assert_eq!(events[0].task_id, Some(task.id));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_typed_task_fields_round_trip() {
+        use crate::hooks::{HookPhase, HookSpec};
+        use crate::sidecar::{ReadinessProbe, SidecarSpec};
+
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let task = Task::new("com-proxy".to_string(), TaskDefinition::command("true"), vec![])
+            .with_sidecar(SidecarSpec::new("proxy", "local-proxy").with_readiness(ReadinessProbe::tcp(8080)))
+            .with_concurrency_group("db")
+            .with_hook(HookSpec::new(HookPhase::Finally, TaskDefinition::command("true")))
+            .with_sla(crate::sla::SlaDeadline::WithinMs(60_000), 75)
+            .with_forced_outcome(crate::forced_outcome::ForcedOutcome::new(crate::forced_outcome::ForcedStatus::Panic));
+        let plain = Task::new("sem-sidecar".to_string(), TaskDefinition::command("true"), vec![]);
+        store.store_tasks(&[task.clone(), plain.clone()]).await.unwrap();
+
+        let loaded = store.get_task(&task.id).await.unwrap().unwrap();
+        assert_eq!(loaded.sidecars.len(), 1);
+        assert_eq!(loaded.sidecars[0].readiness, Some(ReadinessProbe::tcp(8080)));
+        assert_eq!(loaded.concurrency_group.as_deref(), Some("db"));
+        assert_eq!(loaded.hooks.len(), 1);
+        assert_eq!(loaded.sla.as_ref().map(|sla| sla.warn_at), Some(75));
+        assert_eq!(loaded.forced_outcome.map(|outcome| outcome.status), Some(crate::forced_outcome::ForcedStatus::Panic));
+        assert!(loaded.metadata.is_empty());
+        let loaded = store.get_task(&plain.id).await.unwrap().unwrap();
+        assert!(loaded.sidecars.is_empty());
+        assert_eq!(loaded.concurrency_group, None);
+        assert!(loaded.hooks.is_empty());
+        assert_eq!(loaded.sla, None);
+        assert_eq!(loaded.forced_outcome, None);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_search_ranks_name_matches_and_follows_deletes() {
+        let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+        let mut tasks = Vec::new();
+        for i in 0..100 {
+            let mut task = Task::new(format!("job-{}", i), TaskDefinition::command("true"), vec![]);
+            if i % 10 == 0 {
+                task.metadata.insert("project".to_string(), "Invoice reconciliation Q3".to_string());
+            }
+            tasks.push(task);
+        }
+        tasks[42].name = "monthly invoice reconciliation".to_string();
+        tasks[77].name = "Invoice Reconciliation".to_string();
+        for task in &tasks {
+            store.store_task(task).await.unwrap();
+        }
+
+        let found = store.search_tasks("invoice reconciliation", 20).await.unwrap();
+        let ids: Vec<TaskId> = found.iter().map(|task| task.id).collect();
+        assert_eq!(ids.len(), 12);
+        assert_eq!(&ids[..2], &[tasks[77].id, tasks[42].id]);
+        assert!(found[2..].iter().all(|task| task.name.starts_with("job-")));
+
+        store.remove_task(&tasks[42].id).await.unwrap();
+        let found = store.search_tasks("reconciliation", 20).await.unwrap();
+        assert_eq!(found.len(), 11);
+        assert!(found.iter().all(|task| task.id != tasks[42].id));
+
+        // Sintaxe FTS do usuário não vira erro de SQL: operadores viram palavras comuns
+        for hostile in ["reconciliation\" NEAR(", "name:invoice OR *", "^reconciliation - {invoice}"] {
+            assert!(store.search_tasks(hostile, 5).await.is_ok(), "{}", hostile);
+        }
+        assert!(store.search_tasks("reconciliation\" NEAR(", 5).await.unwrap().is_empty());
+        assert!(matches!(store.search_tasks("\"( *", 5).await, Err(TaskMeshError::Configuration(_))));
+    }
+    #[tokio::test]
+    async fn test_sqlite_payload_encryption_and_rewrap() {
+        use crate::encryption::EncryptionConfig;
+        
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("state.db").display());
+        let keys = HashMap::from([
+            ("k1".to_string(), "11".repeat(32)),
+            ("k2".to_string(), "22".repeat(32)),
+        ]);
+        let namespaces = HashMap::from([("acme".to_string(), "k1".to_string())]);
+        let cipher = PayloadCipher::from_config(&EncryptionConfig { keys: keys.clone(), namespaces }).unwrap();
+        let store = SqliteStateStore::new(&url).await.unwrap().with_payload_encryption(Arc::new(cipher));
+        
+        let secret = Task::new(
+            "acme nightly export".to_string(),
+            TaskDefinition::command("curl -H 'Authorization: Bearer s3cr3t-token' https://acme.example"),
+            vec![],
+        )
+        .with_tags(vec!["export".to_string()])
+        .with_metadata("namespace".to_string(), "acme".to_string())
+        .with_metadata("customer".to_string(), "ACME Corp".to_string());
+        let public = Task::new("public export".to_string(), TaskDefinition::command("echo ok"), vec![])
+            .with_metadata("customer".to_string(), "Other Inc".to_string());
+        store.store_task(&secret).await.unwrap();
+        store.store_task(&public).await.unwrap();
+        
+        let raw = |id: TaskId| {
+            sqlx::query_as::<_, (String, String, Option<String>, Option<Vec<u8>>)>(
+                "SELECT definition, metadata, key_id, sealed FROM tasks WHERE id = ?",
+            )
+            .bind(id.to_string())
+            .fetch_one(&store.pool)
+        };
+        let (definition, metadata, key_id, sealed) = raw(secret.id).await.unwrap();
+        assert!(!definition.contains("s3cr3t") && !metadata.contains("ACME"));
+        assert_eq!(key_id.as_deref(), Some("k1"));
+        let sealed = sealed.unwrap();
+        assert!(!sealed.windows(6).any(|w| w == b"s3cr3t") && !sealed.windows(4).any(|w| w == b"ACME"));
+        assert_eq!(raw(public.id).await.unwrap().2, None);
+        
+        // O SQLite guarda `created_at` em segundos: comparar só o conteúdo cifrado
+        let payload = |task: &Task| (task.name.clone(), serde_json::to_value(&task.definition).unwrap(), task.metadata.clone());
+        let loaded = store.get_task(&secret.id).await.unwrap().unwrap();
+        assert_eq!(payload(&loaded), payload(&secret));
+        
+        // Busca e listagens usam só as colunas em claro
+        let found = store.search_tasks("nightly export", 10).await.unwrap();
+        assert_eq!(found.iter().map(|task| task.id).collect::<Vec<_>>(), vec![secret.id]);
+        assert!(store.search_tasks("Corp", 10).await.unwrap().is_empty());
+        assert_eq!(store.search_tasks("Other", 10).await.unwrap().len(), 1);
+        assert_eq!(store.list_tasks().await.unwrap().len(), 2);
+        
+        // Saída da tentativa e argv do manifesto seguem a cifragem da tarefa
+        let started_at = SystemTime::now();
+        let result = TaskResult {
+            exit_code: 0,
+            stdout: "token=s3cr3t-output".to_string(),
+            stderr: String::new(),
+            output_data: None,
+            metrics: ExecutionMetrics::default(),
+            log_ref: None,
+        };
+        let attempt = AttemptRecord::started(secret.id, 1, "worker-0", started_at).finish(
+            TaskStatus::Completed { started_at, completed_at: started_at, result: result.clone() },
+            Some(result),
+        );
+        store.store_attempt(&attempt).await.unwrap();
+        let context = ExecutionContext {
+            worker_id: "worker-0".to_string(),
+            working_directory: "/srv/jobs".to_string(),
+            environment: HashMap::new(),
+            allocated_resources: ResourceAllocation::default(),
+            checkpoint_id: None,
+            progress: None,
+        };
+        let manifest = ExecutionManifest::capture(&secret, 1, &context, crate::executor::TargetPlatform::Unix, "python3");
+        assert!(manifest.argv.iter().any(|arg| arg.contains("s3cr3t")));
+        store.store_manifest(&manifest).await.unwrap();
+        
+        let (status_data, result_column, manifest_column): (String, Option<String>, String) = sqlx::query_as(
+            "SELECT a.status_data, a.result, m.manifest FROM task_attempts a \
+             JOIN execution_manifests m ON m.task_id = a.task_id AND m.attempt = a.attempt WHERE a.task_id = ?",
+        )
+        .bind(secret.id.to_string())
+        .fetch_one(&store.pool)
+        .await
+        .unwrap();
+        assert!(!status_data.contains("s3cr3t") && result_column.is_none() && !manifest_column.contains("s3cr3t"));
+        let sealed_output = |attempts: Vec<AttemptRecord>| match &attempts[..] {
+            [AttemptRecord { status: TaskStatus::Completed { result: status_result, .. }, result: Some(result), .. }] => {
+                (status_result.stdout.clone(), result.stdout.clone())
+            }
+            other => panic!("tentativas: {:?}", other),
+        };
+        let output = ("token=s3cr3t-output".to_string(), "token=s3cr3t-output".to_string());
+        assert_eq!(sealed_output(store.list_attempts(&secret.id).await.unwrap()), output);
+        assert_eq!(store.list_manifests(&secret.id).await.unwrap(), vec![manifest.clone()]);
+        
+        assert_eq!(store.rewrap_namespace("acme", "k2").await.unwrap(), 1);
+        assert_eq!(raw(secret.id).await.unwrap().2.as_deref(), Some("k2"));
+        assert_eq!(store.rewrap_namespace("acme", "k2").await.unwrap(), 0);
+        assert!(store.rewrap_namespace("acme", "k3").await.is_err());
+        
+        // Sem a chave antiga, os dados continuam legíveis
+        drop(store);
+        let rotated = SqliteStateStore::new(&url)
+            .await
+            .unwrap()
+            .with_payload_encryption(Arc::new(PayloadCipher::from_config(&EncryptionConfig {
+                keys: HashMap::from([("k2".to_string(), keys["k2"].clone())]),
+                namespaces: HashMap::from([("acme".to_string(), "k2".to_string())]),
+            }).unwrap()));
+        let loaded = rotated.get_task(&secret.id).await.unwrap().unwrap();
+        assert_eq!(payload(&loaded), payload(&secret));
+        assert_eq!(sealed_output(rotated.list_attempts(&secret.id).await.unwrap()), output);
+        assert_eq!(rotated.list_manifests(&secret.id).await.unwrap(), vec![manifest]);
+        
+        let plain = SqliteStateStore::new(&url).await.unwrap();
+        assert!(matches!(plain.get_task(&secret.id).await, Err(TaskMeshError::Configuration(_))));
+    }
+}
@@ -0,0 +1,349 @@
+//! Suíte de conformidade dos backends de `StateStore`
+//!
+//! Sequências aleatórias de operações são aplicadas a cada backend e a um
+//! modelo de referência em memória; todo resultado observável precisa ser
+//! igual ao do modelo. Em caso de falha o proptest reduz a sequência, e a
+//! mensagem traz as operações no formato de [`replay`], prontas para virar
+//! um teste de regressão.
+//!
+//! Memory e SQLite rodam sempre; Redis exige a feature `redis-tests` e
+//! `REDIS_URL` apontando para um banco descartável.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use proptest::prelude::*;
+
+use crate::state_store::{MemoryStateStore, SqliteStateStore, StateStore};
+use crate::types::*;
+
+/// IDs fixos para que as operações colidam entre si
+const SLOTS: usize = 4;
+const CHECKPOINTS: usize = 3;
+
+fn slot_id(slot: usize) -> TaskId {
+    TaskId::from_u128(0x7a5c_0000_0000_4000_8000_0000_0000_0000 + slot as u128)
+}
+
+fn checkpoint_id(slot: usize) -> String {
+    format!("conformance_{}", slot)
+}
+
+/// Operação sobre o armazenamento
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Store { slot: usize, revision: u8 },
+    Remove { slot: usize },
+    UpdateStatus { slot: usize, status: u8 },
+    GetTask { slot: usize },
+    GetStatus { slot: usize },
+    Checkpoint { slot: usize },
+    Restore { slot: usize },
+    List,
+}
+
+/// Formato replayable: `Op::Store { slot: 0, revision: 1 }`
+impl fmt::Debug for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Store { slot, revision } => write!(f, "Op::Store {{ slot: {}, revision: {} }}", slot, revision),
+            Op::Remove { slot } => write!(f, "Op::Remove {{ slot: {} }}", slot),
+            Op::UpdateStatus { slot, status } => write!(f, "Op::UpdateStatus {{ slot: {}, status: {} }}", slot, status),
+            Op::GetTask { slot } => write!(f, "Op::GetTask {{ slot: {} }}", slot),
+            Op::GetStatus { slot } => write!(f, "Op::GetStatus {{ slot: {} }}", slot),
+            Op::Checkpoint { slot } => write!(f, "Op::Checkpoint {{ slot: {} }}", slot),
+            Op::Restore { slot } => write!(f, "Op::Restore {{ slot: {} }}", slot),
+            Op::List => write!(f, "Op::List"),
+        }
+    }
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..SLOTS, 0..3u8).prop_map(|(slot, revision)| Op::Store { slot, revision }),
+        1 => (0..SLOTS).prop_map(|slot| Op::Remove { slot }),
+        2 => (0..SLOTS, 0..4u8).prop_map(|(slot, status)| Op::UpdateStatus { slot, status }),
+        2 => (0..SLOTS).prop_map(|slot| Op::GetTask { slot }),
+        2 => (0..SLOTS).prop_map(|slot| Op::GetStatus { slot }),
+        1 => (0..CHECKPOINTS).prop_map(|slot| Op::Checkpoint { slot }),
+        1 => (0..CHECKPOINTS).prop_map(|slot| Op::Restore { slot }),
+        1 => Just(Op::List),
+    ]
+}
+
+fn task(slot: usize, revision: u8) -> Task {
+    let mut task = Task::new(
+        format!("slot_{}_rev_{}", slot, revision),
+        TaskDefinition::command(format!("echo {}", revision)),
+        vec![],
+    )
+    .with_priority(Priority::from(revision * 10))
+    .with_tags(vec![format!("rev{}", revision)]);
+    task.id = slot_id(slot);
+    task
+}
+
+fn status(kind: u8) -> TaskStatus {
+    // Instantes fixos: sobrevivem à serialização de todos os backends
+    let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + kind as u64);
+    match kind {
+        0 => TaskStatus::Scheduled,
+        1 => TaskStatus::Running { started_at: at, worker_id: "w".to_string(), process: None, progress: None },
+        2 => TaskStatus::Cancelled { cancelled_at: at, reason: CancelReason::Shutdown, note: None },
+        _ => TaskStatus::Paused { paused_at: at, reason: "conformance".to_string() },
+    }
+}
+
+/// Parte da tarefa que todos os backends preservam
+///
+/// `created_at` fica de fora: o SQLite o armazena com precisão de segundos.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskView {
+    id: TaskId,
+    name: String,
+    priority: Priority,
+    tags: Vec<String>,
+}
+
+impl From<&Task> for TaskView {
+    fn from(task: &Task) -> Self {
+        Self {
+            id: task.id,
+            name: task.name.clone(),
+            priority: task.priority,
+            tags: task.tags.clone(),
+        }
+    }
+}
+
+/// Resultado observável de uma operação
+#[derive(Debug, Clone, PartialEq)]
+pub enum Observation {
+    Done,
+    CheckpointNotFound,
+    Task(Option<TaskView>),
+    Status(TaskStatus),
+    Tasks(Vec<TaskView>),
+}
+
+/// Modelo de referência
+#[derive(Default)]
+struct Model {
+    tasks: BTreeMap<TaskId, TaskView>,
+    statuses: HashMap<TaskId, TaskStatus>,
+    checkpoints: HashMap<String, BTreeMap<TaskId, TaskView>>,
+}
+
+impl Model {
+    fn apply(&mut self, op: Op) -> Observation {
+        match op {
+            Op::Store { slot, revision } => {
+                self.tasks.insert(slot_id(slot), TaskView::from(&task(slot, revision)));
+                Observation::Done
+            }
+            Op::Remove { slot } => {
+                self.tasks.remove(&slot_id(slot));
+                self.statuses.remove(&slot_id(slot));
+                Observation::Done
+            }
+            Op::UpdateStatus { slot, status: kind } => {
+                // Status de tarefas não armazenadas é aceito (o executor depende disso)
+                self.statuses.insert(slot_id(slot), status(kind));
+                Observation::Done
+            }
+            Op::GetTask { slot } => Observation::Task(self.tasks.get(&slot_id(slot)).cloned()),
+            Op::GetStatus { slot } => Observation::Status(
+                self.statuses.get(&slot_id(slot)).cloned().unwrap_or(TaskStatus::Pending),
+            ),
+            Op::Checkpoint { slot } => {
+                self.checkpoints.insert(checkpoint_id(slot), self.tasks.clone());
+                Observation::Done
+            }
+            Op::Restore { slot } => match self.checkpoints.get(&checkpoint_id(slot)) {
+                Some(tasks) => {
+                    self.tasks = tasks.clone();
+                    self.statuses.clear();
+                    Observation::Done
+                }
+                None => Observation::CheckpointNotFound,
+            },
+            Op::List => Observation::Tasks(self.tasks.values().cloned().collect()),
+        }
+    }
+}
+
+/// Aplica uma operação ao backend
+async fn observe(store: &dyn StateStore, op: Op) -> TaskMeshResult<Observation> {
+    Ok(match op {
+        Op::Store { slot, revision } => {
+            store.store_task(&task(slot, revision)).await?;
+            Observation::Done
+        }
+        Op::Remove { slot } => {
+            store.remove_task(&slot_id(slot)).await?;
+            Observation::Done
+        }
+        Op::UpdateStatus { slot, status: kind } => {
+            store.update_task_status(&slot_id(slot), status(kind)).await?;
+            Observation::Done
+        }
+        Op::GetTask { slot } => Observation::Task(store.get_task(&slot_id(slot)).await?.as_ref().map(TaskView::from)),
+        Op::GetStatus { slot } => Observation::Status(store.get_task_status(&slot_id(slot)).await?),
+        Op::Checkpoint { slot } => {
+            store.create_checkpoint(&checkpoint_id(slot)).await?;
+            Observation::Done
+        }
+        Op::Restore { slot } => match store.restore_checkpoint(&checkpoint_id(slot)).await {
+            Ok(()) => Observation::Done,
+            Err(TaskMeshError::CheckpointNotFound(_)) => Observation::CheckpointNotFound,
+            Err(e) => return Err(e),
+        },
+        Op::List => {
+            let mut tasks: Vec<TaskView> = store.list_tasks().await?.iter().map(TaskView::from).collect();
+            tasks.sort_by_key(|task| task.id);
+            Observation::Tasks(tasks)
+        }
+    })
+}
+
+/// Executa a sequência no backend e compara cada passo com o modelo
+///
+/// Retorna a descrição da primeira divergência.
+pub async fn replay(store: &dyn StateStore, ops: &[Op]) -> Result<(), String> {
+    let mut model = Model::default();
+    let describe = |step: usize, detail: String| {
+        format!("passo {}: {}\nsequência (replay): vec!{:?}", step, detail, ops)
+    };
+
+    for (step, &op) in ops.iter().enumerate() {
+        let expected = model.apply(op);
+        let actual = observe(store, op)
+            .await
+            .map_err(|e| describe(step, format!("{:?} falhou: {}", op, e)))?;
+        if actual != expected {
+            return Err(describe(step, format!("{:?}: esperado {:?}, obtido {:?}", op, expected, actual)));
+        }
+
+        // Invariantes pontuais
+        if let Op::Remove { slot } = op {
+            if store.get_task(&slot_id(slot)).await.map_err(|e| describe(step, e.to_string()))?.is_some() {
+                return Err(describe(step, "get_task após remove não é None".to_string()));
+            }
+        }
+    }
+
+    // Status de IDs desconhecidos nunca falha
+    match store.get_task_status(&TaskId::new_v4()).await {
+        Ok(TaskStatus::Pending) => Ok(()),
+        other => Err(describe(ops.len(), format!("status de ID desconhecido: {:?}", other))),
+    }
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn memory_store_conforms(ops in prop::collection::vec(op_strategy(), 1..40)) {
+        let result = runtime().block_on(async {
+            let store = MemoryStateStore::new().await.unwrap();
+            replay(&store, &ops).await
+        });
+        prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+
+    #[test]
+    fn sqlite_store_conforms(ops in prop::collection::vec(op_strategy(), 1..40)) {
+        let result = runtime().block_on(async {
+            let store = SqliteStateStore::new("sqlite::memory:").await.unwrap();
+            replay(&store, &ops).await
+        });
+        prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+}
+
+#[cfg(feature = "redis-tests")]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn redis_store_conforms(ops in prop::collection::vec(op_strategy(), 1..40)) {
+        let result = runtime().block_on(async {
+            let url = std::env::var("REDIS_URL").expect("REDIS_URL é obrigatório com redis-tests");
+            let client = redis::Client::open(url.as_str()).unwrap();
+            let mut conn = client.get_async_connection().await.unwrap();
+            redis::cmd("FLUSHDB").query_async::<_, ()>(&mut conn).await.unwrap();
+
+            let store = crate::state_store::RedisStateStore::new(&url).await.unwrap();
+            replay(&store, &ops).await
+        });
+        prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+}
+
+/// Sequências que já divergiram entre backends
+#[tokio::test]
+async fn test_status_of_unstored_task_and_restore() {
+    let ops = vec![
+        Op::UpdateStatus { slot: 0, status: 1 },
+        Op::Store { slot: 1, revision: 0 },
+        Op::Checkpoint { slot: 0 },
+        Op::UpdateStatus { slot: 1, status: 2 },
+        Op::Store { slot: 1, revision: 2 },
+        Op::Restore { slot: 0 },
+        Op::GetStatus { slot: 1 },
+        Op::GetTask { slot: 1 },
+        Op::Restore { slot: 2 },
+        Op::List,
+    ];
+    replay(&MemoryStateStore::new().await.unwrap(), &ops).await.unwrap();
+    replay(&SqliteStateStore::new("sqlite::memory:").await.unwrap(), &ops).await.unwrap();
+}
+
+/// `restore_checkpoint` reentra em `remove_task`/`store_task`, que tomam a
+/// conexão de novo; segurá-la durante a limpeza travava a restauração
+#[cfg(feature = "redis-tests")]
+#[tokio::test]
+async fn test_redis_restore_checkpoint_does_not_deadlock() {
+    let url = std::env::var("REDIS_URL").expect("REDIS_URL é obrigatório com redis-tests");
+    let client = redis::Client::open(url.as_str()).unwrap();
+    let mut conn = client.get_async_connection().await.unwrap();
+    redis::cmd("FLUSHDB").query_async::<_, ()>(&mut conn).await.unwrap();
+
+    let store = crate::state_store::RedisStateStore::new(&url).await.unwrap();
+    store.store_task(&task(0, 0)).await.unwrap();
+    store.create_checkpoint(&checkpoint_id(0)).await.unwrap();
+    store.store_task(&task(1, 0)).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), store.restore_checkpoint(&checkpoint_id(0)))
+        .await
+        .expect("restore_checkpoint travou")
+        .unwrap();
+    let ids: Vec<TaskId> = store.list_tasks().await.unwrap().iter().map(|task| task.id).collect();
+    assert_eq!(ids, vec![slot_id(0)]);
+}
+
+/// A paginação do Redis lê o índice ordenado, sem carregar todas as tarefas
+#[cfg(feature = "redis-tests")]
+#[tokio::test]
+async fn test_redis_list_tasks_page_follows_id_order() {
+    let url = std::env::var("REDIS_URL").expect("REDIS_URL é obrigatório com redis-tests");
+    let client = redis::Client::open(url.as_str()).unwrap();
+    let mut conn = client.get_async_connection().await.unwrap();
+    redis::cmd("FLUSHDB").query_async::<_, ()>(&mut conn).await.unwrap();
+
+    let store = crate::state_store::RedisStateStore::new(&url).await.unwrap();
+    for slot in (0..SLOTS).rev() {
+        store.store_task(&task(slot, 0)).await.unwrap();
+    }
+    store.remove_task(&slot_id(1)).await.unwrap();
+
+    let first: Vec<TaskId> = store.list_tasks_page(None, 2).await.unwrap().iter().map(|task| task.id).collect();
+    assert_eq!(first, vec![slot_id(0), slot_id(2)]);
+    let rest: Vec<TaskId> = store.list_tasks_page(first.last(), 2).await.unwrap().iter().map(|task| task.id).collect();
+    assert_eq!(rest, vec![slot_id(3)]);
+}
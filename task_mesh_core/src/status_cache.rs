@@ -0,0 +1,259 @@
+//! Cache de leitura do status das tarefas
+//!
+//! Interfaces que consultam o status várias vezes por segundo (ex.: uma UI
+//! a 10Hz por usuário) repetem a mesma leitura no StateStore. O
+//! [`StatusCache`] guarda o último status de cada tarefa por `ttl` (250ms
+//! por padrão) e, acima de `max_entries`, descarta a entrada usada há mais
+//! tempo.
+//!
+//! As escritas de status do executor e dos cancelamentos do core atualizam
+//! a entrada antes de retornar, inclusive quando o executor as acumula no
+//! buffer write-behind: uma transição local nunca é lida desatualizada.
+//! Transições gravadas por outras instâncias (ou pelo watchdog) aparecem em
+//! até `ttl`. Leituras
+//! com [`Consistency::Strong`] vão direto ao StateStore, e restaurar um
+//! checkpoint esvazia o cache.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::types::*;
+use crate::units::DurationSpec;
+
+/// Configuração do cache de status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusCacheConfig {
+    /// Validade de um status lido do StateStore (ex.: `"250ms"`)
+    #[serde(default = "default_ttl")]
+    pub ttl: DurationSpec,
+    /// Tarefas mantidas no cache; 0 desativa
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_ttl() -> DurationSpec {
+    DurationSpec::from_millis(250)
+}
+
+fn default_max_entries() -> usize {
+    10_000
+}
+
+impl Default for StatusCacheConfig {
+    fn default() -> Self {
+        Self { ttl: default_ttl(), max_entries: default_max_entries() }
+    }
+}
+
+/// Consistência de uma leitura de status
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Consistency {
+    /// Aceita um status do cache com até `ttl` de idade
+    #[default]
+    Cached,
+    /// Lê sempre do StateStore
+    Strong,
+}
+
+/// Contadores do cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusCacheStats {
+    /// Leituras atendidas pelo cache
+    pub hits: u64,
+    /// Leituras que foram ao StateStore
+    pub misses: u64,
+    /// Tarefas no cache
+    pub entries: usize,
+}
+
+struct Entry {
+    status: TaskStatus,
+    /// Momento em que o status foi lido ou escrito
+    refreshed_at: Instant,
+    /// Posição em `recency`
+    used: u64,
+    /// Geração da última escrita local (0: só leituras)
+    written: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_task: HashMap<TaskId, Entry>,
+    /// Uso mais recente de cada tarefa, do mais antigo ao mais novo
+    recency: BTreeMap<u64, TaskId>,
+    next_use: u64,
+    /// Geração do último `clear`: leituras iniciadas antes dele são descartadas
+    cleared_at: u64,
+}
+
+impl Entries {
+    fn touch(&mut self, task_id: TaskId) {
+        self.next_use += 1;
+        let used = self.next_use;
+        if let Some(entry) = self.by_task.get_mut(&task_id) {
+            self.recency.remove(&entry.used);
+            entry.used = used;
+            self.recency.insert(used, task_id);
+        }
+    }
+
+    fn insert(&mut self, task_id: TaskId, status: TaskStatus, written: u64, max_entries: usize) {
+        if let Some(previous) = self.by_task.remove(&task_id) {
+            self.recency.remove(&previous.used);
+        }
+        while self.by_task.len() >= max_entries {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.by_task.remove(&oldest);
+        }
+        self.next_use += 1;
+        let entry = Entry { status, refreshed_at: Instant::now(), used: self.next_use, written };
+        self.recency.insert(entry.used, task_id);
+        self.by_task.insert(task_id, entry);
+    }
+}
+
+/// Cache LRU de status com validade, compartilhado por core e executor
+pub struct StatusCache {
+    config: StatusCacheConfig,
+    entries: Mutex<Entries>,
+    /// Gerações de escrita: uma leitura do StateStore iniciada antes de uma
+    /// escrita local não sobrescreve o status escrito
+    generation: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl StatusCache {
+    pub fn new(config: StatusCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(Entries::default()),
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &StatusCacheConfig {
+        &self.config
+    }
+
+    fn enabled(&self) -> bool {
+        self.config.max_entries > 0
+    }
+
+    /// Status da tarefa, do cache se ainda válido ou de `load`
+    pub async fn get_or_load<F>(&self, task_id: &TaskId, load: F) -> TaskMeshResult<TaskStatus>
+    where
+        F: Future<Output = TaskMeshResult<TaskStatus>>,
+    {
+        if !self.enabled() {
+            return load.await;
+        }
+        let ttl = self.config.ttl.as_duration();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            let cached = entries
+                .by_task
+                .get(task_id)
+                .filter(|entry| entry.refreshed_at.elapsed() < ttl)
+                .map(|entry| entry.status.clone());
+            if let Some(status) = cached {
+                entries.touch(*task_id);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(status);
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let started = self.generation.load(Ordering::SeqCst);
+        let status = load.await?;
+        let mut entries = self.entries.lock().unwrap();
+        let overwritten = entries.by_task.get(task_id).is_some_and(|entry| entry.written > started);
+        if !overwritten && started >= entries.cleared_at {
+            entries.insert(*task_id, status.clone(), 0, self.config.max_entries);
+        }
+        Ok(status)
+    }
+
+    /// Registra uma escrita local de status, visível na próxima leitura
+    pub fn record(&self, task_id: &TaskId, status: &TaskStatus) {
+        if !self.enabled() {
+            return;
+        }
+        let written = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.entries.lock().unwrap().insert(*task_id, status.clone(), written, self.config.max_entries);
+    }
+
+    /// Esvazia o cache (ex.: após restaurar um checkpoint)
+    pub fn clear(&self) {
+        let cleared_at = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        *self.entries.lock().unwrap() = Entries { cleared_at, ..Entries::default() };
+    }
+
+    pub fn stats(&self) -> StatusCacheStats {
+        StatusCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().by_task.len(),
+        }
+    }
+}
+
+impl Default for StatusCache {
+    fn default() -> Self {
+        Self::new(StatusCacheConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn cache(ttl: Duration, max_entries: usize) -> StatusCache {
+        StatusCache::new(StatusCacheConfig { ttl: ttl.into(), max_entries })
+    }
+
+    #[tokio::test]
+    async fn test_expires_after_ttl_and_evicts_least_recently_used() {
+        let cache = cache(Duration::from_millis(50), 2);
+        let (a, b, c) = (TaskId::from_u128(1), TaskId::from_u128(2), TaskId::from_u128(3));
+        for task_id in [a, b, a] {
+            cache.get_or_load(&task_id, async { Ok(TaskStatus::Pending) }).await.unwrap();
+        }
+        assert_eq!((cache.stats().hits, cache.stats().misses), (1, 2));
+
+        // `b` é o menos usado: sai para dar lugar a `c`
+        cache.get_or_load(&c, async { Ok(TaskStatus::Scheduled) }).await.unwrap();
+        let loaded = cache.get_or_load(&b, async { Ok(TaskStatus::Scheduled) }).await.unwrap();
+        assert_eq!(loaded, TaskStatus::Scheduled);
+        assert_eq!(cache.stats().entries, 2);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let reloaded = cache.get_or_load(&b, async { Ok(TaskStatus::Pending) }).await.unwrap();
+        assert_eq!(reloaded, TaskStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_read_started_before_a_local_write_does_not_overwrite_it() {
+        let cache = cache(Duration::from_secs(60), 10);
+        let task_id = TaskId::from_u128(1);
+        let stale_read = cache.get_or_load(&task_id, async {
+            cache.record(&task_id, &TaskStatus::Scheduled);
+            Ok(TaskStatus::Pending)
+        });
+        assert_eq!(stale_read.await.unwrap(), TaskStatus::Pending);
+        let status = cache.get_or_load(&task_id, async { Ok(TaskStatus::Pending) }).await.unwrap();
+        assert_eq!(status, TaskStatus::Scheduled);
+
+        cache.clear();
+        let status = cache.get_or_load(&task_id, async { Ok(TaskStatus::Pending) }).await.unwrap();
+        assert_eq!(status, TaskStatus::Pending);
+    }
+}
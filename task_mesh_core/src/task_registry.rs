@@ -1,6 +1,7 @@
 //! Registro centralizado de tarefas com metadados e indexação avançada
 
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::SystemTime;
 use tracing::{debug, error, info, warn};
 
@@ -12,8 +13,8 @@ use crate::TaskMeshResult;
 /// Mantém um índice de todas as tarefas registradas no sistema,
 /// permitindo consultas eficientes por diferentes critérios.
 pub struct TaskRegistry {
-    /// Tarefas indexadas por ID
-    tasks: HashMap<TaskId, Task>,
+    /// Tarefas indexadas por ID (compartilhadas com scheduler e executor)
+    tasks: HashMap<TaskId, SharedTask>,
     
     /// Índice por nome de tarefa
     name_index: HashMap<String, TaskId>,
@@ -126,24 +127,17 @@ impl TaskRegistry {
     }
 
     /// Registra uma nova tarefa
-    pub fn register_task(&mut self, task: Task) -> TaskMeshResult<()> {
+    pub fn register_task(&mut self, task: impl Into<SharedTask>) -> TaskMeshResult<()> {
+        let task: SharedTask = task.into();
         let task_id = task.id;
         
         debug!("Registrando tarefa: {} ({})", task.name, task_id);
 
-        // Verificar se já existe
-        if self.tasks.contains_key(&task_id) {
-            warn!("Tarefa {} já registrada, atualizando", task_id);
-        }
-
-        // Validar dependências
+        // Validar definição e dependências
+        Self::validate_definition(&task)?;
         self.validate_dependencies(&task)?;
 
-        // Atualizar índices
-        self.update_indices(&task);
-
-        // Inserir tarefa
-        self.tasks.insert(task_id, task);
+        self.insert(task);
         
         // Atualizar metadados
         self.metadata.total_tasks = self.tasks.len();
@@ -153,14 +147,125 @@ impl TaskRegistry {
         Ok(())
     }
 
+    /// Registra um lote de tarefas, em ordem
+    ///
+    /// Tarefas do lote podem depender das anteriores. O lote inteiro é
+    /// validado antes da primeira inserção: em caso de erro, nada é registrado.
+    pub fn register_tasks(&mut self, tasks: &[SharedTask]) -> TaskMeshResult<()> {
+        self.validate_batch(tasks.iter().map(Arc::as_ref))?;
+        self.tasks.reserve(tasks.len());
+        self.dependency_index.reserve(tasks.len());
+        for task in tasks {
+            self.insert(task.clone());
+        }
+        self.metadata.total_tasks = self.tasks.len();
+        self.metadata.last_updated = SystemTime::now();
+        debug!("{} tarefas registradas", tasks.len());
+        Ok(())
+    }
+
+    /// Valida um lote como [`Self::register_tasks`], sem registrá-lo
+    ///
+    /// Tarefas do lote podem depender das anteriores.
+    pub fn validate_batch<'a>(&self, tasks: impl IntoIterator<Item = &'a Task>) -> TaskMeshResult<()> {
+        let mut batch = HashSet::new();
+        for task in tasks {
+            Self::validate_definition(task)?;
+            if let Some(dep_id) = task.dependencies.iter()
+                .find(|dep_id| !self.tasks.contains_key(dep_id) && !batch.contains(*dep_id))
+            {
+                return Err(TaskMeshError::TaskNotFound(*dep_id));
+            }
+            if self.would_create_cycle(task) {
+                return Err(TaskMeshError::CircularDependency(task.dependencies.clone()));
+            }
+            batch.insert(task.id);
+        }
+        Ok(())
+    }
+
+    /// Insere uma tarefa já validada e atualiza os índices
+    fn insert(&mut self, task: SharedTask) {
+        if self.tasks.contains_key(&task.id) {
+            warn!("Tarefa {} já registrada, atualizando", task.id);
+        }
+        self.update_indices(&task);
+        self.tasks.insert(task.id, task);
+    }
+
+    /// Registra uma tarefa importada
+    ///
+    /// Ao contrário de `register_task`, não exige que as dependências estejam
+    /// registradas: registros históricos podem referenciar tarefas que não
+    /// foram importadas.
+    pub fn adopt_task(&mut self, task: impl Into<SharedTask>) {
+        let task: SharedTask = task.into();
+        debug!("Adotando tarefa importada: {} ({})", task.name, task.id);
+
+        self.update_indices(&task);
+        self.tasks.insert(task.id, task);
+
+        self.metadata.total_tasks = self.tasks.len();
+        self.metadata.last_updated = SystemTime::now();
+    }
+
+    /// Acrescenta as mesmas dependências a tarefas já registradas
+    ///
+    /// Todas são validadas antes de qualquer alteração: ou todas recebem as
+    /// dependências, ou nenhuma.
+    pub fn add_dependencies(&mut self, task_ids: &[TaskId], dependencies: &[TaskId]) -> TaskMeshResult<()> {
+        let mut updated = Vec::with_capacity(task_ids.len());
+        for task_id in task_ids {
+            let current = self.tasks.get(task_id)
+                .ok_or(TaskMeshError::TaskNotFound(*task_id))?;
+            
+            let mut task = Task::clone(current);
+            for dep in dependencies {
+                if !task.dependencies.contains(dep) {
+                    task.dependencies.push(*dep);
+                }
+            }
+            self.validate_dependencies(&task)?;
+            updated.push(Arc::new(task));
+        }
+        
+        for task in updated {
+            let previous = self.tasks.insert(task.id, task.clone()).expect("tarefa verificada acima");
+            self.remove_from_indices(&previous);
+            self.update_indices(&task);
+        }
+        self.metadata.last_updated = SystemTime::now();
+        Ok(())
+    }
+
+    /// Troca a prioridade de uma tarefa registrada, retornando a nova versão
+    pub fn set_priority(&mut self, task_id: &TaskId, priority: Priority) -> TaskMeshResult<SharedTask> {
+        let current = self.tasks.get(task_id)
+            .ok_or(TaskMeshError::TaskNotFound(*task_id))?;
+        
+        let task = Arc::new(Task { priority, ..Task::clone(current) });
+        let previous = self.tasks.insert(*task_id, task.clone()).expect("tarefa verificada acima");
+        self.remove_from_indices(&previous);
+        self.update_indices(&task);
+        self.metadata.last_updated = SystemTime::now();
+        Ok(task)
+    }
+
     /// Obtém uma tarefa por ID
     pub fn get_task(&self, task_id: &TaskId) -> Option<&Task> {
-        self.tasks.get(task_id)
+        self.tasks.get(task_id).map(Arc::as_ref)
+    }
+
+    /// Obtém a referência compartilhada de uma tarefa, sem copiá-la
+    pub fn get_shared(&self, task_id: &TaskId) -> Option<SharedTask> {
+        self.tasks.get(task_id).cloned()
     }
 
     /// Obtém uma tarefa mutável por ID
+    ///
+    /// Copia a tarefa se ela ainda estiver compartilhada com outro componente.
     pub fn get_task_mut(&mut self, task_id: &TaskId) -> Option<&mut Task> {
-        self.tasks.get_mut(task_id)
+        self.tasks.get_mut(task_id).map(Arc::make_mut)
     }
 
     /// Remove uma tarefa do registro
@@ -178,17 +283,17 @@ impl TaskRegistry {
         self.metadata.last_updated = SystemTime::now();
         
         info!("Tarefa {} removida", task_id);
-        Ok(task)
+        Ok(Arc::try_unwrap(task).unwrap_or_else(|shared| Task::clone(&shared)))
     }
 
     /// Lista todas as tarefas
     pub fn list_tasks(&self) -> TaskMeshResult<Vec<Task>> {
-        Ok(self.tasks.values().cloned().collect())
+        Ok(self.tasks.values().map(|task| Task::clone(task)).collect())
     }
 
     /// Busca tarefas por critérios
     pub fn search_tasks(&self, criteria: &SearchCriteria) -> TaskMeshResult<Vec<&Task>> {
-        let mut results: Vec<&Task> = self.tasks.values().collect();
+        let mut results: Vec<&Task> = self.tasks.values().map(Arc::as_ref).collect();
 
         // Filtrar por nome
         if let Some(pattern) = &criteria.name_pattern {
@@ -242,7 +347,7 @@ impl TaskRegistry {
             .map(|task_ids| {
                 task_ids
                     .iter()
-                    .filter_map(|id| self.tasks.get(id))
+                    .filter_map(|id| self.get_task(id))
                     .collect()
             })
             .unwrap_or_default()
@@ -255,7 +360,7 @@ impl TaskRegistry {
             .map(|task_ids| {
                 task_ids
                     .iter()
-                    .filter_map(|id| self.tasks.get(id))
+                    .filter_map(|id| self.get_task(id))
                     .collect()
             })
             .unwrap_or_default()
@@ -301,6 +406,7 @@ impl TaskRegistry {
     pub fn get_ready_tasks(&self, completed_tasks: &HashSet<TaskId>) -> Vec<&Task> {
         self.tasks
             .values()
+            .map(Arc::as_ref)
             .filter(|task| {
                 task.dependencies
                     .iter()
@@ -420,6 +526,26 @@ impl TaskRegistry {
         }
     }
 
+    /// Valida a definição de uma tarefa (shell e programa existentes)
+    fn validate_definition(task: &Task) -> TaskMeshResult<()> {
+        match &task.definition {
+            TaskDefinition::Command { shell: Some(shell), .. } => {
+                if crate::executor::resolve_program(shell).is_none() {
+                    return Err(TaskMeshError::Configuration(format!(
+                        "Shell '{}' da tarefa {} não encontrado", shell, task.id
+                    )));
+                }
+            }
+            TaskDefinition::Exec { program, .. } if program.is_empty() => {
+                return Err(TaskMeshError::Configuration(format!(
+                    "Programa vazio na tarefa {}", task.id
+                )));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Valida as dependências de uma tarefa
     fn validate_dependencies(&self, task: &Task) -> TaskMeshResult<()> {
         for dep_id in &task.dependencies {
@@ -441,7 +567,7 @@ impl TaskRegistry {
     /// Verifica se adicionar uma tarefa criaria um ciclo
     fn would_create_cycle(&self, task: &Task) -> bool {
         for dep_id in &task.dependencies {
-            if self.has_path_to(dep_id, &task.id) {
+            if self.has_path_to(&task.id, dep_id) {
                 return true;
             }
         }
@@ -533,7 +659,7 @@ mod tests {
     fn create_test_task(name: &str, deps: Vec<TaskId>) -> Task {
         Task::new(
             name.to_string(),
-            TaskDefinition::Command("echo test".to_string()),
+            TaskDefinition::command("echo test"),
             deps,
         )
     }
@@ -549,6 +675,58 @@ mod tests {
         assert!(registry.get_task(&task_id).is_some());
     }
 
+    #[test]
+    fn test_shared_task_is_not_copied() {
+        let mut registry = TaskRegistry::new();
+        let task: SharedTask = Arc::new(create_test_task("shared", vec![]));
+        let task_id = task.id;
+
+        registry.register_task(task.clone()).unwrap();
+        assert!(Arc::ptr_eq(&registry.get_shared(&task_id).unwrap(), &task));
+
+        // Mutação copia apenas a versão do registro
+        registry.get_task_mut(&task_id).unwrap().priority = Priority::custom(99).unwrap();
+        assert_eq!(task.priority, Priority::NORMAL);
+        assert!(!Arc::ptr_eq(&registry.get_shared(&task_id).unwrap(), &task));
+    }
+
+    #[test]
+    fn test_add_dependencies_to_existing_dependents() {
+        let mut registry = TaskRegistry::new();
+        let generator = create_test_task("generator", vec![]);
+        let sink = create_test_task("sink", vec![generator.id]);
+        let child = create_test_task("child", vec![generator.id]);
+        let (generator_id, sink_id, child_id) = (generator.id, sink.id, child.id);
+        for task in [generator, sink, child] {
+            registry.register_task(task).unwrap();
+        }
+
+        // A aresta existente sink -> generator não é um ciclo
+        registry.add_dependencies(&[sink_id], &[child_id]).unwrap();
+        assert!(registry.get_task(&sink_id).unwrap().dependencies.contains(&child_id));
+
+        // generator -> sink fecharia o ciclo; nenhuma das tarefas muda
+        let cycle = registry.add_dependencies(&[child_id, generator_id], &[sink_id]);
+        assert!(matches!(cycle, Err(TaskMeshError::CircularDependency(_))));
+        assert!(!registry.get_task(&child_id).unwrap().dependencies.contains(&sink_id));
+    }
+
+    #[test]
+    fn test_register_tasks_is_all_or_nothing() {
+        let mut registry = TaskRegistry::new();
+        let first = create_test_task("first", vec![]);
+        let second = create_test_task("second", vec![first.id]);
+        let orphan = create_test_task("orphan", vec![TaskId::new_v4()]);
+        let batch: Vec<SharedTask> = vec![first, second, orphan].into_iter().map(Arc::new).collect();
+
+        assert!(registry.validate_batch(batch.iter().map(Arc::as_ref)).is_err());
+        assert!(registry.register_tasks(&batch).is_err());
+        assert!(registry.list_tasks().unwrap().is_empty());
+
+        registry.register_tasks(&batch[..2]).unwrap();
+        assert_eq!(registry.list_tasks().unwrap().len(), 2);
+    }
+
     #[test]
     fn test_search_by_tag() {
         let mut registry = TaskRegistry::new();
@@ -606,11 +784,11 @@ mod tests {
         let mut registry = TaskRegistry::new();
         
         let mut task1 = create_test_task("task1", vec![]);
-        task1.priority = 80;
+        task1.priority = Priority::HIGH;
         task1.tags.push("high-priority".to_string());
         
         let mut task2 = create_test_task("task2", vec![]);
-        task2.priority = 20;
+        task2.priority = Priority::LOW;
         task2.tags.push("low-priority".to_string());
         
         registry.register_task(task1).unwrap();
@@ -621,5 +799,17 @@ mod tests {
         assert_eq!(stats.priority_distribution.len(), 2);
         assert_eq!(stats.popular_tags.len(), 2);
     }
-}
 
+    #[test]
+    fn test_register_rejects_missing_shell() {
+        let mut registry = TaskRegistry::new();
+        let task = Task::new(
+            "shell".to_string(),
+            TaskDefinition::shell_command("definitely-not-a-shell-xyz", "echo hi"),
+            vec![],
+        );
+
+        let result = registry.register_task(task);
+        assert!(matches!(result, Err(TaskMeshError::Configuration(_))));
+    }
+}
@@ -2,18 +2,151 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Identificador único de tarefa
-pub type TaskId = Uuid;
+///
+/// Serializa exatamente como o `Uuid` interno (string no JSON, 16 bytes no
+/// bincode), então estados já persistidos continuam legíveis. Em logs,
+/// prefira [`TaskId::short`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TaskId(Uuid);
+
+impl TaskId {
+    /// Gera um novo identificador aleatório
+    pub fn new_v4() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Identificador determinístico, útil em testes e fixtures
+    pub const fn from_u128(value: u128) -> Self {
+        Self(Uuid::from_u128(value))
+    }
+
+    /// UUID subjacente
+    pub const fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Forma curta (8 primeiros caracteres) para campos de tracing
+    pub fn short(&self) -> String {
+        let mut buffer = Uuid::encode_buffer();
+        self.0.simple().encode_lower(&mut buffer)[..8].to_string()
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for TaskId {
+    type Err = ParseTaskIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s.trim())
+            .map(Self)
+            .map_err(|source| ParseTaskIdError { input: s.to_string(), source })
+    }
+}
+
+impl From<Uuid> for TaskId {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+impl From<TaskId> for Uuid {
+    fn from(id: TaskId) -> Self {
+        id.0
+    }
+}
+
+/// Falha ao interpretar um [`TaskId`] a partir de texto
+#[derive(Debug, thiserror::Error)]
+#[error("ID de tarefa inválido '{input}': {source} (esperado um UUID como 67e55044-10b1-426f-9247-bb680e5fe0c8)")]
+pub struct ParseTaskIdError {
+    input: String,
+    #[source]
+    source: uuid::Error,
+}
 
 /// Identificador de dependência
-pub type DependencyId = Uuid;
+pub type DependencyId = TaskId;
+
+/// Tarefa compartilhada entre registro, scheduler e executor
+///
+/// A tarefa é envolvida uma única vez na submissão; os componentes internos
+/// trocam apenas o ponteiro.
+pub type SharedTask = Arc<Task>;
 
 /// Prioridade de tarefa (0-100, onde 100 é maior prioridade)
-pub type Priority = u8;
+///
+/// No fio (JSON, bincode, coluna `priority`) continua sendo um inteiro, de
+/// modo que estados já persistidos seguem legíveis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "u8", into = "u8")]
+pub struct Priority(u8);
+
+impl Priority {
+    /// Prioridade baixa
+    pub const LOW: Priority = Priority(25);
+    /// Prioridade padrão das tarefas
+    pub const NORMAL: Priority = Priority(50);
+    /// Prioridade alta
+    pub const HIGH: Priority = Priority(75);
+    /// Prioridade máxima
+    pub const CRITICAL: Priority = Priority(100);
+
+    /// Cria uma prioridade arbitrária, rejeitando valores acima de 100
+    pub fn custom(value: u8) -> Result<Self, TaskMeshError> {
+        if value > Self::CRITICAL.0 {
+            return Err(TaskMeshError::Configuration(format!(
+                "Prioridade {} fora do intervalo 0-100", value
+            )));
+        }
+        Ok(Self(value))
+    }
+
+    /// Valor numérico da prioridade
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// Compatibilidade com o antigo `type Priority = u8`
+///
+/// Valores acima de 100 são saturados; código novo deve preferir as
+/// constantes nomeadas ou [`Priority::custom`].
+impl From<u8> for Priority {
+    fn from(value: u8) -> Self {
+        Self(value.min(Self::CRITICAL.0))
+    }
+}
+
+impl From<Priority> for u8 {
+    fn from(priority: Priority) -> Self {
+        priority.0
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
 
 /// Definição de uma tarefa
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +155,9 @@ pub struct Task {
     pub id: TaskId,
     /// Nome da tarefa
     pub name: String,
+    /// Alias legível, único no namespace (ver [`crate::alias`])
+    #[serde(default)]
+    pub alias: Option<String>,
     /// Definição da tarefa
     pub definition: TaskDefinition,
     /// Dependências
@@ -38,6 +174,27 @@ pub struct Task {
     pub max_retries: u32,
     /// Tags para organização
     pub tags: Vec<String>,
+    /// Grupo da tarefa (atribuído por `submit_batch`/`submit_workflow_file`)
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
+    /// Nome legível do grupo
+    #[serde(default)]
+    pub group_name: Option<String>,
+    /// Processos auxiliares com a duração da tarefa (ver [`crate::sidecar`])
+    #[serde(default)]
+    pub sidecars: Vec<crate::sidecar::SidecarSpec>,
+    /// Grupo de concorrência (ver [`crate::concurrency_group`])
+    #[serde(default)]
+    pub concurrency_group: Option<String>,
+    /// Hooks antes e depois da tarefa (ver [`crate::hooks`])
+    #[serde(default)]
+    pub hooks: Vec<crate::hooks::HookSpec>,
+    /// SLA próprio da tarefa (ver [`crate::sla`])
+    #[serde(default)]
+    pub sla: Option<crate::sla::SlaSpec>,
+    /// Resultado simulado no lugar da definição (ver [`crate::forced_outcome`])
+    #[serde(default)]
+    pub forced_outcome: Option<crate::forced_outcome::ForcedOutcome>,
 }
 
 impl Task {
@@ -48,28 +205,42 @@ impl Task {
         dependencies: Vec<TaskId>,
     ) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: TaskId::new_v4(),
             name,
+            alias: None,
             definition,
             dependencies,
-            priority: 50, // Prioridade média por padrão
+            priority: Priority::NORMAL,
             metadata: HashMap::new(),
             created_at: SystemTime::now(),
             timeout: None,
             max_retries: 3,
             tags: Vec::new(),
+            group_id: None,
+            group_name: None,
+            sidecars: Vec::new(),
+            concurrency_group: None,
+            hooks: Vec::new(),
+            sla: None,
+            forced_outcome: None,
         }
     }
 
     /// Define a prioridade da tarefa
-    pub fn with_priority(mut self, priority: Priority) -> Self {
-        self.priority = priority.min(100);
+    pub fn with_priority(mut self, priority: impl Into<Priority>) -> Self {
+        self.priority = priority.into();
+        self
+    }
+
+    /// Define o alias da tarefa (validado na submissão)
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
         self
     }
 
-    /// Define o timeout da tarefa
-    pub fn with_timeout(mut self, timeout: Duration) -> Self {
-        self.timeout = Some(timeout);
+    /// Define o timeout da tarefa (`Duration` ou [`crate::units::DurationSpec`])
+    pub fn with_timeout(mut self, timeout: impl Into<Duration>) -> Self {
+        self.timeout = Some(timeout.into());
         self
     }
 
@@ -102,11 +273,27 @@ impl Task {
 /// Tipos de definição de tarefa
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TaskDefinition {
-    /// Comando shell
-    Command(String),
+    /// Comando executado via shell (`sh -c`/`cmd /C` quando `shell` é `None`)
+    ///
+    /// Aceita também a forma anterior `{"Command": "..."}`.
+    #[serde(deserialize_with = "TaskDefinition::deserialize_command")]
+    Command {
+        command: String,
+        /// Shell alternativo (ex.: bash, zsh, pwsh), validado na submissão
+        shell: Option<String>,
+    },
+    /// Programa executado diretamente, sem shell
+    ///
+    /// Os argumentos chegam literalmente ao `argv` do processo: metacaracteres
+    /// como `;`, `|` ou `$` não são interpretados.
+    Exec {
+        program: String,
+        args: Vec<String>,
+    },
     /// Script Python
     PythonScript {
-        script: String,
+        /// Corpo do script (compartilhado entre cópias da definição)
+        script: Arc<str>,
         args: Vec<String>,
         env: HashMap<String, String>,
     },
@@ -127,6 +314,82 @@ pub enum TaskDefinition {
         tasks: Vec<Task>,
         execution_strategy: WorkflowStrategy,
     },
+    /// Gerador: a saída JSON da fonte é uma lista de tarefas a criar
+    ///
+    /// Ver `generator::GeneratedTaskSpec`. As tarefas geradas são inseridas
+    /// como filhas do gerador por `TaskMeshCore::expand_generator`.
+    Generator {
+        source: Box<TaskDefinition>,
+        /// Máximo de tarefas geradas (padrão `generator::DEFAULT_MAX_FAN_OUT`)
+        #[serde(default)]
+        max_fan_out: Option<usize>,
+    },
+}
+
+impl TaskDefinition {
+    /// Campos de `Command`, aceitando o texto simples gravado antes de `shell`
+    ///
+    /// Formatos binários (bincode) não são autodescritivos e só conhecem a
+    /// forma atual.
+    fn deserialize_command<'de, D>(deserializer: D) -> Result<(String, Option<String>), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Fields {
+            command: String,
+            #[serde(default)]
+            shell: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Fields(Fields),
+            Text(String),
+        }
+
+        if !deserializer.is_human_readable() {
+            let fields = Fields::deserialize(deserializer)?;
+            return Ok((fields.command, fields.shell));
+        }
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Fields(fields) => (fields.command, fields.shell),
+            Stored::Text(command) => (command, None),
+        })
+    }
+
+    /// Comando no shell padrão da plataforma
+    pub fn command(command: impl Into<String>) -> Self {
+        TaskDefinition::Command { command: command.into(), shell: None }
+    }
+
+    /// Comando em um shell específico
+    pub fn shell_command(shell: impl Into<String>, command: impl Into<String>) -> Self {
+        TaskDefinition::Command { command: command.into(), shell: Some(shell.into()) }
+    }
+
+    /// Script Python sem argumentos
+    pub fn python_script(script: impl Into<Arc<str>>) -> Self {
+        TaskDefinition::PythonScript { script: script.into(), args: Vec::new(), env: HashMap::new() }
+    }
+
+    /// Gerador de tarefas a partir de outra definição
+    pub fn generator(source: TaskDefinition, max_fan_out: Option<usize>) -> Self {
+        TaskDefinition::Generator { source: Box::new(source), max_fan_out }
+    }
+
+    /// Programa executado sem shell
+    pub fn exec<I, S>(program: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        TaskDefinition::Exec {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
 }
 
 /// Estratégias de execução de workflow
@@ -151,6 +414,12 @@ pub enum TaskStatus {
     Running {
         started_at: SystemTime,
         worker_id: String,
+        /// Processo da tarefa, para reanexá-la após um reinício
+        #[serde(default)]
+        process: Option<ProcessHandle>,
+        /// Último progresso reportado pela tarefa
+        #[serde(default)]
+        progress: Option<TaskProgress>,
     },
     /// Tarefa concluída com sucesso
     Completed {
@@ -165,10 +434,19 @@ pub enum TaskStatus {
         error: String,
         retry_count: u32,
     },
+    /// Tarefa falhou e aguarda o backoff para a tentativa `attempt`
+    AwaitingRetry {
+        attempt: u32,
+        next_attempt_at: SystemTime,
+    },
     /// Tarefa cancelada
     Cancelled {
         cancelled_at: SystemTime,
-        reason: String,
+        #[serde(deserialize_with = "CancelReason::deserialize_stored")]
+        reason: CancelReason,
+        /// Como a execução terminou após o cancelamento, quando relevante
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        note: Option<CancelNote>,
     },
     /// Tarefa pausada
     Paused {
@@ -193,12 +471,319 @@ impl TaskStatus {
         matches!(self, TaskStatus::Running { .. })
     }
 
+    /// Verifica se a tarefa aguarda despacho (na fila ou em backoff)
+    pub fn is_queued(&self) -> bool {
+        matches!(self, TaskStatus::Pending | TaskStatus::Scheduled | TaskStatus::AwaitingRetry { .. })
+    }
+
     /// Verifica se a tarefa pode ser executada
     pub fn can_execute(&self) -> bool {
         matches!(self, TaskStatus::Scheduled | TaskStatus::Paused { .. })
     }
 }
 
+/// Processo do sistema operacional executando uma tarefa
+///
+/// A impressão digital (instante de início do processo) distingue o processo
+/// original de outro que reutilizou o mesmo PID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessHandle {
+    pub pid: u32,
+    pub start_fingerprint: u64,
+}
+
+/// Progresso reportado por uma tarefa em execução
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskProgress {
+    /// Porcentagem concluída (0 a 100)
+    #[serde(default)]
+    pub percent: Option<u8>,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Tempo restante estimado (segundos)
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
+    pub updated_at: SystemTime,
+}
+
+/// Contagem de tarefas de um grupo por status
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupCounts {
+    pub pending: usize,
+    pub scheduled: usize,
+    pub running: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub awaiting_retry: usize,
+    pub cancelled: usize,
+    pub paused: usize,
+}
+
+impl GroupCounts {
+    /// Contabiliza um status
+    pub fn add(&mut self, status: &TaskStatus) {
+        match status {
+            TaskStatus::Pending => self.pending += 1,
+            TaskStatus::Scheduled => self.scheduled += 1,
+            TaskStatus::Running { .. } => self.running += 1,
+            TaskStatus::Completed { .. } => self.completed += 1,
+            TaskStatus::Failed { .. } => self.failed += 1,
+            TaskStatus::AwaitingRetry { .. } => self.awaiting_retry += 1,
+            TaskStatus::Cancelled { .. } => self.cancelled += 1,
+            TaskStatus::Paused { .. } => self.paused += 1,
+        }
+    }
+
+    /// Total de tarefas contadas
+    pub fn total(&self) -> usize {
+        self.pending
+            + self.scheduled
+            + self.running
+            + self.completed
+            + self.failed
+            + self.awaiting_retry
+            + self.cancelled
+            + self.paused
+    }
+
+    /// Tarefas em estado final
+    pub fn terminal(&self) -> usize {
+        self.completed + self.failed + self.cancelled
+    }
+}
+
+/// Estado agregado de um grupo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupState {
+    /// Alguma tarefa ainda não terminou
+    Running,
+    /// Todas terminaram com sucesso
+    Completed,
+    /// Todas terminaram e ao menos uma falhou (toda dependência é obrigatória:
+    /// a cadeia abaixo dela não executa)
+    Failed,
+    /// Todas terminaram, nenhuma falhou e ao menos uma foi cancelada
+    Cancelled,
+}
+
+impl GroupState {
+    /// Deriva o estado agregado das contagens
+    pub fn from_counts(counts: &GroupCounts) -> Self {
+        if counts.terminal() < counts.total() {
+            GroupState::Running
+        } else if counts.failed > 0 {
+            GroupState::Failed
+        } else if counts.cancelled > 0 {
+            GroupState::Cancelled
+        } else {
+            GroupState::Completed
+        }
+    }
+}
+
+impl fmt::Display for GroupState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GroupState::Running => "running",
+            GroupState::Completed => "completed",
+            GroupState::Failed => "failed",
+            GroupState::Cancelled => "cancelled",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Status agregado de um grupo de tarefas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupStatus {
+    pub group_id: Uuid,
+    pub name: Option<String>,
+    pub counts: GroupCounts,
+    pub state: GroupState,
+}
+
+/// Motivo de um cancelamento
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelReason {
+    /// Pedido explícito (API, CLI)
+    UserRequested,
+    /// Uma dependência foi cancelada ou falhou; `root` é a causa original
+    DependencyFailed { root: TaskId },
+    /// O prazo da tarefa não pode mais ser cumprido
+    DeadlineUnreachable,
+    /// O grupo da tarefa foi cancelado
+    GroupCancelled,
+    /// Encerramento do sistema
+    Shutdown,
+    /// Violação de SLA
+    SlaBreach,
+}
+
+impl CancelReason {
+    /// Aceita também o texto livre gravado antes desta enumeração
+    fn deserialize_stored<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Reason(CancelReason),
+            /// Texto livre antigo: o conteúdo não é usado
+            Text(serde::de::IgnoredAny),
+        }
+
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Reason(reason) => reason,
+            // Até então só o cancelamento manual gravava o status
+            Stored::Text(_) => CancelReason::UserRequested,
+        })
+    }
+}
+
+impl fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelReason::UserRequested => write!(f, "requested by user"),
+            CancelReason::DependencyFailed { root } => write!(f, "dependency failed (root cause {})", root),
+            CancelReason::DeadlineUnreachable => write!(f, "deadline unreachable"),
+            CancelReason::GroupCancelled => write!(f, "group cancelled"),
+            CancelReason::Shutdown => write!(f, "shutdown"),
+            CancelReason::SlaBreach => write!(f, "SLA breach"),
+        }
+    }
+}
+
+/// Observação sobre o término de uma tarefa cancelada
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CancelNote {
+    /// A execução ignorou o cancelamento e foi abortada após o período de tolerância
+    CancelledUncooperatively,
+}
+
+impl fmt::Display for CancelNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelNote::CancelledUncooperatively => write!(f, "aborted after ignoring cancellation"),
+        }
+    }
+}
+
+/// O que acontece com os dependentes de uma tarefa cancelada
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CascadePolicy {
+    /// Cancela toda a subárvore com [`CancelReason::DependencyFailed`]
+    #[default]
+    CancelDependents,
+    /// Mantém os dependentes `Pending`, bloqueados na fila
+    OrphanDependents,
+    /// Marca a subárvore como falha
+    FailDependents,
+}
+
+/// Transição registrada no histórico de status de uma tarefa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusTransition {
+    /// Sequência monotônica da transição
+    pub seq: u64,
+    /// Novo status
+    pub status: TaskStatus,
+    /// Momento da transição
+    pub changed_at: SystemTime,
+}
+
+/// Identificador de uma tentativa: tarefa e número da tentativa (a partir de 1)
+pub type AttemptId = (TaskId, u32);
+
+/// Registro de uma tentativa de execução
+///
+/// Cada tentativa tem status, resultado e métricas próprios; o status da
+/// tarefa continua refletindo a tentativa mais recente.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttemptRecord {
+    pub task_id: TaskId,
+    /// Tentativa (a partir de 1)
+    pub attempt: u32,
+    /// Worker da tentativa (desconhecido em registros migrados)
+    pub worker_id: Option<String>,
+    pub started_at: SystemTime,
+    /// `Running` enquanto executa; depois `Completed`, `Failed` ou `Cancelled`
+    pub status: TaskStatus,
+    /// Resultado do processo, quando houve um (inclui a saída ou o `log_ref`)
+    pub result: Option<TaskResult>,
+    pub metrics: Option<ExecutionMetrics>,
+    /// Hooks executados na tentativa, na ordem (ver [`crate::hooks`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<crate::hooks::HookRun>,
+    /// Marcos do despacho, quando a tentativa passou pela fila do scheduler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dispatch: Option<DispatchTimes>,
+}
+
+/// Marcos entre a submissão e o início de uma tentativa
+///
+/// Ver [`crate::dispatch_latency`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DispatchTimes {
+    /// Submissão da tarefa
+    pub submitted_at: SystemTime,
+    /// Dependências concluídas (ou fim do backoff, em retentativas)
+    pub ready_at: SystemTime,
+    /// Retirada da fila pelo scheduler
+    pub scheduled_at: SystemTime,
+    /// Início no worker
+    pub started_at: SystemTime,
+}
+
+impl DispatchTimes {
+    /// Pronta → retirada da fila
+    pub fn scheduler_lag(&self) -> Duration {
+        self.scheduled_at.duration_since(self.ready_at).unwrap_or_default()
+    }
+
+    /// Retirada da fila → início no worker
+    pub fn dispatch_lag(&self) -> Duration {
+        self.started_at.duration_since(self.scheduled_at).unwrap_or_default()
+    }
+}
+
+impl AttemptRecord {
+    /// Tentativa recém-iniciada
+    pub fn started(task_id: TaskId, attempt: u32, worker_id: &str, started_at: SystemTime) -> Self {
+        Self {
+            task_id,
+            attempt,
+            worker_id: Some(worker_id.to_string()),
+            started_at,
+            status: TaskStatus::Running {
+                started_at,
+                worker_id: worker_id.to_string(),
+                process: None,
+                progress: None,
+            },
+            result: None,
+            metrics: None,
+            hooks: Vec::new(),
+            dispatch: None,
+        }
+    }
+
+    /// Encerra a tentativa com o status final e o resultado, se houver
+    pub fn finish(mut self, status: TaskStatus, result: Option<TaskResult>) -> Self {
+        self.metrics = result.as_ref().map(|result| result.metrics.clone());
+        self.status = status;
+        self.result = result;
+        self
+    }
+
+    pub fn id(&self) -> AttemptId {
+        (self.task_id, self.attempt)
+    }
+}
+
 /// Resultado da execução de uma tarefa
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskResult {
@@ -212,6 +797,18 @@ pub struct TaskResult {
     pub output_data: Option<serde_json::Value>,
     /// Métricas de execução
     pub metrics: ExecutionMetrics,
+    /// Referência ao log em arquivo quando a saída excede o limite inline
+    #[serde(default)]
+    pub log_ref: Option<LogRef>,
+}
+
+/// Referência à saída de uma tarefa armazenada em arquivo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRef {
+    /// Caminho do arquivo de log
+    pub path: String,
+    /// Tamanho do arquivo em bytes
+    pub bytes: u64,
 }
 
 /// Métricas de execução
@@ -241,6 +838,23 @@ impl Default for ExecutionMetrics {
     }
 }
 
+/// Versão persistida de um modelo de aprendizado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelRecord {
+    /// Nome do modelo
+    pub name: String,
+    /// Versão (crescente por nome)
+    pub version: u32,
+    /// Modelo serializado (ver [`crate::learning::encode_model`])
+    pub blob: Vec<u8>,
+    /// Métricas de treino (erro de validação, amostras etc.)
+    pub metrics: HashMap<String, f64>,
+    /// Se é a versão usada nas predições
+    pub promoted: bool,
+    /// Momento do salvamento
+    pub created_at: SystemTime,
+}
+
 /// Contexto de execução para uma tarefa
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionContext {
@@ -254,6 +868,9 @@ pub struct ExecutionContext {
     pub allocated_resources: ResourceAllocation,
     /// Checkpoint ativo
     pub checkpoint_id: Option<String>,
+    /// Canal de progresso da tarefa (funções Rust)
+    #[serde(skip)]
+    pub progress: Option<crate::progress::ProgressReporter>,
 }
 
 /// Alocação de recursos
@@ -267,6 +884,27 @@ pub struct ResourceAllocation {
     pub time_limit: Option<Duration>,
     /// Prioridade de agendamento
     pub scheduling_priority: Priority,
+    /// Nice do processo da tarefa (ver [`crate::process_controls`])
+    #[serde(default)]
+    pub nice: Option<i8>,
+    /// Classe de escalonamento de IO do processo
+    #[serde(default)]
+    pub ionice_class: Option<IoClass>,
+    /// Núcleos em que o processo pode rodar
+    #[serde(default)]
+    pub cpu_affinity: Option<Vec<usize>>,
+}
+
+/// Classe de escalonamento de IO (`ionice`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoClass {
+    /// Acesso prioritário ao disco (exige privilégios)
+    Realtime,
+    /// Padrão do kernel
+    BestEffort,
+    /// Só usa o disco quando ninguém mais usa
+    Idle,
 }
 
 impl Default for ResourceAllocation {
@@ -275,7 +913,10 @@ impl Default for ResourceAllocation {
             cpu_cores: 1.0,
             memory_bytes: 1024 * 1024 * 1024, // 1GB
             time_limit: Some(Duration::from_secs(3600)), // 1 hora
-            scheduling_priority: 50,
+            scheduling_priority: Priority::NORMAL,
+            nice: None,
+            ionice_class: None,
+            cpu_affinity: None,
         }
     }
 }
@@ -309,6 +950,32 @@ impl Default for RetryPolicy {
     }
 }
 
+impl RetryPolicy {
+    /// Indica se uma execução terminada com código de saída deve ser repetida
+    pub fn retries_result(&self, result: &TaskResult) -> bool {
+        result.exit_code != 0
+            && self.retry_conditions.iter().any(|condition| match condition {
+                RetryCondition::ExitCode(codes) => codes.contains(&result.exit_code),
+                RetryCondition::StderrContains(needles) => needles.iter().any(|n| result.stderr.contains(n)),
+                _ => false,
+            })
+    }
+
+    /// Indica se uma execução que terminou em erro deve ser repetida
+    pub fn retries_error(&self, error: &TaskMeshError) -> bool {
+        self.retry_conditions.iter().any(|condition| match (condition, error) {
+            (RetryCondition::Timeout, TaskMeshError::ExecutionTimeout(_)) => true,
+            (RetryCondition::ResourceUnavailable, TaskMeshError::ResourceUnavailable(_)) => true,
+            (RetryCondition::NetworkError, TaskMeshError::Network(_)) => true,
+            (RetryCondition::StderrContains(needles), error) => {
+                let message = error.to_string();
+                needles.iter().any(|n| message.contains(n))
+            }
+            _ => false,
+        })
+    }
+}
+
 /// Estratégias de backoff
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BackoffStrategy {
@@ -330,6 +997,25 @@ pub enum BackoffStrategy {
     },
 }
 
+impl BackoffStrategy {
+    /// Espera antes da tentativa `attempt` (a partir de 1)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let step = attempt.saturating_sub(1);
+        match self {
+            BackoffStrategy::Fixed { delay } => *delay,
+            BackoffStrategy::Linear { initial_delay, increment, max_delay } => {
+                (*initial_delay + increment.saturating_mul(step)).min(*max_delay)
+            }
+            BackoffStrategy::Exponential { initial_delay, max_delay, multiplier } => {
+                let factor = multiplier.max(1.0).powi(step.min(i32::MAX as u32) as i32);
+                Duration::try_from_secs_f64(initial_delay.as_secs_f64() * factor)
+                    .unwrap_or(*max_delay)
+                    .min(*max_delay)
+            }
+        }
+    }
+}
+
 /// Condições para retry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RetryCondition {
@@ -372,50 +1058,224 @@ pub enum TaskMeshError {
     #[error("Recurso indisponível: {0}")]
     ResourceUnavailable(String),
 
+    #[error("Limite de recurso excedido: {0}")]
+    ResourceLimitExceeded(String),
+
     #[error("Timeout na execução da tarefa: {0}")]
     ExecutionTimeout(TaskId),
 
     #[error("Erro na execução da tarefa: {0}")]
     ExecutionError(String),
 
+    #[error("Erro de rede: {0}")]
+    Network(String),
+
     #[error("Checkpoint não encontrado: {0}")]
     CheckpointNotFound(String),
 
+    #[error("Checkpoint corrompido: {0}")]
+    CheckpointCorrupted(String),
+
+    #[error("Formato persistido na versão {found}, mais nova que a suportada ({supported})")]
+    UnsupportedFormatVersion { found: u32, supported: u32 },
+
+    #[error("Fila de tarefas cheia: {pending} pendentes (limite {limit})")]
+    QueueFull { pending: usize, limit: usize },
+
+    #[error("Gerador produziu {generated} tarefas (limite {limit})")]
+    FanOutExceeded { generated: usize, limit: usize },
+
+    #[error("Lote com {tasks} tarefas excede max_workflow_tasks ({limit})")]
+    WorkflowTooLarge { tasks: usize, limit: usize },
+
+    #[error("Alias '{alias}' já pertence à tarefa {existing} no namespace '{namespace}'")]
+    AliasConflict { namespace: String, alias: String, existing: TaskId },
+
+    #[error("Alias não encontrado: {0}")]
+    AliasNotFound(String),
+
+    #[error("Sidecar '{name}' não ficou pronto: {reason}")]
+    SidecarNotReady { name: String, reason: String },
+
+    #[error("Tarefa cancelada sem cooperar; abortada após {0:?}")]
+    CancelledUncooperatively(Duration),
+
+    #[error("Operação não suportada: {0}")]
+    UnsupportedOperation(String),
+
+    #[error("Estado da tarefa não permite a operação: {0}")]
+    InvalidState(String),
+
+    #[error("{0} bloqueante chamado dentro de um contexto async; use o TaskMeshCore com .await")]
+    BlockingInAsyncContext(String),
+
+    #[error("Tempo de espera esgotado: {} de {} tarefas finalizadas", completed.len(), completed.len() + pending.len())]
+    WaitTimeout { completed: Vec<(TaskId, TaskStatus)>, pending: Vec<TaskId> },
+
+    #[error("Tarefa inválida: {0}")]
+    Validation(#[from] crate::validation::ValidationError),
+
     #[error("Erro interno: {0}")]
     Internal(String),
 }
 
+impl TaskMeshError {
+    /// Código HTTP equivalente para superfícies de API
+    pub fn http_status(&self) -> u16 {
+        match self {
+            TaskMeshError::Configuration(_)
+            | TaskMeshError::CircularDependency(_)
+            | TaskMeshError::FanOutExceeded { .. } => 400,
+            TaskMeshError::TaskNotFound(_)
+            | TaskMeshError::CheckpointNotFound(_)
+            | TaskMeshError::AliasNotFound(_) => 404,
+            TaskMeshError::UnsupportedOperation(_) => 405,
+            TaskMeshError::AliasConflict { .. } | TaskMeshError::InvalidState(_) => 409,
+            TaskMeshError::WorkflowTooLarge { .. } => 413,
+            TaskMeshError::Validation(_) => 422,
+            TaskMeshError::QueueFull { .. } => 429,
+            TaskMeshError::Network(_) => 502,
+            TaskMeshError::ResourceUnavailable(_) | TaskMeshError::SidecarNotReady { .. } => 503,
+            TaskMeshError::ExecutionTimeout(_) | TaskMeshError::WaitTimeout { .. } => 504,
+            TaskMeshError::Database(_)
+            | TaskMeshError::Redis(_)
+            | TaskMeshError::Io(_)
+            | TaskMeshError::Serialization(_)
+            | TaskMeshError::ResourceLimitExceeded(_)
+            | TaskMeshError::ExecutionError(_)
+            | TaskMeshError::CheckpointCorrupted(_)
+            | TaskMeshError::UnsupportedFormatVersion { .. }
+            | TaskMeshError::CancelledUncooperatively(_)
+            | TaskMeshError::BlockingInAsyncContext(_)
+            | TaskMeshError::Internal(_) => 500,
+        }
+    }
+
+    /// Código estável do erro (ver [`crate::problem`])
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            TaskMeshError::Configuration(_) => "CONFIGURATION_ERROR",
+            TaskMeshError::Database(_) => "DATABASE_ERROR",
+            TaskMeshError::Redis(_) => "REDIS_ERROR",
+            TaskMeshError::Io(_) => "IO_ERROR",
+            TaskMeshError::Serialization(_) => "SERIALIZATION_ERROR",
+            TaskMeshError::TaskNotFound(_) => "TASK_NOT_FOUND",
+            TaskMeshError::CircularDependency(_) => "CIRCULAR_DEPENDENCY",
+            TaskMeshError::ResourceUnavailable(_) => "RESOURCE_UNAVAILABLE",
+            TaskMeshError::ResourceLimitExceeded(_) => "RESOURCE_LIMIT_EXCEEDED",
+            TaskMeshError::ExecutionTimeout(_) => "EXECUTION_TIMEOUT",
+            TaskMeshError::ExecutionError(_) => "EXECUTION_ERROR",
+            TaskMeshError::Network(_) => "NETWORK_ERROR",
+            TaskMeshError::CheckpointNotFound(_) => "CHECKPOINT_NOT_FOUND",
+            TaskMeshError::CheckpointCorrupted(_) => "CHECKPOINT_CORRUPTED",
+            TaskMeshError::UnsupportedFormatVersion { .. } => "UNSUPPORTED_FORMAT_VERSION",
+            TaskMeshError::QueueFull { .. } => "QUEUE_FULL",
+            TaskMeshError::FanOutExceeded { .. } => "FAN_OUT_EXCEEDED",
+            TaskMeshError::WorkflowTooLarge { .. } => "WORKFLOW_TOO_LARGE",
+            TaskMeshError::AliasConflict { .. } => "ALIAS_CONFLICT",
+            TaskMeshError::AliasNotFound(_) => "ALIAS_NOT_FOUND",
+            TaskMeshError::SidecarNotReady { .. } => "SIDECAR_NOT_READY",
+            TaskMeshError::CancelledUncooperatively(_) => "CANCELLED_UNCOOPERATIVELY",
+            TaskMeshError::UnsupportedOperation(_) => "UNSUPPORTED_OPERATION",
+            TaskMeshError::InvalidState(_) => "INVALID_STATE",
+            TaskMeshError::BlockingInAsyncContext(_) => "BLOCKING_IN_ASYNC_CONTEXT",
+            TaskMeshError::WaitTimeout { .. } => "WAIT_TIMEOUT",
+            TaskMeshError::Validation(_) => "VALIDATION_ERROR",
+            TaskMeshError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+}
+
 /// Resultado padrão do TaskMesh
 pub type TaskMeshResult<T> = Result<T, TaskMeshError>;
 
-/// Evento do sistema
+pub use system_events::{EventSeverity, EventType};
+
+/// Evento do sistema (formato compartilhado com o orchestrator, ver [`system_events`])
+pub type SystemEvent = system_events::SystemEvent<TaskId>;
+
+/// Filtros para consulta de eventos
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SystemEvent {
-    /// Timestamp do evento
-    pub timestamp: SystemTime,
-    /// Tipo do evento
-    pub event_type: EventType,
-    /// ID da tarefa relacionada (se aplicável)
+pub struct EventQuery {
+    /// Filtrar por tarefa
     pub task_id: Option<TaskId>,
-    /// Dados do evento
-    pub data: serde_json::Value,
+    /// Filtrar por tipos (vazio = todos)
+    pub event_types: Vec<EventType>,
+    /// Intervalo de tempo (início, fim), ambos inclusivos
+    pub time_range: (Option<SystemTime>, Option<SystemTime>),
+    /// Número máximo de eventos por página
+    pub limit: usize,
+    /// Cursor opaco retornado pela página anterior
+    pub cursor: Option<String>,
+}
+
+impl Default for EventQuery {
+    fn default() -> Self {
+        Self {
+            task_id: None,
+            event_types: Vec::new(),
+            time_range: (None, None),
+            limit: 100,
+            cursor: None,
+        }
+    }
+}
+
+impl EventQuery {
+    /// Verifica se um evento atende aos filtros (exceto paginação)
+    pub fn matches(&self, event: &SystemEvent) -> bool {
+        if self.task_id.is_some() && event.task_id != self.task_id {
+            return false;
+        }
+        if !self.event_types.is_empty() && !self.event_types.contains(&event.event_type) {
+            return false;
+        }
+        if let Some(start) = self.time_range.0 {
+            if event.timestamp < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.time_range.1 {
+            if event.timestamp > end {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Página de resultados de uma consulta de eventos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventPage {
+    /// Eventos da página, em ordem de inserção
+    pub events: Vec<SystemEvent>,
+    /// Cursor para a próxima página (None quando não há mais eventos)
+    pub next_cursor: Option<String>,
 }
 
-/// Tipos de eventos do sistema
+/// Item da linha do tempo de uma tarefa
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum EventType {
-    TaskSubmitted,
-    TaskScheduled,
-    TaskStarted,
-    TaskCompleted,
-    TaskFailed,
-    TaskCancelled,
-    CheckpointCreated,
-    CheckpointRestored,
-    WorkerStarted,
-    WorkerStopped,
-    SystemStarted,
-    SystemStopped,
+pub enum TimelineItem {
+    /// Transição de status
+    Status(StatusTransition),
+    /// Evento do sistema
+    Event(SystemEvent),
+    /// Manifesto de reprodutibilidade de uma tentativa
+    Manifest(Box<crate::manifest::ExecutionManifest>),
+    /// Marcos do despacho de uma tentativa
+    Dispatch { attempt: u32, times: DispatchTimes },
+}
+
+impl TimelineItem {
+    /// Momento em que o item ocorreu
+    pub fn timestamp(&self) -> SystemTime {
+        match self {
+            TimelineItem::Status(transition) => transition.changed_at,
+            TimelineItem::Event(event) => event.timestamp,
+            TimelineItem::Manifest(manifest) => manifest.captured_at,
+            TimelineItem::Dispatch { times, .. } => times.started_at,
+        }
+    }
 }
 
 /// Informações de um worker
@@ -481,8 +1341,12 @@ impl fmt::Display for TaskStatus {
         match self {
             TaskStatus::Pending => write!(f, "Pending"),
             TaskStatus::Scheduled => write!(f, "Scheduled"),
-            TaskStatus::Running { started_at, worker_id } => {
-                write!(f, "Running on {} since {:?}", worker_id, started_at)
+            TaskStatus::Running { started_at, worker_id, progress, .. } => {
+                write!(f, "Running on {} since {:?}", worker_id, started_at)?;
+                match progress {
+                    Some(progress) => write!(f, " ({})", progress),
+                    None => Ok(()),
+                }
             }
             TaskStatus::Completed { completed_at, .. } => {
                 write!(f, "Completed at {:?}", completed_at)
@@ -490,8 +1354,15 @@ impl fmt::Display for TaskStatus {
             TaskStatus::Failed { error, retry_count, .. } => {
                 write!(f, "Failed ({} retries): {}", retry_count, error)
             }
-            TaskStatus::Cancelled { reason, .. } => {
-                write!(f, "Cancelled: {}", reason)
+            TaskStatus::AwaitingRetry { attempt, next_attempt_at } => {
+                write!(f, "Awaiting retry {} at {:?}", attempt, next_attempt_at)
+            }
+            TaskStatus::Cancelled { reason, note, .. } => {
+                write!(f, "Cancelled: {}", reason)?;
+                match note {
+                    Some(note) => write!(f, " ({})", note),
+                    None => Ok(()),
+                }
             }
             TaskStatus::Paused { reason, .. } => {
                 write!(f, "Paused: {}", reason)
@@ -500,6 +1371,22 @@ impl fmt::Display for TaskStatus {
     }
 }
 
+impl fmt::Display for TaskProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(percent) = self.percent {
+            parts.push(format!("{}%", percent));
+        }
+        if let Some(message) = &self.message {
+            parts.push(message.clone());
+        }
+        if let Some(eta) = self.eta_secs {
+            parts.push(format!("ETA {}s", eta));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
 impl fmt::Display for WorkerStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -511,3 +1398,68 @@ impl fmt::Display for WorkerStatus {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_validation_and_clamping() {
+        assert_eq!(Priority::custom(100).unwrap(), Priority::CRITICAL);
+        assert!(matches!(Priority::custom(101), Err(TaskMeshError::Configuration(_))));
+        assert_eq!(Priority::from(250), Priority::CRITICAL);
+        assert!(Priority::LOW < Priority::NORMAL && Priority::HIGH < Priority::CRITICAL);
+
+        let task = Task::new("t".to_string(), TaskDefinition::command("true"), vec![]).with_priority(200u8);
+        assert_eq!(task.priority, Priority::CRITICAL);
+
+        // Formato de fio inalterado: inteiro simples, saturado na leitura
+        assert_eq!(serde_json::to_string(&Priority::HIGH).unwrap(), "75");
+        assert_eq!(serde_json::from_str::<Priority>("150").unwrap(), Priority::CRITICAL);
+    }
+
+    #[test]
+    fn test_command_accepts_legacy_string_form() {
+        let legacy: TaskDefinition = serde_json::from_str(r#"{"Command":"echo hi"}"#).unwrap();
+        assert!(matches!(&legacy, TaskDefinition::Command { command, shell: None } if command == "echo hi"));
+
+        let definition = TaskDefinition::shell_command("bash", "set -o pipefail; true");
+        let json = serde_json::to_string(&definition).unwrap();
+        assert!(matches!(
+            serde_json::from_str(&json).unwrap(),
+            TaskDefinition::Command { command, shell: Some(shell) } if command == "set -o pipefail; true" && shell == "bash"
+        ));
+        let without_shell: TaskDefinition = serde_json::from_str(r#"{"Command":{"command":"true"}}"#).unwrap();
+        assert!(matches!(without_shell, TaskDefinition::Command { shell: None, .. }));
+
+        let bytes = bincode::serialize(&definition).unwrap();
+        assert!(matches!(bincode::deserialize(&bytes).unwrap(), TaskDefinition::Command { shell: Some(_), .. }));
+    }
+
+    #[test]
+    fn test_task_id_parsing_and_wire_format() {
+        let id: TaskId = "67e55044-10b1-426f-9247-bb680e5fe0c8".parse().unwrap();
+        assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        assert_eq!(id.short(), "67e55044");
+
+        let error = "67e55044-nope".parse::<TaskId>().unwrap_err().to_string();
+        assert!(error.contains("'67e55044-nope'") && error.contains("esperado um UUID"), "{}", error);
+
+        // Mesmo formato do Uuid puro em JSON e bincode
+        let uuid: Uuid = id.into();
+        assert_eq!(serde_json::to_string(&id).unwrap(), serde_json::to_string(&uuid).unwrap());
+        assert_eq!(bincode::serialize(&id).unwrap(), bincode::serialize(&uuid).unwrap());
+        assert_eq!(bincode::deserialize::<TaskId>(&bincode::serialize(&uuid).unwrap()).unwrap(), id);
+    }
+
+    #[test]
+    fn test_system_event_round_trips_with_task_id() {
+        // TaskId não implementa Default: o campo opcional não pode exigi-lo
+        let event = SystemEvent::new(EventType::TaskStarted, Some(TaskId::from_u128(7)), serde_json::json!({ "k": 1 }))
+            .with_source("executor");
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<SystemEvent>(&json).unwrap(), event);
+
+        let without_task = serde_json::json!({ "timestamp": event.timestamp, "event_type": "TaskStarted" });
+        assert_eq!(serde_json::from_value::<SystemEvent>(without_task).unwrap().task_id, None);
+    }
+}
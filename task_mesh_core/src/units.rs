@@ -0,0 +1,316 @@
+//! Durações e tamanhos legíveis em configurações e na CLI
+//!
+//! [`DurationSpec`] aceita `"30s"`, `"10m"`, `"2h30m"` ou `"1.5h"` e
+//! [`ByteSize`] aceita `"512MiB"`, `"2GB"` ou `"1.5GiB"`. Na
+//! desserialização, números continuam aceitos como no formato anterior
+//! (segundos e bytes, respectivamente); a serialização grava a forma legível.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::*;
+
+const DURATION_UNITS: &str = "ns, us, ms, s, m, h, d, w";
+const BYTE_UNITS: &str = "B, KB, MB, GB, TB, KiB, MiB, GiB, TiB";
+
+/// Unidades de duração em nanossegundos, da maior para a menor
+const DURATION_SCALE: [(&str, u128); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// Duração em configurações: `"30s"`, `"2h30m"`, `"1.5h"` ou segundos
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DurationSpec(pub Duration);
+
+impl DurationSpec {
+    pub const fn from_secs(secs: u64) -> Self {
+        Self(Duration::from_secs(secs))
+    }
+
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(Duration::from_millis(millis))
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl From<Duration> for DurationSpec {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<DurationSpec> for Duration {
+    fn from(spec: DurationSpec) -> Self {
+        spec.0
+    }
+}
+
+impl FromStr for DurationSpec {
+    type Err = TaskMeshError;
+
+    /// Um ou mais componentes `<número><unidade>`; número sem unidade são segundos
+    fn from_str(value: &str) -> TaskMeshResult<Self> {
+        let invalid = || {
+            TaskMeshError::Configuration(format!(
+                "duração inválida: '{}' (unidades aceitas: {}; ex.: 30s, 2h30m, 1.5h)",
+                value, DURATION_UNITS
+            ))
+        };
+        let text = value.trim();
+        if let Ok(secs) = text.parse::<f64>() {
+            return seconds(secs).ok_or_else(invalid);
+        }
+
+        let mut rest = text;
+        let mut nanos = 0f64;
+        while !rest.is_empty() {
+            let (amount, unit, tail) = split_component(rest).ok_or_else(invalid)?;
+            let scale = match unit {
+                "w" => 7 * 86_400_000_000_000u64,
+                "min" => 60_000_000_000,
+                "sec" => 1_000_000_000,
+                "µs" => 1_000,
+                unit => DURATION_SCALE.iter().find(|(name, _)| *name == unit).ok_or_else(invalid)?.1 as u64,
+            };
+            nanos += amount * scale as f64;
+            rest = tail.trim_start();
+        }
+        if text.is_empty() || nanos > u64::MAX as f64 {
+            return Err(invalid());
+        }
+        Ok(Self(Duration::from_nanos(nanos.round() as u64)))
+    }
+}
+
+fn seconds(secs: f64) -> Option<DurationSpec> {
+    (secs.is_finite() && secs >= 0.0 && secs <= u64::MAX as f64).then(|| DurationSpec(Duration::from_secs_f64(secs)))
+}
+
+/// Separa `"1.5h30m"` em `(1.5, "h", "30m")`
+fn split_component(text: &str) -> Option<(f64, &str, &str)> {
+    let number_len = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, rest) = text.split_at(number_len);
+    let rest = rest.trim_start();
+    let unit_len = rest.find(|c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+    let (unit, tail) = rest.split_at(unit_len);
+    let amount: f64 = number.parse().ok().filter(|amount: &f64| amount.is_finite())?;
+    (!unit.is_empty()).then_some((amount, unit, tail))
+}
+
+impl fmt::Display for DurationSpec {
+    /// Forma exata mais curta, ex.: `2h30m`, `1s500ms`, `0s`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
+        for (unit, scale) in DURATION_SCALE {
+            if nanos >= scale {
+                write!(f, "{}{}", nanos / scale, unit)?;
+                nanos %= scale;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tamanho em bytes: `"512MiB"`, `"2GB"`, `"1.5GiB"` ou bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub const KIB: u64 = 1024;
+    pub const MIB: u64 = 1024 * Self::KIB;
+    pub const GIB: u64 = 1024 * Self::MIB;
+    pub const TIB: u64 = 1024 * Self::GIB;
+
+    pub const fn mib(mib: u64) -> Self {
+        Self(mib * Self::MIB)
+    }
+
+    pub const fn gib(gib: u64) -> Self {
+        Self(gib * Self::GIB)
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = TaskMeshError;
+
+    /// `<número>[unidade]`; prefixos `K`/`M`/`G`/`T` são decimais, `Ki`/`Mi`/`Gi`/`Ti` binários
+    fn from_str(value: &str) -> TaskMeshResult<Self> {
+        let invalid = || {
+            TaskMeshError::Configuration(format!(
+                "tamanho inválido: '{}' (unidades aceitas: {}; ex.: 512MiB, 2GB)",
+                value, BYTE_UNITS
+            ))
+        };
+        let text = value.trim();
+        let number_len = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+        let (number, unit) = text.split_at(number_len);
+        let amount: f64 = number.parse().map_err(|_| invalid())?;
+        let scale = match unit.trim().to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "kb" => 1_000,
+            "m" | "mb" => 1_000_000,
+            "g" | "gb" => 1_000_000_000,
+            "t" | "tb" => 1_000_000_000_000,
+            "ki" | "kib" => Self::KIB,
+            "mi" | "mib" => Self::MIB,
+            "gi" | "gib" => Self::GIB,
+            "ti" | "tib" => Self::TIB,
+            _ => return Err(invalid()),
+        };
+        let bytes = amount * scale as f64;
+        if !bytes.is_finite() || bytes > u64::MAX as f64 {
+            return Err(invalid());
+        }
+        Ok(Self(bytes.round() as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Maior unidade exata, binária antes de decimal, ex.: `512MiB`, `2GB`, `1500B`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = [
+            ("TiB", Self::TIB),
+            ("GiB", Self::GIB),
+            ("MiB", Self::MIB),
+            ("KiB", Self::KIB),
+            ("TB", 1_000_000_000_000),
+            ("GB", 1_000_000_000),
+            ("MB", 1_000_000),
+            ("KB", 1_000),
+        ];
+        match units.iter().find(|(_, scale)| self.0 != 0 && self.0 % scale == 0) {
+            Some((unit, scale)) => write!(f, "{}{}", self.0 / scale, unit),
+            None => write!(f, "{}B", self.0),
+        }
+    }
+}
+
+/// Forma numérica (formato anterior) ou legível
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Integer(u64),
+    Float(f64),
+    Text(String),
+}
+
+impl Serialize for DurationSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DurationSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Formatos binários (bincode) não aceitam a forma sem tag; lá só o texto é gravado
+        if !deserializer.is_human_readable() {
+            return String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom);
+        }
+        let invalid = |value: f64| serde::de::Error::custom(format!("duração inválida: {} segundos", value));
+        match RawValue::deserialize(deserializer)? {
+            RawValue::Integer(secs) => Ok(Self::from_secs(secs)),
+            RawValue::Float(secs) => seconds(secs).ok_or_else(|| invalid(secs)),
+            RawValue::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match RawValue::deserialize(deserializer)? {
+            RawValue::Integer(bytes) => Ok(Self(bytes)),
+            RawValue::Float(bytes) => Err(serde::de::Error::custom(format!("tamanho inválido: {} bytes", bytes))),
+            RawValue::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_durations_and_sizes() {
+        let duration = |text: &str| text.parse::<DurationSpec>().map(Duration::from);
+        assert_eq!(duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(duration("2h30m").unwrap(), Duration::from_secs(9_000));
+        assert_eq!(duration("1.5h").unwrap(), Duration::from_secs(5_400));
+        assert_eq!(duration(" 1h 0.5m 250ms ").unwrap(), Duration::from_millis(3_630_250));
+        assert_eq!(duration("45").unwrap(), Duration::from_secs(45));
+        for bad in ["", "h", "-3s", "10 parsecs", "1..5h", "30s!"] {
+            let error = duration(bad).unwrap_err().to_string();
+            assert!(error.contains(&format!("'{}'", bad)) && error.contains("ms, s, m, h"), "{}", error);
+        }
+
+        let size = |text: &str| text.parse::<ByteSize>().map(u64::from);
+        assert_eq!(size("512MiB").unwrap(), 512 * 1024 * 1024);
+        assert_eq!(size("2GB").unwrap(), 2_000_000_000);
+        assert_eq!(size("1.5 GiB").unwrap(), 3 * 512 * 1024 * 1024);
+        assert_eq!(size("4096").unwrap(), 4096);
+        let error = size("12 parsecs").unwrap_err().to_string();
+        assert!(error.contains("'12 parsecs'") && error.contains("MiB"), "{}", error);
+    }
+
+    #[test]
+    fn test_round_trip_and_numeric_backward_compat() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Config {
+            interval: DurationSpec,
+            limit: ByteSize,
+        }
+
+        for (interval, text) in [(Duration::from_secs(9_000), "2h30m"), (Duration::from_millis(1_500), "1s500ms")] {
+            let config = Config { interval: interval.into(), limit: ByteSize::mib(512) };
+            let json = serde_json::to_value(&config).unwrap();
+            assert_eq!(json, serde_json::json!({ "interval": text, "limit": "512MiB" }));
+            assert_eq!(serde_json::from_value::<Config>(json).unwrap(), config);
+        }
+        assert_eq!(ByteSize(1_500).to_string(), "1500B");
+        assert_eq!(ByteSize(3_000_000).to_string(), "3MB");
+        assert_eq!(DurationSpec::default().to_string(), "0s");
+
+        let legacy: Config = serde_json::from_str(r#"{ "interval": 30, "limit": 1048576 }"#).unwrap();
+        assert_eq!(legacy, Config { interval: DurationSpec::from_secs(30), limit: ByteSize::mib(1) });
+        let fractional: Config = serde_json::from_str(r#"{ "interval": 0.25, "limit": 0 }"#).unwrap();
+        assert_eq!(fractional.interval, DurationSpec::from_millis(250));
+        let error = serde_json::from_str::<Config>(r#"{ "interval": "1.5h", "limit": "1.5h" }"#).unwrap_err();
+        assert!(error.to_string().contains("'1.5h'"), "{}", error);
+    }
+}
@@ -0,0 +1,189 @@
+//! Validação e normalização de tags e metadados na submissão
+//!
+//! Tags são normalizadas (sem espaços nas pontas, em minúsculas, sem
+//! repetição) antes da validação, de modo que `Tag:Foo` e `tag:foo` são a
+//! mesma tag. Os limites de [`MetadataLimits`] valem em bytes; todas as
+//! violações de uma submissão são reunidas em um único [`ValidationError`].
+
+use std::collections::HashSet;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+use crate::types::Task;
+
+/// Limites de tags e metadados de uma tarefa
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataLimits {
+    #[serde(default = "default_max_tags")]
+    pub max_tags: usize,
+    #[serde(default = "default_max_tag_len")]
+    pub max_tag_len: usize,
+    #[serde(default = "default_max_key_len")]
+    pub max_key_len: usize,
+    #[serde(default = "default_max_value_len")]
+    pub max_value_len: usize,
+    /// Soma de chaves e valores
+    #[serde(default = "default_max_metadata_bytes")]
+    pub max_metadata_bytes: usize,
+}
+
+fn default_max_tags() -> usize {
+    64
+}
+
+fn default_max_tag_len() -> usize {
+    128
+}
+
+fn default_max_key_len() -> usize {
+    64
+}
+
+fn default_max_value_len() -> usize {
+    4 * 1024
+}
+
+fn default_max_metadata_bytes() -> usize {
+    64 * 1024
+}
+
+impl Default for MetadataLimits {
+    fn default() -> Self {
+        Self {
+            max_tags: default_max_tags(),
+            max_tag_len: default_max_tag_len(),
+            max_key_len: default_max_key_len(),
+            max_value_len: default_max_value_len(),
+            max_metadata_bytes: default_max_metadata_bytes(),
+        }
+    }
+}
+
+/// Violação de um limite, com o caminho do campo (`tags[3]`, `metadata.owner`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    pub field: String,
+    pub message: String,
+}
+
+/// Todas as violações encontradas em uma submissão
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let violations: Vec<String> = self
+            .violations
+            .iter()
+            .map(|violation| format!("{}: {}", violation.field, violation.message))
+            .collect();
+        write!(f, "{}", violations.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Forma normalizada de uma tag
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Normaliza as tags, descartando vazias e repetidas (mantém a ordem)
+pub fn normalize_tags(tags: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    let normalized = tags
+        .drain(..)
+        .map(|tag| normalize_tag(&tag))
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.clone()))
+        .collect();
+    *tags = normalized;
+}
+
+impl MetadataLimits {
+    /// Normaliza as tags da tarefa e verifica os limites
+    ///
+    /// Os campos das violações recebem `prefix` (ex.: `tasks[2].`).
+    pub fn validate(&self, task: &mut Task, prefix: &str) -> Vec<Violation> {
+        normalize_tags(&mut task.tags);
+
+        let mut violations = Vec::new();
+        let mut violation = |field: String, message: String| {
+            violations.push(Violation { field: format!("{}{}", prefix, field), message });
+        };
+        if task.tags.len() > self.max_tags {
+            violation("tags".to_string(), format!("{} tags (limite {})", task.tags.len(), self.max_tags));
+        }
+        for (index, tag) in task.tags.iter().enumerate() {
+            if tag.len() > self.max_tag_len {
+                violation(format!("tags[{}]", index), format!("{} bytes (limite {})", tag.len(), self.max_tag_len));
+            }
+        }
+
+        let mut keys: Vec<&String> = task.metadata.keys().collect();
+        keys.sort();
+        for key in keys {
+            if key.len() > self.max_key_len {
+                violation(format!("metadata.{}", key), format!("chave com {} bytes (limite {})", key.len(), self.max_key_len));
+            }
+            let value = &task.metadata[key];
+            if value.len() > self.max_value_len {
+                violation(format!("metadata.{}", key), format!("valor com {} bytes (limite {})", value.len(), self.max_value_len));
+            }
+        }
+        let total: usize = task.metadata.iter().map(|(key, value)| key.len() + value.len()).sum();
+        if total > self.max_metadata_bytes {
+            violation("metadata".to_string(), format!("{} bytes no total (limite {})", total, self.max_metadata_bytes));
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaskDefinition;
+
+    fn task_with(tags: &[&str], metadata: &[(&str, String)]) -> Task {
+        let mut task = Task::new("t".to_string(), TaskDefinition::command("true"), vec![]);
+        task.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        task.metadata = metadata.iter().map(|(key, value)| (key.to_string(), value.clone())).collect();
+        task
+    }
+
+    #[test]
+    fn test_tags_normalized_before_limits() {
+        let limits = MetadataLimits { max_tags: 2, ..MetadataLimits::default() };
+        let mut task = task_with(&[" Tag:Foo ", "tag:foo", "", "ETL"], &[]);
+        assert!(limits.validate(&mut task, "").is_empty());
+        assert_eq!(task.tags, vec!["tag:foo".to_string(), "etl".to_string()]);
+    }
+
+    #[test]
+    fn test_each_limit_reported_with_field_path() {
+        let limits = MetadataLimits {
+            max_tags: 2,
+            max_tag_len: 4,
+            max_key_len: 5,
+            max_value_len: 8,
+            max_metadata_bytes: 20,
+        };
+        let mut task = task_with(
+            &["a", "longa", "c"],
+            &[("owner", "x".repeat(9)), ("chave-longa", "ok".to_string())],
+        );
+        let fields: Vec<(String, String)> = limits
+            .validate(&mut task, "tasks[1].")
+            .into_iter()
+            .map(|violation| (violation.field, violation.message))
+            .collect();
+        assert_eq!(fields, vec![
+            ("tasks[1].tags".to_string(), "3 tags (limite 2)".to_string()),
+            ("tasks[1].tags[1]".to_string(), "5 bytes (limite 4)".to_string()),
+            ("tasks[1].metadata.chave-longa".to_string(), "chave com 11 bytes (limite 5)".to_string()),
+            ("tasks[1].metadata.owner".to_string(), "valor com 9 bytes (limite 8)".to_string()),
+            ("tasks[1].metadata".to_string(), "27 bytes no total (limite 20)".to_string()),
+        ]);
+    }
+}
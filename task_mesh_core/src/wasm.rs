@@ -0,0 +1,174 @@
+//! Funções WASM registradas no [`FunctionRegistry`]
+//!
+//! O módulo exporta `memory`, `alloc(len: i32) -> i32` e
+//! `run(ptr: i32, len: i32) -> i64`: `run` recebe os argumentos em JSON na
+//! região alocada e devolve `(ptr << 32) | len` do resultado, também JSON.
+//!
+//! O cancelamento chega de duas formas:
+//!
+//! - o host importa `taskmesh.is_cancelled() -> i32`, que retorna 1 depois do
+//!   cancelamento da tarefa, para o módulo encerrar por conta própria;
+//! - módulos que não consultam a função são interrompidos por época quando o
+//!   executor aborta a execução (ao fim do período de tolerância).
+//!
+//! A interrupção vale só para a execução abortada: cada chamada tem sua
+//! própria flag, consultada quando a época do engine avança.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use anyhow::Context;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+use wasmtime::{Caller, Config, Engine, Linker, Module, Store, UpdateDeadline};
+
+use crate::functions::{FnContext, FnResult, FunctionRegistry};
+use crate::types::*;
+
+/// Estado de uma chamada
+struct CallState {
+    cancel: CancellationToken,
+    /// A execução foi abortada pelo executor
+    aborted: Arc<AtomicBool>,
+}
+
+/// Módulo WASM compilado
+pub struct WasmFunction {
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmFunction {
+    /// Compila o módulo (binário ou texto WAT)
+    pub fn new(bytes: impl AsRef<[u8]>) -> TaskMeshResult<Self> {
+        let mut config = Config::new();
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config)
+            .map_err(|e| TaskMeshError::Configuration(format!("Engine WASM: {}", e)))?;
+        let module = Module::new(&engine, bytes)
+            .map_err(|e| TaskMeshError::Configuration(format!("Módulo WASM inválido: {}", e)))?;
+        Ok(Self { engine, module })
+    }
+
+    /// Executa `run` em uma thread bloqueante
+    ///
+    /// Descartar o futuro (aborto pelo executor) interrompe a execução na
+    /// próxima verificação de época.
+    pub async fn call(self: Arc<Self>, ctx: FnContext, args: Value) -> FnResult {
+        let aborted = Arc::new(AtomicBool::new(false));
+        let _interrupt = InterruptOnDrop { engine: self.engine.clone(), aborted: aborted.clone() };
+        let state = CallState { cancel: ctx.cancel, aborted };
+        tokio::task::spawn_blocking(move || self.call_blocking(state, &args)).await?
+    }
+
+    fn call_blocking(&self, state: CallState, args: &Value) -> FnResult {
+        let mut store = Store::new(&self.engine, state);
+        store.set_epoch_deadline(1);
+        store.epoch_deadline_callback(|store| {
+            if store.data().aborted.load(Ordering::SeqCst) {
+                anyhow::bail!("execução WASM abortada após o cancelamento");
+            }
+            Ok(UpdateDeadline::Continue(1))
+        });
+
+        let mut linker = Linker::new(&self.engine);
+        linker.func_wrap("taskmesh", "is_cancelled", |caller: Caller<'_, CallState>| -> i32 {
+            caller.data().cancel.is_cancelled() as i32
+        })?;
+        let instance = linker.instantiate(&mut store, &self.module)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .context("módulo sem `memory` exportada")?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let run = instance.get_typed_func::<(i32, i32), i64>(&mut store, "run")?;
+
+        let input = serde_json::to_vec(args)?;
+        let input_len = i32::try_from(input.len()).context("argumentos grandes demais")?;
+        let input_ptr = alloc.call(&mut store, input_len)?;
+        memory.write(&mut store, input_ptr as u32 as usize, &input)?;
+
+        let packed = run.call(&mut store, (input_ptr, input_len))? as u64;
+        let (output_ptr, output_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        let mut output = vec![0; output_len];
+        memory.read(&store, output_ptr, &mut output)?;
+        Ok(serde_json::from_slice(&output)?)
+    }
+}
+
+/// Marca a chamada como abortada e avança a época ao ser descartado
+struct InterruptOnDrop {
+    engine: Engine,
+    aborted: Arc<AtomicBool>,
+}
+
+impl Drop for InterruptOnDrop {
+    fn drop(&mut self) {
+        self.aborted.store(true, Ordering::SeqCst);
+        self.engine.increment_epoch();
+    }
+}
+
+impl FunctionRegistry {
+    /// Compila e registra um módulo WASM sob `name`
+    pub fn register_wasm(&self, name: impl Into<String>, bytes: impl AsRef<[u8]>) -> TaskMeshResult<()> {
+        let function = Arc::new(WasmFunction::new(bytes)?);
+        self.register(name, move |ctx, args| function.clone().call(ctx, args));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+    use crate::progress::ProgressReporter;
+
+    /// `run` devolve `null` (gravado em 1024) depois do laço `body`
+    fn module(body: &str) -> String {
+        format!(
+            r#"(module
+                (import "taskmesh" "is_cancelled" (func $is_cancelled (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "null")
+                (func (export "alloc") (param i32) (result i32) i32.const 0)
+                (func (export "run") (param i32 i32) (result i64)
+                    (loop $wait {})
+                    i64.const 4398046511108))"#,
+            body
+        )
+    }
+
+    fn context(cancel: CancellationToken) -> FnContext {
+        FnContext {
+            task_id: TaskId::new_v4(),
+            cancel,
+            progress: ProgressReporter::channel().0,
+            scratch_dir: std::env::temp_dir(),
+            inputs: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wasm_cooperative_and_interrupted_cancellation() {
+        let registry = FunctionRegistry::new();
+        registry.register_wasm("polite", module("(br_if $wait (i32.eqz (call $is_cancelled)))")).unwrap();
+        registry.register_wasm("stubborn", module("(br $wait)")).unwrap();
+        let grace = Duration::from_millis(200);
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+        let started = Instant::now();
+        let result = registry.call("polite", context(cancel), Value::Null, Duration::from_secs(10), grace).await;
+        assert!(matches!(result, Err(TaskMeshError::ExecutionError(_))), "{:?}", result);
+        assert!(started.elapsed() < grace);
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = registry.call("stubborn", context(cancel), Value::Null, Duration::from_secs(10), grace).await;
+        assert!(matches!(result, Err(TaskMeshError::CancelledUncooperatively(_))), "{:?}", result);
+    }
+}
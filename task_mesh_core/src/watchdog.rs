@@ -0,0 +1,454 @@
+//! Detecção de tarefas presas em execução
+//!
+//! O [`StuckWatchdog`] procura no `StateStore` tarefas em `Running` há mais
+//! que o maior entre o timeout da tarefa e [`StuckConfig::stuck_after_ms`] e
+//! que nenhum worker do executor reporta (worker morto, processo filho
+//! travado sem timeout). Cada tarefa presa recebe a [`StuckPolicy`]
+//! configurada e gera um evento `TaskStuck`; remediações que mudam o status
+//! ficam registradas no histórico de transições.
+//!
+//! Na partida, [`StuckWatchdog::reconcile`] trata as tarefas que o store
+//! ainda registra em `Running`: as que têm processo vivo (mesmo PID e mesma
+//! impressão digital de início) são reanexadas ao executor; as que declaram
+//! comando de retomada voltam à fila com ele; as demais recebem a política
+//! imediatamente, pois nenhum worker do processo anterior sobreviveu.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::event_bus::EventBus;
+use crate::executor::{self, TaskExecutor, PENDING_WORKER_ID};
+use crate::process_metrics;
+use crate::scheduler::Scheduler;
+use crate::state_store::StateStore;
+use crate::status_cache::StatusCache;
+use crate::task_registry::TaskRegistry;
+use crate::types::*;
+
+/// O que fazer com uma tarefa presa
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StuckPolicy {
+    /// Marca a tarefa como falha
+    #[default]
+    MarkFailed,
+    /// Devolve a tarefa à fila enquanto houver retries; depois, falha
+    Requeue,
+    /// Apenas emite o evento (uma vez por execução presa)
+    AlertOnly,
+}
+
+/// Configuração do watchdog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StuckConfig {
+    /// Roda o watchdog periódico
+    #[serde(default)]
+    pub enabled: bool,
+    /// Tempo mínimo em execução para considerar a tarefa presa (ms)
+    #[serde(default = "default_stuck_after_ms")]
+    pub stuck_after_ms: u64,
+    /// Intervalo entre varreduras (ms)
+    #[serde(default = "default_scan_interval_ms")]
+    pub scan_interval_ms: u64,
+    #[serde(default)]
+    pub policy: StuckPolicy,
+}
+
+fn default_stuck_after_ms() -> u64 {
+    3_600_000
+}
+
+fn default_scan_interval_ms() -> u64 {
+    60_000
+}
+
+impl Default for StuckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stuck_after_ms: default_stuck_after_ms(),
+            scan_interval_ms: default_scan_interval_ms(),
+            policy: StuckPolicy::default(),
+        }
+    }
+}
+
+/// Procura e remedia tarefas presas
+pub struct StuckWatchdog {
+    config: StuckConfig,
+    /// Execuções já alertadas (`AlertOnly`), por tarefa e início
+    alerted: Mutex<HashSet<(TaskId, SystemTime)>>,
+}
+
+impl StuckWatchdog {
+    pub fn new(config: StuckConfig) -> Self {
+        Self { config, alerted: Mutex::new(HashSet::new()) }
+    }
+
+    pub fn config(&self) -> &StuckConfig {
+        &self.config
+    }
+
+    /// Varre o `StateStore`, aplica a política e publica os eventos gerados
+    pub async fn scan(
+        &self,
+        store: &dyn StateStore,
+        status_cache: &StatusCache,
+        executor: &TaskExecutor,
+        scheduler: &Scheduler,
+        registry: &RwLock<TaskRegistry>,
+        event_bus: &EventBus,
+    ) -> TaskMeshResult<Vec<SystemEvent>> {
+        let now = SystemTime::now();
+        let stuck_after = Duration::from_millis(self.config.stuck_after_ms);
+        let running_filter = TaskStatus::Running { started_at: SystemTime::UNIX_EPOCH, worker_id: String::new(), process: None, progress: None };
+        // Tarefas ainda na fila do executor têm status `Running` sem worker
+        let backlog = executor.queued_count() > executor.running_count().await;
+
+        let mut events = Vec::new();
+        for task in store.list_tasks_by_status(&[running_filter]).await? {
+            let TaskStatus::Running { started_at, worker_id, .. } = store.get_task_status(&task.id).await? else {
+                continue;
+            };
+            let age = now.duration_since(started_at).unwrap_or_default();
+            if age <= task.timeout.unwrap_or_default().max(stuck_after)
+                || executor.is_running(&task.id)
+                || (worker_id == PENDING_WORKER_ID && backlog)
+            {
+                continue;
+            }
+
+            let reason = format!(
+                "Tarefa presa em execução há {}s (worker '{}' não a reporta)",
+                age.as_secs(),
+                worker_id
+            );
+            let remediated = self
+                .remediate(store, status_cache, executor, scheduler, registry, event_bus, task, started_at, worker_id, reason)
+                .await?;
+            events.extend(remediated);
+        }
+        Ok(events)
+    }
+
+    /// Reconcilia, na partida, as tarefas registradas em `Running`
+    ///
+    /// Deve rodar com o executor recém-iniciado: qualquer tarefa que ele não
+    /// reporta pertence ao processo anterior. Retorna os eventos das tarefas
+    /// retomadas ou remediadas; reanexações não geram evento.
+    pub async fn reconcile(
+        &self,
+        store: &dyn StateStore,
+        status_cache: &StatusCache,
+        executor: &Arc<TaskExecutor>,
+        scheduler: &Scheduler,
+        registry: &RwLock<TaskRegistry>,
+        event_bus: &EventBus,
+    ) -> TaskMeshResult<Vec<SystemEvent>> {
+        let running_filter = TaskStatus::Running { started_at: SystemTime::UNIX_EPOCH, worker_id: String::new(), process: None, progress: None };
+
+        let mut events = Vec::new();
+        for task in store.list_tasks_by_status(&[running_filter]).await? {
+            let TaskStatus::Running { started_at, worker_id, process, .. } = store.get_task_status(&task.id).await? else {
+                continue;
+            };
+            if executor.is_running(&task.id) {
+                continue;
+            }
+            if let Some(handle) = process.filter(process_metrics::is_alive) {
+                executor.reattach(task.id, started_at, worker_id, handle);
+                continue;
+            }
+
+            if let Some(resumed) = executor::resume_task(&task) {
+                write_status(store, status_cache, executor, &task.id, TaskStatus::Pending).await?;
+                self.requeue(scheduler, registry, &task, Arc::new(resumed)).await?;
+                warn!(task_id = %task.id, "Processo da tarefa não sobreviveu ao reinício; retomada do checkpoint");
+                let event = SystemEvent::new(
+                    EventType::TaskStuck,
+                    Some(task.id),
+                    serde_json::json!({
+                        "worker_id": worker_id,
+                        "action": "resumed",
+                        "checkpoint_id": executor::resume_checkpoint_id(&task.id),
+                    }),
+                );
+                event_bus.publish(event.clone()).await?;
+                events.push(event);
+                continue;
+            }
+
+            let reason = match process {
+                Some(handle) => format!("Processo {} da tarefa não sobreviveu ao reinício (worker '{}')", handle.pid, worker_id),
+                None => format!("Tarefa sem processo reanexável após o reinício (worker '{}')", worker_id),
+            };
+            let remediated = self
+                .remediate(store, status_cache, executor, scheduler, registry, event_bus, task, started_at, worker_id, reason)
+                .await?;
+            events.extend(remediated);
+        }
+        Ok(events)
+    }
+
+    /// Aplica a política a uma tarefa presa e publica o evento
+    ///
+    /// Retorna `None` se a tarefa já foi alertada (`AlertOnly`).
+    #[allow(clippy::too_many_arguments)]
+    async fn remediate(
+        &self,
+        store: &dyn StateStore,
+        status_cache: &StatusCache,
+        executor: &TaskExecutor,
+        scheduler: &Scheduler,
+        registry: &RwLock<TaskRegistry>,
+        event_bus: &EventBus,
+        task: Task,
+        started_at: SystemTime,
+        worker_id: String,
+        reason: String,
+    ) -> TaskMeshResult<Option<SystemEvent>> {
+        let now = SystemTime::now();
+        let age = now.duration_since(started_at).unwrap_or_default();
+        let retries = store
+            .get_status_history(&task.id)
+            .await?
+            .iter()
+            .filter(|transition| {
+                matches!(&transition.status, TaskStatus::Running { worker_id, .. } if worker_id != PENDING_WORKER_ID)
+            })
+            .count()
+            .saturating_sub(1) as u32;
+
+        let action = match self.config.policy {
+            StuckPolicy::AlertOnly => {
+                if !self.alerted.lock().unwrap().insert((task.id, started_at)) {
+                    return Ok(None);
+                }
+                "alert"
+            }
+            StuckPolicy::Requeue if retries < task.max_retries => {
+                write_status(store, status_cache, executor, &task.id, TaskStatus::Pending).await?;
+                let shared: SharedTask = Arc::new(task.clone());
+                self.requeue(scheduler, registry, &task, shared).await?;
+                "requeued"
+            }
+            StuckPolicy::MarkFailed | StuckPolicy::Requeue => {
+                let failed = TaskStatus::Failed {
+                    started_at,
+                    failed_at: now,
+                    error: reason.clone(),
+                    retry_count: retries,
+                };
+                write_status(store, status_cache, executor, &task.id, failed).await?;
+                scheduler.report_task_failure(task.id, reason.clone()).await;
+                "failed"
+            }
+        };
+        warn!(task_id = %task.id, "{} ({:?}: {})", reason, self.config.policy, action);
+
+        let event = SystemEvent::new(
+            EventType::TaskStuck,
+            Some(task.id),
+            serde_json::json!({
+                "worker_id": worker_id,
+                "running_ms": age.as_millis() as u64,
+                "policy": self.config.policy,
+                "action": action,
+                "retries": retries,
+            }),
+        ).at(now);
+        event_bus.publish(event.clone()).await?;
+        Ok(Some(event))
+    }
+
+    /// Devolve `scheduled` à fila, registrando `task` se o registro não a conhece
+    async fn requeue(
+        &self,
+        scheduler: &Scheduler,
+        registry: &RwLock<TaskRegistry>,
+        task: &Task,
+        scheduled: SharedTask,
+    ) -> TaskMeshResult<()> {
+        {
+            let mut registry = registry.write().await;
+            if registry.get_task(&task.id).is_none() {
+                registry.adopt_task(Arc::new(task.clone()));
+            }
+        }
+        scheduler.schedule_task(scheduled).await
+    }
+}
+
+/// Grava um status decidido pelo watchdog, mantendo o cache em dia
+///
+/// Mesmo efeito de `TaskMeshCore::write_status`, com os componentes que o
+/// laço do watchdog recebe.
+async fn write_status(
+    store: &dyn StateStore,
+    status_cache: &StatusCache,
+    executor: &TaskExecutor,
+    task_id: &TaskId,
+    status: TaskStatus,
+) -> TaskMeshResult<()> {
+    if status.is_final() {
+        executor.forget_retries(task_id);
+    }
+    status_cache.record(task_id, &status);
+    store.update_task_status(task_id, status).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskMeshConfig, TaskMeshCore};
+
+    async fn core_with(policy: StuckPolicy) -> TaskMeshCore {
+        let stuck = StuckConfig { policy, stuck_after_ms: 60_000, ..StuckConfig::default() };
+        TaskMeshCore::new(TaskMeshConfig { stuck, ..TaskMeshConfig::default() }).await.unwrap()
+    }
+
+    /// Grava direto no store uma tarefa em `Running` desde `running_for` atrás
+    async fn running_task(core: &TaskMeshCore, running_for: Duration, max_retries: u32) -> TaskId {
+        let mut task = Task::new("travada".to_string(), TaskDefinition::command("sleep 1000"), vec![]);
+        task.max_retries = max_retries;
+        core.state_store.store_task(&task).await.unwrap();
+        mark_running(core, &task.id, running_for, "worker-morto").await;
+        task.id
+    }
+
+    async fn mark_running(core: &TaskMeshCore, task_id: &TaskId, running_for: Duration, worker_id: &str) {
+        let status = TaskStatus::Running {
+            started_at: SystemTime::now() - running_for,
+            worker_id: worker_id.to_string(),
+            process: None,
+            progress: None,
+        };
+        core.write_status(task_id, status).await.unwrap();
+    }
+
+    const ANCIENT: Duration = Duration::from_secs(86_400);
+
+    #[tokio::test]
+    async fn test_mark_failed_fails_only_stuck_tasks_and_records_history() {
+        let core = core_with(StuckPolicy::MarkFailed).await;
+        let stuck = running_task(&core, ANCIENT, 3).await;
+        let recent = running_task(&core, Duration::from_secs(5), 3).await;
+
+        let events = core.check_stuck_tasks().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].task_id, events[0].data["action"].as_str()), (Some(stuck), Some("failed")));
+        match core.get_task_status(&stuck).await.unwrap() {
+            TaskStatus::Failed { error, .. } => assert!(error.contains("worker-morto"), "{}", error),
+            other => panic!("status inesperado: {:?}", other),
+        }
+        let history = core.state_store.get_status_history(&stuck).await.unwrap();
+        assert!(matches!(history.last().unwrap().status, TaskStatus::Failed { .. }));
+        assert!(matches!(core.get_task_status(&recent).await.unwrap(), TaskStatus::Running { .. }));
+        assert!(core.check_stuck_tasks().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_requeue_respects_max_retries() {
+        let core = core_with(StuckPolicy::Requeue).await;
+        let task_id = running_task(&core, ANCIENT, 1).await;
+
+        let events = core.check_stuck_tasks().await.unwrap();
+        assert_eq!(events[0].data["action"], "requeued");
+        assert!(matches!(core.get_task_status(&task_id).await.unwrap(), TaskStatus::Pending));
+        assert_eq!(core.scheduler.get_next_task(&ResourceAllocation::default()).await, Some(task_id));
+
+        // Segunda execução também presa: retries esgotados
+        mark_running(&core, &task_id, ANCIENT, "worker-morto-2").await;
+        let events = core.check_stuck_tasks().await.unwrap();
+        assert_eq!((events[0].data["action"].as_str(), events[0].data["retries"].as_u64()), (Some("failed"), Some(1)));
+        assert!(matches!(core.get_task_status(&task_id).await.unwrap(), TaskStatus::Failed { retry_count: 1, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_alert_only_emits_once_and_keeps_status() {
+        let core = core_with(StuckPolicy::AlertOnly).await;
+        let task_id = running_task(&core, ANCIENT, 3).await;
+
+        let events = core.check_stuck_tasks().await.unwrap();
+        assert_eq!(events[0].data["action"], "alert");
+        assert!(core.check_stuck_tasks().await.unwrap().is_empty());
+        assert!(matches!(core.get_task_status(&task_id).await.unwrap(), TaskStatus::Running { .. }));
+        let stored = core.state_store.get_events(None, None).await.unwrap();
+        assert_eq!(stored.iter().filter(|event| event.event_type == EventType::TaskStuck).count(), 1);
+    }
+
+    /// Grava uma tarefa em `Running` no processo `process`, como um executor anterior
+    async fn orphaned_task(core: &TaskMeshCore, task: &Task, process: ProcessHandle) {
+        core.state_store.store_task(task).await.unwrap();
+        let status = TaskStatus::Running {
+            started_at: SystemTime::now() - Duration::from_secs(5),
+            worker_id: "worker-anterior".to_string(),
+            process: Some(process),
+            progress: None,
+        };
+        core.state_store.update_task_status(&task.id, status).await.unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_start_reattaches_live_process_and_fails_with_unknown_exit() {
+        let mut sleeper = std::process::Command::new("sleep").arg("1").spawn().unwrap();
+        let process = process_metrics::process_handle(sleeper.id()).unwrap();
+        let core = core_with(StuckPolicy::MarkFailed).await;
+        let task = Task::new("sobrevivente".to_string(), TaskDefinition::command("sleep 1"), vec![]);
+        orphaned_task(&core, &task, process).await;
+
+        core.start().await.unwrap();
+        assert!(core.executor.is_running(&task.id));
+
+        // O processo termina (fica zumbi, sem wait); sem o código de saída, não é sucesso
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        let error = loop {
+            if let TaskStatus::Failed { error, .. } = core.get_task_status(&task.id).await.unwrap() {
+                break error;
+            }
+            assert!(std::time::Instant::now() < deadline, "tarefa reanexada não terminou");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+        assert!(error.contains("desconhecido"), "{}", error);
+        assert!(!core.executor.is_running(&task.id));
+        sleeper.wait().unwrap();
+        core.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_start_resumes_or_remediates_dead_processes() {
+        let core = core_with(StuckPolicy::MarkFailed).await;
+        // PID vivo com outra impressão digital: o processo original morreu
+        let reused = ProcessHandle { pid: std::process::id(), start_fingerprint: u64::MAX };
+        let lost = Task::new("perdida".to_string(), TaskDefinition::command("sleep 1000"), vec![]);
+        let mut resumable = Task::new("retomável".to_string(), TaskDefinition::command("treinar"), vec![]);
+        resumable.metadata.insert(executor::RESUME_COMMAND_KEY.to_string(), "treinar --from {checkpoint_id}".to_string());
+        orphaned_task(&core, &lost, reused).await;
+        orphaned_task(&core, &resumable, reused).await;
+
+        core.start().await.unwrap();
+        match core.get_task_status(&lost.id).await.unwrap() {
+            TaskStatus::Failed { error, .. } => assert!(error.contains("reinício"), "{}", error),
+            other => panic!("status inesperado: {:?}", other),
+        }
+        assert!(matches!(core.get_task_status(&resumable.id).await.unwrap(), TaskStatus::Pending));
+        let resumed = executor::resume_task(&resumable).unwrap();
+        let expected = format!("treinar --from task-{}", resumable.id);
+        assert!(matches!(resumed.definition, TaskDefinition::Command { command, .. } if command == expected));
+
+        let events = core.state_store.get_events(None, None).await.unwrap();
+        let actions: Vec<_> = events
+            .iter()
+            .filter(|event| event.event_type == EventType::TaskStuck)
+            .map(|event| (event.task_id.unwrap(), event.data["action"].as_str().unwrap().to_string()))
+            .collect();
+        assert!(actions.contains(&(lost.id, "failed".to_string())));
+        assert!(actions.contains(&(resumable.id, "resumed".to_string())));
+        core.shutdown().await.unwrap();
+    }
+}
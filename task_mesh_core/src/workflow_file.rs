@@ -0,0 +1,116 @@
+//! Arquivos de workflow
+//!
+//! Um arquivo JSON lista tarefas cujas dependências são [`TaskRef`]s: IDs,
+//! aliases de tarefas do próprio arquivo ou aliases de tarefas já
+//! submetidas. As referências são resolvidas na submissão
+//! ([`crate::TaskMeshCore::submit_workflow_file`]); como em `submit_batch`,
+//! uma tarefa só pode depender de entradas anteriores do arquivo.
+//!
+//! O bloco `defaults` acrescenta tags e metadados a todas as entradas, sem
+//! sobrescrever os que cada uma declara (ver [`crate::defaults`]).
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::alias::{self, TaskRef};
+use crate::defaults::{DefaultSource, TaskDefaults};
+use crate::state_store::StateStore;
+use crate::types::*;
+
+/// Entrada de um arquivo de workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowTaskSpec {
+    pub name: String,
+    #[serde(default)]
+    pub alias: Option<String>,
+    pub definition: TaskDefinition,
+    #[serde(default)]
+    pub depends_on: Vec<TaskRef>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Arquivo de workflow
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowFile {
+    /// Nome do grupo formado pelas tarefas do arquivo
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Tags e metadados de todas as entradas
+    #[serde(default)]
+    pub defaults: Option<TaskDefaults>,
+    pub tasks: Vec<WorkflowTaskSpec>,
+}
+
+impl WorkflowFile {
+    /// Interpreta um workflow em JSON
+    pub fn from_json(json: &str) -> TaskMeshResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Lê um workflow de arquivo
+    pub fn load(path: impl AsRef<Path>) -> TaskMeshResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Converte as entradas em tarefas, resolvendo as dependências
+    ///
+    /// Aliases definidos no arquivo têm precedência sobre os já registrados
+    /// em `store`.
+    pub async fn into_tasks(self, store: &dyn StateStore) -> TaskMeshResult<Vec<Task>> {
+        let mut local: HashMap<(String, String), TaskId> = HashMap::new();
+        let mut tasks = Vec::with_capacity(self.tasks.len());
+
+        for spec in self.tasks {
+            let mut task = Task::new(spec.name, spec.definition, Vec::new())
+                .with_priority(spec.priority.unwrap_or_default())
+                .with_tags(spec.tags);
+            task.metadata = spec.metadata;
+            task.alias = spec.alias;
+            task.group_name = self.name.clone();
+            if let Some(defaults) = &self.defaults {
+                defaults.apply(&mut task, DefaultSource::Workflow);
+            }
+            if let Some(timeout_ms) = spec.timeout_ms {
+                task = task.with_timeout(std::time::Duration::from_millis(timeout_ms));
+            }
+
+            let namespace = alias::namespace_of(&task).to_string();
+            for reference in &spec.depends_on {
+                let dependency = match reference {
+                    TaskRef::Id(id) => *id,
+                    TaskRef::Alias(qualified) => {
+                        let (ns, name) = alias::split_alias(qualified, &namespace);
+                        match local.get(&(ns.to_string(), name.to_string())) {
+                            Some(id) => *id,
+                            None => store
+                                .resolve_alias(ns, name)
+                                .await?
+                                .ok_or_else(|| TaskMeshError::AliasNotFound(format!("{}/{}", ns, name)))?,
+                        }
+                    }
+                };
+                task.dependencies.push(dependency);
+            }
+
+            if let Some(name) = &task.alias {
+                alias::validate_alias(name)?;
+                if local.insert((namespace, name.clone()), task.id).is_some() {
+                    return Err(TaskMeshError::Configuration(format!(
+                        "Alias '{}' repetido no workflow", name
+                    )));
+                }
+            }
+            tasks.push(task);
+        }
+
+        Ok(tasks)
+    }
+}